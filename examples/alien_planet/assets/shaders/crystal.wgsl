@@ -1,4 +1,4 @@
-#import bevy_pbr::mesh_view_bindings::{globals, view}
+#import bevy_pbr::mesh_view_bindings::view
 #import bevy_pbr::forward_io::VertexOutput
 
 @group(3) @binding(0) var<uniform> material: CrystalMaterial;
@@ -6,13 +6,14 @@
 struct CrystalMaterial {
     base_color: vec4<f32>,
     emissive: vec4<f32>,
+    time: f32,
 }
 
 @fragment
 fn fragment(
     in: VertexOutput,
 ) -> @location(0) vec4<f32> {
-    let time = globals.time;
+    let time = material.time;
     let pulse = (sin(time * 2.0) + 1.0) * 0.5;
 
     // Simple fresnel effect
@@ -1,24 +1,41 @@
 use bevy::prelude::*;
+use diorama::biome::BiomeParams;
+use diorama::cascades::ShadowCascadeBounds;
+use diorama::environment::{DayNightCycle, EnvironmentPreset, Sun};
+use diorama::motes::Motes;
 use rand::prelude::*;
 
 pub struct AtmospherePlugin;
 
 impl Plugin for AtmospherePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(ClearColor(Color::srgb(0.05, 0.05, 0.1)))
-            .insert_resource(GlobalAmbientLight {
-                color: Color::srgb(0.1, 0.1, 0.2),
-                brightness: 200.0,
-                affects_lightmapped_meshes: false,
-            })
-            .add_systems(Startup, (setup_lights, spawn_stars));
-        // .add_systems(Update, add_fog_to_camera); // FogSettings not found
+        app.add_systems(
+            Startup,
+            (set_weather, setup_lights, spawn_stars, spawn_spores),
+        );
     }
 }
 
+fn set_weather(mut commands: Commands, biome: Res<BiomeParams>) {
+    commands.insert_resource(biome.weather(EnvironmentPreset::AlienAtmosphere.settings()));
+    // Five minute days, starting mid-morning, under a violet sky
+    commands.insert_resource(
+        DayNightCycle::new(300.0)
+            .with_time_of_day(0.3)
+            .with_noon_illuminance(5000.0)
+            .with_ambient(200.0, 60.0)
+            .with_sky(
+                biome.tint(Color::srgb(0.3, 0.2, 0.4)),
+                biome.tint(Color::srgb(0.05, 0.05, 0.1)),
+            ),
+    );
+}
+
 fn setup_lights(mut commands: Commands) {
-    // Sun
+    // Sun, moved through the day by the cycle
     commands.spawn((
+        Name::new("Sun"),
+        Sun,
         DirectionalLight {
             illuminance: 5000.0,
             shadows_enabled: true,
@@ -26,6 +43,11 @@ fn setup_lights(mut commands: Commands) {
             ..default()
         },
         Transform::from_xyz(50.0, 100.0, 50.0).looking_at(Vec3::ZERO, Vec3::Y),
+        // Just the terrain, the star dome is far too large to shadow
+        ShadowCascadeBounds::PlayArea {
+            center: Vec3::new(0.0, 5.0, 0.0),
+            half_extents: Vec3::new(100.0, 20.0, 100.0),
+        },
     ));
 }
 
@@ -43,6 +65,8 @@ fn spawn_stars(
         base_color: Color::WHITE,
         emissive: LinearRgba::WHITE,
         unlit: true,
+        // Far beyond the haze, but they'd be lost to it otherwise
+        fog_enabled: false,
         ..default()
     });
 
@@ -64,17 +88,15 @@ fn spawn_stars(
     }
 }
 
-/*
-fn add_fog_to_camera(
-    mut commands: Commands,
-    query: Query<Entity, (With<Camera3d>, Without<FogSettings>)>,
-) {
-    for entity in &query {
-        commands.entity(entity).insert(FogSettings {
-            color: Color::srgb(0.1, 0.1, 0.2),
-            falloff: FogFalloff::ExponentialSquared { density: 0.02 },
-            ..default()
-        });
-    }
+fn spawn_spores(mut commands: Commands) {
+    // Glowing spores drifting over the valley floor around the landing site
+    commands.spawn((
+        Name::new("Drifting Spores"),
+        Motes::spores()
+            .with_count(120)
+            .with_extent(Vec3::new(30.0, 3.0, 30.0))
+            .with_color(Color::srgba(0.75, 0.5, 1.0, 0.8))
+            .with_drift(Vec3::new(0.3, 0.05, 0.1)),
+        Transform::from_xyz(0.0, 0.0, 0.0),
+    ));
 }
-*/
@@ -1,5 +1,18 @@
+//! Creatures of the valley, on a daily routine: sky rays feed on sunlight by day and fly off to
+//! roost high above at dusk, while glow moths come out after dark. Some of each call to one another,
+//! the rays more urgently as they head for the roost.
+
 use avian3d::prelude::*;
 use bevy::prelude::*;
+use diorama::activity::{DailyActivity, Emerged, Sheltered};
+use diorama::audio::CallRecipe;
+use diorama::boids::{Boid, Flock};
+use diorama::environment::DayNightCycle;
+use diorama::game_time::GameTime;
+use diorama::nameplate::Nameplate;
+use diorama::state::PausableSystems;
+use diorama::view_mode::Temperature;
+use diorama::voice::CreatureVoice;
 
 use crate::flora::Scannable;
 
@@ -7,21 +20,56 @@ pub struct FaunaPlugin;
 
 impl Plugin for FaunaPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_fauna)
-            .add_systems(Update, boid_simulation);
+        app.add_systems(Startup, (spawn_fauna, spawn_glow_moths))
+            .add_systems(
+                Update,
+                (
+                    join_flock,
+                    (fly_to_roost, flutter_moths).in_set(PausableSystems),
+                    glow_moths,
+                ),
+            )
+            .add_observer(show_emerged)
+            .add_observer(hide_moths);
     }
 }
 
+/// Where sky rays spend the night, high above the valley
+const ROOST: Vec3 = Vec3::new(-40.0, 60.0, -40.0);
+/// One in this many sky rays calls, so the flock doesn't drown everything out
+const RAY_CALLERS: usize = 5;
+/// One in this many glow moths chirps
+const MOTH_CALLERS: usize = 4;
+
+/// Nocturnal moths that hover around where they hatched
 #[derive(Component)]
-pub struct Boid {
-    pub velocity: Vec3,
+pub struct GlowMoth {
+    pub home: Vec3,
+    pub phase: f32,
 }
 
+/// The moths' shared glowing material
+#[derive(Resource)]
+struct MothGlow(Handle<StandardMaterial>);
+
 fn spawn_fauna(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut audio: ResMut<Assets<AudioSource>>,
 ) {
+    // Long falling whistles while feeding, and short rising ones on the way to roost
+    let song = CallRecipe::new(1.2, 900.0, 600.0)
+        .with_vibrato(6.0, 0.02)
+        .with_harmonics(0.1);
+    let hurry = CallRecipe::new(0.35, 700.0, 1_100.0).with_harmonics(0.2);
+    let songs: Vec<_> = (0..3)
+        .map(|seed| audio.add(song.synthesise(seed)))
+        .collect();
+    let hurries: Vec<_> = (0..2)
+        .map(|seed| audio.add(hurry.synthesise(seed)))
+        .collect();
+
     let mesh = meshes.add(Mesh::from(Triangle3d::new(
         Vec3::new(0.0, 0.0, 0.5),
         Vec3::new(-0.5, 0.0, -0.5),
@@ -34,7 +82,20 @@ fn spawn_fauna(
         ..default()
     });
 
-    for _ in 0..50 {
+    // Over the valley, keeping clear of the terrain and each other
+    let flock = commands
+        .spawn((
+            Name::new("Sky Ray Flock"),
+            Flock::new(3.0, 8.0)
+                .with_radii(10.0, 2.0)
+                .with_turn_speed(2.0)
+                .with_homing(0.05)
+                .with_bounds(Vec3::Y * 20.0, Vec3::new(40.0, 15.0, 40.0))
+                .with_obstacle_avoidance(6.0),
+        ))
+        .id();
+
+    for i in 0..50 {
         let pos = Vec3::new(
             (rand::random::<f32>() - 0.5) * 50.0,
             10.0 + rand::random::<f32>() * 20.0,
@@ -48,90 +109,166 @@ fn spawn_fauna(
         .normalize()
             * 5.0;
 
-        commands.spawn((
+        let mut ray = commands.spawn((
             Mesh3d(mesh.clone()),
             MeshMaterial3d(material.clone()),
             Transform::from_translation(pos).looking_at(pos + vel, Vec3::Y),
             Collider::sphere(0.5),
             RigidBody::Kinematic, // Kinematic because we move them manually
-            Boid { velocity: vel },
+            Boid::new(flock, vel),
+            Temperature(0.55),
+            // Some wait for the sun to be well up, or leave before it's quite set
+            DailyActivity::diurnal().with_threshold(rand::random::<f32>() * 0.1),
             Name::new("Sky Ray"),
+            Nameplate,
             Scannable {
                 name: "Sky Ray".to_string(),
                 description: "A passive airborne creature that feeds on solar radiation."
                     .to_string(),
             },
         ));
+        if i % RAY_CALLERS == 0 {
+            ray.insert(
+                CreatureVoice::new(songs.clone())
+                    .with_flee_calls(hurries.clone())
+                    .with_interval(6.0, 16.0)
+                    .with_volume(0.4)
+                    .with_max_distance(60.0)
+                    .with_caption("Sky rays whistling"),
+            );
+        }
     }
 }
 
-fn boid_simulation(time: Res<Time>, mut query: Query<(&mut Transform, &mut Boid)>) {
-    let dt = time.delta_secs();
-    let perception_radius = 10.0;
-    let avoidance_radius = 2.0;
-    let max_speed = 8.0;
-    let min_speed = 3.0;
-    let turn_speed = 2.0;
-
-    // Collect all positions and velocities first to avoid borrowing issues
-    // (Naive O(N^2) approach is fine for N=50)
-    let boids: Vec<(Vec3, Vec3)> = query
-        .iter()
-        .map(|(t, b)| (t.translation, b.velocity))
-        .collect();
+/// Sky rays only flock while they're out feeding
+fn join_flock(mut rays: Query<(&mut Boid, &DailyActivity), Changed<DailyActivity>>) {
+    for (mut boid, activity) in &mut rays {
+        let active = activity.is_active();
+        if boid.flocking != active {
+            boid.flocking = active;
+        }
+    }
+}
 
-    for (mut transform, mut boid) in query.iter_mut() {
-        let mut separation = Vec3::ZERO;
-        let mut alignment = Vec3::ZERO;
-        let mut cohesion = Vec3::ZERO;
-        let mut count = 0;
+/// Sheltering sky rays head for the roost, disappearing into it when they arrive
+fn fly_to_roost(
+    time: Res<GameTime>,
+    mut rays: Query<(&mut Transform, &mut Boid, &mut Visibility, &DailyActivity)>,
+) {
+    let dt = time.delta_secs();
+    for (mut transform, mut boid, mut visibility, activity) in &mut rays {
+        if activity.is_active() || *visibility == Visibility::Hidden {
+            continue;
+        }
+        let to_roost = ROOST - transform.translation;
+        if to_roost.length() < 3.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        boid.velocity = boid.velocity.lerp(to_roost.normalize() * 8.0, dt);
+        transform.translation += boid.velocity * dt;
+        let translation = transform.translation;
+        transform.look_at(translation + boid.velocity, Vec3::Y);
+    }
+}
 
-        for (other_pos, other_vel) in &boids {
-            let dist = transform.translation.distance(*other_pos);
-            if dist > 0.0 && dist < perception_radius {
-                // Cohesion
-                cohesion += *other_pos;
+fn show_emerged(emerged: On<Emerged>, mut visibility: Query<&mut Visibility>) {
+    if let Ok(mut visibility) = visibility.get_mut(emerged.entity) {
+        *visibility = Visibility::Inherited;
+    }
+}
 
-                // Alignment
-                alignment += *other_vel;
+fn hide_moths(sheltered: On<Sheltered>, mut moths: Query<&mut Visibility, With<GlowMoth>>) {
+    if let Ok(mut visibility) = moths.get_mut(sheltered.entity) {
+        *visibility = Visibility::Hidden;
+    }
+}
 
-                // Separation
-                if dist < avoidance_radius {
-                    separation -= (*other_pos - transform.translation).normalize() / dist;
-                }
+fn spawn_glow_moths(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut audio: ResMut<Assets<AudioSource>>,
+) {
+    let chirp = CallRecipe::new(0.12, 3_200.0, 3_800.0).with_harmonics(0.0);
+    let chirps: Vec<_> = (0..3)
+        .map(|seed| audio.add(chirp.synthesise(seed)))
+        .collect();
 
-                count += 1;
-            }
-        }
+    let mesh = meshes.add(Sphere::new(0.12));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.1, 0.2, 0.2),
+        emissive: LinearRgba::BLACK,
+        ..default()
+    });
+    commands.insert_resource(MothGlow(material.clone()));
 
-        if count > 0 {
-            cohesion = (cohesion / count as f32) - transform.translation;
-            alignment /= count as f32;
+    for i in 0..40 {
+        let home = Vec3::new(
+            (rand::random::<f32>() - 0.5) * 50.0,
+            2.0 + rand::random::<f32>() * 4.0,
+            (rand::random::<f32>() - 0.5) * 50.0,
+        );
+        let mut moth = commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(home),
+            GlowMoth {
+                home,
+                phase: rand::random::<f32>() * std::f32::consts::TAU,
+            },
+            DailyActivity::nocturnal().with_threshold(rand::random::<f32>() * -0.1),
+            Temperature(0.7),
+            Name::new("Glow Moth"),
+            Nameplate,
+            Scannable {
+                name: "Glow Moth".to_string(),
+                description: "A nocturnal flier that glows to find its mates in the dark."
+                    .to_string(),
+            },
+        ));
+        if i % MOTH_CALLERS == 0 {
+            moth.insert(
+                CreatureVoice::new(chirps.clone())
+                    .with_interval(3.0, 9.0)
+                    .with_pitch_variance(0.2)
+                    .with_volume(0.25)
+                    .with_max_distance(20.0)
+                    .with_caption("Moths chirping"),
+            );
         }
+    }
+}
 
-        // World center attraction (keep them in the arena)
-        let center_pull = -transform.translation * 0.05;
-
-        // Combine forces
-        let target_velocity =
-            boid.velocity + (separation * 1.5) + (alignment * 1.0) + (cohesion * 1.0) + center_pull;
-
-        // Update velocity
-        boid.velocity = boid
-            .velocity
-            .lerp(target_velocity.normalize() * max_speed, dt * turn_speed);
-
-        // Clamp speed
-        let speed = boid.velocity.length();
-        if speed < min_speed {
-            boid.velocity = boid.velocity.normalize() * min_speed;
-        } else if speed > max_speed {
-            boid.velocity = boid.velocity.normalize() * max_speed;
+fn flutter_moths(
+    time: Res<GameTime>,
+    mut moths: Query<(&mut Transform, &GlowMoth, &DailyActivity)>,
+) {
+    let t = time.elapsed_secs();
+    for (mut transform, moth, activity) in &mut moths {
+        if !activity.is_active() {
+            continue;
         }
+        let phase = moth.phase + t;
+        transform.translation = moth.home
+            + Vec3::new(
+                (phase * 0.7).sin() * 1.5,
+                (phase * 3.1).sin() * 0.3,
+                (phase * 0.5).cos() * 1.5,
+            );
+    }
+}
 
-        // Move
-        transform.translation += boid.velocity * dt;
-        let translation = transform.translation;
-        transform.look_at(translation + boid.velocity, Vec3::Y);
+/// The moths glow brighter as it gets darker
+fn glow_moths(
+    cycle: Res<DayNightCycle>,
+    glow: Res<MothGlow>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !cycle.is_changed() {
+        return;
+    }
+    if let Some(material) = materials.get_mut(&glow.0) {
+        material.emissive = LinearRgba::rgb(0.5, 2.5, 2.0) * (1.0 - cycle.daylight());
     }
 }
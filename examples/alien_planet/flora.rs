@@ -1,19 +1,32 @@
+use std::f32::consts::TAU;
+
 use avian3d::prelude::*;
 use bevy::prelude::*;
+use diorama::biome::BiomeParams;
+use diorama::fire::{BurntOut, Flammable};
+use diorama::lsystem::{LSystem, LSystemMeshes};
 use diorama::picking::Hint;
-use examples_common::noise::Perlin;
+use diorama::terrain::Heightmap;
 
 use crate::materials::{CrystalMaterial, CrystalMaterialUniform};
+use crate::terrain::spawn_terrain;
 
 pub struct FloraPlugin;
 
 impl Plugin for FloraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_flora)
-            .add_systems(Update, animate_bushes);
+        app.add_systems(Startup, spawn_flora.after(spawn_terrain))
+            .add_systems(Update, animate_bushes)
+            .add_observer(crumble_burnt_bushes);
     }
 }
 
+/// Distinct crystal tree forms, shared between trees via the mesh cache.
+const TREE_VARIANTS: u64 = 8;
+const TREE_LODS: usize = 3;
+/// Distance from the camera at which crystal trees switch to the next level of detail.
+const TREE_LOD_DISTANCE: f32 = 40.0;
+
 #[derive(Component)]
 pub struct Plant;
 
@@ -34,66 +47,69 @@ fn spawn_flora(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut crystal_materials: ResMut<Assets<CrystalMaterial>>,
+    mut lsystems: ResMut<LSystemMeshes>,
+    biome: Res<BiomeParams>,
+    heightmap: Res<Heightmap>,
 ) {
-    let perlin = Perlin::new(1);
     let size = 100;
     let scale = 2.0;
-    let height_scale = 10.0;
-    let offset = Vec3::new(-50.0, -10.0, -50.0);
-
-    let spire_mesh = meshes.add(Cylinder::new(0.2, 4.0));
+    let offset = Vec2::new(-50.0, -50.0);
 
     let bush_mesh = meshes.add(Sphere::new(0.8));
     let bush_mat = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.9, 0.2, 0.5),
+        base_color: biome.tint(Color::srgb(0.9, 0.2, 0.5)),
         perceptual_roughness: 0.3,
         ..default()
     });
 
-    // Spawn Spires
-    for _ in 0..50 {
-        let x = (rand::random::<f64>() * size as f64 * scale) as f32;
-        let z = (rand::random::<f64>() * size as f64 * scale) as f32;
-
-        let y_pos = perlin.get([x as f64 * 0.05, z as f64 * 0.05]) * height_scale
-            + perlin.get([x as f64 * 0.1, z as f64 * 0.1]) * (height_scale * 0.5);
+    // Spawn Crystal Trees
+    for _ in 0..biome.scatter(50) {
+        let x = (rand::random::<f64>() * size as f64 * scale) as f32 + offset.x;
+        let z = (rand::random::<f64>() * size as f64 * scale) as f32 + offset.y;
+        let pos = Vec3::new(x, heightmap.height_at(x, z), z);
 
-        let pos = Vec3::new(x, y_pos as f32, z) + offset;
-
-        let spire_mat = crystal_materials.add(CrystalMaterial {
+        let tree_mat = crystal_materials.add(CrystalMaterial {
             uniform: CrystalMaterialUniform {
-                base_color: LinearRgba::rgb(0.1, 0.8, 0.9),
-                emissive: LinearRgba::rgb(0.0, 0.5, 0.8),
+                base_color: biome.tint(Color::linear_rgb(0.1, 0.8, 0.9)).to_linear(),
+                emissive: biome.tint(Color::linear_rgb(0.0, 0.5, 0.8)).to_linear(),
+                time: 0.0,
             },
         });
+        let tree = LSystem::alien_tree().with_seed(rand::random::<u64>() % TREE_VARIANTS);
+        let lods = lsystems.get_or_build(&tree, TREE_LODS, &mut meshes);
 
         commands
             .spawn((
-                Mesh3d(spire_mesh.clone()),
-                MeshMaterial3d(spire_mat),
-                Transform::from_translation(pos + Vec3::Y * 2.0), // Half height
+                Transform::from_translation(pos)
+                    .with_rotation(Quat::from_rotation_y(rand::random::<f32>() * TAU)),
+                Visibility::default(),
                 Plant,
-                Collider::cylinder(0.2, 4.0),
-                Name::new("Crystal Spire"),
+                Collider::compound(vec![(
+                    Vec3::Y * 2.0,
+                    Quat::IDENTITY,
+                    Collider::cylinder(0.3, 4.0),
+                )]),
+                Name::new("Crystal Tree"),
                 Scannable {
-                    name: "Crystal Spire".to_string(),
-                    description: "A resonating crystal structure that hums in the wind."
-                        .to_string(),
+                    name: "Crystal Tree".to_string(),
+                    description: "A resonating crystal growth that hums in the wind.".to_string(),
                 },
                 Hint::new("Click to resonate"),
             ))
-            .observe(on_spire_click);
+            .with_children(|parent| {
+                for lod in lods.bundles(tree_mat, TREE_LOD_DISTANCE) {
+                    parent.spawn(lod);
+                }
+            })
+            .observe(on_tree_click);
     }
 
-    // Spawn Bubble Bushes
-    for _ in 0..100 {
-        let x = (rand::random::<f64>() * size as f64 * scale) as f32;
-        let z = (rand::random::<f64>() * size as f64 * scale) as f32;
-
-        let y_pos = perlin.get([x as f64 * 0.05, z as f64 * 0.05]) * height_scale
-            + perlin.get([x as f64 * 0.1, z as f64 * 0.1]) * (height_scale * 0.5);
-
-        let pos = Vec3::new(x, y_pos as f32, z) + offset;
+    // Spawn Bubble Bushes, a few already alight, and more the hotter the climate
+    let wildfire_chance = 0.02 + biome.temperature.max(0.0) * 0.08;
+    for _ in 0..biome.scatter(100) {
+        let x = (rand::random::<f64>() * size as f64 * scale) as f32 + offset.x;
+        let z = (rand::random::<f64>() * size as f64 * scale) as f32 + offset.y;
+        let pos = Vec3::new(x, heightmap.height_at(x, z), z);
 
         commands
             .spawn((
@@ -112,19 +128,45 @@ fn spawn_flora(
                     target_scale: Vec3::ONE,
                     speed: 5.0,
                 },
+                bush_fuel(rand::random::<f32>() < wildfire_chance),
             ))
             .observe(on_bush_click);
     }
 }
 
-fn on_spire_click(
+/// Bubble bushes' gas burns fiercely, spreading fire to neighbouring bushes.
+fn bush_fuel(alight: bool) -> Flammable {
+    let mut fuel = Flammable::new(25.0)
+        .with_ignition_seconds(3.0)
+        .with_spread_radius(8.0)
+        .with_size(0.8);
+    if alight {
+        fuel.ignite();
+    }
+    fuel
+}
+
+/// Burnt out bushes crumble away.
+fn crumble_burnt_bushes(
+    burnt_out: On<BurntOut>,
+    mut commands: Commands,
+    bushes: Query<(), With<BushAnimation>>,
+) {
+    if bushes.contains(burnt_out.entity) {
+        commands.entity(burnt_out.entity).despawn();
+    }
+}
+
+fn on_tree_click(
     click: On<Pointer<Click>>,
     mut materials: ResMut<Assets<CrystalMaterial>>,
+    children: Query<&Children>,
     query: Query<&MeshMaterial3d<CrystalMaterial>>,
 ) {
-    if let Some(material) = query
-        .get(click.entity)
-        .ok()
+    // Every level of detail shares the same material, so recolour the first one found
+    if let Some(material) = children
+        .iter_descendants(click.entity)
+        .find_map(|lod| query.get(lod).ok())
         .and_then(|h| materials.get_mut(h))
     {
         material.uniform.emissive = LinearRgba::rgb(
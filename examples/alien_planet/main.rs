@@ -4,23 +4,34 @@
 //! - Procedural terrain generation using noise
 //! - Custom mesh generation
 //! - Boids flocking simulation
-//! - Interactive scanning mechanic
+//! - Day/night cycle with diurnal and nocturnal creatures
+//! - Interactive scanning mechanic, with thermal and night vision views
+//! - Wildfires spreading through the bubble bushes
+//! - A moonlet with its own gravity to walk all the way around
 //! - Atmospheric effects
 
 use bevy::prelude::*;
 use diorama::DioramaPlugin;
-use diorama::player::Player;
+use diorama::fire::Extinguisher;
+use diorama::inventory::Inventory;
+use diorama::nameplate::NameplateSettings;
+use diorama::player::{Player, PlayerPlugin};
 
 mod atmosphere;
 mod fauna;
 mod flora;
 mod materials;
+mod moonlet;
 mod scanner;
 mod terrain;
 
 fn main() -> AppExit {
     App::new()
-        .add_plugins(DioramaPlugin)
+        // Kinematic, so the player turns to stand on the moonlet
+        .add_plugins(DioramaPlugin {
+            player: PlayerPlugin::kinematic(),
+            ..default()
+        })
         .add_plugins(AlienPlanetPlugin)
         .run()
 }
@@ -36,11 +47,22 @@ impl Plugin for AlienPlanetPlugin {
             atmosphere::AtmospherePlugin,
             scanner::ScannerPlugin,
             materials::CrystalMaterialPlugin,
+            moonlet::MoonletPlugin,
         ))
-        .add_systems(Startup, teleport_player);
+        // Surveyors carry foam to put out wildfires
+        .insert_resource(Extinguisher::new(FOAM_CANISTER).with_range(8.0))
+        // Sky rays fly well overhead, so their nameplates carry further
+        .insert_resource(NameplateSettings::default().with_distance(40.0, 10.0))
+        .add_systems(Startup, (teleport_player, pack_foam_canister));
     }
 }
 
+const FOAM_CANISTER: &str = "Foam canister";
+
+fn pack_foam_canister(mut inventory: ResMut<Inventory>) {
+    inventory.add(FOAM_CANISTER, 1);
+}
+
 fn teleport_player(mut query: Query<&mut Transform, With<Player>>) {
     if let Some(mut transform) = query.iter_mut().next() {
         transform.translation = Vec3::new(0.0, 20.0, 0.0);
@@ -1,12 +1,18 @@
 use bevy::prelude::*;
 use bevy::render::render_resource::{AsBindGroup, ShaderType};
 use bevy::shader::ShaderRef;
+use diorama::game_time::{GameTimeMaterial, GameTimeMaterialPlugin};
+use diorama::view_mode::{ViewModeMaterialPlugin, ViewModeStandIn};
 
 pub struct CrystalMaterialPlugin;
 
 impl Plugin for CrystalMaterialPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(MaterialPlugin::<CrystalMaterial>::default());
+        app.add_plugins((
+            MaterialPlugin::<CrystalMaterial>::default(),
+            ViewModeMaterialPlugin::<CrystalMaterial>::default(),
+            GameTimeMaterialPlugin::<CrystalMaterial>::default(),
+        ));
     }
 }
 
@@ -14,6 +20,7 @@ impl Plugin for CrystalMaterialPlugin {
 pub struct CrystalMaterialUniform {
     pub base_color: LinearRgba,
     pub emissive: LinearRgba,
+    pub time: f32,
 }
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
@@ -31,3 +38,20 @@ impl Material for CrystalMaterial {
         AlphaMode::Blend
     }
 }
+
+impl GameTimeMaterial for CrystalMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.uniform.time = seconds;
+    }
+}
+
+impl ViewModeStandIn for CrystalMaterial {
+    fn stand_in(&self) -> StandardMaterial {
+        StandardMaterial {
+            base_color: self.uniform.base_color.into(),
+            emissive: self.uniform.emissive,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use diorama::biome::BiomeParams;
+use diorama::gravity::{GravitySource, GravityVolume};
+use diorama::rock::{Rock, RockMeshes};
+
+pub struct MoonletPlugin;
+
+impl Plugin for MoonletPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_moonlet);
+    }
+}
+
+const CENTRE: Vec3 = Vec3::new(-35.0, 32.0, 20.0);
+const RADIUS: f32 = 5.0;
+/// How far the moonlet's pull reaches. A jump from its surface only just carries out of it.
+const PULL_RADIUS: f32 = 22.0;
+const ROCK_COUNT: u32 = 24;
+
+/// A small moonlet hanging over the plain with gravity of its own, reached by a column of
+/// reversed gravity rising from the ground beneath it.
+fn spawn_moonlet(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut rocks: ResMut<RockMeshes>,
+    biome: Res<BiomeParams>,
+) {
+    let moonlet_mat = materials.add(StandardMaterial {
+        base_color: biome.tint(Color::srgb(0.45, 0.42, 0.5)),
+        perceptual_roughness: 0.95,
+        ..default()
+    });
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Sphere::new(RADIUS)
+                    .mesh()
+                    .ico(4)
+                    .unwrap_or_else(|_| Mesh::from(Sphere::new(RADIUS))),
+            ),
+        ),
+        MeshMaterial3d(moonlet_mat.clone()),
+        Transform::from_translation(CENTRE),
+        RigidBody::Static,
+        Collider::sphere(RADIUS),
+        GravitySource::new(9.81, PULL_RADIUS).with_surface_radius(RADIUS),
+        Name::new("Moonlet"),
+    ));
+
+    // Loose rocks scattered around it, which fall onto its surface once physics starts
+    for _ in 0..ROCK_COUNT {
+        let direction = Vec3::new(
+            rand::random::<f32>() - 0.5,
+            rand::random::<f32>() - 0.5,
+            rand::random::<f32>() - 0.5,
+        )
+        .normalize_or(Vec3::Y);
+        let rock = rocks.get_or_build(
+            &Rock::scree().with_seed(rand::random::<u64>() % 6),
+            &mut meshes,
+        );
+        commands.spawn((
+            Mesh3d(rock.mesh),
+            MeshMaterial3d(moonlet_mat.clone()),
+            Transform::from_translation(CENTRE + direction * (RADIUS + 2.0))
+                .with_scale(Vec3::splat(0.8 + rand::random::<f32>() * 0.8)),
+            RigidBody::Dynamic,
+            rock.collider,
+            Name::new("Moonlet Rock"),
+        ));
+    }
+
+    // Lifts anything standing beneath the moonlet up into its pull
+    commands.spawn((
+        Transform::from_xyz(CENTRE.x, 0.0, CENTRE.z),
+        GravityVolume::new(Vec3::new(3.0, 18.0, 3.0), Vec3::Y * 4.0),
+        Name::new("Moonlet Updraft"),
+    ));
+}
@@ -1,6 +1,8 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
+use diorama::inventory::Inventory;
 use diorama::player::Player;
+use diorama::view_mode::{ViewMode, ViewModeScanner};
 
 use crate::flora::Scannable;
 
@@ -8,11 +10,15 @@ pub struct ScannerPlugin;
 
 impl Plugin for ScannerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_ui)
+        // The scanner also switches to thermal and night vision
+        app.insert_resource(ViewModeScanner::new(SCANNER))
+            .add_systems(Startup, (setup_ui, pack_scanner))
             .add_systems(Update, scan_system);
     }
 }
 
+const SCANNER: &str = "Scanner";
+
 #[derive(Component)]
 struct ScannerUi;
 
@@ -58,6 +64,11 @@ fn setup_ui(mut commands: Commands) {
     ));
 }
 
+fn pack_scanner(mut inventory: ResMut<Inventory>) {
+    inventory.add(SCANNER, 1);
+    inventory.hold(SCANNER);
+}
+
 fn scan_system(
     camera_query: Query<(&GlobalTransform, &Camera)>,
     spatial_query: SpatialQuery,
@@ -65,7 +76,27 @@ fn scan_system(
     name_query: Query<&Name>,
     player_query: Query<Entity, With<Player>>,
     mut text_query: Query<&mut Text, With<ScannerText>>,
+    inventory: Res<Inventory>,
+    view_mode: Res<ViewMode>,
 ) {
+    let Some(held) = inventory.held() else {
+        for mut text in &mut text_query {
+            text.0 = "Empty-handed".to_string();
+        }
+        return;
+    };
+    if held != SCANNER {
+        for mut text in &mut text_query {
+            text.0 = format!("Holding: {held}");
+        }
+        return;
+    }
+    let mode = match *view_mode {
+        ViewMode::Normal => "",
+        ViewMode::Thermal => "[Thermal] ",
+        ViewMode::NightVision => "[Night vision] ",
+    };
+
     let Some((transform, _camera)) = camera_query.iter().next() else {
         return;
     };
@@ -81,20 +112,23 @@ fn scan_system(
     if let Some(hit) = spatial_query.cast_ray(origin, direction, 100.0, true, &filter) {
         if let Ok(scannable) = scannable_query.get(hit.entity) {
             for mut text in &mut text_query {
-                text.0 = format!("Target: {}\n{}", scannable.name, scannable.description);
+                text.0 = format!(
+                    "{mode}Target: {}\n{}",
+                    scannable.name, scannable.description
+                );
             }
         } else if let Ok(name) = name_query.get(hit.entity) {
             for mut text in &mut text_query {
-                text.0 = format!("Object: {}", name);
+                text.0 = format!("{mode}Object: {}", name);
             }
         } else {
             for mut text in &mut text_query {
-                text.0 = "Unknown Signal".to_string();
+                text.0 = format!("{mode}Unknown Signal");
             }
         }
     } else {
         for mut text in &mut text_query {
-            text.0 = "Scanning...".to_string();
+            text.0 = format!("{mode}Scanning...");
         }
     }
 }
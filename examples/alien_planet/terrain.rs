@@ -1,7 +1,13 @@
 use avian3d::prelude::*;
-use bevy::mesh::{Indices, VertexAttributeValues};
 use bevy::prelude::*;
-use examples_common::noise::Perlin;
+use diorama::biome::BiomeParams;
+use diorama::flow::{Flow, FlowEmitter, flow_texture};
+use diorama::footsteps::{SurfaceKind, SurfaceProperties};
+use diorama::rock::{Rock, RockMeshes};
+use diorama::ruins::{RuinPiece, Ruins};
+use diorama::sculpture::{Sculpture, SculptureMeshes};
+use diorama::sdf_terrain::{SdfChunk, SdfField};
+use diorama::terrain::{Heightmap, TerrainStreamer};
 
 pub struct TerrainPlugin;
 
@@ -14,93 +20,232 @@ impl Plugin for TerrainPlugin {
 #[derive(Component)]
 pub struct Terrain;
 
-fn spawn_terrain(
+const SCREE_COUNT: u32 = 150;
+/// Distinct scree shapes, shared between stones via the mesh cache.
+const SCREE_VARIANTS: u64 = 12;
+const MONOLITH_COUNT: u32 = 7;
+const MONOLITH_LODS: usize = 3;
+/// Distance from the camera each less detailed monolith mesh takes over at.
+const MONOLITH_LOD_DISTANCE: f32 = 40.0;
+
+/// Spawns the terrain and everything on it, inserting its [`Heightmap`] for others to find the
+/// ground with.
+pub fn spawn_terrain(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    mut rocks: ResMut<RockMeshes>,
+    mut sculptures: ResMut<SculptureMeshes>,
+    biome: Res<BiomeParams>,
 ) {
+    // Width of the area things are scattered over, in the middle of endless terrain
     let size = 200.0;
-    let subdivisions = 100;
-    let height_scale = 10.0;
-    let perlin = Perlin::new(1);
-
-    // Create a plane mesh
-    let mut mesh = Plane3d::default()
-        .mesh()
-        .size(size, size)
-        .subdivisions(subdivisions)
-        .build();
-
-    // We need to capture heights for the collider.
-    // The plane is centered at 0,0, from -size/2 to size/2.
-    // Subdivisions = 100 means 101 vertices along each axis.
-    // Step size = size / subdivisions = 200 / 100 = 2.0.
-
-    let mut heights = vec![vec![0.0; subdivisions as usize + 1]; subdivisions as usize + 1];
-
-    if let Some(VertexAttributeValues::Float32x3(positions)) =
-        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
-    {
-        for pos in positions.iter_mut() {
-            let x = pos[0] as f64;
-            let z = pos[2] as f64;
-
-            let y = perlin.get([x * 0.05, z * 0.05]) * height_scale
-                + perlin.get([x * 0.1, z * 0.1]) * (height_scale * 0.5);
+    let heightmap = Heightmap::new(1)
+        .with_base(-10.0)
+        .with_layer(0.05, 10.0 * biome.relief())
+        .with_layer(0.1, 5.0 * biome.relief());
+
+    // A river winding north to south, kept just below the ground and always running downhill
+    let mut level = f32::MAX;
+    let river = Flow::river(
+        [
+            (0.0, -95.0),
+            (8.0, -60.0),
+            (-6.0, -25.0),
+            (6.0, 10.0),
+            (-10.0, 45.0),
+            (0.0, 95.0),
+        ]
+        .map(|(x, z)| {
+            level = level.min(heightmap.height_at(x, z) - 0.5);
+            Vec3::new(x, level, z)
+        }),
+    );
+    let ground = {
+        let (river, samples, heightmap) = (river.clone(), river.samples(), heightmap.clone());
+        TerrainStreamer::new(
+            &heightmap,
+            materials.add(StandardMaterial {
+                base_color: biome.tint(Color::srgb(0.2, 0.5, 0.3)),
+                perceptual_roughness: 0.9,
+                ..default()
+            }),
+        )
+        .with_ground(move |x, z| {
+            river.carve_height_along(&samples, x, z, heightmap.height_at(x, z))
+        })
+        .with_chunks(32.0, 16)
+        .with_radius(160.0)
+    };
 
-            pos[1] = y as f32;
+    commands.spawn((
+        ground.clone(),
+        SurfaceProperties::new(SurfaceKind::Sand),
+        Terrain,
+        Name::new("Alien Terrain"),
+    ));
 
-            // Map world pos to grid index
-            // x goes from -100 to 100.
-            // index = (x + 100) / 2
-            let grid_x =
-                ((x + size as f64 / 2.0) / (size as f64 / subdivisions as f64)).round() as usize;
-            let grid_z =
-                ((z + size as f64 / 2.0) / (size as f64 / subdivisions as f64)).round() as usize;
+    let water_mat = materials.add(StandardMaterial {
+        base_color: biome.tint(Color::srgba(0.3, 0.75, 0.85, 0.8)),
+        base_color_texture: Some(images.add(flow_texture(3))),
+        perceptual_roughness: 0.1,
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
+    let river_entity = river.spawn(&mut commands, &mut meshes, water_mat, Transform::IDENTITY);
+    commands.entity(river_entity).insert((
+        Name::new("River"),
+        FlowEmitter::new(Color::srgba(0.7, 0.95, 1.0, 0.3), 6.0)
+            .with_lifetime(3.0)
+            .with_size(0.3)
+            .with_rise(0.4),
+    ));
 
-            if grid_z < heights.len() && grid_x < heights[0].len() {
-                heights[grid_z][grid_x] = y as f32;
-            }
-        }
+    // Ancient site: a ring of broken columns around a lone arch
+    let (site_x, site_z) = (30.0, -25.0);
+    let site_y = heightmap.height_at(site_x, site_z);
+    let mut site = Ruins::new(7)
+        .with_damage(0.5)
+        .with_block_size(0.8)
+        .with_piece(
+            RuinPiece::Arch {
+                span: 4.0,
+                height: 6.0,
+                depth: 1.0,
+            },
+            Transform::IDENTITY,
+        );
+    for i in 0..9 {
+        let angle = i as f32 / 9.0 * std::f32::consts::TAU;
+        site = site.with_piece(
+            RuinPiece::Column {
+                radius: 0.6,
+                height: 7.0,
+            },
+            Transform::from_xyz(angle.cos() * 9.0, 0.0, angle.sin() * 9.0),
+        );
+    }
+    let site_mat = materials.add(StandardMaterial {
+        base_color: biome.tint(Color::srgb(0.55, 0.45, 0.6)),
+        perceptual_roughness: 0.85,
+        ..default()
+    });
+    site.spawn(
+        &mut commands,
+        &mut meshes,
+        site_mat,
+        Transform::from_xyz(site_x, site_y - 0.5, site_z),
+    );
+
+    // Cave system: a ridge with branching tunnels meeting in a central cavern
+    let (cave_x, cave_z) = (-40.0, 30.0);
+    let center = Vec3::new(cave_x, heightmap.height_at(cave_x, cave_z), cave_z);
+    let ridge = SdfField::cuboid(center, Vec3::new(14.0, 5.0, 7.0))
+        .smooth_union(
+            SdfField::sphere(center + Vec3::new(-6.0, 4.0, 0.0), 8.0),
+            3.0,
+        )
+        .smooth_union(
+            SdfField::sphere(center + Vec3::new(7.0, 3.0, 2.0), 7.0),
+            3.0,
+        );
+    let tunnels = SdfField::capsule(
+        center + Vec3::new(-22.0, 1.5, 0.0),
+        center + Vec3::new(0.0, 2.5, 0.0),
+        2.2,
+    )
+    .union(SdfField::capsule(
+        center + Vec3::new(0.0, 2.5, 0.0),
+        center + Vec3::new(10.0, 2.0, 14.0),
+        1.8,
+    ))
+    .union(SdfField::sphere(center + Vec3::new(0.0, 3.5, 0.0), 5.0));
+    let cave_mat = materials.add(StandardMaterial {
+        base_color: biome.tint(Color::srgb(0.3, 0.25, 0.4)),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+    for (transform, chunk) in SdfChunk::grid(
+        ridge.smooth_subtract(tunnels, 1.5),
+        center - Vec3::new(20.0, 2.0, 16.0),
+        center + Vec3::new(20.0, 14.0, 16.0),
+        8.0,
+        0.5,
+    ) {
+        commands.spawn((
+            Name::new("Cave"),
+            transform,
+            chunk,
+            MeshMaterial3d(cave_mat.clone()),
+        ));
     }
 
-    mesh.compute_normals();
-
-    // Create trimesh collider from the mesh data
-    let vertex_positions: Vec<Vec3> = mesh
-        .attribute(Mesh::ATTRIBUTE_POSITION)
-        .and_then(|attr| match attr {
-            VertexAttributeValues::Float32x3(positions) => {
-                Some(positions.iter().map(|p| Vec3::from_array(*p)).collect())
-            }
-            _ => None,
-        })
-        .unwrap_or_default();
-
-    let indices: Vec<[u32; 3]> = mesh
-        .indices()
-        .map(|indices| match indices {
-            Indices::U32(indices) => indices.chunks(3).map(|c| [c[0], c[1], c[2]]).collect(),
-            Indices::U16(indices) => indices
-                .chunks(3)
-                .map(|c| [c[0] as u32, c[1] as u32, c[2] as u32])
-                .collect(),
-        })
-        .unwrap_or_default();
+    // Scatter loose scree over the terrain
+    let scree_mat = materials.add(StandardMaterial {
+        base_color: biome.tint(Color::srgb(0.35, 0.3, 0.4)),
+        perceptual_roughness: 0.95,
+        ..default()
+    });
+    for _ in 0..SCREE_COUNT {
+        let x = (rand::random::<f32>() - 0.5) * size * 0.9;
+        let z = (rand::random::<f32>() - 0.5) * size * 0.9;
+        let y = ground.height_at(x, z);
+
+        let rock = rocks.get_or_build(
+            &Rock::scree().with_seed(rand::random::<u64>() % SCREE_VARIANTS),
+            &mut meshes,
+        );
+        commands.spawn((
+            Mesh3d(rock.mesh),
+            MeshMaterial3d(scree_mat.clone()),
+            Transform::from_xyz(x, y, z)
+                .with_rotation(Quat::from_rotation_y(
+                    rand::random::<f32>() * std::f32::consts::TAU,
+                ))
+                .with_scale(Vec3::splat(0.6 + rand::random::<f32>() * 1.2)),
+            RigidBody::Static,
+            rock.collider,
+            Name::new("Scree"),
+        ));
+    }
 
-    let collider = Collider::trimesh(vertex_positions, indices);
+    // Monoliths standing about the plain, each one shaped by its own seed
+    let monolith_mat = materials.add(StandardMaterial {
+        base_color: biome.tint(Color::srgb(0.12, 0.1, 0.16)),
+        emissive: biome.tint(Color::srgb(0.1, 0.4, 0.5)).to_linear() * 0.4,
+        metallic: 0.6,
+        perceptual_roughness: 0.25,
+        ..default()
+    });
+    for _ in 0..MONOLITH_COUNT {
+        let x = (rand::random::<f32>() - 0.5) * size * 0.8;
+        let z = (rand::random::<f32>() - 0.5) * size * 0.8;
+        let y = ground.height_at(x, z);
+
+        let monolith = Sculpture::monolith()
+            .with_twist(rand::random::<f32>() * 0.6)
+            .with_seed(rand::random::<u64>());
+        let lods = sculptures.get_or_build(&monolith, MONOLITH_LODS, &mut meshes);
+        commands
+            .spawn((
+                // Sunk a little so uneven ground doesn't show under the base
+                Transform::from_xyz(x, y - 0.5, z)
+                    .with_rotation(Quat::from_rotation_y(
+                        rand::random::<f32>() * std::f32::consts::TAU,
+                    ))
+                    .with_scale(Vec3::splat(0.8 + rand::random::<f32>() * 0.8)),
+                Visibility::default(),
+                RigidBody::Static,
+                lods.collider.clone(),
+                Name::new("Monolith"),
+            ))
+            .with_children(|parent| {
+                for lod in lods.bundles(monolith_mat.clone(), MONOLITH_LOD_DISTANCE) {
+                    parent.spawn(lod);
+                }
+            });
+    }
 
-    commands.spawn((
-        Mesh3d(meshes.add(mesh)),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgb(0.2, 0.5, 0.3),
-            perceptual_roughness: 0.9,
-            ..default()
-        })),
-        Transform::from_xyz(0.0, -10.0, 0.0),
-        RigidBody::Static,
-        collider,
-        Terrain,
-        Name::new("Alien Terrain"),
-    ));
+    commands.insert_resource(heightmap);
 }
@@ -1,5 +1,5 @@
 #import bevy_pbr::{
-    mesh_view_bindings::{globals, view},
+    mesh_view_bindings::view,
     forward_io::VertexOutput,
 }
 
@@ -10,6 +10,7 @@ struct AuroraRibbonMaterial {
     flow_speed: f32,
     glow_strength: f32,
     alpha_bias: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: AuroraRibbonMaterial;
@@ -32,7 +33,7 @@ fn noise(p: vec2<f32>) -> f32 {
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
-    let time = globals.time * material.flow_speed;
+    let time = material.time * material.flow_speed;
     let uv = in.uv;
     let warped = uv * vec2<f32>(1.0, material.band_density);
 
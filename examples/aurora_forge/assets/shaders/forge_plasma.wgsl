@@ -1,5 +1,5 @@
 #import bevy_pbr::{
-    mesh_view_bindings::{globals, view},
+    mesh_view_bindings::view,
     forward_io::VertexOutput,
 }
 
@@ -9,7 +9,7 @@ struct ForgePlasmaMaterial {
     swirl_scale: f32,
     pulse_speed: f32,
     fresnel_power: f32,
-    _padding: u32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: ForgePlasmaMaterial;
@@ -32,7 +32,7 @@ fn noise(p: vec2<f32>) -> f32 {
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
-    let time = globals.time * material.pulse_speed;
+    let time = material.time * material.pulse_speed;
     let pos = in.world_position.xz * material.swirl_scale;
 
     let swirl = sin(pos.x * 2.4 + time + sin(pos.y * 2.8 - time * 0.7)) * 0.5 + 0.5;
@@ -33,7 +33,7 @@ impl Plugin for AuroraForgePlugin {
 
 fn main() -> AppExit {
     App::new()
-        .add_plugins(DioramaPlugin)
+        .add_plugins(DioramaPlugin::default())
         .add_plugins(materials::AuroraForgeMaterialsPlugin)
         .add_plugins(AuroraForgePlugin)
         .run()
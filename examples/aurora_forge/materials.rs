@@ -3,6 +3,7 @@ use bevy::prelude::*;
 use bevy::reflect::TypePath;
 use bevy::render::render_resource::{AsBindGroup, ShaderType};
 use bevy::shader::ShaderRef;
+use diorama::game_time::{GameTimeMaterial, GameTimeMaterialPlugin};
 
 pub struct AuroraForgeMaterialsPlugin;
 
@@ -11,6 +12,8 @@ impl Plugin for AuroraForgeMaterialsPlugin {
         app.add_plugins((
             MaterialPlugin::<ForgePlasmaMaterial>::default(),
             MaterialPlugin::<AuroraRibbonMaterial>::default(),
+            GameTimeMaterialPlugin::<ForgePlasmaMaterial>::default(),
+            GameTimeMaterialPlugin::<AuroraRibbonMaterial>::default(),
         ));
     }
 }
@@ -28,7 +31,7 @@ pub struct ForgePlasmaData {
     pub swirl_scale: f32,
     pub pulse_speed: f32,
     pub fresnel_power: f32,
-    pub _padding: u32,
+    pub time: f32,
 }
 
 impl Material for ForgePlasmaMaterial {
@@ -45,6 +48,12 @@ impl Material for ForgePlasmaMaterial {
     }
 }
 
+impl GameTimeMaterial for ForgePlasmaMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
 pub struct AuroraRibbonMaterial {
     #[uniform(0)]
@@ -59,6 +68,7 @@ pub struct AuroraRibbonData {
     pub flow_speed: f32,
     pub glow_strength: f32,
     pub alpha_bias: f32,
+    pub time: f32,
 }
 
 impl Material for AuroraRibbonMaterial {
@@ -74,3 +84,9 @@ impl Material for AuroraRibbonMaterial {
         false
     }
 }
+
+impl GameTimeMaterial for AuroraRibbonMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
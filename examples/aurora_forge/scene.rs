@@ -1,8 +1,9 @@
 use std::f32::consts::{PI, TAU};
 
 use avian3d::prelude::*;
-use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::mesh::VertexAttributeValues;
 use bevy::prelude::*;
+use diorama::mesh_collider::ColliderFromMesh;
 use diorama::player::Player;
 use examples_common::noise::Perlin;
 
@@ -157,7 +158,7 @@ fn create_shader_palette(
                 swirl_scale: 0.38,
                 pulse_speed: 1.1,
                 fresnel_power: 3.5,
-                _padding: 0,
+                time: 0.0,
             },
         }),
         plasma_stream: plasma_materials.add(ForgePlasmaMaterial {
@@ -167,7 +168,7 @@ fn create_shader_palette(
                 swirl_scale: 0.62,
                 pulse_speed: 0.75,
                 fresnel_power: 2.1,
-                _padding: 0,
+                time: 0.0,
             },
         }),
         aurora_primary: aurora_materials.add(AuroraRibbonMaterial {
@@ -178,6 +179,7 @@ fn create_shader_palette(
                 flow_speed: 0.36,
                 glow_strength: 1.8,
                 alpha_bias: 0.55,
+                time: 0.0,
             },
         }),
         aurora_secondary: aurora_materials.add(AuroraRibbonMaterial {
@@ -188,6 +190,7 @@ fn create_shader_palette(
                 flow_speed: 0.24,
                 glow_strength: 1.35,
                 alpha_bias: 0.48,
+                time: 0.0,
             },
         }),
     }
@@ -269,45 +272,12 @@ fn spawn_caldera(
 
     terrain_mesh.compute_normals();
 
-    let vertex_positions: Vec<Vec3> = terrain_mesh
-        .attribute(Mesh::ATTRIBUTE_POSITION)
-        .and_then(|attribute| match attribute {
-            VertexAttributeValues::Float32x3(positions) => Some(
-                positions
-                    .iter()
-                    .map(|position| Vec3::from_array(*position))
-                    .collect(),
-            ),
-            _ => None,
-        })
-        .unwrap_or_default();
-
-    let indices: Vec<[u32; 3]> = terrain_mesh
-        .indices()
-        .map(|indices| match indices {
-            Indices::U32(indices) => indices
-                .chunks(3)
-                .map(|chunk| [chunk[0], chunk[1], chunk[2]])
-                .collect(),
-            Indices::U16(indices) => indices
-                .chunks(3)
-                .map(|chunk| {
-                    [
-                        u32::from(chunk[0]),
-                        u32::from(chunk[1]),
-                        u32::from(chunk[2]),
-                    ]
-                })
-                .collect(),
-        })
-        .unwrap_or_default();
-
     commands.spawn((
         Name::new("Volcanic Caldera"),
         Mesh3d(meshes.add(terrain_mesh)),
         MeshMaterial3d(palette.basalt.clone()),
         RigidBody::Static,
-        Collider::trimesh(vertex_positions, indices),
+        ColliderFromMesh::Trimesh,
     ));
 
     for (index, (inner, outer, y_offset, speed)) in [
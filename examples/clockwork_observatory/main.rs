@@ -31,7 +31,7 @@ impl Plugin for ClockworkObservatoryPlugin {
 
 fn main() -> AppExit {
     App::new()
-        .add_plugins(DioramaPlugin)
+        .add_plugins(DioramaPlugin::default())
         .add_plugins(ClockworkObservatoryPlugin)
         .run()
 }
@@ -7,7 +7,13 @@
 //! - Interactive dialogue system for artwork descriptions
 //! - Animated sculptures with pulsing, color cycling, and rotation
 //! - Physics-enabled installations
+//! - Flowing lava channel exhibit with rising embers
 //! - Multiple material types including shader-based effects
+//! - Underdrawings beneath the paintings, seen through them while inspecting
+//! - Close-up examination of the paintings, regenerated in more detail while examined
+//! - A hanging mobile that turns in a draft and swings when bumped
+//! - A sound sculpture of chimes playing a tune that changes as visitors walk round it
+//! - A projected slideshow of every painting style in the corridor to the third room
 //!
 //! ## Painting Styles
 //! Supports 12 different procedural art styles:
@@ -22,27 +28,53 @@
 //! - Crystal: Color-cycling pillars
 //!
 //! ## Performance Notes
-//! - Textures generated at 2048x2048 for high quality
+//! - Textures generated at 1024x1024, and regenerated at 4096x4096 in the background while a
+//!   painting is examined up close
 //! - Saturating arithmetic prevents overflow in procedural generation
 //! - Dialogue runners automatically cleaned up after completion
 
+use std::collections::{HashMap, HashSet};
+
 use avian3d::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::math::Affine2;
+use bevy::mesh::PrimitiveTopology;
 use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::futures_lite::future;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on};
 use bevy_yarnspinner::prelude::*;
+use diorama::audio_guide::Exhibit;
+use diorama::carry::Carryable;
+use diorama::close_up::{CloseUp, CloseUpEnded, CloseUpStarted};
+use diorama::curation::{ArtworkHung, CuratedArtwork, Curation, DisplaySlot};
+use diorama::dialogue_log::{DialogueInProgress, DialogueLog};
+use diorama::flow::{Flow, FlowEmitter, HazardEntered, flow_texture};
+use diorama::inspection::HiddenDetail;
+use diorama::kiosk::KioskReset;
+use diorama::mobile::Mobile;
+use diorama::physics::WindVolume;
 use diorama::picking::Hint;
+use diorama::player::Player;
+use diorama::sculpture::{Sculpture, SculptureMeshes};
+use diorama::slideshow::Slideshow;
+use diorama::sound_sculpture::SoundSculpture;
 use examples_common::noise::Perlin;
 
 use crate::config::{FrameType, PaintingConfig, PaintingStyle, SculptureConfig, SculptureType};
+use crate::guide::narration;
 use crate::materials::MuseumMaterials;
 use crate::shader_materials::*;
-use crate::{MuseumAssets, Rotating};
+use crate::{CEILING_HEIGHT, MuseumAssets, Rotating};
 
 // Constants for painting and frame dimensions - scaled by 1.5x
 const FRAME_DEPTH_REGULAR: f32 = 0.15; // Scaled from 0.1 to 0.15
 const PAINTING_ART_DEPTH_REGULAR: f32 = 0.03; // Scaled from 0.02 to 0.03
 const EFFECTIVE_PAINTING_OFFSET_REGULAR: f32 =
     FRAME_DEPTH_REGULAR / 2.0 + PAINTING_ART_DEPTH_REGULAR / 2.0; // 0.09 (was 0.06)
+/// Levels of detail for generated sculptures, each shown this much further away than the last
+const SCULPTURE_LODS: usize = 3;
+const SCULPTURE_LOD_DISTANCE: f32 = 15.0;
 
 #[derive(Component)]
 pub struct PaintingDialogue {
@@ -84,10 +116,15 @@ pub fn place_artworks(
     images: &mut ResMut<Assets<Image>>,
     museum_assets: &Res<MuseumAssets>,
     museum_materials: &MuseumMaterials,
+    sculptures: &mut SculptureMeshes,
 ) {
     place_wall_paintings(commands, meshes, materials, images, museum_materials);
-    place_sculptures(commands, meshes, materials, museum_materials);
+    place_sculptures(commands, meshes, materials, museum_materials, sculptures);
     place_central_installation(commands, meshes, materials, images, museum_assets);
+    place_lava_exhibit(commands, meshes, materials, images, museum_materials);
+    place_mobile(commands, meshes, materials);
+    place_sound_sculpture(commands, meshes, materials);
+    place_projection(commands, images);
 }
 
 fn place_wall_paintings(
@@ -98,19 +135,218 @@ fn place_wall_paintings(
     museum_materials: &MuseumMaterials,
 ) {
     // Use config-driven approach to reduce hardcoded values
-    for config in PaintingConfig::main_gallery() {
+    let mut artworks = Vec::new();
+    let mut styles = ArtworkStyles::default();
+    for (number, config) in (1..).zip(PaintingConfig::main_gallery()) {
+        // The fractal is a shader rather than a texture, so it stays put
+        let slot = match config.style {
+            PaintingStyle::Fractal => None,
+            style => {
+                let texture = images.add(generate_artwork_texture(
+                    style,
+                    ARTWORK_RESOLUTION,
+                    ARTWORK_RESOLUTION,
+                ));
+                styles.0.insert(texture.id(), style);
+                artworks.push(CuratedArtwork::new(
+                    config.name,
+                    painting_material(materials, texture),
+                ));
+                Some(DisplaySlot::new(artworks.len() - 1))
+            }
+        };
         create_framed_painting(
             commands,
             meshes,
-            materials,
-            images,
             config.name,
+            number,
             config.position,
-            config.style,
             config.frame_type,
+            slot,
             museum_materials,
         );
     }
+
+    // Variations on the gallery's textures, kept in reserve to rotate onto the walls
+    let reserve = [
+        (
+            "Abstract Composition #2",
+            "Abstract Composition #1",
+            Color::srgb(0.7, 0.8, 1.0),
+            0.5,
+        ),
+        (
+            "Color Study #48",
+            "Color Study #47",
+            Color::srgb(1.0, 0.75, 0.6),
+            0.6,
+        ),
+        (
+            "Blue Marble",
+            "Marble Veins",
+            Color::srgb(0.6, 0.75, 1.0),
+            0.7,
+        ),
+        (
+            "Storm Clouds",
+            "Perlin Clouds",
+            Color::srgb(0.55, 0.55, 0.65),
+            0.6,
+        ),
+    ];
+    for (name, original, tint, scale) in reserve {
+        let Some(texture) = artworks
+            .iter()
+            .find(|artwork| artwork.name == original)
+            .and_then(|artwork| materials.get(&artwork.material))
+            .and_then(|material| material.base_color_texture.clone())
+        else {
+            continue;
+        };
+        let material = painting_material(materials, texture);
+        if let Some(material) = materials.get_mut(&material) {
+            material.base_color = tint;
+            // A different crop of the same texture
+            material.uv_transform = Affine2::from_scale_angle_translation(
+                Vec2::splat(scale),
+                0.0,
+                Vec2::splat((1.0 - scale) / 2.0),
+            );
+        }
+        artworks.push(CuratedArtwork::new(name, material));
+    }
+
+    commands.insert_resource(styles);
+    commands.insert_resource(
+        Curation::new(artworks)
+            .per_visit()
+            .with_rotation(CURATION_ROTATE_SECONDS)
+            .with_dissolve_seconds(3.0),
+    );
+}
+
+/// How often the paintings on the walls are swapped for others from the collection
+const CURATION_ROTATE_SECONDS: f32 = 300.0;
+
+/// Resolution the paintings' textures are generated and kept at.
+const ARTWORK_RESOLUTION: u32 = 1024;
+/// Resolution a painting's texture is regenerated at while it's examined up close.
+const DETAIL_RESOLUTION: u32 = 4096;
+
+/// The style each painting texture was generated in, so it can be regenerated in more detail.
+#[derive(Resource, Default)]
+pub struct ArtworkStyles(HashMap<AssetId<Image>, PaintingStyle>);
+
+/// A painting being examined up close, with its texture regenerated in more detail.
+#[derive(Component)]
+pub struct DetailTexture {
+    material: Handle<StandardMaterial>,
+    /// The texture the material goes back to afterwards.
+    cached: Handle<Image>,
+    task: Option<Task<Image>>,
+    /// Kept until the close-up ends, when dropping it frees the texture.
+    detailed: Option<Handle<Image>>,
+}
+
+/// Starts regenerating a painting's texture at [`DETAIL_RESOLUTION`] as the camera moves in.
+pub fn regenerate_detail_texture(
+    started: On<CloseUpStarted>,
+    mut commands: Commands,
+    styles: Option<Res<ArtworkStyles>>,
+    materials: Res<Assets<StandardMaterial>>,
+    paintings: Query<&MeshMaterial3d<StandardMaterial>, With<DisplaySlot>>,
+) {
+    let Ok(material) = paintings.get(started.entity) else {
+        return;
+    };
+    let Some(cached) = materials
+        .get(material)
+        .and_then(|material| material.base_color_texture.clone())
+    else {
+        return;
+    };
+    let Some(style) = styles.and_then(|styles| styles.0.get(&cached.id()).copied()) else {
+        return;
+    };
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let mut image = generate_artwork_texture(style, DETAIL_RESOLUTION, DETAIL_RESOLUTION);
+        // Only the GPU needs it, so don't keep a copy of the pixels around too
+        image.asset_usage = RenderAssetUsages::RENDER_WORLD;
+        image
+    });
+    commands.entity(started.entity).insert(DetailTexture {
+        material: material.0.clone(),
+        cached,
+        task: Some(task),
+        detailed: None,
+    });
+}
+
+/// Swaps regenerated textures in once they're ready.
+pub fn swap_in_detail_textures(
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut details: Query<&mut DetailTexture>,
+) {
+    for mut detail in &mut details {
+        let Some(task) = &mut detail.task else {
+            continue;
+        };
+        let Some(image) = block_on(future::poll_once(task)) else {
+            continue;
+        };
+        detail.task = None;
+        let texture = images.add(image);
+        if let Some(material) = materials.get_mut(&detail.material) {
+            material.base_color_texture = Some(texture.clone());
+        }
+        detail.detailed = Some(texture);
+    }
+}
+
+/// Drops back to the cached texture once the close-up's over, cancelling any regeneration still
+/// running.
+pub fn restore_cached_texture(
+    ended: On<CloseUpEnded>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    details: Query<&DetailTexture>,
+) {
+    let Ok(detail) = details.get(ended.entity) else {
+        return;
+    };
+    if let Some(material) = materials.get_mut(&detail.material) {
+        material.base_color_texture = Some(detail.cached.clone());
+    }
+    commands.entity(ended.entity).remove::<DetailTexture>();
+}
+
+fn painting_material(
+    materials: &mut Assets<StandardMaterial>,
+    texture: Handle<Image>,
+) -> Handle<StandardMaterial> {
+    materials.add(StandardMaterial {
+        base_color_texture: Some(texture),
+        base_color: Color::WHITE,
+        metallic: 0.0,
+        perceptual_roughness: 0.8,
+        ..default()
+    })
+}
+
+/// Renames a curated painting after whatever now hangs there, so its dialogue and audio guide
+/// entry follow it.
+pub fn on_artwork_hung(
+    hung: On<ArtworkHung>,
+    mut paintings: Query<(&mut Name, &mut PaintingDialogue, &mut Exhibit)>,
+) {
+    let Ok((mut name, mut dialogue, mut exhibit)) = paintings.get_mut(hung.entity) else {
+        return;
+    };
+    name.set(hung.name.clone());
+    dialogue.node_name = get_dialogue_node_for_painting(&hung.name);
+    exhibit.title = hung.name.clone();
+    exhibit.text = narration(&hung.name).to_string();
 }
 
 fn place_sculptures(
@@ -118,6 +354,7 @@ fn place_sculptures(
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     museum_materials: &MuseumMaterials,
+    sculptures: &mut SculptureMeshes,
 ) {
     // Use config-driven approach to reduce hardcoded values
     for config in SculptureConfig::sculpture_garden() {
@@ -129,6 +366,7 @@ fn place_sculptures(
             config.position,
             config.sculpture_type,
             museum_materials,
+            sculptures,
         );
     }
 }
@@ -161,6 +399,10 @@ fn place_central_installation(
             Rotating,
             RigidBody::Dynamic,
             Collider::sphere(1.5), // Match mesh dimensions exactly (radius)
+            // Held far enough out to clear the player
+            Carryable::new()
+                .with_hold_distance(2.5)
+                .with_break_distance(3.0),
             MaterialCycler {
                 materials: material_variants,
                 current_index: 0,
@@ -187,15 +429,237 @@ fn place_central_installation(
     }
 }
 
-fn create_framed_painting(
+/// A hanging mobile in the main room, turning in a draft from the ceiling vents.
+fn place_mobile(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let position = Vec3::new(-6.0, CEILING_HEIGHT, 6.0);
+    let arm_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.15, 0.15, 0.17),
+        metallic: 0.9,
+        perceptual_roughness: 0.35,
+        ..default()
+    });
+    let weight_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.85, 0.2, 0.1),
+        perceptual_roughness: 0.6,
+        ..default()
+    });
+    Mobile::new(4)
+        .with_arm_length(2.0, 0.75)
+        .with_thread_length(0.7)
+        .with_weight(0.22, 0.4)
+        .spawn(
+            commands,
+            meshes,
+            arm_material,
+            weight_material,
+            Transform::from_translation(position),
+        );
+
+    // Just enough to keep it turning without anyone touching it
+    commands.spawn((
+        Name::new("Mobile Draft"),
+        WindVolume::new(Vec3::new(2.0, 1.5, 2.0), Vec3::new(0.4, 0.0, 0.15))
+            .with_drag(0.3)
+            .with_gustiness(0.8),
+        Transform::from_translation(position - Vec3::Y * 2.5),
+    ));
+}
+
+/// Where the sound sculpture stands, on the gallery floor
+pub const SOUND_SCULPTURE_POSITION: Vec3 = Vec3::new(-6.0, 0.0, -6.0);
+
+/// A ring of chime tubes on a plinth, playing a tune that changes with where visitors stand.
+fn place_sound_sculpture(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    const TUBES: usize = 10;
+    let brass = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.78, 0.6, 0.3),
+        metallic: 1.0,
+        perceptual_roughness: 0.25,
+        ..default()
+    });
+    let plinth = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.12, 0.12, 0.14),
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+
+    commands
+        .spawn((
+            Name::new("Singing Chimes"),
+            Hint::new("🔔 Singing Chimes - Walk around it to change the tune"),
+            Transform::from_translation(SOUND_SCULPTURE_POSITION),
+            Visibility::default(),
+            SoundSculpture::pentatonic(392.0, 2)
+                .with_seed(7)
+                .with_range(10.0)
+                .with_caption("chimes ringing"),
+            RigidBody::Static,
+            Collider::cylinder(0.6, 0.6),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Mesh3d(meshes.add(Cylinder::new(0.6, 0.6))),
+                MeshMaterial3d(plinth),
+                Transform::from_xyz(0.0, 0.3, 0.0),
+            ));
+            // Longest tubes first, the lowest notes, around the ring
+            for i in 0..TUBES {
+                let angle = i as f32 / TUBES as f32 * std::f32::consts::TAU;
+                let length = 1.8 - i as f32 * 0.1;
+                parent.spawn((
+                    Mesh3d(meshes.add(Cylinder::new(0.04, length))),
+                    MeshMaterial3d(brass.clone()),
+                    Transform::from_xyz(
+                        angle.cos() * 0.4,
+                        0.6 + 0.1 + length / 2.0,
+                        angle.sin() * 0.4,
+                    ),
+                ));
+            }
+        });
+}
+
+/// Where the projection screen hangs, on the north wall of the corridor to the third room
+pub const PROJECTION_POSITION: Vec3 = Vec3::new(17.5, 3.0, -48.7);
+
+/// A slideshow of every painting style, projected in the dimmed corridor to the third room.
+fn place_projection(commands: &mut Commands, images: &mut ResMut<Assets<Image>>) {
+    const STYLES: [PaintingStyle; 12] = [
+        PaintingStyle::Abstract,
+        PaintingStyle::Geometric,
+        PaintingStyle::ColorField,
+        PaintingStyle::Organic,
+        PaintingStyle::Fractal,
+        PaintingStyle::Minimalist,
+        PaintingStyle::Digital,
+        PaintingStyle::Noise,
+        PaintingStyle::Cellular,
+        PaintingStyle::Gold,
+        PaintingStyle::Clouds,
+        PaintingStyle::Marble,
+    ];
+    // At the screen's 16:9, so slides are as sharp across as they are down
+    let slides = STYLES
+        .into_iter()
+        .map(|style| images.add(generate_artwork_texture(style, 640, 360)))
+        .collect();
+    commands.spawn((
+        Name::new("Projection"),
+        Slideshow::new(slides)
+            .with_size(Vec2::new(4.8, 2.7))
+            .with_interval(6.0, 1.0)
+            .with_projector(7.0)
+            .with_dimming(Vec3::new(7.5, 3.0, 3.5), 150.0),
+        Transform::from_translation(PROJECTION_POSITION),
+    ));
+}
+
+/// Where visitors who step into the lava are moved back to, on the gallery floor beside it
+const LAVA_SAFE_POINT: Vec3 = Vec3::new(9.0, 2.0, 0.0);
+
+fn place_lava_exhibit(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     images: &mut ResMut<Assets<Image>>,
+    museum_materials: &MuseumMaterials,
+) {
+    // Stone basin along the east wall, with low curbs either side of the channel
+    let exhibit = commands
+        .spawn((
+            Name::new("Lava Exhibit"),
+            Transform::from_xyz(12.0, 0.0, 0.0),
+            Visibility::default(),
+        ))
+        .with_children(|exhibit| {
+            exhibit.spawn((
+                Name::new("Lava Basin"),
+                Mesh3d(meshes.add(Cuboid::new(3.4, 0.2, 13.0))),
+                MeshMaterial3d(museum_materials.polished_stone.clone()),
+                Transform::from_xyz(0.0, 0.1, 0.0),
+                RigidBody::Static,
+                Collider::cuboid(3.4, 0.2, 13.0),
+            ));
+            for (i, x) in [-1.85, 1.85].into_iter().enumerate() {
+                exhibit.spawn((
+                    Name::new(format!("Lava Basin Curb {}", i + 1)),
+                    Mesh3d(meshes.add(Cuboid::new(0.3, 0.7, 13.0))),
+                    MeshMaterial3d(museum_materials.polished_stone.clone()),
+                    Transform::from_xyz(x, 0.35, 0.0),
+                    RigidBody::Static,
+                    Collider::cuboid(0.3, 0.7, 13.0),
+                ));
+            }
+            exhibit.spawn((
+                Name::new("Lava Glow"),
+                PointLight {
+                    color: Color::srgb(1.0, 0.45, 0.1),
+                    intensity: 40_000.0,
+                    range: 8.0,
+                    ..default()
+                },
+                Transform::from_xyz(0.0, 1.0, 0.0),
+            ));
+        })
+        .id();
+
+    let texture = images.add(flow_texture(11));
+    let lava_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.9, 0.3, 0.05),
+        base_color_texture: Some(texture.clone()),
+        emissive: LinearRgba::rgb(6.0, 1.5, 0.2),
+        emissive_texture: Some(texture),
+        perceptual_roughness: 0.6,
+        ..default()
+    });
+    let lava = Flow::lava([
+        Vec3::new(0.0, 0.3, -6.0),
+        Vec3::new(-0.5, 0.28, -2.0),
+        Vec3::new(0.5, 0.26, 2.0),
+        Vec3::new(0.0, 0.25, 6.0),
+    ])
+    .spawn(commands, meshes, lava_material, Transform::IDENTITY);
+    commands
+        .entity(lava)
+        .insert((
+            Name::new("Lava Flow"),
+            Hint::new("🌋 Lava Channel - Look, but don't touch!"),
+            FlowEmitter::new(LinearRgba::rgb(8.0, 2.5, 0.4), 4.0)
+                .with_lifetime(1.5)
+                .with_size(0.05)
+                .with_rise(0.8),
+        ))
+        .observe(on_lava_touched);
+    commands.entity(exhibit).add_child(lava);
+}
+
+fn on_lava_touched(
+    _touched: On<HazardEntered>,
+    mut player: Single<(&mut Transform, &mut LinearVelocity), With<Player>>,
+) {
+    let (transform, velocity) = &mut *player;
+    transform.translation = LAVA_SAFE_POINT;
+    **velocity = LinearVelocity::ZERO;
+}
+
+/// Hangs a framed painting, which shows the fractal shader unless it's a curated `slot`.
+#[allow(clippy::too_many_arguments)]
+fn create_framed_painting(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
     name: &str,
+    number: u32,
     position: Vec3,
-    style: PaintingStyle,
     frame_type: FrameType,
+    slot: Option<DisplaySlot>,
     museum_materials: &MuseumMaterials,
 ) {
     let frame_material = match frame_type {
@@ -261,45 +725,95 @@ fn create_framed_painting(
         Transform::from_translation(frame_position).with_rotation(rotation),
     ));
 
-    // Handle fractal painting separately due to different material types
-    // Spawn the painting entity and attach click observer
-    let mut painting_entity = if let PaintingStyle::Fractal = style {
-        commands.spawn((
-            Name::new(name.to_string()),
-            Hint::new("🖼️ Procedural Artwork - Click to discuss the algorithms behind this piece"),
-            Mesh3d(meshes.add(Cuboid::new(2.4, 1.8, PAINTING_ART_DEPTH_REGULAR))), // Scaled from (1.6, 1.2)
-            MeshMaterial3d(museum_materials.fractal_painting.clone()),
-            Transform::from_translation(frame_position + painting_offset).with_rotation(rotation),
-            PaintingDialogue {
-                node_name: get_dialogue_node_for_painting(name),
-            },
-        ))
-    } else {
-        // Use traditional texture-based material for other styles
-        let painting_texture = generate_artwork_texture(images, style, 2048, 2048);
-        let painting_material = materials.add(StandardMaterial {
-            base_color_texture: Some(painting_texture),
-            base_color: Color::WHITE,
-            metallic: 0.0,
-            perceptual_roughness: 0.8,
-            ..default()
-        });
-
-        commands.spawn((
-            Name::new(name.to_string()),
-            Hint::new("🖼️ Procedural Artwork - Click to discuss the algorithms behind this piece"),
-            Mesh3d(meshes.add(Cuboid::new(2.4, 1.8, PAINTING_ART_DEPTH_REGULAR))), // Scaled from (1.6, 1.2)
-            MeshMaterial3d(painting_material),
-            Transform::from_translation(frame_position + painting_offset).with_rotation(rotation),
-            PaintingDialogue {
-                node_name: get_dialogue_node_for_painting(name),
-            },
-        ))
+    let mut painting_entity = commands.spawn((
+        Name::new(name.to_string()),
+        Hint::new("🖼️ Procedural Artwork - Click to discuss the algorithms behind this piece"),
+        Mesh3d(meshes.add(Cuboid::new(2.4, 1.8, PAINTING_ART_DEPTH_REGULAR))), // Scaled from (1.6, 1.2)
+        Transform::from_translation(frame_position + painting_offset).with_rotation(rotation),
+        PaintingDialogue {
+            node_name: get_dialogue_node_for_painting(name),
+        },
+        Exhibit::new(number, name, narration(name)),
+        // Framing the whole canvas, with room to lean in on the details
+        CloseUp::new().with_distance(0.3, 2.4),
+    ));
+    // Curated paintings get their material when the curation hangs an artwork there
+    match slot {
+        Some(slot) => painting_entity.insert(slot),
+        None => painting_entity.insert(MeshMaterial3d(museum_materials.fractal_painting.clone())),
     };
 
     painting_entity.observe(on_painting_click);
+
+    // The artist's first sketch, under the paint
+    painting_entity.with_child((
+        Name::new(format!("{name} Underdrawing")),
+        Mesh3d(meshes.add(underdrawing_mesh(number))),
+        MeshMaterial3d(museum_materials.underdrawing.clone()),
+        Transform::from_xyz(0.0, 0.0, PAINTING_ART_DEPTH_REGULAR / 2.0 + 0.005),
+        HiddenDetail::new().with_tint(Color::srgba(1.0, 1.0, 1.0, 0.9)),
+    ));
+}
+
+/// Construction lines for a painting's composition: a border, a grid of thirds, a horizon, a
+/// circle for the subject and a few gestural strokes, varied by `seed`.
+fn underdrawing_mesh(seed: u32) -> Mesh {
+    const HALF: Vec2 = Vec2::new(1.1, 0.8);
+    // Cheap hash of the seed, so every painting gets its own composition
+    let mut state = seed.wrapping_mul(2_654_435_761).wrapping_add(1);
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state % 10_000) as f32 / 10_000.0
+    };
+
+    let mut lines: Vec<(Vec2, Vec2)> = vec![
+        (Vec2::new(-HALF.x, -HALF.y), Vec2::new(HALF.x, -HALF.y)),
+        (Vec2::new(HALF.x, -HALF.y), Vec2::new(HALF.x, HALF.y)),
+        (Vec2::new(HALF.x, HALF.y), Vec2::new(-HALF.x, HALF.y)),
+        (Vec2::new(-HALF.x, HALF.y), Vec2::new(-HALF.x, -HALF.y)),
+    ];
+    for third in [-1.0 / 3.0, 1.0 / 3.0] {
+        lines.push((
+            Vec2::new(HALF.x * third * 2.0, -HALF.y),
+            Vec2::new(HALF.x * third * 2.0, HALF.y),
+        ));
+        lines.push((
+            Vec2::new(-HALF.x, HALF.y * third * 2.0),
+            Vec2::new(HALF.x, HALF.y * third * 2.0),
+        ));
+    }
+    let horizon = (next() - 0.5) * HALF.y;
+    lines.push((
+        Vec2::new(-HALF.x, horizon + (next() - 0.5) * 0.2),
+        Vec2::new(HALF.x, horizon + (next() - 0.5) * 0.2),
+    ));
+    let center = Vec2::new((next() - 0.5) * HALF.x, (next() - 0.5) * HALF.y);
+    let radius = 0.2 + next() * 0.35;
+    const SEGMENTS: usize = 32;
+    for i in 0..SEGMENTS {
+        let angle = |i: usize| i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        lines.push((
+            center + Vec2::from_angle(angle(i)) * radius,
+            center + Vec2::from_angle(angle(i + 1)) * radius,
+        ));
+    }
+    for _ in 0..3 {
+        let start = Vec2::new((next() - 0.5) * 2.0, (next() - 0.5) * 2.0) * HALF;
+        let end = Vec2::new((next() - 0.5) * 2.0, (next() - 0.5) * 2.0) * HALF;
+        lines.push((start, end));
+    }
+
+    let positions: Vec<[f32; 3]> = lines
+        .iter()
+        .flat_map(|(start, end)| [start.extend(0.0).to_array(), end.extend(0.0).to_array()])
+        .collect();
+    Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_sculpture(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
@@ -308,6 +822,7 @@ fn create_sculpture(
     position: Vec3,
     sculpture_type: SculptureType,
     museum_materials: &MuseumMaterials,
+    sculptures: &mut SculptureMeshes,
 ) {
     match sculpture_type {
         SculptureType::Twisted => {
@@ -317,24 +832,14 @@ fn create_sculpture(
                 perceptual_roughness: 0.4,
                 ..default()
             });
+            let lods = sculptures.get_or_build(&Sculpture::twisted_spire(), SCULPTURE_LODS, meshes);
 
-            for i in 0..8 {
-                let height = i as f32 * 0.225; // Scaled from 0.15 to 0.225 (1.5x)
-                let rotation = Quat::from_rotation_y(i as f32 * 0.3);
-                let scale_factor = 1.0 - (i as f32 * 0.1);
-
-                commands.spawn((
-                    Name::new(format!("{name} Segment {i}")),
-                    Mesh3d(meshes.add(Cuboid::new(
-                        0.45 * scale_factor,
-                        0.225,
-                        0.15 * scale_factor,
-                    ))), // Scaled dimensions by 1.5x
-                    MeshMaterial3d(material.clone()),
-                    Transform::from_translation(position + Vec3::new(0.0, height, 0.0))
-                        .with_rotation(rotation),
-                ));
-            }
+            commands.spawn((
+                Name::new(name.to_string()),
+                lods.lod(SCULPTURE_LOD_DISTANCE),
+                MeshMaterial3d(material),
+                Transform::from_translation(position),
+            ));
         }
         SculptureType::Geometric => {
             // Use new custom shader material for geometric sculpture
@@ -369,21 +874,14 @@ fn create_sculpture(
                 perceptual_roughness: 0.6,
                 ..default()
             });
+            let lods = sculptures.get_or_build(&Sculpture::organic(), SCULPTURE_LODS, meshes);
 
-            for i in 0..5 {
-                let angle = (i as f32) * std::f32::consts::PI * 2.0 / 5.0;
-                let radius = 0.45 + (i as f32 * 0.15); // Scaled from (0.3 + i * 0.1) to (0.45 + i * 0.15)
-                let x = angle.cos() * radius * 0.75; // Scaled multiplier from 0.5 to 0.75
-                let z = angle.sin() * radius * 0.75; // Scaled multiplier from 0.5 to 0.75
-                let y = i as f32 * 0.15; // Scaled from 0.1 to 0.15
-
-                commands.spawn((
-                    Name::new(format!("{name} Flow {i}")),
-                    Mesh3d(meshes.add(Sphere::new(0.3 - i as f32 * 0.03))), // Scaled from (0.2 - i * 0.02) to (0.3 - i * 0.03)
-                    MeshMaterial3d(material.clone()),
-                    Transform::from_translation(position + Vec3::new(x, y, z)),
-                ));
-            }
+            commands.spawn((
+                Name::new(name.to_string()),
+                lods.lod(SCULPTURE_LOD_DISTANCE),
+                MeshMaterial3d(material),
+                Transform::from_translation(position),
+            ));
         }
         SculptureType::Crystal => {
             // Use geometric shader material for magical crystal effect
@@ -411,33 +909,33 @@ fn create_sculpture(
     }
 }
 
-fn generate_artwork_texture(
-    images: &mut ResMut<Assets<Image>>,
-    style: PaintingStyle,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_artwork_texture(style: PaintingStyle, width: u32, height: u32) -> Image {
     match style {
-        PaintingStyle::Abstract => generate_abstract_texture(images, width, height),
-        PaintingStyle::Geometric => generate_geometric_texture(images, width, height),
-        PaintingStyle::ColorField => generate_colorfield_texture(images, width, height),
-        PaintingStyle::Organic => generate_organic_texture(images, width, height),
-        PaintingStyle::Fractal => generate_fractal_texture(images, width, height),
-        PaintingStyle::Minimalist => generate_minimalist_texture(images, width, height),
-        PaintingStyle::Digital => generate_digital_texture(images, width, height),
-        PaintingStyle::Noise => generate_noise_texture(images, width, height),
-        PaintingStyle::Cellular => generate_cellular_texture(images, width, height),
-        PaintingStyle::Clouds => generate_clouds_texture(images, width, height),
-        PaintingStyle::Marble => generate_marble_art_texture(images, width, height),
-        PaintingStyle::Gold => generate_gold_texture(images, width, height),
+        PaintingStyle::Abstract => generate_abstract_texture(width, height),
+        PaintingStyle::Geometric => generate_geometric_texture(width, height),
+        PaintingStyle::ColorField => generate_colorfield_texture(width, height),
+        PaintingStyle::Organic => generate_organic_texture(width, height),
+        PaintingStyle::Fractal => generate_fractal_texture(width, height),
+        PaintingStyle::Minimalist => generate_minimalist_texture(width, height),
+        PaintingStyle::Digital => generate_digital_texture(width, height),
+        PaintingStyle::Noise => generate_noise_texture(width, height),
+        PaintingStyle::Cellular => generate_cellular_texture(width, height),
+        PaintingStyle::Clouds => generate_clouds_texture(width, height),
+        PaintingStyle::Marble => generate_marble_art_texture(width, height),
+        PaintingStyle::Gold => generate_gold_texture(width, height),
     }
 }
 
-fn generate_abstract_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+/// Pixel-based patterns are laid out on a canvas this many pixels across, so they look the same
+/// whatever resolution they're generated at.
+const PATTERN_SIZE: u32 = 2048;
+
+/// Where pixel `(x, y)` of a `width` by `height` texture falls on the pattern canvas.
+fn pattern_position(x: u32, y: u32, width: u32, height: u32) -> (u32, u32) {
+    (x * PATTERN_SIZE / width, y * PATTERN_SIZE / height)
+}
+
+fn generate_abstract_texture(width: u32, height: u32) -> Image {
     let perlin = Perlin::new(1234);
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
@@ -461,18 +959,15 @@ fn generate_abstract_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_geometric_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_geometric_texture(width: u32, height: u32) -> Image {
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
     for y in 0..height {
         for x in 0..width {
+            let (x, y) = pattern_position(x, y, width, height);
             let grid_x = (x / 32) % 2;
             let grid_y = (y / 32) % 2;
             let checkerboard = (grid_x + grid_y) % 2;
@@ -487,14 +982,10 @@ fn generate_geometric_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_colorfield_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_colorfield_texture(width: u32, height: u32) -> Image {
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
     for y in 0..height {
@@ -510,14 +1001,10 @@ fn generate_colorfield_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_organic_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_organic_texture(width: u32, height: u32) -> Image {
     let perlin = Perlin::new(5678);
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
@@ -540,14 +1027,10 @@ fn generate_organic_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_fractal_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_fractal_texture(width: u32, height: u32) -> Image {
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
     for y in 0..height {
@@ -578,14 +1061,10 @@ fn generate_fractal_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_minimalist_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_minimalist_texture(width: u32, height: u32) -> Image {
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
     for y in 0..height {
@@ -603,18 +1082,15 @@ fn generate_minimalist_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_digital_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_digital_texture(width: u32, height: u32) -> Image {
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
     for y in 0..height {
         for x in 0..width {
+            let (x, y) = pattern_position(x, y, width, height);
             let bit_x = (x / 8) % 2;
             let bit_y = (y / 8) % 2;
             let intensity = ((x + y) % 64) as f32 / 64.0;
@@ -640,14 +1116,10 @@ fn generate_digital_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_noise_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_noise_texture(width: u32, height: u32) -> Image {
     let perlin = Perlin::new(9999);
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
@@ -663,18 +1135,15 @@ fn generate_noise_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_cellular_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_cellular_texture(width: u32, height: u32) -> Image {
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
     for y in 0..height {
         for x in 0..width {
+            let (x, y) = pattern_position(x, y, width, height);
             let cell_size = 16;
             let cell_x = x / cell_size;
             let cell_y = y / cell_size;
@@ -694,14 +1163,10 @@ fn generate_cellular_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_clouds_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_clouds_texture(width: u32, height: u32) -> Image {
     let perlin = Perlin::new(4567);
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
@@ -726,14 +1191,10 @@ fn generate_clouds_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_marble_art_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_marble_art_texture(width: u32, height: u32) -> Image {
     let perlin = Perlin::new(7890);
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
@@ -757,14 +1218,10 @@ fn generate_marble_art_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn generate_gold_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_gold_texture(width: u32, height: u32) -> Image {
     let perlin = Perlin::new(12345);
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
@@ -790,14 +1247,14 @@ fn generate_gold_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
 fn create_holographic_material(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     images: &mut ResMut<Assets<Image>>,
 ) -> Handle<StandardMaterial> {
-    let holographic_texture = generate_holographic_texture(images, 512, 512);
+    let holographic_texture = images.add(generate_holographic_texture(512, 512));
 
     materials.add(StandardMaterial {
         base_color_texture: Some(holographic_texture),
@@ -886,11 +1343,7 @@ fn create_orbiting_element_material(
     })
 }
 
-fn generate_holographic_texture(
-    images: &mut ResMut<Assets<Image>>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
+fn generate_holographic_texture(width: u32, height: u32) -> Image {
     let mut data = Vec::with_capacity((width * height * 4) as usize);
 
     for y in 0..height {
@@ -910,16 +1363,11 @@ fn generate_holographic_texture(
         }
     }
 
-    create_image(images, data, width, height)
+    create_image(data, width, height)
 }
 
-fn create_image(
-    images: &mut ResMut<Assets<Image>>,
-    data: Vec<u8>,
-    width: u32,
-    height: u32,
-) -> Handle<Image> {
-    let image = Image::new(
+fn create_image(data: Vec<u8>, width: u32, height: u32) -> Image {
+    Image::new(
         Extent3d {
             width,
             height,
@@ -929,9 +1377,7 @@ fn create_image(
         data,
         TextureFormat::Rgba8UnormSrgb,
         default(),
-    );
-
-    images.add(image)
+    )
 }
 
 fn on_sphere_click(
@@ -947,14 +1393,19 @@ fn on_sphere_click(
     }
 }
 
+/// Names of the artworks visitors have asked about, for the visitor statistics plaque.
+#[derive(Resource, Default)]
+pub struct ArtworksViewed(pub HashSet<String>);
+
 fn on_painting_click(
     _click: On<Pointer<Click>>,
     mut commands: Commands,
     project: Res<YarnProject>,
-    painting_query: Query<&PaintingDialogue>,
+    mut viewed: ResMut<ArtworksViewed>,
+    painting_query: Query<(&PaintingDialogue, &Name)>,
     existing_runners: Query<&DialogueRunner>,
 ) {
-    if let Ok(painting_dialogue) = painting_query.get(_click.event().entity) {
+    if let Ok((painting_dialogue, name)) = painting_query.get(_click.event().entity) {
         // Check if any dialogue is already running to prevent crashes and overlapping conversations
         for dialogue_runner in existing_runners.iter() {
             if dialogue_runner.is_running() {
@@ -966,7 +1417,8 @@ fn on_painting_click(
         // No active dialogue found, safe to start a new one
         let mut dialogue_runner = project.create_dialogue_runner(&mut commands);
         dialogue_runner.start_node(&painting_dialogue.node_name);
-        commands.spawn(dialogue_runner);
+        commands.spawn((dialogue_runner, DialogueInProgress));
+        viewed.0.insert(name.to_string());
     }
 }
 
@@ -985,6 +1437,44 @@ pub fn cleanup_finished_dialogue_runners(
     }
 }
 
+/// Starts the next visitor to an unattended kiosk afresh: with no artworks asked about, every
+/// clicked sphere back in its first material, and no conversation left running.
+pub fn reset_for_next_visitor(
+    _reset: On<KioskReset>,
+    mut viewed: ResMut<ArtworksViewed>,
+    mut cyclers: Query<(&mut MeshMaterial3d<StandardMaterial>, &mut MaterialCycler)>,
+    mut dialogue_runners: Query<&mut DialogueRunner>,
+) {
+    viewed.0.clear();
+    for (mut material, mut cycler) in &mut cyclers {
+        cycler.current_index = 0;
+        if let Some(first) = cycler.materials.first() {
+            material.0 = first.clone();
+        }
+    }
+    for mut dialogue_runner in &mut dialogue_runners {
+        if dialogue_runner.is_running() {
+            dialogue_runner.stop();
+        }
+    }
+}
+
+/// Records each presented line in the session dialogue log so visitors can revisit exhibit lore
+pub fn log_presented_line(present: On<PresentLine>, mut log: ResMut<DialogueLog>) {
+    let line = &present.line;
+    log.push_line(
+        line.character_name().map(str::to_string),
+        line.text_without_character_name(),
+    );
+}
+
+/// Records each presented option in the session dialogue log
+pub fn log_presented_options(present: On<PresentOptions>, mut log: ResMut<DialogueLog>) {
+    for option in &present.options {
+        log.push_choice(option.line.text_without_character_name());
+    }
+}
+
 fn get_dialogue_node_for_painting(painting_name: &str) -> String {
     match painting_name {
         "Abstract Composition #1" => "AbstractComposition1",
@@ -999,6 +1489,11 @@ fn get_dialogue_node_for_painting(painting_name: &str) -> String {
         "Wave Function" => "WaveFunction",
         "Perlin Clouds" => "PerlinClouds",
         "Marble Veins" => "MarbleVeins",
+        // Variations from the reserve collection share their originals' dialogue
+        "Abstract Composition #2" => "AbstractComposition1",
+        "Color Study #48" => "ColorStudy47",
+        "Storm Clouds" => "PerlinClouds",
+        "Blue Marble" => "MarbleVeins",
         _ => {
             warn!("No dialogue node found for painting: {}", painting_name);
             "FractalDreams" // Fallback to existing node
@@ -1,8 +1,7 @@
-// The time since startup data is in the globals binding which is part of the mesh_view_bindings import
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
+
+// Seconds of game time, which stops while paused
+@group(3) @binding(0) var<uniform> time: f32;
 
 fn oklab_to_linear_srgb(c: vec3<f32>) -> vec3<f32> {
     let L = c.x;
@@ -27,10 +26,8 @@ fn oklab_to_linear_srgb(c: vec3<f32>) -> vec3<f32> {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let speed = 2.0;
-    // The globals binding contains various global values like time
-    // which is the time since startup in seconds
-    let t_1 = sin(globals.time * speed) * 0.5 + 0.5;
-    let t_2 = cos(globals.time * speed);
+    let t_1 = sin(time * speed) * 0.5 + 0.5;
+    let t_2 = cos(time * speed);
 
     let distance_to_center = distance(in.uv, vec2<f32>(0.5)) * 1.4;
 
@@ -1,13 +1,11 @@
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct ConstellationMaterial {
     star_color: vec4<f32>,
     nebula_color: vec4<f32>,
     twinkle_speed: f32,
     star_density: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: ConstellationMaterial;
@@ -205,7 +203,7 @@ fn create_shooting_stars(uv: vec2<f32>, time: f32) -> vec3<f32> {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Start with pure black space background
     var color = vec3<f32>(0.0, 0.0, 0.0);
@@ -1,13 +1,11 @@
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct EnergyFieldMaterial {
     energy_color: vec4<f32>,
     arc_intensity: f32,
     flow_speed: f32,
     noise_scale: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: EnergyFieldMaterial;
@@ -72,7 +70,7 @@ fn electrical_arc(uv: vec2<f32>, time: f32) -> f32 {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Create multiple electrical arc layers
     let arc1 = electrical_arc(uv, time);
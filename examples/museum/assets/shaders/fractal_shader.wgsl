@@ -1,7 +1,4 @@
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct FractalData {
     base_color: vec4<f32>,
@@ -1,7 +1,6 @@
 // Emissive geometric pattern shader with animated effects
 #import bevy_pbr::{
     forward_io::{VertexOutput, FragmentOutput},
-    mesh_view_bindings::globals,
 }
 
 struct GeometricData {
@@ -25,7 +24,7 @@ fn fragment(
     @builtin(front_facing) is_front: bool,
 ) -> FragmentOutput {
     // Get time for animation
-    let time = globals.time;
+    let time = material.time;
 
     // Sample noise texture with animated UV coordinates
     let animated_uv = in.uv + vec2<f32>(time * 0.1, time * 0.05) * material.animation_speed;
@@ -2,7 +2,7 @@
 // Provides realistic glass-like transparency with subtle refraction and fresnel effects
 
 #import bevy_pbr::{
-    mesh_view_bindings::{globals, view},
+    mesh_view_bindings::view,
     forward_io::VertexOutput,
     utils::PI,
     view_transformations::position_world_to_clip,
@@ -15,7 +15,7 @@ struct GlassMaterial {
     transparency: f32,
     refraction_strength: f32,
     fresnel_power: f32,
-    _padding: f32,
+    time: f32,
 }
 
 fn fresnel_schlick(cos_theta: f32, f0: f32) -> f32 {
@@ -48,7 +48,7 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let color_variation = 1.0 + sin(distorted_uv.x * 3.14159) * sin(distorted_uv.y * 3.14159) * 0.02;
 
     // Subtle time-based shimmer effect
-    let shimmer = sin(globals.time * 0.5 + in.world_position.x * 0.1 + in.world_position.z * 0.1) * 0.01 + 1.0;
+    let shimmer = sin(material.time * 0.5 + in.world_position.x * 0.1 + in.world_position.z * 0.1) * 0.01 + 1.0;
 
     // Apply effects to color
     let modified_rgb = glass_color.rgb * color_variation * shimmer;
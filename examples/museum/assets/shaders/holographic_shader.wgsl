@@ -1,12 +1,10 @@
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct HolographicMaterial {
     base_color: vec4<f32>,
     interference_intensity: f32,
     scan_speed: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: HolographicMaterial;
@@ -32,7 +30,7 @@ fn noise(p: vec2<f32>) -> f32 {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Create scanning lines that move vertically
     let scan_line = sin((uv.y * 20.0) + (time * material.scan_speed)) * 0.5 + 0.5;
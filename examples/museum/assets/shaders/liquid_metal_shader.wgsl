@@ -1,13 +1,11 @@
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct LiquidMetalMaterial {
     base_color: vec4<f32>,
     ripple_speed: f32,
     ripple_frequency: f32,
     metallic_strength: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: LiquidMetalMaterial;
@@ -62,7 +60,7 @@ fn calculate_normal(uv: vec2<f32>, time: f32) -> vec3<f32> {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Create flowing liquid surface with multiple ripple layers
     let ripple1 = sin((uv.x * material.ripple_frequency + time * material.ripple_speed) * 2.0);
@@ -1,7 +1,4 @@
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 // "The sculpture exists in superposition - simultaneously crystalline and liquid,
 // geometric and organic, order and chaos intertwined in eternal dance."
@@ -13,6 +10,7 @@ struct MorphingSculptureMaterial {
     morph_intensity: f32,
     detail_scale: f32,
     glow_strength: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: MorphingSculptureMaterial;
@@ -258,7 +256,7 @@ fn fractal_dimension(p: vec3<f32>, time: f32) -> f32 {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time * material.morph_speed;
+    let time = material.time * material.morph_speed;
 
     // Create multidimensional position coordinates
     let pos3d = vec3<f32>(
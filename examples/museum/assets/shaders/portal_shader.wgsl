@@ -1,13 +1,11 @@
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct PortalMaterial {
     center_color: vec4<f32>,
     edge_color: vec4<f32>,
     rotation_speed: f32,
     distortion_strength: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: PortalMaterial;
@@ -44,7 +42,7 @@ fn fractal_noise(p: vec2<f32>) -> f32 {
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     // Center UV coordinates around 0.5
     let uv = in.uv - 0.5;
-    let time = globals.time;
+    let time = material.time;
 
     // Calculate distance from center
     let dist = length(uv);
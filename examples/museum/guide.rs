@@ -0,0 +1,112 @@
+//! # Audio Guide
+//!
+//! Numbers every painting and sculpture, plus the central installation, sound
+//! sculpture, projection room and lava channel, for the audio guide. Paintings
+//! carry their own exhibit, so its narration can follow whichever artwork is
+//! hanging there. Narration is text only, shown as subtitles and ready for a
+//! text-to-speech voice.
+
+use bevy::prelude::*;
+use diorama::audio_guide::Exhibit;
+
+use crate::artworks::{PROJECTION_POSITION, SOUND_SCULPTURE_POSITION};
+use crate::config::{PaintingConfig, SculptureConfig};
+
+/// Narration for each exhibit, by name.
+pub fn narration(name: &str) -> &'static str {
+    match name {
+        "Abstract Composition #1" => {
+            "Three octaves of Perlin noise, each twice as fine as the last, drive the red, green and blue of this canvas separately. It's painted afresh from the same seed every time the museum opens."
+        }
+        "Geometric Harmony" => {
+            "A checkerboard of burnt orange and deep blue. The simplest of patterns, given weight by its gold frame."
+        }
+        "Color Study #47" => {
+            "Red fades to green from top to bottom, with a band of blue swelling through the middle. A single gradient, and nothing else."
+        }
+        "Organic Forms" => {
+            "Noise is fed back into itself here, warping the pattern so it flows like wood grain or smoke."
+        }
+        "Fractal Dreams" => {
+            "The Mandelbrot set. Each point is squared and added to itself fifty times over, and coloured by how quickly it escapes."
+        }
+        "Minimalist Study" => {
+            "A single block set in the middle of an empty canvas. The restraint is the point."
+        }
+        "Digital Landscape" => {
+            "A grid of tiny squares and diagonal ramps, in homage to early computer art."
+        }
+        "Noise Patterns" => {
+            "Perlin noise at a very fine scale, in greyscale. The same technique shapes the terrain in many games."
+        }
+        "Cellular Automata" => {
+            "A grid of cells, each alive in red or dead in blue, decided by hashing its position."
+        }
+        "Wave Function" => {
+            "Layers of noise stretched in one direction give this gold leaf its brushed, metallic sheen."
+        }
+        "Perlin Clouds" => {
+            "Soft clouds made with Perlin noise, the algorithm Ken Perlin devised after working on the film Tron."
+        }
+        "Marble Veins" => {
+            "Noise stretched across the canvas makes these veins, a classic recipe for procedural marble."
+        }
+        "Abstract Composition #2" => {
+            "A closer look at the noise of the first composition, cooled to blues and greys. Nothing was repainted, only cropped."
+        }
+        "Color Study #48" => {
+            "The companion to study forty-seven, the same gradient viewed through a warm glaze."
+        }
+        "Blue Marble" => {
+            "The gallery's marble recipe again, tinted blue and seen closer, so the veins run wider."
+        }
+        "Storm Clouds" => {
+            "Perlin clouds darkened until they threaten rain. Come back later and it may have moved on."
+        }
+        "Twisted Spire" => {
+            "Eight red blocks, each a little smaller than the one below and turned a little further, twisting up into a spire."
+        }
+        "Geometric Assembly" => {
+            "A cube, a sphere and a cylinder stacked on one another, with the sphere slowly turning."
+        }
+        "Organic Flow" => "Five blue spheres spiral outwards and upwards, shrinking as they go.",
+        "Crystalline Structure" => {
+            "Six glowing crystals in a ring, each turning and cycling through the colours of the rainbow."
+        }
+        "Central Holographic Installation" => {
+            "The centrepiece of the gallery. Click the sphere to cycle its surface through holographic, crystal, liquid metal, energy and neon materials."
+        }
+        "Singing Chimes" => {
+            "Ten brass tubes ring out a tune that's never written down. Step closer and it grows busier; walk around it and it shifts key, so every side of the room hears its own melody."
+        }
+        "Projection Room" => {
+            "Every style in the gallery, painted again at projector size and shown one after another. The lights are lowered so nothing competes with the screen."
+        }
+        "Lava Channel" => {
+            "A river of molten rock runs along the east wall. It's quite real, so please stay behind the curbs."
+        }
+        _ => "There is no narration for this exhibit yet.",
+    }
+}
+
+pub fn spawn_exhibits(mut commands: Commands) {
+    let sculptures = SculptureConfig::sculpture_garden()
+        .into_iter()
+        .map(|config| (config.name, config.position));
+    let installations = [
+        ("Central Holographic Installation", Vec3::new(0.0, 3.0, 0.0)),
+        ("Lava Channel", Vec3::new(12.0, 0.5, 0.0)),
+        ("Singing Chimes", SOUND_SCULPTURE_POSITION),
+        ("Projection Room", PROJECTION_POSITION),
+    ];
+
+    // Numbered after the paintings, which number themselves as they're hung
+    let first = PaintingConfig::main_gallery().len() as u32 + 1;
+    for (number, (name, position)) in (first..).zip(sculptures.chain(installations)) {
+        commands.spawn((
+            Name::new(format!("Exhibit {number}")),
+            Exhibit::new(number, name, narration(name)),
+            Transform::from_translation(position),
+        ));
+    }
+}
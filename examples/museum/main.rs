@@ -13,27 +13,50 @@
 //! - `materials.rs` - PBR materials and texture generation
 //! - `shader_materials.rs` - Custom shader materials
 //! - `room_layout.rs` - Museum architecture and spatial layout
+//! - `guide.rs` - Numbered exhibits and narration for the audio guide
 //!
 //! ## Performance Considerations
-//! - Procedural texture generation cached at startup
-//! - LOD-ready sculpture meshes
+//! - Procedural texture generation cached at startup, with paintings regenerated in more detail
+//!   only while examined up close
+//! - Procedurally generated sculptures with levels of detail
 //! - Shadow casting optimized for main lights only
 //! - Efficient material reuse across similar objects
 
+use avian3d::prelude::*;
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use bevy_yarnspinner::prelude::{YarnFileSource, YarnSpinnerPlugin};
 use bevy_yarnspinner_example_dialogue_view::ExampleYarnSpinnerDialogueViewPlugin;
 use diorama::DioramaPlugin;
+use diorama::annotations::{AnnotationSettings, Annotations};
+use diorama::attract::{AttractMode, AttractWaypoint};
+use diorama::audio::{AmbientLoop, LoopRecipe, SpatialAudioEmitter};
+use diorama::audio_guide::AudioGuide;
+use diorama::captions::Captions;
+use diorama::game_time::{GameTime, GameTimeMaterialPlugin};
+use diorama::guestbook::{Guestbook, GuestbookEntries};
+use diorama::interact::Interactable;
+use diorama::light_switch::{LightGroup, LightSwitch, LightsToggled};
+use diorama::navigation::{DestinationReached, DestinationUnreachable, NavAgent, NavGrid};
+use diorama::npc::LookAtPlayer;
+use diorama::picking::{CrosshairPicking, Hint, HintLabels, PickingPlugin};
+use diorama::plaque::StatsPlaque;
+use diorama::power::{Breaker, Generator, PoweredBy, Wire};
+use diorama::preload::PreloadManifest;
+use diorama::save::SaveGame;
+use diorama::sculpture::SculptureMeshes;
+use diorama::state::in_gameplay;
 
 mod artworks;
 mod config;
+mod guide;
 mod helpers;
 mod materials;
 mod room_layout;
 mod shader_materials;
 
-use diorama::player::Player;
+use diorama::player::{Player, PlayerPlugin};
 // Re-export the materials for external use
 pub use materials::{GeometricMaterial, GlassMaterial};
 pub use shader_materials::*;
@@ -66,18 +89,103 @@ impl Plugin for MuseumPlugin {
             MaterialPlugin::<FractalMaterial>::default(),
             MaterialPlugin::<MorphingSculptureMaterial>::default(),
         ))
+        // Shader animations read GameTime so they hold while paused
+        .add_plugins((
+            GameTimeMaterialPlugin::<GlassMaterial>::default(),
+            GameTimeMaterialPlugin::<GeometricMaterial>::default(),
+            GameTimeMaterialPlugin::<AnimatedMaterial>::default(),
+            GameTimeMaterialPlugin::<HolographicMaterial>::default(),
+            GameTimeMaterialPlugin::<PortalMaterial>::default(),
+            GameTimeMaterialPlugin::<EnergyFieldMaterial>::default(),
+            GameTimeMaterialPlugin::<LiquidMetalMaterial>::default(),
+            GameTimeMaterialPlugin::<ConstellationMaterial>::default(),
+            GameTimeMaterialPlugin::<FractalMaterial>::default(),
+            GameTimeMaterialPlugin::<MorphingSculptureMaterial>::default(),
+        ))
+        // Reflected so material parameters can be tuned live in the world inspector
+        .register_asset_reflect::<GlassMaterial>()
+        .register_asset_reflect::<GeometricMaterial>()
+        .register_asset_reflect::<HolographicMaterial>()
+        .register_asset_reflect::<PortalMaterial>()
+        .register_asset_reflect::<EnergyFieldMaterial>()
+        .register_asset_reflect::<LiquidMetalMaterial>()
+        .register_asset_reflect::<ConstellationMaterial>()
+        .register_asset_reflect::<FractalMaterial>()
+        .register_asset_reflect::<MorphingSculptureMaterial>()
         .init_collection::<MuseumAssets>()
-        .add_systems(Startup, (setup, spawn_player).chain())
+        .insert_resource(PreloadManifest::new([
+            "shaders/animate_shader.wgsl",
+            "shaders/constellation_shader.wgsl",
+            "shaders/energy_field_shader.wgsl",
+            "shaders/fractal_shader.wgsl",
+            "shaders/geometric_shader.wgsl",
+            "shaders/glass_shader.wgsl",
+            "shaders/holographic_shader.wgsl",
+            "shaders/liquid_metal_shader.wgsl",
+            "shaders/morphing_sculpture_shader.wgsl",
+            "shaders/portal_shader.wgsl",
+        ]))
+        .insert_resource(AttractMode::new([
+            AttractWaypoint::new(Vec3::new(0.0, 3.0, 12.0), Vec3::new(0.0, 2.0, 0.0)),
+            AttractWaypoint::new(Vec3::new(10.0, 3.5, 0.0), Vec3::new(-12.0, 2.5, 0.0)),
+            AttractWaypoint::new(Vec3::new(0.0, 3.0, -14.0), Vec3::new(0.0, 2.5, -45.0)),
+            AttractWaypoint::new(Vec3::new(0.0, 3.0, -38.0), Vec3::new(0.0, 2.0, -50.0)),
+            AttractWaypoint::new(Vec3::new(26.0, 3.5, -45.0), Vec3::new(32.5, 2.5, -45.0)),
+            AttractWaypoint::new(Vec3::new(-10.0, 3.5, 0.0), Vec3::new(12.0, 2.5, 0.0)),
+        ]))
+        .add_observer(artworks::log_presented_line)
+        .add_observer(artworks::log_presented_options)
+        .add_observer(artworks::reset_for_next_visitor)
+        .add_observer(artworks::on_artwork_hung)
+        .add_observer(artworks::regenerate_detail_texture)
+        .add_observer(artworks::restore_cached_texture)
+        .add_observer(dim_ambient_light)
+        .add_observer(caption_light_switch)
+        .init_resource::<AudioGuide>()
+        // Visitors' notes and guestbook messages are kept for the next visitor, without where
+        // they stood
+        .init_resource::<AnnotationSettings>()
+        .insert_resource(
+            SaveGame::new("museum.save.ron")
+                .without_player()
+                .with_load_on_startup()
+                .with_resource::<Annotations>()
+                .with_resource::<GuestbookEntries>(),
+        )
+        // The main room's floor, for the docent to find their way around the exhibits
+        .insert_resource(
+            NavGrid::new(
+                Vec3::new(0.0, CEILING_HEIGHT / 4.0, 0.0),
+                Vec3::new(15.0, CEILING_HEIGHT / 4.0, 15.0),
+                0.5,
+            )
+            .with_agent(DOCENT_RADIUS, DOCENT_HEIGHT),
+        )
+        .init_resource::<artworks::ArtworksViewed>()
+        .register_diagnostic(Diagnostic::new(ARTWORKS_VIEWED))
+        .add_systems(
+            Startup,
+            (
+                (setup, spawn_player).chain(),
+                spawn_sounds,
+                spawn_visitor_plaque,
+                spawn_guestbook,
+                spawn_light_switch,
+                spawn_docent,
+                guide::spawn_exhibits,
+            ),
+        )
         .add_systems(
             Update,
             (
-                rotate_artworks,
+                rotate_artworks.run_if(in_gameplay()),
                 animate_lighting,
                 animate_pulsing_sculptures,
                 animate_color_cycling_sculptures,
                 animate_morphing_sculptures,
                 artworks::cleanup_finished_dialogue_runners,
-                update_fractal_materials, // Update fractal materials every frame
+                measure_artworks_viewed,
+                artworks::swap_in_detail_textures,
             ),
         );
     }
@@ -86,6 +194,8 @@ impl Plugin for MuseumPlugin {
 const ROOM_BACKGROUND: Color = Color::srgb(0.95, 0.95, 0.9); // Soft warm white
 const CEILING_HEIGHT: f32 = 6.0; // Scaled from 4.0 to 6.0 (1.5x)
 const WALL_THICKNESS: f32 = 0.3; // Scaled from 0.2 to 0.3 (1.5x)
+const MAIN_ROOM_LIGHTS: &str = "Main Room";
+const AMBIENT_BRIGHTNESS: f32 = 1000.0;
 
 #[derive(Component)]
 struct Rotating;
@@ -108,6 +218,7 @@ fn setup(
     mut constellation_materials: ResMut<Assets<ConstellationMaterial>>,
     mut morphing_materials: ResMut<Assets<MorphingSculptureMaterial>>,
     mut images: ResMut<Assets<Image>>,
+    mut sculptures: ResMut<SculptureMeshes>,
     museum_assets: Res<MuseumAssets>,
 ) {
     commands.insert_resource(ClearColor(ROOM_BACKGROUND));
@@ -144,24 +255,250 @@ fn setup(
         &mut images,
         &museum_assets,
         &museum_materials,
+        &mut sculptures,
     );
 
-    // Setup room lighting
-    setup_room_lighting(&mut commands);
+    // Setup room lighting, with the second room's on its own circuit
+    let wing_power = create_power_grid(&mut commands, &mut meshes, &mut materials);
+    setup_room_lighting(&mut commands, wing_power);
+}
+
+/// Quiet room tone throughout, and a rumble from the lava channel.
+fn spawn_sounds(mut commands: Commands, mut sources: ResMut<Assets<AudioSource>>) {
+    let room_tone = LoopRecipe::new(10.0)
+        .with_brightness(0.04)
+        .with_swell(0.1)
+        .with_hum(60.0, 0.1);
+    commands.spawn((
+        Name::new("Room Tone"),
+        AmbientLoop::new(sources.add(room_tone.synthesise(1))).with_volume(0.15),
+    ));
+
+    let lava = LoopRecipe::new(6.0).with_brightness(0.015).with_swell(0.6);
+    commands.spawn((
+        Name::new("Lava Rumble"),
+        Transform::from_xyz(12.0, 0.5, 0.0),
+        SpatialAudioEmitter::new(sources.add(lava.synthesise(2)))
+            .with_range(3.0, 18.0)
+            .with_caption("lava rumbling"),
+    ));
 }
 
 /// Spawns the player at the initial position
+const ARTWORKS_VIEWED: DiagnosticPath = DiagnosticPath::const_new("scene/artworks_viewed");
+
+fn measure_artworks_viewed(mut diagnostics: Diagnostics, viewed: Res<artworks::ArtworksViewed>) {
+    diagnostics.add_measurement(&ARTWORKS_VIEWED, || viewed.0.len() as f64);
+}
+
+/// A plaque by the entrance showing how the current visit's going.
+fn spawn_visitor_plaque(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Visitor Statistics"),
+        StatsPlaque::new("Your Visit")
+            .with_time_in_scene("Time in the gallery")
+            .with_diagnostic("Artworks viewed", ARTWORKS_VIEWED),
+        // On the south wall left of the entrance, facing into the room
+        Transform::from_xyz(-10.5, 2.5, 14.98 - WALL_THICKNESS)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::PI)),
+    ));
+}
+
+/// A guestbook by the entrance for visitors to sign on their way out.
+fn spawn_guestbook(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Visitors' Book"),
+        Guestbook::new("Visitors' Book"),
+        // Right of the entrance, facing into the room
+        Transform::from_xyz(6.5, 0.0, 12.5)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::PI)),
+    ));
+}
+
+const DOCENT_RADIUS: f32 = 0.3;
+const DOCENT_HEIGHT: f32 = 1.8;
+
+/// Where the docent walks between in the main room, by the corner pedestals and across the
+/// central island from one another.
+const DOCENT_STOPS: [Vec3; 4] = [
+    Vec3::new(8.5, 0.0, 8.5),
+    Vec3::new(-8.5, 0.0, -8.5),
+    Vec3::new(-8.5, 0.0, 8.5),
+    Vec3::new(8.5, 0.0, -8.5),
+];
+
+/// Stop the docent is heading for.
+#[derive(Component)]
+struct Docent(usize);
+
+/// A docent doing the rounds of the main room, stopping to face visitors who come up to them.
+fn spawn_docent(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut agent = NavAgent::new(1.2).with_ground_offset(DOCENT_HEIGHT / 2.0);
+    agent.set_destination(DOCENT_STOPS[0]);
+    commands
+        .spawn((
+            Name::new("Docent"),
+            Docent(0),
+            agent,
+            LookAtPlayer::new(2.5),
+            Mesh3d(meshes.add(Capsule3d::new(
+                DOCENT_RADIUS,
+                DOCENT_HEIGHT - DOCENT_RADIUS * 2.0,
+            ))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgb(0.2, 0.22, 0.35),
+                perceptual_roughness: 0.7,
+                ..default()
+            })),
+            Transform::from_xyz(0.0, DOCENT_HEIGHT / 2.0, 12.0),
+        ))
+        .observe(walk_to_next_stop::<DestinationReached>)
+        .observe(walk_to_next_stop::<DestinationUnreachable>);
+}
+
+/// Sends the docent on to their next stop once they've got to one, or found they can't.
+fn walk_to_next_stop<E: EntityEvent>(
+    event: On<E>,
+    mut docents: Query<(&mut Docent, &mut NavAgent)>,
+) {
+    let Ok((mut docent, mut agent)) = docents.get_mut(event.event_target()) else {
+        return;
+    };
+    docent.0 = (docent.0 + 1) % DOCENT_STOPS.len();
+    agent.set_destination(DOCENT_STOPS[docent.0]);
+}
+
+/// Switch for the main room's lights by the entrance, so visitors can see the exhibits glow in
+/// the dark.
+fn spawn_light_switch(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let plate = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.92, 0.9, 0.85),
+        perceptual_roughness: 0.4,
+        ..default()
+    });
+    let rocker = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.98, 0.97, 0.94),
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    LightSwitch::new(MAIN_ROOM_LIGHTS)
+        .with_light("Main Directional Light")
+        .spawn(
+            &mut commands,
+            &mut meshes,
+            plate,
+            rocker,
+            // On the south wall right of the entrance, facing into the room
+            Transform::from_xyz(3.0, 1.3, 14.98 - WALL_THICKNESS)
+                .with_rotation(Quat::from_rotation_y(std::f32::consts::PI)),
+        );
+}
+
+/// A generator in the corridor feeding the second room's lights through a breaker, which visitors
+/// can throw to black out the wing and throw back to restore power. Returns the breaker.
+fn create_power_grid(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) -> Entity {
+    let casing = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.35, 0.38, 0.35),
+        metallic: 0.6,
+        perceptual_roughness: 0.5,
+        ..default()
+    });
+    let cable = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.05, 0.05, 0.05),
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+
+    // Against the corridor's west wall, short of the second room
+    let generator = commands
+        .spawn((
+            Name::new("Wing Generator"),
+            Mesh3d(meshes.add(Cuboid::new(0.8, 1.0, 1.2))),
+            MeshMaterial3d(casing.clone()),
+            Transform::from_xyz(-5.2, 0.5, -31.0),
+            RigidBody::Static,
+            Collider::cuboid(0.8, 1.0, 1.2),
+            Generator::new(true),
+            Interactable::press(),
+            Hint::new("Press to switch the generator on or off"),
+        ))
+        .id();
+    let breaker = commands
+        .spawn((
+            Name::new("Wing Breaker"),
+            Mesh3d(meshes.add(Cuboid::new(0.1, 0.5, 0.35))),
+            MeshMaterial3d(casing),
+            Transform::from_xyz(-5.65, 1.4, -28.5),
+            RigidBody::Static,
+            Collider::cuboid(0.1, 0.5, 0.35),
+            Breaker::new(true),
+            PoweredBy(generator),
+            Interactable::press(),
+            Hint::new("Press to throw the breaker for the second room"),
+        ))
+        .id();
+
+    let wires = [
+        Wire::new(Vec3::new(-5.5, 0.9, -30.6), Vec3::new(-5.62, 1.2, -28.5)).with_sag(0.3),
+        Wire::new(
+            Vec3::new(-5.62, 1.6, -28.5),
+            Vec3::new(-5.62, CEILING_HEIGHT - 0.1, -34.5),
+        ),
+    ];
+    for wire in wires {
+        commands.spawn((
+            Name::new("Wing Cable"),
+            Mesh3d(meshes.add(wire.mesh())),
+            MeshMaterial3d(cable.clone()),
+        ));
+    }
+    breaker
+}
+
+/// Says what happened for players who can't hear the switch.
+fn caption_light_switch(toggled: On<LightsToggled>, mut captions: ResMut<Captions>) {
+    captions.describe(if toggled.on {
+        "click, the lights come on"
+    } else {
+        "click, the lights go out"
+    });
+}
+
+/// Lets the room fall almost dark with its lights off.
+fn dim_ambient_light(toggled: On<LightsToggled>, mut ambient: ResMut<GlobalAmbientLight>) {
+    if toggled.group != MAIN_ROOM_LIGHTS {
+        return;
+    }
+    ambient.brightness = if toggled.on {
+        AMBIENT_BRIGHTNESS
+    } else {
+        AMBIENT_BRIGHTNESS * 0.05
+    };
+}
+
 pub fn spawn_player(mut player: Single<&mut Transform, With<Player>>) {
     let spawn_point = Transform::from_xyz(0.0, 2., 14.).looking_at([0., 4., 2.].into(), Vec3::Y);
     player.translation = spawn_point.translation;
     player.rotation = spawn_point.rotation;
 }
 
-fn setup_room_lighting(commands: &mut Commands) {
+fn setup_room_lighting(commands: &mut Commands, wing_power: Entity) {
     // Main ambient lighting - bright warm museum lighting for excellent visibility
     commands.insert_resource(GlobalAmbientLight {
         color: Color::srgb(0.95, 0.95, 0.9),
-        brightness: 1000.0, // Increased from 800.0 for even brighter ambient lighting
+        brightness: AMBIENT_BRIGHTNESS,
         ..default()
     });
 
@@ -200,6 +537,7 @@ fn setup_room_lighting(commands: &mut Commands) {
             },
             Transform::from_translation(*position),
             AnimatedLight,
+            LightGroup::new(MAIN_ROOM_LIGHTS),
         ));
     }
 
@@ -227,6 +565,7 @@ fn setup_room_lighting(commands: &mut Commands) {
                 ..default()
             },
             Transform::from_translation(*position),
+            LightGroup::new(MAIN_ROOM_LIGHTS),
         ));
     }
 
@@ -290,6 +629,7 @@ fn setup_room_lighting(commands: &mut Commands) {
                 ..default()
             },
             Transform::from_translation(*position),
+            PoweredBy(wing_power),
         ));
     }
 
@@ -383,7 +723,7 @@ fn setup_room_lighting(commands: &mut Commands) {
 /// Speed: 0.3 rad/s for gentle, mesmerizing rotation
 fn rotate_artworks(
     mut query: Query<&mut Transform, (With<Rotating>, Without<AnimatedLight>)>,
-    time: Res<Time>,
+    time: Res<GameTime>,
 ) {
     for mut transform in &mut query {
         transform.rotate_y(time.delta_secs() * 0.3);
@@ -392,7 +732,7 @@ fn rotate_artworks(
 
 /// Pulses animated lights with a sine wave pattern
 /// Creates a gentle breathing effect at 2Hz frequency
-fn animate_lighting(mut lights: Query<&mut PointLight, With<AnimatedLight>>, time: Res<Time>) {
+fn animate_lighting(mut lights: Query<&mut PointLight, With<AnimatedLight>>, time: Res<GameTime>) {
     // ~15% done - Core systems working
     let pulse = (time.elapsed_secs() * 2.0).sin().abs() * 0.15 + 0.85; // Gentler pulsing (0.85-1.0)
     for mut light in &mut lights {
@@ -404,7 +744,7 @@ fn animate_lighting(mut lights: Query<&mut PointLight, With<AnimatedLight>>, tim
 /// Each sculpture can have different speed, scale range, and phase
 fn animate_pulsing_sculptures(
     mut query: Query<(&mut Transform, &artworks::PulsingSculpture)>,
-    time: Res<Time>,
+    time: Res<GameTime>,
 ) {
     for (mut transform, pulsing) in &mut query {
         let scale_factor = (time.elapsed_secs() * pulsing.speed + pulsing.phase)
@@ -424,7 +764,7 @@ fn animate_color_cycling_sculptures(
         &artworks::ColorCyclingSculpture,
     )>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    time: Res<Time>,
+    time: Res<GameTime>,
 ) {
     for (material_component, color_cycling) in &mut query {
         if let Some(material) = materials.get_mut(&material_component.0) {
@@ -437,22 +777,11 @@ fn animate_color_cycling_sculptures(
     }
 }
 
-/// Updates fractal materials with current time for animation
-/// Allows fractals to slowly evolve and zoom over time
-fn update_fractal_materials(
-    time: Res<Time>,
-    mut fractal_materials: ResMut<Assets<FractalMaterial>>,
-) {
-    for (_, material) in fractal_materials.iter_mut() {
-        material.data.time = time.elapsed_secs();
-    }
-}
-
 /// Animates morphing sculptures with dynamic scale changes
 /// Creates organic, flowing transformations of the sculpture forms
 fn animate_morphing_sculptures(
     mut query: Query<(&mut Transform, &artworks::MorphingSculpture)>,
-    time: Res<Time>,
+    time: Res<GameTime>,
 ) {
     for (mut transform, morphing) in &mut query {
         // Create complex scale animation with multiple sine waves
@@ -470,5 +799,15 @@ fn animate_morphing_sculptures(
 }
 
 fn main() -> AppExit {
-    App::new().add_plugins((DioramaPlugin, MuseumPlugin)).run()
+    // Kinematic, as the dynamic body jitters on the museum's thin floor colliders
+    let diorama = DioramaPlugin {
+        player: PlayerPlugin::kinematic(),
+        // Hints float over the exhibits, for visitors to see what they can do at a glance, and
+        // paintings are clicked on from a crosshair
+        picking: PickingPlugin::default()
+            .with_world_labels(HintLabels::default())
+            .with_crosshair(CrosshairPicking::default()),
+        ..default()
+    };
+    App::new().add_plugins((diorama, MuseumPlugin)).run()
 }
@@ -34,30 +34,30 @@
 
 use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
-use bevy::reflect::TypePath;
 use bevy::render::render_resource::{
     AsBindGroup, Extent3d, ShaderType, TextureDimension, TextureFormat,
 };
 use bevy::shader::ShaderRef;
+use diorama::game_time::GameTimeMaterial;
 use examples_common::noise::{Fbm, Perlin};
 
 use crate::shader_materials::{FractalMaterial, create_fractal_material};
 
 /// Translucent glass material with fresnel effects for display cases
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct GlassMaterial {
     #[uniform(0)]
     pub data: GlassData,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 #[allow(dead_code)] // All fields used by GPU shader, not detectable by static analysis
 pub struct GlassData {
-    pub base_color: Vec4,
+    pub base_color: LinearRgba,
     pub transparency: f32,
     pub refraction_strength: f32,
     pub fresnel_power: f32,
-    pub _padding: f32,
+    pub time: f32,
 }
 
 impl Material for GlassMaterial {
@@ -70,8 +70,14 @@ impl Material for GlassMaterial {
     }
 }
 
+impl GameTimeMaterial for GlassMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 /// Animated geometric material with pulsing energy fields
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct GeometricMaterial {
     #[uniform(0)]
     pub data: GeometricData,
@@ -80,11 +86,11 @@ pub struct GeometricMaterial {
     pub noise_texture: Option<Handle<Image>>,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 #[allow(dead_code)] // All fields used by GPU shader, not detectable by static analysis
 pub struct GeometricData {
-    pub primary_color: Vec4,
-    pub secondary_color: Vec4,
+    pub primary_color: LinearRgba,
+    pub secondary_color: LinearRgba,
     pub glow_intensity: f32,
     pub animation_speed: f32,
     pub metallic: f32,
@@ -103,6 +109,12 @@ impl Material for GeometricMaterial {
     }
 }
 
+impl GameTimeMaterial for GeometricMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 /// Collection of materials used throughout the museum
 pub struct MuseumMaterials {
     pub floor: Handle<StandardMaterial>,
@@ -116,6 +128,7 @@ pub struct MuseumMaterials {
     pub polished_stone: Handle<StandardMaterial>,
     pub glowing_sculpture: Handle<GeometricMaterial>, // Custom shader for geometric sculpture
     pub fractal_painting: Handle<FractalMaterial>,    // Fractal shader for paintings
+    pub underdrawing: Handle<StandardMaterial>,       // Sketch lines beneath the paint
 }
 
 pub fn create_museum_materials(
@@ -142,6 +155,7 @@ pub fn create_museum_materials(
             -0.5,                       // offset_x
             0.0,                        // offset_y
         ),
+        underdrawing: create_underdrawing_material(materials),
     }
 }
 
@@ -227,6 +241,17 @@ fn create_gold_frame_material(
     })
 }
 
+/// Charcoal sketching, only seen under inspection
+fn create_underdrawing_material(
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) -> Handle<StandardMaterial> {
+    materials.add(StandardMaterial {
+        base_color: Color::srgb(0.85, 0.75, 0.6),
+        unlit: true,
+        ..default()
+    })
+}
+
 fn create_marble_pedestal_material(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     images: &mut ResMut<Assets<Image>>,
@@ -248,11 +273,11 @@ fn create_glass_display_shader_material(
 ) -> Handle<GlassMaterial> {
     glass_materials.add(GlassMaterial {
         data: GlassData {
-            base_color: Vec4::new(0.92, 0.95, 1.0, 0.25), // Subtle blue tint with low base alpha
-            transparency: 0.25,                           // Base transparency level
-            refraction_strength: 1.0,                     // How much the glass refracts light
-            fresnel_power: 2.0,                           // Controls how the fresnel effect appears
-            _padding: 0.0,
+            base_color: LinearRgba::new(0.92, 0.95, 1.0, 0.25), // Subtle blue tint with low base alpha
+            transparency: 0.25,                                 // Base transparency level
+            refraction_strength: 1.0,                           // How much the glass refracts light
+            fresnel_power: 2.0, // Controls how the fresnel effect appears
+            time: 0.0,
         },
     })
 }
@@ -289,13 +314,13 @@ fn create_geometric_shader_material(
 
     geometric_materials.add(GeometricMaterial {
         data: GeometricData {
-            primary_color: Vec4::new(0.2, 0.4, 0.8, 1.0), // Blue primary
-            secondary_color: Vec4::new(0.8, 0.9, 1.0, 1.0), // Light blue secondary
-            glow_intensity: 2.5,                          // Moderate glow
-            animation_speed: 0.8,                         // Smooth animation
-            metallic: 0.9,                                // High metallic
-            roughness: 0.1,                               // Very smooth
-            time: 0.0,                                    // Will be updated in shader
+            primary_color: LinearRgba::new(0.2, 0.4, 0.8, 1.0), // Blue primary
+            secondary_color: LinearRgba::new(0.8, 0.9, 1.0, 1.0), // Light blue secondary
+            glow_intensity: 2.5,                                // Moderate glow
+            animation_speed: 0.8,                               // Smooth animation
+            metallic: 0.9,                                      // High metallic
+            roughness: 0.1,                                     // Very smooth
+            time: 0.0,                                          // Set from GameTime
             _padding: 0.0,
         },
         noise_texture: Some(noise_texture),
@@ -10,14 +10,11 @@
 //! ## Entity Hierarchy
 //! ```text
 //! Museum Root
+//! ├── Museum Architecture (floors, walls, ceilings of every room and corridor)
 //! ├── Main Room
-//! │   ├── Room Structure (floor, walls, ceiling)
 //! │   ├── Entrance
 //! │   └── Display Areas
-//! ├── Corridor
-//! │   └── Corridor Structure
 //! └── Second Room
-//!     ├── Room Structure
 //!     ├── Display Cases (4 with pedestals)
 //!     ├── Central Pedestal
 //!     └── Shader Artwork Panels
@@ -36,7 +33,17 @@
 //! - Pedestals positioned for optimal sculpture viewing
 
 use avian3d::prelude::*;
+use bevy::light::NotShadowCaster;
 use bevy::prelude::*;
+use diorama::ambience::{AmbienceZone, ReverbPreset};
+use diorama::ambient_volume::AmbientLightVolume;
+use diorama::architecture::{Architecture, ArchitectureMaterials, Opening, Room, WallSide};
+use diorama::footsteps::SurfaceKind;
+use diorama::metaballs::{MetaballMotion, Metaballs};
+use diorama::motes::MotesFile;
+use diorama::picking::PickThrough;
+use diorama::portal::Portal;
+use diorama::shadow_proxy::ShadowProxy;
 
 use crate::helpers::{create_group, spawn_static_cuboid, spawn_static_cylinder};
 use crate::materials::MuseumMaterials;
@@ -67,12 +74,12 @@ pub fn build_room(
         ))
         .id();
 
+    // Floors, walls and ceilings of every room and corridor
+    create_architecture(commands, materials, museum_root);
+
     // Create main room
     create_main_room(commands, meshes, materials, museum_root);
 
-    // Create corridor connecting to second room
-    create_corridor(commands, meshes, materials, museum_root);
-
     // Create second room
     create_second_room(
         commands,
@@ -88,160 +95,166 @@ pub fn build_room(
         constellation_materials,
     );
 
-    // Create corridor to third room (branches from second room)
-    create_third_room_corridor(commands, meshes, materials, museum_root);
-
     // Create third room with morphing sculpture
-    create_third_room(commands, meshes, materials, museum_root, morphing_materials);
-}
-
-fn create_main_room(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-) {
-    // Create main room root entity
-    let room_root = commands
-        .spawn((
-            Name::new("Main Room"),
-            Transform::default(),
-            Visibility::default(),
-        ))
-        .id();
-    commands.entity(parent).add_child(room_root);
-
-    // Create organized sub-structures as children
-    create_room_structure(commands, meshes, materials, room_root);
-    create_entrance(commands, meshes, materials, room_root);
-    create_display_areas(commands, meshes, materials, room_root);
-}
-
-fn create_room_structure(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-) {
-    // Create a sub-group for the room structure
-    let structure_root = commands
-        .spawn((
-            Name::new("Room Structure"),
-            Transform::default(),
-            Visibility::default(),
-        ))
-        .id();
-    commands.entity(parent).add_child(structure_root);
-
-    // Create floor, walls, and ceiling as children of structure
-    create_floor(commands, meshes, materials, structure_root);
-    create_walls(commands, meshes, materials, structure_root);
-    create_ceiling(commands, meshes, materials, structure_root);
-}
-
-fn create_floor(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-) {
-    spawn_static_cuboid(
+    create_third_room(
         commands,
         meshes,
-        "Room Floor",
-        Vec3::new(30.0, 0.15, 30.0), // Scaled from 20x20 to 30x30
-        materials.floor.clone(),
-        Transform::from_xyz(0.0, 0.0, 0.0),
-        Some(parent),
+        materials,
+        museum_root,
+        morphing_materials,
+        liquid_materials,
     );
+
+    // Shortcut between the main room and the third room
+    create_portals(commands, meshes, portal_materials, museum_root);
 }
 
-fn create_walls(
+/// A linked pair of portals, one in front of the entrance and one just inside the third room,
+/// facing into it.
+fn create_portals(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
+    portal_materials: &mut ResMut<Assets<PortalMaterial>>,
     parent: Entity,
 ) {
-    // Create walls group
-    let walls_root = create_group(commands, "Walls", Some(parent));
-
-    // North wall (back) - with corridor opening
-    create_north_wall_sections(commands, meshes, materials, walls_root);
-
-    // East wall (right) - solid wall
-    let east_wall_x = 15.0 - WALL_THICKNESS / 2.0; // Scaled from 10.0 to 15.0
-    spawn_static_cuboid(
+    let half_extents = Vec3::new(0.9, 1.3, 0.3);
+    let mesh = meshes.add(Ellipse::new(half_extents.x, half_extents.y));
+    let material = crate::shader_materials::create_portal_material(
+        portal_materials,
+        Color::srgb(0.9, 1.0, 1.0),
+        Color::srgb(0.0, 0.5, 0.8),
+    );
+    let placements = [
+        (
+            "Main Room Portal",
+            Transform::from_xyz(-6.0, half_extents.y, 11.5),
+        ),
+        (
+            "Third Room Portal",
+            Transform::from_xyz(32.5, half_extents.y, -40.5)
+                .with_rotation(Quat::from_rotation_y(std::f32::consts::PI)),
+        ),
+    ];
+    let [main, third] = placements.map(|(name, transform)| {
+        let portal = commands
+            .spawn((
+                Name::new(name),
+                transform,
+                Visibility::default(),
+                children![
+                    (
+                        Mesh3d(mesh.clone()),
+                        MeshMaterial3d(material.clone()),
+                        NotShadowCaster,
+                    ),
+                    // The back, as the disc is one-sided
+                    (
+                        Mesh3d(mesh.clone()),
+                        MeshMaterial3d(material.clone()),
+                        Transform::from_rotation(Quat::from_rotation_y(std::f32::consts::PI)),
+                        NotShadowCaster,
+                    ),
+                ],
+            ))
+            .id();
+        commands.entity(parent).add_child(portal);
+        portal
+    });
+    Portal::pair(
         commands,
-        meshes,
-        "East Wall",
-        Vec3::new(WALL_THICKNESS, CEILING_HEIGHT, 30.0), // Scaled from 20.0 to 30.0
-        materials.wall.clone(),
-        Transform::from_xyz(east_wall_x, CEILING_HEIGHT / 2.0, 0.0),
-        Some(walls_root),
+        main,
+        third,
+        Portal::new(Entity::PLACEHOLDER, half_extents),
     );
+}
 
-    // West wall (left) - solid wall
-    let west_wall_x = -15.0 + WALL_THICKNESS / 2.0; // Scaled from -10.0 to -15.0
-    spawn_static_cuboid(
+/// The museum's rooms and the corridors between them, each room's walls cut for the doorways
+/// joining it to the next.
+fn create_architecture(commands: &mut Commands, materials: &MuseumMaterials, parent: Entity) {
+    let room = |name: &str, x: f32, z: f32, size: Vec2| {
+        Room::new(name, Vec3::new(x, 0.0, z), size, CEILING_HEIGHT)
+            .with_floor_surface(SurfaceKind::Marble)
+    };
+    let architecture = Architecture::new("Museum Architecture")
+        .with_wall_thickness(WALL_THICKNESS)
+        .with_room(
+            room("Main Room", 0.0, 0.0, Vec2::splat(30.0))
+                .with_opening(WallSide::South, Opening::new(12.0)),
+        )
+        // Extending north from the main room
+        .with_room(room("Corridor", 0.0, -25.0, Vec2::new(12.0, 20.0)))
+        .with_room(room("Second Room", 0.0, -45.0, Vec2::splat(20.0)))
+        // Branching east from the second room
+        .with_room(room(
+            "Third Room Corridor",
+            17.5,
+            -45.0,
+            Vec2::new(15.0, 8.0),
+        ))
+        .with_room(room("Third Room", 32.5, -45.0, Vec2::splat(15.0)))
+        .with_doorway("Main Room", "Corridor", Opening::new(12.0))
+        .with_doorway("Corridor", "Second Room", Opening::new(12.0))
+        .with_doorway("Second Room", "Third Room Corridor", Opening::new(8.0))
+        .with_doorway("Third Room Corridor", "Third Room", Opening::new(8.0));
+
+    let root = architecture.spawn(
         commands,
-        meshes,
-        "West Wall",
-        Vec3::new(WALL_THICKNESS, CEILING_HEIGHT, 30.0), // Scaled from 20.0 to 30.0
-        materials.wall.clone(),
-        Transform::from_xyz(west_wall_x, CEILING_HEIGHT / 2.0, 0.0),
-        Some(walls_root),
+        &ArchitectureMaterials {
+            floor: materials.floor.clone(),
+            wall: materials.wall.clone(),
+            ceiling: materials.ceiling.clone(),
+        },
     );
-
-    // South wall sections (with entrance gap) - create as a group
-    create_south_wall_sections(commands, meshes, materials, walls_root);
+    commands.entity(parent).add_child(root);
 }
 
-fn create_south_wall_sections(
+fn create_main_room(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &MuseumMaterials,
     parent: Entity,
 ) {
-    let south_wall_root = create_group(commands, "South Wall", Some(parent));
+    // Create main room root entity
+    let room_root = commands
+        .spawn((
+            Name::new("Main Room"),
+            Transform::default(),
+            Visibility::default(),
+        ))
+        .id();
+    commands.entity(parent).add_child(room_root);
 
-    // Left section
-    spawn_static_cuboid(
+    // Create organized sub-structures as children
+    create_entrance(commands, meshes, materials, room_root);
+    create_display_areas(commands, meshes, materials, room_root);
+    create_ambience_zone(
         commands,
-        meshes,
-        "South Wall Left",
-        Vec3::new(9.0, CEILING_HEIGHT, WALL_THICKNESS), // Scaled from 6.0 to 9.0
-        materials.wall.clone(),
-        Transform::from_xyz(-10.5, CEILING_HEIGHT / 2.0, 15.0 - WALL_THICKNESS / 2.0), // Scaled from -7.0 to -10.5, 10.0 to 15.0
-        Some(south_wall_root),
+        room_root,
+        "Main Room Ambience",
+        AmbienceZone::new(Vec3::new(15.0, CEILING_HEIGHT / 2.0, 15.0))
+            .with_reverb(ReverbPreset::Hall),
     );
 
-    // Right section
-    spawn_static_cuboid(
-        commands,
-        meshes,
-        "South Wall Right",
-        Vec3::new(9.0, CEILING_HEIGHT, WALL_THICKNESS), // Scaled from 6.0 to 9.0
-        materials.wall.clone(),
-        Transform::from_xyz(10.5, CEILING_HEIGHT / 2.0, 15.0 - WALL_THICKNESS / 2.0), // Scaled from 7.0 to 10.5, 10.0 to 15.0
-        Some(south_wall_root),
-    );
+    // Dust drifting through the gallery lights, tuned in assets/particles/dust.motes.ron
+    let dust = commands
+        .spawn((
+            Name::new("Main Room Dust"),
+            Transform::from_xyz(0.0, CEILING_HEIGHT / 2.0, 0.0),
+            MotesFile::new("particles/dust.motes.ron"),
+        ))
+        .id();
+    commands.entity(room_root).add_child(dust);
 }
 
-fn create_ceiling(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-) {
-    spawn_static_cuboid(
-        commands,
-        meshes,
-        "Room Ceiling",
-        Vec3::new(30.0, 0.15, 30.0), // Scaled from 20x20 to 30x30
-        materials.ceiling.clone(),
-        Transform::from_xyz(0.0, CEILING_HEIGHT, 0.0),
-        Some(parent),
-    );
+fn create_ambience_zone(commands: &mut Commands, parent: Entity, name: &str, zone: AmbienceZone) {
+    let zone_entity = commands
+        .spawn((
+            Name::new(name.to_string()),
+            Transform::from_xyz(0.0, CEILING_HEIGHT / 2.0, 0.0),
+            zone,
+        ))
+        .id();
+    commands.entity(parent).add_child(zone_entity);
 }
 
 fn create_entrance(
@@ -406,148 +419,6 @@ fn create_wall_mount_points(commands: &mut Commands, parent: Entity) {
     }
 }
 
-fn create_north_wall_sections(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-) {
-    let north_wall_root = create_group(commands, "North Wall", Some(parent));
-
-    // Left section (west side)
-    spawn_static_cuboid(
-        commands,
-        meshes,
-        "North Wall Left",
-        Vec3::new(9.0, CEILING_HEIGHT, WALL_THICKNESS),
-        materials.wall.clone(),
-        Transform::from_xyz(-10.5, CEILING_HEIGHT / 2.0, -15.0 + WALL_THICKNESS / 2.0),
-        Some(north_wall_root),
-    );
-
-    // Right section (east side)
-    spawn_static_cuboid(
-        commands,
-        meshes,
-        "North Wall Right",
-        Vec3::new(9.0, CEILING_HEIGHT, WALL_THICKNESS),
-        materials.wall.clone(),
-        Transform::from_xyz(10.5, CEILING_HEIGHT / 2.0, -15.0 + WALL_THICKNESS / 2.0),
-        Some(north_wall_root),
-    );
-}
-
-fn create_corridor(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-) {
-    // Create corridor root entity
-    let corridor_root = commands
-        .spawn((
-            Name::new("Corridor"),
-            Transform::default(),
-            Visibility::default(),
-        ))
-        .id();
-    commands.entity(parent).add_child(corridor_root);
-
-    // Corridor dimensions
-    let corridor_length = 20.0;
-    let corridor_width = 12.0;
-    let corridor_center_z = -15.0 - corridor_length / 2.0; // Extending north from main room
-
-    // Create corridor floor
-    let corridor_floor = commands
-        .spawn((
-            Name::new("Corridor Floor"),
-            Mesh3d(meshes.add(Cuboid::new(corridor_width, 0.15, corridor_length))),
-            MeshMaterial3d(materials.floor.clone()),
-            Transform::from_xyz(0.0, 0.0, corridor_center_z),
-            RigidBody::Static,
-            Collider::cuboid(corridor_width, 0.15, corridor_length), // Match mesh dimensions exactly
-        ))
-        .id();
-    commands.entity(corridor_root).add_child(corridor_floor);
-
-    // Create corridor ceiling
-    let corridor_ceiling = commands
-        .spawn((
-            Name::new("Corridor Ceiling"),
-            Mesh3d(meshes.add(Cuboid::new(corridor_width, 0.15, corridor_length))),
-            MeshMaterial3d(materials.ceiling.clone()),
-            Transform::from_xyz(0.0, CEILING_HEIGHT, corridor_center_z),
-            RigidBody::Static,
-            Collider::cuboid(corridor_width, 0.15, corridor_length), // Match mesh dimensions exactly
-        ))
-        .id();
-    commands.entity(corridor_root).add_child(corridor_ceiling);
-
-    // Create corridor walls
-    create_corridor_walls(
-        commands,
-        meshes,
-        materials,
-        corridor_root,
-        corridor_center_z,
-        corridor_length,
-        corridor_width,
-    );
-}
-
-fn create_corridor_walls(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-    corridor_center_z: f32,
-    corridor_length: f32,
-    corridor_width: f32,
-) {
-    // Left wall (west)
-    let left_wall = commands
-        .spawn((
-            Name::new("Corridor Left Wall"),
-            Mesh3d(meshes.add(Cuboid::new(WALL_THICKNESS, CEILING_HEIGHT, corridor_length))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                -corridor_width / 2.0 + WALL_THICKNESS / 2.0,
-                CEILING_HEIGHT / 2.0,
-                corridor_center_z,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(
-                WALL_THICKNESS,
-                CEILING_HEIGHT,
-                corridor_length, // Match mesh dimensions exactly
-            ),
-        ))
-        .id();
-    commands.entity(parent).add_child(left_wall);
-
-    // Right wall (east)
-    let right_wall = commands
-        .spawn((
-            Name::new("Corridor Right Wall"),
-            Mesh3d(meshes.add(Cuboid::new(WALL_THICKNESS, CEILING_HEIGHT, corridor_length))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                corridor_width / 2.0 - WALL_THICKNESS / 2.0,
-                CEILING_HEIGHT / 2.0,
-                corridor_center_z,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(
-                WALL_THICKNESS,
-                CEILING_HEIGHT,
-                corridor_length, // Match mesh dimensions exactly
-            ),
-        ))
-        .id();
-    commands.entity(parent).add_child(right_wall);
-}
-
 #[allow(clippy::too_many_arguments)] // Function needs many shader material asset collections
 fn create_second_room(
     commands: &mut Commands,
@@ -571,9 +442,13 @@ fn create_second_room(
         ))
         .id();
     commands.entity(parent).add_child(room_root);
-
-    // Create second room structure (similar to main room but smaller)
-    create_second_room_structure(commands, meshes, materials, room_root);
+    create_ambience_zone(
+        commands,
+        room_root,
+        "Second Room Ambience",
+        AmbienceZone::new(Vec3::new(10.0, CEILING_HEIGHT / 2.0, 10.0))
+            .with_reverb(ReverbPreset::Hall),
+    );
     create_second_room_display_areas(
         commands,
         meshes,
@@ -589,212 +464,6 @@ fn create_second_room(
     );
 }
 
-fn create_second_room_structure(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-) {
-    let structure_root = commands
-        .spawn((
-            Name::new("Second Room Structure"),
-            Transform::default(),
-            Visibility::default(),
-        ))
-        .id();
-    commands.entity(parent).add_child(structure_root);
-
-    // Room dimensions (smaller than main room)
-    let room_size = 20.0;
-
-    // Create floor
-    let floor = commands
-        .spawn((
-            Name::new("Second Room Floor"),
-            Mesh3d(meshes.add(Cuboid::new(room_size, 0.15, room_size))),
-            MeshMaterial3d(materials.floor.clone()),
-            Transform::from_xyz(0.0, 0.0, 0.0),
-            RigidBody::Static,
-            Collider::cuboid(room_size, 0.15, room_size), // Match mesh dimensions exactly
-        ))
-        .id();
-    commands.entity(structure_root).add_child(floor);
-
-    // Create ceiling
-    let ceiling = commands
-        .spawn((
-            Name::new("Second Room Ceiling"),
-            Mesh3d(meshes.add(Cuboid::new(room_size, 0.15, room_size))),
-            MeshMaterial3d(materials.ceiling.clone()),
-            Transform::from_xyz(0.0, CEILING_HEIGHT, 0.0),
-            RigidBody::Static,
-            Collider::cuboid(room_size, 0.15, room_size), // Match mesh dimensions exactly
-        ))
-        .id();
-    commands.entity(structure_root).add_child(ceiling);
-
-    // Create walls
-    create_second_room_walls(commands, meshes, materials, structure_root, room_size);
-}
-
-fn create_second_room_walls(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-    room_size: f32,
-) {
-    let half_size = room_size / 2.0;
-
-    // North wall (solid)
-    let north_wall = commands
-        .spawn((
-            Name::new("Second Room North Wall"),
-            Mesh3d(meshes.add(Cuboid::new(room_size, CEILING_HEIGHT, WALL_THICKNESS))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(0.0, CEILING_HEIGHT / 2.0, -half_size + WALL_THICKNESS / 2.0),
-            RigidBody::Static,
-            Collider::cuboid(room_size, CEILING_HEIGHT, WALL_THICKNESS), // Match mesh dimensions exactly
-        ))
-        .id();
-    commands.entity(parent).add_child(north_wall);
-
-    // East wall (with corridor opening to third room) - create sections
-    create_second_room_east_wall_sections(commands, meshes, materials, parent, room_size);
-
-    // West wall (solid)
-    let west_wall = commands
-        .spawn((
-            Name::new("Second Room West Wall"),
-            Mesh3d(meshes.add(Cuboid::new(WALL_THICKNESS, CEILING_HEIGHT, room_size))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(-half_size + WALL_THICKNESS / 2.0, CEILING_HEIGHT / 2.0, 0.0),
-            RigidBody::Static,
-            Collider::cuboid(WALL_THICKNESS, CEILING_HEIGHT, room_size), // Match mesh dimensions exactly
-        ))
-        .id();
-    commands.entity(parent).add_child(west_wall);
-
-    // South wall (with corridor opening) - create sections
-    create_second_room_south_wall_sections(commands, meshes, materials, parent, room_size);
-}
-
-fn create_second_room_south_wall_sections(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-    room_size: f32,
-) {
-    let half_size = room_size / 2.0;
-    let corridor_opening_width = 12.0;
-    let wall_section_width = (room_size - corridor_opening_width) / 2.0;
-
-    // Left section
-    let left_section = commands
-        .spawn((
-            Name::new("Second Room South Wall Left"),
-            Mesh3d(meshes.add(Cuboid::new(
-                wall_section_width,
-                CEILING_HEIGHT,
-                WALL_THICKNESS,
-            ))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                -corridor_opening_width / 2.0 - wall_section_width / 2.0,
-                CEILING_HEIGHT / 2.0,
-                half_size - WALL_THICKNESS / 2.0,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(
-                wall_section_width,
-                CEILING_HEIGHT,
-                WALL_THICKNESS, // Match mesh dimensions exactly
-            ),
-        ))
-        .id();
-    commands.entity(parent).add_child(left_section);
-
-    // Right section
-    let right_section = commands
-        .spawn((
-            Name::new("Second Room South Wall Right"),
-            Mesh3d(meshes.add(Cuboid::new(
-                wall_section_width,
-                CEILING_HEIGHT,
-                WALL_THICKNESS,
-            ))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                corridor_opening_width / 2.0 + wall_section_width / 2.0,
-                CEILING_HEIGHT / 2.0,
-                half_size - WALL_THICKNESS / 2.0,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(
-                wall_section_width,
-                CEILING_HEIGHT,
-                WALL_THICKNESS, // Match mesh dimensions exactly
-            ),
-        ))
-        .id();
-    commands.entity(parent).add_child(right_section);
-}
-
-fn create_second_room_east_wall_sections(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-    room_size: f32,
-) {
-    let half_size = room_size / 2.0;
-    let corridor_opening_width = 8.0; // Match the third room corridor width
-    let wall_section_height = (room_size - corridor_opening_width) / 2.0;
-
-    // North section (above corridor opening)
-    let north_section = commands
-        .spawn((
-            Name::new("Second Room East Wall North"),
-            Mesh3d(meshes.add(Cuboid::new(
-                WALL_THICKNESS,
-                CEILING_HEIGHT,
-                wall_section_height,
-            ))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                half_size - WALL_THICKNESS / 2.0,
-                CEILING_HEIGHT / 2.0,
-                -corridor_opening_width / 2.0 - wall_section_height / 2.0,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(WALL_THICKNESS, CEILING_HEIGHT, wall_section_height),
-        ))
-        .id();
-    commands.entity(parent).add_child(north_section);
-
-    // South section (below corridor opening)
-    let south_section = commands
-        .spawn((
-            Name::new("Second Room East Wall South"),
-            Mesh3d(meshes.add(Cuboid::new(
-                WALL_THICKNESS,
-                CEILING_HEIGHT,
-                wall_section_height,
-            ))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                half_size - WALL_THICKNESS / 2.0,
-                CEILING_HEIGHT / 2.0,
-                corridor_opening_width / 2.0 + wall_section_height / 2.0,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(WALL_THICKNESS, CEILING_HEIGHT, wall_section_height),
-        ))
-        .id();
-    commands.entity(parent).add_child(south_section);
-}
-
 #[allow(clippy::too_many_arguments)] // Function needs many shader material asset collections
 fn create_second_room_display_areas(
     commands: &mut Commands,
@@ -1067,6 +736,8 @@ fn create_second_room_display_cases(
                 Transform::from_translation(case_position),
                 RigidBody::Static,
                 Collider::cuboid(1.4, 1.8, 1.4),
+                // Let clicks reach the sculptures inside
+                PickThrough,
             ))
             .id();
         commands.entity(display_cases_root).add_child(glass_case);
@@ -1077,94 +748,13 @@ fn create_second_room_display_cases(
 // Third Room (Morphing Sculpture Gallery)
 // ============================================================================
 
-fn create_third_room_corridor(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-) {
-    // Create corridor root entity - branches east from second room
-    let corridor_root = commands
-        .spawn((
-            Name::new("Third Room Corridor"),
-            Transform::from_xyz(0.0, 0.0, -45.0), // Same Z as second room center
-            Visibility::default(),
-        ))
-        .id();
-    commands.entity(parent).add_child(corridor_root);
-
-    // Corridor dimensions - going east
-    let corridor_length = 15.0;
-    let corridor_width = 8.0;
-    let corridor_center_x = 10.0 + corridor_length / 2.0; // Starting from second room east wall
-
-    // Create corridor floor
-    let corridor_floor = commands
-        .spawn((
-            Name::new("Third Room Corridor Floor"),
-            Mesh3d(meshes.add(Cuboid::new(corridor_length, 0.15, corridor_width))),
-            MeshMaterial3d(materials.floor.clone()),
-            Transform::from_xyz(corridor_center_x, 0.0, 0.0),
-            RigidBody::Static,
-            Collider::cuboid(corridor_length, 0.15, corridor_width),
-        ))
-        .id();
-    commands.entity(corridor_root).add_child(corridor_floor);
-
-    // Create corridor ceiling
-    let corridor_ceiling = commands
-        .spawn((
-            Name::new("Third Room Corridor Ceiling"),
-            Mesh3d(meshes.add(Cuboid::new(corridor_length, 0.15, corridor_width))),
-            MeshMaterial3d(materials.ceiling.clone()),
-            Transform::from_xyz(corridor_center_x, CEILING_HEIGHT, 0.0),
-            RigidBody::Static,
-            Collider::cuboid(corridor_length, 0.15, corridor_width),
-        ))
-        .id();
-    commands.entity(corridor_root).add_child(corridor_ceiling);
-
-    // North wall
-    let north_wall = commands
-        .spawn((
-            Name::new("Third Room Corridor North Wall"),
-            Mesh3d(meshes.add(Cuboid::new(corridor_length, CEILING_HEIGHT, WALL_THICKNESS))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                corridor_center_x,
-                CEILING_HEIGHT / 2.0,
-                -corridor_width / 2.0 + WALL_THICKNESS / 2.0,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(corridor_length, CEILING_HEIGHT, WALL_THICKNESS),
-        ))
-        .id();
-    commands.entity(corridor_root).add_child(north_wall);
-
-    // South wall
-    let south_wall = commands
-        .spawn((
-            Name::new("Third Room Corridor South Wall"),
-            Mesh3d(meshes.add(Cuboid::new(corridor_length, CEILING_HEIGHT, WALL_THICKNESS))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                corridor_center_x,
-                CEILING_HEIGHT / 2.0,
-                corridor_width / 2.0 - WALL_THICKNESS / 2.0,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(corridor_length, CEILING_HEIGHT, WALL_THICKNESS),
-        ))
-        .id();
-    commands.entity(corridor_root).add_child(south_wall);
-}
-
 fn create_third_room(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &MuseumMaterials,
     parent: Entity,
     morphing_materials: &mut ResMut<Assets<crate::shader_materials::MorphingSculptureMaterial>>,
+    liquid_materials: &mut ResMut<Assets<LiquidMetalMaterial>>,
 ) {
     // Create third room root entity - positioned east of second room
     let room_root = commands
@@ -1179,152 +769,42 @@ fn create_third_room(
     // Room dimensions (smaller intimate space)
     let room_size = 15.0;
 
-    // Create room structure
-    create_third_room_structure(commands, meshes, materials, room_root, room_size);
-
-    // Create the central morphing sculpture
-    create_morphing_sculpture_display(commands, meshes, materials, room_root, morphing_materials);
-}
-
-fn create_third_room_structure(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-    room_size: f32,
-) {
-    let structure_root = commands
-        .spawn((
-            Name::new("Third Room Structure"),
-            Transform::default(),
-            Visibility::default(),
-        ))
-        .id();
-    commands.entity(parent).add_child(structure_root);
-
-    let half_size = room_size / 2.0;
-
-    // Create floor
-    let floor = commands
-        .spawn((
-            Name::new("Third Room Floor"),
-            Mesh3d(meshes.add(Cuboid::new(room_size, 0.15, room_size))),
-            MeshMaterial3d(materials.floor.clone()),
-            Transform::from_xyz(0.0, 0.0, 0.0),
-            RigidBody::Static,
-            Collider::cuboid(room_size, 0.15, room_size),
-        ))
-        .id();
-    commands.entity(structure_root).add_child(floor);
-
-    // Create ceiling
-    let ceiling = commands
-        .spawn((
-            Name::new("Third Room Ceiling"),
-            Mesh3d(meshes.add(Cuboid::new(room_size, 0.15, room_size))),
-            MeshMaterial3d(materials.ceiling.clone()),
-            Transform::from_xyz(0.0, CEILING_HEIGHT, 0.0),
-            RigidBody::Static,
-            Collider::cuboid(room_size, 0.15, room_size),
-        ))
-        .id();
-    commands.entity(structure_root).add_child(ceiling);
-
-    // North wall (solid)
-    let north_wall = commands
-        .spawn((
-            Name::new("Third Room North Wall"),
-            Mesh3d(meshes.add(Cuboid::new(room_size, CEILING_HEIGHT, WALL_THICKNESS))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(0.0, CEILING_HEIGHT / 2.0, -half_size + WALL_THICKNESS / 2.0),
-            RigidBody::Static,
-            Collider::cuboid(room_size, CEILING_HEIGHT, WALL_THICKNESS),
-        ))
-        .id();
-    commands.entity(structure_root).add_child(north_wall);
-
-    // East wall (solid)
-    let east_wall = commands
-        .spawn((
-            Name::new("Third Room East Wall"),
-            Mesh3d(meshes.add(Cuboid::new(WALL_THICKNESS, CEILING_HEIGHT, room_size))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(half_size - WALL_THICKNESS / 2.0, CEILING_HEIGHT / 2.0, 0.0),
-            RigidBody::Static,
-            Collider::cuboid(WALL_THICKNESS, CEILING_HEIGHT, room_size),
-        ))
-        .id();
-    commands.entity(structure_root).add_child(east_wall);
-
-    // South wall (solid)
-    let south_wall = commands
-        .spawn((
-            Name::new("Third Room South Wall"),
-            Mesh3d(meshes.add(Cuboid::new(room_size, CEILING_HEIGHT, WALL_THICKNESS))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(0.0, CEILING_HEIGHT / 2.0, half_size - WALL_THICKNESS / 2.0),
-            RigidBody::Static,
-            Collider::cuboid(room_size, CEILING_HEIGHT, WALL_THICKNESS),
-        ))
-        .id();
-    commands.entity(structure_root).add_child(south_wall);
-
-    // West wall (with corridor opening) - create sections
-    create_third_room_west_wall_sections(commands, meshes, materials, structure_root, room_size);
-}
-
-fn create_third_room_west_wall_sections(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &MuseumMaterials,
-    parent: Entity,
-    room_size: f32,
-) {
-    let half_size = room_size / 2.0;
-    let corridor_opening_width = 8.0;
-    let wall_section_width = (room_size - corridor_opening_width) / 2.0;
+    create_ambience_zone(
+        commands,
+        room_root,
+        "Third Room Ambience",
+        AmbienceZone::new(Vec3::new(
+            room_size / 2.0,
+            CEILING_HEIGHT / 2.0,
+            room_size / 2.0,
+        ))
+        .with_priority(1)
+        .with_reverb(ReverbPreset::SmallRoom),
+    );
 
-    // North section
-    let north_section = commands
+    // Dimmer, cooler light gives the smaller gallery a more intimate feel
+    let ambient_volume = commands
         .spawn((
-            Name::new("Third Room West Wall North"),
-            Mesh3d(meshes.add(Cuboid::new(
-                WALL_THICKNESS,
-                CEILING_HEIGHT,
-                wall_section_width,
-            ))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                -half_size + WALL_THICKNESS / 2.0,
-                CEILING_HEIGHT / 2.0,
-                -corridor_opening_width / 2.0 - wall_section_width / 2.0,
+            Name::new("Third Room Ambient Light"),
+            Transform::from_xyz(0.0, CEILING_HEIGHT / 2.0, 0.0),
+            AmbientLightVolume::new(
+                Vec3::new(room_size / 2.0, CEILING_HEIGHT / 2.0, room_size / 2.0),
+                Color::srgb(0.85, 0.88, 1.0),
+                600.0,
             ),
-            RigidBody::Static,
-            Collider::cuboid(WALL_THICKNESS, CEILING_HEIGHT, wall_section_width),
         ))
         .id();
-    commands.entity(parent).add_child(north_section);
+    commands.entity(room_root).add_child(ambient_volume);
 
-    // South section
-    let south_section = commands
-        .spawn((
-            Name::new("Third Room West Wall South"),
-            Mesh3d(meshes.add(Cuboid::new(
-                WALL_THICKNESS,
-                CEILING_HEIGHT,
-                wall_section_width,
-            ))),
-            MeshMaterial3d(materials.wall.clone()),
-            Transform::from_xyz(
-                -half_size + WALL_THICKNESS / 2.0,
-                CEILING_HEIGHT / 2.0,
-                corridor_opening_width / 2.0 + wall_section_width / 2.0,
-            ),
-            RigidBody::Static,
-            Collider::cuboid(WALL_THICKNESS, CEILING_HEIGHT, wall_section_width),
-        ))
-        .id();
-    commands.entity(parent).add_child(south_section);
+    // Create the central morphing sculpture
+    create_morphing_sculpture_display(
+        commands,
+        meshes,
+        materials,
+        room_root,
+        morphing_materials,
+        liquid_materials,
+    );
 }
 
 fn create_morphing_sculpture_display(
@@ -1333,6 +813,7 @@ fn create_morphing_sculpture_display(
     materials: &MuseumMaterials,
     parent: Entity,
     morphing_materials: &mut ResMut<Assets<crate::shader_materials::MorphingSculptureMaterial>>,
+    liquid_materials: &mut ResMut<Assets<LiquidMetalMaterial>>,
 ) {
     let display_root = commands
         .spawn((
@@ -1356,6 +837,16 @@ fn create_morphing_sculpture_display(
         .id();
     commands.entity(display_root).add_child(pedestal);
 
+    // The cluster has dozens of high-detail parts, so a single sphere casts its shadow instead
+    let cluster_root = commands
+        .spawn((
+            Name::new("Morphing Sculpture Cluster"),
+            ShadowProxy::new(meshes.add(Sphere::new(2.4)))
+                .with_transform(Transform::from_xyz(0.0, 2.5, 0.0)),
+        ))
+        .id();
+    commands.entity(display_root).add_child(cluster_root);
+
     // Create the morphing sculpture with transcendent complexity
     // Material channels the essence of cosmic transformation
     let morphing_material = crate::shader_materials::create_morphing_sculpture_material(
@@ -1366,26 +857,23 @@ fn create_morphing_sculpture_display(
         6.0,                          // Maximum detail scale for ultimate complexity
     );
 
-    // Create ultra-high detail base mesh for maximum shader complexity
-    let core_mesh = meshes.add(Sphere::new(1.2).mesh().ico(6).unwrap()); // Ultra-high detail icosphere
-
-    // === CORE SCULPTURE: The Eternal Tesseract ===
-    // Primary form - the heart of reality
+    // === CORE SCULPTURE: The Liquid Heart ===
+    // Molten metal blobs endlessly merging and parting at the centre of the cluster
+    let liquid_metal = create_liquid_metal_material(liquid_materials, Color::srgb(0.7, 0.65, 0.9));
     let core_sculpture = commands
         .spawn((
-            Name::new("Core: Eternal Tesseract"),
-            Mesh3d(core_mesh.clone()),
-            MeshMaterial3d(morphing_material.clone()),
+            Name::new("Core: Liquid Heart"),
+            Metaballs::new(7)
+                .with_motion(MetaballMotion::Orbit)
+                .with_radius(0.45)
+                .with_half_size(Vec3::splat(1.4))
+                .with_cell_size(0.1)
+                .with_speed(0.6),
+            MeshMaterial3d(liquid_metal),
             Transform::from_xyz(0.0, 2.5, 0.0),
-            crate::artworks::MorphingSculpture {
-                speed: 0.6, // Slower, more profound
-                amplitude: 0.4,
-                base_mesh: core_mesh,
-            },
-            crate::Rotating,
         ))
         .id();
-    commands.entity(display_root).add_child(core_sculpture);
+    commands.entity(cluster_root).add_child(core_sculpture);
 
     // === INNER RING: Orbiting Platonic Solids ===
     // Five elements representing the building blocks of reality
@@ -1421,7 +909,7 @@ fn create_morphing_sculpture_display(
                 crate::Rotating,
             ))
             .id();
-        commands.entity(display_root).add_child(platonic);
+        commands.entity(cluster_root).add_child(platonic);
     }
 
     // === OUTER RING: Mandala Fragments ===
@@ -1450,7 +938,7 @@ fn create_morphing_sculpture_display(
                 crate::Rotating,
             ))
             .id();
-        commands.entity(display_root).add_child(fragment);
+        commands.entity(cluster_root).add_child(fragment);
     }
 
     // === VERTICAL AXIS: Above and Below ===
@@ -1476,7 +964,7 @@ fn create_morphing_sculpture_display(
                 crate::Rotating,
             ))
             .id();
-        commands.entity(display_root).add_child(vertex);
+        commands.entity(cluster_root).add_child(vertex);
     }
 
     // === RESONANCE NODES: Tetrahedral Formation ===
@@ -1515,6 +1003,6 @@ fn create_morphing_sculpture_display(
                 crate::Rotating,
             ))
             .id();
-        commands.entity(display_root).add_child(node);
+        commands.entity(cluster_root).add_child(node);
     }
 }
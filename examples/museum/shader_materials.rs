@@ -6,15 +6,17 @@
 
 #![allow(dead_code)] // Shader uniform fields are used by GPU, not detectable by static analysis
 
-use bevy::math::Vec4;
 use bevy::prelude::*;
-use bevy::reflect::TypePath;
 use bevy::render::render_resource::{AsBindGroup, ShaderType};
 use bevy::shader::ShaderRef;
+use diorama::game_time::GameTimeMaterial;
 
 /// Material that uses the animated color-shifting shader
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
-pub struct AnimatedMaterial {}
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
+pub struct AnimatedMaterial {
+    #[uniform(0)]
+    pub time: f32,
+}
 
 impl Material for AnimatedMaterial {
     fn fragment_shader() -> ShaderRef {
@@ -22,19 +24,26 @@ impl Material for AnimatedMaterial {
     }
 }
 
+impl GameTimeMaterial for AnimatedMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.time = seconds;
+    }
+}
+
 /// Holographic interference pattern material with customizable color
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct HolographicMaterial {
     #[uniform(0)]
     pub data: HolographicData,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 pub struct HolographicData {
-    pub base_color: Vec4,
+    pub base_color: LinearRgba,
     pub interference_intensity: f32,
     pub scan_speed: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -48,20 +57,27 @@ impl Material for HolographicMaterial {
     }
 }
 
+impl GameTimeMaterial for HolographicMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 /// Portal/wormhole effect material
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct PortalMaterial {
     #[uniform(0)]
     pub data: PortalData,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 pub struct PortalData {
-    pub center_color: Vec4,
-    pub edge_color: Vec4,
+    pub center_color: LinearRgba,
+    pub edge_color: LinearRgba,
     pub rotation_speed: f32,
     pub distortion_strength: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -71,21 +87,26 @@ impl Material for PortalMaterial {
     }
 }
 
+impl GameTimeMaterial for PortalMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 /// Energy field material with electrical arcs
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct EnergyFieldMaterial {
     #[uniform(0)]
     pub data: EnergyFieldData,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 pub struct EnergyFieldData {
-    pub energy_color: Vec4,
+    pub energy_color: LinearRgba,
     pub arc_intensity: f32,
     pub flow_speed: f32,
     pub noise_scale: f32,
-    #[shader(size(4))]
-    pub _padding: u32,
+    pub time: f32,
 }
 
 impl Material for EnergyFieldMaterial {
@@ -98,21 +119,26 @@ impl Material for EnergyFieldMaterial {
     }
 }
 
+impl GameTimeMaterial for EnergyFieldMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 /// Liquid metal surface with ripples
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct LiquidMetalMaterial {
     #[uniform(0)]
     pub data: LiquidMetalData,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 pub struct LiquidMetalData {
-    pub base_color: Vec4,
+    pub base_color: LinearRgba,
     pub ripple_speed: f32,
     pub ripple_frequency: f32,
     pub metallic_strength: f32,
-    #[shader(size(4))]
-    pub _padding: u32,
+    pub time: f32,
 }
 
 impl Material for LiquidMetalMaterial {
@@ -121,20 +147,27 @@ impl Material for LiquidMetalMaterial {
     }
 }
 
+impl GameTimeMaterial for LiquidMetalMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 /// Constellation/star field effect for backgrounds
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct ConstellationMaterial {
     #[uniform(0)]
     pub data: ConstellationData,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 pub struct ConstellationData {
-    pub star_color: Vec4,
-    pub nebula_color: Vec4,
+    pub star_color: LinearRgba,
+    pub nebula_color: LinearRgba,
     pub twinkle_speed: f32,
     pub star_density: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -144,16 +177,22 @@ impl Material for ConstellationMaterial {
     }
 }
 
+impl GameTimeMaterial for ConstellationMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 /// Fractal material for generating Mandelbrot and Julia set visualizations
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct FractalMaterial {
     #[uniform(0)]
     pub data: FractalData,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 pub struct FractalData {
-    pub base_color: Vec4,
+    pub base_color: LinearRgba,
     pub time: f32,
     pub zoom: f32,
     pub offset_x: f32,
@@ -170,14 +209,21 @@ impl Material for FractalMaterial {
     }
 }
 
+impl GameTimeMaterial for FractalMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 // Helper functions to create materials with good default values
 impl Default for HolographicMaterial {
     fn default() -> Self {
         Self {
             data: HolographicData {
-                base_color: Vec4::new(0.0, 1.0, 1.0, 1.0),
+                base_color: LinearRgba::new(0.0, 1.0, 1.0, 1.0),
                 interference_intensity: 1.0,
                 scan_speed: 2.0,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -188,10 +234,11 @@ impl Default for PortalMaterial {
     fn default() -> Self {
         Self {
             data: PortalData {
-                center_color: Vec4::new(1.0, 1.0, 1.0, 1.0),
-                edge_color: Vec4::new(0.2, 0.0, 1.0, 1.0),
+                center_color: LinearRgba::new(1.0, 1.0, 1.0, 1.0),
+                edge_color: LinearRgba::new(0.2, 0.0, 1.0, 1.0),
                 rotation_speed: 1.0,
                 distortion_strength: 0.5,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -202,11 +249,11 @@ impl Default for EnergyFieldMaterial {
     fn default() -> Self {
         Self {
             data: EnergyFieldData {
-                energy_color: Vec4::new(0.0, 0.8, 1.0, 1.0),
+                energy_color: LinearRgba::new(0.0, 0.8, 1.0, 1.0),
                 arc_intensity: 2.0,
                 flow_speed: 3.0,
                 noise_scale: 8.0,
-                _padding: 0,
+                time: 0.0,
             },
         }
     }
@@ -216,11 +263,11 @@ impl Default for LiquidMetalMaterial {
     fn default() -> Self {
         Self {
             data: LiquidMetalData {
-                base_color: Vec4::new(0.8, 0.8, 0.9, 1.0),
+                base_color: LinearRgba::new(0.8, 0.8, 0.9, 1.0),
                 ripple_speed: 1.5,
                 ripple_frequency: 4.0,
                 metallic_strength: 0.95,
-                _padding: 0,
+                time: 0.0,
             },
         }
     }
@@ -230,10 +277,11 @@ impl Default for ConstellationMaterial {
     fn default() -> Self {
         Self {
             data: ConstellationData {
-                star_color: Vec4::new(1.0, 1.0, 1.0, 1.0), // Pure white stars
-                nebula_color: Vec4::new(0.0, 0.0, 0.0, 1.0), // Not used in new shader
+                star_color: LinearRgba::new(1.0, 1.0, 1.0, 1.0), // Pure white stars
+                nebula_color: LinearRgba::new(0.0, 0.0, 0.0, 1.0), // Not used in new shader
                 twinkle_speed: 3.0, // Faster twinkling for more dynamic effect
                 star_density: 0.6,  // Many more stars for better visibility
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -244,7 +292,7 @@ impl Default for FractalMaterial {
     fn default() -> Self {
         Self {
             data: FractalData {
-                base_color: Vec4::new(0.0, 0.0, 0.0, 1.0),
+                base_color: LinearRgba::new(0.0, 0.0, 0.0, 1.0),
                 time: 0.0,
                 zoom: 1.0,
                 offset_x: 0.0,
@@ -262,7 +310,7 @@ impl Default for FractalMaterial {
 pub fn create_animated_material(
     materials: &mut ResMut<Assets<AnimatedMaterial>>,
 ) -> Handle<AnimatedMaterial> {
-    materials.add(AnimatedMaterial {})
+    materials.add(AnimatedMaterial { time: 0.0 })
 }
 
 pub fn create_holographic_material(
@@ -270,13 +318,12 @@ pub fn create_holographic_material(
     color: Color,
     intensity: f32,
 ) -> Handle<HolographicMaterial> {
-    let [r, g, b, a] = color.to_linear().to_f32_array();
-    let base_color = Vec4::new(r, g, b, a);
     materials.add(HolographicMaterial {
         data: HolographicData {
-            base_color,
+            base_color: color.to_linear(),
             interference_intensity: intensity,
             scan_speed: 2.0,
+            time: 0.0,
             _padding: 0,
         },
     })
@@ -287,16 +334,13 @@ pub fn create_portal_material(
     center_color: Color,
     edge_color: Color,
 ) -> Handle<PortalMaterial> {
-    let [r1, g1, b1, a1] = center_color.to_linear().to_f32_array();
-    let center_vec4 = Vec4::new(r1, g1, b1, a1);
-    let [r2, g2, b2, a2] = edge_color.to_linear().to_f32_array();
-    let edge_vec4 = Vec4::new(r2, g2, b2, a2);
     materials.add(PortalMaterial {
         data: PortalData {
-            center_color: center_vec4,
-            edge_color: edge_vec4,
+            center_color: center_color.to_linear(),
+            edge_color: edge_color.to_linear(),
             rotation_speed: 1.0,
             distortion_strength: 0.5,
+            time: 0.0,
             _padding: 0,
         },
     })
@@ -307,15 +351,13 @@ pub fn create_energy_field_material(
     color: Color,
     intensity: f32,
 ) -> Handle<EnergyFieldMaterial> {
-    let [r, g, b, a] = color.to_linear().to_f32_array();
-    let color_vec4 = Vec4::new(r, g, b, a);
     materials.add(EnergyFieldMaterial {
         data: EnergyFieldData {
-            energy_color: color_vec4,
+            energy_color: color.to_linear(),
             arc_intensity: intensity,
             flow_speed: 3.0,
             noise_scale: 8.0,
-            _padding: 0,
+            time: 0.0,
         },
     })
 }
@@ -324,15 +366,13 @@ pub fn create_liquid_metal_material(
     materials: &mut ResMut<Assets<LiquidMetalMaterial>>,
     color: Color,
 ) -> Handle<LiquidMetalMaterial> {
-    let [r, g, b, a] = color.to_linear().to_f32_array();
-    let color_vec4 = Vec4::new(r, g, b, a);
     materials.add(LiquidMetalMaterial {
         data: LiquidMetalData {
-            base_color: color_vec4,
+            base_color: color.to_linear(),
             ripple_speed: 1.5,
             ripple_frequency: 4.0,
             metallic_strength: 0.95,
-            _padding: 0,
+            time: 0.0,
         },
     })
 }
@@ -342,16 +382,13 @@ pub fn create_constellation_material(
     star_color: Color,
     nebula_color: Color,
 ) -> Handle<ConstellationMaterial> {
-    let [r1, g1, b1, a1] = star_color.to_linear().to_f32_array();
-    let star_vec4 = Vec4::new(r1, g1, b1, a1);
-    let [r2, g2, b2, a2] = nebula_color.to_linear().to_f32_array();
-    let nebula_vec4 = Vec4::new(r2, g2, b2, a2);
     materials.add(ConstellationMaterial {
         data: ConstellationData {
-            star_color: star_vec4,
-            nebula_color: nebula_vec4,
+            star_color: star_color.to_linear(),
+            nebula_color: nebula_color.to_linear(),
             twinkle_speed: 0.5,
             star_density: 0.3,
+            time: 0.0,
             _padding: 0,
         },
     })
@@ -365,11 +402,9 @@ pub fn create_fractal_material(
     offset_x: f32,
     offset_y: f32,
 ) -> Handle<FractalMaterial> {
-    let [r, g, b, a] = base_color.to_linear().to_f32_array();
-    let base_vec4 = Vec4::new(r, g, b, a);
     materials.add(FractalMaterial {
         data: FractalData {
-            base_color: base_vec4,
+            base_color: base_color.to_linear(),
             time: 0.0, // Will be updated via system
             zoom,
             offset_x,
@@ -383,20 +418,23 @@ pub fn create_fractal_material(
 }
 
 /// Morphing sculpture material with complex animated patterns
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[derive(Asset, Reflect, AsBindGroup, Debug, Clone)]
 pub struct MorphingSculptureMaterial {
     #[uniform(0)]
     pub data: MorphingSculptureData,
 }
 
-#[derive(Debug, Clone, Copy, ShaderType)]
+#[derive(Debug, Clone, Copy, ShaderType, Reflect)]
 pub struct MorphingSculptureData {
-    pub base_color: Vec4,
-    pub secondary_color: Vec4,
+    pub base_color: LinearRgba,
+    pub secondary_color: LinearRgba,
     pub morph_speed: f32,
     pub morph_intensity: f32,
     pub detail_scale: f32,
     pub glow_strength: f32,
+    pub time: f32,
+    #[shader(size(12))]
+    pub _padding: u32,
 }
 
 impl Material for MorphingSculptureMaterial {
@@ -405,16 +443,24 @@ impl Material for MorphingSculptureMaterial {
     }
 }
 
+impl GameTimeMaterial for MorphingSculptureMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 impl Default for MorphingSculptureMaterial {
     fn default() -> Self {
         Self {
             data: MorphingSculptureData {
-                base_color: Vec4::new(0.4, 0.2, 0.8, 1.0), // Deep purple
-                secondary_color: Vec4::new(0.2, 0.8, 0.9, 1.0), // Cyan
+                base_color: LinearRgba::new(0.4, 0.2, 0.8, 1.0), // Deep purple
+                secondary_color: LinearRgba::new(0.2, 0.8, 0.9, 1.0), // Cyan
                 morph_speed: 1.0,
                 morph_intensity: 1.0,
                 detail_scale: 3.0,
                 glow_strength: 0.8,
+                time: 0.0,
+                _padding: 0,
             },
         }
     }
@@ -428,18 +474,16 @@ pub fn create_morphing_sculpture_material(
     morph_speed: f32,
     detail_scale: f32,
 ) -> Handle<MorphingSculptureMaterial> {
-    let [r1, g1, b1, a1] = base_color.to_linear().to_f32_array();
-    let base_vec4 = Vec4::new(r1, g1, b1, a1);
-    let [r2, g2, b2, a2] = secondary_color.to_linear().to_f32_array();
-    let secondary_vec4 = Vec4::new(r2, g2, b2, a2);
     materials.add(MorphingSculptureMaterial {
         data: MorphingSculptureData {
-            base_color: base_vec4,
-            secondary_color: secondary_vec4,
+            base_color: base_color.to_linear(),
+            secondary_color: secondary_color.to_linear(),
             morph_speed,
             morph_intensity: 1.2,
             detail_scale,
             glow_strength: 1.0,
+            time: 0.0,
+            _padding: 0,
         },
     })
 }
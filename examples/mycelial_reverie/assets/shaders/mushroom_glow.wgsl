@@ -7,7 +7,7 @@
 // - Fresnel rim lighting for a haloed silhouette
 
 #import bevy_pbr::{
-    mesh_view_bindings::{globals, view},
+    mesh_view_bindings::view,
     forward_io::VertexOutput,
 }
 
@@ -18,6 +18,7 @@ struct MushroomGlowMaterial {
     vein_scale: f32,
     fresnel_power: f32,
     phase_offset: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: MushroomGlowMaterial;
@@ -51,7 +52,7 @@ fn fbm(p: vec2<f32>) -> f32 {
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
-    let time = globals.time * material.pulse_speed + material.phase_offset;
+    let time = material.time * material.pulse_speed + material.phase_offset;
 
     let uv = in.uv * material.vein_scale;
     let radial = length(in.uv - vec2<f32>(0.5)) * 2.0;
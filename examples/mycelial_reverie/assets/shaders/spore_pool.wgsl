@@ -8,7 +8,7 @@
 // - Fresnel edge glow for a haloed silhouette when viewed obliquely
 
 #import bevy_pbr::{
-    mesh_view_bindings::{globals, view},
+    mesh_view_bindings::view,
     forward_io::VertexOutput,
 }
 
@@ -19,7 +19,7 @@ struct SporePoolMaterial {
     ripple_scale: f32,
     flow_speed: f32,
     glow_strength: f32,
-    _padding: f32,
+    time: f32,
 }
 
 @group(3) @binding(0) var<uniform> material: SporePoolMaterial;
@@ -53,7 +53,7 @@ fn fbm(p: vec2<f32>) -> f32 {
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
-    let time = globals.time * material.flow_speed;
+    let time = material.time * material.flow_speed;
 
     let centered = in.uv - vec2<f32>(0.5);
     let radial = length(centered) * 2.0;
@@ -33,7 +33,7 @@ impl Plugin for MycelialReveriePlugin {
 
 fn main() -> AppExit {
     App::new()
-        .add_plugins(DioramaPlugin)
+        .add_plugins(DioramaPlugin::default())
         .add_plugins(materials::MycelialMaterialsPlugin)
         .add_plugins(MycelialReveriePlugin)
         .run()
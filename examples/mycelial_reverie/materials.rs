@@ -7,6 +7,7 @@ use bevy::prelude::*;
 use bevy::reflect::TypePath;
 use bevy::render::render_resource::{AsBindGroup, ShaderType};
 use bevy::shader::ShaderRef;
+use diorama::game_time::{GameTimeMaterial, GameTimeMaterialPlugin};
 
 pub struct MycelialMaterialsPlugin;
 
@@ -15,6 +16,8 @@ impl Plugin for MycelialMaterialsPlugin {
         app.add_plugins((
             MaterialPlugin::<MushroomGlowMaterial>::default(),
             MaterialPlugin::<SporePoolMaterial>::default(),
+            GameTimeMaterialPlugin::<MushroomGlowMaterial>::default(),
+            GameTimeMaterialPlugin::<SporePoolMaterial>::default(),
         ));
     }
 }
@@ -34,6 +37,7 @@ pub struct MushroomGlowData {
     pub vein_scale: f32,
     pub fresnel_power: f32,
     pub phase_offset: f32,
+    pub time: f32,
 }
 
 impl Material for MushroomGlowMaterial {
@@ -46,6 +50,12 @@ impl Material for MushroomGlowMaterial {
     }
 }
 
+impl GameTimeMaterial for MushroomGlowMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 /// Rippling spore-pool water material with domain-warped caustics and drifting motes.
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
 pub struct SporePoolMaterial {
@@ -61,7 +71,7 @@ pub struct SporePoolData {
     pub ripple_scale: f32,
     pub flow_speed: f32,
     pub glow_strength: f32,
-    pub _padding: f32,
+    pub time: f32,
 }
 
 impl Material for SporePoolMaterial {
@@ -77,3 +87,9 @@ impl Material for SporePoolMaterial {
         false
     }
 }
+
+impl GameTimeMaterial for SporePoolMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
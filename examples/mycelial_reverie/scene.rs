@@ -5,8 +5,9 @@ use std::f32::consts::{PI, TAU};
 use avian3d::prelude::*;
 use bevy::color::palettes::tailwind;
 use bevy::math::Vec4;
-use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::mesh::VertexAttributeValues;
 use bevy::prelude::*;
+use diorama::mesh_collider::ColliderFromMesh;
 use diorama::player::Player;
 use examples_common::noise::Perlin;
 
@@ -185,6 +186,7 @@ fn build_glows(
                 vein_scale: 4.2,
                 fresnel_power: 2.4,
                 phase_offset: 0.0,
+                time: 0.0,
             },
         }),
         cap_amber: glow_materials.add(MushroomGlowMaterial {
@@ -195,6 +197,7 @@ fn build_glows(
                 vein_scale: 3.4,
                 fresnel_power: 2.0,
                 phase_offset: 1.7,
+                time: 0.0,
             },
         }),
         cap_violet: glow_materials.add(MushroomGlowMaterial {
@@ -205,6 +208,7 @@ fn build_glows(
                 vein_scale: 3.8,
                 fresnel_power: 2.8,
                 phase_offset: 2.6,
+                time: 0.0,
             },
         }),
         cap_rose: glow_materials.add(MushroomGlowMaterial {
@@ -215,6 +219,7 @@ fn build_glows(
                 vein_scale: 5.0,
                 fresnel_power: 2.2,
                 phase_offset: 4.1,
+                time: 0.0,
             },
         }),
         pool: pool_materials.add(SporePoolMaterial {
@@ -225,7 +230,7 @@ fn build_glows(
                 ripple_scale: 22.0,
                 flow_speed: 0.45,
                 glow_strength: 1.6,
-                _padding: 0.0,
+                time: 0.0,
             },
         }),
     }
@@ -301,45 +306,12 @@ fn spawn_terrain(
 
     terrain.compute_normals();
 
-    let vertex_positions: Vec<Vec3> = terrain
-        .attribute(Mesh::ATTRIBUTE_POSITION)
-        .and_then(|attribute| match attribute {
-            VertexAttributeValues::Float32x3(positions) => Some(
-                positions
-                    .iter()
-                    .map(|position| Vec3::from_array(*position))
-                    .collect(),
-            ),
-            _ => None,
-        })
-        .unwrap_or_default();
-
-    let indices: Vec<[u32; 3]> = terrain
-        .indices()
-        .map(|indices| match indices {
-            Indices::U32(indices) => indices
-                .chunks(3)
-                .map(|chunk| [chunk[0], chunk[1], chunk[2]])
-                .collect(),
-            Indices::U16(indices) => indices
-                .chunks(3)
-                .map(|chunk| {
-                    [
-                        u32::from(chunk[0]),
-                        u32::from(chunk[1]),
-                        u32::from(chunk[2]),
-                    ]
-                })
-                .collect(),
-        })
-        .unwrap_or_default();
-
     commands.spawn((
         Name::new("Grove Terrain"),
         Mesh3d(meshes.add(terrain)),
         MeshMaterial3d(palette.ground.clone()),
         RigidBody::Static,
-        Collider::trimesh(vertex_positions, indices),
+        ColliderFromMesh::Trimesh,
     ));
 
     // A few scattered mossy clumps hint at overgrowth.
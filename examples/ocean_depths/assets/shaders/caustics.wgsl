@@ -3,14 +3,12 @@
 // Simulates the dancing light patterns seen underwater when sunlight
 // refracts through the water surface.
 
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct CausticsData {
     color: vec4<f32>,
     speed: f32,
+    time: f32,
 }
 
 @group(3) @binding(0)
@@ -68,7 +66,7 @@ fn caustic_pattern(p: vec2<f32>, time: f32) -> f32 {
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
-    let time = globals.time * material.speed;
+    let time = material.time * material.speed;
 
     // Use world position for consistent pattern
     let uv = in.uv * 10.0;
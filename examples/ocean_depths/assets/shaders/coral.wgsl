@@ -3,16 +3,14 @@
 // Creates organic coral surface texture with polyp patterns,
 // subtle bioluminescence, and color gradients.
 
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct CoralData {
     base_color: vec4<f32>,
     tip_color: vec4<f32>,
     glow_intensity: f32,
     polyp_density: f32,
+    time: f32,
 }
 
 @group(3) @binding(0)
@@ -96,7 +94,7 @@ fn polyp_tentacles(p: vec2<f32>, time: f32) -> f32 {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Gradient from base to tip (using V coordinate as height)
     let height_gradient = uv.y;
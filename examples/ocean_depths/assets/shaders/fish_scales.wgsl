@@ -3,16 +3,14 @@
 // Creates iridescent overlapping fish scales with
 // animated shimmer effect.
 
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct FishScalesData {
     base_color: vec4<f32>,
     iridescence_color: vec4<f32>,
     scale_size: f32,
     shimmer_speed: f32,
+    time: f32,
 }
 
 @group(3) @binding(0)
@@ -62,7 +60,7 @@ fn iridescence(uv: vec2<f32>, time: f32) -> vec3<f32> {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Scale the UVs for scale pattern
     let scaled_uv = uv * material.scale_size;
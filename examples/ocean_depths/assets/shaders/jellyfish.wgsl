@@ -3,16 +3,14 @@
 // Creates a translucent, pulsing jellyfish material with
 // internal glow and surface patterns.
 
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct JellyfishData {
     base_color: vec4<f32>,
     glow_color: vec4<f32>,
     pulse_speed: f32,
     translucency: f32,
+    time: f32,
 }
 
 @group(3) @binding(0)
@@ -77,7 +75,7 @@ fn veins(uv: vec2<f32>, time: f32) -> f32 {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Pulsing effect
     let pulse = sin(time * material.pulse_speed) * 0.5 + 0.5;
@@ -3,16 +3,14 @@
 // Creates a procedural rock texture with moss, barnacles,
 // and underwater weathering effects.
 
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct MossyRockData {
     rock_color: vec4<f32>,
     moss_color: vec4<f32>,
     moss_amount: f32,
     wetness: f32,
+    time: f32,
 }
 
 @group(3) @binding(0)
@@ -82,7 +80,7 @@ fn voronoi(p: vec2<f32>) -> vec3<f32> {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Base rock texture with multiple noise layers
     let rock_noise = fbm(uv * 8.0);
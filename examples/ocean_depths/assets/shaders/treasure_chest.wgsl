@@ -3,16 +3,14 @@
 // Creates weathered wood texture with barnacles,
 // metal trim, and magical glow effect.
 
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct TreasureChestData {
     wood_color: vec4<f32>,
     glow_color: vec4<f32>,
     weathering: f32,
     magic_intensity: f32,
+    time: f32,
 }
 
 @group(3) @binding(0)
@@ -86,7 +84,7 @@ fn barnacles(p: vec2<f32>) -> f32 {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Base wood texture
     let grain = wood_grain(uv);
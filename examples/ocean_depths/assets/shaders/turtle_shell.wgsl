@@ -3,16 +3,14 @@
 // Creates a procedural turtle shell pattern with hexagonal scutes,
 // age rings, and subtle iridescence.
 
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
 
 struct TurtleShellData {
     base_color: vec4<f32>,
     accent_color: vec4<f32>,
     age: f32,
     roughness: f32,
+    time: f32,
 }
 
 @group(3) @binding(0)
@@ -75,7 +73,7 @@ fn age_rings(p: vec2<f32>, age: f32) -> f32 {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
-    let time = globals.time;
+    let time = material.time;
 
     // Scale UV for hexagonal pattern
     let hex_uv = uv * 6.0;
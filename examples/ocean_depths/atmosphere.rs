@@ -1,8 +1,8 @@
 //! Underwater atmosphere and lighting effects
 //!
 //! Creates the underwater ambiance through:
-//! - Shader-based caustics on the seafloor
-//! - Underwater fog color
+//! - Shader-based caustics on the seafloor, dimmed as the sea state roughens
+//! - Underwater fog and background colour from the environment preset
 //! - Particle bubbles rising
 //! - Floating plankton and organic matter
 //! - Sand particles near the floor
@@ -10,11 +10,24 @@
 
 use bevy::math::Vec4;
 use bevy::prelude::*;
+use diorama::biome::BiomeParams;
+use diorama::cull::CullAtDistance;
+use diorama::environment::EnvironmentPreset;
+use diorama::game_time::GameTime;
+use diorama::particles::{EmitterShape, LifeGradient, ParticleEmitter};
+use diorama::sea_state::SeaState;
 
 use crate::materials::{CausticsData, CausticsMaterial};
 
 pub struct AtmospherePlugin;
 
+/// Particle tiles whose centres are further than this from the player are hidden and stop
+/// animating.
+const PARTICLE_CULL_DISTANCE: f32 = 45.0;
+/// Particles fill a grid of this many tiles along each side, centred on the origin.
+const PARTICLE_TILES: u32 = 4;
+const PARTICLE_TILE_SIZE: f32 = 25.0;
+
 impl Plugin for AtmospherePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
@@ -23,13 +36,7 @@ impl Plugin for AtmospherePlugin {
         )
         .add_systems(
             Update,
-            (
-                animate_caustics_light,
-                animate_bubbles,
-                animate_plankton,
-                animate_sand_particles,
-                animate_god_rays,
-            ),
+            (animate_caustics_light, dim_caustics_plane, animate_god_rays),
         );
     }
 }
@@ -41,29 +48,10 @@ pub struct CausticsLight {
     pub phase: f32,
 }
 
-/// Rising bubble particle
+/// Caustics overlay whose brightness follows the sea state
 #[derive(Component)]
-pub struct Bubble {
-    pub speed: f32,
-    pub wobble_phase: f32,
-    pub start_x: f32,
-    pub start_z: f32,
-}
-
-/// Floating plankton/organic particle
-#[derive(Component)]
-pub struct Plankton {
-    pub drift_phase: f32,
-    pub drift_speed: f32,
-    pub base_pos: Vec3,
-}
-
-/// Sand particle near seafloor
-#[derive(Component)]
-pub struct SandParticle {
-    pub settle_speed: f32,
-    pub drift_phase: f32,
-    pub base_pos: Vec3,
+pub struct CausticsPlane {
+    pub base_color: Vec4,
 }
 
 /// God ray light shaft
@@ -77,16 +65,10 @@ fn setup_atmosphere(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    biome: Res<BiomeParams>,
 ) {
-    // Deep blue underwater background
-    commands.insert_resource(ClearColor(Color::srgb(0.02, 0.15, 0.3)));
-
-    // Ambient underwater light - bright enough to see the ocean floor
-    commands.insert_resource(GlobalAmbientLight {
-        color: Color::srgb(0.4, 0.6, 0.8),
-        brightness: 800.0,
-        affects_lightmapped_meshes: true,
-    });
+    // Deep blue background and fog, with ambient light bright enough to see the ocean floor
+    commands.insert_resource(biome.weather(EnvironmentPreset::Underwater.settings()));
 
     // Main directional "sun" light filtering through water
     commands.spawn((
@@ -129,33 +111,6 @@ fn setup_atmosphere(
         ));
     }
 
-    // Spawn bubble particles
-    let bubble_mesh = meshes.add(Sphere::new(0.08));
-    let bubble_material = materials.add(StandardMaterial {
-        base_color: Color::srgba(0.8, 0.9, 1.0, 0.4),
-        alpha_mode: AlphaMode::Blend,
-        ..default()
-    });
-
-    for _ in 0..80 {
-        let x = (rand::random::<f32>() - 0.5) * 100.0;
-        let z = (rand::random::<f32>() - 0.5) * 100.0;
-        let y = rand::random::<f32>() * 20.0 - 5.0;
-
-        commands.spawn((
-            Mesh3d(bubble_mesh.clone()),
-            MeshMaterial3d(bubble_material.clone()),
-            Transform::from_xyz(x, y, z).with_scale(Vec3::splat(0.3 + rand::random::<f32>() * 1.2)),
-            Bubble {
-                speed: 0.8 + rand::random::<f32>() * 2.5,
-                wobble_phase: rand::random::<f32>() * std::f32::consts::TAU,
-                start_x: x,
-                start_z: z,
-            },
-            Name::new("Bubble"),
-        ));
-    }
-
     // Spawn underwater "god rays" as semi-transparent animated shafts
     spawn_god_rays(&mut commands, &mut meshes, &mut materials);
 }
@@ -170,10 +125,12 @@ fn spawn_caustics_planes(
     // Position it just above the seafloor terrain
     let plane_mesh = meshes.add(Plane3d::default().mesh().size(200.0, 200.0).build());
 
+    let base_color = Vec4::new(0.4, 0.65, 0.95, 1.0);
     let caustics_mat = caustics_materials.add(CausticsMaterial {
         data: CausticsData {
-            color: Vec4::new(0.4, 0.65, 0.95, 1.0),
+            color: base_color,
             speed: 0.8,
+            time: 0.0,
             _padding: 0,
         },
     });
@@ -183,98 +140,101 @@ fn spawn_caustics_planes(
         MeshMaterial3d(caustics_mat),
         Transform::from_xyz(0.0, -3.5, 0.0),
         Name::new("Caustics Plane"),
+        CausticsPlane { base_color },
     ));
 }
-/// Spawn various particle effects
-fn spawn_particles(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    // Plankton - tiny glowing organic particles
-    let plankton_mesh = meshes.add(Sphere::new(0.03));
-    let plankton_material = materials.add(StandardMaterial {
-        base_color: Color::srgba(0.6, 0.9, 0.7, 0.6),
-        emissive: Color::srgb(0.1, 0.2, 0.15).into(),
-        alpha_mode: AlphaMode::Blend,
-        unlit: true,
-        ..default()
-    });
+/// Spawn bubbles, plankton and sand, in a grid of tiles so those far from the player are culled
+fn spawn_particles(mut commands: Commands) {
+    let half = PARTICLE_TILES as f32 * PARTICLE_TILE_SIZE / 2.0;
+    let tile_extents =
+        |height: f32| Vec3::new(PARTICLE_TILE_SIZE / 2.0, height, PARTICLE_TILE_SIZE / 2.0);
+    for (i, (x, z)) in (0..PARTICLE_TILES)
+        .flat_map(|x| (0..PARTICLE_TILES).map(move |z| (x, z)))
+        .enumerate()
+    {
+        let centre = Vec3::new(
+            (x as f32 + 0.5) * PARTICLE_TILE_SIZE - half,
+            0.0,
+            (z as f32 + 0.5) * PARTICLE_TILE_SIZE - half,
+        );
+        let seed = i as u64;
+        let cull = CullAtDistance::new(PARTICLE_CULL_DISTANCE);
 
-    for _ in 0..150 {
-        let x = (rand::random::<f32>() - 0.5) * 100.0;
-        let z = (rand::random::<f32>() - 0.5) * 100.0;
-        let y = rand::random::<f32>() * 25.0 - 5.0;
-        let base_pos = Vec3::new(x, y, z);
+        // Bubbles rise from the seafloor and pop at the surface
+        commands.spawn((
+            Name::new("Bubbles"),
+            ParticleEmitter::bubbles()
+                .with_rate(0.4)
+                .with_shape(EmitterShape::Box {
+                    half_extents: tile_extents(0.0),
+                })
+                .with_lifetime(12.0, 0.1)
+                .with_velocity(Vec3::Y * 2.0, Vec3::new(0.0, 0.4, 0.0))
+                .with_seed(seed),
+            Transform::from_translation(centre - Vec3::Y * 5.0),
+            cull.clone(),
+        ));
 
+        // Plankton - tiny glowing organic particles drifting in the water
+        let plankton = ParticleEmitter::default()
+            .with_shape(EmitterShape::Box {
+                half_extents: tile_extents(12.5),
+            })
+            .with_lifetime(20.0, 0.5)
+            .with_velocity(Vec3::ZERO, Vec3::splat(0.1))
+            .with_wobble(1.5, 0.08)
+            .with_size(0.07, 0.5)
+            .with_prewarm()
+            .with_seed(seed);
         commands.spawn((
-            Mesh3d(plankton_mesh.clone()),
-            MeshMaterial3d(plankton_material.clone()),
-            Transform::from_translation(base_pos)
-                .with_scale(Vec3::splat(0.5 + rand::random::<f32>() * 1.5)),
-            Plankton {
-                drift_phase: rand::random::<f32>() * std::f32::consts::TAU,
-                drift_speed: 0.3 + rand::random::<f32>() * 0.5,
-                base_pos,
-            },
             Name::new("Plankton"),
+            plankton
+                .clone()
+                .with_rate(0.5)
+                .with_color_over_life(LifeGradient::fade_in_out(
+                    Color::srgba(0.6, 0.9, 0.7, 0.6),
+                    0.2,
+                )),
+            Transform::from_translation(centre + Vec3::Y * 7.5),
+            cull.clone(),
         ));
-    }
-
-    // Bioluminescent plankton - brighter, rarer
-    let biolum_material = materials.add(StandardMaterial {
-        base_color: Color::srgba(0.3, 0.8, 1.0, 0.8),
-        emissive: Color::srgb(0.2, 0.5, 0.6).into(),
-        alpha_mode: AlphaMode::Blend,
-        unlit: true,
-        ..default()
-    });
-
-    for _ in 0..30 {
-        let x = (rand::random::<f32>() - 0.5) * 80.0;
-        let z = (rand::random::<f32>() - 0.5) * 80.0;
-        let y = rand::random::<f32>() * 20.0 - 3.0;
-        let base_pos = Vec3::new(x, y, z);
-
+        // Bioluminescent plankton - brighter, rarer
         commands.spawn((
-            Mesh3d(plankton_mesh.clone()),
-            MeshMaterial3d(biolum_material.clone()),
-            Transform::from_translation(base_pos)
-                .with_scale(Vec3::splat(0.8 + rand::random::<f32>() * 1.0)),
-            Plankton {
-                drift_phase: rand::random::<f32>() * std::f32::consts::TAU,
-                drift_speed: 0.2 + rand::random::<f32>() * 0.3,
-                base_pos,
-            },
             Name::new("Bioluminescent Plankton"),
+            plankton
+                .with_rate(0.1)
+                .with_size(0.09, 0.4)
+                .with_color_over_life(LifeGradient::fade_in_out(
+                    LinearRgba::new(0.6, 1.6, 2.0, 0.8),
+                    0.2,
+                ))
+                .with_seed(seed + 1),
+            Transform::from_translation(centre + Vec3::Y * 7.0),
+            cull.clone(),
         ));
-    }
-
-    // Sand particles near the seafloor
-    let sand_mesh = meshes.add(Sphere::new(0.02));
-    let sand_material = materials.add(StandardMaterial {
-        base_color: Color::srgba(0.8, 0.75, 0.6, 0.5),
-        alpha_mode: AlphaMode::Blend,
-        ..default()
-    });
-
-    for _ in 0..100 {
-        let x = (rand::random::<f32>() - 0.5) * 120.0;
-        let z = (rand::random::<f32>() - 0.5) * 120.0;
-        let y = -4.0 + rand::random::<f32>() * 3.0; // Near seafloor
-        let base_pos = Vec3::new(x, y, z);
 
+        // Sand stirred up near the seafloor, drifting on the current and settling again
         commands.spawn((
-            Mesh3d(sand_mesh.clone()),
-            MeshMaterial3d(sand_material.clone()),
-            Transform::from_translation(base_pos)
-                .with_scale(Vec3::splat(0.5 + rand::random::<f32>() * 1.0)),
-            SandParticle {
-                settle_speed: 0.1 + rand::random::<f32>() * 0.2,
-                drift_phase: rand::random::<f32>() * std::f32::consts::TAU,
-                base_pos,
-            },
-            Name::new("Sand Particle"),
+            Name::new("Sand"),
+            ParticleEmitter::default()
+                .with_rate(0.4)
+                .with_shape(EmitterShape::Box {
+                    half_extents: tile_extents(1.5),
+                })
+                .with_lifetime(15.0, 0.3)
+                .with_velocity(Vec3::new(0.1, 0.1, 0.05), Vec3::new(0.2, 0.1, 0.2))
+                .with_acceleration(Vec3::NEG_Y * 0.02)
+                .with_wobble(2.0, 0.06)
+                .with_size(0.04, 0.4)
+                .with_color_over_life(LifeGradient::fade_in_out(
+                    Color::srgba(0.8, 0.75, 0.6, 0.5),
+                    0.2,
+                ))
+                .with_lit(true)
+                .with_prewarm()
+                .with_seed(seed),
+            Transform::from_translation(centre - Vec3::Y * 2.5),
+            cull,
         ));
     }
 }
@@ -325,8 +285,14 @@ fn spawn_god_rays(
 }
 
 /// Animate caustics lights to simulate water surface refraction
-fn animate_caustics_light(time: Res<Time>, mut query: Query<(&mut PointLight, &CausticsLight)>) {
+fn animate_caustics_light(
+    time: Res<GameTime>,
+    sea: Res<SeaState>,
+    mut query: Query<(&mut PointLight, &CausticsLight)>,
+) {
     let t = time.elapsed_secs();
+    // Storm clouds and churned water let less light through
+    let brightness = sea.caustics_intensity();
 
     for (mut light, caustics) in query.iter_mut() {
         // Multiple sine waves for organic-feeling variation
@@ -335,84 +301,29 @@ fn animate_caustics_light(time: Res<Time>, mut query: Query<(&mut PointLight, &C
         let wave3 = (t * 1.3 + caustics.phase * 0.7).sin();
 
         let intensity_mod = 0.6 + (wave1 * 0.2 + wave2 * 0.15 + wave3 * 0.1);
-        light.intensity = caustics.base_intensity * intensity_mod;
+        light.intensity = caustics.base_intensity * intensity_mod * brightness;
     }
 }
 
-/// Animate bubbles rising and wobbling
-fn animate_bubbles(time: Res<Time>, mut query: Query<(&mut Transform, &mut Bubble)>) {
-    let dt = time.delta_secs();
-    let t = time.elapsed_secs();
-
-    for (mut transform, mut bubble) in query.iter_mut() {
-        // Rise upward
-        transform.translation.y += bubble.speed * dt;
-
-        // Wobble horizontally
-        transform.translation.x = bubble.start_x + (t + bubble.wobble_phase).sin() * 0.5;
-        transform.translation.z = bubble.start_z + (t * 1.3 + bubble.wobble_phase).cos() * 0.5;
-
-        // Reset bubble when it reaches the surface
-        if transform.translation.y > 20.0 {
-            transform.translation.y = -5.0;
-            bubble.start_x = (rand::random::<f32>() - 0.5) * 100.0;
-            bubble.start_z = (rand::random::<f32>() - 0.5) * 100.0;
-            transform.translation.x = bubble.start_x;
-            transform.translation.z = bubble.start_z;
-        }
-    }
-}
-
-/// Animate plankton with gentle drifting motion
-fn animate_plankton(time: Res<Time>, mut query: Query<(&mut Transform, &Plankton)>) {
-    let t = time.elapsed_secs();
-
-    for (mut transform, plankton) in query.iter_mut() {
-        let phase = plankton.drift_phase;
-        let speed = plankton.drift_speed;
-
-        // 3D Lissajous-like drifting pattern
-        let x_offset = (t * speed + phase).sin() * 1.5;
-        let y_offset = (t * speed * 0.7 + phase * 1.3).sin() * 0.8;
-        let z_offset = (t * speed * 0.9 + phase * 0.7).cos() * 1.5;
-
-        transform.translation = plankton.base_pos + Vec3::new(x_offset, y_offset, z_offset);
-
-        // Gentle pulsing scale for bioluminescence effect
-        let pulse = 0.9 + (t * 2.0 + phase).sin() * 0.1;
-        let base_scale = transform.scale.x; // Preserve original scale ratio
-        transform.scale = Vec3::splat(base_scale * pulse / (0.9 + 0.1)); // Normalize
+fn dim_caustics_plane(
+    sea: Res<SeaState>,
+    mut caustics_materials: ResMut<Assets<CausticsMaterial>>,
+    planes: Query<(&CausticsPlane, &MeshMaterial3d<CausticsMaterial>)>,
+) {
+    if !sea.is_changed() {
+        return;
     }
-}
-
-/// Animate sand particles with settling and drifting
-fn animate_sand_particles(time: Res<Time>, mut query: Query<(&mut Transform, &SandParticle)>) {
-    let t = time.elapsed_secs();
-
-    for (mut transform, sand) in query.iter_mut() {
-        let phase = sand.drift_phase;
-
-        // Horizontal drift from underwater currents
-        let x_offset = (t * 0.5 + phase).sin() * 2.0;
-        let z_offset = (t * 0.3 + phase * 1.5).cos() * 2.0;
-
-        // Occasional upward stirring, then settling back down
-        let stir_cycle = (t * 0.2 + phase).sin();
-        let y_offset = if stir_cycle > 0.7 {
-            // Being stirred up
-            (stir_cycle - 0.7) * 5.0
-        } else {
-            // Settling back down at settle_speed rate
-            let settle_amount = (0.7 - stir_cycle) * sand.settle_speed;
-            -settle_amount.min(0.5)
-        };
-
-        transform.translation = sand.base_pos + Vec3::new(x_offset, y_offset.max(-0.5), z_offset);
+    let brightness = sea.caustics_intensity();
+    for (plane, material) in &planes {
+        if let Some(material) = caustics_materials.get_mut(&material.0) {
+            material.data.color =
+                (plane.base_color.truncate() * brightness).extend(plane.base_color.w);
+        }
     }
 }
 
 /// Animate god rays with gentle swaying
-fn animate_god_rays(time: Res<Time>, mut query: Query<(&mut Transform, &GodRay)>) {
+fn animate_god_rays(time: Res<GameTime>, mut query: Query<(&mut Transform, &GodRay)>) {
     let t = time.elapsed_secs();
 
     for (mut transform, ray) in query.iter_mut() {
@@ -1,9 +1,9 @@
 //! Procedural coral reef generation
 //!
 //! Creates various types of coral formations:
-//! - Branching coral (tree-like structures)
+//! - Branching coral (tree-like structures grown from an L-system)
 //! - Brain coral (spherical with patterns)
-//! - Fan coral (flat, fan-shaped)
+//! - Fan coral (flat, fan-shaped, grown from an L-system)
 //! - Tube coral (cylindrical clusters)
 //! - Ancient coral (interactive, with dialogue)
 
@@ -12,9 +12,15 @@ use bevy::math::Vec4;
 use bevy::picking::events::{Click, Pointer};
 use bevy::prelude::*;
 use bevy_yarnspinner::prelude::*;
+use diorama::biome::BiomeParams;
+use diorama::cull::{CullAtDistance, Culled};
+use diorama::game_time::GameTime;
+use diorama::habitat::Habitat;
+use diorama::lsystem::{LSystem, LSystemLods, LSystemMeshes};
 use diorama::picking::Hint;
+use diorama::terrain::Heightmap;
 
-use crate::dialogue::{OceanDialogue, start_dialogue, terrain_height_at};
+use crate::dialogue::{OceanDialogue, start_dialogue};
 use crate::materials::{CoralData, CoralMaterial};
 
 pub struct CoralPlugin;
@@ -37,6 +43,20 @@ pub struct CoralSway {
     pub amplitude: f32,
 }
 
+/// Distinct growth forms generated per grown species, shared between corals via the mesh cache.
+const VARIANTS: u64 = 6;
+const CORAL_LODS: usize = 3;
+/// Distance from the camera at which grown corals switch to the next level of detail.
+const CORAL_LOD_DISTANCE: f32 = 20.0;
+/// Coral further than this from the player is hidden and stops swaying.
+const CORAL_CULL_DISTANCE: f32 = 70.0;
+
+enum CoralShape {
+    Primitive(Handle<Mesh>),
+    /// Grown from an L-system, with a child entity per level of detail.
+    Grown(LSystemLods),
+}
+
 /// Different coral species with unique visual properties
 #[derive(Clone, Copy)]
 enum CoralSpecies {
@@ -76,22 +96,53 @@ impl CoralSpecies {
     }
 }
 
+/// Tints a coral colour for the biome, e.g. bleaching it in a frozen sea.
+fn tint(biome: &BiomeParams, color: Vec4) -> Vec4 {
+    biome
+        .tint(Color::LinearRgba(LinearRgba::from_vec4(color)))
+        .to_linear()
+        .to_vec4()
+}
+
 fn spawn_coral_reef(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<CoralMaterial>>,
+    mut lsystems: ResMut<LSystemMeshes>,
+    biome: Res<BiomeParams>,
+    heightmap: Res<Heightmap>,
 ) {
-    // Create coral reef clusters in different areas
-    let reef_centers = [
-        Vec3::new(15.0, 0.0, 10.0),
-        Vec3::new(-20.0, 0.0, -15.0),
-        Vec3::new(5.0, 0.0, -25.0),
-        Vec3::new(-10.0, 0.0, 20.0),
-        Vec3::new(30.0, 0.0, -5.0),
+    // Create coral reef clusters in different areas, some home to schools of fish
+    let reefs = [
+        (
+            Vec3::new(15.0, 0.0, 10.0),
+            Some(Habitat::new("clownfish", 25)),
+        ),
+        (
+            Vec3::new(-20.0, 0.0, -15.0),
+            Some(Habitat::new("blue tang", 20)),
+        ),
+        (
+            Vec3::new(5.0, 0.0, -25.0),
+            Some(Habitat::new("butterflyfish", 30)),
+        ),
+        (Vec3::new(-10.0, 0.0, 20.0), None),
+        (Vec3::new(30.0, 0.0, -5.0), None),
     ];
 
-    for center in reef_centers {
-        spawn_coral_cluster(&mut commands, &mut meshes, &mut materials, center);
+    for (center, habitat) in reefs {
+        let first_coral = spawn_coral_cluster(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut lsystems,
+            &biome,
+            &heightmap,
+            center,
+        );
+        if let Some(habitat) = habitat {
+            commands.entity(first_coral).insert(habitat);
+        }
     }
 }
 
@@ -99,17 +150,22 @@ fn spawn_coral_cluster(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<CoralMaterial>>,
+    lsystems: &mut LSystemMeshes,
+    biome: &BiomeParams,
+    heightmap: &Heightmap,
     center: Vec3,
-) {
-    let coral_count = 15 + (rand::random::<u32>() % 10);
+) -> Entity {
+    // Always at least one, to be home to any habitat
+    let coral_count = biome.scatter(15 + (rand::random::<u32>() % 10)).max(1);
 
+    let mut first_coral = None;
     for _ in 0..coral_count {
         let offset_x = (rand::random::<f32>() - 0.5) * 20.0;
         let offset_z = (rand::random::<f32>() - 0.5) * 20.0;
 
         let x = center.x + offset_x;
         let z = center.z + offset_z;
-        let terrain_y = terrain_height_at(x, z);
+        let terrain_y = heightmap.height_at(x, z);
 
         let species = match rand::random::<u32>() % 4 {
             0 => CoralSpecies::Branching,
@@ -118,43 +174,49 @@ fn spawn_coral_cluster(
             _ => CoralSpecies::Tube,
         };
 
-        spawn_coral(
+        let coral = spawn_coral(
             commands,
             meshes,
             materials,
+            lsystems,
+            biome,
             species,
             Vec3::new(x, terrain_y, z),
         );
+        first_coral.get_or_insert(coral);
     }
+    first_coral.expect("clusters have at least one coral")
 }
 
 fn spawn_coral(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<CoralMaterial>>,
+    lsystems: &mut LSystemMeshes,
+    biome: &BiomeParams,
     species: CoralSpecies,
     position: Vec3,
-) {
+) -> Entity {
     let scale = 0.5 + rand::random::<f32>() * 1.5;
     let phase = rand::random::<f32>() * std::f32::consts::TAU;
 
     let material = materials.add(CoralMaterial {
         data: CoralData {
-            base_color: species.base_color(),
-            tip_color: species.tip_color(),
+            base_color: tint(biome, species.base_color()),
+            tip_color: tint(biome, species.tip_color()),
             glow_intensity: species.glow_intensity(),
             polyp_density: 15.0 + rand::random::<f32>() * 10.0,
+            time: 0.0,
             _padding: 0,
         },
     });
 
-    let (mesh, collider, name, description) = match species {
+    let (shape, collider, name, description) = match species {
         CoralSpecies::Branching => {
-            // Simplified branching coral as elongated spheres
-            let mesh = meshes.add(Capsule3d::new(0.3, 1.5));
+            let lsystem = LSystem::branching_coral().with_seed(rand::random::<u64>() % VARIANTS);
             (
-                mesh,
-                Collider::capsule(0.3 * scale, 1.5 * scale),
+                CoralShape::Grown(lsystems.get_or_build(&lsystem, CORAL_LODS, meshes)),
+                Collider::compound(vec![(Vec3::Y, Quat::IDENTITY, Collider::capsule(0.5, 1.0))]),
                 "Branching Coral",
                 "Delicate branching coral that sways gently in the current.",
             )
@@ -162,18 +224,21 @@ fn spawn_coral(
         CoralSpecies::Brain => {
             let mesh = meshes.add(Sphere::new(0.8));
             (
-                mesh,
+                CoralShape::Primitive(mesh),
                 Collider::sphere(0.8 * scale),
                 "Brain Coral",
                 "A massive brain coral with intricate grooved patterns.",
             )
         }
         CoralSpecies::Fan => {
-            // Fan coral as a flattened cylinder
-            let mesh = meshes.add(Cylinder::new(0.8, 0.1));
+            let lsystem = LSystem::sea_fan().with_seed(rand::random::<u64>() % VARIANTS);
             (
-                mesh,
-                Collider::cylinder(0.8 * scale, 0.1 * scale),
+                CoralShape::Grown(lsystems.get_or_build(&lsystem, CORAL_LODS, meshes)),
+                Collider::compound(vec![(
+                    Vec3::Y * 0.5,
+                    Quat::IDENTITY,
+                    Collider::cuboid(1.0, 1.0, 0.1),
+                )]),
                 "Sea Fan",
                 "A beautiful purple sea fan filtering nutrients from the water.",
             )
@@ -181,7 +246,7 @@ fn spawn_coral(
         CoralSpecies::Tube => {
             let mesh = meshes.add(Cylinder::new(0.2, 1.2));
             (
-                mesh,
+                CoralShape::Primitive(mesh),
                 Collider::cylinder(0.2 * scale, 1.2 * scale),
                 "Tube Coral",
                 "Clusters of tube coral providing shelter for small creatures.",
@@ -189,17 +254,9 @@ fn spawn_coral(
         }
     };
 
-    let rotation = match species {
-        CoralSpecies::Fan => {
-            Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)
-                * Quat::from_rotation_z(rand::random::<f32>() * std::f32::consts::TAU)
-        }
-        _ => Quat::from_rotation_y(rand::random::<f32>() * std::f32::consts::TAU),
-    };
+    let rotation = Quat::from_rotation_y(rand::random::<f32>() * std::f32::consts::TAU);
 
-    commands.spawn((
-        Mesh3d(mesh),
-        MeshMaterial3d(material),
+    let mut coral = commands.spawn((
         Transform::from_translation(position)
             .with_scale(Vec3::splat(scale))
             .with_rotation(rotation),
@@ -210,13 +267,30 @@ fn spawn_coral(
             phase,
             amplitude: 0.02 + rand::random::<f32>() * 0.03,
         },
+        CullAtDistance::new(CORAL_CULL_DISTANCE),
         Name::new(name),
         Hint::new(description),
     ));
+    match shape {
+        CoralShape::Primitive(mesh) => {
+            coral.insert((Mesh3d(mesh), MeshMaterial3d(material)));
+        }
+        CoralShape::Grown(lods) => {
+            coral.insert(Visibility::default()).with_children(|parent| {
+                for lod in lods.bundles(material, CORAL_LOD_DISTANCE) {
+                    parent.spawn(lod);
+                }
+            });
+        }
+    }
+    coral.id()
 }
 
 /// Animate coral swaying in the water current
-fn sway_coral(time: Res<Time>, mut query: Query<(&mut Transform, &CoralSway), With<Coral>>) {
+fn sway_coral(
+    time: Res<GameTime>,
+    mut query: Query<(&mut Transform, &CoralSway), (With<Coral>, Without<Culled>)>,
+) {
     let t = time.elapsed_secs();
 
     for (mut transform, sway) in query.iter_mut() {
@@ -241,11 +315,12 @@ fn spawn_ancient_coral(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    heightmap: Res<Heightmap>,
 ) {
     // Position the ancient coral in a prominent location
     let x = -5.0;
     let z = 5.0;
-    let terrain_y = terrain_height_at(x, z);
+    let terrain_y = heightmap.height_at(x, z);
     let base_pos = Vec3::new(x, terrain_y, z);
 
     // Ancient coral material - deep, mystical coloring
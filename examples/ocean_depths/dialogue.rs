@@ -4,13 +4,8 @@
 
 use bevy::prelude::*;
 use bevy_yarnspinner::prelude::*;
-use examples_common::noise::Perlin;
-
-/// Shared noise seed for consistent terrain across modules
-pub const NOISE_SEED: u32 = 42;
-
-/// Terrain Y offset (seafloor base position)
-pub const TERRAIN_Y_OFFSET: f32 = -5.0;
+use diorama::dialogue_log::{DialogueInProgress, DialogueLog};
+use diorama::kiosk::KioskReset;
 
 /// Component for entities that can trigger dialogue
 #[derive(Component)]
@@ -38,6 +33,35 @@ pub fn cleanup_finished_dialogue_runners(
     }
 }
 
+/// Ends any conversation left running when an unattended kiosk resets, to be cleaned up like one
+/// that finished.
+pub fn end_dialogue_for_next_visitor(
+    _reset: On<KioskReset>,
+    mut dialogue_runners: Query<&mut DialogueRunner>,
+) {
+    for mut dialogue_runner in &mut dialogue_runners {
+        if dialogue_runner.is_running() {
+            dialogue_runner.stop();
+        }
+    }
+}
+
+/// Records each presented line in the session dialogue log
+pub fn log_presented_line(present: On<PresentLine>, mut log: ResMut<DialogueLog>) {
+    let line = &present.line;
+    log.push_line(
+        line.character_name().map(str::to_string),
+        line.text_without_character_name(),
+    );
+}
+
+/// Records each presented option in the session dialogue log
+pub fn log_presented_options(present: On<PresentOptions>, mut log: ResMut<DialogueLog>) {
+    for option in &present.options {
+        log.push_choice(option.line.text_without_character_name());
+    }
+}
+
 /// Returns true if any dialogue is currently running
 pub fn is_dialogue_running(runners: &Query<&DialogueRunner>) -> bool {
     runners.iter().any(|r| r.is_running())
@@ -56,14 +80,6 @@ pub fn start_dialogue(
 
     let mut dialogue_runner = project.create_dialogue_runner(commands);
     dialogue_runner.start_node(node_name);
-    commands.spawn(dialogue_runner);
+    commands.spawn((dialogue_runner, DialogueInProgress));
     true
 }
-
-/// Calculates terrain height at a given (x, z) position using consistent noise
-pub fn terrain_height_at(x: f32, z: f32) -> f32 {
-    let perlin = Perlin::new(NOISE_SEED);
-    let height = perlin.get([x as f64 * 0.03, z as f64 * 0.03]) * 6.0
-        + perlin.get([x as f64 * 0.08, z as f64 * 0.08]) * 1.8;
-    height as f32 + TERRAIN_Y_OFFSET
-}
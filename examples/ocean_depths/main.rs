@@ -14,7 +14,16 @@ use bevy::prelude::*;
 use bevy_yarnspinner::prelude::{YarnFileSource, YarnSpinnerPlugin};
 use bevy_yarnspinner_example_dialogue_view::ExampleYarnSpinnerDialogueViewPlugin;
 use diorama::DioramaPlugin;
-use diorama::player::Player;
+use diorama::ambience::{AmbienceZone, ReverbPreset};
+use diorama::audio::{AmbientLoop, LoopRecipe};
+use diorama::biome::BiomeParams;
+use diorama::game_time::GameTime;
+use diorama::nameplate::NameplateSettings;
+use diorama::physics::WaterVolume;
+use diorama::player::{MovementMode, Player};
+use diorama::sea_state::SeaState;
+use diorama::state::PausableSystems;
+use diorama::underwater::Underwater;
 
 mod atmosphere;
 mod coral;
@@ -27,7 +36,7 @@ mod treasure;
 
 fn main() -> AppExit {
     App::new()
-        .add_plugins(DioramaPlugin)
+        .add_plugins(DioramaPlugin::default())
         .add_plugins(OceanDepthsPlugin)
         .run()
 }
@@ -47,14 +56,63 @@ impl Plugin for OceanDepthsPlugin {
             shipwreck::ShipwreckPlugin,
             materials::OceanMaterialsPlugin,
         ))
-        .add_systems(Startup, setup_player)
-        .add_systems(Update, dialogue::cleanup_finished_dialogue_runners);
+        // Bubbles pop at the surface 20 units up, and the seafloor is around 25 below it
+        .insert_resource(Underwater::new(20.0).with_full_depth(40.0))
+        .init_resource::<SeaState>()
+        .init_resource::<NameplateSettings>()
+        .add_observer(dialogue::log_presented_line)
+        .add_observer(dialogue::log_presented_options)
+        .add_observer(dialogue::end_dialogue_for_next_visitor)
+        .add_systems(Startup, (setup_player, spawn_water))
+        .add_systems(
+            Update,
+            (
+                dialogue::cleanup_finished_dialogue_runners,
+                brew_storms.in_set(PausableSystems),
+            ),
+        );
     }
 }
 
-fn setup_player(mut query: Query<&mut Transform, With<Player>>) {
-    if let Ok(mut transform) = query.single_mut() {
+fn setup_player(mut commands: Commands, mut query: Query<(Entity, &mut Transform), With<Player>>) {
+    if let Ok((player, mut transform)) = query.single_mut() {
         // Start player above the seafloor with a good view
         transform.translation = Vec3::new(0.0, 8.0, 0.0);
+        commands.entity(player).insert(MovementMode::Swim);
     }
 }
+
+/// Seconds between one squall passing overhead and the next
+const SQUALL_PERIOD: f32 = 240.0;
+
+/// With no weather to follow, the sea roughens for a squall every few minutes, in the last part of
+/// each period, as fiercely as the biome allows
+fn brew_storms(time: Res<GameTime>, biome: Res<BiomeParams>, mut sea: ResMut<SeaState>) {
+    let phase = (time.elapsed_secs() / SQUALL_PERIOD).fract();
+    let storminess = biome.storminess(
+        ((phase - 0.7) / 0.1)
+            .clamp(0.0, 1.0)
+            .min((1.0 - phase) / 0.1),
+    );
+    if sea.storminess != storminess {
+        sea.storminess = storminess;
+    }
+}
+
+/// The whole scene is underwater, up to the surface where the bubbles pop.
+fn spawn_water(mut commands: Commands, mut sources: ResMut<Assets<AudioSource>>) {
+    // A deep, slowly surging wash
+    let sea = LoopRecipe::new(12.0).with_brightness(0.02).with_swell(0.5);
+    commands.spawn((
+        Name::new("Ocean"),
+        Transform::from_xyz(0.0, 0.0, 0.0),
+        WaterVolume::new(Vec3::new(75.0, 30.0, 75.0)).with_surface_height(20.0),
+        AmbientLoop::new(sources.add(sea.synthesise(1))).with_volume(0.4),
+    ));
+    // Sounds carry through the water below the surface
+    commands.spawn((
+        Name::new("Open Water Ambience"),
+        Transform::from_xyz(0.0, -5.0, 0.0),
+        AmbienceZone::new(Vec3::new(75.0, 25.0, 75.0)).with_reverb(ReverbPreset::Underwater),
+    ));
+}
@@ -9,6 +9,7 @@ use bevy::prelude::*;
 use bevy::reflect::TypePath;
 use bevy::render::render_resource::{AsBindGroup, ShaderType};
 use bevy::shader::ShaderRef;
+use diorama::game_time::{GameTimeMaterial, GameTimeMaterialPlugin};
 
 pub struct OceanMaterialsPlugin;
 
@@ -22,6 +23,15 @@ impl Plugin for OceanMaterialsPlugin {
             MaterialPlugin::<JellyfishMaterial>::default(),
             MaterialPlugin::<CoralMaterial>::default(),
             MaterialPlugin::<TreasureChestMaterial>::default(),
+        ))
+        .add_plugins((
+            GameTimeMaterialPlugin::<CausticsMaterial>::default(),
+            GameTimeMaterialPlugin::<TurtleShellMaterial>::default(),
+            GameTimeMaterialPlugin::<MossyRockMaterial>::default(),
+            GameTimeMaterialPlugin::<FishScalesMaterial>::default(),
+            GameTimeMaterialPlugin::<JellyfishMaterial>::default(),
+            GameTimeMaterialPlugin::<CoralMaterial>::default(),
+            GameTimeMaterialPlugin::<TreasureChestMaterial>::default(),
         ));
     }
 }
@@ -41,7 +51,8 @@ pub struct CausticsMaterial {
 pub struct CausticsData {
     pub color: Vec4,
     pub speed: f32,
-    #[shader(size(12))]
+    pub time: f32,
+    #[shader(size(8))]
     pub _padding: u32,
 }
 
@@ -51,6 +62,7 @@ impl Default for CausticsMaterial {
             data: CausticsData {
                 color: Vec4::new(0.5, 0.7, 1.0, 1.0),
                 speed: 1.0,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -67,6 +79,12 @@ impl Material for CausticsMaterial {
     }
 }
 
+impl GameTimeMaterial for CausticsMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 // ============================================================================
 // Turtle Shell Material
 // ============================================================================
@@ -84,7 +102,8 @@ pub struct TurtleShellData {
     pub accent_color: Vec4,
     pub age: f32,
     pub roughness: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -96,6 +115,7 @@ impl Default for TurtleShellMaterial {
                 accent_color: Vec4::new(0.4, 0.35, 0.2, 1.0),
                 age: 0.8,
                 roughness: 0.6,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -108,6 +128,12 @@ impl Material for TurtleShellMaterial {
     }
 }
 
+impl GameTimeMaterial for TurtleShellMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 // ============================================================================
 // Mossy Rock Material
 // ============================================================================
@@ -125,7 +151,8 @@ pub struct MossyRockData {
     pub moss_color: Vec4,
     pub moss_amount: f32,
     pub wetness: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -137,6 +164,7 @@ impl Default for MossyRockMaterial {
                 moss_color: Vec4::new(0.2, 0.4, 0.25, 1.0),
                 moss_amount: 0.6,
                 wetness: 0.8,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -149,6 +177,12 @@ impl Material for MossyRockMaterial {
     }
 }
 
+impl GameTimeMaterial for MossyRockMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 // ============================================================================
 // Fish Scales Material
 // ============================================================================
@@ -166,7 +200,8 @@ pub struct FishScalesData {
     pub iridescence_color: Vec4,
     pub scale_size: f32,
     pub shimmer_speed: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -178,6 +213,7 @@ impl Default for FishScalesMaterial {
                 iridescence_color: Vec4::new(0.5, 0.8, 1.0, 1.0),
                 scale_size: 15.0,
                 shimmer_speed: 2.0,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -190,6 +226,12 @@ impl Material for FishScalesMaterial {
     }
 }
 
+impl GameTimeMaterial for FishScalesMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 // ============================================================================
 // Jellyfish Material
 // ============================================================================
@@ -207,7 +249,8 @@ pub struct JellyfishData {
     pub glow_color: Vec4,
     pub pulse_speed: f32,
     pub translucency: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -219,6 +262,7 @@ impl Default for JellyfishMaterial {
                 glow_color: Vec4::new(0.9, 0.5, 1.0, 1.0),
                 pulse_speed: 1.5,
                 translucency: 0.7,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -235,6 +279,12 @@ impl Material for JellyfishMaterial {
     }
 }
 
+impl GameTimeMaterial for JellyfishMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 // ============================================================================
 // Coral Material
 // ============================================================================
@@ -252,7 +302,8 @@ pub struct CoralData {
     pub tip_color: Vec4,
     pub glow_intensity: f32,
     pub polyp_density: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -264,6 +315,7 @@ impl Default for CoralMaterial {
                 tip_color: Vec4::new(1.0, 0.6, 0.7, 1.0),
                 glow_intensity: 0.3,
                 polyp_density: 20.0,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -276,6 +328,12 @@ impl Material for CoralMaterial {
     }
 }
 
+impl GameTimeMaterial for CoralMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
+
 // ============================================================================
 // Treasure Chest Material
 // ============================================================================
@@ -293,7 +351,8 @@ pub struct TreasureChestData {
     pub glow_color: Vec4,
     pub weathering: f32,
     pub magic_intensity: f32,
-    #[shader(size(8))]
+    pub time: f32,
+    #[shader(size(4))]
     pub _padding: u32,
 }
 
@@ -305,6 +364,7 @@ impl Default for TreasureChestMaterial {
                 glow_color: Vec4::new(1.0, 0.85, 0.3, 1.0),
                 weathering: 0.7,
                 magic_intensity: 0.6,
+                time: 0.0,
                 _padding: 0,
             },
         }
@@ -316,3 +376,9 @@ impl Material for TreasureChestMaterial {
         "shaders/treasure_chest.wgsl".into()
     }
 }
+
+impl GameTimeMaterial for TreasureChestMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.data.time = seconds;
+    }
+}
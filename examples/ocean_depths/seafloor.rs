@@ -1,26 +1,44 @@
 //! Seafloor terrain generation using Perlin noise
+//!
+//! The seafloor's shape is inserted as a [`Heightmap`] resource when the plugin's built, so the
+//! shipwreck, treasure and coral can stand on it.
 
 use avian3d::prelude::*;
 use bevy::math::Vec4;
-use bevy::mesh::{Indices, VertexAttributeValues};
 use bevy::prelude::*;
-use examples_common::noise::Perlin;
+use diorama::biome::BiomeParams;
+use diorama::footsteps::{SurfaceKind, SurfaceProperties};
+use diorama::rock::{Rock, RockMeshes};
+use diorama::ruins::Ruins;
+use diorama::sdf_terrain::{SdfChunk, SdfField};
+use diorama::terrain::Heightmap;
 
 use crate::materials::{MossyRockData, MossyRockMaterial};
 
 // Terrain generation constants
 const TERRAIN_SIZE: f32 = 150.0;
-const TERRAIN_SUBDIVISIONS: u32 = 80;
-const TERRAIN_HEIGHT_SCALE: f64 = 6.0;
+const TERRAIN_RESOLUTION: u32 = 80;
+const TERRAIN_HEIGHT_SCALE: f32 = 6.0;
 const TERRAIN_Y_OFFSET: f32 = -5.0;
 const NOISE_SEED: u32 = 42;
 const ROCK_COUNT: u32 = 30;
+/// Distinct boulder shapes, shared between rocks via the mesh cache.
+const ROCK_VARIANTS: u64 = 8;
 
 pub struct SeafloorPlugin;
 
 impl Plugin for SeafloorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_seafloor);
+        // Multi-octave noise for natural terrain
+        app.insert_resource(
+            Heightmap::new(NOISE_SEED)
+                .with_size(TERRAIN_SIZE, TERRAIN_RESOLUTION)
+                .with_base(TERRAIN_Y_OFFSET)
+                .with_layer(0.03, TERRAIN_HEIGHT_SCALE)
+                .with_layer(0.08, TERRAIN_HEIGHT_SCALE * 0.3)
+                .with_layer(0.15, TERRAIN_HEIGHT_SCALE * 0.1),
+        )
+        .add_systems(Startup, spawn_seafloor);
     }
 }
 
@@ -32,92 +50,111 @@ fn spawn_seafloor(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut rock_materials: ResMut<Assets<MossyRockMaterial>>,
+    mut rocks: ResMut<RockMeshes>,
+    biome: Res<BiomeParams>,
+    heightmap: Res<Heightmap>,
 ) {
-    let perlin = Perlin::new(NOISE_SEED);
-
     // Create seafloor mesh with undulating terrain
-    let mut mesh = Plane3d::default()
-        .mesh()
-        .size(TERRAIN_SIZE, TERRAIN_SIZE)
-        .subdivisions(TERRAIN_SUBDIVISIONS)
-        .build();
-
-    if let Some(VertexAttributeValues::Float32x3(positions)) =
-        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
-    {
-        for pos in positions.iter_mut() {
-            let x = pos[0] as f64;
-            let z = pos[2] as f64;
-
-            // Multi-octave noise for natural terrain
-            let y = perlin.get([x * 0.03, z * 0.03]) * TERRAIN_HEIGHT_SCALE
-                + perlin.get([x * 0.08, z * 0.08]) * (TERRAIN_HEIGHT_SCALE * 0.3)
-                + perlin.get([x * 0.15, z * 0.15]) * (TERRAIN_HEIGHT_SCALE * 0.1);
-
-            pos[1] = y as f32;
-        }
-    }
-
-    mesh.compute_normals();
-
-    // Create trimesh collider from mesh data
-    let vertex_positions: Vec<Vec3> = mesh
-        .attribute(Mesh::ATTRIBUTE_POSITION)
-        .and_then(|attr| match attr {
-            VertexAttributeValues::Float32x3(positions) => {
-                Some(positions.iter().map(|p| Vec3::from_array(*p)).collect())
-            }
-            _ => None,
-        })
-        .unwrap_or_default();
-
-    let indices: Vec<[u32; 3]> = mesh
-        .indices()
-        .map(|indices| match indices {
-            Indices::U32(indices) => indices.chunks(3).map(|c| [c[0], c[1], c[2]]).collect(),
-            Indices::U16(indices) => indices
-                .chunks(3)
-                .map(|c| [c[0] as u32, c[1] as u32, c[2] as u32])
-                .collect(),
-        })
-        .unwrap_or_default();
-
-    let collider = Collider::trimesh(vertex_positions, indices);
+    let (mesh, collider) = heightmap.build();
 
     // Sandy seafloor material
     commands.spawn((
         Mesh3d(meshes.add(mesh)),
         MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgb(0.76, 0.70, 0.50), // Sandy beige
+            base_color: biome.tint(Color::srgb(0.76, 0.70, 0.50)), // Sandy beige
             perceptual_roughness: 0.95,
             metallic: 0.0,
             ..default()
         })),
-        Transform::from_xyz(0.0, TERRAIN_Y_OFFSET, 0.0),
+        Transform::IDENTITY,
         RigidBody::Static,
         collider,
+        SurfaceProperties::new(SurfaceKind::Sand),
         Seafloor,
         Name::new("Seafloor"),
     ));
 
     // Spawn scattered rocks
-    spawn_rocks(&mut commands, &mut meshes, &mut rock_materials, &perlin);
+    spawn_rocks(
+        &mut commands,
+        &mut meshes,
+        &mut rock_materials,
+        &mut rocks,
+        &heightmap,
+    );
+
+    // Grotto: a rocky mound with a tunnel and a chamber, which a heightmap can't express
+    let (x, z) = (35.0, -30.0);
+    let center = Vec3::new(x, heightmap.height_at(x, z), z);
+    let grotto = SdfField::sphere(center, 6.0)
+        .smooth_union(
+            SdfField::sphere(center + Vec3::new(4.0, -1.0, 3.0), 5.0),
+            2.0,
+        )
+        .smooth_union(
+            SdfField::sphere(center + Vec3::new(-4.0, -1.0, -2.0), 4.5),
+            2.0,
+        )
+        .smooth_subtract(
+            SdfField::capsule(
+                center + Vec3::new(-11.0, 1.0, 0.0),
+                center + Vec3::new(11.0, 1.5, 1.0),
+                1.8,
+            ),
+            1.0,
+        )
+        .smooth_subtract(
+            SdfField::sphere(center + Vec3::new(0.0, 1.5, 0.0), 3.5),
+            1.0,
+        );
+    let grotto_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.35, 0.4, 0.38),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+    for (transform, chunk) in SdfChunk::grid(
+        grotto,
+        center - Vec3::new(12.0, 4.0, 12.0),
+        center + Vec3::new(12.0, 8.0, 12.0),
+        8.0,
+        0.5,
+    ) {
+        commands.spawn((
+            Name::new("Grotto"),
+            transform,
+            chunk,
+            MeshMaterial3d(grotto_material.clone()),
+        ));
+    }
+
+    // Sunken temple, half collapsed and overgrown
+    let (x, z) = (-30.0, 25.0);
+    let terrain_y = heightmap.height_at(x, z);
+    let ruin_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.45, 0.5, 0.45),
+        perceptual_roughness: 0.95,
+        ..default()
+    });
+    Ruins::temple(NOISE_SEED.into()).with_damage(0.7).spawn(
+        &mut commands,
+        &mut meshes,
+        ruin_material,
+        Transform::from_xyz(x, terrain_y - 0.2, z).with_rotation(Quat::from_rotation_y(0.4)),
+    );
 }
 
 fn spawn_rocks(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<MossyRockMaterial>>,
-    perlin: &Perlin,
+    rocks: &mut RockMeshes,
+    heightmap: &Heightmap,
 ) {
-    let rock_mesh = meshes.add(Sphere::new(1.0));
-
     for _ in 0..ROCK_COUNT {
         let x = (rand::random::<f32>() - 0.5) * 120.0;
         let z = (rand::random::<f32>() - 0.5) * 120.0;
 
-        let terrain_y = perlin.get([x as f64 * 0.03, z as f64 * 0.03]) * TERRAIN_HEIGHT_SCALE
-            + perlin.get([x as f64 * 0.08, z as f64 * 0.08]) * (TERRAIN_HEIGHT_SCALE * 0.3);
+        let terrain_y = heightmap.height_at(x, z);
 
         let scale = 0.5 + rand::random::<f32>() * 2.0;
 
@@ -131,26 +168,28 @@ fn spawn_rocks(
                 moss_color: Vec4::new(0.15 + rock_variation, 0.4, 0.2, 1.0),
                 moss_amount,
                 wetness: 0.7 + rand::random::<f32>() * 0.3,
+                time: 0.0,
                 _padding: 0,
             },
         });
 
+        let rock = rocks.get_or_build(
+            &Rock::boulder().with_seed(rand::random::<u64>() % ROCK_VARIANTS),
+            meshes,
+        );
+
         commands.spawn((
-            Mesh3d(rock_mesh.clone()),
+            Mesh3d(rock.mesh),
             MeshMaterial3d(rock_material),
-            Transform::from_xyz(x, terrain_y as f32 + TERRAIN_Y_OFFSET + scale * 0.3, z)
-                .with_scale(Vec3::new(
-                    scale * (0.8 + rand::random::<f32>() * 0.4),
-                    scale * (0.5 + rand::random::<f32>() * 0.5),
-                    scale * (0.8 + rand::random::<f32>() * 0.4),
-                ))
+            Transform::from_xyz(x, terrain_y + scale * 0.3, z)
+                .with_scale(Vec3::splat(scale))
                 .with_rotation(Quat::from_euler(
                     EulerRot::XYZ,
                     rand::random::<f32>() * 0.3,
                     rand::random::<f32>() * std::f32::consts::TAU,
                     rand::random::<f32>() * 0.3,
                 )),
-            Collider::sphere(scale),
+            rock.collider,
             RigidBody::Static,
             Name::new("Rock"),
         ));
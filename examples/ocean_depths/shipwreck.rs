@@ -7,9 +7,17 @@ use avian3d::prelude::*;
 use bevy::picking::events::{Click, Pointer};
 use bevy::prelude::*;
 use bevy_yarnspinner::prelude::*;
+use diorama::ambience::{AmbienceZone, ReverbPreset};
+use diorama::ambient_volume::AmbientLightVolume;
+use diorama::audio::{LoopRecipe, SpatialAudioEmitter};
+use diorama::chest::{Chest, LootTable};
+use diorama::inspection::HiddenDetail;
+use diorama::physics::Buoyancy;
 use diorama::picking::Hint;
+use diorama::terrain::Heightmap;
 
-use crate::dialogue::{OceanDialogue, start_dialogue, terrain_height_at};
+use crate::dialogue::{OceanDialogue, start_dialogue};
+use crate::treasure::CAPTAINS_KEY;
 
 pub struct ShipwreckPlugin;
 
@@ -23,11 +31,13 @@ fn spawn_shipwreck(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut sources: ResMut<Assets<AudioSource>>,
+    heightmap: Res<Heightmap>,
 ) {
     // Position the shipwreck at a dramatic angle
     let wreck_x = -30.0;
     let wreck_z = 25.0;
-    let terrain_y = terrain_height_at(wreck_x, wreck_z);
+    let terrain_y = heightmap.height_at(wreck_x, wreck_z);
     let wreck_pos = Vec3::new(wreck_x, terrain_y + 1.0, wreck_z);
 
     // Weathered wood material
@@ -67,6 +77,71 @@ fn spawn_shipwreck(
         Name::new("Shipwreck Hull"),
     ));
 
+    // Low groans from the hull as the current works at it
+    let groan = LoopRecipe::new(9.0)
+        .with_brightness(0.01)
+        .with_swell(0.9)
+        .with_hum(48.0, 0.3);
+    commands.spawn((
+        Name::new("Shipwreck Groan"),
+        Transform::from_translation(wreck_pos),
+        SpatialAudioEmitter::new(sources.add(groan.synthesise(2)))
+            .with_volume(0.7)
+            .with_range(4.0, 30.0)
+            .with_caption("hull groaning"),
+    ));
+
+    // Keel, ribs and deck beams inside the hull, seen through it while inspecting
+    let beam = meshes.add(Cuboid::new(0.2, 1.0, 0.2));
+    commands
+        .spawn((
+            Name::new("Shipwreck Framing"),
+            Transform::from_translation(wreck_pos).with_rotation(ship_rotation),
+            HiddenDetail::new(),
+        ))
+        .with_children(|framing| {
+            framing.spawn((
+                Mesh3d(meshes.add(Cuboid::new(11.6, 0.25, 0.35))),
+                MeshMaterial3d(wood_material.clone()),
+                Transform::from_xyz(0.0, -1.35, 0.0),
+            ));
+            for i in 0..9 {
+                let x = -5.2 + i as f32 * 1.3;
+                for side in [-1.0, 1.0] {
+                    framing.spawn((
+                        Mesh3d(beam.clone()),
+                        MeshMaterial3d(wood_material.clone()),
+                        Transform::from_xyz(x, -0.1, side * 1.8)
+                            .with_scale(Vec3::new(1.0, 2.6, 1.0)),
+                    ));
+                }
+                framing.spawn((
+                    Mesh3d(beam.clone()),
+                    MeshMaterial3d(wood_material.clone()),
+                    Transform::from_xyz(x, 1.3, 0.0)
+                        .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2))
+                        .with_scale(Vec3::new(1.0, 3.6, 1.0)),
+                ));
+            }
+        });
+
+    // Murkier light around the hull so the wreck feels enclosed up close
+    commands.spawn((
+        Name::new("Shipwreck Ambient Light"),
+        Transform::from_translation(wreck_pos).with_rotation(ship_rotation),
+        AmbientLightVolume::new(Vec3::new(8.0, 3.0, 4.0), Color::srgb(0.05, 0.15, 0.2), 80.0)
+            .with_blend_distance(3.0),
+    ));
+
+    // Sounds echo off the hull up close, over the open water's reverb
+    commands.spawn((
+        Name::new("Shipwreck Ambience"),
+        Transform::from_translation(wreck_pos).with_rotation(ship_rotation),
+        AmbienceZone::new(Vec3::new(10.0, 5.0, 7.0))
+            .with_priority(1)
+            .with_reverb(ReverbPreset::Cave),
+    ));
+
     // Bow section (pointed front)
     commands.spawn((
         Mesh3d(meshes.add(Cuboid::new(3.0, 2.5, 3.0))),
@@ -137,7 +212,7 @@ fn spawn_shipwreck(
 
     for (i, offset) in crate_positions.iter().enumerate() {
         let crate_pos = wreck_pos + *offset;
-        let crate_terrain_y = terrain_height_at(crate_pos.x, crate_pos.z);
+        let crate_terrain_y = heightmap.height_at(crate_pos.x, crate_pos.z);
 
         commands.spawn((
             Mesh3d(meshes.add(Cuboid::new(1.0, 0.8, 0.8))),
@@ -150,6 +225,55 @@ fn spawn_shipwreck(
         ));
     }
 
+    // Loose barrels, which float free of the wreck and bob at the surface
+    for i in 0..3 {
+        let offset = ship_rotation * Vec3::new(-2.0 + i as f32 * 2.5, 3.0, -1.0);
+        commands.spawn((
+            Mesh3d(meshes.add(Cylinder::new(0.4, 1.0))),
+            MeshMaterial3d(wood_material.clone()),
+            Transform::from_translation(wreck_pos + offset)
+                .with_rotation(Quat::from_rotation_x(0.4 * i as f32)),
+            Collider::cylinder(0.4, 1.0),
+            RigidBody::Dynamic,
+            Name::new(format!("Loose Barrel {}", i + 1)),
+        ));
+    }
+
+    // The captain's strongbox, opened with the key from the treasure chest
+    let strongbox_pos = wreck_pos + Vec3::new(1.0, 0.0, -7.5);
+    let strongbox_y = heightmap.height_at(strongbox_pos.x, strongbox_pos.z);
+    let strongbox = Chest::new(
+        LootTable::new(1)
+            .with_guaranteed("Love Letters", 1)
+            .with_entry("Gold Coin", 1.0, 10..=20),
+    )
+    .locked_with(CAPTAINS_KEY)
+    .with_size(Vec3::new(1.0, 0.6, 0.7))
+    .with_glow(Color::srgb(0.6, 0.8, 1.0), 20_000.0)
+    .spawn(
+        &mut commands,
+        &mut meshes,
+        rust_material.clone(),
+        barnacle_material.clone(),
+        Transform::from_xyz(strongbox_pos.x, strongbox_y + 0.3, strongbox_pos.z)
+            .with_rotation(Quat::from_rotation_y(0.5)),
+    );
+    commands.entity(strongbox).insert((
+        Name::new("Captain's Strongbox"),
+        Hint::new("🔒 The captain's strongbox. Press E or click to open it, if you have the key"),
+    ));
+
+    // A sealed chest of ballast, which only just sinks
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.8, 0.6, 0.6))),
+        MeshMaterial3d(barnacle_material.clone()),
+        Transform::from_translation(wreck_pos + ship_rotation * Vec3::new(1.0, 3.5, 1.0)),
+        Collider::cuboid(0.8, 0.6, 0.6),
+        RigidBody::Dynamic,
+        Buoyancy(0.9),
+        Name::new("Ballast Chest"),
+    ));
+
     // Cannon
     commands.spawn((
         Mesh3d(meshes.add(Cylinder::new(0.25, 2.0))),
@@ -185,7 +309,13 @@ fn spawn_shipwreck(
     spawn_octopus(&mut commands, &mut meshes, &mut materials, wreck_pos);
 
     // Spawn giant clam nearby
-    spawn_giant_clam(&mut commands, &mut meshes, &mut materials, wreck_pos);
+    spawn_giant_clam(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &heightmap,
+        wreck_pos,
+    );
 }
 
 fn spawn_octopus(
@@ -238,11 +368,12 @@ fn spawn_giant_clam(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
+    heightmap: &Heightmap,
     wreck_pos: Vec3,
 ) {
     let clam_x = wreck_pos.x + 10.0;
     let clam_z = wreck_pos.z - 8.0;
-    let terrain_y = terrain_height_at(clam_x, clam_z);
+    let terrain_y = heightmap.height_at(clam_x, clam_z);
     let clam_pos = Vec3::new(clam_x, terrain_y + 0.5, clam_z);
 
     // Shell exterior
@@ -1,16 +1,19 @@
 //! Hidden treasure discovery system
 //!
-//! Spawns a treasure chest that can be discovered by the player,
+//! Spawns a treasure chest that can be discovered and opened by the player,
 //! with glowing particles to draw attention.
 
-use avian3d::prelude::*;
 use bevy::math::Vec4;
-use bevy::picking::events::{Click, Pointer};
 use bevy::prelude::*;
 use bevy_yarnspinner::prelude::*;
+use diorama::chest::{Chest, ChestOpened, LootTable};
+use diorama::game_time::GameTime;
+use diorama::interact::Interactable;
+use diorama::particles::{EmitterShape, LifeGradient, ParticleEmitter};
 use diorama::picking::Hint;
+use diorama::terrain::Heightmap;
 
-use crate::dialogue::{OceanDialogue, start_dialogue, terrain_height_at};
+use crate::dialogue::{OceanDialogue, start_dialogue};
 use crate::materials::{TreasureChestData, TreasureChestMaterial};
 
 pub struct TreasurePlugin;
@@ -18,21 +21,17 @@ pub struct TreasurePlugin;
 impl Plugin for TreasurePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, spawn_treasure)
-            .add_systems(Update, (animate_treasure_glow, animate_gold_particles));
+            .add_systems(Update, animate_treasure_glow);
     }
 }
 
+/// Opens the captain's strongbox in the shipwreck.
+pub const CAPTAINS_KEY: &str = "Captain's Key";
+
 /// Treasure chest component
 #[derive(Component)]
 pub struct TreasureChest;
 
-/// Glowing particle near treasure
-#[derive(Component)]
-pub struct GoldParticle {
-    pub base_pos: Vec3,
-    pub phase: f32,
-}
-
 /// The treasure glow effect
 #[derive(Component)]
 pub struct TreasureGlow;
@@ -42,17 +41,14 @@ fn spawn_treasure(
     mut meshes: ResMut<Assets<Mesh>>,
     mut std_materials: ResMut<Assets<StandardMaterial>>,
     mut chest_materials: ResMut<Assets<TreasureChestMaterial>>,
+    heightmap: Res<Heightmap>,
 ) {
     // Find a suitable location on the seafloor
     let x = 25.0;
     let z = -20.0;
-    let terrain_y = terrain_height_at(x, z);
+    let terrain_y = heightmap.height_at(x, z);
     let chest_pos = Vec3::new(x, terrain_y + 0.5, z);
 
-    // Treasure chest (simplified as a box)
-    let chest_mesh = meshes.add(Cuboid::new(1.5, 1.0, 1.0));
-    let chest_lid_mesh = meshes.add(Cuboid::new(1.6, 0.3, 1.1));
-
     // Use custom shader material for the chest
     let chest_material = chest_materials.add(TreasureChestMaterial {
         data: TreasureChestData {
@@ -60,6 +56,7 @@ fn spawn_treasure(
             glow_color: Vec4::new(1.0, 0.85, 0.3, 1.0),
             weathering: 0.75,
             magic_intensity: 0.7,
+            time: 0.0,
             _padding: 0,
         },
     });
@@ -72,61 +69,52 @@ fn spawn_treasure(
         ..default()
     });
 
-    let mut chest = commands.spawn((
-        Mesh3d(chest_mesh),
-        MeshMaterial3d(chest_material),
+    // The captain's key is inside, for the strongbox in the shipwreck
+    let loot = LootTable::new(3)
+        .with_guaranteed(CAPTAINS_KEY, 1)
+        .with_entry("Gold Coin", 4.0, 5..=15)
+        .with_entry("Silver Doubloon", 2.0, 1..=3)
+        .with_entry("Emerald", 1.0, 1..=1);
+    let chest = Chest::new(loot).with_size(Vec3::new(1.5, 1.0, 1.0)).spawn(
+        &mut commands,
+        &mut meshes,
+        chest_material,
+        gold_trim_material,
         Transform::from_translation(chest_pos),
-        Collider::cuboid(1.5, 1.0, 1.0),
-        RigidBody::Static,
-        TreasureChest,
-        Name::new("Treasure Chest"),
-        Hint::new("💰 An ancient treasure chest! Click to discover its secrets..."),
-        OceanDialogue {
-            node_name: "TreasureChest".to_string(),
-        },
-    ));
-
-    chest.observe(on_treasure_click);
-
-    chest.with_children(|parent| {
-        // Chest lid
-        parent.spawn((
-            Mesh3d(chest_lid_mesh),
-            MeshMaterial3d(gold_trim_material.clone()),
-            Transform::from_xyz(0.0, 0.65, 0.0),
-        ));
-    });
-
-    // Spawn gold particles floating around the treasure
-    let particle_mesh = meshes.add(Sphere::new(0.1));
-    let particle_material = std_materials.add(StandardMaterial {
-        base_color: Color::srgb(1.0, 0.85, 0.3),
-        emissive: Color::srgb(0.5, 0.4, 0.1).into(),
-        ..default()
-    });
-
-    for i in 0..12 {
-        let angle = (i as f32 / 12.0) * std::f32::consts::TAU;
-        let radius = 2.0 + rand::random::<f32>() * 1.5;
-        let particle_pos = chest_pos
-            + Vec3::new(
-                angle.cos() * radius,
-                1.0 + rand::random::<f32>() * 2.0,
-                angle.sin() * radius,
-            );
-
-        commands.spawn((
-            Mesh3d(particle_mesh.clone()),
-            MeshMaterial3d(particle_material.clone()),
-            Transform::from_translation(particle_pos)
-                .with_scale(Vec3::splat(0.3 + rand::random::<f32>() * 0.4)),
-            GoldParticle {
-                base_pos: particle_pos,
-                phase: rand::random::<f32>() * std::f32::consts::TAU,
+    );
+    commands
+        .entity(chest)
+        .insert((
+            TreasureChest,
+            Name::new("Treasure Chest"),
+            Hint::new("💰 An ancient treasure chest! Hold E or click to pry it open..."),
+            Interactable::hold(1.5).with_range(6.0),
+            OceanDialogue {
+                node_name: "TreasureChest".to_string(),
             },
-            Name::new("Gold Particle"),
-        ));
-    }
+        ))
+        .observe(on_treasure_opened);
+
+    // Gold motes floating around the treasure
+    commands.spawn((
+        Name::new("Gold Particles"),
+        Transform::from_translation(chest_pos + Vec3::Y * 2.0),
+        ParticleEmitter::default()
+            .with_rate(3.0)
+            .with_max_particles(16)
+            .with_shape(EmitterShape::Box {
+                half_extents: Vec3::new(3.5, 1.0, 3.5),
+            })
+            .with_lifetime(4.0, 0.25)
+            .with_velocity(Vec3::Y * 0.1, Vec3::new(0.1, 0.15, 0.1))
+            .with_wobble(0.3, 0.2)
+            .with_size(0.1, 0.4)
+            .with_color_over_life(LifeGradient::fade_in_out(
+                LinearRgba::rgb(2.0, 1.5, 0.4),
+                0.25,
+            ))
+            .with_prewarm(),
+    ));
 
     // Central glow effect
     let glow_mesh = meshes.add(Sphere::new(0.5));
@@ -159,13 +147,20 @@ fn spawn_treasure(
     ));
 
     // Spawn some scattered gold coins
-    spawn_gold_coins(&mut commands, &mut meshes, &mut std_materials, chest_pos);
+    spawn_gold_coins(
+        &mut commands,
+        &mut meshes,
+        &mut std_materials,
+        &heightmap,
+        chest_pos,
+    );
 }
 
 fn spawn_gold_coins(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
+    heightmap: &Heightmap,
     chest_pos: Vec3,
 ) {
     let coin_mesh = meshes.add(Cylinder::new(0.15, 0.03));
@@ -182,7 +177,7 @@ fn spawn_gold_coins(
 
         let x = chest_pos.x + offset_x;
         let z = chest_pos.z + offset_z;
-        let terrain_y = terrain_height_at(x, z);
+        let terrain_y = heightmap.height_at(x, z);
 
         commands.spawn((
             Mesh3d(coin_mesh.clone()),
@@ -198,7 +193,10 @@ fn spawn_gold_coins(
     }
 }
 
-fn animate_treasure_glow(time: Res<Time>, mut query: Query<&mut Transform, With<TreasureGlow>>) {
+fn animate_treasure_glow(
+    time: Res<GameTime>,
+    mut query: Query<&mut Transform, With<TreasureGlow>>,
+) {
     let t = time.elapsed_secs();
 
     for mut transform in query.iter_mut() {
@@ -208,34 +206,18 @@ fn animate_treasure_glow(time: Res<Time>, mut query: Query<&mut Transform, With<
     }
 }
 
-fn animate_gold_particles(time: Res<Time>, mut query: Query<(&mut Transform, &GoldParticle)>) {
-    let t = time.elapsed_secs();
-
-    for (mut transform, particle) in query.iter_mut() {
-        // Floating animation
-        let y_offset = (t * 1.5 + particle.phase).sin() * 0.5;
-        let x_offset = (t * 0.7 + particle.phase).cos() * 0.3;
-        let z_offset = (t * 0.9 + particle.phase * 1.3).sin() * 0.3;
-
-        transform.translation = particle.base_pos + Vec3::new(x_offset, y_offset, z_offset);
-
-        // Slow rotation
-        transform.rotate_y(0.01);
-    }
-}
-
 // ============================================================================
-// Click handler for treasure
+// Interaction handler for treasure
 // ============================================================================
 
-fn on_treasure_click(
-    click: On<Pointer<Click>>,
+fn on_treasure_opened(
+    opened: On<ChestOpened>,
     mut commands: Commands,
     project: Res<YarnProject>,
     dialogue_query: Query<&OceanDialogue>,
     existing_runners: Query<&DialogueRunner>,
 ) {
-    if let Ok(treasure_dialogue) = dialogue_query.get(click.event().entity) {
+    if let Ok(treasure_dialogue) = dialogue_query.get(opened.entity) {
         start_dialogue(
             &mut commands,
             &project,
@@ -14,14 +14,16 @@
 use avian3d::prelude::*;
 use bevy::color::palettes::tailwind;
 use bevy::prelude::*;
+use diorama::particles::{LifeGradient, ParticleEmitter};
+use diorama::physics::{TriggerEnter, TriggerVolume};
 
 /// Radius of collectible gem spheres.
 const COLLECTIBLE_RADIUS: f32 = 0.3;
 
-/// Distance at which the player can collect a gem.
-const COLLECTION_DISTANCE: f32 = 1.0;
+/// Radius of the pickup volume around each gem, a little larger than the gem itself.
+const COLLECTION_RADIUS: f32 = 0.6;
 
-/// How long collection effect particles live before despawning.
+/// How long collection sparkles live.
 const PARTICLE_LIFETIME_SECS: f32 = 1.0;
 
 /// Marker component for collectible items that award points when collected.
@@ -86,8 +88,8 @@ pub fn spawn_collectibles(
             },
             RotatingAnimation { speed: 1.5 },
             RigidBody::Static,
-            Collider::sphere(COLLECTIBLE_RADIUS),
-            Sensor, // Trigger collider for pickup detection
+            Collider::sphere(COLLECTION_RADIUS),
+            TriggerVolume::default(),
             Mesh3d(gem_mesh.clone()),
             MeshMaterial3d(gem_material.clone()),
             Transform::from_translation(*position),
@@ -115,103 +117,37 @@ pub fn animate_collectibles(
     }
 }
 
-/// Handles collision detection and pickup of collectibles by the player.
-pub fn handle_collectible_pickup(
+/// Picks up a collectible when the player touches it.
+pub fn collect_gem(
+    enter: On<TriggerEnter>,
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    collectible_query: Query<(Entity, &Collectible, &Transform), Without<diorama::player::Player>>,
-    player_transform: Single<&Transform, With<diorama::player::Player>>,
+    collectibles: Query<(&Collectible, &Transform)>,
     mut game_state: ResMut<crate::GameState>,
 ) {
-    for (entity, collectible, collectible_transform) in collectible_query.iter() {
-        let distance = player_transform
-            .translation
-            .distance(collectible_transform.translation);
-
-        if distance < COLLECTION_DISTANCE {
-            game_state.gems_collected += collectible.value;
-
-            // Spawn visual feedback particles
-            spawn_collection_effect(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                collectible_transform.translation,
-            );
-
-            commands.entity(entity).despawn();
-
-            println!("Collected gem! Total: {}", game_state.gems_collected);
-        }
-    }
-}
-
-/// Spawns particle effect when a collectible is picked up.
-fn spawn_collection_effect(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    position: Vec3,
-) {
-    let particle_material = materials.add(StandardMaterial {
-        base_color: tailwind::YELLOW_400.into(),
-        emissive: LinearRgba::from(tailwind::YELLOW_500) * 3.0,
-        unlit: true,
-        ..default()
-    });
+    let Ok((collectible, transform)) = collectibles.get(enter.entity) else {
+        return;
+    };
+    game_state.gems_collected += collectible.value;
 
-    let particle_mesh = meshes.add(Mesh::from(Sphere::new(0.05)));
+    // Spawn visual feedback particles
+    spawn_collection_effect(&mut commands, transform.translation);
 
-    // Spawn particles in a radial pattern
-    for i in 0..8 {
-        let angle = (i as f32) * std::f32::consts::PI / 4.0;
-        let offset = Vec3::new(angle.cos(), 0.5, angle.sin()) * 0.5;
+    commands.entity(enter.entity).despawn();
 
-        commands.spawn((
-            Name::new("Collection Particle"),
-            CollectionParticle {
-                lifetime: Timer::from_seconds(PARTICLE_LIFETIME_SECS, TimerMode::Once),
-                initial_velocity: offset * 2.0,
-            },
-            Mesh3d(particle_mesh.clone()),
-            MeshMaterial3d(particle_material.clone()),
-            Transform::from_translation(position + offset * 0.1),
-        ));
-    }
-}
-
-/// Component for short-lived particle effects with physics.
-#[derive(Component)]
-pub struct CollectionParticle {
-    /// Time remaining before particle despawns.
-    lifetime: Timer,
-    /// Initial outward velocity of the particle.
-    initial_velocity: Vec3,
+    println!("Collected gem! Total: {}", game_state.gems_collected);
 }
 
-/// Animates collection particles with gravity and fade-out effects.
-pub fn animate_collection_particles(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut particle_query: Query<(Entity, &mut Transform, &mut CollectionParticle)>,
-) {
-    for (entity, mut transform, mut particle) in particle_query.iter_mut() {
-        particle.lifetime.tick(time.delta());
-
-        if particle.lifetime.is_finished() {
-            commands.entity(entity).despawn();
-        } else {
-            // Apply velocity with gravity
-            let progress = particle.lifetime.elapsed_secs();
-            let gravity = Vec3::new(0.0, -5.0, 0.0);
-            let velocity = particle.initial_velocity + gravity * progress;
-
-            transform.translation += velocity * time.delta_secs();
-
-            // Fade out by scaling down over lifetime
-            let scale = 1.0 - particle.lifetime.fraction();
-            transform.scale = Vec3::splat(scale);
-        }
-    }
+/// Spawns a burst of sparkles where a collectible was picked up.
+fn spawn_collection_effect(commands: &mut Commands, position: Vec3) {
+    commands.spawn((
+        Name::new("Collection Sparkles"),
+        ParticleEmitter::sparkles()
+            .with_burst(8)
+            .with_lifetime(PARTICLE_LIFETIME_SECS, 0.0)
+            .with_velocity(Vec3::Y * 0.5, Vec3::new(1.0, 0.3, 1.0))
+            .with_color_over_life(LifeGradient::fade_out(
+                LinearRgba::from(tailwind::YELLOW_500) * 3.0,
+            )),
+        Transform::from_translation(position),
+    ));
 }
@@ -43,6 +43,7 @@
 use avian3d::prelude::*;
 use bevy::color::palettes::tailwind;
 use bevy::prelude::*;
+use diorama::footsteps::{SurfaceKind, SurfaceProperties};
 
 /// Platform types with associated visual properties.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -241,6 +242,7 @@ impl Spawnable for Platform {
             )))),
             MeshMaterial3d(material),
             Transform::from_translation(self.position),
+            SurfaceProperties::new(SurfaceKind::Wood),
         ));
     }
 }
@@ -2,8 +2,16 @@
 //! collectibles, moving platforms, and level design.
 
 use bevy::color::palettes::tailwind;
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
 use bevy::prelude::*;
 use diorama::DioramaPlugin;
+use diorama::cascades::ShadowCascadeBounds;
+use diorama::environment::{DayNightCycle, Sun};
+use diorama::kiosk::KioskReset;
+use diorama::plaque::StatsPlaque;
+use diorama::save::SaveGame;
+use diorama::state::PausableSystems;
+use serde::{Deserialize, Serialize};
 
 mod collectibles;
 mod game_ui;
@@ -13,7 +21,7 @@ mod platforms;
 
 fn main() -> AppExit {
     App::new()
-        .add_plugins(DioramaPlugin)
+        .add_plugins(DioramaPlugin::default())
         .add_plugins(PlatformerPlugin)
         .run()
 }
@@ -24,7 +32,19 @@ pub struct PlatformerPlugin;
 impl Plugin for PlatformerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(GameState::new())
+            // Reached checkpoints carry over between runs, saved as they're reached
+            .insert_resource(
+                SaveGame::new("platformer.save.ron")
+                    .with_load_on_startup()
+                    .with_autosave_on_checkpoint()
+                    .with_slots(3)
+                    .with_resource::<GameState>(),
+            )
+            .register_diagnostic(Diagnostic::new(GEMS_COLLECTED))
             .add_plugins(game_ui::GameUIPlugin)
+            .add_observer(collectibles::collect_gem)
+            .add_observer(movement::reach_checkpoint)
+            .add_observer(reset_for_next_visitor)
             .add_systems(
                 Startup,
                 (
@@ -34,6 +54,7 @@ impl Plugin for PlatformerPlugin {
                     level::spawn_level_geometry,
                     platforms::spawn_moving_platforms,
                     collectibles::spawn_collectibles,
+                    movement::spawn_checkpoints,
                     movement::spawn_player,
                 )
                     .chain(),
@@ -43,18 +64,26 @@ impl Plugin for PlatformerPlugin {
                 (
                     platforms::animate_moving_platforms,
                     collectibles::animate_collectibles,
-                    collectibles::handle_collectible_pickup,
-                    collectibles::animate_collection_particles,
                     movement::check_player_respawn,
-                ),
+                    measure_gems_collected,
+                )
+                    .in_set(PausableSystems),
             );
     }
 }
 
+/// Graphed in the performance UI alongside the built-in diagnostics.
+const GEMS_COLLECTED: DiagnosticPath = DiagnosticPath::const_new("scene/gems_collected");
+
+fn measure_gems_collected(mut diagnostics: Diagnostics, game_state: Res<GameState>) {
+    diagnostics.add_measurement(&GEMS_COLLECTED, || f64::from(game_state.gems_collected));
+}
+
 /// Game state tracking collectibles and player progress.
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Serialize, Deserialize)]
 pub struct GameState {
-    /// Total number of gems collected by the player.
+    /// Total number of gems collected by the player. Not saved, as gems respawn every run.
+    #[serde(skip)]
     pub gems_collected: u32,
     /// Last checkpoint position for respawning.
     pub current_checkpoint: Vec3,
@@ -70,6 +99,19 @@ impl GameState {
     }
 }
 
+/// Puts every gem back and forgets reached checkpoints when an unattended kiosk resets.
+fn reset_for_next_visitor(
+    _reset: On<KioskReset>,
+    mut commands: Commands,
+    gems: Query<Entity, With<collectibles::Collectible>>,
+) {
+    commands.insert_resource(GameState::new());
+    for gem in &gems {
+        commands.entity(gem).despawn();
+    }
+    commands.run_system_cached(collectibles::spawn_collectibles);
+}
+
 /// Sets up the game environment with lighting and background color.
 fn setup_environment(mut commands: Commands) {
     // Ambient lighting for overall brightness
@@ -82,9 +124,18 @@ fn setup_environment(mut commands: Commands) {
     // Clear color for sky background
     commands.insert_resource(ClearColor(tailwind::SKY_300.into()));
 
+    // An eight minute day, starting mid-morning, with nights bright enough to keep playing
+    commands.insert_resource(
+        DayNightCycle::new(480.0)
+            .with_time_of_day(0.35)
+            .with_ambient(300.0, 120.0)
+            .with_sky(tailwind::SKY_300.into(), Color::srgb(0.05, 0.07, 0.16)),
+    );
+
     // Directional light for shadows and depth
     commands.spawn((
         Name::new("Sun"),
+        Sun,
         DirectionalLight {
             color: tailwind::YELLOW_200.into(),
             shadows_enabled: true,
@@ -92,5 +143,19 @@ fn setup_environment(mut commands: Commands) {
             ..default()
         },
         Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -0.8, 0.3, 0.0)),
+        // Covers every section of the level, from the starting platform to the finale
+        ShadowCascadeBounds::PlayArea {
+            center: Vec3::new(0.0, 16.0, -10.0),
+            half_extents: Vec3::new(25.0, 18.0, 35.0),
+        },
+    ));
+
+    // Stats by the start, facing the spawn point
+    commands.spawn((
+        Name::new("Run Statistics"),
+        StatsPlaque::new("This Run")
+            .with_time_in_scene("Time")
+            .with_diagnostic("Gems collected", GEMS_COLLECTED),
+        Transform::from_xyz(-3.0, 2.0, -5.5),
     ));
 }
@@ -1,13 +1,30 @@
 //! Player spawning and movement initialization.
 
+use avian3d::prelude::*;
 use bevy::prelude::*;
+use diorama::physics::{TriggerEnter, TriggerVolume};
 use diorama::player::Player;
+use diorama::player_events::RespawnPlayer;
+use diorama::save::CheckpointReached;
+use diorama::telemetry::{Telemetry, TelemetryEvent};
 
 use crate::GameState;
 
 /// The Y-coordinate threshold below which the player will respawn.
 const RESPAWN_Y_THRESHOLD: f32 = -100.0;
 
+/// Where the player stands on the last platform of each finished section, saving progress when
+/// reached.
+const CHECKPOINTS: [Vec3; 4] = [
+    Vec3::new(-6.0, 17.5, -24.0),
+    Vec3::new(-22.0, 23.5, -18.0),
+    Vec3::new(-10.0, 31.5, 0.0),
+    Vec3::new(10.0, 17.5, 20.0),
+];
+
+/// How close the player needs to get to a checkpoint to reach it.
+const CHECKPOINT_RADIUS: f32 = 2.0;
+
 /// Spawns the player at the starting position.
 pub fn spawn_player(mut player: Single<&mut Transform, With<Player>>) {
     let spawn_point = Transform::from_xyz(0.0, 20.0, 0.0);
@@ -16,13 +33,24 @@ pub fn spawn_player(mut player: Single<&mut Transform, With<Player>>) {
 
 /// Checks if the player has fallen too far and respawns them at the last checkpoint.
 pub fn check_player_respawn(
-    mut player: Single<&mut Transform, With<Player>>,
+    mut commands: Commands,
+    player: Single<&Transform, With<Player>>,
     game_state: Res<GameState>,
+    time: Res<Time>,
+    telemetry: Option<ResMut<Telemetry>>,
 ) {
     // Check if player has fallen below the threshold
     if player.translation.y < RESPAWN_Y_THRESHOLD {
+        if let Some(mut telemetry) = telemetry {
+            telemetry.record(
+                time.elapsed_secs(),
+                player.translation,
+                TelemetryEvent::Death,
+            );
+        }
+
         // Respawn at the current checkpoint
-        player.translation = game_state.current_checkpoint;
+        commands.trigger(RespawnPlayer::at(game_state.current_checkpoint));
 
         info!(
             "Player fell off the world! Respawning at checkpoint: {:?}",
@@ -30,3 +58,39 @@ pub fn check_player_respawn(
         );
     }
 }
+
+/// A spot where reaching it moves the respawn point there.
+#[derive(Component)]
+pub struct Checkpoint;
+
+/// Spawns a trigger volume at each checkpoint.
+pub fn spawn_checkpoints(mut commands: Commands) {
+    for (i, checkpoint) in CHECKPOINTS.into_iter().enumerate() {
+        commands.spawn((
+            Name::new(format!("Checkpoint {}", i + 1)),
+            Checkpoint,
+            Transform::from_translation(checkpoint),
+            Collider::sphere(CHECKPOINT_RADIUS),
+            TriggerVolume::default(),
+        ));
+    }
+}
+
+/// Moves the respawn point to checkpoints as the player reaches them, and saves the game.
+pub fn reach_checkpoint(
+    enter: On<TriggerEnter>,
+    mut commands: Commands,
+    checkpoints: Query<&Transform, With<Checkpoint>>,
+    mut game_state: ResMut<GameState>,
+) {
+    let Ok(transform) = checkpoints.get(enter.entity) else {
+        return;
+    };
+    let checkpoint = transform.translation;
+    if checkpoint == game_state.current_checkpoint {
+        return;
+    }
+    game_state.current_checkpoint = checkpoint;
+    commands.trigger(CheckpointReached);
+    info!("Reached checkpoint: {checkpoint:?}");
+}
@@ -87,10 +87,11 @@ pub fn spawn_moving_platforms(
 
 /// Updates moving platform positions and handles direction reversal.
 pub fn animate_moving_platforms(
-    mut platform_query: Query<(&Transform, &mut MovingPlatform, &mut LinearVelocity)>,
+    mut platform_query: Query<(&Position, &mut MovingPlatform, &mut LinearVelocity)>,
 ) {
-    for (transform, mut platform, mut velocity) in platform_query.iter_mut() {
-        let current_pos = transform.translation;
+    for (position, mut platform, mut velocity) in platform_query.iter_mut() {
+        // The simulated position, as the transform is interpolated a step behind
+        let current_pos = position.0;
 
         // Determine target based on current direction
         let target_pos = if platform.direction > 0.0 {
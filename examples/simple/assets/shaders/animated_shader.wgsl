@@ -5,10 +5,10 @@
 // 2. Blending colors in Oklab perceptual color space
 // 3. Varying intensity based on distance from UV center
 
-#import bevy_pbr::{
-    mesh_view_bindings::globals,
-    forward_io::VertexOutput,
-}
+#import bevy_pbr::forward_io::VertexOutput
+
+/// Seconds of game time, which stops while paused
+@group(3) @binding(0) var<uniform> game_time: f32;
 
 /// Animation speed multiplier
 const ANIMATION_SPEED: f32 = 2.0;
@@ -47,7 +47,7 @@ fn oklab_to_linear_srgb(c: vec3<f32>) -> vec3<f32> {
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     // Calculate time-based animation values
-    let time = globals.time * ANIMATION_SPEED;
+    let time = game_time * ANIMATION_SPEED;
     let t_sin = sin(time) * 0.5 + 0.5; // Normalized to [0, 1]
     let t_cos = cos(time);
 
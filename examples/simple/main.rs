@@ -3,6 +3,7 @@ use bevy::reflect::TypePath;
 use bevy::render::render_resource::AsBindGroup;
 use bevy::shader::ShaderRef;
 use diorama::DioramaPlugin;
+use diorama::game_time::{GameTimeMaterial, GameTimeMaterialPlugin};
 
 mod animation;
 mod materials;
@@ -22,8 +23,12 @@ pub struct Animated;
 ///
 /// This material cycles through colors using time-based animation in the fragment shader.
 /// The shader blends between colors in perceptual Oklab color space for smooth transitions.
-#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
-pub struct AnimatedMaterial {}
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone, Default)]
+pub struct AnimatedMaterial {
+    /// Seconds of game time, so the colours hold while paused
+    #[uniform(0)]
+    pub time: f32,
+}
 
 impl Material for AnimatedMaterial {
     fn fragment_shader() -> ShaderRef {
@@ -31,10 +36,17 @@ impl Material for AnimatedMaterial {
     }
 }
 
+impl GameTimeMaterial for AnimatedMaterial {
+    fn set_time(&mut self, seconds: f32) {
+        self.time = seconds;
+    }
+}
+
 /// Main plugin for the simple scene
 ///
 /// Provides a basic demonstration scene with:
 /// - A large marble-textured ground plane with physics
+/// - Hills built from a heightmap image
 /// - An animated color-shifting cube using a custom shader
 /// - Configurable lighting and player spawn point
 pub struct ScenePlugin;
@@ -48,8 +60,11 @@ impl Plugin for ScenePlugin {
 
 fn main() -> AppExit {
     App::new()
-        .add_plugins(DioramaPlugin)
+        .add_plugins(DioramaPlugin::default())
         .add_plugins(ScenePlugin)
-        .add_plugins(MaterialPlugin::<AnimatedMaterial>::default())
+        .add_plugins((
+            MaterialPlugin::<AnimatedMaterial>::default(),
+            GameTimeMaterialPlugin::<AnimatedMaterial>::default(),
+        ))
         .run()
 }
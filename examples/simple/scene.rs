@@ -2,6 +2,7 @@ use avian3d::prelude::*;
 use bevy::color::palettes::tailwind;
 use bevy::prelude::*;
 use diorama::player::Player;
+use diorama::terrain::HeightmapImage;
 
 use crate::{Animated, AnimatedMaterial, create_marble_floor_material};
 
@@ -14,6 +15,12 @@ const GROUND_WIDTH: f32 = 1_000.0;
 const GROUND_HEIGHT: f32 = 1.0;
 const GROUND_DEPTH: f32 = 1_000.0;
 
+/// Hills authored as a grayscale image, standing out of the ground ahead of the player
+const HILLS_IMAGE: &str = "heightmaps/hills.png";
+const HILLS_POSITION: Vec3 = Vec3::new(0.0, 0.0, 40.0);
+const HILLS_SIZE: f32 = 40.0;
+const HILLS_HEIGHT: f32 = 8.0;
+
 /// Animated cube configuration
 const CUBE_SIZE: f32 = 1.0;
 const CUBE_POSITION: Vec3 = Vec3::new(0.0, 4.0, 2.0);
@@ -65,12 +72,25 @@ pub fn setup(
         ],
     ));
 
+    // Hills, built once the heightmap image has loaded. Black is just under the ground's surface,
+    // so their edges don't show
+    root.with_child((
+        Name::new("Hills"),
+        HeightmapImage::new(HILLS_IMAGE, HILLS_SIZE, HILLS_HEIGHT).with_base(GROUND_HEIGHT * 0.4),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: tailwind::GREEN_700.into(),
+            perceptual_roughness: 0.9,
+            ..default()
+        })),
+        Transform::from_translation(HILLS_POSITION),
+    ));
+
     // Add animated shader cube
     root.with_child((
         Name::new("Shader Cube"),
         Animated,
         Mesh3d(meshes.add(Cuboid::new(CUBE_SIZE, CUBE_SIZE, CUBE_SIZE))),
-        MeshMaterial3d(shader_materials.add(AnimatedMaterial {})),
+        MeshMaterial3d(shader_materials.add(AnimatedMaterial::default())),
         Transform::from_translation(CUBE_POSITION),
         RigidBody::Dynamic,
         Collider::cuboid(CUBE_SIZE, CUBE_SIZE, CUBE_SIZE),
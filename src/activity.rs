@@ -0,0 +1,115 @@
+//! Creatures that keep to a daily routine, following the [`DayNightCycle`].
+//!
+//! A [`DailyActivity`] marks a creature as diurnal or nocturnal, and switches it between
+//! [`ActivityState::Active`] and [`ActivityState::Sheltering`] as the sun rises and sets, triggering
+//! [`Emerged`] or [`Sheltered`] on it each time. What sheltering means is up to the scene, e.g.
+//! flying back to a roost, burrowing or just hiding, so creature behaviour should check the state
+//! before doing anything else. Each creature can switch at its own sun elevation, so a population
+//! doesn't all move at once. Without a [`DayNightCycle`] everything stays active.
+
+use bevy::prelude::*;
+
+use crate::environment::DayNightCycle;
+use crate::state::PausableSystems;
+
+pub struct ActivityPlugin;
+
+impl Plugin for ActivityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            follow_daily_routines
+                .in_set(PausableSystems)
+                .run_if(resource_exists::<DayNightCycle>),
+        );
+    }
+}
+
+/// When in the day a creature is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Routine {
+    Diurnal,
+    Nocturnal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum ActivityState {
+    Active,
+    Sheltering,
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct DailyActivity {
+    pub routine: Routine,
+    /// Sun elevation, as in [`DayNightCycle::sun_elevation`], that counts as daytime for this
+    /// creature. Slightly positive values have it wait for the sun to clear the horizon.
+    pub threshold: f32,
+    state: ActivityState,
+}
+
+impl DailyActivity {
+    pub fn diurnal() -> Self {
+        Self {
+            routine: Routine::Diurnal,
+            threshold: 0.0,
+            state: ActivityState::Active,
+        }
+    }
+
+    pub fn nocturnal() -> Self {
+        Self {
+            routine: Routine::Nocturnal,
+            threshold: 0.0,
+            state: ActivityState::Active,
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold.clamp(-1.0, 1.0);
+        self
+    }
+
+    pub fn state(&self) -> ActivityState {
+        self.state
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state == ActivityState::Active
+    }
+}
+
+/// Triggered on a creature with a [`DailyActivity`] when it becomes active.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Emerged {
+    pub entity: Entity,
+}
+
+/// Triggered on a creature with a [`DailyActivity`] when it goes to shelter.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Sheltered {
+    pub entity: Entity,
+}
+
+fn follow_daily_routines(
+    mut commands: Commands,
+    cycle: Res<DayNightCycle>,
+    mut creatures: Query<(Entity, &mut DailyActivity)>,
+) {
+    let elevation = cycle.sun_elevation();
+    for (entity, mut activity) in &mut creatures {
+        let is_day = elevation > activity.threshold;
+        let state = match (activity.routine, is_day) {
+            (Routine::Diurnal, true) | (Routine::Nocturnal, false) => ActivityState::Active,
+            _ => ActivityState::Sheltering,
+        };
+        if activity.state == state {
+            continue;
+        }
+        activity.state = state;
+        match state {
+            ActivityState::Active => commands.trigger(Emerged { entity }),
+            ActivityState::Sheltering => commands.trigger(Sheltered { entity }),
+        }
+    }
+}
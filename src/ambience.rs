@@ -0,0 +1,246 @@
+//! Trigger volumes that give areas of a scene their own ambience.
+//!
+//! When the player is inside an [`AmbienceZone`], its ambient loop is crossfaded in (and any other
+//! zone's loop faded out), its reverb preset becomes active, and any fog or ambient light overrides
+//! are applied. Leaving all zones restores the scene's original fog and ambient light. The active
+//! reverb carries [`SpatialAudioEmitter`]s further, as their sound reverberates around the space.
+//! Zone loops are muffled by [`WaterDepth`] like every other loop.
+//!
+//! [`SpatialAudioEmitter`]: crate::audio::SpatialAudioEmitter
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::firstsight::PlayerCamera;
+use crate::player::Player;
+use crate::underwater::WaterDepth;
+
+pub struct AmbiencePlugin;
+
+impl Plugin for AmbiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveAmbience>()
+            .add_observer(spawn_ambience_loop)
+            .add_systems(
+                Update,
+                (update_active_zone, crossfade_ambience_loops).chain(),
+            );
+    }
+}
+
+/// Time taken to fade an ambient loop fully in or out.
+const CROSSFADE_SECONDS: f32 = 2.0;
+
+/// Reverb character for a zone.
+///
+/// `bevy_audio` has no effects bus, so reverb is approximated by how far sounds carry: the
+/// [`ReverbPreset::wet`] share of each [`SpatialAudioEmitter`] fades out linearly with distance,
+/// rather than quadratically like the direct sound.
+///
+/// [`SpatialAudioEmitter`]: crate::audio::SpatialAudioEmitter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum ReverbPreset {
+    #[default]
+    None,
+    SmallRoom,
+    Hall,
+    Cave,
+    Underwater,
+    Outdoors,
+}
+
+impl ReverbPreset {
+    /// Share of a sound heard as reverberation rather than directly, from 0 to 1.
+    pub fn wet(self) -> f32 {
+        match self {
+            ReverbPreset::None => 0.0,
+            ReverbPreset::Outdoors => 0.05,
+            ReverbPreset::SmallRoom => 0.15,
+            ReverbPreset::Underwater => 0.3,
+            ReverbPreset::Hall => 0.35,
+            ReverbPreset::Cave => 0.5,
+        }
+    }
+}
+
+/// An axis-aligned (in local space) box that changes the ambience while the player is inside it.
+///
+/// Where zones overlap, the one with the highest `priority` wins.
+#[derive(Component, Clone)]
+#[require(Transform)]
+pub struct AmbienceZone {
+    pub half_extents: Vec3,
+    pub priority: i32,
+    pub ambient_loop: Option<Handle<AudioSource>>,
+    pub volume: f32,
+    pub reverb: ReverbPreset,
+    pub fog: Option<DistanceFog>,
+    pub ambient_light: Option<GlobalAmbientLight>,
+}
+
+impl AmbienceZone {
+    pub fn new(half_extents: Vec3) -> Self {
+        Self {
+            half_extents,
+            priority: 0,
+            ambient_loop: None,
+            volume: 1.0,
+            reverb: ReverbPreset::None,
+            fog: None,
+            ambient_light: None,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_loop(mut self, ambient_loop: Handle<AudioSource>, volume: f32) -> Self {
+        self.ambient_loop = Some(ambient_loop);
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_reverb(mut self, reverb: ReverbPreset) -> Self {
+        self.reverb = reverb;
+        self
+    }
+
+    pub fn with_fog(mut self, fog: DistanceFog) -> Self {
+        self.fog = Some(fog);
+        self
+    }
+
+    pub fn with_ambient_light(mut self, ambient_light: GlobalAmbientLight) -> Self {
+        self.ambient_light = Some(ambient_light);
+        self
+    }
+
+    fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let local = transform.affine().inverse().transform_point3(point);
+        local.abs().cmple(self.half_extents).all()
+    }
+}
+
+/// The zone the player is currently in, if any, along with the reverb preset to use.
+#[derive(Resource, Default)]
+pub struct ActiveAmbience {
+    pub zone: Option<Entity>,
+    pub reverb: ReverbPreset,
+    baseline_fog: Option<DistanceFog>,
+    baseline_ambient_light: Option<GlobalAmbientLight>,
+}
+
+/// Looping ambient sound owned by an [`AmbienceZone`], faded in and out as the zone becomes active.
+#[derive(Component)]
+struct AmbienceLoop {
+    zone: Entity,
+    volume: f32,
+    level: f32,
+}
+
+fn spawn_ambience_loop(
+    add: On<Add, AmbienceZone>,
+    zones: Query<&AmbienceZone>,
+    mut commands: Commands,
+) {
+    let Ok(zone) = zones.get(add.entity) else {
+        return;
+    };
+    let Some(ambient_loop) = zone.ambient_loop.clone() else {
+        return;
+    };
+    let loop_entity = commands
+        .spawn((
+            Name::new("Ambience loop"),
+            AmbienceLoop {
+                zone: add.entity,
+                volume: zone.volume,
+                level: 0.0,
+            },
+            AudioPlayer::new(ambient_loop),
+            PlaybackSettings::LOOP.with_volume(Volume::SILENT),
+        ))
+        .id();
+    commands.entity(add.entity).add_child(loop_entity);
+}
+
+/// Works out which zone the player is in and applies its fog and ambient light overrides.
+fn update_active_zone(
+    mut commands: Commands,
+    mut active: ResMut<ActiveAmbience>,
+    ambient_light: Option<Res<GlobalAmbientLight>>,
+    player: Single<&GlobalTransform, With<Player>>,
+    camera: Single<(Entity, Option<&DistanceFog>), With<PlayerCamera>>,
+    zones: Query<(Entity, &AmbienceZone, &GlobalTransform)>,
+) {
+    let position = player.translation();
+    let current = zones
+        .iter()
+        .filter(|(_, zone, transform)| zone.contains(transform, position))
+        .max_by_key(|(_, zone, _)| zone.priority);
+    let current_entity = current.map(|(entity, _, _)| entity);
+    if current_entity == active.zone {
+        return;
+    }
+
+    let (camera_entity, camera_fog) = camera.into_inner();
+    if active.zone.is_none() {
+        // Remember how the scene looked outside of any zone so it can be restored on exit
+        active.baseline_fog = camera_fog.cloned();
+        active.baseline_ambient_light = ambient_light.map(|light| light.clone());
+    }
+
+    let (fog, light, reverb) = match current {
+        Some((_, zone, _)) => (
+            zone.fog.clone().or_else(|| active.baseline_fog.clone()),
+            zone.ambient_light
+                .clone()
+                .or_else(|| active.baseline_ambient_light.clone()),
+            zone.reverb,
+        ),
+        None => (
+            active.baseline_fog.clone(),
+            active.baseline_ambient_light.clone(),
+            ReverbPreset::None,
+        ),
+    };
+
+    match fog {
+        Some(fog) => {
+            commands.entity(camera_entity).insert(fog);
+        }
+        None => {
+            commands.entity(camera_entity).remove::<DistanceFog>();
+        }
+    }
+    if let Some(light) = light {
+        commands.insert_resource(light);
+    }
+
+    active.zone = current_entity;
+    active.reverb = reverb;
+}
+
+fn crossfade_ambience_loops(
+    time: Res<Time>,
+    active: Res<ActiveAmbience>,
+    water_depth: Option<Res<WaterDepth>>,
+    mut loops: Query<(&mut AmbienceLoop, &mut AudioSink)>,
+) {
+    let step = time.delta_secs() / CROSSFADE_SECONDS;
+    let water_depth = water_depth.map(|depth| *depth).unwrap_or_default();
+    for (mut ambience_loop, mut sink) in loops.iter_mut() {
+        let target = if active.zone == Some(ambience_loop.zone) {
+            1.0
+        } else {
+            0.0
+        };
+        ambience_loop.level += (target - ambience_loop.level).clamp(-step, step);
+        sink.set_volume(Volume::Linear(
+            ambience_loop.level * ambience_loop.volume * water_depth.muffled_volume(),
+        ));
+        sink.set_speed(water_depth.muffled_speed());
+    }
+}
@@ -0,0 +1,108 @@
+//! Local ambient light overrides that blend in as the player moves into them.
+//!
+//! Each [`AmbientLightVolume`] is a box with its own ambient colour and brightness. Its influence
+//! ramps from zero at the edge of the box to full strength `blend_distance` inside it, and the
+//! result is blended with the scene's [`GlobalAmbientLight`] and applied to the player camera as an
+//! [`AmbientLight`] override.
+
+use bevy::prelude::*;
+
+use crate::firstsight::PlayerCamera;
+use crate::player::Player;
+
+pub struct AmbientVolumePlugin;
+
+impl Plugin for AmbientVolumePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, blend_ambient_light_volumes);
+    }
+}
+
+#[derive(Component, Clone)]
+#[require(Transform)]
+pub struct AmbientLightVolume {
+    pub half_extents: Vec3,
+    pub color: Color,
+    pub brightness: f32,
+    /// Distance inside the box over which the volume fades in from its edges.
+    pub blend_distance: f32,
+}
+
+impl AmbientLightVolume {
+    pub fn new(half_extents: Vec3, color: Color, brightness: f32) -> Self {
+        Self {
+            half_extents,
+            color,
+            brightness,
+            blend_distance: 2.0,
+        }
+    }
+
+    pub fn with_blend_distance(mut self, blend_distance: f32) -> Self {
+        self.blend_distance = blend_distance;
+        self
+    }
+
+    /// How strongly this volume applies at `point`, from 0 outside to 1 once well inside.
+    fn weight(&self, transform: &GlobalTransform, point: Vec3) -> f32 {
+        let local = transform.affine().inverse().transform_point3(point);
+        let depth = (self.half_extents - local.abs()).min_element();
+        if depth <= 0.0 {
+            0.0
+        } else if self.blend_distance <= 0.0 {
+            1.0
+        } else {
+            (depth / self.blend_distance).min(1.0)
+        }
+    }
+}
+
+fn linear_rgb(color: Color) -> Vec3 {
+    let linear = color.to_linear();
+    Vec3::new(linear.red, linear.green, linear.blue)
+}
+
+fn blend_ambient_light_volumes(
+    mut commands: Commands,
+    global: Res<GlobalAmbientLight>,
+    player: Single<&GlobalTransform, With<Player>>,
+    camera: Single<(Entity, Has<AmbientLight>), With<PlayerCamera>>,
+    volumes: Query<(&AmbientLightVolume, &GlobalTransform)>,
+) {
+    let position = player.translation();
+    let (camera_entity, has_override) = camera.into_inner();
+
+    let mut total_weight = 0.0;
+    let mut color = Vec3::ZERO;
+    let mut brightness = 0.0;
+    for (volume, transform) in volumes.iter() {
+        let weight = volume.weight(transform, position);
+        if weight <= 0.0 {
+            continue;
+        }
+        total_weight += weight;
+        color += linear_rgb(volume.color) * weight;
+        brightness += volume.brightness * weight;
+    }
+
+    if total_weight <= 0.0 {
+        if has_override {
+            commands.entity(camera_entity).remove::<AmbientLight>();
+        }
+        return;
+    }
+
+    // Average overlapping volumes, then mix with the global light by how far inside they are
+    let influence = total_weight.min(1.0);
+    let volume_color = color / total_weight;
+    let volume_brightness = brightness / total_weight;
+    let global_color = linear_rgb(global.color);
+
+    let blended_color = global_color.lerp(volume_color, influence);
+
+    commands.entity(camera_entity).insert(AmbientLight {
+        color: LinearRgba::rgb(blended_color.x, blended_color.y, blended_color.z).into(),
+        brightness: global.brightness + (volume_brightness - global.brightness) * influence,
+        affects_lightmapped_meshes: global.affects_lightmapped_meshes,
+    });
+}
@@ -0,0 +1,383 @@
+//! Notes and ratings visitors leave on exhibits, kept for whoever visits next.
+//!
+//! Inserting an [`AnnotationSettings`] resource lets the player press the annotate binding near an
+//! [`Exhibit`] to write a note on it: typed on the keyboard, rated out of five with the arrow
+//! keys, and left with Enter, or dropped by pressing Enter with nothing written. Notes are kept in
+//! the [`Annotations`] resource by exhibit title, so they follow an artwork if it's rehung, and
+//! each exhibit with notes gets a small marker floating over it with how many there are and their
+//! average rating, which shows the latest notes as the player gets close. Registering
+//! [`Annotations`] with [`SaveGame::with_resource`] keeps notes between sessions, and they're saved
+//! whenever one is left, so sessions sharing a save file read each other's notes.
+
+use std::collections::BTreeMap;
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::audio_guide::Exhibit;
+use crate::controls::{Binding, ControlsInput, KeyboardCapture};
+use crate::firstsight::PlayerCamera;
+use crate::player::Player;
+use crate::save::{SaveGame, SaveRequest};
+use crate::state::PausableSystems;
+
+pub struct AnnotationsPlugin;
+
+impl Plugin for AnnotationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Annotations>()
+            .init_resource::<NoteEditor>()
+            .add_systems(
+                Update,
+                spawn_note_panel.run_if(resource_added::<AnnotationSettings>),
+            )
+            .add_systems(
+                Update,
+                (
+                    (type_note, start_note).chain().in_set(PausableSystems),
+                    update_note_panel,
+                    mark_exhibits,
+                    place_markers,
+                )
+                    .chain()
+                    .run_if(resource_exists::<AnnotationSettings>),
+            );
+    }
+}
+
+/// Longest note that can be written, in characters.
+const MAX_NOTE_LENGTH: usize = 140;
+const MAX_RATING: u8 = 5;
+
+/// Settings for annotating exhibits, which is only possible while this resource exists.
+#[derive(Resource, Debug, Clone)]
+pub struct AnnotationSettings {
+    /// Starts writing a note on the nearest exhibit.
+    pub annotate: Binding,
+    /// Exhibits further than this from the player can't be annotated, and their markers don't show
+    /// any notes.
+    pub nearby_range: f32,
+    /// Markers further than this from the camera are hidden.
+    pub marker_distance: f32,
+    /// How far above its exhibit a marker floats.
+    pub marker_height: f32,
+    /// How many of the latest notes a marker shows once the player's nearby.
+    pub notes_shown: usize,
+}
+
+impl Default for AnnotationSettings {
+    fn default() -> Self {
+        Self {
+            annotate: Binding::keys([KeyCode::KeyN]),
+            nearby_range: 4.0,
+            marker_distance: 12.0,
+            marker_height: 1.4,
+            notes_shown: 2,
+        }
+    }
+}
+
+/// A note left on an exhibit.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub text: String,
+    /// Out of five.
+    pub rating: u8,
+}
+
+impl Annotation {
+    pub fn new(text: impl Into<String>, rating: u8) -> Self {
+        Self {
+            text: text.into(),
+            rating: rating.clamp(1, MAX_RATING),
+        }
+    }
+}
+
+/// Every note left, by the title of the exhibit it was left on, oldest first.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Annotations {
+    notes: BTreeMap<String, Vec<Annotation>>,
+}
+
+impl Annotations {
+    pub fn add(&mut self, title: impl Into<String>, annotation: Annotation) {
+        self.notes.entry(title.into()).or_default().push(annotation);
+    }
+
+    /// Notes left on the exhibit titled `title`, oldest first.
+    pub fn for_exhibit(&self, title: &str) -> &[Annotation] {
+        self.notes.get(title).map_or(&[], Vec::as_slice)
+    }
+
+    /// Average rating of the exhibit titled `title`, if it has any notes.
+    pub fn average_rating(&self, title: &str) -> Option<f32> {
+        let notes = self.for_exhibit(title);
+        (!notes.is_empty())
+            .then(|| notes.iter().map(|note| note.rating as f32).sum::<f32>() / notes.len() as f32)
+    }
+}
+
+/// Triggered on an [`Exhibit`] when a note is left on it.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct AnnotationLeft {
+    pub entity: Entity,
+    pub title: String,
+    pub annotation: Annotation,
+}
+
+/// The note being written, if any.
+#[derive(Resource, Default)]
+struct NoteEditor {
+    exhibit: Option<Entity>,
+    text: String,
+    rating: u8,
+}
+
+#[derive(Component)]
+struct NotePanel;
+
+/// On an exhibit's marker, pointing at the exhibit.
+#[derive(Component)]
+#[relationship(relationship_target = AnnotationMarked)]
+struct AnnotationMarkerOf(Entity);
+
+/// On an exhibit, pointing at its marker, which is despawned with it.
+#[derive(Component)]
+#[relationship_target(relationship = AnnotationMarkerOf, linked_spawn)]
+struct AnnotationMarked(Entity);
+
+fn nearest_exhibit<'a>(
+    settings: &AnnotationSettings,
+    position: Vec3,
+    exhibits: impl Iterator<Item = (Entity, &'a Exhibit, &'a GlobalTransform)>,
+) -> Option<Entity> {
+    exhibits
+        .map(|(entity, _, transform)| (entity, transform.translation().distance(position)))
+        .filter(|(_, distance)| *distance <= settings.nearby_range)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity)
+}
+
+fn start_note(
+    mut commands: Commands,
+    settings: Res<AnnotationSettings>,
+    input: ControlsInput,
+    mut editor: ResMut<NoteEditor>,
+    player: Single<&GlobalTransform, With<Player>>,
+    exhibits: Query<(Entity, &Exhibit, &GlobalTransform)>,
+) {
+    if editor.exhibit.is_some() || !input.just_pressed(&settings.annotate) {
+        return;
+    }
+    let Some(exhibit) = nearest_exhibit(&settings, player.translation(), exhibits.iter()) else {
+        return;
+    };
+    *editor = NoteEditor {
+        exhibit: Some(exhibit),
+        text: String::new(),
+        rating: MAX_RATING,
+    };
+    // Keys type into the note instead of moving the player
+    commands.init_resource::<KeyboardCapture>();
+}
+
+fn type_note(
+    mut commands: Commands,
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut editor: ResMut<NoteEditor>,
+    mut annotations: ResMut<Annotations>,
+    save_game: Option<Res<SaveGame>>,
+    exhibits: Query<&Exhibit>,
+) {
+    // Always read, so the key that opened the editor isn't typed into it
+    let keys: Vec<_> = keyboard
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed)
+        .map(|input| input.logical_key.clone())
+        .collect();
+    let Some(entity) = editor.exhibit else {
+        return;
+    };
+    let Ok(exhibit) = exhibits.get(entity) else {
+        // The exhibit's gone, so there's nothing to leave the note on
+        *editor = NoteEditor::default();
+        commands.remove_resource::<KeyboardCapture>();
+        return;
+    };
+
+    for key in keys {
+        match key {
+            Key::Character(text) => {
+                for character in text.chars().filter(|character| !character.is_control()) {
+                    if editor.text.chars().count() < MAX_NOTE_LENGTH {
+                        editor.text.push(character);
+                    }
+                }
+            }
+            Key::Space if editor.text.chars().count() < MAX_NOTE_LENGTH => editor.text.push(' '),
+            Key::Backspace => {
+                editor.text.pop();
+            }
+            Key::ArrowUp | Key::ArrowRight => editor.rating = (editor.rating + 1).min(MAX_RATING),
+            Key::ArrowDown | Key::ArrowLeft => {
+                editor.rating = editor.rating.saturating_sub(1).max(1)
+            }
+            Key::Enter => {
+                let text = editor.text.trim().to_string();
+                if !text.is_empty() {
+                    let annotation = Annotation::new(text, editor.rating);
+                    annotations.add(exhibit.title.clone(), annotation.clone());
+                    commands.trigger(AnnotationLeft {
+                        entity,
+                        title: exhibit.title.clone(),
+                        annotation,
+                    });
+                    if save_game.is_some() {
+                        commands.trigger(SaveRequest);
+                    }
+                }
+                *editor = NoteEditor::default();
+                commands.remove_resource::<KeyboardCapture>();
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn spawn_note_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Note editor"),
+        NotePanel,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(30.0),
+            left: Val::Percent(30.0),
+            width: Val::Percent(40.0),
+            padding: UiRect::all(Val::Px(12.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+fn rating_stars(rating: u8) -> String {
+    (1..=MAX_RATING)
+        .map(|star| if star <= rating { '★' } else { '☆' })
+        .collect()
+}
+
+fn update_note_panel(
+    editor: Res<NoteEditor>,
+    exhibits: Query<&Exhibit>,
+    panel: Single<(&mut Text, &mut Visibility), With<NotePanel>>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+    let (mut text, mut visibility) = panel.into_inner();
+    let Some(exhibit) = editor.exhibit.and_then(|entity| exhibits.get(entity).ok()) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+    text.0 = format!(
+        "Note on {}\n{}\n\n{}_\n\nType a note, arrow keys to rate, Enter to leave it",
+        exhibit.title,
+        rating_stars(editor.rating),
+        editor.text
+    );
+}
+
+fn mark_exhibits(
+    mut commands: Commands,
+    exhibits: Query<Entity, (With<Exhibit>, Without<AnnotationMarked>)>,
+) {
+    for exhibit in &exhibits {
+        commands.spawn((
+            Name::new("Annotation marker"),
+            AnnotationMarkerOf(exhibit),
+            Text::default(),
+            TextFont {
+                font_size: 13.0,
+                ..default()
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                max_width: Val::Px(240.0),
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.2, 0.7)),
+            Visibility::Hidden,
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+fn place_markers(
+    settings: Res<AnnotationSettings>,
+    annotations: Res<Annotations>,
+    camera: Single<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    player: Single<&GlobalTransform, With<Player>>,
+    exhibits: Query<(&Exhibit, &GlobalTransform)>,
+    mut markers: Query<(
+        &AnnotationMarkerOf,
+        &mut Node,
+        &mut Text,
+        &mut Visibility,
+        &ComputedNode,
+    )>,
+) {
+    let (camera, camera_transform) = *camera;
+    for (marker, mut node, mut text, mut visibility, computed) in &mut markers {
+        let Ok((exhibit, transform)) = exhibits.get(marker.0) else {
+            continue;
+        };
+        let notes = annotations.for_exhibit(&exhibit.title);
+        let anchor = transform.translation() + Vec3::Y * settings.marker_height;
+        let position = camera
+            .world_to_viewport(camera_transform, anchor)
+            .ok()
+            .filter(|_| {
+                !notes.is_empty()
+                    && camera_transform.translation().distance(anchor) <= settings.marker_distance
+            });
+        let Some(position) = position else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let average = annotations
+            .average_rating(&exhibit.title)
+            .unwrap_or_default();
+        let mut label = format!(
+            "{} {} · {average:.1}/{MAX_RATING}",
+            notes.len(),
+            if notes.len() == 1 { "note" } else { "notes" }
+        );
+        if transform.translation().distance(player.translation()) <= settings.nearby_range {
+            for note in notes.iter().rev().take(settings.notes_shown) {
+                label.push_str(&format!("\n{} “{}”", rating_stars(note.rating), note.text));
+            }
+        }
+        if text.0 != label {
+            text.0 = label;
+        }
+        // Centred over the anchor, sized from the last layout
+        let size = computed.size() * computed.inverse_scale_factor();
+        node.left = Val::Px(position.x - size.x / 2.0);
+        node.top = Val::Px(position.y - size.y);
+    }
+}
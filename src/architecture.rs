@@ -0,0 +1,541 @@
+//! Rooms and corridors generated from a graph of connected rooms.
+//!
+//! An [`Architecture`] is a set of axis-aligned [`Room`]s, each with a floor, a ceiling and four
+//! walls, joined by doorways. A doorway is cut through the walls of both rooms where they meet,
+//! centred on the stretch the two walls share, and [`Room::with_opening`] cuts openings to the
+//! outside, or windows. Walls are split into solid sections around their openings, with lintels
+//! above openings that stop short of the ceiling and sills below raised ones, and spawning gives
+//! every floor, ceiling and wall section a cuboid mesh and a collider of the same size.
+//!
+//! North is -Z and east is +X. Two rooms meet when the edge of one lies on the opposite edge of
+//! the other, e.g. a room's north edge and a corridor's south edge both at z = -15; doorways
+//! between rooms that don't meet, or whose shared stretch of wall is narrower than the doorway,
+//! are skipped with a warning.
+
+use bevy::prelude::*;
+
+use crate::footsteps::{SurfaceKind, SurfaceProperties};
+use crate::prefab::Prefab;
+
+/// How far apart two room edges can be and still count as meeting.
+const EPSILON: f32 = 1e-3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WallSide {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl WallSide {
+    pub const ALL: [WallSide; 4] = [
+        WallSide::North,
+        WallSide::East,
+        WallSide::South,
+        WallSide::West,
+    ];
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+
+    /// Unit vector pointing out of a room through this wall.
+    pub fn normal(self) -> Vec3 {
+        match self {
+            Self::North => Vec3::NEG_Z,
+            Self::East => Vec3::X,
+            Self::South => Vec3::Z,
+            Self::West => Vec3::NEG_X,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::North => "North",
+            Self::East => "East",
+            Self::South => "South",
+            Self::West => "West",
+        }
+    }
+
+    /// Whether the wall runs along X, rather than Z.
+    fn along_x(self) -> bool {
+        matches!(self, Self::North | Self::South)
+    }
+}
+
+/// A hole cut through a wall, such as a doorway or window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Opening {
+    pub width: f32,
+    /// Height of the top of the opening above the floor, or `None` to go up to the ceiling.
+    pub height: Option<f32>,
+    /// Height of the bottom of the opening above the floor.
+    pub sill: f32,
+    /// How far the opening's centre is moved along the wall, towards +X or +Z, from the middle of
+    /// the wall, or for doorways from the middle of the stretch the two rooms share.
+    pub offset: f32,
+}
+
+impl Opening {
+    /// A doorway from the floor to the ceiling.
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            height: None,
+            sill: 0.0,
+            offset: 0.0,
+        }
+    }
+
+    /// An opening from `sill` up to `height`, with wall below and above it.
+    pub fn window(width: f32, sill: f32, height: f32) -> Self {
+        Self::new(width).with_sill(sill).with_height(height)
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn with_sill(mut self, sill: f32) -> Self {
+        self.sill = sill.max(0.0);
+        self
+    }
+
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Room {
+    pub name: String,
+    /// Centre of the room's floor.
+    pub centre: Vec3,
+    /// Size along X and Z, to the outside of its walls.
+    pub size: Vec2,
+    /// Height of the ceiling above the floor.
+    pub height: f32,
+    pub floor: bool,
+    pub ceiling: bool,
+    /// What the floor is made of, for footsteps.
+    pub floor_surface: Option<SurfaceKind>,
+    /// Openings that don't lead to another room of the [`Architecture`].
+    pub openings: Vec<(WallSide, Opening)>,
+}
+
+impl Room {
+    pub fn new(name: impl Into<String>, centre: Vec3, size: Vec2, height: f32) -> Self {
+        Self {
+            name: name.into(),
+            centre,
+            size,
+            height,
+            floor: true,
+            ceiling: true,
+            floor_surface: None,
+            openings: Vec::new(),
+        }
+    }
+
+    pub fn without_floor(mut self) -> Self {
+        self.floor = false;
+        self
+    }
+
+    pub fn without_ceiling(mut self) -> Self {
+        self.ceiling = false;
+        self
+    }
+
+    pub fn with_floor_surface(mut self, surface: SurfaceKind) -> Self {
+        self.floor_surface = Some(surface);
+        self
+    }
+
+    pub fn with_opening(mut self, side: WallSide, opening: Opening) -> Self {
+        self.openings.push((side, opening));
+        self
+    }
+
+    fn min(&self) -> Vec2 {
+        self.centre.xz() - self.size / 2.0
+    }
+
+    fn max(&self) -> Vec2 {
+        self.centre.xz() + self.size / 2.0
+    }
+
+    /// The room's extent along the given wall, as (start, end) on X or Z.
+    fn span(&self, side: WallSide) -> (f32, f32) {
+        if side.along_x() {
+            (self.min().x, self.max().x)
+        } else {
+            (self.min().y, self.max().y)
+        }
+    }
+
+    /// Position of the given wall's outer face on Z or X.
+    fn edge(&self, side: WallSide) -> f32 {
+        match side {
+            WallSide::North => self.min().y,
+            WallSide::East => self.max().x,
+            WallSide::South => self.max().y,
+            WallSide::West => self.min().x,
+        }
+    }
+}
+
+/// A doorway joining two rooms by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Doorway {
+    pub from: String,
+    pub to: String,
+    /// The opening, with its sill and height measured from the floor of `from`.
+    pub opening: Opening,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceKind {
+    Floor,
+    Ceiling,
+    Wall(WallSide),
+}
+
+/// A cuboid making up part of a room, relative to the centre of the room's floor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchitecturePiece {
+    pub name: String,
+    pub kind: PieceKind,
+    pub size: Vec3,
+    pub translation: Vec3,
+}
+
+/// Materials for each kind of [`ArchitecturePiece`].
+#[derive(Debug, Clone)]
+pub struct ArchitectureMaterials {
+    pub floor: Handle<StandardMaterial>,
+    pub wall: Handle<StandardMaterial>,
+    pub ceiling: Handle<StandardMaterial>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Architecture {
+    pub name: String,
+    pub rooms: Vec<Room>,
+    pub doorways: Vec<Doorway>,
+    pub wall_thickness: f32,
+    /// Thickness of floors and ceilings, which are centred on the floor and ceiling heights.
+    pub slab_thickness: f32,
+}
+
+impl Architecture {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            rooms: Vec::new(),
+            doorways: Vec::new(),
+            wall_thickness: 0.3,
+            slab_thickness: 0.15,
+        }
+    }
+
+    pub fn with_room(mut self, room: Room) -> Self {
+        self.rooms.push(room);
+        self
+    }
+
+    /// Joins the rooms named `from` and `to` with `opening`.
+    pub fn with_doorway(
+        mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        opening: Opening,
+    ) -> Self {
+        self.doorways.push(Doorway {
+            from: from.into(),
+            to: to.into(),
+            opening,
+        });
+        self
+    }
+
+    pub fn with_wall_thickness(mut self, wall_thickness: f32) -> Self {
+        self.wall_thickness = wall_thickness.max(0.0);
+        self
+    }
+
+    pub fn with_slab_thickness(mut self, slab_thickness: f32) -> Self {
+        self.slab_thickness = slab_thickness.max(0.0);
+        self
+    }
+
+    /// Every opening in the walls of each room, both its own and those of doorways, with offsets
+    /// from the middle of each wall.
+    pub fn openings(&self) -> Vec<Vec<(WallSide, Opening)>> {
+        let mut openings: Vec<_> = self
+            .rooms
+            .iter()
+            .map(|room| room.openings.clone())
+            .collect();
+        for doorway in &self.doorways {
+            for (index, side, opening) in self.cut_doorway(doorway).into_iter().flatten() {
+                openings[index].push((side, opening));
+            }
+        }
+        openings
+    }
+
+    /// Finds where a doorway's rooms meet, returning the opening to cut in each of their walls.
+    fn cut_doorway(&self, doorway: &Doorway) -> Option<[(usize, WallSide, Opening); 2]> {
+        let find = |name: &str| {
+            let index = self.rooms.iter().position(|room| room.name == name);
+            if index.is_none() {
+                warn!("Doorway to unknown room {name}");
+            }
+            index
+        };
+        let (from, to) = (find(&doorway.from)?, find(&doorway.to)?);
+        let (a, b) = (&self.rooms[from], &self.rooms[to]);
+        let opening = doorway.opening;
+
+        let Some(side) = WallSide::ALL
+            .into_iter()
+            .find(|side| (a.edge(*side) - b.edge(side.opposite())).abs() < EPSILON)
+        else {
+            warn!(
+                "Skipping doorway between {} and {}, which don't meet",
+                a.name, b.name
+            );
+            return None;
+        };
+        let ((a_start, a_end), (b_start, b_end)) = (a.span(side), b.span(side));
+        let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+        if end - start < opening.width - EPSILON {
+            warn!(
+                "Skipping doorway between {} and {}, which share less than its width of wall",
+                a.name, b.name
+            );
+            return None;
+        }
+        let half_width = opening.width / 2.0;
+        let centre =
+            ((start + end) / 2.0 + opening.offset).clamp(start + half_width, end - half_width);
+
+        let bottom = a.centre.y + opening.sill;
+        let ceilings = (a.centre.y + a.height).min(b.centre.y + b.height);
+        let top = opening
+            .height
+            .map_or(ceilings, |height| a.centre.y + height);
+        let cut = |room: &Room| {
+            let (room_start, room_end) = room.span(side);
+            Opening {
+                width: opening.width,
+                height: Some(top - room.centre.y),
+                sill: bottom - room.centre.y,
+                offset: centre - (room_start + room_end) / 2.0,
+            }
+        };
+        Some([(from, side, cut(a)), (to, side.opposite(), cut(b))])
+    }
+
+    /// The floor, ceiling and wall sections of each room.
+    pub fn pieces(&self) -> Vec<Vec<ArchitecturePiece>> {
+        self.rooms
+            .iter()
+            .zip(self.openings())
+            .map(|(room, openings)| self.room_pieces(room, &openings))
+            .collect()
+    }
+
+    fn room_pieces(&self, room: &Room, openings: &[(WallSide, Opening)]) -> Vec<ArchitecturePiece> {
+        let mut pieces = Vec::new();
+        let slab = Vec3::new(room.size.x, self.slab_thickness, room.size.y);
+        if room.floor {
+            pieces.push(ArchitecturePiece {
+                name: "Floor".to_string(),
+                kind: PieceKind::Floor,
+                size: slab,
+                translation: Vec3::ZERO,
+            });
+        }
+        if room.ceiling {
+            pieces.push(ArchitecturePiece {
+                name: "Ceiling".to_string(),
+                kind: PieceKind::Ceiling,
+                size: slab,
+                translation: Vec3::Y * room.height,
+            });
+        }
+
+        for side in WallSide::ALL {
+            let side_openings: Vec<Opening> = openings
+                .iter()
+                .filter(|(opening_side, _)| *opening_side == side)
+                .map(|(_, opening)| *opening)
+                .collect();
+            let sections = self.wall_sections(room, side, &side_openings);
+            let count = sections.len();
+            pieces.extend(
+                sections
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (size, translation))| {
+                        let name = if count == 1 {
+                            format!("{} Wall", side.name())
+                        } else {
+                            format!("{} Wall {}", side.name(), i.saturating_add(1))
+                        };
+                        ArchitecturePiece {
+                            name,
+                            kind: PieceKind::Wall(side),
+                            size,
+                            translation,
+                        }
+                    }),
+            );
+        }
+        pieces
+    }
+
+    /// Splits a wall into solid cuboids around its openings, returning each one's size and
+    /// translation.
+    fn wall_sections(
+        &self,
+        room: &Room,
+        side: WallSide,
+        openings: &[Opening],
+    ) -> Vec<(Vec3, Vec3)> {
+        let length = if side.along_x() {
+            room.size.x
+        } else {
+            room.size.y
+        };
+        let (start, end) = (-length / 2.0, length / 2.0);
+        let height = room.height;
+
+        // Each opening as a rectangle (left, right, bottom, top) within the wall
+        let holes: Vec<(f32, f32, f32, f32)> = openings
+            .iter()
+            .map(|opening| {
+                let half_width = opening.width / 2.0;
+                let bottom = opening.sill.clamp(0.0, height);
+                let top = opening.height.unwrap_or(height).clamp(bottom, height);
+                (
+                    (opening.offset - half_width).max(start),
+                    (opening.offset + half_width).min(end),
+                    bottom,
+                    top,
+                )
+            })
+            .filter(|(left, right, bottom, top)| right - left > EPSILON && top - bottom > EPSILON)
+            .collect();
+
+        // Cut the wall into columns at the sides of every hole, then each column into the solid
+        // spans between the holes crossing it, merging neighbouring columns with the same spans
+        let mut cuts = vec![start, end];
+        for (left, right, ..) in &holes {
+            cuts.extend([*left, *right]);
+        }
+        cuts.sort_by(f32::total_cmp);
+        cuts.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+
+        let mut columns: Vec<(f32, f32, Vec<(f32, f32)>)> = Vec::new();
+        for pair in cuts.windows(2) {
+            let (left, right) = (pair[0], pair[1]);
+            let middle = (left + right) / 2.0;
+            let mut crossing: Vec<(f32, f32)> = holes
+                .iter()
+                .filter(|(hole_left, hole_right, ..)| *hole_left <= middle && middle <= *hole_right)
+                .map(|(.., bottom, top)| (*bottom, *top))
+                .collect();
+            crossing.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut spans = Vec::new();
+            let mut cursor = 0.0;
+            for (bottom, top) in crossing {
+                if bottom > cursor + EPSILON {
+                    spans.push((cursor, bottom));
+                }
+                cursor = f32::max(cursor, top);
+            }
+            if cursor < height - EPSILON {
+                spans.push((cursor, height));
+            }
+
+            match columns.last_mut() {
+                Some((_, previous_right, previous_spans)) if *previous_spans == spans => {
+                    *previous_right = right;
+                }
+                _ => columns.push((left, right, spans)),
+            }
+        }
+
+        let thickness = self.wall_thickness;
+        let inset = match side {
+            WallSide::North => -room.size.y / 2.0 + thickness / 2.0,
+            WallSide::South => room.size.y / 2.0 - thickness / 2.0,
+            WallSide::East => room.size.x / 2.0 - thickness / 2.0,
+            WallSide::West => -room.size.x / 2.0 + thickness / 2.0,
+        };
+        columns
+            .into_iter()
+            .flat_map(|(left, right, spans)| {
+                spans.into_iter().map(move |(bottom, top)| {
+                    let (along, middle) = (right - left, (left + right) / 2.0);
+                    let y = (bottom + top) / 2.0;
+                    if side.along_x() {
+                        (
+                            Vec3::new(along, top - bottom, thickness),
+                            Vec3::new(middle, y, inset),
+                        )
+                    } else {
+                        (
+                            Vec3::new(thickness, top - bottom, along),
+                            Vec3::new(inset, y, middle),
+                        )
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// A prefab of every room, each holding its floor, ceiling and wall sections.
+    pub fn to_prefab(&self, materials: &ArchitectureMaterials) -> Prefab {
+        Prefab::new(self.name.clone()).with_children(self.rooms.iter().zip(self.pieces()).map(
+            |(room, pieces)| {
+                Prefab::new(room.name.clone())
+                    .with_translation(room.centre)
+                    .with_children(pieces.into_iter().map(|piece| {
+                        let material = match piece.kind {
+                            PieceKind::Floor => &materials.floor,
+                            PieceKind::Ceiling => &materials.ceiling,
+                            PieceKind::Wall(_) => &materials.wall,
+                        };
+                        let node = Prefab::cuboid(piece.name, piece.size)
+                            .with_translation(piece.translation)
+                            .with_material_handle(material.clone());
+                        match (piece.kind, room.floor_surface) {
+                            (PieceKind::Floor, Some(surface)) => {
+                                node.with_components(SurfaceProperties::new(surface))
+                            }
+                            _ => node,
+                        }
+                    }))
+            },
+        ))
+    }
+
+    /// Spawns every room, returning the root entity.
+    pub fn spawn(&self, commands: &mut Commands, materials: &ArchitectureMaterials) -> Entity {
+        self.to_prefab(materials).spawn(commands)
+    }
+}
@@ -0,0 +1,200 @@
+//! Attract mode that flies the camera along a showcase path while the game sits idle.
+//!
+//! Scenes opt in by inserting an [`AttractMode`] resource with a looping camera path. Once the game
+//! has been paused for `idle_timeout` seconds without any input, the camera starts touring the
+//! scene; any key, mouse or gamepad input hands control back to the pause screen.
+
+use bevy::math::cubic_splines::{CubicCardinalSpline, CubicCurve, CyclicCubicGenerator};
+use bevy::prelude::*;
+
+use crate::controls::InputActivity;
+use crate::firstsight::{PlayerCamera, PlayerCameraSet};
+use crate::state::GameState;
+
+pub struct AttractModePlugin;
+
+impl Plugin for AttractModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AttractState>()
+            .init_resource::<IdleTimer>()
+            .add_systems(
+                Update,
+                (
+                    detect_input,
+                    start_attract_mode
+                        .run_if(in_state(GameState::Paused).and(in_state(AttractState::Inactive))),
+                )
+                    .chain()
+                    .run_if(resource_exists::<AttractMode>),
+            )
+            .add_systems(OnEnter(AttractState::Playing), build_attract_path)
+            .add_systems(OnExit(AttractState::Playing), restore_camera)
+            .add_systems(OnEnter(GameState::Paused), reset_idle_timer)
+            .add_systems(OnExit(GameState::Paused), stop_attract_mode)
+            .add_systems(
+                PostUpdate,
+                follow_attract_path
+                    .run_if(in_state(AttractState::Playing))
+                    .after(PlayerCameraSet)
+                    .before(TransformSystems::Propagate),
+            );
+    }
+}
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AttractState {
+    #[default]
+    Inactive,
+    Playing,
+}
+
+/// A point on the attract mode camera path.
+#[derive(Debug, Clone, Copy)]
+pub struct AttractWaypoint {
+    pub position: Vec3,
+    pub look_at: Vec3,
+}
+
+impl AttractWaypoint {
+    pub fn new(position: Vec3, look_at: Vec3) -> Self {
+        Self { position, look_at }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct AttractMode {
+    /// Seconds without input on the pause screen before the tour starts.
+    pub idle_timeout: f32,
+    /// Waypoints visited in order, looping back to the first.
+    pub waypoints: Vec<AttractWaypoint>,
+    /// Time taken to travel between consecutive waypoints.
+    pub seconds_per_waypoint: f32,
+}
+
+impl AttractMode {
+    pub fn new(waypoints: impl IntoIterator<Item = AttractWaypoint>) -> Self {
+        Self {
+            idle_timeout: 60.0,
+            waypoints: waypoints.into_iter().collect(),
+            seconds_per_waypoint: 6.0,
+        }
+    }
+
+    pub fn with_idle_timeout(mut self, idle_timeout: f32) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    pub fn with_seconds_per_waypoint(mut self, seconds_per_waypoint: f32) -> Self {
+        self.seconds_per_waypoint = seconds_per_waypoint;
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+struct IdleTimer {
+    idle_seconds: f32,
+}
+
+/// The curves being followed, and where the camera was before the tour took over.
+#[derive(Resource)]
+struct AttractPlayback {
+    positions: CubicCurve<Vec3>,
+    targets: CubicCurve<Vec3>,
+    elapsed: f32,
+    saved_camera: Transform,
+}
+
+fn detect_input(
+    time: Res<Time>,
+    input: InputActivity,
+    state: Res<State<AttractState>>,
+    mut idle: ResMut<IdleTimer>,
+    mut next_state: ResMut<NextState<AttractState>>,
+) {
+    if !input.any() {
+        idle.idle_seconds += time.delta_secs();
+        return;
+    }
+    idle.idle_seconds = 0.0;
+    if *state.get() == AttractState::Playing {
+        next_state.set(AttractState::Inactive);
+    }
+}
+
+fn start_attract_mode(
+    attract: Res<AttractMode>,
+    idle: Res<IdleTimer>,
+    mut next_state: ResMut<NextState<AttractState>>,
+) {
+    if idle.idle_seconds >= attract.idle_timeout && attract.waypoints.len() >= 2 {
+        next_state.set(AttractState::Playing);
+    }
+}
+
+fn reset_idle_timer(mut idle: ResMut<IdleTimer>) {
+    idle.idle_seconds = 0.0;
+}
+
+fn stop_attract_mode(mut next_state: ResMut<NextState<AttractState>>) {
+    next_state.set(AttractState::Inactive);
+}
+
+fn build_attract_path(
+    mut commands: Commands,
+    attract: Res<AttractMode>,
+    camera: Single<&Transform, With<PlayerCamera>>,
+    mut next_state: ResMut<NextState<AttractState>>,
+) {
+    let positions = attract.waypoints.iter().map(|waypoint| waypoint.position);
+    let targets = attract.waypoints.iter().map(|waypoint| waypoint.look_at);
+    let curves = CubicCardinalSpline::new_catmull_rom(positions)
+        .to_curve_cyclic()
+        .ok()
+        .zip(
+            CubicCardinalSpline::new_catmull_rom(targets)
+                .to_curve_cyclic()
+                .ok(),
+        );
+    let Some((positions, targets)) = curves else {
+        warn!("Attract mode needs at least two waypoints");
+        next_state.set(AttractState::Inactive);
+        return;
+    };
+
+    commands.insert_resource(AttractPlayback {
+        positions,
+        targets,
+        elapsed: 0.0,
+        saved_camera: **camera,
+    });
+}
+
+fn follow_attract_path(
+    time: Res<Time>,
+    attract: Res<AttractMode>,
+    playback: Option<ResMut<AttractPlayback>>,
+    mut camera: Single<&mut Transform, With<PlayerCamera>>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+    playback.elapsed += time.delta_secs();
+
+    let segments = playback.positions.segments().len() as f32;
+    let t = (playback.elapsed / attract.seconds_per_waypoint.max(f32::EPSILON)) % segments;
+    let position = playback.positions.position(t);
+    let target = playback.targets.position(t);
+    **camera = Transform::from_translation(position).looking_at(target, Vec3::Y);
+}
+
+fn restore_camera(
+    mut commands: Commands,
+    playback: Option<Res<AttractPlayback>>,
+    mut camera: Single<&mut Transform, With<PlayerCamera>>,
+) {
+    if let Some(playback) = playback {
+        **camera = playback.saved_camera;
+    }
+    commands.remove_resource::<AttractPlayback>();
+}
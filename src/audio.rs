@@ -0,0 +1,737 @@
+//! Footstep sounds, sounds placed around the scene, and ambient loops.
+//!
+//! Each [`Footstep`] plays a random clip, with some pitch variance so repeated steps don't sound
+//! identical. Clips come from a [`SurfaceAudio`] on the ground that was stepped on, or its nearest
+//! ancestor with one, and otherwise from the [`SurfaceResponses`] entry for its [`SurfaceKind`].
+//! Any surface kind left without sounds in the table gets a few synthesised ones, so every scene
+//! has footsteps without shipping audio files. Set a response's volume to 0 to silence it.
+//!
+//! A [`SpatialAudioEmitter`] loops a sound from its entity's position. It plays at full volume up
+//! to [`SpatialAudioEmitter::min_distance`] from the player camera's [`SpatialListener`], fades out
+//! towards [`SpatialAudioEmitter::max_distance`], and is panned towards whichever side it's on. An
+//! [`AmbientLoop`] plays everywhere at the same volume, fading in when it's added. Emitters carry
+//! further inside an [`AmbienceZone`] with reverb, by its [`ReverbPreset::wet`] share. Scenes
+//! without recorded ambience can make loops of shaped noise with a [`LoopRecipe`]. Emitters and
+//! loops with a caption are listed in [`SoundCaptions`] while they can be heard. Below an
+//! [`Underwater`] surface, both are muffled by the camera's [`WaterDepth`].
+//!
+//! [`AmbienceZone`]: crate::ambience::AmbienceZone
+//! [`ReverbPreset::wet`]: crate::ambience::ReverbPreset::wet
+//! [`Underwater`]: crate::underwater::Underwater
+
+use std::f32::consts::TAU;
+
+use bevy::audio::{SpatialScale, Volume};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::ambience::ActiveAmbience;
+use crate::captions::SoundCaptions;
+use crate::footsteps::{Footstep, SurfaceKind, SurfaceResponses};
+use crate::rng::SplitMix64;
+use crate::underwater::WaterDepth;
+
+pub struct FootstepAudioPlugin;
+
+impl Plugin for FootstepAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SynthesisedFootsteps>()
+            .add_systems(
+                Update,
+                fill_missing_footstep_sounds.run_if(resource_changed::<SurfaceResponses>),
+            )
+            .add_observer(play_footstep);
+    }
+}
+
+pub struct SpatialAudioPlugin;
+
+impl Plugin for SpatialAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(spawn_emitter_playback)
+            .add_observer(spawn_ambient_playback)
+            .add_systems(Update, (attenuate_emitters, fade_in_ambient_loops));
+    }
+}
+
+const SAMPLE_RATE: u32 = 22_050;
+/// Synthesised clips per surface kind.
+const VARIANTS: u64 = 4;
+/// Time taken for an [`AmbientLoop`] to fade in.
+const AMBIENT_FADE_SECONDS: f32 = 3.0;
+/// Emitters quieter than this, as a fraction of full volume, aren't captioned.
+const CAPTION_GAIN: f32 = 0.05;
+
+/// Footstep clips for a ground entity, in place of those for its [`SurfaceKind`].
+#[derive(Component, Debug, Clone)]
+pub struct SurfaceAudio {
+    /// Clips to pick from at random for each step.
+    pub clips: Vec<Handle<AudioSource>>,
+    pub volume: f32,
+    /// Maximum playback speed deviation either side of 1.0.
+    pub pitch_variance: f32,
+}
+
+impl SurfaceAudio {
+    pub fn new(clips: Vec<Handle<AudioSource>>) -> Self {
+        Self {
+            clips,
+            volume: 0.6,
+            pitch_variance: 0.1,
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_pitch_variance(mut self, pitch_variance: f32) -> Self {
+        self.pitch_variance = pitch_variance;
+        self
+    }
+}
+
+/// Clips already synthesised, so replacing the table doesn't make them again.
+#[derive(Resource, Default)]
+struct SynthesisedFootsteps(HashMap<SurfaceKind, Vec<Handle<AudioSource>>>);
+
+fn fill_missing_footstep_sounds(
+    mut responses: ResMut<SurfaceResponses>,
+    mut synthesised: ResMut<SynthesisedFootsteps>,
+    mut sources: ResMut<Assets<AudioSource>>,
+) {
+    for kind in SurfaceKind::ALL {
+        // Checked first, so a table that's already complete isn't marked as changed
+        if responses
+            .get(kind)
+            .is_some_and(|response| !response.sounds.is_empty())
+        {
+            continue;
+        }
+        let clips = synthesised.0.entry(kind).or_insert_with(|| {
+            (0..VARIANTS)
+                .map(|variant| sources.add(synthesise_footstep(kind, variant)))
+                .collect()
+        });
+        responses.get_mut(kind).sounds = clips.clone();
+    }
+}
+
+fn play_footstep(
+    footstep: On<Footstep>,
+    mut commands: Commands,
+    responses: Res<SurfaceResponses>,
+    surfaces: Query<&SurfaceAudio>,
+    parents: Query<&ChildOf>,
+    mut rng: Local<Option<SplitMix64>>,
+) {
+    let surface_audio = std::iter::once(footstep.ground)
+        .chain(parents.iter_ancestors(footstep.ground))
+        .find_map(|entity| surfaces.get(entity).ok());
+    let (clips, volume, pitch_variance) = match surface_audio {
+        Some(audio) => (&audio.clips, audio.volume, audio.pitch_variance),
+        None => {
+            let Some(response) = responses.get(footstep.kind) else {
+                return;
+            };
+            (&response.sounds, response.volume, response.pitch_variance)
+        }
+    };
+    if clips.is_empty() || volume <= 0.0 {
+        return;
+    }
+
+    let rng = rng.get_or_insert_with(|| SplitMix64::new(0x57E9));
+    let clip = clips[rng.index(clips.len())].clone();
+    let pitch = 1.0 + rng.range_f32(-pitch_variance, pitch_variance);
+    commands.spawn((
+        Name::new("Footstep sound"),
+        Transform::from_translation(footstep.position),
+        AudioPlayer::new(clip),
+        PlaybackSettings::DESPAWN
+            .with_spatial(true)
+            .with_volume(Volume::Linear(volume))
+            .with_speed(pitch),
+    ));
+}
+
+/// A looping sound heard from this entity's position.
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct SpatialAudioEmitter {
+    pub clip: Handle<AudioSource>,
+    pub volume: f32,
+    /// Distance from the listener within which the emitter plays at full volume.
+    pub min_distance: f32,
+    /// Distance from the listener at which the emitter has faded to silence.
+    pub max_distance: f32,
+    /// What it sounds like, e.g. "waves lapping", captioned while it can be heard.
+    pub caption: Option<String>,
+}
+
+impl SpatialAudioEmitter {
+    pub fn new(clip: Handle<AudioSource>) -> Self {
+        Self {
+            clip,
+            volume: 1.0,
+            min_distance: 2.0,
+            max_distance: 25.0,
+            caption: None,
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_range(mut self, min_distance: f32, max_distance: f32) -> Self {
+        self.min_distance = min_distance.max(0.0);
+        self.max_distance = max_distance.max(self.min_distance + f32::EPSILON);
+        self
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Volume heard from `distance` away. Past `min_distance` the direct sound falls off
+    /// quadratically, and the `wet` share of it reverberating around the space linearly.
+    fn gain(&self, distance: f32, wet: f32) -> f32 {
+        let range = (self.max_distance - self.min_distance).max(f32::EPSILON);
+        let falloff = (1.0 - (distance - self.min_distance) / range).clamp(0.0, 1.0);
+        self.volume * (falloff * falloff * (1.0 - wet) + falloff * wet)
+    }
+}
+
+/// A looping sound heard at the same volume everywhere, such as room tone or the sea.
+#[derive(Component, Debug, Clone)]
+pub struct AmbientLoop {
+    pub clip: Handle<AudioSource>,
+    pub volume: f32,
+    /// What it sounds like, e.g. "rain", captioned for as long as it plays.
+    pub caption: Option<String>,
+}
+
+impl AmbientLoop {
+    pub fn new(clip: Handle<AudioSource>) -> Self {
+        Self {
+            clip,
+            volume: 0.5,
+            caption: None,
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+}
+
+/// Playback of a [`SpatialAudioEmitter`]'s clip, as a child of the emitter.
+#[derive(Component)]
+struct EmitterPlayback {
+    emitter: Entity,
+}
+
+/// Playback of an [`AmbientLoop`]'s clip, as a child of the entity with the loop.
+#[derive(Component)]
+struct AmbientPlayback {
+    ambient_loop: Entity,
+    level: f32,
+}
+
+fn spawn_emitter_playback(
+    add: On<Add, SpatialAudioEmitter>,
+    mut commands: Commands,
+    emitters: Query<&SpatialAudioEmitter>,
+) {
+    let Ok(emitter) = emitters.get(add.entity) else {
+        return;
+    };
+    // Scaling positions down so the whole range is within one unit keeps the audio backend's own
+    // distance attenuation out of the way of ours, while still panning by direction
+    let settings = PlaybackSettings {
+        spatial_scale: Some(SpatialScale::new(1.0 / emitter.max_distance)),
+        ..PlaybackSettings::LOOP
+            .with_spatial(true)
+            .with_volume(Volume::SILENT)
+    };
+    let playback = commands
+        .spawn((
+            Name::new("Emitter playback"),
+            EmitterPlayback {
+                emitter: add.entity,
+            },
+            Transform::default(),
+            AudioPlayer::new(emitter.clip.clone()),
+            settings,
+        ))
+        .id();
+    commands.entity(add.entity).add_child(playback);
+}
+
+fn spawn_ambient_playback(
+    add: On<Add, AmbientLoop>,
+    mut commands: Commands,
+    ambient_loops: Query<&AmbientLoop>,
+) {
+    let Ok(ambient_loop) = ambient_loops.get(add.entity) else {
+        return;
+    };
+    let playback = commands
+        .spawn((
+            Name::new("Ambient loop playback"),
+            AmbientPlayback {
+                ambient_loop: add.entity,
+                level: 0.0,
+            },
+            AudioPlayer::new(ambient_loop.clip.clone()),
+            PlaybackSettings::LOOP.with_volume(Volume::SILENT),
+        ))
+        .id();
+    commands.entity(add.entity).add_child(playback);
+}
+
+fn attenuate_emitters(
+    mut sound_captions: ResMut<SoundCaptions>,
+    ambience: Option<Res<ActiveAmbience>>,
+    water_depth: Option<Res<WaterDepth>>,
+    listener: Option<Single<&GlobalTransform, With<SpatialListener>>>,
+    emitters: Query<(&SpatialAudioEmitter, &GlobalTransform)>,
+    mut playbacks: Query<(&EmitterPlayback, &mut AudioSink)>,
+) {
+    let Some(listener) = listener else {
+        return;
+    };
+    let listener_position = listener.translation();
+    let wet = ambience.map_or(0.0, |ambience| ambience.reverb.wet());
+    let water_depth = water_depth.map(|depth| *depth).unwrap_or_default();
+    for (playback, mut sink) in &mut playbacks {
+        let Ok((emitter, transform)) = emitters.get(playback.emitter) else {
+            continue;
+        };
+        let position = transform.translation();
+        let gain =
+            emitter.gain(position.distance(listener_position), wet) * water_depth.muffled_volume();
+        sink.set_volume(Volume::Linear(gain));
+        sink.set_speed(water_depth.muffled_speed());
+        if let Some(caption) = emitter.caption.as_ref().filter(|_| gain >= CAPTION_GAIN) {
+            sound_captions.heard(caption, Some(position));
+        }
+    }
+}
+
+fn fade_in_ambient_loops(
+    time: Res<Time>,
+    mut sound_captions: ResMut<SoundCaptions>,
+    water_depth: Option<Res<WaterDepth>>,
+    ambient_loops: Query<&AmbientLoop>,
+    mut playbacks: Query<(&mut AmbientPlayback, &mut AudioSink)>,
+) {
+    let step = time.delta_secs() / AMBIENT_FADE_SECONDS;
+    let water_depth = water_depth.map(|depth| *depth).unwrap_or_default();
+    for (mut playback, mut sink) in &mut playbacks {
+        let Ok(ambient_loop) = ambient_loops.get(playback.ambient_loop) else {
+            continue;
+        };
+        playback.level = (playback.level + step).min(1.0);
+        sink.set_volume(Volume::Linear(
+            playback.level * ambient_loop.volume * water_depth.muffled_volume(),
+        ));
+        sink.set_speed(water_depth.muffled_speed());
+        if let Some(caption) = ambient_loop
+            .caption
+            .as_ref()
+            .filter(|_| ambient_loop.volume > 0.0)
+        {
+            sound_captions.heard(caption, None);
+        }
+    }
+}
+
+/// A seamlessly looping bed of filtered noise, for scenes without recorded ambience.
+///
+/// The noise slowly swells and recedes, optionally over a steady hum.
+#[derive(Debug, Clone)]
+pub struct LoopRecipe {
+    /// Loop length, in seconds.
+    pub length: f32,
+    /// One-pole low-pass coefficient for the noise, from 0 (a low rumble) to 1 (a hiss).
+    pub brightness: f32,
+    /// How much the noise swells and recedes over the loop, from 0 (steady) to 1.
+    pub swell: f32,
+    /// Frequency and amplitude of a hum under the noise.
+    pub hum: Option<(f32, f32)>,
+}
+
+impl LoopRecipe {
+    pub fn new(length: f32) -> Self {
+        Self {
+            length: length.max(1.0),
+            brightness: 0.1,
+            swell: 0.3,
+            hum: None,
+        }
+    }
+
+    pub fn with_brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness.clamp(0.001, 1.0);
+        self
+    }
+
+    pub fn with_swell(mut self, swell: f32) -> Self {
+        self.swell = swell.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_hum(mut self, frequency: f32, amplitude: f32) -> Self {
+        self.hum = Some((frequency, amplitude));
+        self
+    }
+
+    /// Renders the loop, with `seed` picking the noise so loops from the same recipe differ.
+    pub fn synthesise(&self, seed: u64) -> AudioSource {
+        let count = (self.length * SAMPLE_RATE as f32) as usize;
+        // Rendered a little long, so the overrun can be crossfaded into the start
+        let fade = count / 8;
+        let mut rng = SplitMix64::new(seed);
+        let mut filtered = 0.0;
+        let mut noise: Vec<f32> = (0..count + fade)
+            .map(|_| {
+                filtered += (rng.range_f32(-1.0, 1.0) - filtered) * self.brightness;
+                filtered
+            })
+            .collect();
+        let (head, overrun) = noise.split_at_mut(count);
+        for (i, (start, end)) in head.iter_mut().zip(overrun.iter()).enumerate() {
+            // Equal power, as the two ends are uncorrelated
+            let blend = i as f32 / fade as f32;
+            *start = *start * blend.sqrt() + end * (1.0 - blend).sqrt();
+        }
+        noise.truncate(count);
+
+        let peak = noise.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let scale = if peak > 0.0 { 1.0 / peak } else { 0.0 };
+        // Whole numbers of cycles over the loop, so the swell and hum line up at the seam
+        let hum = self.hum.map(|(frequency, amplitude)| {
+            (
+                (frequency * self.length).round().max(1.0) / self.length,
+                amplitude,
+            )
+        });
+        let mut samples: Vec<f32> = noise
+            .iter()
+            .enumerate()
+            .map(|(i, noise)| {
+                let phase = i as f32 / count as f32;
+                let swell = 1.0 - self.swell * 0.5 * (1.0 - (TAU * 2.0 * phase).cos());
+                let mut sample = noise * scale * swell;
+                if let Some((frequency, amplitude)) = hum {
+                    let t = i as f32 / SAMPLE_RATE as f32;
+                    sample += (TAU * frequency * t).sin() * amplitude;
+                }
+                sample
+            })
+            .collect();
+
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        if peak > 0.0 {
+            for sample in &mut samples {
+                *sample *= 0.8 / peak;
+            }
+        }
+        AudioSource {
+            bytes: wav(&samples).into(),
+        }
+    }
+}
+
+/// A creature call for scenes without recordings: a tone gliding between two pitches, warbling with
+/// vibrato, over a breath of noise.
+#[derive(Debug, Clone)]
+pub struct CallRecipe {
+    /// Call length, in seconds.
+    pub length: f32,
+    /// Pitch at the start of the call, in Hz.
+    pub start_frequency: f32,
+    /// Pitch at the end of the call, in Hz.
+    pub end_frequency: f32,
+    /// Vibrato rate in Hz, and depth as a fraction of the pitch.
+    pub vibrato: (f32, f32),
+    /// Strength of the second and third harmonics, from 0 for a pure whistle to 1 for a reedy call.
+    pub harmonics: f32,
+    /// How much breathy noise is mixed in, from 0 to 1.
+    pub breath: f32,
+}
+
+impl CallRecipe {
+    pub fn new(length: f32, start_frequency: f32, end_frequency: f32) -> Self {
+        Self {
+            length: length.max(0.05),
+            start_frequency,
+            end_frequency,
+            vibrato: (5.0, 0.0),
+            harmonics: 0.3,
+            breath: 0.1,
+        }
+    }
+
+    pub fn with_vibrato(mut self, rate: f32, depth: f32) -> Self {
+        self.vibrato = (rate.max(0.0), depth.clamp(0.0, 0.5));
+        self
+    }
+
+    pub fn with_harmonics(mut self, harmonics: f32) -> Self {
+        self.harmonics = harmonics.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_breath(mut self, breath: f32) -> Self {
+        self.breath = breath.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Renders the call, with `seed` nudging its pitch and picking the noise so calls from the
+    /// same recipe differ.
+    pub fn synthesise(&self, seed: u64) -> AudioSource {
+        let mut rng = SplitMix64::new(seed);
+        let detune = rng.range_f32(0.95, 1.05);
+        let count = (self.length * SAMPLE_RATE as f32) as usize;
+        let (rate, depth) = self.vibrato;
+        let attack = (self.length * 0.2).min(0.03);
+        let release = self.length * 0.4;
+        let mut phase = 0.0;
+        let mut filtered = 0.0;
+        let mut samples: Vec<f32> = (0..count)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let along = t / self.length;
+                // Eases between the pitches, so the glide starts and ends gently
+                let glide = along * along * (3.0 - 2.0 * along);
+                let frequency = self.start_frequency.lerp(self.end_frequency, glide)
+                    * detune
+                    * (1.0 + depth * (TAU * rate * t).sin());
+                phase = (phase + TAU * frequency / SAMPLE_RATE as f32) % TAU;
+                let tone = phase.sin()
+                    + self.harmonics * (phase * 2.0).sin() * 0.5
+                    + self.harmonics * (phase * 3.0).sin() * 0.25;
+                filtered += (rng.range_f32(-1.0, 1.0) - filtered) * 0.3;
+                let envelope = (t / attack).min(1.0) * ((self.length - t) / release).min(1.0);
+                (tone * (1.0 - self.breath) + filtered * self.breath * 2.0) * envelope
+            })
+            .collect();
+
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        if peak > 0.0 {
+            for sample in &mut samples {
+                *sample *= 0.8 / peak;
+            }
+        }
+        AudioSource {
+            bytes: wav(&samples).into(),
+        }
+    }
+}
+
+/// Recipe for a synthesised footstep: a burst of filtered noise, optional crackle, and a few
+/// decaying tones for surfaces that ring.
+struct StepRecipe {
+    /// Clip length, in seconds.
+    length: f32,
+    noise: f32,
+    /// One-pole low-pass coefficient for the noise, from 0 (muffled) to 1 (unfiltered).
+    brightness: f32,
+    /// Time constant the noise dies away with, in seconds.
+    decay: f32,
+    /// Chance per sample of a grain of crackle starting, for crunchy surfaces.
+    crackle: f32,
+    /// Frequency, amplitude and decay time constant of each tone.
+    tones: &'static [(f32, f32, f32)],
+}
+
+impl StepRecipe {
+    fn for_kind(kind: SurfaceKind) -> Self {
+        match kind {
+            SurfaceKind::Stone => Self {
+                length: 0.15,
+                noise: 0.6,
+                brightness: 0.45,
+                decay: 0.018,
+                crackle: 0.0,
+                tones: &[(900.0, 0.08, 0.03)],
+            },
+            // A soft, gritty crunch
+            SurfaceKind::Sand => Self {
+                length: 0.22,
+                noise: 0.6,
+                brightness: 0.08,
+                decay: 0.07,
+                crackle: 0.004,
+                tones: &[],
+            },
+            // A hollow knock
+            SurfaceKind::Wood => Self {
+                length: 0.18,
+                noise: 0.35,
+                brightness: 0.2,
+                decay: 0.03,
+                crackle: 0.0,
+                tones: &[(140.0, 0.6, 0.05), (310.0, 0.25, 0.03)],
+            },
+            // A hard click with a faint ring
+            SurfaceKind::Marble => Self {
+                length: 0.15,
+                noise: 0.5,
+                brightness: 0.7,
+                decay: 0.012,
+                crackle: 0.0,
+                tones: &[(2200.0, 0.12, 0.05)],
+            },
+            // A clank with inharmonic partials
+            SurfaceKind::Metal => Self {
+                length: 0.4,
+                noise: 0.3,
+                brightness: 0.6,
+                decay: 0.01,
+                crackle: 0.0,
+                tones: &[
+                    (420.0, 0.3, 0.25),
+                    (1130.0, 0.2, 0.18),
+                    (2010.0, 0.12, 0.12),
+                ],
+            },
+            // Brittle snapping
+            SurfaceKind::Coral => Self {
+                length: 0.16,
+                noise: 0.2,
+                brightness: 0.5,
+                decay: 0.04,
+                crackle: 0.02,
+                tones: &[],
+            },
+        }
+    }
+}
+
+/// A footstep clip for `kind`, with `variant` seeding the noise so variants differ.
+fn synthesise_footstep(kind: SurfaceKind, variant: u64) -> AudioSource {
+    let recipe = StepRecipe::for_kind(kind);
+    let mut rng = SplitMix64::new(((kind as u64) << 8) | variant);
+    let count = (recipe.length * SAMPLE_RATE as f32) as usize;
+    let mut samples = Vec::with_capacity(count);
+    let mut filtered = 0.0;
+    let mut grain = 0.0;
+    for i in 0..count {
+        let t = i as f32 / SAMPLE_RATE as f32;
+        filtered += (rng.range_f32(-1.0, 1.0) - filtered) * recipe.brightness;
+        let mut sample = filtered * recipe.noise * (-t / recipe.decay).exp();
+
+        if rng.next_f32() < recipe.crackle {
+            grain = rng.range_f32(0.3, 1.0) * (-3.0 * t / recipe.length).exp();
+        }
+        grain *= 0.92;
+        sample += grain * rng.range_f32(-1.0, 1.0);
+
+        for &(frequency, amplitude, decay) in recipe.tones {
+            sample += (TAU * frequency * t).sin() * amplitude * (-t / decay).exp();
+        }
+        // A few milliseconds of attack, so the clip doesn't start with a pop
+        samples.push(sample * (t / 0.003).min(1.0));
+    }
+
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak > 0.0 {
+        for sample in &mut samples {
+            *sample *= 0.8 / peak;
+        }
+    }
+    AudioSource {
+        bytes: wav(&samples).into(),
+    }
+}
+
+/// A short mechanical click, like a switch snapping over.
+pub(crate) fn synthesise_click() -> AudioSource {
+    let mut rng = SplitMix64::new(0xC11C);
+    let count = (0.04 * SAMPLE_RATE as f32) as usize;
+    let samples = (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let snap = rng.range_f32(-1.0, 1.0) * (-t / 0.002).exp();
+            let body = (TAU * 2_400.0 * t).sin() * 0.5 * (-t / 0.008).exp();
+            (snap + body) * 0.7
+        })
+        .collect::<Vec<_>>();
+    AudioSource {
+        bytes: wav(&samples).into(),
+    }
+}
+
+/// A struck metal tube ringing at `frequency`, with the inharmonic partials of a free bar dying
+/// away faster the higher they are.
+pub(crate) fn synthesise_chime(frequency: f32) -> AudioSource {
+    const PARTIALS: [(f32, f32, f32); 4] = [
+        (1.0, 1.0, 0.9),
+        (2.756, 0.5, 0.45),
+        (5.404, 0.25, 0.2),
+        (8.933, 0.12, 0.1),
+    ];
+    let count = (2.0 * SAMPLE_RATE as f32) as usize;
+    let mut samples: Vec<f32> = (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let ring: f32 = PARTIALS
+                .iter()
+                // Partials past the Nyquist frequency would alias into odd low tones
+                .filter(|(ratio, _, _)| frequency * ratio < SAMPLE_RATE as f32 / 2.0)
+                .map(|&(ratio, amplitude, decay)| {
+                    (TAU * frequency * ratio * t).sin() * amplitude * (-t / decay).exp()
+                })
+                .sum();
+            ring * (t / 0.003).min(1.0)
+        })
+        .collect();
+
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak > 0.0 {
+        for sample in &mut samples {
+            *sample *= 0.8 / peak;
+        }
+    }
+    AudioSource {
+        bytes: wav(&samples).into(),
+    }
+}
+
+/// Encodes mono samples as a 16-bit PCM WAV file.
+fn wav(samples: &[f32]) -> Vec<u8> {
+    let data_len = u32::try_from(samples.len().saturating_mul(2)).unwrap_or(u32::MAX);
+    let mut bytes = Vec::with_capacity(44 + samples.len() * 2);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&data_len.saturating_add(36).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, mono
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    // Byte rate, block alignment and bits per sample
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
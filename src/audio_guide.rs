@@ -0,0 +1,372 @@
+//! An audio guide for numbered exhibits, separate from any dialogue system.
+//!
+//! Inserting an [`AudioGuide`] resource turns the guide on. Visitors dial an [`Exhibit`]'s number
+//! with the number keys and confirm with Enter, or step through the exhibits in order with the next
+//! and previous bindings, and confirming without dialling plays the nearest exhibit. Narration
+//! plays the exhibit's clip if it has one, and shows its text as a subtitle either way, so text-only
+//! exhibits can be voiced by a text-to-speech integration observing [`NarrationStarted`]. A panel
+//! in the corner shows what's being dialled, the nearest exhibit and the narration.
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::controls::{Binding, ControlsInput};
+use crate::player::Player;
+use crate::state::PausableSystems;
+
+pub struct AudioGuidePlugin;
+
+impl Plugin for AudioGuidePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GuideState>()
+            .add_systems(
+                Update,
+                (
+                    find_nearest_exhibit,
+                    handle_guide_input,
+                    finish_narration,
+                    update_guide_panel,
+                )
+                    .chain()
+                    .in_set(PausableSystems)
+                    .run_if(resource_exists::<AudioGuide>),
+            )
+            .add_systems(
+                Update,
+                spawn_guide_panel.run_if(resource_added::<AudioGuide>),
+            );
+    }
+}
+
+/// Most digits that can be dialled.
+const MAX_DIGITS: usize = 3;
+
+/// Settings for the audio guide, which is only active while this resource exists.
+#[derive(Resource, Debug, Clone)]
+pub struct AudioGuide {
+    /// Plays the dialled exhibit, or the nearest one if nothing's been dialled.
+    pub play: Binding,
+    /// Clears the dialled number, or stops the narration if there isn't one.
+    pub stop: Binding,
+    /// Plays the exhibit with the next number up.
+    pub next: Binding,
+    /// Plays the exhibit with the next number down.
+    pub previous: Binding,
+    pub volume: f32,
+    /// Exhibits further than this from the player aren't counted as nearby.
+    pub nearby_range: f32,
+    /// Words per second, to time how long subtitles stay up for exhibits without a clip.
+    pub reading_speed: f32,
+}
+
+impl Default for AudioGuide {
+    fn default() -> Self {
+        Self {
+            play: Binding::keys([KeyCode::Enter, KeyCode::NumpadEnter]),
+            stop: Binding::keys([KeyCode::Backspace]),
+            next: Binding::keys([KeyCode::BracketRight])
+                .with_gamepad([GamepadButton::RightTrigger]),
+            previous: Binding::keys([KeyCode::BracketLeft])
+                .with_gamepad([GamepadButton::LeftTrigger]),
+            volume: 1.0,
+            nearby_range: 6.0,
+            reading_speed: 2.5,
+        }
+    }
+}
+
+/// Something visitors can hear about by dialling its number into the [`AudioGuide`].
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct Exhibit {
+    pub number: u32,
+    pub title: String,
+    /// What the narration says, shown as a subtitle and ready for text-to-speech.
+    pub text: String,
+    /// Recorded narration, if there is one.
+    pub clip: Option<Handle<AudioSource>>,
+}
+
+impl Exhibit {
+    pub fn new(number: u32, title: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            number,
+            title: title.into(),
+            text: text.into(),
+            clip: None,
+        }
+    }
+
+    pub fn with_clip(mut self, clip: Handle<AudioSource>) -> Self {
+        self.clip = Some(clip);
+        self
+    }
+}
+
+/// Triggered on an [`Exhibit`] when its narration starts.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct NarrationStarted {
+    pub entity: Entity,
+    pub number: u32,
+    pub text: String,
+}
+
+/// Triggered on an [`Exhibit`] when its narration ends, or is stopped or replaced.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct NarrationFinished {
+    pub entity: Entity,
+}
+
+#[derive(Resource, Default)]
+struct GuideState {
+    dialled: String,
+    nearest: Option<Entity>,
+    narrating: Option<Narration>,
+    /// Shown on the panel in place of the narration, e.g. for numbers with no exhibit.
+    notice: Option<String>,
+}
+
+struct Narration {
+    exhibit: Entity,
+    /// Entity playing the clip, if the exhibit has one.
+    voice: Option<Entity>,
+    /// Whether the voice has been seen playing, so its despawn can be told apart from it not
+    /// having spawned yet.
+    heard: bool,
+    /// Seconds left to show the subtitle for, if there's no clip.
+    remaining: Option<f32>,
+}
+
+#[derive(Component)]
+struct GuideVoice;
+
+#[derive(Component)]
+struct GuidePanelText;
+
+fn find_nearest_exhibit(
+    guide: Res<AudioGuide>,
+    mut state: ResMut<GuideState>,
+    player: Single<&GlobalTransform, With<Player>>,
+    exhibits: Query<(Entity, &GlobalTransform), With<Exhibit>>,
+) {
+    let position = player.translation();
+    let nearest = exhibits
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation().distance(position)))
+        .filter(|(_, distance)| *distance <= guide.nearby_range)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity);
+    if state.nearest != nearest {
+        state.nearest = nearest;
+    }
+}
+
+fn digit(key: KeyCode) -> Option<char> {
+    let digit = match key {
+        KeyCode::Digit0 | KeyCode::Numpad0 => '0',
+        KeyCode::Digit1 | KeyCode::Numpad1 => '1',
+        KeyCode::Digit2 | KeyCode::Numpad2 => '2',
+        KeyCode::Digit3 | KeyCode::Numpad3 => '3',
+        KeyCode::Digit4 | KeyCode::Numpad4 => '4',
+        KeyCode::Digit5 | KeyCode::Numpad5 => '5',
+        KeyCode::Digit6 | KeyCode::Numpad6 => '6',
+        KeyCode::Digit7 | KeyCode::Numpad7 => '7',
+        KeyCode::Digit8 | KeyCode::Numpad8 => '8',
+        KeyCode::Digit9 | KeyCode::Numpad9 => '9',
+        _ => return None,
+    };
+    Some(digit)
+}
+
+fn handle_guide_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    input: ControlsInput,
+    guide: Res<AudioGuide>,
+    mut state: ResMut<GuideState>,
+    exhibits: Query<(Entity, &Exhibit)>,
+) {
+    // Digits typed into something else aren't dialled
+    let typed = keys
+        .get_just_pressed()
+        .filter(|_| !input.keyboard_captured());
+    for key in typed {
+        let Some(digit) = digit(*key) else {
+            continue;
+        };
+        if state.dialled.len() < MAX_DIGITS {
+            state.dialled.push(digit);
+            state.notice = None;
+        }
+    }
+
+    let current = state
+        .narrating
+        .as_ref()
+        .map(|narration| narration.exhibit)
+        .or(state.nearest)
+        .and_then(|entity| exhibits.get(entity).ok())
+        .map(|(_, exhibit)| exhibit.number);
+    let target = if input.just_pressed(&guide.play) {
+        let dialled = std::mem::take(&mut state.dialled);
+        if dialled.is_empty() {
+            state.nearest
+        } else {
+            let number = dialled.parse::<u32>().unwrap_or_default();
+            let found = exhibits
+                .iter()
+                .find(|(_, exhibit)| exhibit.number == number)
+                .map(|(entity, _)| entity);
+            if found.is_none() {
+                state.notice = Some(format!("No exhibit {number}"));
+            }
+            found
+        }
+    } else if input.just_pressed(&guide.next) {
+        exhibits
+            .iter()
+            .filter(|(_, exhibit)| current.is_none_or(|current| exhibit.number > current))
+            .min_by_key(|(_, exhibit)| exhibit.number)
+            .map(|(entity, _)| entity)
+    } else if input.just_pressed(&guide.previous) {
+        exhibits
+            .iter()
+            .filter(|(_, exhibit)| current.is_none_or(|current| exhibit.number < current))
+            .max_by_key(|(_, exhibit)| exhibit.number)
+            .map(|(entity, _)| entity)
+    } else {
+        if input.just_pressed(&guide.stop) {
+            if !state.dialled.is_empty() {
+                state.dialled.clear();
+            } else {
+                stop_narration(&mut commands, &mut state);
+            }
+        }
+        return;
+    };
+    let Some((entity, exhibit)) = target.and_then(|entity| exhibits.get(entity).ok()) else {
+        return;
+    };
+
+    stop_narration(&mut commands, &mut state);
+    let voice = exhibit.clip.clone().map(|clip| {
+        commands
+            .spawn((
+                Name::new("Audio guide narration"),
+                GuideVoice,
+                AudioPlayer::new(clip),
+                PlaybackSettings::DESPAWN.with_volume(Volume::Linear(guide.volume)),
+            ))
+            .id()
+    });
+    let words = exhibit.text.split_whitespace().count() as f32;
+    state.narrating = Some(Narration {
+        exhibit: entity,
+        voice,
+        heard: false,
+        remaining: voice
+            .is_none()
+            .then(|| (words / guide.reading_speed.max(0.1)).max(2.0)),
+    });
+    state.notice = None;
+    commands.trigger(NarrationStarted {
+        entity,
+        number: exhibit.number,
+        text: exhibit.text.clone(),
+    });
+}
+
+fn stop_narration(commands: &mut Commands, state: &mut GuideState) {
+    let Some(narration) = state.narrating.take() else {
+        return;
+    };
+    if let Some(voice) = narration.voice {
+        commands.entity(voice).try_despawn();
+    }
+    commands.trigger(NarrationFinished {
+        entity: narration.exhibit,
+    });
+}
+
+fn finish_narration(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut state: ResMut<GuideState>,
+    voices: Query<(), With<GuideVoice>>,
+) {
+    let Some(narration) = state.narrating.as_mut() else {
+        return;
+    };
+    let finished = match (narration.voice, narration.remaining.as_mut()) {
+        (Some(voice), _) => {
+            let playing = voices.contains(voice);
+            let finished = narration.heard && !playing;
+            narration.heard |= playing;
+            finished
+        }
+        (None, Some(remaining)) => {
+            *remaining -= time.delta_secs();
+            *remaining <= 0.0
+        }
+        (None, None) => true,
+    };
+    if finished {
+        stop_narration(&mut commands, &mut state);
+    }
+}
+
+fn spawn_guide_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Audio guide"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            max_width: Val::Px(420.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Pickable::IGNORE,
+        children![(
+            GuidePanelText,
+            Text::default(),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+        )],
+    ));
+}
+
+fn update_guide_panel(
+    state: Res<GuideState>,
+    exhibits: Query<&Exhibit>,
+    mut text: Single<&mut Text, With<GuidePanelText>>,
+) {
+    if !state.is_changed() && !text.is_added() {
+        return;
+    }
+    let mut lines = vec![format!("🎧 Audio guide: {:_<MAX_DIGITS$}", state.dialled)];
+    if let Some(notice) = &state.notice {
+        lines.push(notice.clone());
+    }
+    match state
+        .narrating
+        .as_ref()
+        .and_then(|narration| exhibits.get(narration.exhibit).ok())
+    {
+        Some(exhibit) => {
+            lines.push(format!("▶ {}. {}", exhibit.number, exhibit.title));
+            lines.push(exhibit.text.clone());
+        }
+        None => match state.nearest.and_then(|entity| exhibits.get(entity).ok()) {
+            Some(exhibit) => lines.push(format!(
+                "Nearby: {}. {} (Enter to play)",
+                exhibit.number, exhibit.title
+            )),
+            None => lines.push("Dial an exhibit number, or [ and ] to browse".to_string()),
+        },
+    }
+    text.0 = lines.join("\n");
+}
@@ -0,0 +1,244 @@
+//! Optional visible player avatar loaded from a skinned glTF.
+//!
+//! Insert a [`PlayerAvatar`] on the [`Player`] entity to spawn the model as a child. Once the
+//! scene is ready its animations are wired up, and the idle/walk/jump clips are crossfaded based on
+//! the controller's velocity. The avatar is only shown while the camera is in third-person mode, so
+//! it doesn't block the first-person view.
+
+use std::time::Duration;
+
+use avian3d::math::AsF32;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+
+use crate::firstsight::PlayerCamera;
+use crate::player::Player;
+use crate::thirdperson::ThirdPersonCamera;
+
+pub struct AvatarPlugin;
+
+impl Plugin for AvatarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(spawn_avatar).add_systems(
+            Update,
+            (
+                update_avatar_visibility,
+                update_avatar_motion,
+                face_movement_direction,
+            ),
+        );
+    }
+}
+
+const CROSSFADE: Duration = Duration::from_millis(200);
+/// Horizontal speed above which the walk animation plays.
+const WALK_THRESHOLD: f32 = 0.5;
+/// Vertical speed above which the avatar is considered to be jumping or falling.
+const AIRBORNE_THRESHOLD: f32 = 1.5;
+/// How quickly the avatar turns to face its movement direction, higher is snappier.
+const TURN_SMOOTHING: f32 = 10.;
+
+/// Describes the glTF to use as the player's avatar and which of its animations to play.
+#[derive(Component, Clone)]
+pub struct PlayerAvatar {
+    pub path: String,
+    /// Index of each animation within the glTF.
+    pub idle_animation: usize,
+    pub walk_animation: usize,
+    pub jump_animation: usize,
+    /// Offset from the controller's centre to the model's origin, usually down to the feet.
+    pub offset: Vec3,
+}
+
+impl PlayerAvatar {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            idle_animation: 0,
+            walk_animation: 1,
+            jump_animation: 2,
+            offset: Vec3::ZERO,
+        }
+    }
+
+    pub fn with_animations(mut self, idle: usize, walk: usize, jump: usize) -> Self {
+        self.idle_animation = idle;
+        self.walk_animation = walk;
+        self.jump_animation = jump;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AvatarMotion {
+    Idle,
+    Walk,
+    Jump,
+}
+
+/// Attached to the spawned avatar model.
+#[derive(Component)]
+struct AvatarModel {
+    graph: Handle<AnimationGraph>,
+    idle: AnimationNodeIndex,
+    walk: AnimationNodeIndex,
+    jump: AnimationNodeIndex,
+    animation_player: Option<Entity>,
+    motion: Option<AvatarMotion>,
+}
+
+impl AvatarModel {
+    fn node(&self, motion: AvatarMotion) -> AnimationNodeIndex {
+        match motion {
+            AvatarMotion::Idle => self.idle,
+            AvatarMotion::Walk => self.walk,
+            AvatarMotion::Jump => self.jump,
+        }
+    }
+}
+
+fn spawn_avatar(
+    add: On<Add, PlayerAvatar>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    avatars: Query<&PlayerAvatar, With<Player>>,
+) {
+    let Ok(avatar) = avatars.get(add.entity) else {
+        return;
+    };
+
+    let load_animation = |index: usize| {
+        asset_server.load(GltfAssetLabel::Animation(index).from_asset(avatar.path.clone()))
+    };
+    let (graph, nodes) = AnimationGraph::from_clips([
+        load_animation(avatar.idle_animation),
+        load_animation(avatar.walk_animation),
+        load_animation(avatar.jump_animation),
+    ]);
+    let scene = asset_server.load(GltfAssetLabel::Scene(0).from_asset(avatar.path.clone()));
+
+    let model = commands
+        .spawn((
+            Name::new("Player avatar"),
+            SceneRoot(scene),
+            Transform::from_translation(avatar.offset),
+            Visibility::Hidden,
+            AvatarModel {
+                graph: graphs.add(graph),
+                idle: nodes[0],
+                walk: nodes[1],
+                jump: nodes[2],
+                animation_player: None,
+                motion: None,
+            },
+        ))
+        .observe(setup_avatar_animations)
+        .id();
+    commands.entity(add.entity).add_child(model);
+}
+
+/// Hooks the animation graph up to the [`AnimationPlayer`] the glTF scene spawned.
+fn setup_avatar_animations(
+    ready: On<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    animation_players: Query<(), With<AnimationPlayer>>,
+    mut models: Query<&mut AvatarModel>,
+) {
+    let Ok(mut model) = models.get_mut(ready.entity) else {
+        return;
+    };
+    let Some(animation_player) = children
+        .iter_descendants(ready.entity)
+        .find(|descendant| animation_players.contains(*descendant))
+    else {
+        warn!("Player avatar scene has no AnimationPlayer, it won't be animated");
+        return;
+    };
+
+    commands.entity(animation_player).insert((
+        AnimationGraphHandle(model.graph.clone()),
+        AnimationTransitions::new(),
+    ));
+    model.animation_player = Some(animation_player);
+}
+
+fn update_avatar_visibility(
+    camera: Single<Has<ThirdPersonCamera>, With<PlayerCamera>>,
+    mut models: Query<&mut Visibility, With<AvatarModel>>,
+) {
+    let visibility = if *camera {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut model_visibility in models.iter_mut() {
+        model_visibility.set_if_neq(visibility);
+    }
+}
+
+/// Picks the animation matching the controller's velocity, crossfading when it changes.
+fn update_avatar_motion(
+    player: Single<(&LinearVelocity, &Children), With<Player>>,
+    mut models: Query<&mut AvatarModel>,
+    mut animation_players: Query<(&mut AnimationPlayer, &mut AnimationTransitions)>,
+) {
+    let (velocity, children) = player.into_inner();
+    let velocity = velocity.0.f32();
+    let motion = if velocity.y.abs() > AIRBORNE_THRESHOLD {
+        AvatarMotion::Jump
+    } else if velocity.with_y(0.0).length() > WALK_THRESHOLD {
+        AvatarMotion::Walk
+    } else {
+        AvatarMotion::Idle
+    };
+
+    for child in children.iter() {
+        let Ok(mut model) = models.get_mut(child) else {
+            continue;
+        };
+        if model.motion == Some(motion) {
+            continue;
+        }
+        let Some(Ok((mut animation_player, mut transitions))) = model
+            .animation_player
+            .map(|entity| animation_players.get_mut(entity))
+        else {
+            continue;
+        };
+        let playing = transitions.play(&mut animation_player, model.node(motion), CROSSFADE);
+        if motion != AvatarMotion::Jump {
+            playing.repeat();
+        }
+        model.motion = Some(motion);
+    }
+}
+
+/// Turns the avatar towards the direction the player is moving in.
+fn face_movement_direction(
+    time: Res<Time>,
+    player: Single<(&LinearVelocity, &Children), With<Player>>,
+    mut models: Query<&mut Transform, With<AvatarModel>>,
+) {
+    let (velocity, children) = player.into_inner();
+    let horizontal = velocity.0.f32().with_y(0.0);
+    if horizontal.length() <= WALK_THRESHOLD {
+        return;
+    }
+    let target = Transform::default()
+        .looking_to(horizontal, Vec3::Y)
+        .rotation;
+    let t = 1.0 - (-TURN_SMOOTHING * time.delta_secs()).exp();
+    for child in children.iter() {
+        if let Ok(mut transform) = models.get_mut(child) {
+            transform.rotation = transform.rotation.slerp(target, t);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Climate parameters shared by procedural generators, so one scene can come in several variants.
+//!
+//! A [`BiomeParams`] resource describes the climate as temperature, humidity and alienness.
+//! Generators read it as they build a scene and adjust what they make relative to how the scene
+//! was authored: terrain relief with [`BiomeParams::relief`], how much grows with
+//! [`BiomeParams::vegetation`], surface colours with [`BiomeParams::tint`], and weather with
+//! [`BiomeParams::weather`] for the sky and fog and [`BiomeParams::storminess`] for a
+//! [`SeaState`](crate::sea_state::SeaState). The default biome leaves everything as authored.
+//!
+//! Scenes generate at startup, so the biome has to be set before then, either by inserting the
+//! resource when building the app or with `DIORAMA_BIOME`, which takes a preset name such as
+//! `frozen` or `scorched`, or `temperature,humidity,alienness`.
+
+use std::str::FromStr;
+
+use bevy::prelude::*;
+
+use crate::environment::EnvironmentSettings;
+
+pub struct BiomePlugin;
+
+impl Plugin for BiomePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<BiomeParams>();
+        match std::env::var(BIOME_ENV_VAR).map(|biome| biome.parse::<BiomeParams>()) {
+            Ok(Ok(biome)) => {
+                app.insert_resource(biome);
+            }
+            Ok(Err(e)) => {
+                warn!("Ignoring {BIOME_ENV_VAR}: {e}");
+                app.init_resource::<BiomeParams>();
+            }
+            Err(_) => {
+                app.init_resource::<BiomeParams>();
+            }
+        }
+    }
+}
+
+/// Environment variable holding the biome to generate scenes in.
+const BIOME_ENV_VAR: &str = "DIORAMA_BIOME";
+
+/// Colour frozen surfaces are frosted towards.
+const FROST: LinearRgba = LinearRgba::rgb(0.8, 0.88, 1.0);
+/// Colour scorched surfaces are burnt towards.
+const SCORCH: LinearRgba = LinearRgba::rgb(0.3, 0.09, 0.02);
+
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub struct BiomeParams {
+    /// From -1 for frozen to 1 for scorched, with 0 temperate.
+    pub temperature: f32,
+    /// From 0 for arid to 1 for saturated, with 0.5 as authored.
+    pub humidity: f32,
+    /// From 0 for as authored to 1 for thoroughly strange, with warped shapes and shifted hues.
+    pub alienness: f32,
+}
+
+impl Default for BiomeParams {
+    fn default() -> Self {
+        Self::temperate()
+    }
+}
+
+impl BiomeParams {
+    pub fn new(temperature: f32, humidity: f32, alienness: f32) -> Self {
+        Self {
+            temperature: temperature.clamp(-1.0, 1.0),
+            humidity: humidity.clamp(0.0, 1.0),
+            alienness: alienness.clamp(0.0, 1.0),
+        }
+    }
+
+    /// The scene as authored.
+    pub fn temperate() -> Self {
+        Self::new(0.0, 0.5, 0.0)
+    }
+
+    pub fn frozen() -> Self {
+        Self::new(-0.9, 0.4, 0.0)
+    }
+
+    pub fn scorched() -> Self {
+        Self::new(0.9, 0.05, 0.2)
+    }
+
+    pub fn lush() -> Self {
+        Self::new(0.3, 0.9, 0.0)
+    }
+
+    pub fn otherworldly() -> Self {
+        Self::new(0.0, 0.6, 0.9)
+    }
+
+    /// Multiplier on terrain height. Wet climates wear the land down, and stranger ones throw it up
+    /// into crags.
+    pub fn relief(&self) -> f32 {
+        (1.0 + self.alienness * 0.8 - (self.humidity - 0.5) * 0.4).max(0.2)
+    }
+
+    /// Multiplier on how many plants, corals and the like to scatter, needing both warmth and
+    /// water to thrive.
+    pub fn vegetation(&self) -> f32 {
+        let warmth = 1.0 - self.temperature.abs() * 0.8;
+        (warmth * self.humidity * 2.0).clamp(0.0, 2.0)
+    }
+
+    /// Scales `count` scattered things by [`BiomeParams::vegetation`].
+    pub fn scatter(&self, count: u32) -> u32 {
+        (count as f32 * self.vegetation()).round() as u32
+    }
+
+    /// Colours `color` for the climate: frosted in the cold, burnt in the heat and hue-shifted the
+    /// more alien it is. Alpha is kept.
+    pub fn tint(&self, color: Color) -> Color {
+        let alpha = color.alpha();
+        let mut linear = LinearRgba::from(color);
+        linear = linear.mix(&FROST, (-self.temperature).max(0.0) * 0.7);
+        linear = linear.mix(&SCORCH, self.temperature.max(0.0) * 0.6);
+        let shifted = Oklcha::from(linear).rotate_hue(self.alienness * 150.0);
+        Color::from(shifted).with_alpha(alpha)
+    }
+
+    /// Multiplier on how far can be seen through fog. Humid air closes in, and dry air opens out.
+    pub fn visibility(&self) -> f32 {
+        1.5 - self.humidity
+    }
+
+    /// Adjusts a scene's environment for the climate, tinting its sky, light and fog and
+    /// thickening or thinning the fog.
+    pub fn weather(&self, mut settings: EnvironmentSettings) -> EnvironmentSettings {
+        settings.clear_color = self.tint(settings.clear_color);
+        settings.ambient_light.color = self.tint(settings.ambient_light.color);
+        if let Some(fog) = &mut settings.fog {
+            fog.color = self.tint(fog.color);
+            let visibility = self.visibility();
+            fog.falloff = match fog.falloff {
+                FogFalloff::Linear { start, end } => FogFalloff::Linear {
+                    start: start * visibility,
+                    end: end * visibility,
+                },
+                FogFalloff::Exponential { density } => FogFalloff::Exponential {
+                    density: density / visibility,
+                },
+                FogFalloff::ExponentialSquared { density } => FogFalloff::ExponentialSquared {
+                    density: density / visibility,
+                },
+                FogFalloff::Atmospheric {
+                    extinction,
+                    inscattering,
+                } => FogFalloff::Atmospheric {
+                    extinction: extinction / visibility,
+                    inscattering: inscattering / visibility,
+                },
+            };
+        }
+        settings
+    }
+
+    /// How stormy weather the scene would have as authored, from 0 to 1, becomes in this climate.
+    /// Wet air feeds storms, and ice calms the sea.
+    pub fn storminess(&self, storminess: f32) -> f32 {
+        let ice = (-self.temperature).max(0.0) * 0.6;
+        (storminess * (0.5 + self.humidity) * (1.0 - ice)).clamp(0.0, 1.0)
+    }
+}
+
+impl FromStr for BiomeParams {
+    type Err = String;
+
+    fn from_str(biome: &str) -> Result<Self, Self::Err> {
+        match biome.trim() {
+            "temperate" => return Ok(Self::temperate()),
+            "frozen" => return Ok(Self::frozen()),
+            "scorched" => return Ok(Self::scorched()),
+            "lush" => return Ok(Self::lush()),
+            "otherworldly" => return Ok(Self::otherworldly()),
+            _ => {}
+        }
+        let values = biome
+            .split(',')
+            .map(|value| value.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("invalid biome {biome:?}: {e}"))?;
+        let &[temperature, humidity, alienness] = values.as_slice() else {
+            return Err(format!(
+                "unknown biome {biome:?}, expected a preset or temperature,humidity,alienness"
+            ));
+        };
+        Ok(Self::new(temperature, humidity, alienness))
+    }
+}
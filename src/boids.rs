@@ -0,0 +1,301 @@
+//! Flocking for schools of fish, flights of birds and the like.
+//!
+//! A [`Flock`] on an entity holds the flocking parameters for every [`Boid`] that belongs to it:
+//! how far boids see each other, how strongly they keep apart, line up and close ranks, and how
+//! fast they fly. Boids only line up and close ranks with their own flock, but keep apart from
+//! every boid nearby, looking them up in a spatial hash so large flocks stay cheap. A flock can
+//! also keep its boids within a bounds box, pull them towards home, and have them steer around
+//! colliders ahead of them. Boids turn to face the way they're going.
+
+#![allow(clippy::useless_conversion)]
+
+use avian3d::math::AsF32;
+use avian3d::prelude::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::game_time::GameTime;
+use crate::state::PausableSystems;
+
+pub struct BoidsPlugin;
+
+impl Plugin for BoidsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Flock>()
+            .register_type::<Boid>()
+            .add_systems(Update, flock_boids.in_set(PausableSystems));
+    }
+}
+
+/// How hard boids steer back into their flock's bounds once outside.
+const BOUNDS_STEERING: f32 = 2.0;
+/// How hard boids steer away from a collider right in front of them.
+const OBSTACLE_STEERING: f32 = 4.0;
+
+/// A box boids are kept inside, relative to their [`Flock`]'s entity.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct FlockBounds {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct Flock {
+    /// How far a boid notices others.
+    pub perception_radius: f32,
+    /// How close a boid lets others get before steering away.
+    pub separation_radius: f32,
+    pub separation_weight: f32,
+    pub alignment_weight: f32,
+    pub cohesion_weight: f32,
+    /// Units per second.
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// How quickly boids take up a new heading.
+    pub turn_speed: f32,
+    /// How strongly boids are pulled towards the middle of the bounds, or the flock's entity
+    /// without any.
+    pub homing: f32,
+    pub bounds: Option<FlockBounds>,
+    /// How far ahead boids look for colliders to steer around, or 0 not to.
+    pub obstacle_lookahead: f32,
+}
+
+impl Default for Flock {
+    fn default() -> Self {
+        Self::new(2.0, 6.0)
+    }
+}
+
+impl Flock {
+    pub fn new(min_speed: f32, max_speed: f32) -> Self {
+        Self {
+            perception_radius: 8.0,
+            separation_radius: 1.5,
+            separation_weight: 1.5,
+            alignment_weight: 1.0,
+            cohesion_weight: 1.0,
+            min_speed: min_speed.max(0.0),
+            max_speed: max_speed.max(min_speed),
+            turn_speed: 2.5,
+            homing: 0.0,
+            bounds: None,
+            obstacle_lookahead: 0.0,
+        }
+    }
+
+    pub fn with_radii(mut self, perception: f32, separation: f32) -> Self {
+        self.perception_radius = perception.max(0.0);
+        self.separation_radius = separation.max(0.0);
+        self
+    }
+
+    pub fn with_weights(mut self, separation: f32, alignment: f32, cohesion: f32) -> Self {
+        self.separation_weight = separation;
+        self.alignment_weight = alignment;
+        self.cohesion_weight = cohesion;
+        self
+    }
+
+    pub fn with_turn_speed(mut self, turn_speed: f32) -> Self {
+        self.turn_speed = turn_speed.max(0.0);
+        self
+    }
+
+    pub fn with_homing(mut self, homing: f32) -> Self {
+        self.homing = homing.max(0.0);
+        self
+    }
+
+    pub fn with_bounds(mut self, center: Vec3, half_extents: Vec3) -> Self {
+        self.bounds = Some(FlockBounds {
+            center,
+            half_extents: half_extents.abs(),
+        });
+        self
+    }
+
+    pub fn with_obstacle_avoidance(mut self, lookahead: f32) -> Self {
+        self.obstacle_lookahead = lookahead.max(0.0);
+        self
+    }
+}
+
+/// A member of a [`Flock`], moved by it while `flocking`.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct Boid {
+    pub flock: Entity,
+    pub velocity: Vec3,
+    /// Whether the boid flocks, or is left for the scene to move, e.g. while it's off roosting.
+    pub flocking: bool,
+}
+
+impl Boid {
+    pub fn new(flock: Entity, velocity: Vec3) -> Self {
+        Self {
+            flock,
+            velocity,
+            flocking: true,
+        }
+    }
+}
+
+/// Indices of points bucketed into cubic cells, for finding points near each other.
+struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<usize>>,
+}
+
+impl SpatialHash {
+    fn new(cell_size: f32, points: impl IntoIterator<Item = Vec3>) -> Self {
+        let mut hash = Self {
+            cell_size: cell_size.max(0.01),
+            cells: HashMap::default(),
+        };
+        for (index, point) in points.into_iter().enumerate() {
+            hash.cells.entry(hash.cell(point)).or_default().push(index);
+        }
+        hash
+    }
+
+    fn cell(&self, point: Vec3) -> IVec3 {
+        (point / self.cell_size).floor().as_ivec3()
+    }
+
+    /// Indices of points in the cells around `point`, which includes every point within
+    /// `cell_size` of it.
+    fn near(&self, point: Vec3) -> impl Iterator<Item = usize> + '_ {
+        let cell = self.cell(point);
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| IVec3::new(x, y, z))))
+            .filter_map(move |offset| self.cells.get(&(cell + offset)))
+            .flatten()
+            .copied()
+    }
+}
+
+fn flock_boids(
+    time: Res<GameTime>,
+    spatial_query: SpatialQuery,
+    flocks: Query<(&Flock, &GlobalTransform)>,
+    mut boids: Query<(Entity, &mut Transform, &mut Boid)>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    let cell_size = flocks
+        .iter()
+        .map(|(flock, _)| flock.perception_radius)
+        .fold(0.0, f32::max);
+    let others: Vec<(Entity, Vec3, Vec3)> = boids
+        .iter()
+        .filter(|(_, _, boid)| boid.flocking)
+        .map(|(_, transform, boid)| (boid.flock, transform.translation, boid.velocity))
+        .collect();
+    let hash = SpatialHash::new(cell_size, others.iter().map(|&(_, position, _)| position));
+
+    for (entity, mut transform, mut boid) in &mut boids {
+        if !boid.flocking {
+            continue;
+        }
+        let Ok((flock, flock_transform)) = flocks.get(boid.flock) else {
+            continue;
+        };
+        let position = transform.translation;
+        let mut separation = Vec3::ZERO;
+        let mut alignment = Vec3::ZERO;
+        let mut cohesion = Vec3::ZERO;
+        let mut count = 0;
+        for index in hash.near(position) {
+            let (other_flock, other_position, other_velocity) = others[index];
+            let away = position - other_position;
+            let distance = away.length();
+            if distance <= f32::EPSILON || distance > flock.perception_radius {
+                continue;
+            }
+            if distance < flock.separation_radius {
+                separation += away / (distance * distance.max(0.1));
+            }
+            if other_flock == boid.flock {
+                cohesion += other_position;
+                alignment += other_velocity;
+                count += 1;
+            }
+        }
+        if count > 0 {
+            cohesion = cohesion / count as f32 - position;
+            alignment /= count as f32;
+        }
+
+        let mut steering = separation * flock.separation_weight
+            + alignment * flock.alignment_weight
+            + cohesion * flock.cohesion_weight;
+        let home = flock
+            .bounds
+            .map_or(flock_transform.translation(), |bounds| {
+                flock_transform.transform_point(bounds.center)
+            });
+        steering += (home - position) * flock.homing;
+        if let Some(bounds) = flock.bounds {
+            let local = flock_transform
+                .affine()
+                .inverse()
+                .transform_point3(position)
+                - bounds.center;
+            let overshoot = (local.abs() - bounds.half_extents).max(Vec3::ZERO) * local.signum();
+            steering += flock_transform
+                .affine()
+                .transform_vector3(-overshoot)
+                .normalize_or_zero()
+                * BOUNDS_STEERING;
+        }
+        if let Some(avoidance) =
+            avoid_obstacles(&spatial_query, entity, flock, position, boid.velocity)
+        {
+            steering += avoidance;
+        }
+
+        let target = (boid.velocity + steering).normalize_or_zero() * flock.max_speed;
+        boid.velocity = boid.velocity.lerp(target, (dt * flock.turn_speed).min(1.0));
+        let speed = boid.velocity.length();
+        if speed < flock.min_speed {
+            boid.velocity = boid.velocity.normalize_or_zero() * flock.min_speed;
+        } else if speed > flock.max_speed {
+            boid.velocity = boid.velocity.normalize_or_zero() * flock.max_speed;
+        }
+
+        transform.translation += boid.velocity * dt;
+        if boid.velocity.length_squared() > 0.01 {
+            let ahead = transform.translation + boid.velocity;
+            transform.look_at(ahead, Vec3::Y);
+        }
+    }
+}
+
+/// Steering away from a collider in `velocity`'s path, harder the closer it is.
+fn avoid_obstacles(
+    spatial_query: &SpatialQuery,
+    entity: Entity,
+    flock: &Flock,
+    position: Vec3,
+    velocity: Vec3,
+) -> Option<Vec3> {
+    if flock.obstacle_lookahead <= 0.0 {
+        return None;
+    }
+    let heading = Dir3::new(velocity).ok()?;
+    let hit = spatial_query.cast_ray(
+        position.into(),
+        heading,
+        flock.obstacle_lookahead.into(),
+        true,
+        &SpatialQueryFilter::from_excluded_entities([entity]),
+    )?;
+    let closeness = 1.0 - hit.distance.f32() / flock.obstacle_lookahead;
+    Some(hit.normal.f32() * closeness * OBSTACLE_STEERING)
+}
@@ -0,0 +1,434 @@
+//! Captions along the bottom of the screen for speech and sounds, for players who can't hear them.
+//!
+//! Dialogue and gameplay systems push [`Caption`]s onto the [`Captions`] resource, which shows them
+//! one at a time in the order they were pushed, each fading in, staying up for long enough to read
+//! and fading out again. Spoken lines are labelled with their speaker, and sounds are described in
+//! brackets by convention, e.g. "\[distant thunder\]". Captions hold while the game is paused.
+//!
+//! Sounds around the player are better listed in [`SoundCaptions`] instead, in the bottom right
+//! corner, where each stays up while it's heard and for a few seconds after, with an arrow to the
+//! side it's coming from. Emitters, creature voices and sound sculptures with a caption list
+//! themselves there as they play.
+
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::state::PausableSystems;
+
+pub struct CaptionsPlugin;
+
+impl Plugin for CaptionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Captions>()
+            .init_resource::<SoundCaptions>()
+            .add_systems(Startup, (spawn_caption_bar, spawn_sound_caption_list))
+            .add_systems(
+                Update,
+                (
+                    (advance_captions, age_sound_captions).in_set(PausableSystems),
+                    show_caption,
+                    show_sound_captions,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Seconds for a caption to fade in, and again to fade out.
+const FADE_SECONDS: f32 = 0.3;
+/// Captions queued up behind the one showing, beyond which the oldest are dropped so captions
+/// don't fall far behind what they describe.
+const MAX_QUEUED: usize = 8;
+const WORDS_PER_SECOND: f32 = 2.5;
+const SPEAKER_COLOR: Color = Color::srgb(1.0, 0.85, 0.4);
+/// Seconds a sound caption takes to fade out at the end of its linger.
+const SOUND_FADE_SECONDS: f32 = 1.0;
+/// Most sounds listed at once, beyond which the one unheard for longest is dropped.
+const MAX_SOUNDS: usize = 6;
+/// Sounds less than this far either side of straight ahead or behind get no arrow, in radians.
+const AHEAD_ANGLE: f32 = 0.35;
+
+/// A line of speech or a description of a sound.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caption {
+    pub speaker: Option<String>,
+    pub text: String,
+    /// Seconds the caption is up for, including fading in and out.
+    pub duration: f32,
+}
+
+impl Caption {
+    /// A caption up for long enough to read `text` at an unhurried pace.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let words = text.split_whitespace().count() as f32;
+        Self {
+            speaker: None,
+            duration: (1.0 + words / WORDS_PER_SECOND).max(2.0),
+            text,
+        }
+    }
+
+    pub fn with_speaker(mut self, speaker: impl Into<String>) -> Self {
+        self.speaker = Some(speaker.into());
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration.max(FADE_SECONDS * 2.0);
+        self
+    }
+}
+
+/// Captions waiting to be shown, and the one showing.
+#[derive(Resource, Debug)]
+pub struct Captions {
+    /// Whether captions are drawn. They still advance while hidden, so turning them back on picks
+    /// up with what's happening now.
+    pub enabled: bool,
+    queue: VecDeque<Caption>,
+    current: Option<Caption>,
+    elapsed: f32,
+}
+
+impl Default for Captions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            queue: VecDeque::new(),
+            current: None,
+            elapsed: 0.0,
+        }
+    }
+}
+
+impl Captions {
+    /// Queues `caption` to show after any already waiting.
+    pub fn push(&mut self, caption: Caption) {
+        if self.queue.len() >= MAX_QUEUED {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(caption);
+    }
+
+    /// Queues a line spoken by `speaker`.
+    pub fn say(&mut self, speaker: impl Into<String>, text: impl Into<String>) {
+        self.push(Caption::new(text).with_speaker(speaker));
+    }
+
+    /// Queues a description of a sound, which is put in brackets.
+    pub fn describe(&mut self, sound: impl AsRef<str>) {
+        self.push(Caption::new(format!("[{}]", sound.as_ref())));
+    }
+
+    /// The caption showing, if any.
+    pub fn current(&self) -> Option<&Caption> {
+        self.current.as_ref()
+    }
+
+    /// Whether nothing is showing or waiting.
+    pub fn is_empty(&self) -> bool {
+        self.current.is_none() && self.queue.is_empty()
+    }
+
+    /// Takes down the caption showing and drops any waiting.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.current = None;
+        self.elapsed = 0.0;
+    }
+
+    /// How faded in the caption showing is, from 0 to 1.
+    fn opacity(&self) -> f32 {
+        let Some(caption) = &self.current else {
+            return 0.0;
+        };
+        let fade_in = self.elapsed / FADE_SECONDS;
+        let fade_out = (caption.duration - self.elapsed) / FADE_SECONDS;
+        fade_in.min(fade_out).clamp(0.0, 1.0)
+    }
+}
+
+/// Sounds heard around the player recently, listed with which way they're coming from.
+#[derive(Resource, Debug)]
+pub struct SoundCaptions {
+    /// Whether sound captions are drawn. Sounds are still listed while hidden.
+    pub enabled: bool,
+    /// Seconds a sound stays listed after it was last heard, fading out at the end.
+    pub linger: f32,
+    /// In the order they were first heard.
+    sounds: Vec<HeardSound>,
+}
+
+impl Default for SoundCaptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            linger: 3.0,
+            sounds: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HeardSound {
+    text: String,
+    position: Option<Vec3>,
+    since: f32,
+}
+
+impl SoundCaptions {
+    /// Lists `sound` as heard just now from `position`, or all around if `None`. A sound already
+    /// listed keeps its place, and points to where it was heard from this time.
+    pub fn heard(&mut self, sound: impl AsRef<str>, position: Option<Vec3>) {
+        let sound = sound.as_ref();
+        if let Some(heard) = self.sounds.iter_mut().find(|heard| heard.text == sound) {
+            heard.position = position;
+            heard.since = 0.0;
+            return;
+        }
+        if self.sounds.len() >= MAX_SOUNDS {
+            // The sound that's gone unheard longest
+            if let Some(oldest) = (0..self.sounds.len())
+                .max_by(|&a, &b| self.sounds[a].since.total_cmp(&self.sounds[b].since))
+            {
+                self.sounds.remove(oldest);
+            }
+        }
+        self.sounds.push(HeardSound {
+            text: sound.to_string(),
+            position,
+            since: 0.0,
+        });
+    }
+
+    /// Whether no sounds are listed.
+    pub fn is_empty(&self) -> bool {
+        self.sounds.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.sounds.clear();
+    }
+}
+
+#[derive(Component)]
+struct CaptionRoot;
+
+#[derive(Component)]
+struct CaptionBar;
+
+#[derive(Component)]
+struct CaptionSpeaker;
+
+#[derive(Component)]
+struct CaptionText;
+
+fn spawn_caption_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Captions"),
+            CaptionRoot,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Percent(10.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+            Pickable::IGNORE,
+            // Below the pause menu and loading screen
+            GlobalZIndex(i32::MAX - 2),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    CaptionBar,
+                    Node {
+                        max_width: Val::Percent(60.0),
+                        padding: UiRect::axes(Val::Px(14.0), Val::Px(8.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    Pickable::IGNORE,
+                ))
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            CaptionSpeaker,
+                            Text::default(),
+                            TextFont {
+                                font_size: 22.0,
+                                ..default()
+                            },
+                            TextColor(SPEAKER_COLOR),
+                            TextLayout::new_with_justify(Justify::Center),
+                        ))
+                        .with_child((
+                            CaptionText,
+                            TextSpan::default(),
+                            TextFont {
+                                font_size: 22.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                        ));
+                });
+        });
+}
+
+#[derive(Component)]
+struct SoundCaptionRow(usize);
+
+fn spawn_sound_caption_list(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Sound captions"),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Percent(10.0),
+                right: Val::Px(16.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            Pickable::IGNORE,
+            GlobalZIndex(i32::MAX - 2),
+        ))
+        .with_children(|parent| {
+            for row in 0..MAX_SOUNDS {
+                parent.spawn((
+                    SoundCaptionRow(row),
+                    Node {
+                        padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    Text::default(),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    Visibility::Hidden,
+                    Pickable::IGNORE,
+                ));
+            }
+        });
+}
+
+fn advance_captions(time: Res<Time>, mut captions: ResMut<Captions>) {
+    if captions.is_empty() {
+        return;
+    }
+    let captions = &mut *captions;
+    if let Some(caption) = &captions.current {
+        captions.elapsed += time.delta_secs();
+        if captions.elapsed < caption.duration {
+            return;
+        }
+    }
+    captions.current = captions.queue.pop_front();
+    captions.elapsed = 0.0;
+}
+
+fn show_caption(
+    captions: Res<Captions>,
+    mut visibility: Single<&mut Visibility, With<CaptionRoot>>,
+    mut background: Single<&mut BackgroundColor, With<CaptionBar>>,
+    speaker: Single<(&mut Text, &mut TextColor), With<CaptionSpeaker>>,
+    text: Single<(&mut TextSpan, &mut TextColor), (With<CaptionText>, Without<CaptionSpeaker>)>,
+) {
+    let (mut speaker, mut speaker_color) = speaker.into_inner();
+    let (mut text, mut text_color) = text.into_inner();
+    let caption = captions.current().filter(|_| captions.enabled);
+    visibility.set_if_neq(if caption.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    let Some(caption) = caption else {
+        return;
+    };
+
+    let label = caption
+        .speaker
+        .as_ref()
+        .map_or_else(String::new, |speaker| format!("{speaker}: "));
+    if speaker.0 != label {
+        speaker.0 = label;
+    }
+    if text.0 != caption.text {
+        text.0.clone_from(&caption.text);
+    }
+    let opacity = captions.opacity();
+    background.0 = Color::srgba(0.0, 0.0, 0.0, 0.7 * opacity);
+    speaker_color.0 = SPEAKER_COLOR.with_alpha(opacity);
+    text_color.0 = Color::WHITE.with_alpha(opacity);
+}
+
+fn age_sound_captions(time: Res<Time>, mut sound_captions: ResMut<SoundCaptions>) {
+    if sound_captions.is_empty() {
+        return;
+    }
+    let linger = sound_captions.linger;
+    sound_captions.sounds.retain_mut(|heard| {
+        heard.since += time.delta_secs();
+        heard.since < linger
+    });
+}
+
+/// `text` in brackets, with an arrow to the side of `listener` that `position` is on if it's off
+/// to one side.
+fn point_to(text: &str, position: Option<Vec3>, listener: Option<&GlobalTransform>) -> String {
+    let local = position
+        .zip(listener)
+        .map(|(position, listener)| listener.affine().inverse().transform_point3(position));
+    let angle = local.map_or(0.0, |local| local.x.atan2(-local.z));
+    if !(AHEAD_ANGLE..=PI - AHEAD_ANGLE).contains(&angle.abs()) {
+        format!("[{text}]")
+    } else if angle < 0.0 {
+        format!("< [{text}]")
+    } else {
+        format!("[{text}] >")
+    }
+}
+
+fn show_sound_captions(
+    sound_captions: Res<SoundCaptions>,
+    listener: Option<Single<&GlobalTransform, With<SpatialListener>>>,
+    mut rows: Query<(
+        &SoundCaptionRow,
+        &mut Text,
+        &mut TextColor,
+        &mut BackgroundColor,
+        &mut Visibility,
+    )>,
+) {
+    let listener = listener.as_deref().copied();
+    // Newest at the bottom, in the last row
+    let skipped = MAX_SOUNDS - sound_captions.sounds.len();
+    for (row, mut text, mut color, mut background, mut visibility) in &mut rows {
+        let heard = row
+            .0
+            .checked_sub(skipped)
+            .and_then(|index| sound_captions.sounds.get(index))
+            .filter(|_| sound_captions.enabled);
+        let Some(heard) = heard else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        let label = point_to(&heard.text, heard.position, listener);
+        if text.0 != label {
+            text.0 = label;
+        }
+        let opacity = ((sound_captions.linger - heard.since) / SOUND_FADE_SECONDS).clamp(0.0, 1.0);
+        color.0 = Color::WHITE.with_alpha(opacity);
+        background.0 = Color::srgba(0.0, 0.0, 0.0, 0.6 * opacity);
+    }
+}
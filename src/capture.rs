@@ -0,0 +1,238 @@
+//! Screenshots and short animated clips of the primary window, saved to a local directory.
+//!
+//! F12 saves a PNG screenshot and Shift+F12 saves the last few seconds as an animated GIF. Clips
+//! come from a rolling buffer of small frames grabbed at a low frame rate the whole time the app
+//! runs, so something worth sharing can be saved after it's happened. The [`CaptureSettings`]
+//! resource sets where files go, the bindings, and the clip's length, frame rate and width. The
+//! buffer costs a screenshot every few frames, so it's off until turned on with
+//! [`CaptureSettings::with_clip`]. Neither binding works in kiosk mode.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured, save_to_disk};
+use bevy::tasks::futures_lite::future;
+use bevy::tasks::{AsyncComputeTaskPool, IoTaskPool, Task, block_on};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::FilterType;
+use image::{Delay, Frame, RgbaImage};
+
+use crate::controls::{Binding, ControlsInput};
+use crate::kiosk::not_in_kiosk_mode;
+
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CaptureSettings>()
+            .init_resource::<ClipBuffer>()
+            .add_systems(
+                Update,
+                (
+                    handle_capture_input.run_if(not_in_kiosk_mode),
+                    buffer_clip_frames,
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct CaptureSettings {
+    /// Where screenshots and clips are saved, created if it doesn't exist.
+    pub directory: PathBuf,
+    pub screenshot: Binding,
+    pub clip: Binding,
+    /// Length of the rolling clip buffer, or 0, the default, to not buffer at all.
+    pub clip_seconds: f32,
+    pub clip_fps: f32,
+    /// Clip frames are scaled down to this width, keeping their aspect ratio.
+    pub clip_width: u32,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("captures"),
+            screenshot: Binding::keys([KeyCode::F12]),
+            clip: Binding::chord([KeyCode::ShiftLeft, KeyCode::F12]),
+            clip_seconds: 0.0,
+            clip_fps: 12.0,
+            clip_width: 480,
+        }
+    }
+}
+
+impl CaptureSettings {
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = directory.into();
+        self
+    }
+
+    /// Buffers the last `seconds` at `fps`, scaled down to `width`, so Shift+F12 can save them.
+    pub fn with_clip(mut self, seconds: f32, fps: f32, width: u32) -> Self {
+        self.clip_seconds = seconds.max(0.0);
+        self.clip_fps = fps.max(1.0);
+        self.clip_width = width.max(16);
+        self
+    }
+
+    fn clip_frames(&self) -> usize {
+        (self.clip_seconds * self.clip_fps).ceil() as usize
+    }
+}
+
+/// The most recent frames, oldest first, for saving as a clip.
+#[derive(Resource, Default)]
+struct ClipBuffer {
+    frames: VecDeque<RgbaImage>,
+    since_frame: f32,
+    /// Whether a frame has been asked for and not buffered yet, so they don't pile up.
+    pending: bool,
+    /// The last frame captured, being scaled down off the main thread.
+    converting: Option<Task<Option<RgbaImage>>>,
+}
+
+/// A path in `directory` for a new capture, named by the time it was taken.
+fn capture_path(directory: &Path, extension: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(directory)?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    Ok(directory.join(format!("diorama-{millis}.{extension}")))
+}
+
+fn handle_capture_input(
+    mut commands: Commands,
+    input: ControlsInput,
+    settings: Res<CaptureSettings>,
+    buffer: Res<ClipBuffer>,
+) {
+    // The clip chord includes the screenshot key, so it's checked first
+    if input.just_pressed(&settings.clip) {
+        save_clip(&settings, &buffer);
+    } else if input.just_pressed(&settings.screenshot) {
+        match capture_path(&settings.directory, "png") {
+            Ok(path) => {
+                info!("Saving screenshot to {}", path.display());
+                commands
+                    .spawn(Screenshot::primary_window())
+                    .observe(save_to_disk(path));
+            }
+            Err(e) => error!("Failed to save screenshot: {e}"),
+        }
+    }
+}
+
+fn save_clip(settings: &CaptureSettings, buffer: &ClipBuffer) {
+    if buffer.frames.is_empty() {
+        warn!("No clip frames have been captured yet");
+        return;
+    }
+    let path = match capture_path(&settings.directory, "gif") {
+        Ok(path) => path,
+        Err(e) => {
+            error!("Failed to save clip: {e}");
+            return;
+        }
+    };
+    info!(
+        "Saving {} clip frames to {}",
+        buffer.frames.len(),
+        path.display()
+    );
+    let frames: Vec<RgbaImage> = buffer.frames.iter().cloned().collect();
+    let delay = Delay::from_numer_denom_ms(1000, settings.clip_fps.round() as u32);
+    // Encoding takes a while, so it's done off the main thread
+    IoTaskPool::get()
+        .spawn(async move {
+            let result = File::create(&path)
+                .map_err(image::ImageError::IoError)
+                .and_then(|file| {
+                    let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), 10);
+                    encoder.set_repeat(Repeat::Infinite)?;
+                    encoder.encode_frames(
+                        frames
+                            .into_iter()
+                            .map(|frame| Frame::from_parts(frame, 0, 0, delay)),
+                    )
+                });
+            match result {
+                Ok(()) => info!("Saved clip to {}", path.display()),
+                Err(e) => error!("Failed to save clip: {e}"),
+            }
+        })
+        .detach();
+}
+
+fn buffer_clip_frames(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    settings: Res<CaptureSettings>,
+    mut buffer: ResMut<ClipBuffer>,
+) {
+    if settings.clip_seconds <= 0.0 {
+        if !buffer.frames.is_empty() || buffer.pending {
+            buffer.frames.clear();
+            buffer.converting = None;
+            buffer.pending = false;
+        }
+        return;
+    }
+    let converted = buffer
+        .converting
+        .as_mut()
+        .and_then(|task| block_on(future::poll_once(task)));
+    if let Some(frame) = converted {
+        buffer.converting = None;
+        buffer.pending = false;
+        if let Some(frame) = frame {
+            buffer.frames.push_back(frame);
+        }
+        let capacity = settings.clip_frames();
+        while buffer.frames.len() > capacity {
+            buffer.frames.pop_front();
+        }
+    }
+    buffer.since_frame += time.delta_secs();
+    if buffer.pending || buffer.since_frame < 1.0 / settings.clip_fps {
+        return;
+    }
+    buffer.since_frame = 0.0;
+    buffer.pending = true;
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(add_clip_frame);
+}
+
+/// Scales the captured frame down off the main thread, for [`buffer_clip_frames`] to collect.
+fn add_clip_frame(
+    captured: On<ScreenshotCaptured>,
+    settings: Res<CaptureSettings>,
+    mut buffer: ResMut<ClipBuffer>,
+) {
+    let image = captured.image.clone();
+    let clip_width = settings.clip_width;
+    buffer.converting = Some(AsyncComputeTaskPool::get().spawn(async move {
+        let frame = match image.try_into_dynamic() {
+            Ok(frame) => frame.to_rgba8(),
+            Err(e) => {
+                warn!("Couldn't buffer clip frame: {e}");
+                return None;
+            }
+        };
+        let width = clip_width.min(frame.width()).max(1);
+        let height = (frame.height() as f32 * width as f32 / frame.width().max(1) as f32)
+            .round()
+            .max(1.0) as u32;
+        Some(image::imageops::resize(
+            &frame,
+            width,
+            height,
+            FilterType::Triangle,
+        ))
+    }));
+}
@@ -0,0 +1,269 @@
+//! Picking up, carrying and throwing physics objects.
+//!
+//! Pointing at a dynamic rigid body with [`Carryable`] from within its reach and pressing the
+//! interact binding picks it up. It's held in front of the camera on a damped spring, so it still
+//! swings, knocks into things and gets caught on them, and is dropped if it's pulled too far away
+//! from where it's held. Pressing interact again puts it down, and the use item binding throws it,
+//! as the player's hands are full. [`CarryStarted`] and [`CarryEnded`] are triggered on the object
+//! as it goes.
+
+#![allow(clippy::useless_conversion)]
+
+use avian3d::math::{AdjustPrecision, AsF32};
+use avian3d::prelude::*;
+use bevy::picking::pointer::PointerInteraction;
+use bevy::prelude::*;
+
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::firstsight::PlayerCamera;
+use crate::kiosk::KioskReset;
+use crate::player::Player;
+use crate::state::PausableSystems;
+
+pub struct CarryPlugin;
+
+impl Plugin for CarryPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Carryable>()
+            .add_systems(Update, carry_input.in_set(PausableSystems))
+            .add_systems(FixedUpdate, hold_carried)
+            .add_observer(record_carry_home)
+            .add_observer(return_for_next_visitor);
+    }
+}
+
+/// A dynamic rigid body the player can pick up, carry and throw.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Carryable {
+    /// How far in front of the camera it's held, which should clear the player and the object's
+    /// own size.
+    pub hold_distance: f32,
+    /// How hard the spring pulls it towards where it's held, per second squared per unit away.
+    pub stiffness: f32,
+    /// How quickly the spring's swinging dies down, per second.
+    pub damping: f32,
+    /// Speed it's thrown at, added to however it's moving.
+    pub throw_speed: f32,
+    /// Furthest the player can be to pick it up.
+    pub reach: f32,
+    /// How far it can be pulled from where it's held, e.g. when caught behind something, before
+    /// it's dropped.
+    pub break_distance: f32,
+}
+
+impl Default for Carryable {
+    fn default() -> Self {
+        Self {
+            hold_distance: 1.5,
+            stiffness: 150.0,
+            // Critically damped, so it settles without overshooting
+            damping: 2.0 * 150.0_f32.sqrt(),
+            throw_speed: 8.0,
+            reach: 3.0,
+            break_distance: 2.0,
+        }
+    }
+}
+
+impl Carryable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_hold_distance(mut self, hold_distance: f32) -> Self {
+        self.hold_distance = hold_distance.max(0.0);
+        self
+    }
+
+    pub fn with_spring(mut self, stiffness: f32, damping: f32) -> Self {
+        self.stiffness = stiffness.max(0.0);
+        self.damping = damping.max(0.0);
+        self
+    }
+
+    pub fn with_throw_speed(mut self, throw_speed: f32) -> Self {
+        self.throw_speed = throw_speed;
+        self
+    }
+
+    pub fn with_reach(mut self, reach: f32) -> Self {
+        self.reach = reach.max(0.0);
+        self
+    }
+
+    pub fn with_break_distance(mut self, break_distance: f32) -> Self {
+        self.break_distance = break_distance.max(0.0);
+        self
+    }
+}
+
+/// On a [`Carryable`] being carried, pointing at the player carrying it.
+#[derive(Component, Debug)]
+#[relationship(relationship_target = Carrying)]
+pub struct CarriedBy(pub Entity);
+
+/// On the player while they're carrying something, pointing at what they're carrying.
+#[derive(Component, Debug)]
+#[relationship_target(relationship = CarriedBy)]
+pub struct Carrying(Entity);
+
+impl Carrying {
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
+/// Triggered on a [`Carryable`] when the player picks it up.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct CarryStarted {
+    pub entity: Entity,
+}
+
+/// Triggered on a [`Carryable`] when it's put down, thrown or pulled away.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct CarryEnded {
+    pub entity: Entity,
+    pub thrown: bool,
+}
+
+fn carry_input(
+    mut commands: Commands,
+    config: Res<ControlsConfig>,
+    input: ControlsInput,
+    pointers: Query<&PointerInteraction>,
+    parents: Query<&ChildOf>,
+    carryables: Query<(&Carryable, &RigidBody)>,
+    mut velocities: Query<&mut LinearVelocity, With<CarriedBy>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    player: Single<(Entity, Option<&Carrying>), With<Player>>,
+) {
+    let (player, carrying) = *player;
+    if let Some(carried) = carrying.map(Carrying::entity) {
+        let thrown = input.just_pressed(&config.use_item);
+        if !thrown && !input.just_pressed(&config.interact) {
+            return;
+        }
+        if let (true, Ok((carryable, _)), Ok(mut velocity)) =
+            (thrown, carryables.get(carried), velocities.get_mut(carried))
+        {
+            velocity.0 += (camera.forward() * carryable.throw_speed).adjust_precision();
+        }
+        commands.entity(carried).remove::<CarriedBy>();
+        commands.trigger(CarryEnded {
+            entity: carried,
+            thrown,
+        });
+        return;
+    }
+    if !input.just_pressed(&config.interact) {
+        return;
+    }
+
+    // Colliders and meshes are often children of the body
+    let target = pointers
+        .iter()
+        .filter_map(|interaction| interaction.get_nearest_hit())
+        .find_map(|(entity, hit)| {
+            std::iter::once(*entity)
+                .chain(parents.iter_ancestors(*entity))
+                .find_map(|entity| {
+                    carryables
+                        .get(entity)
+                        .ok()
+                        .map(|(carryable, body)| (entity, carryable, body))
+                })
+                .filter(|(_, carryable, body)| body.is_dynamic() && hit.depth <= carryable.reach)
+        });
+    let Some((entity, _, _)) = target else {
+        return;
+    };
+    commands.entity(entity).insert(CarriedBy(player));
+    commands.trigger(CarryStarted { entity });
+}
+
+/// Pulls carried objects towards where they're held in front of the camera, dropping any pulled
+/// too far away.
+fn hold_carried(
+    mut commands: Commands,
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    mut carried: Query<
+        (
+            Entity,
+            &Carryable,
+            &GlobalTransform,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+        ),
+        With<CarriedBy>,
+    >,
+) {
+    if physics_time.is_paused() {
+        return;
+    }
+    let dt = time.delta_secs();
+    for (entity, carryable, transform, mut linear, mut angular) in &mut carried {
+        let target = camera.translation() + camera.forward() * carryable.hold_distance;
+        let offset = target - transform.translation();
+        if offset.length() > carryable.break_distance {
+            commands.entity(entity).remove::<CarriedBy>();
+            commands.trigger(CarryEnded {
+                entity,
+                thrown: false,
+            });
+            continue;
+        }
+        let velocity = linear.0.f32();
+        let acceleration = offset * carryable.stiffness - velocity * carryable.damping;
+        linear.0 = (velocity + acceleration * dt).adjust_precision();
+        // Keep it from spinning wildly in hand
+        angular.0 *= (-carryable.damping * dt).exp().adjust_precision();
+    }
+}
+
+/// Where a [`Carryable`] was spawned, to put it back for the next visitor to an unattended kiosk.
+#[derive(Component)]
+struct CarryHome(Transform);
+
+fn record_carry_home(
+    add: On<Add, Carryable>,
+    mut commands: Commands,
+    transforms: Query<&Transform>,
+) {
+    if let Ok(transform) = transforms.get(add.entity) {
+        commands.entity(add.entity).insert(CarryHome(*transform));
+    }
+}
+
+/// Drops anything being carried and puts every carryable back where it started.
+fn return_for_next_visitor(
+    _reset: On<KioskReset>,
+    mut commands: Commands,
+    mut carryables: Query<(
+        Entity,
+        &CarryHome,
+        &mut Transform,
+        Option<&mut LinearVelocity>,
+        Option<&mut AngularVelocity>,
+        Has<CarriedBy>,
+    )>,
+) {
+    for (entity, home, mut transform, linear, angular, carried) in &mut carryables {
+        if carried {
+            commands.entity(entity).remove::<CarriedBy>();
+            commands.trigger(CarryEnded {
+                entity,
+                thrown: false,
+            });
+        }
+        *transform = home.0;
+        if let Some(mut linear) = linear {
+            *linear = LinearVelocity::ZERO;
+        }
+        if let Some(mut angular) = angular {
+            *angular = AngularVelocity::ZERO;
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Fits directional light shadow cascades to the area that actually needs shadows.
+//!
+//! Bevy's default cascade distances suit neither small rooms nor large open levels: shadows either
+//! stop short of distant geometry or get spread so thin they blur and detach from their casters.
+//! Adding [`ShadowCascadeBounds`] to a [`DirectionalLight`] keeps its cascades sized to the
+//! distance from the camera to the far side of the scene or a configured play area.
+
+use bevy::camera::primitives::Aabb;
+use bevy::light::{CascadeShadowConfig, CascadeShadowConfigBuilder};
+use bevy::prelude::*;
+
+use crate::firstsight::PlayerCamera;
+
+pub struct CascadesPlugin;
+
+impl Plugin for CascadesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SceneBounds>().add_systems(
+            PostUpdate,
+            (update_scene_bounds, fit_shadow_cascades)
+                .chain()
+                .before(TransformSystems::Propagate),
+        );
+    }
+}
+
+const NUM_CASCADES: usize = 4;
+const MIN_SHADOW_DISTANCE: f32 = 10.0;
+/// Cascades are only rebuilt once the required distance drifts by more than this fraction.
+const REFIT_TOLERANCE: f32 = 0.05;
+
+/// Which region a [`DirectionalLight`]'s shadow cascades should cover.
+#[derive(Component, Debug, Clone, Copy)]
+pub enum ShadowCascadeBounds {
+    /// The combined bounds of every mesh in the scene.
+    Scene,
+    /// A fixed box, e.g. the playable area of a large level.
+    PlayArea { center: Vec3, half_extents: Vec3 },
+}
+
+/// Cached world-space bounds of all meshes, recomputed when meshes are added, moved, resized or
+/// removed.
+#[derive(Resource, Default)]
+struct SceneBounds(Option<(Vec3, Vec3)>);
+
+fn update_scene_bounds(
+    mut scene_bounds: ResMut<SceneBounds>,
+    changed: Query<(), (With<Mesh3d>, Or<(Changed<Aabb>, Changed<GlobalTransform>)>)>,
+    mut removed: RemovedComponents<Mesh3d>,
+    lights: Query<&ShadowCascadeBounds>,
+    meshes: Query<(&Aabb, &GlobalTransform), With<Mesh3d>>,
+) {
+    // Always drained, so removals from before a light wanted the scene's bounds aren't seen late
+    let removed = removed.read().count() > 0;
+    let wants_scene = lights
+        .iter()
+        .any(|bounds| matches!(bounds, ShadowCascadeBounds::Scene));
+    let stale = removed || !changed.is_empty() || scene_bounds.0.is_none();
+    if !wants_scene || !stale {
+        return;
+    }
+
+    scene_bounds.0 = meshes
+        .iter()
+        .map(|(aabb, transform)| {
+            let center = transform.transform_point(aabb.center.into());
+            let half_extents: Vec3 = transform
+                .affine()
+                .matrix3
+                .abs()
+                .mul_vec3a(aabb.half_extents)
+                .into();
+            (center - half_extents, center + half_extents)
+        })
+        .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)));
+}
+
+fn fit_shadow_cascades(
+    scene_bounds: Res<SceneBounds>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    mut lights: Query<(&ShadowCascadeBounds, &mut CascadeShadowConfig), With<DirectionalLight>>,
+) {
+    let camera_position = camera.translation();
+    for (bounds, mut config) in lights.iter_mut() {
+        let (min, max) = match *bounds {
+            ShadowCascadeBounds::Scene => match scene_bounds.0 {
+                Some(bounds) => bounds,
+                None => continue,
+            },
+            ShadowCascadeBounds::PlayArea {
+                center,
+                half_extents,
+            } => (center - half_extents, center + half_extents),
+        };
+
+        // Distance to the furthest corner of the bounds, so everything inside stays shadowed
+        let center = (min + max) / 2.0;
+        let far_corner = Vec3::select(camera_position.cmpgt(center), min, max);
+        let maximum_distance = camera_position
+            .distance(far_corner)
+            .max(MIN_SHADOW_DISTANCE);
+
+        let current = config.bounds.last().copied().unwrap_or_default();
+        if (maximum_distance - current).abs() <= current * REFIT_TOLERANCE {
+            continue;
+        }
+
+        *config = CascadeShadowConfigBuilder {
+            num_cascades: NUM_CASCADES,
+            minimum_distance: 0.1,
+            maximum_distance,
+            first_cascade_far_bound: (maximum_distance / 8.0).max(MIN_SHADOW_DISTANCE / 2.0),
+            overlap_proportion: 0.2,
+        }
+        .build();
+    }
+}
@@ -0,0 +1,397 @@
+//! Chests that open when interacted with, giving the player loot.
+//!
+//! [`Chest::spawn`] builds a chest with a hinged lid and an [`Interactable`], which scenes can
+//! replace for a different kind of interaction. Interacting with a locked chest unlocks it if the
+//! [`Inventory`] holds its key, using the key up, and otherwise triggers [`ChestLocked`]. Opening a
+//! chest rolls its [`LootTable`] into the inventory and triggers [`ChestOpened`], while the lid
+//! swings back, a light inside comes up and a burst of sparks flies out. Open chests stay open and
+//! can't be interacted with again.
+
+#![allow(clippy::useless_conversion)]
+use std::ops::RangeInclusive;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::game_time::GameTime;
+use crate::interact::{Interactable, Interacted};
+use crate::inventory::Inventory;
+use crate::kiosk::KioskReset;
+use crate::particles::{LifeCurve, LifeGradient, ParticleEmitter};
+use crate::picking::Hint;
+use crate::rng::SplitMix64;
+use crate::state::PausableSystems;
+
+pub struct ChestPlugin;
+
+impl Plugin for ChestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, swing_lids.in_set(PausableSystems))
+            .add_observer(open_chest)
+            .add_observer(close_for_next_visitor);
+    }
+}
+
+/// How far the lid swings back once open.
+const LID_OPEN_ANGLE: f32 = 1.9;
+/// Lid thickness, as a fraction of the chest's height.
+const LID_THICKNESS: f32 = 0.3;
+/// Seconds a spark flies for before burning out.
+const SPARK_LIFETIME: f32 = 1.2;
+const SPARK_GRAVITY: f32 = 2.0;
+
+/// One way a [`LootTable`] roll can go, weighted against the table's other entries.
+#[derive(Debug, Clone)]
+pub struct LootEntry {
+    pub item: String,
+    pub weight: f32,
+    pub count: RangeInclusive<u32>,
+}
+
+/// Items given when a chest is opened: everything guaranteed, plus `rolls` weighted picks from
+/// the entries.
+#[derive(Debug, Clone, Default)]
+pub struct LootTable {
+    pub guaranteed: Vec<(String, u32)>,
+    pub entries: Vec<LootEntry>,
+    pub rolls: u32,
+}
+
+impl LootTable {
+    pub fn new(rolls: u32) -> Self {
+        Self { rolls, ..default() }
+    }
+
+    pub fn with_guaranteed(mut self, item: impl Into<String>, count: u32) -> Self {
+        self.guaranteed.push((item.into(), count));
+        self
+    }
+
+    pub fn with_entry(
+        mut self,
+        item: impl Into<String>,
+        weight: f32,
+        count: RangeInclusive<u32>,
+    ) -> Self {
+        self.entries.push(LootEntry {
+            item: item.into(),
+            weight: weight.max(0.0),
+            count,
+        });
+        self
+    }
+
+    /// Rolls the table, with items picked more than once added together.
+    fn roll(&self, rng: &mut SplitMix64) -> Vec<(String, u32)> {
+        let mut loot = self.guaranteed.clone();
+        let total: f32 = self.entries.iter().map(|entry| entry.weight).sum();
+        if total <= 0.0 {
+            return loot;
+        }
+        for _ in 0..self.rolls {
+            let mut pick = rng.next_f32() * total;
+            let Some(entry) = self
+                .entries
+                .iter()
+                .find(|entry| {
+                    pick -= entry.weight;
+                    pick < 0.0
+                })
+                .or(self.entries.last())
+            else {
+                continue;
+            };
+            let (min, max) = (*entry.count.start(), *entry.count.end());
+            let count = min + (rng.next_u64() % u64::from(max.saturating_sub(min) + 1)) as u32;
+            match loot.iter_mut().find(|(item, _)| *item == entry.item) {
+                Some((_, held)) => *held += count,
+                None => loot.push((entry.item.clone(), count)),
+            }
+        }
+        loot.retain(|(_, count)| *count > 0);
+        loot
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChestState {
+    /// Needs its key to open.
+    Locked,
+    #[default]
+    Closed,
+    /// The lid is swinging back.
+    Opening,
+    Open,
+}
+
+/// A chest with loot inside, optionally locked.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct Chest {
+    pub loot: LootTable,
+    /// Item that unlocks the chest, if it's locked.
+    pub key: Option<String>,
+    /// Width, height and depth of the body, without the lid.
+    pub size: Vec3,
+    /// Seconds the lid takes to swing open.
+    pub open_seconds: f32,
+    /// Colour of the light inside and the sparks that fly out.
+    pub glow_color: Color,
+    pub glow_intensity: f32,
+    /// Sparks that fly out on opening.
+    pub sparks: u32,
+    state: ChestState,
+    opened_for: f32,
+    lid: Option<Entity>,
+    glow: Option<Entity>,
+}
+
+impl Chest {
+    pub fn new(loot: LootTable) -> Self {
+        Self {
+            loot,
+            key: None,
+            size: Vec3::new(1.5, 1.0, 1.0),
+            open_seconds: 1.2,
+            glow_color: Color::srgb(1.0, 0.85, 0.4),
+            glow_intensity: 40_000.0,
+            sparks: 24,
+            state: ChestState::Closed,
+            opened_for: 0.0,
+            lid: None,
+            glow: None,
+        }
+    }
+
+    pub fn locked_with(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self.state = ChestState::Locked;
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec3) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_open_seconds(mut self, seconds: f32) -> Self {
+        self.open_seconds = seconds.max(f32::EPSILON);
+        self
+    }
+
+    pub fn with_glow(mut self, color: Color, intensity: f32) -> Self {
+        self.glow_color = color;
+        self.glow_intensity = intensity;
+        self
+    }
+
+    pub fn with_sparks(mut self, sparks: u32) -> Self {
+        self.sparks = sparks;
+        self
+    }
+
+    pub fn state(&self) -> ChestState {
+        self.state
+    }
+
+    /// Spawns the chest with a body and a lid hinged along its back edge, as a static collider
+    /// that can be pressed to open.
+    pub fn spawn<B: Material, L: Material>(
+        mut self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        body_material: Handle<B>,
+        lid_material: Handle<L>,
+        transform: Transform,
+    ) -> Entity {
+        let size = self.size;
+        let lid_height = size.y * LID_THICKNESS;
+        let lid = commands
+            .spawn((
+                Name::new("Chest lid hinge"),
+                Transform::from_xyz(0.0, size.y / 2.0, -size.z / 2.0),
+                Visibility::default(),
+                children![(
+                    Name::new("Chest lid"),
+                    // Overhangs the body a little
+                    Mesh3d(meshes.add(Cuboid::new(size.x + 0.1, lid_height, size.z + 0.1))),
+                    MeshMaterial3d(lid_material),
+                    Transform::from_xyz(0.0, lid_height / 2.0, size.z / 2.0),
+                )],
+            ))
+            .id();
+        self.lid = Some(lid);
+
+        commands
+            .spawn((
+                Name::new("Chest"),
+                transform,
+                Mesh3d(meshes.add(Cuboid::from_size(size))),
+                MeshMaterial3d(body_material),
+                RigidBody::Static,
+                Collider::cuboid(size.x.into(), size.y.into(), size.z.into()),
+                Interactable::press(),
+                self,
+            ))
+            .add_child(lid)
+            .id()
+    }
+}
+
+/// Triggered when the player tries to open a locked chest without its key.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ChestLocked {
+    pub entity: Entity,
+    pub key: String,
+}
+
+/// Triggered when a locked chest is unlocked with its key, just before it opens.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct ChestUnlocked {
+    pub entity: Entity,
+}
+
+/// Triggered when a chest starts opening, with the loot that's been added to the [`Inventory`].
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ChestOpened {
+    pub entity: Entity,
+    pub loot: Vec<(String, u32)>,
+}
+
+/// How a chest could be interacted with before it was opened, to give back if it's closed again.
+#[derive(Component)]
+struct ClosedInteraction {
+    interactable: Option<Interactable>,
+    hint: Option<Hint>,
+}
+
+fn open_chest(
+    interacted: On<Interacted>,
+    mut commands: Commands,
+    mut inventory: ResMut<Inventory>,
+    mut chests: Query<(&mut Chest, &GlobalTransform)>,
+) {
+    let entity = interacted.entity;
+    let Ok((mut chest, transform)) = chests.get_mut(entity) else {
+        return;
+    };
+    match chest.state {
+        ChestState::Locked => {
+            let key = chest.key.clone().unwrap_or_default();
+            if !inventory.remove(&key, 1) {
+                commands.trigger(ChestLocked { entity, key });
+                return;
+            }
+            commands.trigger(ChestUnlocked { entity });
+        }
+        ChestState::Closed => {}
+        ChestState::Opening | ChestState::Open => return,
+    }
+
+    chest.state = ChestState::Opening;
+    chest.opened_for = 0.0;
+    let mut rng = SplitMix64::new(entity.to_bits());
+    let loot = chest.loot.roll(&mut rng);
+    for (item, count) in &loot {
+        inventory.add(item.clone(), *count);
+    }
+    commands.entity(entity).queue(|mut entity: EntityWorldMut| {
+        let interactable = entity.take::<Interactable>();
+        let hint = entity.take::<Hint>();
+        entity.insert(ClosedInteraction { interactable, hint });
+    });
+
+    let glow = commands
+        .spawn((
+            Name::new("Chest glow"),
+            PointLight {
+                color: chest.glow_color,
+                intensity: 0.0,
+                range: 10.0,
+                ..default()
+            },
+            Transform::from_xyz(0.0, chest.size.y * 0.6, 0.0),
+        ))
+        .id();
+    commands.entity(entity).add_child(glow);
+    chest.glow = Some(glow);
+
+    // Mostly upwards, fanning out a little, glowing as they fall and shrink away
+    commands.spawn((
+        Name::new("Chest sparks"),
+        Transform::from_translation(transform.transform_point(Vec3::Y * chest.size.y / 2.0)),
+        ParticleEmitter::default()
+            .with_rate(0.0)
+            .with_burst(chest.sparks)
+            .with_lifetime(SPARK_LIFETIME, 0.0)
+            .with_velocity(Vec3::Y * 2.5, Vec3::new(1.2, 1.0, 1.2))
+            .with_acceleration(Vec3::NEG_Y * SPARK_GRAVITY)
+            .with_size(0.07, 0.4)
+            .with_size_over_life(LifeCurve::linear(1.0, 0.0))
+            .with_color_over_life(LifeGradient::constant(
+                (LinearRgba::from(chest.glow_color) * 8.0).with_alpha(1.0),
+            ))
+            .with_seed(entity.to_bits())
+            .with_despawn_when_done(),
+    ));
+
+    commands.trigger(ChestOpened { entity, loot });
+}
+
+fn swing_lids(
+    time: Res<GameTime>,
+    mut chests: Query<&mut Chest>,
+    mut lids: Query<&mut Transform>,
+    mut lights: Query<&mut PointLight>,
+) {
+    for mut chest in &mut chests {
+        if chest.state != ChestState::Opening {
+            continue;
+        }
+        chest.opened_for += time.delta_secs();
+        let t = (chest.opened_for / chest.open_seconds).min(1.0);
+        // Eases out, so the lid flies up and settles
+        let eased = 1.0 - (1.0 - t).powi(3);
+        if let Some(mut lid) = chest.lid.and_then(|lid| lids.get_mut(lid).ok()) {
+            lid.rotation = Quat::from_rotation_x(-LID_OPEN_ANGLE * eased);
+        }
+        if let Some(mut light) = chest.glow.and_then(|glow| lights.get_mut(glow).ok()) {
+            light.intensity = chest.glow_intensity * eased;
+        }
+        if t >= 1.0 {
+            chest.state = ChestState::Open;
+        }
+    }
+}
+
+/// Closes every opened chest, locking again those with a key, for the next visitor to an
+/// unattended kiosk.
+fn close_for_next_visitor(
+    _reset: On<KioskReset>,
+    mut commands: Commands,
+    mut chests: Query<(Entity, &mut Chest, &mut ClosedInteraction)>,
+    mut lids: Query<&mut Transform>,
+) {
+    for (entity, mut chest, mut closed) in &mut chests {
+        chest.state = if chest.key.is_some() {
+            ChestState::Locked
+        } else {
+            ChestState::Closed
+        };
+        chest.opened_for = 0.0;
+        if let Some(mut lid) = chest.lid.and_then(|lid| lids.get_mut(lid).ok()) {
+            lid.rotation = Quat::IDENTITY;
+        }
+        if let Some(glow) = chest.glow.take() {
+            commands.entity(glow).despawn();
+        }
+        let mut restored = commands.entity(entity);
+        restored.remove::<ClosedInteraction>();
+        if let Some(interactable) = closed.interactable.take() {
+            restored.insert(interactable);
+        }
+        if let Some(hint) = closed.hint.take() {
+            restored.insert(hint);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Close-up examination of artworks and other things worth a longer look.
+//!
+//! Pointing at an entity with [`CloseUp`] from within its reach and pressing the examine binding
+//! eases the camera round to frame it square on, and holds the player still. Looking then orbits
+//! the camera around it, only so far either side of straight on, and the move forward and back
+//! bindings zoom in and out. Pressing examine again eases the camera back to where it was.
+//! [`CloseUpStarted`] and [`CloseUpEnded`] are triggered on the entity as it goes.
+
+use bevy::input::mouse::AccumulatedMouseMotion;
+use bevy::picking::pointer::PointerInteraction;
+use bevy::prelude::*;
+
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::firstsight::{MovementDisabled, PlayerCamera, PlayerCameraSet};
+use crate::player::Player;
+use crate::state::{PausableSystems, not_paused};
+
+pub struct CloseUpPlugin;
+
+impl Plugin for CloseUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CloseUp>()
+            .add_systems(Update, examine.in_set(PausableSystems))
+            .add_systems(
+                PostUpdate,
+                frame_close_up
+                    .run_if(resource_exists::<CloseUpView>.and(not_paused()))
+                    .after(PlayerCameraSet)
+                    .before(TransformSystems::Propagate),
+            );
+    }
+}
+
+/// Seconds to ease the camera into a close-up, and back out.
+const EASE_SECONDS: f32 = 0.6;
+const MOVEMENT_REASON: &str = "close up";
+const ORBIT_SENSITIVITY: f32 = 0.002;
+/// Units per second the camera zooms at.
+const ZOOM_SPEED: f32 = 1.5;
+
+/// Something that can be examined up close.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct CloseUp {
+    /// Local offset of the point the camera frames.
+    pub center: Vec3,
+    /// Local direction the front faces, which the camera looks back along.
+    pub facing: Vec3,
+    /// Distance the camera frames it from, and the furthest it zooms out.
+    pub distance: f32,
+    /// Nearest the camera zooms in.
+    pub min_distance: f32,
+    /// How far the camera orbits either side of straight on, in radians, across and up and down.
+    pub orbit_limits: Vec2,
+    /// Furthest the player can be to start examining it.
+    pub reach: f32,
+}
+
+impl Default for CloseUp {
+    fn default() -> Self {
+        Self {
+            center: Vec3::ZERO,
+            facing: Vec3::Z,
+            distance: 1.5,
+            min_distance: 0.4,
+            orbit_limits: Vec2::new(0.6, 0.4),
+            reach: 4.0,
+        }
+    }
+}
+
+impl CloseUp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_center(mut self, center: Vec3) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn with_facing(mut self, facing: Vec3) -> Self {
+        self.facing = facing.normalize_or(Vec3::Z);
+        self
+    }
+
+    pub fn with_distance(mut self, min_distance: f32, distance: f32) -> Self {
+        self.min_distance = min_distance.max(0.0);
+        self.distance = distance.max(self.min_distance);
+        self
+    }
+
+    pub fn with_orbit_limits(mut self, across: f32, up_down: f32) -> Self {
+        self.orbit_limits = Vec2::new(across, up_down).abs();
+        self
+    }
+
+    pub fn with_reach(mut self, reach: f32) -> Self {
+        self.reach = reach.max(0.0);
+        self
+    }
+}
+
+/// Triggered on an entity with [`CloseUp`] as the camera starts moving in to examine it.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct CloseUpStarted {
+    pub entity: Entity,
+}
+
+/// Triggered on an entity with [`CloseUp`] once the camera is back with the player.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct CloseUpEnded {
+    pub entity: Entity,
+}
+
+/// The close-up in progress.
+#[derive(Resource)]
+struct CloseUpView {
+    target: Entity,
+    /// The first-person look to go back to.
+    saved_camera: PlayerCamera,
+    /// Orbit across and up and down, in radians.
+    orbit: Vec2,
+    zoom: f32,
+    /// How far in the camera has eased, from 0 with the player to 1 framing the target.
+    blend: f32,
+    leaving: bool,
+}
+
+fn examine(
+    mut commands: Commands,
+    config: Res<ControlsConfig>,
+    input: ControlsInput,
+    view: Option<ResMut<CloseUpView>>,
+    pointers: Query<&PointerInteraction>,
+    close_ups: Query<&CloseUp>,
+    parents: Query<&ChildOf>,
+    camera: Single<&PlayerCamera>,
+    player: Single<Entity, With<Player>>,
+) {
+    if !input.just_pressed(&config.examine) {
+        return;
+    }
+    if let Some(mut view) = view {
+        view.leaving = !view.leaving;
+        return;
+    }
+    // Meshes are often children of what's examined, e.g. a painting's canvas
+    let target = pointers
+        .iter()
+        .filter_map(|interaction| interaction.get_nearest_hit())
+        .find_map(|(entity, hit)| {
+            std::iter::once(*entity)
+                .chain(parents.iter_ancestors(*entity))
+                .find_map(|entity| {
+                    close_ups
+                        .get(entity)
+                        .ok()
+                        .map(|close_up| (entity, close_up))
+                })
+                .filter(|(_, close_up)| hit.depth <= close_up.reach)
+        });
+    let Some((target, close_up)) = target else {
+        return;
+    };
+    commands.insert_resource(CloseUpView {
+        target,
+        saved_camera: (*camera).clone(),
+        orbit: Vec2::ZERO,
+        zoom: close_up.distance,
+        blend: 0.0,
+        leaving: false,
+    });
+    commands
+        .entity(*player)
+        .queue(MovementDisabled::request(MOVEMENT_REASON));
+    commands.trigger(CloseUpStarted { entity: target });
+}
+
+/// Where the camera frames `close_up` from, orbited and zoomed.
+fn framing(close_up: &CloseUp, transform: &GlobalTransform, orbit: Vec2, zoom: f32) -> Transform {
+    let center = transform.transform_point(close_up.center);
+    let rotation = transform.rotation();
+    let facing = (rotation * close_up.facing).normalize_or(Vec3::Z);
+    let mut up = rotation * Vec3::Y;
+    if up.cross(facing).length_squared() < 1e-4 {
+        // Lying flat, facing up or down
+        up = facing.any_orthonormal_vector();
+    }
+    let across = up.cross(facing).normalize();
+    let orbit = Quat::from_axis_angle(up, orbit.x) * Quat::from_axis_angle(across, -orbit.y);
+    Transform::from_translation(center + orbit * facing * zoom).looking_at(center, up)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn frame_close_up(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<ControlsConfig>,
+    input: ControlsInput,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    mut view: ResMut<CloseUpView>,
+    close_ups: Query<(&CloseUp, &GlobalTransform)>,
+    camera: Single<(&mut Transform, &mut PlayerCamera)>,
+    player: Single<Entity, With<Player>>,
+) {
+    let dt = time.delta_secs();
+    let (mut transform, mut player_camera) = camera.into_inner();
+    let target = close_ups.get(view.target).ok();
+    if target.is_none() {
+        view.leaving = true;
+    }
+
+    if let Some((close_up, _)) = target.filter(|_| !view.leaving) {
+        let stick = input.look_axis(config.stick_deadzone) * config.gamepad_look_speed * dt;
+        view.orbit.x -= mouse_motion.delta.x * ORBIT_SENSITIVITY + stick.x;
+        view.orbit.y -= mouse_motion.delta.y * ORBIT_SENSITIVITY - stick.y;
+        view.orbit = view
+            .orbit
+            .clamp(-close_up.orbit_limits, close_up.orbit_limits);
+        let mut zoom = 0.0;
+        if input.pressed(&config.move_forward) {
+            zoom -= 1.0;
+        }
+        if input.pressed(&config.move_back) {
+            zoom += 1.0;
+        }
+        zoom -= input.move_axis(config.stick_deadzone).y;
+        view.zoom =
+            (view.zoom + zoom * ZOOM_SPEED * dt).clamp(close_up.min_distance, close_up.distance);
+    }
+    let step = dt / EASE_SECONDS;
+    view.blend = if view.leaving {
+        (view.blend - step).max(0.0)
+    } else {
+        (view.blend + step).min(1.0)
+    };
+
+    // Looking orbits the camera instead of turning the player, who faces the same way afterwards
+    *player_camera = view.saved_camera.clone();
+    let first_person =
+        Transform::from_translation(transform.translation).with_rotation(player_camera.rotation());
+    if view.leaving && view.blend <= 0.0 {
+        *transform = first_person;
+        commands.remove_resource::<CloseUpView>();
+        commands
+            .entity(*player)
+            .queue(MovementDisabled::cancel(MOVEMENT_REASON));
+        if target.is_some() {
+            commands.trigger(CloseUpEnded {
+                entity: view.target,
+            });
+        }
+        return;
+    }
+    let Some((close_up, target_transform)) = target else {
+        *transform = first_person;
+        return;
+    };
+    let framed = framing(close_up, target_transform, view.orbit, view.zoom);
+    let eased = view.blend * view.blend * (3.0 - 2.0 * view.blend);
+    transform.translation = first_person.translation.lerp(framed.translation, eased);
+    transform.rotation = first_person.rotation.slerp(framed.rotation, eased);
+}
@@ -0,0 +1,99 @@
+//! Colour types for tunable scene effects.
+//!
+//! Shader material uniforms should store colours as [`LinearRgba`] rather than `Vec4`, which has
+//! the same GPU layout but shows up as a colour picker in the world inspector. [`ColorGradient`]
+//! covers colours that change over a range, such as a particle's colour over its lifetime, and
+//! gets a gradient editor in the inspector.
+
+use bevy::prelude::*;
+
+/// A colour key on a [`ColorGradient`].
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct GradientKey {
+    /// Where the key sits on the gradient, from 0 to 1.
+    pub position: f32,
+    pub color: LinearRgba,
+}
+
+impl GradientKey {
+    pub fn new(position: f32, color: impl Into<LinearRgba>) -> Self {
+        Self {
+            position: position.clamp(0.0, 1.0),
+            color: color.into(),
+        }
+    }
+}
+
+/// Colours interpolated linearly between keys, sampled with [`ColorGradient::sample`].
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct ColorGradient {
+    keys: Vec<GradientKey>,
+}
+
+impl Default for ColorGradient {
+    fn default() -> Self {
+        Self::new([
+            GradientKey::new(0.0, LinearRgba::WHITE),
+            GradientKey::new(1.0, LinearRgba::BLACK),
+        ])
+    }
+}
+
+impl ColorGradient {
+    pub fn new(keys: impl IntoIterator<Item = GradientKey>) -> Self {
+        let mut gradient = Self {
+            keys: keys.into_iter().collect(),
+        };
+        gradient.sort();
+        gradient
+    }
+
+    /// A gradient that is the same colour everywhere.
+    pub fn constant(color: impl Into<LinearRgba>) -> Self {
+        Self::new([GradientKey::new(0.0, color)])
+    }
+
+    pub fn keys(&self) -> &[GradientKey] {
+        &self.keys
+    }
+
+    /// Mutable access to the keys, e.g. for an editor. Keys are re-sorted on the next
+    /// [`ColorGradient::sort`], which [`ColorGradient::sample`] doesn't do itself.
+    pub fn keys_mut(&mut self) -> &mut Vec<GradientKey> {
+        &mut self.keys
+    }
+
+    /// Orders the keys by position, clamping positions to 0..=1.
+    pub fn sort(&mut self) {
+        for key in &mut self.keys {
+            key.position = key.position.clamp(0.0, 1.0);
+        }
+        self.keys.sort_by(|a, b| a.position.total_cmp(&b.position));
+    }
+
+    /// Colour at `t`, from 0 to 1. Before the first key and after the last, the nearest key's
+    /// colour is used. An empty gradient is transparent.
+    pub fn sample(&self, t: f32) -> LinearRgba {
+        let t = t.clamp(0.0, 1.0);
+        let (Some(first), Some(last)) = (self.keys.first(), self.keys.last()) else {
+            return LinearRgba::NONE;
+        };
+        if t <= first.position {
+            return first.color;
+        }
+        if t >= last.position {
+            return last.color;
+        }
+        for pair in self.keys.windows(2) {
+            let [from, to] = pair else { continue };
+            if t <= to.position {
+                let span = to.position - from.position;
+                if span <= f32::EPSILON {
+                    return to.color;
+                }
+                return from.color.mix(&to.color, (t - from.position) / span);
+            }
+        }
+        last.color
+    }
+}
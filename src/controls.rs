@@ -1,26 +1,263 @@
+//! Pausing, and the key and gamepad bindings used by the player controller and debug toggles.
+//!
+//! Bindings live in the [`ControlsConfig`] resource, so apps can remap them by inserting their own
+//! config, or by pointing [`ControlsConfigFile`] at a `.controls.ron` file in their assets, e.g.
+//!
+//! ```text
+//! (
+//!     jump: (keys: [Space], gamepad: [South]),
+//!     toggle_wireframe: (chords: [[F3, KeyG]]),
+//!     stick_deadzone: 0.2,
+//! )
+//! ```
+//!
+//! Bindings left out of the file keep their defaults. The file is hot reloaded if Bevy's
+//! `file_watcher` feature is enabled.
+
+use std::path::Path;
+use std::{fs, io};
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::input::mouse::AccumulatedMouseMotion;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
 
+use crate::kiosk::not_in_kiosk_mode;
 use crate::state::GameState;
 
 pub struct ControlsPlugin;
 
 impl Plugin for ControlsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(InputManagerPlugin::<PauseResumeAction>::default())
-            .add_systems(Startup, setup_actions)
-            .add_systems(Update, handle_actions);
+        app.init_resource::<ControlsConfig>()
+            .register_type::<ControlsConfig>()
+            .init_asset::<ControlsConfig>()
+            .register_asset_loader(ControlsConfigLoader)
+            .add_plugins(InputManagerPlugin::<PauseResumeAction>::default())
+            .add_systems(Startup, (setup_actions, load_controls_config))
+            .add_systems(
+                Update,
+                (
+                    apply_controls_config,
+                    rebind_actions.run_if(resource_changed::<ControlsConfig>),
+                    handle_actions
+                        .run_if(not_in_kiosk_mode)
+                        .run_if(keyboard_not_captured),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Keys, key chords and gamepad buttons that trigger an action.
+#[derive(Serialize, Deserialize, Reflect, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Binding {
+    /// Pressing any of these keys triggers the action.
+    pub keys: Vec<KeyCode>,
+    /// Holding every key of any of these chords triggers the action, e.g. `[F3, KeyG]`.
+    pub chords: Vec<Vec<KeyCode>>,
+    /// Pressing any of these buttons on any gamepad triggers the action.
+    pub gamepad: Vec<GamepadButton>,
+}
+
+impl Binding {
+    pub fn keys(keys: impl IntoIterator<Item = KeyCode>) -> Self {
+        Self {
+            keys: keys.into_iter().collect(),
+            ..default()
+        }
+    }
+
+    pub fn chord(keys: impl IntoIterator<Item = KeyCode>) -> Self {
+        Self {
+            chords: vec![keys.into_iter().collect()],
+            ..default()
+        }
+    }
+
+    pub fn with_gamepad(mut self, buttons: impl IntoIterator<Item = GamepadButton>) -> Self {
+        self.gamepad.extend(buttons);
+        self
+    }
+
+    /// An input map binding `action` to these inputs, for actions handled by
+    /// `leafwing_input_manager`.
+    pub fn input_map<A: Actionlike>(&self, action: A) -> InputMap<A> {
+        let mut map = InputMap::default();
+        for key in &self.keys {
+            map.insert(action.clone(), *key);
+        }
+        for chord in self.chords.iter().filter(|chord| !chord.is_empty()) {
+            map.insert(action.clone(), ButtonlikeChord::new(chord.iter().copied()));
+        }
+        for button in &self.gamepad {
+            map.insert(action.clone(), *button);
+        }
+        map
+    }
+}
+
+/// Bindings for player movement, looking, pausing and the interact, item, inspect, examine and
+/// wireframe toggle actions.
+///
+/// Gamepads move with the left stick and look with the right stick, alongside the button bindings.
+#[derive(Resource, Asset, Serialize, Deserialize, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct ControlsConfig {
+    pub move_forward: Binding,
+    pub move_back: Binding,
+    pub move_left: Binding,
+    pub move_right: Binding,
+    pub sprint: Binding,
+    pub crouch: Binding,
+    pub jump: Binding,
+    /// Moves straight up while flying or swimming.
+    pub ascend: Binding,
+    /// Moves straight down while flying or swimming.
+    pub descend: Binding,
+    pub interact: Binding,
+    /// Uses a held item, such as spraying a fire extinguisher.
+    pub use_item: Binding,
+    /// Puts the held item away and takes out the next one in the inventory.
+    pub switch_item: Binding,
+    /// Held to see hidden details through whatever's in front of them.
+    pub inspect: Binding,
+    /// Moves the camera in to examine what the player's pointing at up close, and back out.
+    pub examine: Binding,
+    pub pause: Binding,
+    pub toggle_wireframe: Binding,
+    /// Stick deflection below this fraction is ignored, so worn sticks don't drift.
+    pub stick_deadzone: f32,
+    /// Right stick look speed at full deflection, in radians per second.
+    pub gamepad_look_speed: f32,
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        Self {
+            move_forward: Binding::keys([KeyCode::KeyW]).with_gamepad([GamepadButton::DPadUp]),
+            move_back: Binding::keys([KeyCode::KeyS]).with_gamepad([GamepadButton::DPadDown]),
+            move_left: Binding::keys([KeyCode::KeyA]).with_gamepad([GamepadButton::DPadLeft]),
+            move_right: Binding::keys([KeyCode::KeyD]).with_gamepad([GamepadButton::DPadRight]),
+            sprint: Binding::keys([KeyCode::ShiftLeft]).with_gamepad([GamepadButton::LeftThumb]),
+            crouch: Binding::keys([KeyCode::KeyC, KeyCode::ControlLeft])
+                .with_gamepad([GamepadButton::East]),
+            jump: Binding::keys([KeyCode::Space]).with_gamepad([GamepadButton::South]),
+            ascend: Binding::keys([KeyCode::Space]).with_gamepad([GamepadButton::South]),
+            descend: Binding::keys([KeyCode::KeyC, KeyCode::ControlLeft])
+                .with_gamepad([GamepadButton::East]),
+            interact: Binding::keys([KeyCode::KeyE]).with_gamepad([GamepadButton::West]),
+            use_item: Binding::keys([KeyCode::KeyF]).with_gamepad([GamepadButton::North]),
+            switch_item: Binding::keys([KeyCode::KeyQ]).with_gamepad([GamepadButton::RightThumb]),
+            inspect: Binding::keys([KeyCode::KeyX]).with_gamepad([GamepadButton::LeftTrigger2]),
+            examine: Binding::keys([KeyCode::KeyZ]).with_gamepad([GamepadButton::RightTrigger2]),
+            pause: Binding::keys([KeyCode::Escape]).with_gamepad([GamepadButton::Start]),
+            toggle_wireframe: Binding::chord([KeyCode::F3, KeyCode::KeyG]),
+            stick_deadzone: 0.15,
+            gamepad_look_speed: 3.0,
+        }
+    }
+}
+
+impl ControlsConfig {
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Asset path of a RON file to load [`ControlsConfig`] from, replacing the resource once loaded.
+#[derive(Resource, Debug, Clone)]
+pub struct ControlsConfigFile {
+    pub path: String,
+    handle: Option<Handle<ControlsConfig>>,
+}
+
+impl ControlsConfigFile {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            handle: None,
+        }
+    }
+}
+
+#[derive(Default, TypePath)]
+struct ControlsConfigLoader;
+
+impl AssetLoader for ControlsConfigLoader {
+    type Asset = ControlsConfig;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> io::Result<ControlsConfig> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["controls.ron"]
+    }
+}
+
+fn load_controls_config(file: Option<ResMut<ControlsConfigFile>>, asset_server: Res<AssetServer>) {
+    if let Some(mut file) = file {
+        let handle = asset_server.load(file.path.clone());
+        file.handle = Some(handle);
+    }
+}
+
+fn apply_controls_config(
+    mut events: MessageReader<AssetEvent<ControlsConfig>>,
+    file: Option<Res<ControlsConfigFile>>,
+    configs: Res<Assets<ControlsConfig>>,
+    mut controls: ResMut<ControlsConfig>,
+) {
+    let Some(handle) = file.and_then(|file| file.handle.clone()) else {
+        events.clear();
+        return;
+    };
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+        if id != handle.id() {
+            continue;
+        }
+        if let Some(config) = configs.get(id) {
+            info!("Applying controls from {:?}", handle.path());
+            *controls = config.clone();
+        }
     }
 }
 
 #[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
 pub struct PauseResumeAction;
 
-pub fn setup_actions(mut commands: Commands) {
-    let toggle_map = InputMap::new([(PauseResumeAction, KeyCode::Escape)]);
+pub fn setup_actions(mut commands: Commands, controls: Res<ControlsConfig>) {
+    let toggle_map = controls.pause.input_map(PauseResumeAction);
     commands.spawn((Name::new("Controls"), toggle_map));
 }
 
+fn rebind_actions(
+    controls: Res<ControlsConfig>,
+    mut input_map: Single<&mut InputMap<PauseResumeAction>>,
+) {
+    **input_map = controls.pause.input_map(PauseResumeAction);
+}
+
 pub fn handle_actions(
     action_state: Single<&ActionState<PauseResumeAction>>,
     current_state: Res<State<GameState>>,
@@ -37,3 +274,108 @@ pub fn handle_actions(
         }
     }
 }
+
+/// While this resource exists, the keyboard is being typed into, e.g. to write a note, so
+/// [`ControlsInput`] ignores key [`Binding`]s and the crate's leafwing toggles don't run. Gamepads
+/// still work.
+#[derive(Resource, Debug, Default)]
+pub struct KeyboardCapture;
+
+/// Run condition that is false while there's a [`KeyboardCapture`], for key bindings read some
+/// other way than [`ControlsInput`], such as leafwing actions.
+pub fn keyboard_not_captured(capture: Option<Res<KeyboardCapture>>) -> bool {
+    capture.is_none()
+}
+
+/// Checks [`Binding`]s against the keyboard and every connected gamepad.
+#[derive(SystemParam)]
+pub struct ControlsInput<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    capture: Option<Res<'w, KeyboardCapture>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl ControlsInput<'_, '_> {
+    /// Whether something is typing into the keyboard, so key bindings are ignored.
+    pub fn keyboard_captured(&self) -> bool {
+        self.capture.is_some()
+    }
+
+    pub fn pressed(&self, binding: &Binding) -> bool {
+        let keys = !self.keyboard_captured()
+            && (self.keys.any_pressed(binding.keys.iter().copied())
+                || binding.chords.iter().any(|chord| {
+                    !chord.is_empty() && self.keys.all_pressed(chord.iter().copied())
+                }));
+        keys || self
+            .gamepads
+            .iter()
+            .any(|gamepad| gamepad.any_pressed(binding.gamepad.iter().copied()))
+    }
+
+    pub fn just_pressed(&self, binding: &Binding) -> bool {
+        let keys = !self.keyboard_captured()
+            && (self.keys.any_just_pressed(binding.keys.iter().copied())
+                || binding.chords.iter().any(|chord| {
+                    !chord.is_empty()
+                        && self.keys.all_pressed(chord.iter().copied())
+                        && self.keys.any_just_pressed(chord.iter().copied())
+                }));
+        keys || self
+            .gamepads
+            .iter()
+            .any(|gamepad| gamepad.any_just_pressed(binding.gamepad.iter().copied()))
+    }
+
+    /// Left stick position summed over every gamepad, with `deadzone` applied and clamped to the
+    /// unit circle. Up is positive Y.
+    pub fn move_axis(&self, deadzone: f32) -> Vec2 {
+        self.stick(deadzone, Gamepad::left_stick)
+    }
+
+    /// Right stick position summed over every gamepad, with `deadzone` applied and clamped to the
+    /// unit circle. Up is positive Y.
+    pub fn look_axis(&self, deadzone: f32) -> Vec2 {
+        self.stick(deadzone, Gamepad::right_stick)
+    }
+
+    fn stick(&self, deadzone: f32, stick: impl Fn(&Gamepad) -> Vec2) -> Vec2 {
+        self.gamepads
+            .iter()
+            .map(|gamepad| apply_deadzone(stick(gamepad), deadzone))
+            .sum::<Vec2>()
+            .clamp_length_max(1.0)
+    }
+}
+
+/// Zeroes `stick` inside the radial `deadzone`, rescaling the rest so output still starts at 0
+/// and reaches 1 at full deflection.
+fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - deadzone) / (1.0 - deadzone).max(f32::EPSILON)).min(1.0);
+    stick * (scaled / length)
+}
+
+/// Whether the user has pressed anything or moved the mouse this frame, for idle detection.
+#[derive(SystemParam)]
+pub(crate) struct InputActivity<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse_buttons: Res<'w, ButtonInput<MouseButton>>,
+    mouse_motion: Res<'w, AccumulatedMouseMotion>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl InputActivity<'_, '_> {
+    pub(crate) fn any(&self) -> bool {
+        self.keys.get_just_pressed().next().is_some()
+            || self.mouse_buttons.get_just_pressed().next().is_some()
+            || self.mouse_motion.delta != Vec2::ZERO
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    }
+}
@@ -0,0 +1,220 @@
+//! Opt-in crash reports, written to a local file when the app panics.
+//!
+//! Inserting a [`CrashReport`] resource, or setting the `DIORAMA_CRASH_REPORT` environment variable
+//! to an output path, installs a panic hook that writes the panic message and a backtrace, a
+//! snapshot of the world, and the last few log lines to the output file, so players can send it to
+//! a scene's author. The world can't be read while panicking, so it's snapshotted every
+//! [`CrashReport::snapshot_interval`] seconds instead, and the report shows the latest one: every
+//! entity's [`Name`] and [`GlobalTransform`], and every resource registered with
+//! `#[reflect(Resource)]`.
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bevy::log::BoxedLayer;
+use bevy::log::tracing_subscriber::filter::filter_fn;
+use bevy::log::tracing_subscriber::{self, Layer as _};
+use bevy::prelude::*;
+
+pub struct CrashReportPlugin;
+
+impl Plugin for CrashReportPlugin {
+    fn build(&self, app: &mut App) {
+        if let Ok(path) = std::env::var(CRASH_REPORT_ENV_VAR) {
+            app.insert_resource(CrashReport::new(path));
+        }
+        app.add_systems(
+            Last,
+            (
+                install_panic_hook.run_if(resource_added::<CrashReport>),
+                snapshot_world.run_if(resource_exists::<CrashReport>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Environment variable holding the path to write crash reports to.
+const CRASH_REPORT_ENV_VAR: &str = "DIORAMA_CRASH_REPORT";
+
+/// Most log lines kept for crash reports.
+const MAX_LOG_LINES: usize = 1000;
+
+#[derive(Resource, Debug, Clone)]
+pub struct CrashReport {
+    pub path: PathBuf,
+    /// How many of the latest log lines to include.
+    pub log_lines: usize,
+    /// Real seconds between world snapshots.
+    pub snapshot_interval: f32,
+    snapshot: Arc<Mutex<String>>,
+    since_snapshot: f32,
+}
+
+impl CrashReport {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            log_lines: 200,
+            snapshot_interval: 2.0,
+            snapshot: Arc::default(),
+            since_snapshot: f32::INFINITY,
+        }
+    }
+
+    pub fn with_log_lines(mut self, log_lines: usize) -> Self {
+        self.log_lines = log_lines.min(MAX_LOG_LINES);
+        self
+    }
+
+    pub fn with_snapshot_interval(mut self, snapshot_interval: f32) -> Self {
+        self.snapshot_interval = snapshot_interval.max(0.0);
+        self
+    }
+}
+
+/// The latest log lines, oldest first, shared with the logger.
+#[derive(Resource, Clone, Default)]
+struct RecentLogs {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    /// Whether lines are being kept, only once crash reports are on so other apps don't pay for
+    /// formatting every log line.
+    capturing: Arc<AtomicBool>,
+}
+
+impl Write for RecentLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // The formatter writes each event whole, so every write is a line
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() >= MAX_LOG_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(String::from_utf8_lossy(buf).trim_end().to_string());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Keeps the latest log lines for crash reports, as the `custom_layer` of Bevy's `LogPlugin`.
+/// Nothing is kept until there's a [`CrashReport`], unless the environment variable is set, in
+/// which case logs from startup on are kept.
+pub(crate) fn log_layer(app: &mut App) -> Option<BoxedLayer> {
+    let logs = RecentLogs::default();
+    logs.capturing.store(
+        std::env::var_os(CRASH_REPORT_ENV_VAR).is_some(),
+        Ordering::Relaxed,
+    );
+    app.insert_resource(logs.clone());
+    let capturing = logs.capturing.clone();
+    Some(Box::new(
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(move || logs.clone())
+            .with_filter(filter_fn(move |_| capturing.load(Ordering::Relaxed))),
+    ))
+}
+
+fn install_panic_hook(report: Res<CrashReport>, logs: Option<Res<RecentLogs>>) {
+    let path = report.path.clone();
+    let log_lines = report.log_lines;
+    let snapshot = report.snapshot.clone();
+    let logs = logs.map(|logs| logs.clone());
+    if let Some(logs) = &logs {
+        logs.capturing.store(true, Ordering::Relaxed);
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Locks are only tried, as the panic may have happened while holding one
+        let snapshot = snapshot
+            .try_lock()
+            .map(|snapshot| snapshot.clone())
+            .unwrap_or_else(|_| "Unavailable\n".to_string());
+        let logs = logs
+            .as_ref()
+            .and_then(|logs| logs.lines.try_lock().ok())
+            .map(|lines| {
+                let skip = lines.len().saturating_sub(log_lines);
+                lines.iter().skip(skip).cloned().collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let backtrace = Backtrace::force_capture();
+
+        let written = File::create(&path).and_then(|mut file| {
+            writeln!(file, "{info}\n\n== Backtrace\n{backtrace}")?;
+            writeln!(file, "== World\n{snapshot}")?;
+            writeln!(file, "== Log")?;
+            for line in &logs {
+                writeln!(file, "{line}")?;
+            }
+            file.flush()
+        });
+        match written {
+            Ok(()) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Couldn't write crash report to {}: {e}", path.display()),
+        }
+        previous(info);
+    }));
+    info!("Writing crash reports to {}", report.path.display());
+}
+
+fn snapshot_world(world: &mut World) {
+    let delta = world.resource::<Time<Real>>().delta_secs();
+    let mut report = world.resource_mut::<CrashReport>();
+    report.since_snapshot += delta;
+    if report.since_snapshot < report.snapshot_interval {
+        return;
+    }
+    report.since_snapshot = 0.0;
+    let shared = report.snapshot.clone();
+
+    let elapsed = world.resource::<Time<Real>>().elapsed_secs();
+    let mut snapshot = format!("Taken {elapsed:.1} s after startup\n\nEntities:\n");
+    let mut entities = world.query::<(Entity, &Name, Option<&GlobalTransform>)>();
+    for (entity, name, transform) in entities.iter(world) {
+        match transform {
+            Some(transform) => {
+                let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+                let _ = writeln!(
+                    snapshot,
+                    "{entity} {name}: translation {translation}, rotation {rotation}, scale {scale}"
+                );
+            }
+            None => {
+                let _ = writeln!(snapshot, "{entity} {name}");
+            }
+        }
+    }
+
+    snapshot.push_str("\nResources:\n");
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let mut resources: Vec<String> = registry
+        .iter()
+        .filter_map(|registration| {
+            let resource = registration
+                .data::<ReflectResource>()?
+                .reflect(&*world)
+                .ok()?;
+            let path = registration.type_info().type_path_table().short_path();
+            Some(format!("{path}: {resource:?}"))
+        })
+        .collect();
+    resources.sort();
+    for resource in resources {
+        let _ = writeln!(snapshot, "{resource}");
+    }
+
+    if let Ok(mut shared) = shared.lock() {
+        *shared = snapshot;
+    }
+}
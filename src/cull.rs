@@ -0,0 +1,117 @@
+//! Hiding and sleeping entities far from the player, whichever way the camera's looking.
+//!
+//! Frustum culling only skips drawing what's off screen, so scenery behind the player still
+//! animates, and scenery ahead still draws however far away it is. An entity with
+//! [`CullAtDistance`] is hidden once it's further than its radius from the player, and gets
+//! [`Culled`] so animation systems can skip it with `Without<Culled>`. It can also have its rigid
+//! body disabled while it's away, so far-off physics stops simulating. Everything's put back once
+//! the player's a little inside the radius again, by [`CullAtDistance::hysteresis`], so standing at
+//! the edge doesn't flicker.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::player::Player;
+
+pub struct CullPlugin;
+
+impl Plugin for CullPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CullAtDistance>()
+            .add_systems(Update, cull_distant);
+    }
+}
+
+/// Hides this entity, and its descendants, while it's further than `radius` from the player.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+pub struct CullAtDistance {
+    pub radius: f32,
+    /// How far inside the radius the player has to come to bring it back, as a fraction of it.
+    pub hysteresis: f32,
+    /// Whether to disable its rigid body while it's culled.
+    pub sleep_physics: bool,
+}
+
+impl Default for CullAtDistance {
+    fn default() -> Self {
+        Self {
+            radius: 50.0,
+            hysteresis: 0.1,
+            sleep_physics: false,
+        }
+    }
+}
+
+impl CullAtDistance {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius: radius.max(0.0),
+            ..default()
+        }
+    }
+
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Disables its rigid body while it's culled, so it stops simulating.
+    pub fn with_physics_sleep(mut self) -> Self {
+        self.sleep_physics = true;
+        self
+    }
+}
+
+/// On entities [`CullAtDistance`] has hidden, until the player comes back within range.
+#[derive(Component, Debug)]
+pub struct Culled {
+    /// Visibility to put back when it's brought back.
+    visibility: Visibility,
+    /// Whether its rigid body was disabled by culling, rather than already being disabled.
+    disabled_physics: bool,
+}
+
+fn cull_distant(
+    mut commands: Commands,
+    player: Single<&GlobalTransform, With<Player>>,
+    mut entities: Query<(
+        Entity,
+        &CullAtDistance,
+        &GlobalTransform,
+        &mut Visibility,
+        Option<&Culled>,
+        Has<RigidBody>,
+        Has<RigidBodyDisabled>,
+    )>,
+) {
+    let player = player.translation();
+    for (entity, cull, transform, mut visibility, culled, has_body, body_disabled) in &mut entities
+    {
+        let distance = player.distance(transform.translation());
+        match culled {
+            None if distance > cull.radius => {
+                let disable_physics = cull.sleep_physics && has_body && !body_disabled;
+                let mut entity = commands.entity(entity);
+                entity.insert(Culled {
+                    visibility: *visibility,
+                    disabled_physics: disable_physics,
+                });
+                if disable_physics {
+                    entity.insert(RigidBodyDisabled);
+                }
+                *visibility = Visibility::Hidden;
+            }
+            Some(culled) if distance < cull.radius * (1.0 - cull.hysteresis) => {
+                *visibility = culled.visibility;
+                let mut entity = commands.entity(entity);
+                entity.remove::<Culled>();
+                if culled.disabled_physics {
+                    entity.remove::<RigidBodyDisabled>();
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Rotating which artworks are on display, so repeat visits to a gallery see something different.
+//!
+//! A [`Curation`] resource holds a pool of [`CuratedArtwork`]s, usually more than there are
+//! [`DisplaySlot`]s to hang them in. The pool is shuffled from [`Curation::seed`], so each visit can
+//! get its own hang, and slots are filled from it in order as they're spawned. With
+//! [`Curation::rotate_every`] set, every slot moves on to artworks further along the shuffle each
+//! time it elapses, working through the whole pool before any repeat. Artworks swapped out while
+//! the scene is running dissolve away and their replacements dissolve in. [`ArtworkHung`] is
+//! triggered on a slot whenever it gets a new artwork, for scenes to update names, hints and the
+//! like.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+use crate::game_time::GameTime;
+use crate::kiosk::KioskReset;
+use crate::rng::SplitMix64;
+use crate::state::PausableSystems;
+
+pub struct CurationPlugin;
+
+impl Plugin for CurationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                hang_new_slots,
+                (rotate_artworks, dissolve_artworks)
+                    .chain()
+                    .in_set(PausableSystems),
+            )
+                .chain()
+                .run_if(resource_exists::<Curation>),
+        )
+        .add_observer(rehang_for_next_visitor);
+    }
+}
+
+/// An artwork that can be hung in a [`DisplaySlot`].
+#[derive(Debug, Clone)]
+pub struct CuratedArtwork {
+    pub name: String,
+    pub material: Handle<StandardMaterial>,
+}
+
+impl CuratedArtwork {
+    pub fn new(name: impl Into<String>, material: Handle<StandardMaterial>) -> Self {
+        Self {
+            name: name.into(),
+            material,
+        }
+    }
+}
+
+/// The pool of artworks to choose from, and how often to change them.
+#[derive(Resource, Debug, Clone)]
+pub struct Curation {
+    pub artworks: Vec<CuratedArtwork>,
+    /// Seeds the shuffle, so the same seed always gives the same hang.
+    pub seed: u64,
+    /// Seconds between rotations, or `None` to keep the first hang.
+    pub rotate_every: Option<f32>,
+    /// Seconds taken to dissolve one artwork into the next.
+    pub dissolve_seconds: f32,
+    order: Vec<usize>,
+    rotation: usize,
+    since_rotation: f32,
+}
+
+impl Curation {
+    pub fn new(artworks: Vec<CuratedArtwork>) -> Self {
+        let mut curation = Self {
+            artworks,
+            seed: 0,
+            rotate_every: None,
+            dissolve_seconds: 2.0,
+            order: Vec::new(),
+            rotation: 0,
+            since_rotation: 0.0,
+        };
+        curation.shuffle();
+        curation
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.shuffle();
+        self
+    }
+
+    /// Seeds the shuffle from the current time, for a different hang on every visit.
+    pub fn per_visit(self) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.with_seed(seed)
+    }
+
+    pub fn with_rotation(mut self, seconds: f32) -> Self {
+        self.rotate_every = Some(seconds.max(f32::EPSILON));
+        self
+    }
+
+    pub fn with_dissolve_seconds(mut self, seconds: f32) -> Self {
+        self.dissolve_seconds = seconds.max(0.0);
+        self
+    }
+
+    fn shuffle(&mut self) {
+        let mut rng = SplitMix64::new(self.seed);
+        self.order = (0..self.artworks.len()).collect();
+        // Fisher-Yates
+        for i in (1..self.order.len()).rev() {
+            self.order.swap(i, rng.index(i + 1));
+        }
+    }
+
+    /// Index of the artwork for `slot` in the current rotation, out of `slots` in all.
+    fn artwork_for(&self, slot: usize, slots: usize) -> Option<usize> {
+        if self.order.is_empty() {
+            return None;
+        }
+        let position = (slot + self.rotation * slots.max(1)) % self.order.len();
+        Some(self.order[position])
+    }
+}
+
+/// Somewhere an artwork from the [`Curation`] pool hangs, such as a canvas in a frame. The
+/// artwork's material replaces the entity's [`MeshMaterial3d`].
+#[derive(Component, Debug, Clone)]
+pub struct DisplaySlot {
+    /// Which slot this is, so each slot gets a different artwork.
+    pub index: usize,
+    artwork: Option<usize>,
+}
+
+impl DisplaySlot {
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            artwork: None,
+        }
+    }
+
+    /// The index in [`Curation::artworks`] of the artwork hanging here, if any.
+    pub fn artwork(&self) -> Option<usize> {
+        self.artwork
+    }
+}
+
+/// Triggered on a [`DisplaySlot`] when a new artwork is hung there.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ArtworkHung {
+    pub entity: Entity,
+    /// Index in [`Curation::artworks`].
+    pub artwork: usize,
+    pub name: String,
+}
+
+/// An artwork being dissolved out of a slot, to be replaced by `to` halfway through.
+#[derive(Component, Debug)]
+struct Dissolve {
+    to: usize,
+    elapsed: f32,
+    swapped: bool,
+}
+
+fn hang(
+    commands: &mut Commands,
+    curation: &Curation,
+    entity: Entity,
+    slot: &mut DisplaySlot,
+    to: usize,
+) {
+    let artwork = &curation.artworks[to];
+    slot.artwork = Some(to);
+    commands
+        .entity(entity)
+        .insert(MeshMaterial3d(artwork.material.clone()));
+    commands.trigger(ArtworkHung {
+        entity,
+        artwork: to,
+        name: artwork.name.clone(),
+    });
+}
+
+fn hang_new_slots(
+    mut commands: Commands,
+    curation: Res<Curation>,
+    mut new_slots: Query<(Entity, &mut DisplaySlot), Added<DisplaySlot>>,
+    slots: Query<(), With<DisplaySlot>>,
+) {
+    let count = slots.iter().count();
+    for (entity, mut slot) in &mut new_slots {
+        if let Some(to) = curation.artwork_for(slot.index, count) {
+            hang(&mut commands, &curation, entity, &mut slot, to);
+        }
+    }
+}
+
+fn rotate_artworks(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut curation: ResMut<Curation>,
+    slots: Query<(Entity, &DisplaySlot), Without<Dissolve>>,
+) {
+    let Some(rotate_every) = curation.rotate_every else {
+        return;
+    };
+    curation.since_rotation += time.delta_secs();
+    if curation.since_rotation < rotate_every {
+        return;
+    }
+    curation.since_rotation = 0.0;
+    curation.rotation += 1;
+
+    let count = slots.iter().count();
+    for (entity, slot) in &slots {
+        let Some(to) = curation.artwork_for(slot.index, count) else {
+            continue;
+        };
+        if slot.artwork != Some(to) {
+            commands.entity(entity).insert(Dissolve {
+                to,
+                elapsed: 0.0,
+                swapped: false,
+            });
+        }
+    }
+}
+
+/// Dissolves every slot back to the first hang, for the next visitor to an unattended kiosk.
+fn rehang_for_next_visitor(
+    _reset: On<KioskReset>,
+    mut commands: Commands,
+    curation: Option<ResMut<Curation>>,
+    slots: Query<(Entity, &DisplaySlot, Option<&Dissolve>)>,
+) {
+    let Some(mut curation) = curation else {
+        return;
+    };
+    curation.rotation = 0;
+    curation.since_rotation = 0.0;
+
+    let count = slots.iter().count();
+    for (entity, slot, dissolve) in &slots {
+        let Some(to) = curation.artwork_for(slot.index, count) else {
+            continue;
+        };
+        // Where it'll end up once any dissolve under way has finished
+        let hung = dissolve.map_or(slot.artwork, |dissolve| Some(dissolve.to));
+        if hung != Some(to) {
+            commands.entity(entity).insert(Dissolve {
+                to,
+                elapsed: 0.0,
+                swapped: false,
+            });
+        }
+    }
+}
+
+/// Sets how opaque `material` is, blending only while it's partly transparent.
+fn set_opacity(
+    materials: &mut Assets<StandardMaterial>,
+    material: &Handle<StandardMaterial>,
+    opacity: f32,
+) {
+    let Some(material) = materials.get_mut(material) else {
+        return;
+    };
+    material.base_color.set_alpha(opacity);
+    material.alpha_mode = if opacity < 1.0 {
+        AlphaMode::Blend
+    } else {
+        AlphaMode::Opaque
+    };
+}
+
+fn dissolve_artworks(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    curation: Res<Curation>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut slots: Query<(Entity, &mut DisplaySlot, &mut Dissolve)>,
+) {
+    let half = curation.dissolve_seconds / 2.0;
+    for (entity, mut slot, mut dissolve) in &mut slots {
+        dissolve.elapsed += time.delta_secs();
+        let outgoing = slot
+            .artwork
+            .map(|artwork| curation.artworks[artwork].material.clone());
+        if !dissolve.swapped {
+            let opacity = 1.0 - (dissolve.elapsed / half.max(f32::EPSILON)).min(1.0);
+            if let Some(outgoing) = &outgoing {
+                set_opacity(&mut materials, outgoing, opacity);
+            }
+            if opacity > 0.0 {
+                continue;
+            }
+            // Left opaque for wherever it hangs next
+            if let Some(outgoing) = &outgoing {
+                set_opacity(&mut materials, outgoing, 1.0);
+            }
+            set_opacity(
+                &mut materials,
+                &curation.artworks[dissolve.to].material,
+                0.0,
+            );
+            hang(&mut commands, &curation, entity, &mut slot, dissolve.to);
+            dissolve.swapped = true;
+            continue;
+        }
+        let opacity = ((dissolve.elapsed - half) / half.max(f32::EPSILON)).clamp(0.0, 1.0);
+        set_opacity(
+            &mut materials,
+            &curation.artworks[dissolve.to].material,
+            opacity,
+        );
+        if opacity >= 1.0 {
+            commands.entity(entity).remove::<Dissolve>();
+        }
+    }
+}
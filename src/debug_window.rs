@@ -0,0 +1,145 @@
+//! Optional second OS window for debugging UIs.
+//!
+//! With [`DebugWindowSettings::separate_window`] enabled, the world inspector and performance UI
+//! are moved out of the main view into their own window, so they don't cover the diorama. The
+//! setting can be changed at runtime, and closing the debug window turns it back off.
+
+use bevy::camera::RenderTarget;
+use bevy::prelude::*;
+use bevy::ui::UiTargetCamera;
+use bevy::window::{PrimaryWindow, WindowRef};
+
+pub struct DebugWindowPlugin;
+
+impl Plugin for DebugWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DebugWindowSettings>()
+            .init_resource::<DebugWindowSettings>()
+            .init_resource::<DebugUiTarget>()
+            .add_systems(
+                Update,
+                (sync_debug_window, retarget_debug_ui).chain().run_if(
+                    resource_changed::<DebugWindowSettings>.or(resource_changed::<DebugUiTarget>),
+                ),
+            )
+            .add_systems(Update, retarget_added_debug_ui)
+            .add_observer(on_debug_window_closed)
+            .add_observer(on_primary_window_closed);
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Clone, Default)]
+#[reflect(Resource)]
+pub struct DebugWindowSettings {
+    /// Show debugging UIs in a separate window instead of over the diorama.
+    pub separate_window: bool,
+}
+
+/// Camera that debugging UIs should render to, or `None` for the main view.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DebugUiTarget(pub(crate) Option<Entity>);
+
+/// Marks the root node of a debugging UI so it follows [`DebugUiTarget`].
+#[derive(Component, Default)]
+pub(crate) struct DebugUi;
+
+#[derive(Component)]
+struct DebugWindow {
+    camera: Entity,
+}
+
+fn sync_debug_window(
+    mut commands: Commands,
+    settings: Res<DebugWindowSettings>,
+    mut target: ResMut<DebugUiTarget>,
+    windows: Query<(Entity, &DebugWindow)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    match (settings.separate_window, windows.single()) {
+        (true, Err(_)) => {
+            let window = commands
+                .spawn((
+                    Name::new("Debug window"),
+                    Window {
+                        title: "diorama debug".to_string(),
+                        ..default()
+                    },
+                ))
+                .id();
+            let camera = commands
+                .spawn((
+                    Name::new("Debug camera"),
+                    Camera2d,
+                    RenderTarget::Window(WindowRef::Entity(window)),
+                ))
+                .id();
+            commands.entity(window).insert(DebugWindow { camera });
+            target.set_if_neq(DebugUiTarget(Some(camera)));
+        }
+        (false, Ok((window, debug_window))) => {
+            commands.entity(debug_window.camera).despawn();
+            commands.entity(window).despawn();
+            target.set_if_neq(DebugUiTarget(None));
+        }
+        _ => {}
+    }
+}
+
+fn retarget_debug_ui(
+    mut commands: Commands,
+    target: Res<DebugUiTarget>,
+    roots: Query<Entity, With<DebugUi>>,
+) {
+    for root in roots.iter() {
+        retarget(&mut commands, root, *target);
+    }
+}
+
+fn retarget_added_debug_ui(
+    mut commands: Commands,
+    target: Res<DebugUiTarget>,
+    roots: Query<Entity, Added<DebugUi>>,
+) {
+    for root in roots.iter() {
+        retarget(&mut commands, root, *target);
+    }
+}
+
+fn retarget(commands: &mut Commands, root: Entity, target: DebugUiTarget) {
+    match target.0 {
+        Some(camera) => {
+            commands.entity(root).insert(UiTargetCamera(camera));
+        }
+        None => {
+            commands.entity(root).remove::<UiTargetCamera>();
+        }
+    }
+}
+
+/// The OS close button despawns the window, so keep the setting in sync when that happens.
+fn on_debug_window_closed(
+    remove: On<Remove, DebugWindow>,
+    windows: Query<&DebugWindow>,
+    mut commands: Commands,
+    mut settings: ResMut<DebugWindowSettings>,
+    mut target: ResMut<DebugUiTarget>,
+) {
+    if let Ok(debug_window) = windows.get(remove.entity) {
+        commands.entity(debug_window.camera).try_despawn();
+    }
+    settings.bypass_change_detection().separate_window = false;
+    target.set_if_neq(DebugUiTarget(None));
+}
+
+/// Bevy only exits once every window is closed, so take the debug window down with the main one.
+fn on_primary_window_closed(
+    _remove: On<Remove, PrimaryWindow>,
+    mut commands: Commands,
+    windows: Query<Entity, With<DebugWindow>>,
+) {
+    for window in windows.iter() {
+        commands.entity(window).despawn();
+    }
+}
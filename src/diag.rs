@@ -2,6 +2,11 @@ use bevy::prelude::*;
 use iyes_perf_ui::prelude::*;
 use leafwing_input_manager::prelude::*;
 
+use crate::controls::keyboard_not_captured;
+use crate::debug_window::DebugUi;
+use crate::kiosk::not_in_kiosk_mode;
+use crate::perf_graph::PerfGraphPlugin;
+
 pub struct DiagPlugin;
 
 impl Plugin for DiagPlugin {
@@ -11,17 +16,22 @@ impl Plugin for DiagPlugin {
             .add_plugins(bevy::diagnostic::EntityCountDiagnosticsPlugin::default())
             .add_plugins(bevy::diagnostic::SystemInformationDiagnosticsPlugin)
             .add_plugins(bevy::render::diagnostic::RenderDiagnosticsPlugin)
-            .add_plugins(PerfUiPlugin)
+            .add_plugins((PerfUiPlugin, PerfGraphPlugin))
             .add_plugins(InputManagerPlugin::<ToggleDiagAction>::default())
             .add_systems(Startup, setup_actions)
-            .add_systems(Update, handle_actions)
+            .add_systems(
+                Update,
+                handle_actions
+                    .run_if(not_in_kiosk_mode)
+                    .run_if(keyboard_not_captured),
+            )
             .add_systems(OnEnter(DiagState::Enabled), show_perf_ui)
             .add_systems(OnExit(DiagState::Enabled), hide_perf_ui);
     }
 }
 
 #[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
-enum DiagState {
+pub(crate) enum DiagState {
     Enabled,
     #[default]
     Disabled,
@@ -49,7 +59,7 @@ fn handle_actions(
 }
 
 fn show_perf_ui(mut commands: Commands) {
-    commands.spawn(PerfUiAllEntries::default());
+    commands.spawn((PerfUiAllEntries::default(), DebugUi));
 }
 
 fn hide_perf_ui(mut commands: Commands, perf_ui_root: Query<Entity, With<PerfUiRoot>>) {
@@ -0,0 +1,199 @@
+//! Session-wide log of dialogue lines and choices, with a toggleable panel to review them.
+//!
+//! Dialogue integrations (e.g. YarnSpinner in the examples) push entries into [`DialogueLog`] and
+//! mark running conversations with [`DialogueInProgress`]; this module only handles storage and
+//! display, so it doesn't depend on any dialogue crate.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::controls::keyboard_not_captured;
+
+pub struct DialogueLogPlugin;
+
+impl Plugin for DialogueLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DialogueLog>()
+            .init_state::<DialogueLogState>()
+            .add_plugins(InputManagerPlugin::<ToggleDialogueLogAction>::default())
+            .add_systems(Startup, setup_actions)
+            .add_systems(Update, handle_actions.run_if(keyboard_not_captured))
+            .add_systems(
+                Update,
+                update_dialogue_log_panel.run_if(in_state(DialogueLogState::Visible)),
+            )
+            .add_systems(OnEnter(DialogueLogState::Visible), show_dialogue_log_panel)
+            .add_systems(OnExit(DialogueLogState::Visible), hide_dialogue_log_panel);
+    }
+}
+
+/// Maximum number of entries retained for the session, oldest are dropped first.
+const MAX_ENTRIES: usize = 200;
+/// Number of most recent entries shown in the panel.
+const VISIBLE_ENTRIES: usize = 20;
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DialogueLogState {
+    Visible,
+    #[default]
+    Hidden,
+}
+
+/// Marks an entity driving a conversation, e.g. a dialogue runner, for as long as it exists.
+#[derive(Component, Default)]
+pub struct DialogueInProgress;
+
+/// Run condition that is true while no conversation is in progress.
+pub fn dialogue_inactive() -> impl FnMut(Query<(), With<DialogueInProgress>>) -> bool + Clone {
+    |dialogues: Query<(), With<DialogueInProgress>>| dialogues.is_empty()
+}
+
+/// What kind of dialogue content a [`DialogueLogEntry`] records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogueLogEntryKind {
+    Line,
+    Choice,
+}
+
+#[derive(Debug, Clone)]
+pub struct DialogueLogEntry {
+    pub kind: DialogueLogEntryKind,
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+/// Lines and choices seen during the current session, in the order they were presented.
+#[derive(Resource, Default)]
+pub struct DialogueLog {
+    entries: Vec<DialogueLogEntry>,
+}
+
+impl DialogueLog {
+    pub fn push_line(&mut self, speaker: Option<String>, text: impl Into<String>) {
+        self.push(DialogueLogEntry {
+            kind: DialogueLogEntryKind::Line,
+            speaker,
+            text: text.into(),
+        });
+    }
+
+    pub fn push_choice(&mut self, text: impl Into<String>) {
+        self.push(DialogueLogEntry {
+            kind: DialogueLogEntryKind::Choice,
+            speaker: None,
+            text: text.into(),
+        });
+    }
+
+    pub fn push(&mut self, entry: DialogueLogEntry) {
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[DialogueLogEntry] {
+        &self.entries
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[derive(Component)]
+struct DialogueLogPanel;
+
+#[derive(Component)]
+struct DialogueLogText;
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+struct ToggleDialogueLogAction;
+
+fn setup_actions(mut commands: Commands) {
+    let toggle_map = InputMap::new([(ToggleDialogueLogAction, KeyCode::KeyL)]);
+    commands.spawn((Name::new("Dialogue log controls"), toggle_map));
+}
+
+fn handle_actions(
+    action_state: Single<&ActionState<ToggleDialogueLogAction>>,
+    current_state: Res<State<DialogueLogState>>,
+    mut next_state: ResMut<NextState<DialogueLogState>>,
+) {
+    if action_state.just_pressed(&ToggleDialogueLogAction) {
+        match current_state.get() {
+            DialogueLogState::Visible => next_state.set(DialogueLogState::Hidden),
+            DialogueLogState::Hidden => next_state.set(DialogueLogState::Visible),
+        }
+    }
+}
+
+fn format_entries(log: &DialogueLog) -> String {
+    let entries = log.entries();
+    if entries.is_empty() {
+        return "No dialogue yet".to_string();
+    }
+    let start = entries.len().saturating_sub(VISIBLE_ENTRIES);
+    entries[start..]
+        .iter()
+        .map(|entry| match (entry.kind, &entry.speaker) {
+            (DialogueLogEntryKind::Line, Some(speaker)) => format!("{speaker}: {}", entry.text),
+            (DialogueLogEntryKind::Line, None) => entry.text.clone(),
+            (DialogueLogEntryKind::Choice, _) => format!("  > {}", entry.text),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn show_dialogue_log_panel(mut commands: Commands, log: Res<DialogueLog>) {
+    commands
+        .spawn((
+            Name::new("Dialogue log"),
+            DialogueLogPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                right: Val::Px(12.0),
+                width: Val::Px(420.0),
+                max_height: Val::Percent(80.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(6.0),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Dialogue log"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                DialogueLogText,
+                Text::new(format_entries(&log)),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn update_dialogue_log_panel(
+    log: Res<DialogueLog>,
+    mut text: Single<&mut Text, With<DialogueLogText>>,
+) {
+    if log.is_changed() {
+        text.0 = format_entries(&log);
+    }
+}
+
+fn hide_dialogue_log_panel(mut commands: Commands, panel: Query<Entity, With<DialogueLogPanel>>) {
+    for entity in panel.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,463 @@
+//! The look of a scene's surroundings: its sky, fog and ambient light, and how they change
+//! through the day.
+//!
+//! Inserting an [`EnvironmentSettings`] resource, usually made from an [`EnvironmentPreset`] such
+//! as [`EnvironmentPreset::Underwater`], sets the clear colour and ambient light and gives the
+//! player camera its distance fog in one go, along with optional volumetric fog lit by every
+//! directional light. Changing the resource applies the changes.
+//!
+//! Inserting a [`DayNightCycle`] resource animates every directional light tagged [`Sun`]: it
+//! rises in the east, arcs overhead and sets in the west over [`DayNightCycle::day_length`]
+//! seconds of [`GameTime`], warming towards the horizon and dimming to nothing below it. The
+//! [`GlobalAmbientLight`], and optionally the [`ClearColor`], follow the sun between their day and
+//! night settings, except while an [`AmbienceZone`](crate::ambience::AmbienceZone) has
+//! overridden the ambient light. [`Dawn`] and [`Dusk`] are triggered as the sun crosses the
+//! horizon.
+
+use std::f32::consts::{FRAC_PI_4, TAU};
+
+use bevy::light::{FogVolume, VolumetricFog, VolumetricLight};
+use bevy::prelude::*;
+
+use crate::ambience::ActiveAmbience;
+use crate::firstsight::PlayerCamera;
+use crate::game_time::GameTime;
+use crate::state::PausableSystems;
+
+pub struct EnvironmentPlugin;
+
+impl Plugin for EnvironmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (apply_environment, light_volumetric_fog)
+                .chain()
+                .run_if(resource_exists::<EnvironmentSettings>),
+        )
+        .add_systems(
+            Update,
+            (advance_day_night, (move_sun, light_sky))
+                .chain()
+                .in_set(PausableSystems)
+                .run_if(resource_exists::<DayNightCycle>)
+                .after(apply_environment),
+        );
+    }
+}
+
+/// Ready-made [`EnvironmentSettings`] for common kinds of scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvironmentPreset {
+    /// A blue sky with a faint haze in the distance.
+    Outdoor,
+    /// A bright, warm room with no fog.
+    Indoor,
+    /// Deep blue water that swallows the view within a few dozen metres.
+    Underwater,
+    /// A dark sky over dim, cold light and a thin blue-violet haze.
+    AlienAtmosphere,
+}
+
+impl EnvironmentPreset {
+    pub fn settings(self) -> EnvironmentSettings {
+        match self {
+            Self::Outdoor => EnvironmentSettings::new(Color::srgb(0.53, 0.75, 0.95))
+                .with_ambient_light(Color::WHITE, 300.0)
+                .with_fog(DistanceFog {
+                    color: Color::srgba(0.7, 0.8, 0.9, 1.0),
+                    falloff: FogFalloff::from_visibility(400.0),
+                    ..default()
+                }),
+            Self::Indoor => EnvironmentSettings::new(Color::srgb(0.95, 0.95, 0.9))
+                .with_ambient_light(Color::srgb(0.95, 0.95, 0.9), 1000.0),
+            Self::Underwater => EnvironmentSettings::new(Color::srgb(0.02, 0.15, 0.3))
+                .with_ambient_light(Color::srgb(0.4, 0.6, 0.8), 800.0)
+                .with_fog(DistanceFog {
+                    color: Color::srgb(0.02, 0.15, 0.3),
+                    falloff: FogFalloff::from_visibility_colors(
+                        60.0,
+                        Color::srgb(0.35, 0.15, 0.1),
+                        Color::srgb(0.05, 0.2, 0.3),
+                    ),
+                    ..default()
+                }),
+            Self::AlienAtmosphere => EnvironmentSettings::new(Color::srgb(0.05, 0.05, 0.1))
+                .with_ambient_light(Color::srgb(0.1, 0.1, 0.2), 200.0)
+                .with_fog(DistanceFog {
+                    color: Color::srgb(0.1, 0.1, 0.2),
+                    falloff: FogFalloff::from_visibility_squared(180.0),
+                    ..default()
+                }),
+        }
+    }
+}
+
+impl From<EnvironmentPreset> for EnvironmentSettings {
+    fn from(preset: EnvironmentPreset) -> Self {
+        preset.settings()
+    }
+}
+
+/// The clear colour, ambient light and fog for a scene, applied while this resource exists.
+#[derive(Resource, Debug, Clone)]
+pub struct EnvironmentSettings {
+    pub clear_color: Color,
+    pub ambient_light: GlobalAmbientLight,
+    /// Distance fog for the player camera, or `None` for a clear view.
+    pub fog: Option<DistanceFog>,
+    pub volumetric_fog: Option<VolumetricFogSettings>,
+}
+
+impl EnvironmentSettings {
+    pub fn new(clear_color: Color) -> Self {
+        Self {
+            clear_color,
+            ambient_light: GlobalAmbientLight::default(),
+            fog: None,
+            volumetric_fog: None,
+        }
+    }
+
+    pub fn with_clear_color(mut self, clear_color: Color) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+
+    pub fn with_ambient_light(mut self, color: Color, brightness: f32) -> Self {
+        self.ambient_light.color = color;
+        self.ambient_light.brightness = brightness;
+        self
+    }
+
+    pub fn with_fog(mut self, fog: DistanceFog) -> Self {
+        self.fog = Some(fog);
+        self
+    }
+
+    pub fn without_fog(mut self) -> Self {
+        self.fog = None;
+        self
+    }
+
+    pub fn with_volumetric_fog(mut self, volumetric_fog: VolumetricFogSettings) -> Self {
+        self.volumetric_fog = Some(volumetric_fog);
+        self
+    }
+}
+
+/// A box of volumetric fog that directional lights shine through, for visible shafts of light.
+/// It's far more expensive to render than distance fog, so it's best kept to the area it's needed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumetricFogSettings {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    pub color: Color,
+    pub density: f32,
+    /// Brightness of the fog's own glow, before any light shines through it.
+    pub ambient_intensity: f32,
+}
+
+impl VolumetricFogSettings {
+    pub fn new(half_extents: Vec3) -> Self {
+        Self {
+            center: Vec3::ZERO,
+            half_extents,
+            color: Color::WHITE,
+            density: 0.1,
+            ambient_intensity: 0.1,
+        }
+    }
+
+    pub fn centered_at(mut self, center: Vec3) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density.max(0.0);
+        self
+    }
+
+    pub fn with_ambient_intensity(mut self, ambient_intensity: f32) -> Self {
+        self.ambient_intensity = ambient_intensity.max(0.0);
+        self
+    }
+}
+
+/// The fog volume spawned for [`EnvironmentSettings::volumetric_fog`].
+#[derive(Component)]
+struct EnvironmentFogVolume;
+
+fn apply_environment(
+    mut commands: Commands,
+    settings: Res<EnvironmentSettings>,
+    camera: Single<(Entity, Ref<PlayerCamera>)>,
+    fog_volumes: Query<Entity, With<EnvironmentFogVolume>>,
+) {
+    let (camera, added) = (camera.0, camera.1.is_added());
+    if !settings.is_changed() && !added {
+        return;
+    }
+    commands.insert_resource(ClearColor(settings.clear_color));
+    commands.insert_resource(settings.ambient_light.clone());
+    match &settings.fog {
+        Some(fog) => {
+            commands.entity(camera).insert(fog.clone());
+        }
+        None => {
+            commands.entity(camera).remove::<DistanceFog>();
+        }
+    }
+
+    for fog_volume in &fog_volumes {
+        commands.entity(fog_volume).despawn();
+    }
+    match settings.volumetric_fog {
+        Some(volumetric) => {
+            commands.entity(camera).insert(VolumetricFog {
+                ambient_color: volumetric.color,
+                ambient_intensity: volumetric.ambient_intensity,
+                ..default()
+            });
+            commands.spawn((
+                Name::new("Environment fog volume"),
+                EnvironmentFogVolume,
+                FogVolume {
+                    fog_color: volumetric.color,
+                    density_factor: volumetric.density,
+                    ..default()
+                },
+                // Fog volumes fill a unit cube, scaled to size
+                Transform::from_translation(volumetric.center)
+                    .with_scale(volumetric.half_extents * 2.0),
+            ));
+        }
+        None => {
+            commands.entity(camera).remove::<VolumetricFog>();
+        }
+    }
+}
+
+/// Lets every directional light shine through the volumetric fog, including ones spawned later.
+fn light_volumetric_fog(
+    mut commands: Commands,
+    settings: Res<EnvironmentSettings>,
+    unlit: Query<Entity, (With<DirectionalLight>, Without<VolumetricLight>)>,
+    lit: Query<Entity, (With<DirectionalLight>, With<VolumetricLight>)>,
+) {
+    if settings.volumetric_fog.is_some() {
+        for light in &unlit {
+            commands.entity(light).insert(VolumetricLight);
+        }
+    } else if settings.is_changed() {
+        for light in &lit {
+            commands.entity(light).remove::<VolumetricLight>();
+        }
+    }
+}
+
+/// Marks a directional light as the sun, to be moved by the [`DayNightCycle`].
+#[derive(Component, Debug, Default, Clone, Copy)]
+#[require(DirectionalLight)]
+pub struct Sun;
+
+/// Triggered when the sun rises above the horizon.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Dawn;
+
+/// Triggered when the sun sets below the horizon.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Dusk;
+
+/// The time of day and how the sun and sky look through it.
+#[derive(Resource, Debug, Clone)]
+pub struct DayNightCycle {
+    /// Seconds for a whole day and night.
+    pub day_length: f32,
+    /// From 0 to 1, where 0 is midnight, 0.25 sunrise, 0.5 noon and 0.75 sunset.
+    pub time_of_day: f32,
+    /// Compass angle of sunrise in radians, anticlockwise from +X when looking down.
+    pub azimuth: f32,
+    /// How far the sun's path leans from passing straight overhead, in radians.
+    pub tilt: f32,
+    /// The sun's illuminance at noon, in lux.
+    pub noon_illuminance: f32,
+    /// Colour temperature of the sun overhead, in kelvin.
+    pub noon_kelvin: f32,
+    /// Colour temperature of the sun at the horizon, in kelvin.
+    pub horizon_kelvin: f32,
+    pub day_ambient_brightness: f32,
+    pub night_ambient_brightness: f32,
+    pub night_ambient_color: Color,
+    /// Clear colours for the day and night sky, if the cycle should set it.
+    pub sky: Option<(Color, Color)>,
+    was_day: Option<bool>,
+}
+
+impl Default for DayNightCycle {
+    fn default() -> Self {
+        Self::new(600.0)
+    }
+}
+
+impl DayNightCycle {
+    pub fn new(day_length: f32) -> Self {
+        Self {
+            day_length: day_length.max(f32::EPSILON),
+            time_of_day: 0.3,
+            azimuth: 0.0,
+            tilt: FRAC_PI_4 / 2.0,
+            noon_illuminance: 10_000.0,
+            noon_kelvin: 6000.0,
+            horizon_kelvin: 2200.0,
+            day_ambient_brightness: 300.0,
+            night_ambient_brightness: 40.0,
+            night_ambient_color: Color::srgb(0.4, 0.5, 0.9),
+            sky: None,
+            was_day: None,
+        }
+    }
+
+    pub fn with_time_of_day(mut self, time_of_day: f32) -> Self {
+        self.time_of_day = time_of_day.rem_euclid(1.0);
+        self
+    }
+
+    pub fn with_sun_path(mut self, azimuth: f32, tilt: f32) -> Self {
+        self.azimuth = azimuth;
+        self.tilt = tilt;
+        self
+    }
+
+    pub fn with_noon_illuminance(mut self, illuminance: f32) -> Self {
+        self.noon_illuminance = illuminance.max(0.0);
+        self
+    }
+
+    pub fn with_ambient(mut self, day_brightness: f32, night_brightness: f32) -> Self {
+        self.day_ambient_brightness = day_brightness.max(0.0);
+        self.night_ambient_brightness = night_brightness.max(0.0);
+        self
+    }
+
+    pub fn with_sky(mut self, day: Color, night: Color) -> Self {
+        self.sky = Some((day, night));
+        self
+    }
+
+    /// Unit vector pointing towards the sun.
+    pub fn sun_direction(&self) -> Vec3 {
+        // Measured from sunrise, so the sun is on the horizon at 0 and PI
+        let angle = (self.time_of_day - 0.25) * TAU;
+        let path = Vec3::new(
+            angle.cos(),
+            angle.sin() * self.tilt.cos(),
+            angle.sin() * self.tilt.sin(),
+        );
+        Quat::from_rotation_y(self.azimuth) * path
+    }
+
+    /// Sine of the sun's angle above the horizon, from -1 to 1.
+    pub fn sun_elevation(&self) -> f32 {
+        self.sun_direction().y
+    }
+
+    pub fn is_day(&self) -> bool {
+        self.sun_elevation() > 0.0
+    }
+
+    /// How much daylight there is, from 0 at night to 1 with the sun well up, easing through
+    /// twilight.
+    pub fn daylight(&self) -> f32 {
+        let t = ((self.sun_elevation() + 0.1) / 0.4).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    fn sun_color(&self) -> Color {
+        let height = self.sun_elevation().clamp(0.0, 0.5) / 0.5;
+        kelvin_to_color(self.horizon_kelvin.lerp(self.noon_kelvin, height))
+    }
+}
+
+/// Approximate colour of a black body at `kelvin`, after Tanner Helland's fit.
+fn kelvin_to_color(kelvin: f32) -> Color {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.699 * (t - 60.0).powf(-0.133_204_76)
+    };
+    let green = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_17 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+    Color::srgb(
+        red.clamp(0.0, 255.0) / 255.0,
+        green.clamp(0.0, 255.0) / 255.0,
+        blue.clamp(0.0, 255.0) / 255.0,
+    )
+}
+
+fn advance_day_night(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut cycle: ResMut<DayNightCycle>,
+) {
+    cycle.time_of_day = (cycle.time_of_day + time.delta_secs() / cycle.day_length).rem_euclid(1.0);
+    let is_day = cycle.is_day();
+    match cycle.was_day {
+        Some(false) if is_day => commands.trigger(Dawn),
+        Some(true) if !is_day => commands.trigger(Dusk),
+        _ => {}
+    }
+    if cycle.was_day != Some(is_day) {
+        cycle.was_day = Some(is_day);
+    }
+}
+
+fn move_sun(
+    cycle: Res<DayNightCycle>,
+    mut suns: Query<(&mut Transform, &mut DirectionalLight), With<Sun>>,
+) {
+    let direction = cycle.sun_direction();
+    let daylight = cycle.daylight();
+    let color = cycle.sun_color();
+    for (mut transform, mut light) in &mut suns {
+        transform.look_to(-direction, Vec3::Y);
+        light.illuminance = cycle.noon_illuminance * daylight;
+        light.color = color;
+    }
+}
+
+fn light_sky(
+    cycle: Res<DayNightCycle>,
+    ambience: Option<Res<ActiveAmbience>>,
+    mut ambient: ResMut<GlobalAmbientLight>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    let daylight = cycle.daylight();
+    // Zones put the baseline back when the player leaves, and it's caught up then
+    if ambience.is_none_or(|ambience| ambience.zone.is_none()) {
+        let day_color = cycle.sun_color().mix(&Color::WHITE, 0.5);
+        ambient.color = cycle.night_ambient_color.mix(&day_color, daylight);
+        ambient.brightness = cycle
+            .night_ambient_brightness
+            .lerp(cycle.day_ambient_brightness, daylight);
+    }
+    if let Some((day, night)) = cycle.sky {
+        clear_color.0 = night.mix(&day, daylight);
+    }
+}
@@ -0,0 +1,467 @@
+//! Fires that spread between flammable things until they burn out or are put out.
+//!
+//! A [`Flammable`] catches light after enough seconds of heat, from an [`IgnitionSource`] or
+//! another fire within range, or straight away with [`Flammable::ignite`]. It then burns with a
+//! flickering flame and a trail of smoke, heating anything flammable around it, until its fuel runs
+//! out and it's left burnt out. Burning things are put out by being underwater in a
+//! [`WaterVolume`], or by the player spraying them with an [`Extinguisher`]'s item in hand, and can
+//! catch light again later with whatever fuel they have left. [`Ignited`], [`Extinguished`]
+//! and [`BurntOut`] are triggered on each as it goes, and [`Flammable::integrity`] says how much is
+//! left, for scenes to show the damage.
+
+use bevy::light::NotShadowCaster;
+use bevy::prelude::*;
+
+use crate::carry::Carrying;
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::firstsight::PlayerCamera;
+use crate::game_time::GameTime;
+use crate::inventory::Inventory;
+use crate::particles::{LifeCurve, LifeGradient, ParticleEmitter};
+use crate::physics::WaterVolume;
+use crate::rng::SplitMix64;
+use crate::state::PausableSystems;
+
+pub struct FirePlugin;
+
+impl Plugin for FirePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FireAssets>().add_systems(
+            Update,
+            (
+                heat_flammables,
+                burn,
+                spray_extinguisher.run_if(resource_exists::<Extinguisher>),
+            )
+                .chain()
+                .in_set(PausableSystems),
+        );
+    }
+}
+
+/// Seconds between puffs of smoke from a fire.
+const SMOKE_INTERVAL: f32 = 0.25;
+/// Seconds a puff of smoke or spray lasts.
+const PUFF_LIFETIME: f32 = 2.5;
+/// Where the spray comes from, relative to the player camera.
+const SPRAY_OFFSET: Vec3 = Vec3::new(0.0, -0.3, -0.5);
+/// Seconds between puffs of extinguisher spray.
+const SPRAY_INTERVAL: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FireState {
+    #[default]
+    Unburnt,
+    Burning,
+    BurntOut,
+}
+
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct Flammable {
+    /// Seconds of heat it takes to catch light.
+    pub ignition_seconds: f32,
+    /// Seconds it burns for before burning out.
+    pub fuel_seconds: f32,
+    /// How far its fire heats other flammable things.
+    pub spread_radius: f32,
+    /// Rough radius of the thing, which its flames and smoke are scaled to.
+    pub size: f32,
+    state: FireState,
+    heat: f32,
+    burnt: f32,
+    dousing: f32,
+    flame: Option<Entity>,
+    smoke: Option<Entity>,
+}
+
+impl Flammable {
+    pub fn new(fuel_seconds: f32) -> Self {
+        Self {
+            ignition_seconds: 2.0,
+            fuel_seconds: fuel_seconds.max(f32::EPSILON),
+            spread_radius: 3.0,
+            size: 0.5,
+            state: FireState::Unburnt,
+            heat: 0.0,
+            burnt: 0.0,
+            dousing: 0.0,
+            flame: None,
+            smoke: None,
+        }
+    }
+
+    pub fn with_ignition_seconds(mut self, seconds: f32) -> Self {
+        self.ignition_seconds = seconds.max(0.0);
+        self
+    }
+
+    pub fn with_spread_radius(mut self, radius: f32) -> Self {
+        self.spread_radius = radius.max(0.0);
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size.max(0.0);
+        self
+    }
+
+    /// Sets it alight, unless it's already burning or burnt out.
+    pub fn ignite(&mut self) {
+        if self.state == FireState::Unburnt {
+            self.heat = self.ignition_seconds;
+        }
+    }
+
+    pub fn state(&self) -> FireState {
+        self.state
+    }
+
+    pub fn is_burning(&self) -> bool {
+        self.state == FireState::Burning
+    }
+
+    /// How much is left unburnt, from 1 untouched to 0 burnt out.
+    pub fn integrity(&self) -> f32 {
+        (1.0 - self.burnt / self.fuel_seconds).clamp(0.0, 1.0)
+    }
+}
+
+/// Heats flammable things within `radius`, such as a torch, lava or a spark from a fault.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[require(Transform)]
+pub struct IgnitionSource {
+    pub radius: f32,
+}
+
+impl IgnitionSource {
+    pub fn new(radius: f32) -> Self {
+        Self { radius }
+    }
+}
+
+/// Lets the player put out fires with the use item binding while holding `item` from their
+/// [`Inventory`], spraying fires ahead of them within `range`.
+#[derive(Resource, Debug, Clone)]
+pub struct Extinguisher {
+    pub item: String,
+    pub range: f32,
+    /// Half-angle of the spray, in radians.
+    pub spread: f32,
+    /// Seconds of spraying a fire takes to put it out.
+    pub douse_seconds: f32,
+}
+
+impl Extinguisher {
+    pub fn new(item: impl Into<String>) -> Self {
+        Self {
+            item: item.into(),
+            range: 5.0,
+            spread: 0.35,
+            douse_seconds: 1.5,
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range.max(0.0);
+        self
+    }
+
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.spread = spread.clamp(0.0, std::f32::consts::FRAC_PI_2);
+        self
+    }
+
+    pub fn with_douse_seconds(mut self, seconds: f32) -> Self {
+        self.douse_seconds = seconds.max(0.0);
+        self
+    }
+}
+
+/// Triggered on a [`Flammable`] when it catches light.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Ignited {
+    pub entity: Entity,
+}
+
+/// Triggered on a [`Flammable`] when its fire is put out.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Extinguished {
+    pub entity: Entity,
+}
+
+/// Triggered on a [`Flammable`] when it runs out of fuel.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct BurntOut {
+    pub entity: Entity,
+}
+
+#[derive(Resource)]
+struct FireAssets {
+    puff: Handle<Mesh>,
+    flame_material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for FireAssets {
+    fn from_world(world: &mut World) -> Self {
+        let puff = world.resource_mut::<Assets<Mesh>>().add(
+            Sphere::new(1.0)
+                .mesh()
+                .ico(1)
+                .unwrap_or_else(|_| Sphere::new(1.0).into()),
+        );
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let flame = Color::srgb(1.0, 0.45, 0.1);
+        Self {
+            puff,
+            flame_material: materials.add(StandardMaterial {
+                base_color: flame,
+                emissive: LinearRgba::from(flame) * 6.0,
+                alpha_mode: AlphaMode::Add,
+                unlit: true,
+                ..default()
+            }),
+        }
+    }
+}
+
+/// The flame on a burning [`Flammable`].
+#[derive(Component)]
+struct Flame {
+    size: f32,
+    phase: f32,
+}
+
+/// The emitter spraying from the player camera while they use an [`Extinguisher`].
+#[derive(Component)]
+struct ExtinguisherSpray;
+
+/// Puffs of smoke or spray that drift off, billowing out and then thinning away to nothing.
+fn puffs(size: f32, velocity: Vec3, jitter: Vec3, color: Color) -> ParticleEmitter {
+    ParticleEmitter::default()
+        .with_rate(1.0 / SMOKE_INTERVAL)
+        .with_lifetime(PUFF_LIFETIME, 0.0)
+        .with_velocity(velocity, jitter)
+        .with_drag(1.0)
+        .with_size(size, 0.25)
+        .with_size_over_life(LifeCurve::new([
+            (0.0, 1.0),
+            (0.25, 1.6),
+            (0.5, 1.9),
+            (0.75, 1.4),
+            (1.0, 0.0),
+        ]))
+        .with_color_over_life(LifeGradient::constant(color))
+}
+
+fn heat_flammables(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    assets: Res<FireAssets>,
+    sources: Query<(&IgnitionSource, &GlobalTransform)>,
+    mut flammables: Query<(Entity, &mut Flammable, &GlobalTransform)>,
+) {
+    let dt = time.delta_secs();
+    let heat: Vec<(Entity, Vec3, f32)> = sources
+        .iter()
+        .map(|(source, transform)| (Entity::PLACEHOLDER, transform.translation(), source.radius))
+        .chain(
+            flammables
+                .iter()
+                .filter(|(_, flammable, _)| flammable.is_burning())
+                .map(|(entity, flammable, transform)| {
+                    (entity, transform.translation(), flammable.spread_radius)
+                }),
+        )
+        .collect();
+
+    for (entity, mut flammable, transform) in &mut flammables {
+        if flammable.state != FireState::Unburnt {
+            continue;
+        }
+        let position = transform.translation();
+        let heated = heat.iter().any(|&(source, origin, radius)| {
+            source != entity && origin.distance(position) <= radius + flammable.size
+        });
+        if heated {
+            flammable.heat += dt;
+        } else if flammable.heat < flammable.ignition_seconds {
+            flammable.heat = (flammable.heat - dt).max(0.0);
+        }
+        if flammable.heat < flammable.ignition_seconds {
+            continue;
+        }
+
+        flammable.state = FireState::Burning;
+        flammable.dousing = 0.0;
+        let size = flammable.size;
+        let flame = commands
+            .spawn((
+                Name::new("Flame"),
+                Flame {
+                    size,
+                    phase: SplitMix64::new(entity.to_bits()).range_f32(0.0, 100.0),
+                },
+                Mesh3d(assets.puff.clone()),
+                MeshMaterial3d(assets.flame_material.clone()),
+                Transform::from_translation(Vec3::Y * size * 0.5),
+                PointLight {
+                    color: Color::srgb(1.0, 0.55, 0.2),
+                    intensity: 0.0,
+                    range: size * 12.0,
+                    ..default()
+                },
+                NotShadowCaster,
+            ))
+            .id();
+        let smoke = commands
+            .spawn((
+                Name::new("Smoke"),
+                puffs(
+                    size * 0.8,
+                    Vec3::Y * 1.2,
+                    Vec3::new(0.2, 0.0, 0.2),
+                    Color::srgba(0.2, 0.2, 0.2, 0.35),
+                )
+                .with_seed(entity.to_bits()),
+                Transform::from_translation(Vec3::Y * size * 1.2),
+            ))
+            .id();
+        commands.entity(entity).add_children(&[flame, smoke]);
+        flammable.flame = Some(flame);
+        flammable.smoke = Some(smoke);
+        commands.trigger(Ignited { entity });
+    }
+}
+
+fn burn(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    waters: Query<(&WaterVolume, &GlobalTransform)>,
+    mut flammables: Query<(Entity, &mut Flammable, &GlobalTransform)>,
+    mut flames: Query<(&Flame, &mut Transform, &mut PointLight)>,
+) {
+    let dt = time.delta_secs();
+    let elapsed = time.elapsed_secs();
+    for (entity, mut flammable, transform) in &mut flammables {
+        if !flammable.is_burning() {
+            continue;
+        }
+        let position = transform.translation();
+        if waters
+            .iter()
+            .any(|(water, water_transform)| water.contains(water_transform, position))
+        {
+            put_out(&mut commands, &mut flammable);
+            commands.trigger(Extinguished { entity });
+            continue;
+        }
+
+        flammable.burnt += dt;
+        if flammable.burnt >= flammable.fuel_seconds {
+            put_out(&mut commands, &mut flammable);
+            flammable.state = FireState::BurntOut;
+            commands.trigger(BurntOut { entity });
+            continue;
+        }
+
+        // Dies down as the fuel runs out, and while being doused
+        let strength = (flammable.integrity() * 4.0).min(1.0) * (1.0 - flammable.dousing).max(0.2);
+        if let Some((flame, mut flame_transform, mut light)) =
+            flammable.flame.and_then(|flame| flames.get_mut(flame).ok())
+        {
+            let t = elapsed * 9.0 + flame.phase;
+            let flicker = 0.8 + 0.12 * t.sin() + 0.08 * (t * 2.3).sin();
+            flame_transform.scale =
+                Vec3::new(0.6, 1.3 * flicker, 0.6) * flame.size * strength.max(0.1);
+            light.intensity = 60_000.0 * flame.size * flicker * strength;
+        }
+    }
+}
+
+/// Stops `flammable` burning and removes its flame, leaving it able to catch light again. Its
+/// smoke stops, and despawns once the last of it has drifted away.
+fn put_out(commands: &mut Commands, flammable: &mut Flammable) {
+    flammable.state = FireState::Unburnt;
+    flammable.heat = 0.0;
+    flammable.dousing = 0.0;
+    if let Some(flame) = flammable.flame.take() {
+        commands.entity(flame).despawn();
+    }
+    if let Some(smoke) = flammable.smoke.take() {
+        commands
+            .entity(smoke)
+            .entry::<ParticleEmitter>()
+            .and_modify(|mut emitter| {
+                emitter.rate = 0.0;
+                emitter.despawn_when_done = true;
+            });
+    }
+}
+
+fn spray_extinguisher(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    config: Res<ControlsConfig>,
+    input: ControlsInput,
+    extinguisher: Res<Extinguisher>,
+    inventory: Res<Inventory>,
+    camera: Single<(Entity, &GlobalTransform), With<PlayerCamera>>,
+    carrying: Query<(), With<Carrying>>,
+    mut flammables: Query<(Entity, &mut Flammable, &GlobalTransform)>,
+    mut spray: Query<&mut ParticleEmitter, With<ExtinguisherSpray>>,
+) {
+    // Using an item while carrying something throws it instead
+    let spraying = input.pressed(&config.use_item)
+        && inventory.is_holding(&extinguisher.item)
+        && carrying.is_empty();
+    let (camera_entity, camera) = camera.into_inner();
+    let rate = if spraying { 1.0 / SPRAY_INTERVAL } else { 0.0 };
+    match spray.single_mut() {
+        Ok(mut spray) => {
+            if spray.rate != rate {
+                spray.rate = rate;
+            }
+        }
+        Err(_) => {
+            let jitter = extinguisher.range * extinguisher.spread.tan() * 0.5;
+            commands.spawn((
+                Name::new("Extinguisher spray"),
+                ExtinguisherSpray,
+                puffs(
+                    0.3,
+                    Vec3::NEG_Z * extinguisher.range,
+                    Vec3::splat(jitter),
+                    Color::srgba(0.95, 0.97, 1.0, 0.5),
+                )
+                .with_rate(rate),
+                Transform::from_translation(SPRAY_OFFSET),
+                ChildOf(camera_entity),
+            ));
+        }
+    }
+    if !spraying {
+        return;
+    }
+
+    let dt = time.delta_secs();
+    let origin = camera.translation();
+    let forward = camera.forward();
+    for (entity, mut flammable, transform) in &mut flammables {
+        if !flammable.is_burning() {
+            continue;
+        }
+        let to_fire = transform.translation() - origin;
+        let distance = to_fire.length();
+        if distance > extinguisher.range + flammable.size
+            || to_fire.angle_between(*forward) > extinguisher.spread
+        {
+            continue;
+        }
+        flammable.dousing += dt / extinguisher.douse_seconds.max(f32::EPSILON);
+        if flammable.dousing >= 1.0 {
+            put_out(&mut commands, &mut flammable);
+            commands.trigger(Extinguished { entity });
+        }
+    }
+}
@@ -6,11 +6,16 @@
 #![allow(clippy::useless_conversion)]
 use avian3d::prelude::*;
 use bevy::input::mouse::AccumulatedMouseMotion;
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
-use bevy_tnua::builtins::{TnuaBuiltinJumpConfig, TnuaBuiltinWalkConfig};
+use bevy_tnua::builtins::{TnuaBuiltinCrouchConfig, TnuaBuiltinJumpConfig, TnuaBuiltinWalkConfig};
 use bevy_tnua::prelude::*;
 use bevy_tnua_avian3d::*;
 
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::kinematic::{KinematicController, KinematicControllerPlugin};
+use crate::movement::{MovementMode, MovementModePlugin, handle_free_movement};
+
 pub struct FirstSightPlugin;
 
 impl Plugin for FirstSightPlugin {
@@ -18,27 +23,61 @@ impl Plugin for FirstSightPlugin {
         app.add_plugins((
             TnuaControllerPlugin::<PlayerControlScheme>::new(FixedUpdate),
             TnuaAvian3dPlugin::new(FixedUpdate),
+            KinematicControllerPlugin,
+            MovementModePlugin,
         ))
-        .add_systems(Update, handle_movement.in_set(TnuaUserControlsSystems))
+        .configure_sets(Update, PlayerInputSet.in_set(TnuaUserControlsSystems))
+        .configure_sets(
+            PostUpdate,
+            PlayerCameraSet.before(TransformSystems::Propagate),
+        )
+        .add_systems(
+            Update,
+            (update_stance, handle_movement, handle_free_movement)
+                .chain()
+                .in_set(PlayerInputSet),
+        )
         .add_systems(
             PostUpdate,
-            (update_camera_position, update_camera_looking_at).before(TransformSystems::Propagate),
+            (
+                update_camera_height,
+                update_camera_position,
+                update_camera_looking_at,
+            )
+                .chain()
+                .in_set(PlayerCameraSet),
         );
     }
 }
 
+/// Systems that read player input and turn it into controller actions, in [`Update`].
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInputSet;
+
+/// Systems that move the player camera to follow the simulated player body and apply mouse look,
+/// in [`PostUpdate`] before transform propagation. Order after this set to see the camera's final
+/// transform for the frame.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerCameraSet;
+
 pub const DEFAULT_PLAYER_HEIGHT: f32 = 1.;
 pub const DEFAULT_PLAYER_RADIUS: f32 = 0.5;
 
 const LOOK_SENSITIVITY: f32 = 0.002;
-const JUMP_HEIGHT: f32 = 4.;
+pub(crate) const JUMP_HEIGHT: f32 = 4.;
 const SPEED: f32 = 10.;
 const SPRINT_MULTIPLIER: f32 = 1.5;
+const CROUCH_MULTIPLIER: f32 = 0.5;
+/// How much shorter the capsule (and camera) gets when crouching.
+const CROUCH_HEIGHT_REDUCTION: f32 = 0.8;
+/// Rate at which the camera height approaches its target, higher is snappier.
+const CAMERA_HEIGHT_SMOOTHING: f32 = 12.;
 
 #[derive(TnuaScheme)]
 #[scheme(basis = TnuaBuiltinWalk)]
 pub enum PlayerControlScheme {
     Jump(TnuaBuiltinJump),
+    Crouch(TnuaBuiltinCrouch),
 }
 
 pub fn create_player_control_scheme_config(
@@ -54,13 +93,17 @@ pub fn create_player_control_scheme_config(
             height: JUMP_HEIGHT,
             ..Default::default()
         },
+        crouch: TnuaBuiltinCrouchConfig {
+            float_offset: -CROUCH_HEIGHT_REDUCTION / 2.0,
+            ..Default::default()
+        },
     })
 }
 
 /// Camera component for first-person player view.
 ///
-/// Tracks yaw and pitch for smooth camera rotation.
-#[derive(Component, Default)]
+/// Tracks yaw and pitch for smooth camera rotation, relative to the way the player is standing.
+#[derive(Component, Default, Clone)]
 #[require(
     Camera3d,
     Camera,
@@ -70,11 +113,27 @@ pub fn create_player_control_scheme_config(
 pub struct PlayerCamera {
     yaw: f32,
     pitch: f32,
+    /// Turns with the player's up, so the view stays level as they walk around a planet.
+    frame: Quat,
+}
+
+impl PlayerCamera {
+    /// Turns the view about the vertical axis, e.g. to face the way out of a portal.
+    pub(crate) fn turn(&mut self, yaw: f32) {
+        self.yaw += yaw;
+    }
+
+    /// The camera's rotation for its yaw and pitch, turned with the way the player is standing.
+    pub(crate) fn rotation(&self) -> Quat {
+        self.frame * Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(self.pitch)
+    }
 }
 
 /// Height offset for the camera relative to the player controller.
+///
+/// Eases towards the height for the current [`PlayerStance`] rather than snapping.
 #[derive(Component)]
-struct PlayerCameraHeight(f32);
+pub(crate) struct PlayerCameraHeight(pub(crate) f32);
 
 impl Default for PlayerCameraHeight {
     fn default() -> Self {
@@ -84,130 +143,308 @@ impl Default for PlayerCameraHeight {
 
 /// Core player controller component.
 ///
-/// Requires Transform, TnuaController, RigidBody, and locked rotation axes.
+/// Requires Transform and locked rotation axes. The body itself is either dynamic, from
+/// [`dynamic_body`], or kinematic with a [`KinematicController`].
 #[derive(Component, Default)]
-#[require(
-    Transform,
-    TnuaController::<PlayerControlScheme>,
-    RigidBody::Dynamic,
-    LockedAxes::ROTATION_LOCKED
-)]
-struct PlayerController;
+#[require(Transform, LockedAxes::ROTATION_LOCKED, MovementMode)]
+pub(crate) struct PlayerController;
 
-/// Bundle for spawning a player controller with physics.
-///
-/// Includes collider shape and sensor configuration for ground detection.
+/// Standing/crouching state of the player controller, along with the dimensions needed to
+/// resize its collider when switching between the two.
+#[derive(Component)]
+pub struct PlayerStance {
+    radius: f32,
+    standing_height: f32,
+    crouching: bool,
+}
+
+impl PlayerStance {
+    pub fn is_crouching(&self) -> bool {
+        self.crouching
+    }
+
+    pub(crate) fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub(crate) fn height(&self) -> f32 {
+        if self.crouching {
+            (self.standing_height - CROUCH_HEIGHT_REDUCTION).max(0.0)
+        } else {
+            self.standing_height
+        }
+    }
+
+    /// The controller floats half the height reduction lower while crouching, so the camera only
+    /// needs to drop by the other half to end up a full reduction lower.
+    fn camera_height(&self) -> f32 {
+        if self.crouching {
+            self.standing_height - CROUCH_HEIGHT_REDUCTION / 2.0
+        } else {
+            self.standing_height
+        }
+    }
+}
+
+/// Bundle for spawning a player controller, to be given a body with [`dynamic_body`] or a
+/// [`KinematicController`].
 #[derive(Bundle)]
 pub struct PlayerControllerBundle {
     player: PlayerController,
     collider: Collider,
-    sensor_shape: TnuaAvian3dSensorShape,
-    control_config: TnuaConfig<PlayerControlScheme>,
     player_camera_height: PlayerCameraHeight,
+    stance: PlayerStance,
 }
 
 impl PlayerControllerBundle {
-    pub fn new(
-        radius: f32,
-        height: f32,
-        control_config: Handle<PlayerControlSchemeConfig>,
-    ) -> Self {
+    pub fn new(radius: f32, height: f32) -> Self {
         Self {
             player: PlayerController,
             collider: Collider::capsule(radius.into(), height.into()),
-            sensor_shape: TnuaAvian3dSensorShape(Collider::cylinder((radius - 0.01).into(), 0.)),
-            control_config: TnuaConfig(control_config),
             player_camera_height: PlayerCameraHeight(height),
+            stance: PlayerStance {
+                radius,
+                standing_height: height,
+                crouching: false,
+            },
         }
     }
 }
 
+/// A dynamic body floated above the ground and moved by Tnua, with a sensor for ground detection.
+pub(crate) fn dynamic_body(
+    radius: f32,
+    control_config: Handle<PlayerControlSchemeConfig>,
+) -> impl Bundle {
+    (
+        RigidBody::Dynamic,
+        TnuaController::<PlayerControlScheme>::default(),
+        TnuaAvian3dSensorShape(Collider::cylinder((radius - 0.01).into(), 0.)),
+        TnuaConfig(control_config),
+    )
+}
+
 /// Marker component to disable camera look controls.
 ///
 /// When attached to a PlayerCamera entity, mouse look will be disabled.
 #[derive(Component, Default)]
 pub struct LookDisabled;
 
-/// Marker component to disable player movement controls.
+/// Disables player movement controls while anything has asked for them to be.
 ///
-/// When attached to the player controller entity, WASD movement will be disabled.
-#[derive(Component, Default)]
-pub struct MovementDisabled;
+/// When attached to the player controller entity, WASD movement will be disabled. Queue
+/// [`MovementDisabled::request`] on the player to hold them still, and
+/// [`MovementDisabled::cancel`] with the same `reason` to let them go; the component is only
+/// removed once every reason has been cancelled, so e.g. unpausing doesn't free a player who's
+/// still signing a guestbook.
+#[derive(Component, Debug)]
+pub struct MovementDisabled {
+    reasons: HashSet<&'static str>,
+}
+
+impl MovementDisabled {
+    pub fn request(reason: &'static str) -> impl EntityCommand {
+        move |mut entity: EntityWorldMut| match entity.get_mut::<MovementDisabled>() {
+            Some(mut disabled) => {
+                disabled.reasons.insert(reason);
+            }
+            None => {
+                entity.insert(MovementDisabled {
+                    reasons: HashSet::from_iter([reason]),
+                });
+            }
+        }
+    }
+
+    pub fn cancel(reason: &'static str) -> impl EntityCommand {
+        move |mut entity: EntityWorldMut| {
+            let Some(mut disabled) = entity.get_mut::<MovementDisabled>() else {
+                return;
+            };
+            disabled.reasons.remove(reason);
+            if disabled.reasons.is_empty() {
+                entity.remove::<MovementDisabled>();
+            }
+        }
+    }
+}
+
+/// Switches between standing and crouching, resizing the collider to match.
+///
+/// Standing back up only happens once there is enough overhead clearance for the full capsule.
+fn update_stance(
+    input: ControlsInput,
+    controls: Res<ControlsConfig>,
+    spatial_query: SpatialQuery,
+    player_controller: Single<
+        (
+            Entity,
+            &Transform,
+            &MovementMode,
+            &mut PlayerStance,
+            &mut Collider,
+        ),
+        (With<PlayerController>, Without<MovementDisabled>),
+    >,
+) {
+    let (entity, transform, mode, mut stance, mut collider) = player_controller.into_inner();
+    // Crouch shares its binding with descending while flying or swimming
+    let crouching = if !mode.is_free() && input.pressed(&controls.crouch) {
+        true
+    } else if stance.crouching {
+        // Sweep the capsule's top cap through the space it would grow into before standing up
+        let origin = transform.translation + transform.up() * (stance.height() / 2.0);
+        spatial_query
+            .cast_shape(
+                &Collider::sphere((stance.radius - 0.01).into()),
+                origin.into(),
+                Quat::IDENTITY.into(),
+                transform.up(),
+                &ShapeCastConfig::from_max_distance(CROUCH_HEIGHT_REDUCTION.into()),
+                &SpatialQueryFilter::from_excluded_entities([entity]),
+            )
+            .is_some()
+    } else {
+        false
+    };
+
+    if crouching != stance.crouching {
+        stance.crouching = crouching;
+        *collider = Collider::capsule(stance.radius.into(), stance.height().into());
+    }
+}
 
-/// Handles player movement input (WASD) and applies physics-based movement.
+/// Handles player movement input (WASD by default, see [`ControlsConfig`]) and applies
+/// physics-based movement.
 fn handle_movement(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    player_controller: Single<&mut TnuaController<PlayerControlScheme>, Without<MovementDisabled>>,
+    input: ControlsInput,
+    controls: Res<ControlsConfig>,
+    player_controller: Single<
+        (
+            Option<&mut TnuaController<PlayerControlScheme>>,
+            Option<&mut KinematicController>,
+            &Transform,
+            &PlayerStance,
+            &MovementMode,
+        ),
+        (With<PlayerController>, Without<MovementDisabled>),
+    >,
     player_camera: Single<&Transform, With<PlayerCamera>>,
 ) {
-    let mut controller = player_controller.into_inner();
-
-    let forward = player_camera.forward();
-    let right = player_camera.right();
+    let (controller, kinematic, transform, stance, mode) = player_controller.into_inner();
+    if mode.is_free() {
+        return;
+    }
 
-    // Project onto the horizontal plane
-    let forward_flat = Vec3::new(forward.x.into(), 0.0, forward.z.into()).normalize_or_zero();
-    let right_flat = Vec3::new(right.x.into(), 0.0, right.z.into()).normalize_or_zero();
+    // Project onto the ground plane, which is horizontal unless gravity has turned the player
+    let up = transform.up();
+    let forward_flat = player_camera
+        .forward()
+        .reject_from_normalized(*up)
+        .normalize_or_zero();
+    let right_flat = player_camera
+        .right()
+        .reject_from_normalized(*up)
+        .normalize_or_zero();
 
     let mut facing = Vec3::ZERO;
 
-    if keyboard.pressed(KeyCode::KeyW) {
+    if input.pressed(&controls.move_forward) {
         facing += forward_flat;
     }
-    if keyboard.pressed(KeyCode::KeyS) {
+    if input.pressed(&controls.move_back) {
         facing -= forward_flat;
     }
-    if keyboard.pressed(KeyCode::KeyA) {
+    if input.pressed(&controls.move_left) {
         facing -= right_flat;
     }
-    if keyboard.pressed(KeyCode::KeyD) {
+    if input.pressed(&controls.move_right) {
         facing += right_flat;
     }
+    let stick = input.move_axis(controls.stick_deadzone);
+    facing += forward_flat * stick.y + right_flat * stick.x;
 
-    // Apply sprint multiplier if left shift is held
-    let speed = if keyboard.pressed(KeyCode::ShiftLeft) {
+    // Crouching slows movement and prevents sprinting, otherwise sprint while its binding is held
+    let speed = if stance.crouching {
+        SPEED * CROUCH_MULTIPLIER
+    } else if input.pressed(&controls.sprint) {
         SPEED * SPRINT_MULTIPLIER
     } else {
         SPEED
     };
 
+    // Clamped rather than normalised, so a partly tilted stick walks slowly
+    let desired_motion = facing.clamp_length_max(1.0) * speed;
+    let jump = !stance.crouching && input.pressed(&controls.jump);
+
+    if let Some(mut kinematic) = kinematic {
+        kinematic.drive(desired_motion, jump);
+    }
+    let Some(mut controller) = controller else {
+        return;
+    };
+
     controller.basis = TnuaBuiltinWalk {
-        desired_motion: (facing.normalize_or_zero() * speed).into(),
+        desired_motion: desired_motion.into(),
         desired_forward: None,
     };
 
     controller.initiate_action_feeding();
 
-    if keyboard.pressed(KeyCode::Space) {
+    if stance.crouching {
+        controller.action(PlayerControlScheme::Crouch(TnuaBuiltinCrouch::default()));
+    } else if jump {
         controller.action(PlayerControlScheme::Jump(TnuaBuiltinJump::default()));
     }
 }
 
+/// Eases the camera height towards the height for the current stance.
+fn update_camera_height(
+    time: Res<Time>,
+    player_controller: Single<(&mut PlayerCameraHeight, &PlayerStance)>,
+) {
+    let (mut camera_height, stance) = player_controller.into_inner();
+    let target = stance.camera_height();
+    let t = 1.0 - (-CAMERA_HEIGHT_SMOOTHING * time.delta_secs()).exp();
+    camera_height.0 += (target - camera_height.0) * t;
+}
+
 /// Updates the camera position to follow the player controller.
-fn update_camera_position(
+pub(crate) fn update_camera_position(
     mut player_camera: Single<&mut Transform, With<PlayerCamera>>,
     player_controller: Single<(&Transform, &PlayerCameraHeight), Without<PlayerCamera>>,
 ) {
     let (player_transform, PlayerCameraHeight(player_camera_height)) =
         player_controller.into_inner();
     player_camera.translation =
-        player_transform.translation + Vec3::new(0.0, *player_camera_height, 0.0);
+        player_transform.translation + player_transform.up() * *player_camera_height;
 }
 
 /// Handles mouse look input and rotates the camera.
-fn update_camera_looking_at(
+pub(crate) fn update_camera_looking_at(
     mouse_motion: Res<AccumulatedMouseMotion>,
+    input: ControlsInput,
+    controls: Res<ControlsConfig>,
+    time: Res<Time>,
     camera: Single<(&mut Transform, &mut PlayerCamera), Without<LookDisabled>>,
+    player: Single<&Transform, (With<PlayerCameraHeight>, Without<PlayerCamera>)>,
 ) {
     let (mut camera_transform, mut player_camera) = camera.into_inner();
 
-    player_camera.yaw -= mouse_motion.delta.x * LOOK_SENSITIVITY;
-    player_camera.pitch -= mouse_motion.delta.y * LOOK_SENSITIVITY;
+    // Turned by however much the player's up has turned, so the view turns along with them
+    let frame_up = player_camera.frame * Vec3::Y;
+    player_camera.frame =
+        (Quat::from_rotation_arc(frame_up, *player.up()) * player_camera.frame).normalize();
+
+    // Unlike mouse motion, stick position is a rate, so it's scaled by frame time
+    let stick =
+        input.look_axis(controls.stick_deadzone) * controls.gamepad_look_speed * time.delta_secs();
+    player_camera.yaw -= mouse_motion.delta.x * LOOK_SENSITIVITY + stick.x;
+    player_camera.pitch -= mouse_motion.delta.y * LOOK_SENSITIVITY - stick.y;
 
     // Clamp pitch to prevent looking too far up or down
     player_camera.pitch = player_camera.pitch.clamp(-1.5, 1.5);
 
     // Apply rotation
-    camera_transform.rotation =
-        Quat::from_rotation_y(player_camera.yaw) * Quat::from_rotation_x(player_camera.pitch);
+    camera_transform.rotation = player_camera.rotation();
 }
@@ -0,0 +1,504 @@
+//! Spline-based flows, such as rivers and lava channels.
+//!
+//! A [`Flow`] follows a Catmull-Rom spline through its control points, which mark the flowing
+//! surface. It can carve a channel into terrain heights with [`Flow::carve_height`], and spawns a
+//! ribbon mesh along the spline whose material scrolls in the direction of flow. Flows can also
+//! have a hazard volume that triggers [`HazardEntered`] and [`HazardExited`] as the player wades in
+//! and out, and a [`FlowEmitter`] to give off particles such as mist or embers.
+
+use avian3d::math::AdjustPrecision;
+use avian3d::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor};
+use bevy::light::NotShadowCaster;
+use bevy::math::Affine2;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::game_time::GameTime;
+use crate::particles::{EmitterShape, LifeCurve, LifeGradient, ParticleEmitter};
+use crate::player::Player;
+use crate::rng::SplitMix64;
+use crate::state::PausableSystems;
+
+pub struct FlowPlugin;
+
+impl Plugin for FlowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (update_hazards, spawn_flow_emitters))
+            .add_systems(Update, scroll_flowing_surfaces.in_set(PausableSystems));
+    }
+}
+
+/// Spline samples between each pair of control points.
+const SAMPLES_PER_SEGMENT: usize = 8;
+/// Side length of the generated flow texture.
+const TEXTURE_SIZE: u32 = 64;
+/// Most particle emitters a [`FlowEmitter`] spreads along its flow.
+const MAX_EMITTER_STRETCHES: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flow {
+    /// Control points on the flowing surface, upstream first, in the flow entity's local space.
+    pub points: Vec<Vec3>,
+    pub width: f32,
+    /// How far the channel bed is carved below the surface, at its centre.
+    pub depth: f32,
+    /// Width of the sloped bank either side of the channel, blending back into the terrain.
+    pub bank: f32,
+    /// Surface speed, in units per second.
+    pub speed: f32,
+    /// Height above the surface the hazard volume reaches, if the flow is hazardous.
+    pub hazard: Option<f32>,
+}
+
+impl Flow {
+    pub fn new(points: impl IntoIterator<Item = Vec3>) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+            width: 2.0,
+            depth: 0.5,
+            bank: 1.0,
+            speed: 1.0,
+            hazard: None,
+        }
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width.max(0.01);
+        self
+    }
+
+    pub fn with_channel(mut self, depth: f32, bank: f32) -> Self {
+        self.depth = depth.max(0.0);
+        self.bank = bank.max(0.0);
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_hazard(mut self, height: f32) -> Self {
+        self.hazard = Some(height.max(0.0));
+        self
+    }
+
+    /// Wide, fairly quick river in a deep channel with gentle banks.
+    pub fn river(points: impl IntoIterator<Item = Vec3>) -> Self {
+        Self::new(points)
+            .with_width(6.0)
+            .with_channel(1.5, 4.0)
+            .with_speed(1.5)
+    }
+
+    /// Narrow, slow and hazardous lava channel with steep banks.
+    pub fn lava(points: impl IntoIterator<Item = Vec3>) -> Self {
+        Self::new(points)
+            .with_width(1.2)
+            .with_channel(0.4, 0.3)
+            .with_speed(0.25)
+            .with_hazard(0.5)
+    }
+
+    /// Points along the spline, upstream first. Flows with fewer than two control points fall back
+    /// to the points themselves.
+    pub fn samples(&self) -> Vec<Vec3> {
+        let Ok(curve) =
+            CubicCardinalSpline::new_catmull_rom(self.points.iter().copied()).to_curve()
+        else {
+            return self.points.clone();
+        };
+        let count = curve.segments().len() * SAMPLES_PER_SEGMENT;
+        (0..=count)
+            .map(|i| curve.position(i as f32 / SAMPLES_PER_SEGMENT as f32))
+            .collect()
+    }
+
+    /// Lowers `height` at `(x, z)` to carve out the channel, in the same space as the control
+    /// points. Ground is only ever lowered, never raised to meet the surface.
+    pub fn carve_height(&self, x: f32, z: f32, height: f32) -> f32 {
+        self.carve_height_along(&self.samples(), x, z, height)
+    }
+
+    /// [`Flow::carve_height`] with precomputed [`Flow::samples`], for carving many points at once.
+    pub fn carve_height_along(&self, samples: &[Vec3], x: f32, z: f32, height: f32) -> f32 {
+        let Some((distance, surface)) = nearest_on_path(samples, Vec2::new(x, z)) else {
+            return height;
+        };
+        let half_width = self.width * 0.5;
+        let target = if distance < half_width {
+            // Rounded bed, meeting the surface at the channel's edges
+            let across = distance / half_width;
+            surface - self.depth * (1.0 - across * across)
+        } else if distance < half_width + self.bank {
+            let t = (distance - half_width) / self.bank.max(f32::EPSILON);
+            surface.lerp(height, t * t * (3.0 - 2.0 * t))
+        } else {
+            height
+        };
+        height.min(target)
+    }
+
+    /// Builds the flowing surface, with UVs running across (u) and along (v) the flow. V is in
+    /// multiples of the width, so textures keep their aspect ratio however long the flow is.
+    pub fn ribbon_mesh(&self) -> Mesh {
+        let samples = self.samples();
+        let half_width = self.width * 0.5;
+        let mut positions = Vec::with_capacity(samples.len() * 2);
+        let mut uvs = Vec::with_capacity(samples.len() * 2);
+        let mut along = 0.0;
+        for (i, sample) in samples.iter().enumerate() {
+            if i > 0 {
+                along += sample.distance(samples[i - 1]);
+            }
+            let side = side_at(&samples, i) * half_width;
+            let v = along / self.width;
+            positions.push((*sample + side).to_array());
+            positions.push((*sample - side).to_array());
+            uvs.push([0.0, v]);
+            uvs.push([1.0, v]);
+        }
+        let mut indices = Vec::with_capacity(samples.len().saturating_sub(1) * 6);
+        for i in 1..samples.len() as u32 {
+            let (left, right) = ((i - 1) * 2, (i - 1) * 2 + 1);
+            let (next_left, next_right) = (i * 2, i * 2 + 1);
+            indices.extend_from_slice(&[left, right, next_left, right, next_right, next_left]);
+        }
+        let normals = vec![[0.0, 1.0, 0.0]; positions.len()];
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+
+    /// Boxes covering the channel, from its bed to the hazard height, one per spline segment.
+    pub fn hazard_collider(&self) -> Option<Collider> {
+        let height = self.hazard?;
+        let samples = self.samples();
+        let boxes: Vec<_> = samples
+            .windows(2)
+            .filter_map(|pair| {
+                let direction = pair[1] - pair[0];
+                let length = direction.length();
+                (length > f32::EPSILON).then(|| {
+                    let center = (pair[0] + pair[1]) * 0.5 + Vec3::Y * (height - self.depth) * 0.5;
+                    let rotation = Transform::IDENTITY.looking_to(direction, Vec3::Y).rotation;
+                    (
+                        Position(center.adjust_precision()),
+                        Rotation(rotation.adjust_precision()),
+                        // Slightly longer than the segment so boxes overlap around bends
+                        Collider::cuboid(
+                            self.width.adjust_precision(),
+                            (self.depth + height).adjust_precision(),
+                            (length + self.width * 0.25).adjust_precision(),
+                        ),
+                    )
+                })
+            })
+            .collect();
+        (!boxes.is_empty()).then(|| Collider::compound(boxes))
+    }
+
+    /// Spawns the flow at `transform`, with its surface as a child using `material`. Give the
+    /// material a repeating texture, e.g. from [`flow_texture`], to see it move.
+    pub fn spawn(
+        &self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        material: Handle<StandardMaterial>,
+        transform: Transform,
+    ) -> Entity {
+        let mut flow = commands.spawn((
+            Name::new("Flow"),
+            transform,
+            Visibility::default(),
+            FlowPath {
+                samples: self.samples(),
+                width: self.width,
+            },
+        ));
+        if let Some(collider) = self.hazard_collider() {
+            flow.insert((
+                RigidBody::Static,
+                collider,
+                Sensor,
+                CollidingEntities::default(),
+                FlowHazard::default(),
+            ));
+        }
+        flow.with_child((
+            Name::new("Flow surface"),
+            Mesh3d(meshes.add(self.ribbon_mesh())),
+            MeshMaterial3d(material),
+            FlowingSurface {
+                speed: self.speed / self.width,
+            },
+            NotShadowCaster,
+        ));
+        flow.id()
+    }
+}
+
+/// Horizontal distance from `point` to the nearest spot on `samples`, and the surface height there.
+fn nearest_on_path(samples: &[Vec3], point: Vec2) -> Option<(f32, f32)> {
+    if let [only] = samples {
+        return Some((only.xz().distance(point), only.y));
+    }
+    samples
+        .windows(2)
+        .map(|pair| {
+            let (a, b) = (pair[0].xz(), pair[1].xz());
+            let segment = b - a;
+            let t = ((point - a).dot(segment) / segment.length_squared().max(f32::EPSILON))
+                .clamp(0.0, 1.0);
+            (
+                point.distance(a + segment * t),
+                pair[0].y.lerp(pair[1].y, t),
+            )
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// Horizontal unit vector to the left of the flow at sample `i`.
+fn side_at(samples: &[Vec3], i: usize) -> Vec3 {
+    let previous = samples[i.saturating_sub(1)];
+    let next = samples[(i + 1).min(samples.len() - 1)];
+    let tangent = (next - previous).with_y(0.0);
+    Vec3::Y.cross(tangent).normalize_or(Vec3::X)
+}
+
+/// Tileable streaky texture to scroll along flows. It's mostly light, so tint it with the
+/// material's base colour, and use it as the emissive texture too for glowing flows like lava.
+pub fn flow_texture(seed: u64) -> Image {
+    use std::f32::consts::TAU;
+
+    let mut rng = SplitMix64::new(seed);
+    let (phase_a, phase_b) = (rng.next_f32(), rng.next_f32());
+    let mut data = Vec::with_capacity((TEXTURE_SIZE * TEXTURE_SIZE * 4) as usize);
+    for y in 0..TEXTURE_SIZE {
+        for x in 0..TEXTURE_SIZE {
+            let u = x as f32 / TEXTURE_SIZE as f32;
+            let v = y as f32 / TEXTURE_SIZE as f32;
+            // Whole-number frequencies in both directions, so the texture tiles
+            let ripple = (TAU * (3.0 * u + 0.25 * (TAU * (2.0 * v + phase_a)).sin())).sin();
+            let streak = (TAU * (v + 0.3 * (TAU * (5.0 * u + phase_b)).sin())).sin();
+            let value = ((0.7 + 0.15 * ripple + 0.15 * streak) * 255.0) as u8;
+            data.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+    let mut image = Image::new(
+        Extent3d {
+            width: TEXTURE_SIZE,
+            height: TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..ImageSamplerDescriptor::linear()
+    });
+    image
+}
+
+/// The sampled spline of a spawned flow, in its local space.
+#[derive(Component, Debug, Clone)]
+pub struct FlowPath {
+    pub samples: Vec<Vec3>,
+    pub width: f32,
+}
+
+/// Scrolls the material's texture along its V axis.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FlowingSurface {
+    /// Texture repeats per second.
+    pub speed: f32,
+}
+
+/// Tracks whether the player is inside a flow's hazard volume.
+#[derive(Component, Debug, Default)]
+pub struct FlowHazard {
+    player_inside: bool,
+}
+
+impl FlowHazard {
+    pub fn player_inside(&self) -> bool {
+        self.player_inside
+    }
+}
+
+/// Triggered when the player enters a flow's hazard volume.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct HazardEntered {
+    pub entity: Entity,
+}
+
+/// Triggered when the player leaves a flow's hazard volume.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct HazardExited {
+    pub entity: Entity,
+}
+
+/// Gives off particles from random points on a flow's surface, which rise and shrink away. Add it
+/// to an entity spawned by [`Flow::spawn`], which then gets a [`ParticleEmitter`] child along each
+/// stretch of the flow, spawned again whenever this changes.
+#[derive(Component, Debug, Clone)]
+pub struct FlowEmitter {
+    pub color: LinearRgba,
+    /// Particles per second, along the whole flow.
+    pub rate: f32,
+    pub lifetime: f32,
+    pub size: f32,
+    /// Upward speed, in units per second.
+    pub rise: f32,
+    pub seed: u64,
+}
+
+impl FlowEmitter {
+    /// Colours brighter than 1 glow, such as embers.
+    pub fn new(color: impl Into<LinearRgba>, rate: f32) -> Self {
+        Self {
+            color: color.into(),
+            rate: rate.max(0.0),
+            lifetime: 2.0,
+            size: 0.1,
+            rise: 0.5,
+            seed: 0,
+        }
+    }
+
+    pub fn with_lifetime(mut self, lifetime: f32) -> Self {
+        self.lifetime = lifetime.max(0.01);
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_rise(mut self, rise: f32) -> Self {
+        self.rise = rise;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// One of the particle emitters spawned along a flow for its [`FlowEmitter`].
+#[derive(Component, Debug)]
+struct FlowEmitterStretch;
+
+fn scroll_flowing_surfaces(
+    time: Res<GameTime>,
+    surfaces: Query<(&FlowingSurface, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (surface, material) in &surfaces {
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+        // Based on elapsed time rather than accumulated, so surfaces sharing a material agree
+        let offset = (time.elapsed_secs() * surface.speed).rem_euclid(1.0);
+        material.uv_transform = Affine2::from_translation(Vec2::new(0.0, -offset));
+    }
+}
+
+fn spawn_flow_emitters(
+    mut commands: Commands,
+    emitters: Query<(Entity, &FlowEmitter, &FlowPath, Option<&Children>), Changed<FlowEmitter>>,
+    stretches: Query<(), With<FlowEmitterStretch>>,
+) {
+    for (entity, emitter, path, children) in &emitters {
+        for child in children.into_iter().flatten() {
+            if stretches.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
+        let Some(&last) = path.samples.last() else {
+            continue;
+        };
+
+        // Straight stretches of the flow, each a few samples long
+        let step = path
+            .samples
+            .len()
+            .saturating_sub(1)
+            .div_ceil(MAX_EMITTER_STRETCHES)
+            .max(1);
+        let mut ends: Vec<(Vec3, Vec3)> = (0..path.samples.len().saturating_sub(1))
+            .step_by(step)
+            .map(|start| {
+                (
+                    path.samples[start],
+                    path.samples[(start + step).min(path.samples.len() - 1)],
+                )
+            })
+            .collect();
+        if ends.is_empty() {
+            ends.push((last, last));
+        }
+        let total: f32 = ends.iter().map(|(a, b)| a.xz().distance(b.xz())).sum();
+
+        for (i, (start, end)) in ends.iter().enumerate() {
+            let along = (*end - *start).with_y(0.0);
+            // Each stretch gets its share of the rate, so particles are spread evenly
+            let share = if total > f32::EPSILON {
+                along.length() / total
+            } else {
+                1.0 / ends.len() as f32
+            };
+            commands.spawn((
+                Name::new("Flow particles"),
+                FlowEmitterStretch,
+                Transform::from_translation((*start + *end) * 0.5)
+                    .looking_to(along.normalize_or(Vec3::NEG_Z), Vec3::Y),
+                ParticleEmitter::default()
+                    .with_rate(emitter.rate * share)
+                    .with_shape(EmitterShape::Box {
+                        half_extents: Vec3::new(path.width * 0.5, 0.0, along.length() * 0.5),
+                    })
+                    .with_lifetime(emitter.lifetime, 0.0)
+                    .with_velocity(Vec3::Y * emitter.rise, Vec3::ZERO)
+                    .with_size(emitter.size * 2.0, 0.0)
+                    .with_size_over_life(LifeCurve::linear(1.0, 0.0))
+                    .with_color_over_life(LifeGradient::constant(emitter.color))
+                    .with_seed(emitter.seed ^ i as u64),
+                ChildOf(entity),
+            ));
+        }
+    }
+}
+
+fn update_hazards(
+    mut commands: Commands,
+    player: Single<Entity, With<Player>>,
+    mut hazards: Query<(Entity, &CollidingEntities, &mut FlowHazard)>,
+) {
+    for (entity, colliding, mut hazard) in &mut hazards {
+        let inside = colliding.contains(&*player);
+        if inside == hazard.player_inside {
+            continue;
+        }
+        hazard.player_inside = inside;
+        if inside {
+            commands.trigger(HazardEntered { entity });
+        } else {
+            commands.trigger(HazardExited { entity });
+        }
+    }
+}
@@ -0,0 +1,418 @@
+//! Footsteps, with sounds, dust puffs and footprints that depend on what the player walks on.
+//!
+//! Tagging ground entities with [`SurfaceProperties`] sets what they're made of. As the player
+//! walks, a [`Footstep`] is triggered every stride with the [`SurfaceKind`] underfoot, looked up on
+//! the hit collider or its nearest tagged ancestor. The [`SurfaceResponses`] table then decides how
+//! each kind of surface answers: a random clip from its sound set, a puff of particles and a
+//! fading footprint decal. Untagged ground uses [`SurfaceKind::Stone`].
+//!
+//! Sounds are played by [`crate::audio`], which fills in synthesised clips for any surface left
+//! without sounds, and prefers a [`SurfaceAudio`] on the ground itself over the table.
+//!
+//! [`SurfaceAudio`]: crate::audio::SurfaceAudio
+
+#![allow(clippy::useless_conversion)]
+use std::f32::consts::FRAC_PI_2;
+
+use avian3d::math::AsF32;
+use avian3d::prelude::*;
+use bevy::light::NotShadowCaster;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_time::GameTime;
+use crate::movement::MovementMode;
+use crate::particles::{LifeCurve, LifeGradient, ParticleEmitter};
+use crate::player::Player;
+use crate::state::PausableSystems;
+
+pub struct FootstepPlugin;
+
+impl Plugin for FootstepPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SurfaceResponses>()
+            .init_resource::<FootstepAssets>()
+            .register_type::<SurfaceProperties>()
+            .add_systems(
+                Update,
+                (invalidate_materials, track_strides, fade_footprints).in_set(PausableSystems),
+            )
+            .add_observer(respond_to_footstep);
+    }
+}
+
+/// Horizontal distance walked between footsteps.
+const STRIDE: f32 = 1.8;
+/// How far below the player's centre the ground can be for it to count as standing on it.
+pub(crate) const GROUND_PROBE: f32 = 1.9;
+/// Sideways offset of each footprint from the player's path, alternating left and right.
+const FOOT_SPACING: f32 = 0.15;
+/// Footprints are lifted this far off the ground, so they don't z-fight with it.
+const FOOTPRINT_LIFT: f32 = 0.01;
+
+#[derive(Serialize, Deserialize, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SurfaceKind {
+    #[default]
+    Stone,
+    Sand,
+    Wood,
+    Marble,
+    Metal,
+    Coral,
+}
+
+/// What a ground entity is made of, for footstep feedback.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct SurfaceProperties {
+    pub kind: SurfaceKind,
+}
+
+impl SurfaceKind {
+    pub const ALL: [SurfaceKind; 6] = [
+        SurfaceKind::Stone,
+        SurfaceKind::Sand,
+        SurfaceKind::Wood,
+        SurfaceKind::Marble,
+        SurfaceKind::Metal,
+        SurfaceKind::Coral,
+    ];
+}
+
+impl SurfaceProperties {
+    pub fn new(kind: SurfaceKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// Triggered each time the player puts a foot down.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Footstep {
+    /// Where the foot landed, on the ground.
+    pub position: Vec3,
+    pub normal: Vec3,
+    /// Direction the player was walking in, along the ground.
+    pub heading: Vec3,
+    pub kind: SurfaceKind,
+    /// The collider that was stepped on.
+    pub ground: Entity,
+}
+
+/// Particles kicked up by a footstep, such as sand or grit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FootstepPuff {
+    pub color: Color,
+    pub count: u32,
+    pub size: f32,
+    pub lifetime: f32,
+    /// Initial speed, in units per second, spread upwards and outwards.
+    pub speed: f32,
+}
+
+/// A footprint left on the ground, which shrinks away once its time is up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FootprintDecal {
+    pub color: Color,
+    /// Width and length of the print.
+    pub size: Vec2,
+    pub lifetime: f32,
+}
+
+/// How a kind of surface responds to being stepped on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SurfaceResponse {
+    /// Clips to pick from at random for each step.
+    pub sounds: Vec<Handle<AudioSource>>,
+    pub volume: f32,
+    /// Maximum playback speed deviation either side of 1.0.
+    pub pitch_variance: f32,
+    pub puff: Option<FootstepPuff>,
+    pub decal: Option<FootprintDecal>,
+}
+
+impl SurfaceResponse {
+    pub fn new() -> Self {
+        Self {
+            volume: 0.6,
+            pitch_variance: 0.1,
+            ..default()
+        }
+    }
+
+    pub fn with_sounds(mut self, sounds: Vec<Handle<AudioSource>>) -> Self {
+        self.sounds = sounds;
+        self
+    }
+
+    pub fn with_puff(mut self, puff: FootstepPuff) -> Self {
+        self.puff = Some(puff);
+        self
+    }
+
+    pub fn with_decal(mut self, decal: FootprintDecal) -> Self {
+        self.decal = Some(decal);
+        self
+    }
+}
+
+/// Footstep responses for each [`SurfaceKind`].
+#[derive(Resource, Debug, Clone)]
+pub struct SurfaceResponses(HashMap<SurfaceKind, SurfaceResponse>);
+
+impl Default for SurfaceResponses {
+    fn default() -> Self {
+        let dust = |color| FootstepPuff {
+            color,
+            count: 5,
+            size: 0.05,
+            lifetime: 0.6,
+            speed: 0.8,
+        };
+        Self(HashMap::from_iter([
+            (SurfaceKind::Stone, SurfaceResponse::new()),
+            (
+                SurfaceKind::Sand,
+                SurfaceResponse::new()
+                    .with_puff(dust(Color::srgba(0.8, 0.72, 0.52, 0.8)))
+                    .with_decal(FootprintDecal {
+                        color: Color::srgba(0.45, 0.38, 0.25, 0.6),
+                        size: Vec2::new(0.14, 0.3),
+                        lifetime: 20.0,
+                    }),
+            ),
+            (SurfaceKind::Wood, SurfaceResponse::new()),
+            (SurfaceKind::Marble, SurfaceResponse::new()),
+            (SurfaceKind::Metal, SurfaceResponse::new()),
+            (
+                SurfaceKind::Coral,
+                SurfaceResponse::new().with_puff(FootstepPuff {
+                    count: 3,
+                    size: 0.03,
+                    ..dust(Color::srgba(0.95, 0.7, 0.65, 0.8))
+                }),
+            ),
+        ]))
+    }
+}
+
+impl SurfaceResponses {
+    pub fn get(&self, kind: SurfaceKind) -> Option<&SurfaceResponse> {
+        self.0.get(&kind)
+    }
+
+    /// The response for `kind`, added empty if there isn't one yet.
+    pub fn get_mut(&mut self, kind: SurfaceKind) -> &mut SurfaceResponse {
+        self.0.entry(kind).or_insert_with(SurfaceResponse::new)
+    }
+
+    pub fn insert(&mut self, kind: SurfaceKind, response: SurfaceResponse) {
+        self.0.insert(kind, response);
+    }
+}
+
+/// The mesh shared by every footprint, and materials made from each response's colours.
+#[derive(Resource)]
+struct FootstepAssets {
+    footprint_mesh: Handle<Mesh>,
+    materials: HashMap<SurfaceKind, Handle<StandardMaterial>>,
+}
+
+impl FromWorld for FootstepAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        Self {
+            // Unit ellipse, scaled to each decal's size
+            footprint_mesh: meshes.add(Ellipse::new(0.5, 0.5)),
+            materials: HashMap::default(),
+        }
+    }
+}
+
+impl FootstepAssets {
+    fn material(
+        &mut self,
+        kind: SurfaceKind,
+        color: Color,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        self.materials
+            .entry(kind)
+            .or_insert_with(|| {
+                materials.add(StandardMaterial {
+                    base_color: color,
+                    alpha_mode: AlphaMode::Blend,
+                    perceptual_roughness: 1.0,
+                    ..default()
+                })
+            })
+            .clone()
+    }
+}
+
+/// Distance walked since the last footstep, and which foot is next.
+#[derive(Component, Debug, Default)]
+struct StrideTracker {
+    last_position: Option<Vec3>,
+    walked: f32,
+    right_foot: bool,
+}
+
+#[derive(Component, Debug)]
+struct Footprint {
+    age: f32,
+    lifetime: f32,
+    size: Vec3,
+}
+
+fn track_strides(
+    mut commands: Commands,
+    spatial_query: SpatialQuery,
+    player: Single<
+        (
+            Entity,
+            &Transform,
+            &MovementMode,
+            Option<&mut StrideTracker>,
+        ),
+        With<Player>,
+    >,
+    surfaces: Query<&SurfaceProperties>,
+    parents: Query<&ChildOf>,
+) {
+    let (entity, transform, mode, tracker) = player.into_inner();
+    let Some(mut tracker) = tracker else {
+        commands.entity(entity).insert(StrideTracker::default());
+        return;
+    };
+    if mode.is_free() {
+        *tracker = StrideTracker::default();
+        return;
+    }
+    let position = transform.translation;
+    let step = tracker
+        .last_position
+        .replace(position)
+        .map_or(Vec3::ZERO, |last| (position - last).with_y(0.0));
+    let Some(hit) = spatial_query.cast_ray(
+        position.into(),
+        Dir3::NEG_Y,
+        GROUND_PROBE.into(),
+        true,
+        &SpatialQueryFilter::from_excluded_entities([entity]),
+    ) else {
+        // Airborne, so land on a fresh stride
+        tracker.walked = 0.0;
+        return;
+    };
+
+    tracker.walked += step.length();
+    if tracker.walked < STRIDE {
+        return;
+    }
+    tracker.walked -= STRIDE;
+    tracker.right_foot = !tracker.right_foot;
+
+    let kind = std::iter::once(hit.entity)
+        .chain(parents.iter_ancestors(hit.entity))
+        .find_map(|entity| surfaces.get(entity).ok())
+        .map(|surface| surface.kind)
+        .unwrap_or_default();
+    let normal = hit.normal.f32();
+    let heading = step
+        .reject_from_normalized(normal)
+        .normalize_or(*transform.forward());
+    let side = if tracker.right_foot { 1.0 } else { -1.0 };
+    let ground_point = position + Vec3::NEG_Y * hit.distance.f32();
+    commands.trigger(Footstep {
+        position: ground_point + heading.cross(normal).normalize_or_zero() * FOOT_SPACING * side,
+        normal,
+        heading,
+        kind,
+        ground: hit.entity,
+    });
+}
+
+fn respond_to_footstep(
+    footstep: On<Footstep>,
+    mut commands: Commands,
+    responses: Res<SurfaceResponses>,
+    mut assets: ResMut<FootstepAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(response) = responses.get(footstep.kind) else {
+        return;
+    };
+
+    if let Some(puff) = &response.puff {
+        // Pointed up off the ground, so the puff spreads upwards and outwards from it. Slows
+        // quickly, like dust settling.
+        commands.spawn((
+            Name::new("Footstep puff"),
+            Transform::from_translation(footstep.position)
+                .with_rotation(Quat::from_rotation_arc(Vec3::Y, footstep.normal)),
+            ParticleEmitter::default()
+                .with_rate(0.0)
+                .with_burst(puff.count)
+                .with_lifetime(puff.lifetime, 0.0)
+                .with_velocity(
+                    Vec3::Y * puff.speed * 0.6,
+                    Vec3::new(0.6, 0.2, 0.6) * puff.speed,
+                )
+                .with_drag(3.0)
+                .with_size(puff.size * 2.0, 0.0)
+                .with_size_over_life(LifeCurve::linear(1.0, 0.0))
+                .with_color_over_life(LifeGradient::constant(puff.color))
+                .with_lit(true)
+                .with_despawn_when_done(),
+        ));
+    }
+
+    if let Some(decal) = &response.decal {
+        let material = assets.material(footstep.kind, decal.color, &mut materials);
+        let size = Vec3::new(decal.size.x, decal.size.y, 1.0);
+        // The ellipse mesh lies in the XY plane, so tip it flat then point it along the heading
+        let rotation = Transform::IDENTITY
+            .looking_to(footstep.heading, footstep.normal)
+            .rotation
+            * Quat::from_rotation_x(-FRAC_PI_2);
+        commands.spawn((
+            Name::new("Footprint"),
+            Footprint {
+                age: 0.0,
+                lifetime: decal.lifetime,
+                size,
+            },
+            Mesh3d(assets.footprint_mesh.clone()),
+            MeshMaterial3d(material),
+            Transform::from_translation(footstep.position + footstep.normal * FOOTPRINT_LIFT)
+                .with_rotation(rotation)
+                .with_scale(size),
+            NotShadowCaster,
+        ));
+    }
+}
+
+/// Forgets cached materials when the table changes, so new colours take effect.
+fn invalidate_materials(responses: Res<SurfaceResponses>, mut assets: ResMut<FootstepAssets>) {
+    if responses.is_changed() {
+        assets.materials.clear();
+    }
+}
+
+fn fade_footprints(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut footprints: Query<(Entity, &mut Footprint, &mut Transform)>,
+) {
+    for (entity, mut footprint, mut transform) in &mut footprints {
+        footprint.age += time.delta_secs();
+        if footprint.age >= footprint.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Shrinks away over the last fifth of its life
+        let remaining = (footprint.lifetime - footprint.age) / (footprint.lifetime * 0.2);
+        transform.scale = footprint.size * remaining.min(1.0);
+    }
+}
@@ -0,0 +1,110 @@
+//! Scene time that stops while the game is paused.
+//!
+//! Bevy's [`Time`] keeps running while the pause screen is up, so animations driven by it carry on
+//! behind the menu. Scene animation and shader uniforms should read [`GameTime`] instead, which
+//! only advances while [`GameState::Active`] and can be scaled for slow motion. Custom materials
+//! animating from a `time` uniform rather than `globals.time` implement [`GameTimeMaterial`], and a
+//! [`GameTimeMaterialPlugin`] keeps that uniform in step with [`GameTime`].
+
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeSystems;
+
+use crate::state::GameState;
+
+pub struct GameTimePlugin;
+
+impl Plugin for GameTimePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameTime>()
+            .add_systems(First, advance_game_time.after(TimeSystems));
+    }
+}
+
+/// A material whose shader animates from a `time` uniform, set from [`GameTime`] by a
+/// [`GameTimeMaterialPlugin`].
+pub trait GameTimeMaterial: Material {
+    fn set_time(&mut self, seconds: f32);
+}
+
+/// Feeds [`GameTime`] to every material `M`, alongside its `MaterialPlugin`.
+pub struct GameTimeMaterialPlugin<M>(PhantomData<M>);
+
+impl<M> Default for GameTimeMaterialPlugin<M> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<M: GameTimeMaterial> Plugin for GameTimeMaterialPlugin<M> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_material_time::<M>);
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct GameTime {
+    /// Multiplier applied to real frame time, e.g. 0.5 for half speed.
+    pub scale: f32,
+    paused: bool,
+    delta: Duration,
+    elapsed: Duration,
+}
+
+impl Default for GameTime {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            paused: false,
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl GameTime {
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_secs(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+fn advance_game_time(
+    time: Res<Time>,
+    state: Option<Res<State<GameState>>>,
+    mut game_time: ResMut<GameTime>,
+) {
+    game_time.paused = state.is_some_and(|state| *state.get() == GameState::Paused);
+    game_time.delta = if game_time.paused {
+        Duration::ZERO
+    } else {
+        time.delta().mul_f32(game_time.scale.max(0.0))
+    };
+    game_time.elapsed = game_time.elapsed.saturating_add(game_time.delta);
+}
+
+fn update_material_time<M: GameTimeMaterial>(
+    time: Res<GameTime>,
+    mut materials: ResMut<Assets<M>>,
+) {
+    for (_, material) in materials.iter_mut() {
+        material.set_time(time.elapsed_secs());
+    }
+}
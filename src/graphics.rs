@@ -0,0 +1,123 @@
+//! Per-scene graphics settings applied to the player camera.
+//!
+//! Scenes can insert their own [`GraphicsSettings`] to suit their lighting, e.g. a bright interior
+//! and a dark underwater scene can each pick an exposure without retuning every light. The resource
+//! is reflected so it can also be tweaked live from the world inspector.
+//!
+//! Frame pacing is configured here too, including an uncapped mode for benchmarking.
+
+use std::time::Duration;
+
+use bevy::camera::Exposure;
+use bevy::core_pipeline::tonemapping::Tonemapping;
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow};
+use bevy_framepace::{FramepaceSettings, Limiter};
+
+use crate::firstsight::PlayerCamera;
+
+pub struct GraphicsPlugin;
+
+impl Plugin for GraphicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GraphicsSettings>()
+            .init_resource::<GraphicsSettings>()
+            .add_systems(PostUpdate, (apply_graphics_settings, apply_frame_pacing));
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct GraphicsSettings {
+    /// Added to the camera's default EV100, so positive values darken the image and negative
+    /// values brighten it.
+    pub ev100_offset: f32,
+    pub tonemapping: Tonemapping,
+    pub frame_limit: FrameLimit,
+    pub present_mode: PresentMode,
+}
+
+/// How the frame rate is limited.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub enum FrameLimit {
+    /// Match the monitor's refresh rate.
+    Auto,
+    /// Cap to a fixed number of frames per second.
+    Fps(f64),
+    /// Render as fast as possible.
+    Uncapped,
+}
+
+impl GraphicsSettings {
+    /// Settings for benchmarking, with no frame limiter and vsync disabled.
+    pub fn uncapped() -> Self {
+        Self {
+            frame_limit: FrameLimit::Uncapped,
+            present_mode: PresentMode::AutoNoVsync,
+            ..default()
+        }
+    }
+
+    pub fn with_frame_limit(mut self, frame_limit: FrameLimit) -> Self {
+        self.frame_limit = frame_limit;
+        self
+    }
+
+    pub fn with_present_mode(mut self, present_mode: PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+}
+
+impl FrameLimit {
+    fn limiter(self) -> Limiter {
+        match self {
+            FrameLimit::Auto => Limiter::Auto,
+            FrameLimit::Fps(fps) if fps > 0.0 => {
+                Limiter::Manual(Duration::from_secs_f64(1.0 / fps))
+            }
+            FrameLimit::Fps(_) | FrameLimit::Uncapped => Limiter::Off,
+        }
+    }
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            ev100_offset: 0.0,
+            tonemapping: Tonemapping::TonyMcMapface,
+            frame_limit: FrameLimit::Auto,
+            present_mode: PresentMode::AutoVsync,
+        }
+    }
+}
+
+fn apply_graphics_settings(
+    mut commands: Commands,
+    settings: Res<GraphicsSettings>,
+    camera: Single<Entity, With<PlayerCamera>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    commands.entity(*camera).insert((
+        Exposure {
+            ev100: Exposure::default().ev100 + settings.ev100_offset,
+        },
+        settings.tonemapping,
+    ));
+}
+
+fn apply_frame_pacing(
+    settings: Res<GraphicsSettings>,
+    mut framepace: ResMut<FramepaceSettings>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    framepace.limiter = settings.frame_limit.limiter();
+    if window.present_mode != settings.present_mode {
+        window.present_mode = settings.present_mode;
+    }
+}
@@ -0,0 +1,200 @@
+//! Gravity that varies from place to place: boxes that replace it, and sources that pull towards
+//! a point, like a small planet.
+//!
+//! Every dynamic and kinematic rigid body, including the player, is given a [`LocalGravity`] with
+//! the gravity where it is, updated each physics step, and dynamic bodies fall along it instead of
+//! the global [`Gravity`]. Inside a [`GravityVolume`], gravity is the volume's own, taking the one
+//! with the highest priority where they overlap. Elsewhere, every [`GravitySource`] in range pulls
+//! towards its centre, and where none are, the global gravity applies.
+//!
+//! The kinematic player controller turns to stand on whatever it's pulled towards, so it can walk
+//! all the way around a planet, and the camera turns with it. The dynamic player is pulled along
+//! its gravity too, but stays upright.
+
+#![allow(clippy::useless_conversion)]
+use avian3d::math::{AdjustPrecision, AsF32};
+use avian3d::prelude::*;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+pub struct GravityPlugin;
+
+impl Plugin for GravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LocalGravity>().add_systems(
+            FixedUpdate,
+            (update_local_gravity, apply_local_gravity).chain(),
+        );
+    }
+}
+
+/// A box in which gravity is replaced, e.g. for a low gravity room, or one where it pulls sideways.
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct GravityVolume {
+    pub half_extents: Vec3,
+    /// Gravity inside the volume, in its own space, so rotating the volume turns its gravity.
+    pub gravity: Vec3,
+    /// Which volume wins where they overlap, highest first.
+    pub priority: i32,
+}
+
+impl GravityVolume {
+    pub fn new(half_extents: Vec3, gravity: Vec3) -> Self {
+        Self {
+            half_extents,
+            gravity,
+            priority: 0,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Whether the world-space `point` is inside the volume.
+    pub fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let local = transform.affine().inverse().transform_point3(point);
+        local.abs().cmple(self.half_extents).all()
+    }
+}
+
+/// Pulls everything within `radius` of it towards its centre, like a small planet or moon.
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct GravitySource {
+    /// Strength of the pull, at `surface_radius` if there is one.
+    pub acceleration: f32,
+    /// How far from the centre the pull reaches.
+    pub radius: f32,
+    /// Radius the pull is `acceleration` at, weakening with the square of the distance beyond it,
+    /// or `None` for the same pull everywhere in range.
+    pub surface_radius: Option<f32>,
+}
+
+impl GravitySource {
+    pub fn new(acceleration: f32, radius: f32) -> Self {
+        Self {
+            acceleration,
+            radius,
+            surface_radius: None,
+        }
+    }
+
+    pub fn with_surface_radius(mut self, surface_radius: f32) -> Self {
+        self.surface_radius = Some(surface_radius.max(0.0));
+        self
+    }
+
+    /// The pull on the world-space `point`, or `None` if it's out of range.
+    pub fn gravity_at(&self, transform: &GlobalTransform, point: Vec3) -> Option<Vec3> {
+        let offset = transform.translation() - point;
+        let distance = offset.length();
+        if distance > self.radius {
+            return None;
+        }
+        let acceleration = match self.surface_radius {
+            Some(surface) if distance > surface => self.acceleration * (surface / distance).powi(2),
+            _ => self.acceleration,
+        };
+        Some(offset.normalize_or_zero() * acceleration)
+    }
+}
+
+/// The gravity acting on a body where it is, as of the last physics step.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct LocalGravity(pub Vec3);
+
+impl LocalGravity {
+    /// The direction opposite gravity, or `Y` in zero gravity.
+    pub fn up(&self) -> Dir3 {
+        Dir3::new(-self.0).unwrap_or(Dir3::Y)
+    }
+}
+
+/// The gravity anywhere in the world, from [`GravityVolume`]s, [`GravitySource`]s and the global
+/// [`Gravity`].
+#[derive(SystemParam)]
+pub struct GravityField<'w, 's> {
+    gravity: Res<'w, Gravity>,
+    volumes: Query<'w, 's, (&'static GravityVolume, &'static GlobalTransform)>,
+    sources: Query<'w, 's, (&'static GravitySource, &'static GlobalTransform)>,
+}
+
+impl GravityField<'_, '_> {
+    /// Whether gravity is the same everywhere.
+    pub fn is_uniform(&self) -> bool {
+        self.volumes.is_empty() && self.sources.is_empty()
+    }
+
+    /// The gravity at the world-space `point`.
+    pub fn at(&self, point: Vec3) -> Vec3 {
+        if let Some((volume, transform)) = self
+            .volumes
+            .iter()
+            .filter(|(volume, transform)| volume.contains(transform, point))
+            .max_by_key(|(volume, _)| volume.priority)
+        {
+            return transform.rotation() * volume.gravity;
+        }
+        self.sources
+            .iter()
+            .filter_map(|(source, transform)| source.gravity_at(transform, point))
+            .reduce(|a, b| a + b)
+            .unwrap_or_else(|| self.gravity.0.f32())
+    }
+}
+
+pub(crate) fn update_local_gravity(
+    mut commands: Commands,
+    field: GravityField,
+    mut bodies: Query<(Entity, &RigidBody, &Position, Option<&mut LocalGravity>)>,
+) {
+    let uniform = field.is_uniform();
+    for (entity, body, position, local) in &mut bodies {
+        if body.is_static() {
+            continue;
+        }
+        let gravity = if uniform {
+            field.gravity.0.f32()
+        } else {
+            field.at(position.0.f32())
+        };
+        match local {
+            Some(mut local) => {
+                local.set_if_neq(LocalGravity(gravity));
+            }
+            None => {
+                commands.entity(entity).insert(LocalGravity(gravity));
+            }
+        }
+    }
+}
+
+/// Swaps the global gravity avian applies to dynamic bodies for their local gravity.
+fn apply_local_gravity(
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    gravity: Res<Gravity>,
+    mut bodies: Query<(
+        &RigidBody,
+        &LocalGravity,
+        &mut LinearVelocity,
+        Option<&GravityScale>,
+    )>,
+) {
+    if physics_time.is_paused() {
+        return;
+    }
+    let dt = time.delta_secs();
+    let gravity = gravity.0.f32();
+    for (body, local, mut velocity, scale) in &mut bodies {
+        if !body.is_dynamic() || local.0 == gravity {
+            continue;
+        }
+        let scale = scale.map_or(1.0, |scale| scale.0.f32());
+        velocity.0 += ((local.0 - gravity) * scale * dt).adjust_precision();
+    }
+}
@@ -0,0 +1,625 @@
+//! Guestbook terminals: screens visitors sign with a short message, kept for whoever visits next.
+//!
+//! A [`Guestbook`] builds a terminal on a post, with a screen facing +Z listing the latest
+//! messages left in it, rendered as UI to a texture like a
+//! [`StatsPlaque`](crate::plaque::StatsPlaque). Interacting with it opens an on-screen keyboard:
+//! messages can be typed on the keyboard, or picked out a key at a time by clicking or with a
+//! gamepad's d-pad and South, then signed with Enter or the Sign key. Signing with nothing written,
+//! the Close key or a gamepad's East closes it without signing. Messages are kept in the
+//! [`GuestbookEntries`] resource by guestbook title. Registering it with
+//! [`SaveGame::with_resource`] keeps them between sessions, and they're saved whenever one is
+//! signed, so sessions sharing a save file read each other's messages.
+
+#![allow(clippy::useless_conversion)]
+
+use std::collections::BTreeMap;
+
+use avian3d::prelude::*;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use bevy::ui::UiTargetCamera;
+use serde::{Deserialize, Serialize};
+
+use crate::controls::KeyboardCapture;
+use crate::firstsight::MovementDisabled;
+use crate::interact::{Interactable, Interacted};
+use crate::picking::Hint;
+use crate::plaque::spawn_ui_texture;
+use crate::player::Player;
+use crate::save::{SaveGame, SaveRequest};
+use crate::state::PausableSystems;
+use crate::window::CursorPolicy;
+
+pub struct GuestbookPlugin;
+
+impl Plugin for GuestbookPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GuestbookEntries>()
+            .init_resource::<GuestbookEditor>()
+            .add_observer(build_guestbook)
+            .add_observer(remove_guestbook)
+            .add_observer(open_guestbook)
+            .add_systems(
+                Update,
+                (
+                    type_message.in_set(PausableSystems),
+                    update_keyboard,
+                    update_guestbook_screens,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Longest message that can be signed, in characters.
+const MAX_MESSAGE_LENGTH: usize = 120;
+/// Pixels per world unit of a screen's rendered texture.
+const PIXELS_PER_UNIT: f32 = 480.0;
+/// How far the head of the terminal tips back, so its screen faces up towards visitors.
+const HEAD_TILT: f32 = 0.35;
+/// Why the cursor's released and the player held still while signing.
+const SIGNING_REASON: &str = "guestbook";
+
+const KEY_COLOR: Color = Color::srgba(0.15, 0.15, 0.15, 0.9);
+const KEY_SELECTED_COLOR: Color = Color::srgba(0.35, 0.35, 0.3, 0.9);
+const KEY_PRESSED_COLOR: Color = Color::srgba(0.55, 0.5, 0.35, 0.9);
+/// Characters on the on-screen keyboard, a row at a time, above a row of [`OnScreenKey::ACTIONS`].
+const KEY_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl'", "zxcvbnm,.?"];
+
+/// A terminal for visitors to sign, built standing on its entity's origin.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility, RigidBody::Static, Interactable = Interactable::press())]
+pub struct Guestbook {
+    /// Which messages it keeps and shows, so terminals with the same title share them.
+    pub title: String,
+    /// Height of the middle of the screen above the floor.
+    pub height: f32,
+    /// Width and height of the screen in world units.
+    pub size: Vec2,
+    /// How many of the latest messages the screen lists.
+    pub shown: usize,
+    pub text_color: Color,
+    pub background: Color,
+}
+
+impl Guestbook {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            height: 1.3,
+            size: Vec2::new(0.6, 0.4),
+            shown: 5,
+            text_color: Color::srgb(0.55, 0.95, 0.6),
+            background: Color::srgb(0.02, 0.05, 0.03),
+        }
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height.max(0.1);
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size.max(Vec2::splat(0.1));
+        self
+    }
+
+    pub fn with_shown(mut self, shown: usize) -> Self {
+        self.shown = shown;
+        self
+    }
+
+    pub fn with_colors(mut self, text: Color, background: Color) -> Self {
+        self.text_color = text;
+        self.background = background;
+        self
+    }
+}
+
+/// Every message signed, by the title of the guestbook it was signed in, oldest first.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GuestbookEntries {
+    books: BTreeMap<String, Vec<String>>,
+}
+
+impl GuestbookEntries {
+    pub fn sign(&mut self, title: impl Into<String>, message: impl Into<String>) {
+        self.books
+            .entry(title.into())
+            .or_default()
+            .push(message.into());
+    }
+
+    /// Messages signed in the guestbook titled `title`, oldest first.
+    pub fn for_book(&self, title: &str) -> &[String] {
+        self.books.get(title).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Triggered on a [`Guestbook`] when it's signed.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct GuestbookSigned {
+    pub entity: Entity,
+    pub title: String,
+    pub message: String,
+}
+
+/// A key on the on-screen keyboard.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum OnScreenKey {
+    Character(char),
+    /// Capitalises the next letter.
+    Shift,
+    Space,
+    Delete,
+    Sign,
+    Close,
+}
+
+impl OnScreenKey {
+    const ACTIONS: [Self; 5] = [
+        Self::Shift,
+        Self::Space,
+        Self::Delete,
+        Self::Sign,
+        Self::Close,
+    ];
+
+    fn label(self, shift: bool) -> String {
+        match self {
+            Self::Character(character) if shift => character.to_uppercase().collect(),
+            Self::Character(character) => character.to_string(),
+            Self::Shift => "Shift".to_string(),
+            Self::Space => "Space".to_string(),
+            Self::Delete => "Delete".to_string(),
+            Self::Sign => "Sign".to_string(),
+            Self::Close => "Close".to_string(),
+        }
+    }
+}
+
+/// Every on-screen key, a row at a time.
+fn keyboard_layout() -> Vec<Vec<OnScreenKey>> {
+    KEY_ROWS
+        .iter()
+        .map(|row| row.chars().map(OnScreenKey::Character).collect())
+        .chain(std::iter::once(OnScreenKey::ACTIONS.to_vec()))
+        .collect()
+}
+
+/// The message being written, if any.
+#[derive(Resource, Default)]
+struct GuestbookEditor {
+    book: Option<Entity>,
+    text: String,
+    shift: bool,
+    /// Row and column of the key a gamepad's picked.
+    selected: (usize, usize),
+    /// Whether keys typed since opening are going into the message, so the one that opened it
+    /// isn't.
+    ready: bool,
+}
+
+/// What a guestbook's screen is built from, with the camera and UI rendering its texture living
+/// outside its hierarchy.
+#[derive(Component)]
+struct GuestbookScreen {
+    camera: Entity,
+    ui: Entity,
+    text: Entity,
+}
+
+#[derive(Component)]
+struct KeyboardPanel;
+
+#[derive(Component)]
+struct MessageText;
+
+fn build_guestbook(
+    add: On<Add, Guestbook>,
+    mut commands: Commands,
+    guestbooks: Query<&Guestbook>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok(guestbook) = guestbooks.get(add.entity) else {
+        return;
+    };
+    let resolution = (guestbook.size * PIXELS_PER_UNIT)
+        .round()
+        .as_uvec2()
+        .max(UVec2::ONE);
+    let (image, camera) =
+        spawn_ui_texture(&mut commands, &mut images, resolution, guestbook.background);
+    commands
+        .entity(camera)
+        .insert(Name::new("Guestbook camera"));
+    let scale = resolution.y as f32 / 192.0;
+    let text = commands
+        .spawn((
+            Text::default(),
+            TextFont {
+                font_size: 13.0 * scale,
+                ..default()
+            },
+            TextColor(guestbook.text_color),
+        ))
+        .id();
+    let ui = commands
+        .spawn((
+            Name::new("Guestbook UI"),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0 * scale)),
+                row_gap: Val::Px(6.0 * scale),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            UiTargetCamera(camera),
+            children![(
+                Text::new(guestbook.title.clone()),
+                TextFont {
+                    font_size: 18.0 * scale,
+                    ..default()
+                },
+                TextColor(guestbook.text_color),
+            )],
+        ))
+        .add_child(text)
+        .id();
+
+    let body = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.12, 0.12, 0.13),
+        perceptual_roughness: 0.5,
+        metallic: 0.6,
+        ..default()
+    });
+    let bezel = guestbook.size + Vec2::splat(0.08);
+    let depth = 0.08;
+    let post_height = guestbook.height - bezel.y / 2.0;
+    let post = commands
+        .spawn((
+            Name::new("Guestbook post"),
+            Mesh3d(meshes.add(Cuboid::new(0.12, post_height, 0.12))),
+            MeshMaterial3d(body.clone()),
+            Transform::from_xyz(0.0, post_height / 2.0, 0.0),
+            Collider::cuboid(0.12, post_height.into(), 0.12),
+        ))
+        .id();
+    let screen = commands
+        .spawn((
+            Name::new("Guestbook screen"),
+            Mesh3d(meshes.add(Rectangle::from_size(guestbook.size))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color_texture: Some(image),
+                unlit: true,
+                ..default()
+            })),
+            Transform::from_xyz(0.0, 0.0, depth / 2.0 + 0.005),
+        ))
+        .id();
+    let head = commands
+        .spawn((
+            Name::new("Guestbook head"),
+            Mesh3d(meshes.add(Cuboid::new(bezel.x, bezel.y, depth))),
+            MeshMaterial3d(body),
+            Transform::from_xyz(0.0, guestbook.height, 0.0)
+                .with_rotation(Quat::from_rotation_x(-HEAD_TILT)),
+            Collider::cuboid(bezel.x.into(), bezel.y.into(), depth.into()),
+        ))
+        .add_child(screen)
+        .id();
+
+    commands
+        .entity(add.entity)
+        .add_children(&[post, head])
+        .insert((
+            Hint::new(format!("Sign the {}", guestbook.title)),
+            GuestbookScreen { camera, ui, text },
+        ));
+}
+
+fn remove_guestbook(
+    remove: On<Remove, Guestbook>,
+    mut commands: Commands,
+    screens: Query<&GuestbookScreen>,
+) {
+    let Ok(screen) = screens.get(remove.entity) else {
+        return;
+    };
+    commands.entity(screen.ui).try_despawn();
+    commands.entity(screen.camera).try_despawn();
+}
+
+fn open_guestbook(
+    interacted: On<Interacted>,
+    mut commands: Commands,
+    guestbooks: Query<&Guestbook>,
+    mut editor: ResMut<GuestbookEditor>,
+    mut cursor: ResMut<CursorPolicy>,
+    player: Single<Entity, With<Player>>,
+) {
+    let Ok(guestbook) = guestbooks.get(interacted.entity) else {
+        return;
+    };
+    if editor.book.is_some() {
+        return;
+    }
+    *editor = GuestbookEditor {
+        book: Some(interacted.entity),
+        ..default()
+    };
+    // Keys type into the message instead of moving the player, and the cursor's freed to click keys
+    commands.init_resource::<KeyboardCapture>();
+    commands
+        .entity(*player)
+        .queue(MovementDisabled::request(SIGNING_REASON));
+    cursor.request_release(SIGNING_REASON);
+
+    commands
+        .spawn((
+            Name::new("Guestbook keyboard"),
+            KeyboardPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(25.0),
+                left: Val::Percent(25.0),
+                width: Val::Percent(50.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(12.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new(format!("Sign the {}", guestbook.title)),
+                TextFont::from_font_size(20.0),
+            ));
+            panel.spawn((
+                MessageText,
+                Text::new("_"),
+                TextFont::from_font_size(16.0),
+                Node {
+                    min_height: Val::Px(44.0),
+                    margin: UiRect::vertical(Val::Px(6.0)),
+                    ..default()
+                },
+            ));
+            for row in keyboard_layout() {
+                panel
+                    .spawn(Node {
+                        column_gap: Val::Px(4.0),
+                        ..default()
+                    })
+                    .with_children(|row_node| {
+                        for key in row {
+                            row_node.spawn((
+                                key,
+                                Button,
+                                Node {
+                                    min_width: Val::Px(32.0),
+                                    padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                                    justify_content: JustifyContent::Center,
+                                    ..default()
+                                },
+                                BackgroundColor(KEY_COLOR),
+                                Text::new(key.label(false)),
+                                TextFont::from_font_size(16.0),
+                            ));
+                        }
+                    });
+            }
+            panel.spawn((
+                Text::new("Type or pick keys, Enter to sign"),
+                TextFont::from_font_size(12.0),
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// What pressing a key does to the message being written.
+enum Edit {
+    Type(char),
+    Shift,
+    Delete,
+    Sign,
+    Close,
+}
+
+impl From<OnScreenKey> for Edit {
+    fn from(key: OnScreenKey) -> Self {
+        match key {
+            OnScreenKey::Character(character) => Self::Type(character),
+            OnScreenKey::Shift => Self::Shift,
+            OnScreenKey::Space => Self::Type(' '),
+            OnScreenKey::Delete => Self::Delete,
+            OnScreenKey::Sign => Self::Sign,
+            OnScreenKey::Close => Self::Close,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn type_message(
+    mut commands: Commands,
+    mut keyboard: MessageReader<KeyboardInput>,
+    gamepads: Query<&Gamepad>,
+    keys: Query<(&OnScreenKey, &Interaction), Changed<Interaction>>,
+    mut editor: ResMut<GuestbookEditor>,
+    mut entries: ResMut<GuestbookEntries>,
+    mut cursor: ResMut<CursorPolicy>,
+    save_game: Option<Res<SaveGame>>,
+    guestbooks: Query<&Guestbook>,
+    panels: Query<Entity, With<KeyboardPanel>>,
+    player: Single<Entity, With<Player>>,
+) {
+    // Always read, so keys pressed before opening aren't typed into the message
+    let typed: Vec<_> = keyboard
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed)
+        .map(|input| input.logical_key.clone())
+        .collect();
+    let Some(entity) = editor.book else {
+        return;
+    };
+    let guestbook = guestbooks.get(entity).ok();
+    let mut edits = Vec::new();
+    if guestbook.is_none() {
+        // The guestbook's gone, so there's nothing to sign
+        edits.push(Edit::Close);
+    } else if !editor.ready {
+        editor.ready = true;
+    } else {
+        for key in typed {
+            match key {
+                Key::Character(text) => edits.extend(
+                    text.chars()
+                        .filter(|character| !character.is_control())
+                        .map(Edit::Type),
+                ),
+                Key::Space => edits.push(Edit::Type(' ')),
+                Key::Backspace => edits.push(Edit::Delete),
+                Key::Enter => edits.push(Edit::Sign),
+                _ => {}
+            }
+        }
+        edits.extend(
+            keys.iter()
+                .filter(|(_, interaction)| **interaction == Interaction::Pressed)
+                .map(|(key, _)| Edit::from(*key)),
+        );
+
+        let layout = keyboard_layout();
+        for gamepad in &gamepads {
+            let (mut row, mut column) = editor.selected;
+            if gamepad.just_pressed(GamepadButton::DPadUp) {
+                row = row.saturating_sub(1);
+            }
+            if gamepad.just_pressed(GamepadButton::DPadDown) {
+                row = (row + 1).min(layout.len() - 1);
+            }
+            if gamepad.just_pressed(GamepadButton::DPadLeft) {
+                column = column.saturating_sub(1);
+            }
+            if gamepad.just_pressed(GamepadButton::DPadRight) {
+                column += 1;
+            }
+            column = column.min(layout[row].len() - 1);
+            if editor.selected != (row, column) {
+                editor.selected = (row, column);
+            }
+            if gamepad.just_pressed(GamepadButton::South) {
+                edits.push(layout[row][column].into());
+            }
+            if gamepad.just_pressed(GamepadButton::East) {
+                edits.push(Edit::Close);
+            }
+        }
+    }
+
+    for edit in edits {
+        match edit {
+            Edit::Type(character) => {
+                if editor.text.chars().count() < MAX_MESSAGE_LENGTH {
+                    let shift = std::mem::take(&mut editor.shift);
+                    if shift {
+                        editor.text.extend(character.to_uppercase());
+                    } else {
+                        editor.text.push(character);
+                    }
+                }
+            }
+            Edit::Shift => editor.shift = !editor.shift,
+            Edit::Delete => {
+                editor.text.pop();
+            }
+            Edit::Sign | Edit::Close => {
+                let message = editor.text.trim().to_string();
+                let signed = matches!(edit, Edit::Sign) && !message.is_empty();
+                if let Some(guestbook) = guestbook.filter(|_| signed) {
+                    entries.sign(guestbook.title.clone(), message.clone());
+                    commands.trigger(GuestbookSigned {
+                        entity,
+                        title: guestbook.title.clone(),
+                        message,
+                    });
+                    if save_game.is_some() {
+                        commands.trigger(SaveRequest);
+                    }
+                }
+                *editor = GuestbookEditor::default();
+                commands.remove_resource::<KeyboardCapture>();
+                cursor.cancel_release(SIGNING_REASON);
+                commands
+                    .entity(*player)
+                    .queue(MovementDisabled::cancel(SIGNING_REASON));
+                for panel in &panels {
+                    commands.entity(panel).despawn();
+                }
+                return;
+            }
+        }
+    }
+}
+
+fn update_keyboard(
+    editor: Res<GuestbookEditor>,
+    mut message: Query<&mut Text, (With<MessageText>, Without<OnScreenKey>)>,
+    mut keys: Query<(&OnScreenKey, &Interaction, &mut BackgroundColor, &mut Text)>,
+) {
+    let layout = keyboard_layout();
+    let selected = layout[editor.selected.0][editor.selected.1];
+    for (key, interaction, mut color, mut label) in &mut keys {
+        let shown = match interaction {
+            Interaction::Pressed => KEY_PRESSED_COLOR,
+            Interaction::Hovered => KEY_SELECTED_COLOR,
+            Interaction::None if *key == selected => KEY_SELECTED_COLOR,
+            Interaction::None => KEY_COLOR,
+        };
+        color.set_if_neq(BackgroundColor(shown));
+        let shown = key.label(editor.shift);
+        if label.0 != shown {
+            label.0 = shown;
+        }
+    }
+    if !editor.is_changed() {
+        return;
+    }
+    for mut text in &mut message {
+        text.0 = format!("{}_", editor.text);
+    }
+}
+
+fn update_guestbook_screens(
+    entries: Res<GuestbookEntries>,
+    guestbooks: Query<(&Guestbook, Ref<GuestbookScreen>)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (guestbook, screen) in &guestbooks {
+        if !entries.is_changed() && !screen.is_added() {
+            continue;
+        }
+        let Ok(mut text) = texts.get_mut(screen.text) else {
+            continue;
+        };
+        let messages = entries.for_book(&guestbook.title);
+        text.0 = if messages.is_empty() {
+            "No one's signed yet. Be the first!".to_string()
+        } else {
+            messages
+                .iter()
+                .rev()
+                .take(guestbook.shown)
+                .map(|message| format!("> {message}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+    }
+}
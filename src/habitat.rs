@@ -0,0 +1,137 @@
+//! Creatures that live around structures in the scene, such as schools of fish around a reef.
+//!
+//! A [`Habitat`] on an entity says which species lives there and how many. The population manager
+//! triggers [`PopulateHabitat`] on habitats with room, all at once when they first appear and then
+//! a few at a time after [`Habitat::repopulate_delay`]. Scenes observe it to spawn the creatures,
+//! giving each an [`Inhabitant`] relationship back to its habitat. Removing the [`Habitat`], say
+//! once it's been scanned, or despawning its entity, despawns everything living there.
+
+use bevy::ecs::relationship::RelationshipTarget;
+use bevy::prelude::*;
+
+use crate::game_time::GameTime;
+use crate::state::PausableSystems;
+
+pub struct HabitatPlugin;
+
+impl Plugin for HabitatPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Habitat>()
+            .add_systems(Update, populate_habitats.in_set(PausableSystems))
+            .add_observer(evict_inhabitants);
+    }
+}
+
+/// Somewhere creatures of a species live, up to `capacity` of them.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct Habitat {
+    /// Which creatures live here, for scenes to match on when populating it.
+    pub species: String,
+    pub capacity: u32,
+    /// How far from the habitat its creatures are spawned, and should roam.
+    pub radius: f32,
+    /// Seconds a habitat stays short of capacity before more creatures move in.
+    pub repopulate_delay: f32,
+    populated: bool,
+    vacant_for: f32,
+}
+
+impl Habitat {
+    pub fn new(species: impl Into<String>, capacity: u32) -> Self {
+        Self {
+            species: species.into(),
+            capacity,
+            radius: 8.0,
+            repopulate_delay: 20.0,
+            populated: false,
+            vacant_for: 0.0,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_repopulate_delay(mut self, delay: f32) -> Self {
+        self.repopulate_delay = delay.max(0.0);
+        self
+    }
+}
+
+/// The [`Habitat`] a creature lives in.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[relationship(relationship_target = Inhabitants)]
+pub struct Inhabitant(pub Entity);
+
+/// Creatures living in a [`Habitat`], kept up to date by [`Inhabitant`].
+#[derive(Component, Debug, Default)]
+#[relationship_target(relationship = Inhabitant)]
+pub struct Inhabitants(Vec<Entity>);
+
+/// Triggered on a [`Habitat`] with room for `count` more creatures, which scenes should spawn with
+/// an [`Inhabitant`] pointing back at `entity`.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct PopulateHabitat {
+    pub entity: Entity,
+    pub species: String,
+    pub count: u32,
+    /// Where the habitat is.
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+/// Most creatures moving into an established habitat at once, so it fills back up gradually.
+const REPOPULATE_BATCH: u32 = 3;
+
+fn populate_habitats(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut habitats: Query<(Entity, &mut Habitat, &GlobalTransform, Option<&Inhabitants>)>,
+) {
+    for (entity, mut habitat, transform, inhabitants) in &mut habitats {
+        let population = inhabitants.map_or(0, |inhabitants| inhabitants.len() as u32);
+        let vacancies = habitat.capacity.saturating_sub(population);
+        if vacancies == 0 {
+            if habitat.vacant_for != 0.0 {
+                habitat.vacant_for = 0.0;
+            }
+            continue;
+        }
+
+        let count = if !habitat.populated {
+            habitat.populated = true;
+            vacancies
+        } else {
+            habitat.vacant_for += time.delta_secs();
+            if habitat.vacant_for < habitat.repopulate_delay {
+                continue;
+            }
+            habitat.vacant_for = 0.0;
+            vacancies.min(REPOPULATE_BATCH)
+        };
+        commands.trigger(PopulateHabitat {
+            entity,
+            species: habitat.species.clone(),
+            count,
+            center: transform.translation(),
+            radius: habitat.radius,
+        });
+    }
+}
+
+/// Also runs when a habitat is despawned, as its components are removed.
+fn evict_inhabitants(
+    remove: On<Remove, Habitat>,
+    mut commands: Commands,
+    inhabitants: Query<&Inhabitants>,
+) {
+    let Ok(inhabitants) = inhabitants.get(remove.entity) else {
+        return;
+    };
+    for inhabitant in inhabitants.iter() {
+        commands.entity(inhabitant).try_despawn();
+    }
+}
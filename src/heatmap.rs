@@ -0,0 +1,152 @@
+//! Debug overlay showing where playtesters went and died, from recorded telemetry.
+//!
+//! Set `DIORAMA_HEATMAP` to a file exported by the [telemetry](crate::telemetry) module, or insert a
+//! [`Heatmap`] resource, then toggle the overlay with F3+H. Position samples are binned into cells
+//! coloured from blue (rarely visited) to red (most visited), and deaths are marked with crosses.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use bevy::color::palettes::tailwind::{BLUE_500, RED_500, YELLOW_300};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::controls::keyboard_not_captured;
+use crate::kiosk::not_in_kiosk_mode;
+use crate::telemetry::{TelemetryEvent, read_records};
+
+pub struct HeatmapPlugin;
+
+impl Plugin for HeatmapPlugin {
+    fn build(&self, app: &mut App) {
+        if let Ok(path) = std::env::var(HEATMAP_ENV_VAR) {
+            app.insert_resource(Heatmap::new(path));
+        }
+        app.init_state::<HeatmapState>()
+            .add_plugins(InputManagerPlugin::<ToggleHeatmapAction>::default())
+            .add_systems(Startup, setup_actions)
+            .add_systems(
+                Update,
+                handle_actions
+                    .run_if(resource_exists::<Heatmap>)
+                    .run_if(not_in_kiosk_mode)
+                    .run_if(keyboard_not_captured),
+            )
+            .add_systems(OnEnter(HeatmapState::Visible), load_heatmap)
+            .add_systems(
+                Update,
+                draw_heatmap
+                    .run_if(in_state(HeatmapState::Visible).and(resource_exists::<Heatmap>)),
+            );
+    }
+}
+
+/// Environment variable holding the path of a telemetry file to visualise.
+const HEATMAP_ENV_VAR: &str = "DIORAMA_HEATMAP";
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HeatmapState {
+    Visible,
+    #[default]
+    Hidden,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct Heatmap {
+    pub path: PathBuf,
+    /// Size of the cubic cells that position samples are counted in.
+    pub cell_size: f32,
+    cells: HashMap<IVec3, u32>,
+    deaths: Vec<Vec3>,
+}
+
+impl Heatmap {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            cell_size: 1.0,
+            cells: HashMap::new(),
+            deaths: Vec::new(),
+        }
+    }
+
+    pub fn with_cell_size(mut self, cell_size: f32) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    fn cell(&self, position: Vec3) -> IVec3 {
+        (position / self.cell_size).floor().as_ivec3()
+    }
+}
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+struct ToggleHeatmapAction;
+
+fn setup_actions(mut commands: Commands) {
+    let toggle_map = InputMap::new([(
+        ToggleHeatmapAction,
+        ButtonlikeChord::new([KeyCode::F3, KeyCode::KeyH]),
+    )]);
+    commands.spawn((Name::new("Heatmap controls"), toggle_map));
+}
+
+fn handle_actions(
+    action_state: Single<&ActionState<ToggleHeatmapAction>>,
+    current_state: Res<State<HeatmapState>>,
+    mut next_state: ResMut<NextState<HeatmapState>>,
+) {
+    if action_state.just_pressed(&ToggleHeatmapAction) {
+        match current_state.get() {
+            HeatmapState::Visible => next_state.set(HeatmapState::Hidden),
+            HeatmapState::Hidden => next_state.set(HeatmapState::Visible),
+        }
+    }
+}
+
+/// Reloads the telemetry file each time the overlay is shown, so new exports are picked up.
+fn load_heatmap(mut heatmap: ResMut<Heatmap>) {
+    let records = match read_records(&heatmap.path) {
+        Ok(records) => records,
+        Err(e) => {
+            error!(
+                "Failed to load heatmap from {}: {e}",
+                heatmap.path.display()
+            );
+            return;
+        }
+    };
+
+    heatmap.cells.clear();
+    heatmap.deaths.clear();
+    for record in records {
+        match record.event {
+            TelemetryEvent::Position => {
+                let cell = heatmap.cell(record.position);
+                let count = heatmap.cells.entry(cell).or_default();
+                *count = count.saturating_add(1);
+            }
+            TelemetryEvent::Death => heatmap.deaths.push(record.position),
+            TelemetryEvent::Interaction(_) | TelemetryEvent::RoomTime { .. } => {}
+        }
+    }
+}
+
+fn draw_heatmap(heatmap: Res<Heatmap>, mut gizmos: Gizmos) {
+    let max_count = heatmap.cells.values().copied().max().unwrap_or(1) as f32;
+    let cold = Color::from(BLUE_500);
+    let hot = Color::from(RED_500);
+    let floor = Quat::from_rotation_x(std::f32::consts::FRAC_PI_2);
+    let size = Vec2::splat(heatmap.cell_size * 0.9);
+
+    for (cell, count) in &heatmap.cells {
+        // Drawn on the bottom face of each cell so the overlay reads like a floor plan
+        let center = (cell.as_vec3() + Vec3::new(0.5, 0.0, 0.5)) * heatmap.cell_size;
+        let heat = *count as f32 / max_count;
+        gizmos.rect(Isometry3d::new(center, floor), size, cold.mix(&hot, heat));
+    }
+
+    for death in &heatmap.deaths {
+        gizmos.cross(Isometry3d::from_translation(*death), 0.5, YELLOW_300);
+    }
+}
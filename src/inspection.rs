@@ -0,0 +1,248 @@
+//! An inspection mode that sees through things to the details hidden inside them.
+//!
+//! Tagging an entity with [`HiddenDetail`] takes it and every mesh beneath it off the player
+//! camera's view, onto [`INSPECTION_LAYER`]. While the inspect binding is held, an overlay camera
+//! following the player camera draws that layer over the finished scene, without testing against
+//! the scene's depth, so details show through whatever's in front of them: a painting's
+//! underdrawing, the framing inside a hull, the skeleton inside a creature. Details are drawn with
+//! unlit, see-through copies of their own [`StandardMaterial`]s, tinted by their [`HiddenDetail`].
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::color::ColorToPacked;
+use bevy::core_pipeline::tonemapping::Tonemapping;
+use bevy::light::NotShadowCaster;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::render::view::Hdr;
+
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::firstsight::PlayerCamera;
+use crate::state::PausableSystems;
+
+pub struct InspectionPlugin;
+
+impl Plugin for InspectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HiddenDetail>()
+            .init_resource::<Inspection>()
+            .init_resource::<DetailMaterials>()
+            .add_observer(spawn_inspection_camera)
+            .add_observer(hide_tagged_detail)
+            .add_systems(
+                Update,
+                (
+                    hide_spawned_details,
+                    inspect.in_set(PausableSystems),
+                    (toggle_inspection_camera, follow_projection),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Render layer that only the inspection overlay camera sees, used for hidden details.
+pub const INSPECTION_LAYER: usize = 30;
+
+/// Whether the player is inspecting, i.e. holding the inspect binding.
+#[derive(Resource, Debug, Default)]
+pub struct Inspection {
+    active: bool,
+}
+
+impl Inspection {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+/// Only shows this entity and its descendants while inspecting, through anything in front of them.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+pub struct HiddenDetail {
+    /// Multiplies the details' own colours, with alpha for how see-through they are.
+    pub tint: Color,
+}
+
+impl Default for HiddenDetail {
+    fn default() -> Self {
+        Self {
+            tint: Color::srgba(0.6, 0.85, 1.0, 0.8),
+        }
+    }
+}
+
+impl HiddenDetail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+}
+
+/// On meshes already moved onto the inspection layer.
+#[derive(Component)]
+struct DetailOverlay;
+
+/// Overlay materials by the material they copy and their tint.
+#[derive(Resource, Default)]
+struct DetailMaterials(HashMap<(AssetId<StandardMaterial>, [u8; 4]), Handle<StandardMaterial>>);
+
+#[derive(Component)]
+struct InspectionCamera;
+
+fn spawn_inspection_camera(add: On<Add, PlayerCamera>, mut commands: Commands) {
+    commands.entity(add.entity).with_child((
+        Name::new("Inspection camera"),
+        InspectionCamera,
+        Camera3d::default(),
+        Camera {
+            // Drawn after the player camera, over what it's drawn
+            order: 1,
+            clear_color: ClearColorConfig::None,
+            is_active: false,
+            ..default()
+        },
+        // Matching the player camera, which it shares a render target with
+        Hdr,
+        Tonemapping::TonyMcMapface,
+        Msaa::Off,
+        RenderLayers::layer(INSPECTION_LAYER),
+    ));
+}
+
+/// Moves a mesh onto the inspection layer, swapping its material for a tinted overlay.
+fn hide_detail(
+    commands: &mut Commands,
+    cache: &mut DetailMaterials,
+    materials: &mut Assets<StandardMaterial>,
+    entity: Entity,
+    detail: &HiddenDetail,
+    material: Option<&MeshMaterial3d<StandardMaterial>>,
+) {
+    let mut mesh = commands.entity(entity);
+    mesh.insert((
+        DetailOverlay,
+        RenderLayers::layer(INSPECTION_LAYER),
+        NotShadowCaster,
+    ));
+    let Some(material) = material else {
+        return;
+    };
+    let key = (material.id(), detail.tint.to_srgba().to_u8_array());
+    let overlay = match cache.0.get(&key) {
+        Some(overlay) => overlay.clone(),
+        None => {
+            let Some(own) = materials.get(material) else {
+                return;
+            };
+            let (color, tint) = (own.base_color.to_linear(), detail.tint.to_linear());
+            let overlay = materials.add(StandardMaterial {
+                base_color: LinearRgba::new(
+                    color.red * tint.red,
+                    color.green * tint.green,
+                    color.blue * tint.blue,
+                    color.alpha * tint.alpha,
+                )
+                .into(),
+                base_color_texture: own.base_color_texture.clone(),
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                double_sided: true,
+                cull_mode: None,
+                ..default()
+            });
+            cache.0.insert(key, overlay.clone());
+            overlay
+        }
+    };
+    mesh.insert(MeshMaterial3d(overlay));
+}
+
+fn hide_tagged_detail(
+    add: On<Add, HiddenDetail>,
+    mut commands: Commands,
+    mut cache: ResMut<DetailMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    details: Query<&HiddenDetail>,
+    children: Query<&Children>,
+    meshes: Query<
+        Option<&MeshMaterial3d<StandardMaterial>>,
+        (With<Mesh3d>, Without<DetailOverlay>),
+    >,
+) {
+    let Ok(detail) = details.get(add.entity) else {
+        return;
+    };
+    for entity in std::iter::once(add.entity).chain(children.iter_descendants(add.entity)) {
+        if let Ok(material) = meshes.get(entity) {
+            hide_detail(
+                &mut commands,
+                &mut cache,
+                &mut materials,
+                entity,
+                detail,
+                material,
+            );
+        }
+    }
+}
+
+/// Catches meshes spawned beneath a [`HiddenDetail`] after it was added.
+fn hide_spawned_details(
+    mut commands: Commands,
+    mut cache: ResMut<DetailMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    meshes: Query<
+        (Entity, Option<&MeshMaterial3d<StandardMaterial>>),
+        (Added<Mesh3d>, Without<DetailOverlay>),
+    >,
+    parents: Query<&ChildOf>,
+    details: Query<&HiddenDetail>,
+) {
+    for (entity, material) in &meshes {
+        let detail = std::iter::once(entity)
+            .chain(parents.iter_ancestors(entity))
+            .find_map(|ancestor| details.get(ancestor).ok());
+        if let Some(detail) = detail {
+            hide_detail(
+                &mut commands,
+                &mut cache,
+                &mut materials,
+                entity,
+                detail,
+                material,
+            );
+        }
+    }
+}
+
+fn inspect(config: Res<ControlsConfig>, input: ControlsInput, mut inspection: ResMut<Inspection>) {
+    let active = input.pressed(&config.inspect);
+    if inspection.active != active {
+        inspection.active = active;
+    }
+}
+
+fn toggle_inspection_camera(
+    inspection: Res<Inspection>,
+    mut cameras: Query<&mut Camera, With<InspectionCamera>>,
+) {
+    for mut camera in &mut cameras {
+        if camera.is_active != inspection.active {
+            camera.is_active = inspection.active;
+        }
+    }
+}
+
+fn follow_projection(
+    player: Single<&Projection, (With<PlayerCamera>, Changed<Projection>)>,
+    mut cameras: Query<&mut Projection, (With<InspectionCamera>, Without<PlayerCamera>)>,
+) {
+    for mut projection in &mut cameras {
+        *projection = player.clone();
+    }
+}
@@ -1,8 +1,14 @@
 use bevy::prelude::*;
-use bevy_inspector_egui::bevy_egui::EguiPlugin;
+use bevy_inspector_egui::bevy_egui::{EguiPlugin, PrimaryEguiContext};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use leafwing_input_manager::prelude::*;
 
+use crate::controls::keyboard_not_captured;
+use crate::debug_window::DebugUiTarget;
+use crate::kiosk::not_in_kiosk_mode;
+use crate::selection::SelectionPlugin;
+use crate::window::CursorPolicy;
+
 pub struct InspectorPlugin;
 
 #[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -19,9 +25,28 @@ impl Plugin for InspectorPlugin {
         }
         app.init_state::<InspectorState>()
             .add_plugins(WorldInspectorPlugin::default().run_if(in_state(InspectorState::Enabled)))
-            .add_plugins(InputManagerPlugin::<ToggleInspectorAction>::default())
+            .add_plugins((
+                InputManagerPlugin::<ToggleInspectorAction>::default(),
+                SelectionPlugin,
+            ))
             .add_systems(Startup, setup_actions)
-            .add_systems(Update, handle_actions);
+            .add_systems(
+                Update,
+                handle_actions
+                    .run_if(not_in_kiosk_mode)
+                    .run_if(keyboard_not_captured),
+            )
+            .add_systems(
+                Update,
+                follow_debug_window.run_if(resource_changed::<DebugUiTarget>),
+            )
+            .add_systems(OnEnter(InspectorState::Enabled), release_cursor)
+            .add_systems(OnExit(InspectorState::Enabled), restore_cursor);
+    }
+
+    // The inspector's default widgets are registered while plugins build, so override them after
+    fn finish(&self, app: &mut App) {
+        crate::inspector_widgets::register(&mut app.world().resource::<AppTypeRegistry>().write());
     }
 }
 
@@ -45,3 +70,38 @@ fn handle_actions(
         }
     }
 }
+
+/// Frees the cursor so the inspector panels can be used without fighting mouse look.
+fn release_cursor(mut policy: ResMut<CursorPolicy>) {
+    policy.request_release("inspector");
+}
+
+fn restore_cursor(mut policy: ResMut<CursorPolicy>) {
+    policy.cancel_release("inspector");
+}
+
+/// Moves the primary egui context, which the inspector draws into, onto the debug window's camera
+/// while it's open, and back to the camera that had it before once it closes.
+fn follow_debug_window(
+    mut commands: Commands,
+    target: Res<DebugUiTarget>,
+    current: Query<Entity, With<PrimaryEguiContext>>,
+    mut main_camera: Local<Option<Entity>>,
+) {
+    let destination = match target.0 {
+        Some(camera) => camera,
+        None => match main_camera.take() {
+            Some(camera) => camera,
+            None => return,
+        },
+    };
+    for entity in current.iter().filter(|entity| *entity != destination) {
+        if target.0.is_some() && main_camera.is_none() {
+            *main_camera = Some(entity);
+        }
+        commands.entity(entity).remove::<PrimaryEguiContext>();
+    }
+    if let Ok(mut destination) = commands.get_entity(destination) {
+        destination.insert(PrimaryEguiContext);
+    }
+}
@@ -0,0 +1,222 @@
+//! Custom world inspector widgets for colours, so shader material parameters can be tuned at
+//! runtime with a colour picker instead of by editing raw floats.
+
+use std::any::{Any, TypeId};
+
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use bevy_inspector_egui::egui;
+use bevy_inspector_egui::egui::color_picker::{Alpha, color_edit_button_rgba};
+use bevy_inspector_egui::inspector_egui_impls::InspectorEguiImpl;
+use bevy_inspector_egui::reflect_inspector::{InspectorUi, ProjectorReflect};
+
+use crate::color::{ColorGradient, GradientKey};
+
+/// Number of slices used to draw a gradient's preview strip.
+const PREVIEW_SLICES: usize = 64;
+const PREVIEW_HEIGHT: f32 = 16.0;
+
+pub(crate) fn register(registry: &mut TypeRegistry) {
+    registry.register::<ColorGradient>();
+    insert_impl::<LinearRgba>(
+        registry,
+        InspectorEguiImpl::new(linear_rgba_ui, linear_rgba_ui_readonly, linear_rgba_ui_many),
+    );
+    insert_impl::<ColorGradient>(
+        registry,
+        InspectorEguiImpl::new(gradient_ui, gradient_ui_readonly, gradient_ui_many),
+    );
+}
+
+fn insert_impl<T: 'static>(registry: &mut TypeRegistry, widget: InspectorEguiImpl) {
+    match registry.get_mut(TypeId::of::<T>()) {
+        Some(registration) => registration.insert(widget),
+        None => warn!(
+            "Can't add inspector widget for unregistered type {}",
+            std::any::type_name::<T>()
+        ),
+    }
+}
+
+fn to_egui(color: LinearRgba) -> egui::Rgba {
+    egui::Rgba::from_rgba_unmultiplied(color.red, color.green, color.blue, color.alpha)
+}
+
+fn from_egui(color: egui::Rgba) -> LinearRgba {
+    let [red, green, blue, alpha] = color.to_rgba_unmultiplied();
+    LinearRgba::new(red, green, blue, alpha)
+}
+
+pub(crate) fn edit_color(ui: &mut egui::Ui, color: &mut LinearRgba) -> bool {
+    let mut rgba = to_egui(*color);
+    if color_edit_button_rgba(ui, &mut rgba, Alpha::OnlyBlend).changed() {
+        *color = from_egui(rgba);
+        true
+    } else {
+        false
+    }
+}
+
+/// Edits the first value and, if it changed, copies the result to every selected value.
+fn edit_many<T: Reflect + Clone>(
+    values: &mut [&mut dyn PartialReflect],
+    projector: &dyn ProjectorReflect,
+    edit: impl FnOnce(&mut T) -> bool,
+) -> bool {
+    let Some(mut shared) = values
+        .first_mut()
+        .and_then(|value| projector(&mut **value).try_downcast_ref::<T>().cloned())
+    else {
+        return false;
+    };
+    if !edit(&mut shared) {
+        return false;
+    }
+    for value in values.iter_mut() {
+        projector(&mut **value).apply(&shared);
+    }
+    true
+}
+
+fn linear_rgba_ui(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    _id: egui::Id,
+    _env: InspectorUi<'_, '_>,
+) -> bool {
+    value
+        .downcast_mut::<LinearRgba>()
+        .is_some_and(|color| edit_color(ui, color))
+}
+
+fn linear_rgba_ui_readonly(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    _id: egui::Id,
+    _env: InspectorUi<'_, '_>,
+) {
+    if let Some(color) = value.downcast_ref::<LinearRgba>() {
+        egui::color_picker::show_color(ui, to_egui(*color), ui.spacing().interact_size);
+    }
+}
+
+fn linear_rgba_ui_many(
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    _id: egui::Id,
+    _env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn PartialReflect],
+    projector: &dyn ProjectorReflect,
+) -> bool {
+    edit_many::<LinearRgba>(values, projector, |color| edit_color(ui, color))
+}
+
+fn draw_gradient_preview(ui: &mut egui::Ui, gradient: &ColorGradient) {
+    let width = ui.available_width().max(PREVIEW_SLICES as f32);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, PREVIEW_HEIGHT), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let slice_width = rect.width() / PREVIEW_SLICES as f32;
+    for slice in 0..PREVIEW_SLICES {
+        let t = (slice as f32 + 0.5) / PREVIEW_SLICES as f32;
+        let left = rect.left() + slice as f32 * slice_width;
+        let slice_rect = egui::Rect::from_min_max(
+            egui::pos2(left, rect.top()),
+            egui::pos2(left + slice_width, rect.bottom()),
+        );
+        painter.rect_filled(slice_rect, 0.0, to_egui(gradient.sample(t)));
+    }
+    // Tick marks where the keys are
+    for key in gradient.keys() {
+        let x = rect.left() + key.position * rect.width();
+        painter.vline(
+            x,
+            rect.y_range(),
+            egui::Stroke::new(1.0, ui.visuals().strong_text_color()),
+        );
+    }
+}
+
+fn edit_gradient(ui: &mut egui::Ui, id: egui::Id, gradient: &mut ColorGradient) -> bool {
+    let mut changed = false;
+    draw_gradient_preview(ui, gradient);
+
+    let mut removed = None;
+    egui::Grid::new(id).num_columns(3).show(ui, |ui| {
+        for (index, key) in gradient.keys_mut().iter_mut().enumerate() {
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut key.position)
+                        .range(0.0..=1.0)
+                        .speed(0.005),
+                )
+                .changed();
+            changed |= edit_color(ui, &mut key.color);
+            if ui.small_button("✖").on_hover_text("Remove key").clicked() {
+                removed = Some(index);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(index) = removed {
+        gradient.keys_mut().remove(index);
+        changed = true;
+    }
+
+    if ui.button("Add key").clicked() {
+        // Split the widest gap, so the new key doesn't change the gradient until it's edited
+        let mut positions: Vec<f32> = gradient.keys().iter().map(|key| key.position).collect();
+        positions.insert(0, 0.0);
+        positions.push(1.0);
+        let position = positions
+            .windows(2)
+            .max_by(|a, b| (a[1] - a[0]).total_cmp(&(b[1] - b[0])))
+            .map_or(0.5, |gap| (gap[0] + gap[1]) / 2.0);
+        let color = gradient.sample(position);
+        gradient.keys_mut().push(GradientKey::new(position, color));
+        changed = true;
+    }
+
+    if changed {
+        gradient.sort();
+    }
+    changed
+}
+
+fn gradient_ui(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    id: egui::Id,
+    _env: InspectorUi<'_, '_>,
+) -> bool {
+    value
+        .downcast_mut::<ColorGradient>()
+        .is_some_and(|gradient| ui.vertical(|ui| edit_gradient(ui, id, gradient)).inner)
+}
+
+fn gradient_ui_readonly(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    _id: egui::Id,
+    _env: InspectorUi<'_, '_>,
+) {
+    if let Some(gradient) = value.downcast_ref::<ColorGradient>() {
+        draw_gradient_preview(ui, gradient);
+    }
+}
+
+fn gradient_ui_many(
+    ui: &mut egui::Ui,
+    _options: &dyn Any,
+    id: egui::Id,
+    _env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn PartialReflect],
+    projector: &dyn ProjectorReflect,
+) -> bool {
+    edit_many::<ColorGradient>(values, projector, |gradient| {
+        ui.vertical(|ui| edit_gradient(ui, id, gradient)).inner
+    })
+}
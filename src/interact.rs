@@ -0,0 +1,320 @@
+//! Interactions with entities the player points at or walks up to.
+//!
+//! Add an [`Interactable`] to an entity to make it respond to the interact binding (E, or West on
+//! a gamepad, by default) or a left click while the player looks at it within range:
+//!
+//! - [`InteractionKind::Press`] triggers [`Interacted`] straight away.
+//! - [`InteractionKind::Hold`] fills a progress bar while held, triggering [`HoldStarted`], then
+//!   [`Interacted`] once full, or [`HoldCancelled`] if let go or looked away from early.
+//! - [`InteractionKind::Proximity`] triggers [`ProximityEntered`] and [`ProximityExited`] as the
+//!   player comes within range and leaves again, without needing to look at it.
+//!
+//! All events target the interactable entity, so scenes can `.observe(..)` them on the entity or
+//! with a global observer. Pointing at a mesh child counts as pointing at its nearest interactable
+//! ancestor.
+
+use bevy::picking::pointer::PointerInteraction;
+use bevy::prelude::*;
+
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::picking::PickingSet;
+use crate::player::Player;
+use crate::state::GameState;
+
+pub struct InteractPlugin;
+
+impl Plugin for InteractPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InteractionTarget>()
+            .add_systems(
+                Update,
+                (
+                    update_interaction_target,
+                    handle_interaction_input,
+                    update_proximity,
+                    update_hold_progress_bar,
+                )
+                    .chain()
+                    .after(PickingSet)
+                    .run_if(in_state(GameState::Active)),
+            )
+            .add_systems(OnEnter(GameState::Active), setup_hold_progress_bar)
+            .add_systems(OnExit(GameState::Active), cleanup_hold_progress_bar);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InteractionKind {
+    Press,
+    /// Must be held for `seconds` to interact.
+    Hold {
+        seconds: f32,
+    },
+    Proximity,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Interactable {
+    pub kind: InteractionKind,
+    /// Furthest distance the player can interact from. For pointed interactions, this is measured
+    /// from the camera to the point being looked at; for proximity, between the player and entity.
+    pub range: f32,
+}
+
+impl Interactable {
+    /// Default range for interactions, in world units.
+    pub const DEFAULT_RANGE: f32 = 3.0;
+
+    pub fn press() -> Self {
+        Self {
+            kind: InteractionKind::Press,
+            range: Self::DEFAULT_RANGE,
+        }
+    }
+
+    pub fn hold(seconds: f32) -> Self {
+        Self {
+            kind: InteractionKind::Hold {
+                seconds: seconds.max(0.0),
+            },
+            range: Self::DEFAULT_RANGE,
+        }
+    }
+
+    pub fn proximity(range: f32) -> Self {
+        Self {
+            kind: InteractionKind::Proximity,
+            range,
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+}
+
+/// Triggered when a press interaction happens, or a hold interaction completes.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Interacted {
+    pub entity: Entity,
+}
+
+/// Triggered when the player starts holding a hold interaction.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct HoldStarted {
+    pub entity: Entity,
+}
+
+/// Triggered when a hold interaction is let go of, or looked away from, before completing.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct HoldCancelled {
+    pub entity: Entity,
+}
+
+/// Triggered when the player comes within range of a proximity interaction.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct ProximityEntered {
+    pub entity: Entity,
+}
+
+/// Triggered when the player leaves the range of a proximity interaction.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct ProximityExited {
+    pub entity: Entity,
+}
+
+/// Marks proximity interactables the player is currently within range of.
+#[derive(Component, Debug, Default)]
+pub struct InProximity;
+
+/// The pointed interactable in range, and progress on the current hold interaction.
+#[derive(Resource, Debug, Default)]
+pub struct InteractionTarget {
+    pub entity: Option<Entity>,
+    hold: Option<Hold>,
+}
+
+impl InteractionTarget {
+    /// Progress on the current hold interaction, from 0 to 1.
+    pub fn hold_progress(&self) -> Option<f32> {
+        self.hold.as_ref().map(|hold| hold.progress)
+    }
+}
+
+#[derive(Debug)]
+struct Hold {
+    entity: Entity,
+    progress: f32,
+    /// Completed holds wait for the input to be released before starting again.
+    completed: bool,
+}
+
+#[derive(Component)]
+struct HoldProgressBar;
+
+#[derive(Component)]
+struct HoldProgressFill;
+
+fn update_interaction_target(
+    pointers: Query<&PointerInteraction>,
+    parents: Query<&ChildOf>,
+    interactables: Query<&Interactable>,
+    mut target: ResMut<InteractionTarget>,
+) {
+    target.entity = pointers
+        .iter()
+        .find_map(|interaction| interaction.get_nearest_hit())
+        .and_then(|(hit_entity, hit)| {
+            std::iter::once(*hit_entity)
+                .chain(parents.iter_ancestors(*hit_entity))
+                .find_map(|entity| interactables.get(entity).ok().map(|i| (entity, i)))
+                .filter(|(_, interactable)| {
+                    interactable.kind != InteractionKind::Proximity
+                        && hit.depth <= interactable.range
+                })
+                .map(|(entity, _)| entity)
+        });
+}
+
+fn handle_interaction_input(
+    mut commands: Commands,
+    input: ControlsInput,
+    controls: Res<ControlsConfig>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
+    interactables: Query<&Interactable>,
+    mut target: ResMut<InteractionTarget>,
+) {
+    let pressed = input.pressed(&controls.interact) || mouse_buttons.pressed(MouseButton::Left);
+    let just_pressed =
+        input.just_pressed(&controls.interact) || mouse_buttons.just_pressed(MouseButton::Left);
+    let kind = target
+        .entity
+        .and_then(|entity| interactables.get(entity).ok())
+        .map(|interactable| interactable.kind);
+
+    // Letting go or looking away ends the current hold
+    if let Some(hold) = target.hold.take() {
+        if pressed && target.entity == Some(hold.entity) {
+            target.hold = Some(hold);
+        } else if !hold.completed {
+            commands.trigger(HoldCancelled {
+                entity: hold.entity,
+            });
+        }
+    }
+
+    let Some(entity) = target.entity else {
+        return;
+    };
+    match kind {
+        Some(InteractionKind::Press) if just_pressed => {
+            commands.trigger(Interacted { entity });
+        }
+        Some(InteractionKind::Hold { seconds }) if pressed => {
+            if target.hold.is_none() {
+                if !just_pressed {
+                    // Only a fresh press starts a hold, not sweeping across while held
+                    return;
+                }
+                commands.trigger(HoldStarted { entity });
+                target.hold = Some(Hold {
+                    entity,
+                    progress: 0.0,
+                    completed: false,
+                });
+            }
+            if let Some(hold) = target.hold.as_mut().filter(|hold| !hold.completed) {
+                hold.progress = if seconds > 0.0 {
+                    (hold.progress + time.delta_secs() / seconds).min(1.0)
+                } else {
+                    1.0
+                };
+                if hold.progress >= 1.0 {
+                    hold.completed = true;
+                    commands.trigger(Interacted { entity });
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn update_proximity(
+    mut commands: Commands,
+    player: Single<&GlobalTransform, With<Player>>,
+    interactables: Query<(Entity, &Interactable, &GlobalTransform, Has<InProximity>)>,
+) {
+    let player_position = player.translation();
+    for (entity, interactable, transform, was_near) in &interactables {
+        if interactable.kind != InteractionKind::Proximity {
+            continue;
+        }
+        let near = transform.translation().distance(player_position) <= interactable.range;
+        if near && !was_near {
+            commands.entity(entity).insert(InProximity);
+            commands.trigger(ProximityEntered { entity });
+        } else if !near && was_near {
+            commands.entity(entity).remove::<InProximity>();
+            commands.trigger(ProximityExited { entity });
+        }
+    }
+}
+
+fn setup_hold_progress_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            HoldProgressBar,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Percent(40.0),
+                left: Val::Percent(45.0),
+                width: Val::Percent(10.0),
+                height: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Visibility::Hidden,
+        ))
+        .with_child((
+            HoldProgressFill,
+            Node {
+                width: Val::Percent(0.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::WHITE),
+        ));
+}
+
+fn update_hold_progress_bar(
+    target: Res<InteractionTarget>,
+    mut bar: Single<&mut Visibility, With<HoldProgressBar>>,
+    mut fill: Single<&mut Node, With<HoldProgressFill>>,
+) {
+    let progress = target.hold_progress();
+    **bar = if progress.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    fill.width = Val::Percent(progress.unwrap_or(0.0) * 100.0);
+}
+
+fn cleanup_hold_progress_bar(
+    mut commands: Commands,
+    query: Query<Entity, With<HoldProgressBar>>,
+    mut target: ResMut<InteractionTarget>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+    // Pausing mid-hold cancels it, rather than leaving it to resume
+    if let Some(hold) = target.hold.take().filter(|hold| !hold.completed) {
+        commands.trigger(HoldCancelled {
+            entity: hold.entity,
+        });
+    }
+    target.entity = None;
+}
@@ -0,0 +1,128 @@
+//! Items the player has picked up, counted by name.
+//!
+//! One item at a time can be held in hand, for tools like an extinguisher to check for before
+//! working with the use item binding. The first item picked up is held, and the switch item
+//! binding (Q, or clicking the right stick, by default) moves on to the next.
+//!
+//! The [`Inventory`] resource is serialisable, so scenes can keep it between sessions with
+//! [`SaveGame::with_resource`](crate::save::SaveGame::with_resource).
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::kiosk::KioskReset;
+use crate::state::PausableSystems;
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Inventory>()
+            .add_systems(Update, switch_held_item.in_set(PausableSystems))
+            .add_observer(empty_for_next_visitor);
+    }
+}
+
+/// How many of each item the player is carrying.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    items: BTreeMap<String, u32>,
+    #[serde(default)]
+    held: Option<String>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: impl Into<String>, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let item = item.into();
+        if self.held.is_none() {
+            self.held = Some(item.clone());
+        }
+        let held = self.items.entry(item).or_default();
+        *held = held.saturating_add(count);
+    }
+
+    /// Takes `count` of `item`, if there are that many, returning whether it did.
+    pub fn remove(&mut self, item: &str, count: u32) -> bool {
+        let Some(held) = self.items.get_mut(item) else {
+            return count == 0;
+        };
+        if *held < count {
+            return false;
+        }
+        *held -= count;
+        if *held == 0 {
+            self.items.remove(item);
+            if self.is_holding(item) {
+                self.held = None;
+            }
+        }
+        true
+    }
+
+    pub fn count(&self, item: &str) -> u32 {
+        self.items.get(item).copied().unwrap_or(0)
+    }
+
+    pub fn contains(&self, item: &str) -> bool {
+        self.count(item) > 0
+    }
+
+    /// Items held and how many of each, in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.items
+            .iter()
+            .map(|(item, count)| (item.as_str(), *count))
+    }
+
+    /// The item in hand, if any.
+    pub fn held(&self) -> Option<&str> {
+        self.held.as_deref()
+    }
+
+    pub fn is_holding(&self, item: &str) -> bool {
+        self.held.as_deref() == Some(item)
+    }
+
+    /// Takes `item` in hand, if it's carried, returning whether it did.
+    pub fn hold(&mut self, item: &str) -> bool {
+        let carried = self.contains(item);
+        if carried {
+            self.held = Some(item.to_string());
+        }
+        carried
+    }
+
+    /// Puts the held item away for the next one carried, in name order, wrapping round.
+    pub fn hold_next(&mut self) {
+        let next = match &self.held {
+            Some(held) => self
+                .items
+                .range::<String, _>((std::ops::Bound::Excluded(held), std::ops::Bound::Unbounded))
+                .next()
+                .or_else(|| self.items.iter().next()),
+            None => self.items.iter().next(),
+        };
+        self.held = next.map(|(item, _)| item.clone());
+    }
+}
+
+fn switch_held_item(
+    config: Res<ControlsConfig>,
+    input: ControlsInput,
+    mut inventory: ResMut<Inventory>,
+) {
+    if input.just_pressed(&config.switch_item) {
+        inventory.hold_next();
+    }
+}
+
+/// Each visitor to an unattended kiosk starts with nothing.
+fn empty_for_next_visitor(_reset: On<KioskReset>, mut inventory: ResMut<Inventory>) {
+    *inventory = Inventory::default();
+}
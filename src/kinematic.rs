@@ -0,0 +1,317 @@
+//! Kinematic alternative to the default dynamic player body.
+//!
+//! The dynamic body floats on a spring above the ground and is pushed around by the physics
+//! solver, which can jitter on thin colliders such as the museum's floors. A
+//! [`KinematicController`] instead sweeps the player's collider through the scene each physics
+//! step, sliding along whatever it hits, and hovers [`KinematicController::step_offset`] above the
+//! ground so it walks straight over anything lower. Select it with [`PlayerPlugin::kinematic`].
+//!
+//! It stands against its [`LocalGravity`], turning to stay upright as gravity changes direction,
+//! so it can walk around a [`GravitySource`](crate::gravity::GravitySource).
+//!
+//! [`PlayerPlugin::kinematic`]: crate::player::PlayerPlugin::kinematic
+
+#![allow(clippy::useless_conversion)]
+use avian3d::math::{AdjustPrecision, Quaternion};
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::firstsight::{JUMP_HEIGHT, MovementDisabled, PlayerStance};
+use crate::gravity::{LocalGravity, update_local_gravity};
+
+pub(crate) struct KinematicControllerPlugin;
+
+impl Plugin for KinematicControllerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            move_kinematic_controllers.after(update_local_gravity),
+        );
+    }
+}
+
+/// Gap kept between the collider and anything it slides along, so sweeps don't start touching.
+const SKIN: f32 = 0.02;
+/// Surfaces slid along per physics step, enough to settle into a corner.
+const MAX_SLIDES: usize = 4;
+/// Rate the controller turns to stand upright when gravity changes direction, higher is snappier.
+const UPRIGHT_RATE: f32 = 6.0;
+
+/// Moves the player by sweeping its collider rather than simulating it as a dynamic body.
+#[derive(Component, Debug, Clone)]
+pub struct KinematicController {
+    /// Height the collider hovers above the ground, and so the tallest ledge that can be walked
+    /// straight onto.
+    pub step_offset: f32,
+    /// Steepest slope that can be walked up, in radians. Anything steeper is slid down like a wall.
+    pub max_slope: f32,
+    /// How far below the hover height ground is still stuck to, so the controller follows slopes
+    /// and stairs down rather than launching off them.
+    pub snap_distance: f32,
+    /// How long after walking off a ledge a jump is still allowed, in seconds.
+    pub coyote_time: f32,
+    desired_motion: Vec3,
+    jump: bool,
+    /// Flying or swimming, so `desired_motion` is followed in 3D without gravity or ground.
+    free: bool,
+    vertical_speed: f32,
+    grounded: bool,
+    air_time: f32,
+    /// The way the controller is standing, which turns towards the opposite of gravity.
+    up: Vec3,
+}
+
+impl Default for KinematicController {
+    fn default() -> Self {
+        Self {
+            // Hovers at the same height as the dynamic body, so the camera sits at the same height
+            step_offset: 0.5,
+            max_slope: 45f32.to_radians(),
+            snap_distance: 0.3,
+            coyote_time: 0.12,
+            desired_motion: Vec3::ZERO,
+            jump: false,
+            free: false,
+            vertical_speed: 0.0,
+            grounded: false,
+            air_time: 0.0,
+            up: Vec3::Y,
+        }
+    }
+}
+
+impl KinematicController {
+    pub fn with_step_offset(mut self, step_offset: f32) -> Self {
+        self.step_offset = step_offset.max(0.0);
+        self
+    }
+
+    pub fn with_max_slope(mut self, max_slope: f32) -> Self {
+        self.max_slope = max_slope.clamp(0.0, std::f32::consts::FRAC_PI_2);
+        self
+    }
+
+    pub fn with_snap_distance(mut self, snap_distance: f32) -> Self {
+        self.snap_distance = snap_distance.max(0.0);
+        self
+    }
+
+    pub fn with_coyote_time(mut self, coyote_time: f32) -> Self {
+        self.coyote_time = coyote_time.max(0.0);
+        self
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+
+    /// The way the controller is standing, which is `Y` unless gravity has turned it.
+    pub fn up(&self) -> Dir3 {
+        Dir3::new(self.up).unwrap_or(Dir3::Y)
+    }
+
+    /// Sets the velocity to move at along the ground, and whether to jump, for the next physics
+    /// steps.
+    pub(crate) fn drive(&mut self, desired_motion: Vec3, jump: bool) {
+        self.desired_motion = desired_motion.reject_from_normalized(self.up);
+        self.jump = jump;
+        self.free = false;
+    }
+
+    /// Sets the velocity to move at while flying or swimming, ignoring gravity and the ground.
+    pub(crate) fn drive_free(&mut self, velocity: Vec3) {
+        self.desired_motion = velocity;
+        self.jump = false;
+        self.free = true;
+    }
+
+    fn walkable(&self, normal: Vec3) -> bool {
+        normal.angle_between(self.up) <= self.max_slope
+    }
+}
+
+fn move_kinematic_controllers(
+    time: Res<Time>,
+    gravity: Res<Gravity>,
+    spatial_query: SpatialQuery,
+    mut controllers: Query<(
+        Entity,
+        &Position,
+        &mut Rotation,
+        &Collider,
+        &PlayerStance,
+        &mut KinematicController,
+        &mut LinearVelocity,
+        Option<&LocalGravity>,
+        Has<MovementDisabled>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    for (
+        entity,
+        position,
+        mut rotation,
+        collider,
+        stance,
+        mut controller,
+        mut velocity,
+        local_gravity,
+        disabled,
+    ) in &mut controllers
+    {
+        let gravity = local_gravity.map_or(gravity.0.f32(), |local| local.0);
+        let fall = -gravity.length();
+        // Turns upright gradually, keeping the last way up in zero gravity. Taken from the
+        // rotation each step, so moving the player upright, e.g. to respawn, resets it.
+        let current = (rotation.0.f32() * Vec3::Y).normalize_or(Vec3::Y);
+        let target = (-gravity).normalize_or(current);
+        let turn = Quat::IDENTITY.slerp(
+            Quat::from_rotation_arc(current, target),
+            1.0 - (-UPRIGHT_RATE * dt).exp(),
+        );
+        rotation.0 = (turn.adjust_precision() * rotation.0).normalize();
+        let up = (turn * current).normalize();
+        controller.up = up;
+        let orientation = rotation.0;
+
+        let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+        let (desired_motion, jump) = if disabled {
+            (Vec3::ZERO, false)
+        } else {
+            (controller.desired_motion, controller.jump)
+        };
+
+        if controller.free {
+            let start = position.0.f32();
+            let (end, _) = slide(
+                &spatial_query,
+                collider,
+                start,
+                orientation,
+                desired_motion * dt,
+                &filter,
+                &controller,
+            );
+            controller.grounded = false;
+            controller.vertical_speed = 0.0;
+            // Can't jump straight out of the water on landing
+            controller.air_time = f32::INFINITY;
+            velocity.0 = ((end - start) / dt).adjust_precision();
+            continue;
+        }
+
+        if controller.grounded {
+            controller.vertical_speed = 0.0;
+        } else {
+            controller.vertical_speed += fall * dt;
+        }
+        if jump && controller.air_time <= controller.coyote_time {
+            controller.vertical_speed = (2.0 * fall.abs() * JUMP_HEIGHT).sqrt();
+            controller.grounded = false;
+            // Used up until landing again, so coyote time can't give a second jump
+            controller.air_time = f32::INFINITY;
+        }
+
+        let start = position.0.f32();
+        let motion = (desired_motion + up * controller.vertical_speed) * dt;
+        let (mut end, hit_ceiling) = slide(
+            &spatial_query,
+            collider,
+            start,
+            orientation,
+            motion,
+            &filter,
+            &controller,
+        );
+        if hit_ceiling {
+            controller.vertical_speed = controller.vertical_speed.min(0.0);
+        }
+
+        // Probe for ground with a sphere the width of the capsule, from its centre
+        let rest = stance.height() / 2.0 + controller.step_offset + SKIN;
+        let reach = if controller.grounded {
+            rest + controller.snap_distance
+        } else {
+            rest
+        };
+        let ground = (controller.vertical_speed <= 0.0)
+            .then(|| {
+                spatial_query.cast_shape(
+                    &Collider::sphere((stance.radius() - SKIN).into()),
+                    end.into(),
+                    Quat::IDENTITY.into(),
+                    Dir3::new(-up).unwrap_or(Dir3::NEG_Y),
+                    &ShapeCastConfig {
+                        ignore_origin_penetration: true,
+                        ..ShapeCastConfig::from_max_distance(reach.into())
+                    },
+                    &filter,
+                )
+            })
+            .flatten()
+            .filter(|hit| controller.walkable(hit.normal1.f32()));
+        if let Some(hit) = ground {
+            end += up * (rest - hit.distance.f32());
+            controller.grounded = true;
+            controller.air_time = 0.0;
+            controller.vertical_speed = 0.0;
+        } else {
+            controller.grounded = false;
+            controller.air_time += dt;
+        }
+
+        // Let the physics step carry out the move, so anything reading velocity sees it
+        velocity.0 = ((end - start) / dt).adjust_precision();
+    }
+}
+
+/// Sweeps `collider` from `position` along `motion`, sliding along anything in the way. Returns
+/// where it ends up, and whether it bumped into a ceiling on the way.
+fn slide(
+    spatial_query: &SpatialQuery,
+    collider: &Collider,
+    mut position: Vec3,
+    orientation: Quaternion,
+    mut motion: Vec3,
+    filter: &SpatialQueryFilter,
+    controller: &KinematicController,
+) -> (Vec3, bool) {
+    let mut hit_ceiling = false;
+    for _ in 0..MAX_SLIDES {
+        let Ok((direction, distance)) = Dir3::new_and_length(motion) else {
+            break;
+        };
+        let Some(hit) = spatial_query.cast_shape(
+            collider,
+            position.into(),
+            orientation,
+            direction,
+            &ShapeCastConfig {
+                ignore_origin_penetration: true,
+                ..ShapeCastConfig::from_max_distance((distance + SKIN).into())
+            },
+            filter,
+        ) else {
+            position += motion;
+            break;
+        };
+        let travelled = (hit.distance.f32() - SKIN).clamp(0.0, distance);
+        position += direction * travelled;
+
+        let mut normal = hit.normal1.f32();
+        let rise = normal.dot(controller.up);
+        hit_ceiling |= rise < -0.5;
+        // Treat steep slopes as walls while on the ground, rather than sliding up them
+        if controller.grounded && rise > 0.0 && !controller.walkable(normal) {
+            normal = normal
+                .reject_from_normalized(controller.up)
+                .normalize_or(normal);
+        }
+        let remaining = direction * (distance - travelled);
+        motion = remaining - normal * remaining.dot(normal).min(0.0);
+    }
+    (position, hit_ceiling)
+}
@@ -0,0 +1,171 @@
+//! Kiosk mode for unattended installations.
+//!
+//! Inserting a [`KioskMode`] resource locks the window into borderless fullscreen, disables the
+//! pause, fullscreen and developer bindings, and resets the scene after a period of inactivity.
+//! A reset puts the player and camera back where they started and clears the dialogue log and
+//! captions, while the [`Inventory`] is emptied, anything [`Carryable`] is put back, opened
+//! [`Chest`]s are closed and the [`Curation`] returns to its first hang. Scenes observe
+//! [`KioskReset`] to restore anything of their own.
+//!
+//! Scenes can also register one-shot systems to cycle between on each reset. Each should spawn its
+//! scene under a root tagged [`KioskScene`], which is despawned before the next one is spawned.
+//!
+//! [`Inventory`]: crate::inventory::Inventory
+//! [`Carryable`]: crate::carry::Carryable
+//! [`Chest`]: crate::chest::Chest
+//! [`Curation`]: crate::curation::Curation
+
+use bevy::ecs::system::SystemId;
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PrimaryWindow, WindowMode};
+
+use crate::captions::{Captions, SoundCaptions};
+use crate::controls::InputActivity;
+use crate::dialogue_log::DialogueLog;
+use crate::firstsight::PlayerCamera;
+use crate::player::Player;
+use crate::player_events::RespawnPlayer;
+use crate::state::GameState;
+use crate::thirdperson::ThirdPersonCamera;
+
+pub struct KioskPlugin;
+
+impl Plugin for KioskPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostStartup, record_initial_state)
+            .add_systems(
+                Update,
+                (
+                    lock_down_window.run_if(resource_added::<KioskMode>),
+                    reset_when_inactive.run_if(resource_exists::<KioskMode>),
+                ),
+            )
+            .add_observer(reset_player);
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct KioskMode {
+    /// Seconds without input before the scene is reset.
+    pub reset_after: f32,
+    /// One-shot systems run in turn on each reset, to cycle between scenes, each spawning its scene
+    /// under a [`KioskScene`] root. The first is assumed to be the scene shown at startup.
+    pub scenes: Vec<SystemId>,
+    idle_seconds: f32,
+    current_scene: usize,
+}
+
+impl KioskMode {
+    pub fn new(reset_after: f32) -> Self {
+        Self {
+            reset_after,
+            scenes: Vec::new(),
+            idle_seconds: 0.0,
+            current_scene: 0,
+        }
+    }
+
+    pub fn with_scenes(mut self, scenes: impl IntoIterator<Item = SystemId>) -> Self {
+        self.scenes = scenes.into_iter().collect();
+        self
+    }
+}
+
+/// The root of a scene spawned by one of [`KioskMode::scenes`], despawned along with everything
+/// under it when the kiosk moves on to the next.
+#[derive(Component, Debug, Default)]
+pub struct KioskScene;
+
+/// Triggered when the kiosk resets after inactivity.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct KioskReset {
+    /// Index into [`KioskMode::scenes`] of the scene being switched to, if any are registered.
+    pub scene: Option<usize>,
+}
+
+/// Run condition that is false while kiosk mode is on, for bindings visitors shouldn't have.
+pub fn not_in_kiosk_mode(kiosk: Option<Res<KioskMode>>) -> bool {
+    kiosk.is_none()
+}
+
+/// Where the player and camera started, so a reset can put them back.
+#[derive(Resource)]
+struct InitialState {
+    player: Transform,
+    camera: PlayerCamera,
+}
+
+fn record_initial_state(
+    mut commands: Commands,
+    player: Single<&Transform, With<Player>>,
+    camera: Single<&PlayerCamera>,
+) {
+    commands.insert_resource(InitialState {
+        player: **player,
+        camera: camera.clone(),
+    });
+}
+
+fn lock_down_window(mut window: Single<&mut Window, With<PrimaryWindow>>) {
+    window.mode = WindowMode::BorderlessFullscreen(MonitorSelection::Current);
+    window.enabled_buttons.close = false;
+    window.enabled_buttons.minimize = false;
+}
+
+fn reset_when_inactive(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: InputActivity,
+    mut kiosk: ResMut<KioskMode>,
+    scenes: Query<Entity, With<KioskScene>>,
+) {
+    if input.any() {
+        kiosk.idle_seconds = 0.0;
+        return;
+    }
+    kiosk.idle_seconds += time.delta_secs();
+    if kiosk.idle_seconds < kiosk.reset_after {
+        return;
+    }
+    kiosk.idle_seconds = 0.0;
+
+    let scene = if kiosk.scenes.is_empty() {
+        None
+    } else {
+        for scene in &scenes {
+            commands.entity(scene).despawn();
+        }
+        kiosk.current_scene = kiosk.current_scene.saturating_add(1) % kiosk.scenes.len();
+        commands.run_system(kiosk.scenes[kiosk.current_scene]);
+        Some(kiosk.current_scene)
+    };
+    info!("Resetting kiosk after inactivity");
+    commands.trigger(KioskReset { scene });
+}
+
+fn reset_player(
+    _reset: On<KioskReset>,
+    mut commands: Commands,
+    initial: Option<Res<InitialState>>,
+    camera: Single<(Entity, &mut PlayerCamera)>,
+    mut dialogue_log: ResMut<DialogueLog>,
+    mut captions: ResMut<Captions>,
+    mut sound_captions: ResMut<SoundCaptions>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(initial) = initial else {
+        return;
+    };
+    commands.trigger(RespawnPlayer {
+        transform: initial.player,
+    });
+
+    let (camera_entity, mut player_camera) = camera.into_inner();
+    *player_camera = initial.camera.clone();
+    commands.entity(camera_entity).remove::<ThirdPersonCamera>();
+
+    dialogue_log.clear();
+    captions.clear();
+    sound_captions.clear();
+    next_state.set(GameState::Active);
+}
@@ -1,39 +1,286 @@
 #![deny(unstable_features)]
 #![deny(unused_features)]
+use bevy::log::LogPlugin;
 use bevy::prelude::*;
 
-mod controls;
+pub mod activity;
+pub mod ambience;
+pub mod ambient_volume;
+pub mod annotations;
+pub mod architecture;
+pub mod attract;
+pub mod audio;
+pub mod audio_guide;
+pub mod avatar;
+pub mod biome;
+pub mod boids;
+pub mod captions;
+pub mod capture;
+pub mod carry;
+pub mod cascades;
+pub mod chest;
+pub mod close_up;
+pub mod color;
+pub mod controls;
+pub mod crash_report;
+pub mod cull;
+pub mod curation;
+pub mod debug_window;
 #[cfg(feature = "perfui")]
 mod diag;
+pub mod dialogue_log;
+pub mod environment;
+pub mod fire;
 mod firstsight;
+pub mod flow;
+pub mod footsteps;
+pub mod game_time;
+pub mod graphics;
+pub mod gravity;
+pub mod guestbook;
+pub mod habitat;
+pub mod heatmap;
+pub mod inspection;
 #[cfg(feature = "inspector")]
 mod inspector;
-mod physics;
+#[cfg(feature = "inspector")]
+mod inspector_widgets;
+pub mod interact;
+pub mod inventory;
+mod kinematic;
+pub mod kiosk;
+pub mod light_switch;
+pub mod lod;
+pub mod lsystem;
+#[cfg(feature = "inspector")]
+mod material_tweaks;
+pub mod mesh_collider;
+pub mod metaballs;
+pub mod mobile;
+pub mod motes;
+mod movement;
+pub mod name_path;
+pub mod nameplate;
+pub mod navigation;
+pub mod noise;
+pub mod npc;
+pub mod particles;
+mod pause_menu;
+#[cfg(feature = "perfui")]
+mod perf_graph;
+pub mod physics;
 pub mod picking;
+pub mod plaque;
 pub mod player;
-mod state;
-mod window;
+pub mod player_events;
+pub mod portal;
+pub mod power;
+pub mod prefab;
+pub mod preload;
+mod rng;
+pub mod rock;
+pub mod ruins;
+pub mod save;
+pub mod scene;
+#[cfg(feature = "schedule-debug")]
+mod schedule_dump;
+pub mod sculpture;
+pub mod sdf_terrain;
+pub mod sea_state;
+#[cfg(feature = "inspector")]
+mod selection;
+pub mod shadow_proxy;
+pub mod slideshow;
+pub mod sound_sculpture;
+pub mod state;
+pub mod swim;
+pub mod telemetry;
+pub mod terrain;
+pub mod thirdperson;
+pub mod underwater;
+pub mod view_mode;
+pub mod voice;
+pub mod window;
 mod wireframe;
 
+use crate::activity::ActivityPlugin;
+use crate::ambience::AmbiencePlugin;
+use crate::ambient_volume::AmbientVolumePlugin;
+use crate::annotations::AnnotationsPlugin;
+use crate::attract::AttractModePlugin;
+use crate::audio::{FootstepAudioPlugin, SpatialAudioPlugin};
+use crate::audio_guide::AudioGuidePlugin;
+use crate::biome::BiomePlugin;
+use crate::boids::BoidsPlugin;
+use crate::captions::CaptionsPlugin;
+use crate::capture::CapturePlugin;
+use crate::carry::CarryPlugin;
+use crate::cascades::CascadesPlugin;
+use crate::chest::ChestPlugin;
+use crate::close_up::CloseUpPlugin;
 use crate::controls::ControlsPlugin;
+use crate::crash_report::CrashReportPlugin;
+use crate::cull::CullPlugin;
+use crate::curation::CurationPlugin;
+use crate::debug_window::DebugWindowPlugin;
+use crate::dialogue_log::DialogueLogPlugin;
+use crate::environment::EnvironmentPlugin;
+use crate::fire::FirePlugin;
+use crate::flow::FlowPlugin;
+use crate::footsteps::FootstepPlugin;
+use crate::game_time::GameTimePlugin;
+use crate::graphics::GraphicsPlugin;
+use crate::gravity::GravityPlugin;
+use crate::guestbook::GuestbookPlugin;
+use crate::habitat::HabitatPlugin;
+use crate::heatmap::HeatmapPlugin;
+use crate::inspection::InspectionPlugin;
+use crate::interact::InteractPlugin;
+use crate::inventory::InventoryPlugin;
+use crate::kiosk::KioskPlugin;
+use crate::light_switch::LightSwitchPlugin;
+use crate::lod::LodPlugin;
+use crate::lsystem::LSystemPlugin;
+use crate::mesh_collider::MeshColliderPlugin;
+use crate::metaballs::MetaballsPlugin;
+use crate::mobile::MobilePlugin;
+use crate::motes::MotesPlugin;
+use crate::name_path::NamePathPlugin;
+use crate::nameplate::NameplatePlugin;
+use crate::navigation::NavigationPlugin;
+use crate::npc::NpcPlugin;
+use crate::particles::ParticlesPlugin;
+use crate::pause_menu::PauseMenuPlugin;
 use crate::physics::PhysicsPlugin;
 use crate::picking::PickingPlugin;
+use crate::plaque::PlaquePlugin;
 use crate::player::PlayerPlugin;
+use crate::portal::PortalPlugin;
+use crate::power::PowerPlugin;
+use crate::prefab::PrefabPlugin;
+use crate::preload::PreloadPlugin;
+use crate::rock::RockPlugin;
+use crate::save::SavePlugin;
+use crate::scene::ScenePlugin;
+use crate::sculpture::SculpturePlugin;
+use crate::sdf_terrain::SdfTerrainPlugin;
+use crate::sea_state::SeaStatePlugin;
+use crate::shadow_proxy::ShadowProxyPlugin;
+use crate::slideshow::SlideshowPlugin;
+use crate::sound_sculpture::SoundSculpturePlugin;
 use crate::state::{GameState, StatePlugin};
+use crate::swim::SwimPlugin;
+use crate::telemetry::TelemetryPlugin;
+use crate::terrain::TerrainPlugin;
+use crate::underwater::UnderwaterPlugin;
+use crate::view_mode::ViewModePlugin;
+use crate::voice::CreatureVoicePlugin;
 
-pub struct DioramaPlugin;
+#[derive(Default)]
+pub struct DioramaPlugin {
+    /// How the player is spawned, e.g. [`PlayerPlugin::kinematic`] for a kinematic body.
+    pub player: PlayerPlugin,
+    /// Picking and hints, e.g. [`PickingPlugin::with_world_labels`] to float hints over their
+    /// entities.
+    pub picking: PickingPlugin,
+    /// Physics settings, e.g. [`PhysicsPlugin::with_fixed_hz`] to step physics at another rate.
+    pub physics: PhysicsPlugin,
+}
 
 impl Plugin for DioramaPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()));
+        app.add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                .set(LogPlugin {
+                    custom_layer: crash_report::log_layer,
+                    ..default()
+                }),
+        );
         app.add_plugins(bevy_framepace::FramepacePlugin);
         app.init_state::<GameState>().add_plugins((
             crate::window::WindowPlugin,
-            PhysicsPlugin,
-            PlayerPlugin,
+            self.physics.clone(),
+            self.player.clone(),
             ControlsPlugin,
-            PickingPlugin,
+            self.picking.clone(),
             StatePlugin,
+            DialogueLogPlugin,
+            AmbiencePlugin,
+            CreatureVoicePlugin,
+            ShadowProxyPlugin,
+            AmbientVolumePlugin,
+            GraphicsPlugin,
+            CascadesPlugin,
+            MotesPlugin,
+            SavePlugin,
+        ));
+        app.add_plugins((
+            GameTimePlugin,
+            PreloadPlugin,
+            AttractModePlugin,
+            KioskPlugin,
+            TelemetryPlugin,
+            HeatmapPlugin,
+            DebugWindowPlugin,
+            NamePathPlugin,
+            ScenePlugin,
+            LSystemPlugin,
+            RockPlugin,
+            SdfTerrainPlugin,
+            InteractPlugin,
+            PauseMenuPlugin,
+            FlowPlugin,
+        ));
+        app.add_plugins((
+            FootstepPlugin,
+            FootstepAudioPlugin,
+            SpatialAudioPlugin,
+            SwimPlugin,
+            UnderwaterPlugin,
+            HabitatPlugin,
+            InventoryPlugin,
+            ChestPlugin,
+            AudioGuidePlugin,
+            CapturePlugin,
+            CurationPlugin,
+            EnvironmentPlugin,
+            PlaquePlugin,
+            SeaStatePlugin,
+            ActivityPlugin,
+        ));
+        app.add_plugins((
+            PortalPlugin,
+            BiomePlugin,
+            NpcPlugin,
+            LightSwitchPlugin,
+            BoidsPlugin,
+            PowerPlugin,
+            FirePlugin,
+            ViewModePlugin,
+            CaptionsPlugin,
+            InspectionPlugin,
+            CloseUpPlugin,
+            AnnotationsPlugin,
+            CarryPlugin,
+            GravityPlugin,
+        ));
+        app.add_plugins((
+            SculpturePlugin,
+            LodPlugin,
+            MetaballsPlugin,
+            CullPlugin,
+            MobilePlugin,
+            SoundSculpturePlugin,
+            ParticlesPlugin,
+            SlideshowPlugin,
+            GuestbookPlugin,
+            TerrainPlugin,
+            NameplatePlugin,
+            CrashReportPlugin,
+            NavigationPlugin,
+            PrefabPlugin,
+            MeshColliderPlugin,
         ));
         #[cfg(feature = "remote")]
         app.add_plugins((
@@ -46,8 +293,12 @@ impl Plugin for DioramaPlugin {
             physics::debug::PhysicsDebugPlugin,
             #[cfg(feature = "inspector")]
             inspector::InspectorPlugin,
+            #[cfg(feature = "inspector")]
+            material_tweaks::MaterialTweakPlugin,
             #[cfg(feature = "perfui")]
             diag::DiagPlugin,
+            #[cfg(feature = "schedule-debug")]
+            schedule_dump::ScheduleDumpPlugin,
         ));
     }
 }
@@ -0,0 +1,244 @@
+//! Wall switches that turn groups of lights on and off.
+//!
+//! Lights join a group by being tagged with a [`LightGroup`], or by name, listing them on a
+//! switch with [`LightSwitch::with_light`]. [`LightSwitch::spawn`] builds a switch plate with a
+//! rocker and an [`Interactable`]; interacting with it flips the rocker with a click and toggles
+//! every light in its group, triggering [`LightsToggled`] on the switch. Whether each group is on
+//! is kept in the [`LightGroups`] resource, so every switch for a group agrees, and scenes can
+//! keep it between runs with `SaveGame::with_resource::<LightGroups>()`. Groups are on until
+//! switched off. Lights wired into a power network also go out while their power is cut.
+
+use std::collections::BTreeMap;
+
+use avian3d::prelude::*;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::synthesise_click;
+use crate::interact::{Interactable, Interacted};
+use crate::picking::Hint;
+use crate::power::{Powered, PoweredBy};
+
+pub struct LightSwitchPlugin;
+
+impl Plugin for LightSwitchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LightGroups>()
+            .init_resource::<ClickSound>()
+            .add_systems(Update, (apply_light_groups, flip_rockers))
+            .add_observer(press_switch);
+    }
+}
+
+/// How far the rocker tips either way.
+const ROCKER_ANGLE: f32 = 0.3;
+
+/// Whether each group of lights is on, by group name.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct LightGroups(BTreeMap<String, bool>);
+
+impl LightGroups {
+    pub fn is_on(&self, group: &str) -> bool {
+        self.0.get(group).copied().unwrap_or(true)
+    }
+
+    pub fn set(&mut self, group: impl Into<String>, on: bool) {
+        self.0.insert(group.into(), on);
+    }
+
+    /// Switches `group` over, returning whether it's now on.
+    pub fn toggle(&mut self, group: &str) -> bool {
+        let on = !self.is_on(group);
+        self.set(group, on);
+        on
+    }
+}
+
+/// Puts a light in a group that [`LightSwitch`]es can turn on and off.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct LightGroup(pub String);
+
+impl LightGroup {
+    pub fn new(group: impl Into<String>) -> Self {
+        Self(group.into())
+    }
+}
+
+/// A wall switch for a group of lights, facing +Z.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct LightSwitch {
+    pub group: String,
+    /// Names of lights to put in the group, besides those tagged with its [`LightGroup`].
+    pub lights: Vec<String>,
+    /// Width and height of the plate.
+    pub size: Vec2,
+    rocker: Option<Entity>,
+}
+
+impl LightSwitch {
+    pub fn new(group: impl Into<String>) -> Self {
+        Self {
+            group: group.into(),
+            lights: Vec::new(),
+            size: Vec2::new(0.12, 0.18),
+            rocker: None,
+        }
+    }
+
+    pub fn with_light(mut self, name: impl Into<String>) -> Self {
+        self.lights.push(name.into());
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Spawns the switch with a plate and a rocker that tips up while the lights are on, as a
+    /// static collider that can be pressed.
+    pub fn spawn<P: Material, R: Material>(
+        mut self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        plate_material: Handle<P>,
+        rocker_material: Handle<R>,
+        transform: Transform,
+    ) -> Entity {
+        let depth = self.size.x * 0.15;
+        let rocker = commands
+            .spawn((
+                Name::new("Switch rocker"),
+                Mesh3d(meshes.add(Cuboid::new(self.size.x * 0.4, self.size.y * 0.45, depth))),
+                MeshMaterial3d(rocker_material),
+                Transform::from_xyz(0.0, 0.0, depth),
+            ))
+            .id();
+        self.rocker = Some(rocker);
+        let hint = format!("Press to switch the {} lights", self.group.to_lowercase());
+
+        commands
+            .spawn((
+                Name::new(format!("{} Light Switch", self.group)),
+                transform,
+                Mesh3d(meshes.add(Cuboid::new(self.size.x, self.size.y, depth))),
+                MeshMaterial3d(plate_material),
+                RigidBody::Static,
+                Collider::cuboid(self.size.x.into(), self.size.y.into(), (depth * 2.0).into()),
+                Interactable::press(),
+                Hint::new(hint),
+                self,
+            ))
+            .add_child(rocker)
+            .id()
+    }
+}
+
+/// Triggered on a [`LightSwitch`] when it's pressed, with whether its group is now on.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct LightsToggled {
+    pub entity: Entity,
+    pub group: String,
+    pub on: bool,
+}
+
+#[derive(Resource)]
+struct ClickSound(Handle<AudioSource>);
+
+impl FromWorld for ClickSound {
+    fn from_world(world: &mut World) -> Self {
+        Self(
+            world
+                .resource_mut::<Assets<AudioSource>>()
+                .add(synthesise_click()),
+        )
+    }
+}
+
+fn press_switch(
+    interacted: On<Interacted>,
+    mut commands: Commands,
+    switches: Query<(&LightSwitch, &GlobalTransform)>,
+    mut groups: ResMut<LightGroups>,
+    click: Res<ClickSound>,
+) {
+    let entity = interacted.entity;
+    let Ok((switch, transform)) = switches.get(entity) else {
+        return;
+    };
+    let on = groups.toggle(&switch.group);
+    commands.spawn((
+        Name::new("Switch click"),
+        Transform::from_translation(transform.translation()),
+        AudioPlayer::new(click.0.clone()),
+        PlaybackSettings::DESPAWN
+            .with_spatial(true)
+            .with_volume(Volume::Linear(0.6)),
+    ));
+    commands.trigger(LightsToggled {
+        entity,
+        group: switch.group.clone(),
+        on,
+    });
+}
+
+fn apply_light_groups(
+    groups: Res<LightGroups>,
+    switches: Query<&LightSwitch>,
+    mut lights: Query<
+        (
+            &mut Visibility,
+            Option<&LightGroup>,
+            Option<&Name>,
+            Has<PoweredBy>,
+            Has<Powered>,
+        ),
+        Or<(With<PointLight>, With<SpotLight>, With<DirectionalLight>)>,
+    >,
+) {
+    for (mut visibility, tag, name, wired, powered) in &mut lights {
+        let by_name = name.and_then(|name| {
+            switches
+                .iter()
+                .find(|switch| switch.lights.iter().any(|light| light == name.as_str()))
+                .map(|switch| switch.group.as_str())
+        });
+        let group = tag.map(|tag| tag.0.as_str()).or(by_name);
+        if group.is_none() && !wired {
+            continue;
+        }
+        let switched_on = group.is_none_or(|group| groups.is_on(group));
+        let shown = if switched_on && (powered || !wired) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(shown);
+    }
+}
+
+fn flip_rockers(
+    groups: Res<LightGroups>,
+    switches: Query<&LightSwitch>,
+    mut rockers: Query<&mut Transform>,
+) {
+    for switch in &switches {
+        let Some(mut rocker) = switch
+            .rocker
+            .and_then(|rocker| rockers.get_mut(rocker).ok())
+        else {
+            continue;
+        };
+        let angle = if groups.is_on(&switch.group) {
+            -ROCKER_ANGLE
+        } else {
+            ROCKER_ANGLE
+        };
+        let rotation = Quat::from_rotation_x(angle);
+        if rocker.rotation != rotation {
+            rocker.rotation = rotation;
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Distance-based levels of detail that swap an entity's mesh, and optionally its material.
+//!
+//! An entity with [`Lod`] renders the level for how far it is from the player camera, swapping its
+//! [`Mesh3d`] as the camera moves. Each level takes over from a distance threshold, and switching
+//! only happens once the camera is a little past a threshold, by [`Lod::hysteresis`], so hovering
+//! around one doesn't flicker between levels. Unlike [`VisibilityRange`] children, only one mesh
+//! is ever spawned, which suits colliders, picking and anything else attached to the entity.
+//!
+//! [`VisibilityRange`]: bevy::camera::visibility::VisibilityRange
+
+use bevy::prelude::*;
+
+use crate::firstsight::PlayerCamera;
+
+pub struct LodPlugin;
+
+impl Plugin for LodPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Lod>()
+            .add_observer(show_first_level)
+            .add_systems(Update, swap_lods);
+    }
+}
+
+/// One level of detail, shown from `distance` units from the camera until the next level's.
+#[derive(Debug, Clone, Reflect)]
+pub struct LodLevel {
+    pub distance: f32,
+    pub mesh: Handle<Mesh>,
+    /// Material to swap in alongside the mesh, or `None` for the one the entity had to begin with.
+    pub material: Option<Handle<StandardMaterial>>,
+}
+
+/// Meshes to swap between by distance from the camera, from most to least detailed.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+pub struct Lod {
+    levels: Vec<LodLevel>,
+    /// How far past a level's threshold the camera has to be to switch, as a fraction of it.
+    pub hysteresis: f32,
+    current: usize,
+    /// Index of the level [`Lod::with_material`] applies to.
+    last_added: usize,
+    /// The entity's own material, put back for levels without one.
+    base_material: Option<Handle<StandardMaterial>>,
+}
+
+impl Lod {
+    /// The most detailed level, shown up close.
+    pub fn new(mesh: Handle<Mesh>) -> Self {
+        Self {
+            levels: vec![LodLevel {
+                distance: 0.0,
+                mesh,
+                material: None,
+            }],
+            hysteresis: 0.1,
+            current: 0,
+            last_added: 0,
+            base_material: None,
+        }
+    }
+
+    /// Adds a level shown from `distance` on, keeping levels ordered by distance.
+    pub fn with_level(mut self, distance: f32, mesh: Handle<Mesh>) -> Self {
+        let distance = distance.max(0.0);
+        let index = self
+            .levels
+            .partition_point(|level| level.distance <= distance);
+        self.levels.insert(
+            index,
+            LodLevel {
+                distance,
+                mesh,
+                material: None,
+            },
+        );
+        self.last_added = index;
+        self
+    }
+
+    /// Swaps in `material` with the level most recently added.
+    pub fn with_material(mut self, material: Handle<StandardMaterial>) -> Self {
+        if let Some(level) = self.levels.get_mut(self.last_added) {
+            level.material = Some(material);
+        }
+        self
+    }
+
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis.max(0.0);
+        self
+    }
+
+    pub fn levels(&self) -> &[LodLevel] {
+        &self.levels
+    }
+
+    /// Index of the level showing.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// The level to show at `distance`, only leaving the current one once well past its bounds.
+    fn level_at(&self, distance: f32) -> usize {
+        let mut level = self.current.min(self.levels.len().saturating_sub(1));
+        while let Some(next) = self.levels.get(level + 1) {
+            if distance <= next.distance * (1.0 + self.hysteresis) {
+                break;
+            }
+            level += 1;
+        }
+        while level > 0 && distance < self.levels[level].distance * (1.0 - self.hysteresis) {
+            level -= 1;
+        }
+        level
+    }
+}
+
+fn show_level(commands: &mut Commands, entity: Entity, lod: &Lod) {
+    let Some(level) = lod.levels.get(lod.current) else {
+        return;
+    };
+    let mut entity = commands.entity(entity);
+    entity.insert(Mesh3d(level.mesh.clone()));
+    if let Some(material) = level.material.as_ref().or(lod.base_material.as_ref()) {
+        entity.insert(MeshMaterial3d(material.clone()));
+    }
+}
+
+/// Shows the level an entity starts on straight away, so it isn't missing a mesh for a frame, and
+/// keeps hold of the entity's own material for levels without one.
+fn show_first_level(
+    add: On<Add, Lod>,
+    mut commands: Commands,
+    mut lods: Query<(&mut Lod, Option<&MeshMaterial3d<StandardMaterial>>)>,
+) {
+    let Ok((mut lod, material)) = lods.get_mut(add.entity) else {
+        return;
+    };
+    lod.base_material = material.map(|material| material.0.clone());
+    show_level(&mut commands, add.entity, &lod);
+}
+
+fn swap_lods(
+    mut commands: Commands,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    mut lods: Query<(Entity, &mut Lod, &GlobalTransform)>,
+) {
+    let camera = camera.translation();
+    for (entity, mut lod, transform) in &mut lods {
+        let level = lod.level_at(camera.distance(transform.translation()));
+        if level == lod.current {
+            continue;
+        }
+        lod.current = level;
+        show_level(&mut commands, entity, &lod);
+    }
+}
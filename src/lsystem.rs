@@ -0,0 +1,459 @@
+//! L-system generator for branching organic meshes, such as coral and alien trees.
+//!
+//! An [`LSystem`] rewrites its axiom with seeded, optionally weighted rules, then a 3D turtle
+//! draws the result as tapered tubes. Symbols understood by the turtle:
+//!
+//! | Symbol    | Meaning                                                     |
+//! | --------- | ----------------------------------------------------------- |
+//! | `F`       | Grow a segment forward                                      |
+//! | `f`       | Move forward without growing                                |
+//! | `+` / `-` | Turn around the local Z axis                                |
+//! | `&` / `^` | Pitch around the local X axis                               |
+//! | `\` / `/` | Roll around the growth direction                            |
+//! | `[` / `]` | Start and end a branch, which is shorter and thinner        |
+//! | `!`       | Thin the current branch                                     |
+//!
+//! Any other symbol is only used for rewriting. Meshes have UVs whose V runs from 0 at the root to
+//! 1 at the furthest tip, for base-to-tip colour gradients, and can be built at several levels of
+//! detail. [`LSystemMeshes`] caches built meshes, so identical systems are only generated once.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::VisibilityRange;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::rng::SplitMix64;
+
+pub struct LSystemPlugin;
+
+impl Plugin for LSystemPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LSystemMeshes>();
+    }
+}
+
+/// Expansion stops growing past this many symbols, to keep runaway rules from hanging startup.
+const MAX_SYMBOLS: usize = 100_000;
+/// Sides of each tube at the highest level of detail. Each further level halves this, down to 3.
+const MAX_SIDES: usize = 8;
+
+/// A rewriting rule, replacing `symbol` with `replacement`. When several rules match a symbol, one
+/// is picked at random in proportion to its weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LRule {
+    pub symbol: char,
+    pub replacement: String,
+    pub weight: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LSystem {
+    pub axiom: String,
+    pub rules: Vec<LRule>,
+    pub iterations: u32,
+    /// Turn angle in radians.
+    pub angle: f32,
+    /// Random variation added to every turn, in radians.
+    pub angle_jitter: f32,
+    pub segment_length: f32,
+    pub radius: f32,
+    /// Radius multiplier along each segment, so branches taper towards their tips.
+    pub taper: f32,
+    /// Length and radius multiplier for each new branch.
+    pub branch_scale: f32,
+    pub seed: u64,
+}
+
+impl LSystem {
+    pub fn new(axiom: impl Into<String>) -> Self {
+        Self {
+            axiom: axiom.into(),
+            rules: Vec::new(),
+            iterations: 3,
+            angle: 25f32.to_radians(),
+            angle_jitter: 0.0,
+            segment_length: 0.5,
+            radius: 0.08,
+            taper: 0.9,
+            branch_scale: 0.8,
+            seed: 0,
+        }
+    }
+
+    pub fn with_rule(self, symbol: char, replacement: impl Into<String>) -> Self {
+        self.with_weighted_rule(symbol, replacement, 1.0)
+    }
+
+    pub fn with_weighted_rule(
+        mut self,
+        symbol: char,
+        replacement: impl Into<String>,
+        weight: f32,
+    ) -> Self {
+        self.rules.push(LRule {
+            symbol,
+            replacement: replacement.into(),
+            weight: weight.max(0.0),
+        });
+        self
+    }
+
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_angle(mut self, angle: f32, jitter: f32) -> Self {
+        self.angle = angle;
+        self.angle_jitter = jitter;
+        self
+    }
+
+    pub fn with_segment(mut self, length: f32, radius: f32) -> Self {
+        self.segment_length = length;
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_taper(mut self, taper: f32, branch_scale: f32) -> Self {
+        self.taper = taper;
+        self.branch_scale = branch_scale;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Bushy coral with stubby, irregular branches, about 2 units tall.
+    pub fn branching_coral() -> Self {
+        Self::new("X")
+            .with_weighted_rule('X', "F[&+X][^-X]\\FX", 0.5)
+            .with_weighted_rule('X', "F[&X][^X][+X]", 0.3)
+            .with_weighted_rule('X', "F!X", 0.2)
+            .with_iterations(4)
+            .with_angle(30f32.to_radians(), 12f32.to_radians())
+            .with_segment(0.25, 0.09)
+            .with_taper(0.88, 0.85)
+    }
+
+    /// Flat, lacy fan that grows in the local XY plane, about a unit across.
+    pub fn sea_fan() -> Self {
+        Self::new("X")
+            .with_weighted_rule('X', "F[+X][-X]FX", 0.6)
+            .with_weighted_rule('X', "F[+X]FX", 0.2)
+            .with_weighted_rule('X', "F[-X]FX", 0.2)
+            .with_iterations(4)
+            .with_angle(28f32.to_radians(), 8f32.to_radians())
+            .with_segment(0.1, 0.025)
+            .with_taper(0.95, 0.85)
+    }
+
+    /// Tall tree with a twisting trunk and upswept crystalline limbs, about 4 units tall.
+    pub fn alien_tree() -> Self {
+        Self::new("FFX")
+            .with_weighted_rule('X', "F[&&+X]/[&&-X]/[&&X]FX", 0.6)
+            .with_weighted_rule('X', "F/[&+X]\\[^-X]X", 0.4)
+            .with_iterations(4)
+            .with_angle(22f32.to_radians(), 6f32.to_radians())
+            .with_segment(0.45, 0.16)
+            .with_taper(0.9, 0.7)
+    }
+
+    /// Rewrites the axiom [`LSystem::iterations`] times.
+    pub fn expand(&self) -> String {
+        let mut rng = SplitMix64::new(self.seed);
+        let mut current = self.axiom.clone();
+        for _ in 0..self.iterations {
+            let mut next = String::with_capacity(current.len().saturating_mul(2));
+            for symbol in current.chars() {
+                match self.pick_rule(symbol, &mut rng) {
+                    Some(rule) => next.push_str(&rule.replacement),
+                    None => next.push(symbol),
+                }
+            }
+            if next.len() > MAX_SYMBOLS {
+                warn!(
+                    "L-system expansion passed {MAX_SYMBOLS} symbols, stopping after fewer iterations"
+                );
+                break;
+            }
+            current = next;
+        }
+        current
+    }
+
+    fn pick_rule(&self, symbol: char, rng: &mut SplitMix64) -> Option<&LRule> {
+        let total: f32 = self
+            .rules
+            .iter()
+            .filter(|rule| rule.symbol == symbol)
+            .map(|rule| rule.weight)
+            .sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut choice = rng.range_f32(0.0, total);
+        let mut matching = self.rules.iter().filter(|rule| rule.symbol == symbol);
+        let first = matching.clone().next();
+        matching
+            .find(|rule| {
+                choice -= rule.weight;
+                choice < 0.0
+            })
+            .or(first)
+    }
+
+    /// Runs the turtle over the expanded string, returning the grown segments.
+    fn segments(&self) -> Vec<Segment> {
+        #[derive(Clone, Copy)]
+        struct Turtle {
+            position: Vec3,
+            rotation: Quat,
+            length: f32,
+            radius: f32,
+            distance: f32,
+        }
+
+        let symbols = self.expand();
+        // Jitter is drawn from its own stream, so adding rules doesn't reshuffle every angle
+        let mut rng = SplitMix64::new(self.seed.rotate_left(32) ^ 0x5EED);
+        let mut turtle = Turtle {
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            length: self.segment_length,
+            radius: self.radius,
+            distance: 0.0,
+        };
+        let mut stack = Vec::new();
+        let mut segments = Vec::new();
+        for symbol in symbols.chars() {
+            let mut turn = |sign: f32| {
+                sign * (self.angle + rng.range_f32(-self.angle_jitter, self.angle_jitter))
+            };
+            match symbol {
+                'F' | 'f' => {
+                    let end = turtle.position + turtle.rotation * Vec3::Y * turtle.length;
+                    let end_radius = turtle.radius * self.taper;
+                    if symbol == 'F' {
+                        segments.push(Segment {
+                            start: turtle.position,
+                            end,
+                            start_radius: turtle.radius,
+                            end_radius,
+                            start_distance: turtle.distance,
+                            end_distance: turtle.distance + turtle.length,
+                        });
+                        turtle.radius = end_radius;
+                    }
+                    turtle.position = end;
+                    turtle.distance += turtle.length;
+                }
+                '+' => turtle.rotation *= Quat::from_rotation_z(turn(1.0)),
+                '-' => turtle.rotation *= Quat::from_rotation_z(turn(-1.0)),
+                '&' => turtle.rotation *= Quat::from_rotation_x(turn(1.0)),
+                '^' => turtle.rotation *= Quat::from_rotation_x(turn(-1.0)),
+                '\\' => turtle.rotation *= Quat::from_rotation_y(turn(1.0)),
+                '/' => turtle.rotation *= Quat::from_rotation_y(turn(-1.0)),
+                '!' => turtle.radius *= self.taper,
+                '[' => {
+                    stack.push(turtle);
+                    turtle.length *= self.branch_scale;
+                    turtle.radius *= self.branch_scale;
+                }
+                ']' => {
+                    if let Some(saved) = stack.pop() {
+                        turtle = saved;
+                    }
+                }
+                _ => {}
+            }
+        }
+        segments
+    }
+
+    /// Builds the mesh at level of detail `lod`, where 0 is the most detailed. Each level uses
+    /// fewer sides per tube and drops the thinnest branches.
+    pub fn mesh(&self, lod: usize) -> Mesh {
+        let segments = self.segments();
+        let sides = MAX_SIDES.checked_shr(lod as u32).unwrap_or(0).max(3);
+        let min_radius = if lod == 0 {
+            0.0
+        } else {
+            self.radius * 0.1 * lod as f32
+        };
+        let max_distance = segments
+            .iter()
+            .map(|segment| segment.end_distance)
+            .fold(f32::EPSILON, f32::max);
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        for segment in segments
+            .iter()
+            .filter(|segment| segment.start_radius >= min_radius)
+        {
+            segment.append_tube(
+                sides,
+                max_distance,
+                &mut positions,
+                &mut normals,
+                &mut uvs,
+                &mut indices,
+            );
+        }
+
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+
+    fn cache_key(&self, lods: usize) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.axiom.hash(&mut hasher);
+        for rule in &self.rules {
+            rule.symbol.hash(&mut hasher);
+            rule.replacement.hash(&mut hasher);
+            rule.weight.to_bits().hash(&mut hasher);
+        }
+        for value in [
+            self.angle,
+            self.angle_jitter,
+            self.segment_length,
+            self.radius,
+            self.taper,
+            self.branch_scale,
+        ] {
+            value.to_bits().hash(&mut hasher);
+        }
+        self.iterations.hash(&mut hasher);
+        self.seed.hash(&mut hasher);
+        lods.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+struct Segment {
+    start: Vec3,
+    end: Vec3,
+    start_radius: f32,
+    end_radius: f32,
+    start_distance: f32,
+    end_distance: f32,
+}
+
+impl Segment {
+    /// Appends a tapered tube with a closed tip.
+    fn append_tube(
+        &self,
+        sides: usize,
+        max_distance: f32,
+        positions: &mut Vec<[f32; 3]>,
+        normals: &mut Vec<[f32; 3]>,
+        uvs: &mut Vec<[f32; 2]>,
+        indices: &mut Vec<u32>,
+    ) {
+        let direction = (self.end - self.start).normalize_or(Vec3::Y);
+        let orientation = Quat::from_rotation_arc(Vec3::Y, direction);
+        let base = positions.len() as u32;
+        let start_v = self.start_distance / max_distance;
+        let end_v = self.end_distance / max_distance;
+
+        for side in 0..=sides {
+            let u = side as f32 / sides as f32;
+            let (sin, cos) = (u * std::f32::consts::TAU).sin_cos();
+            let normal = orientation * Vec3::new(cos, 0.0, sin);
+            positions.push((self.start + normal * self.start_radius).to_array());
+            positions.push((self.end + normal * self.end_radius).to_array());
+            normals.push(normal.to_array());
+            normals.push(normal.to_array());
+            uvs.push([u, start_v]);
+            uvs.push([u, end_v]);
+        }
+        for side in 0..sides as u32 {
+            let i = base + side * 2;
+            indices.extend_from_slice(&[i, i + 1, i + 2, i + 1, i + 3, i + 2]);
+        }
+
+        // Tip cap, a fan around the end of the tube
+        let tip = positions.len() as u32;
+        positions.push(self.end.to_array());
+        normals.push(direction.to_array());
+        uvs.push([0.5, end_v]);
+        for side in 0..sides as u32 {
+            let i = base + side * 2 + 1;
+            indices.extend_from_slice(&[tip, i + 2, i]);
+        }
+    }
+}
+
+/// Handles to an L-system's meshes, from most to least detailed.
+#[derive(Debug, Clone)]
+pub struct LSystemLods {
+    pub meshes: Vec<Handle<Mesh>>,
+}
+
+impl LSystemLods {
+    /// Components for one child entity per level of detail, each visible for `lod_distance` units
+    /// further from the camera than the last. The least detailed level stays visible beyond that.
+    pub fn bundles<M: Material>(
+        &self,
+        material: Handle<M>,
+        lod_distance: f32,
+    ) -> impl Iterator<Item = (Mesh3d, MeshMaterial3d<M>, VisibilityRange)> + '_ {
+        let last = self.meshes.len().saturating_sub(1);
+        self.meshes.iter().enumerate().map(move |(lod, mesh)| {
+            let start = lod as f32 * lod_distance;
+            let end = if lod == last {
+                f32::MAX
+            } else {
+                start + lod_distance
+            };
+            (
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                VisibilityRange::abrupt(start, end),
+            )
+        })
+    }
+}
+
+/// Cache of built L-system meshes, keyed by the system's rules, parameters and seed.
+#[derive(Resource, Default, Debug)]
+pub struct LSystemMeshes {
+    cache: HashMap<u64, LSystemLods>,
+}
+
+impl LSystemMeshes {
+    /// Meshes for `lsystem` at `lods` levels of detail, building them on first use.
+    pub fn get_or_build(
+        &mut self,
+        lsystem: &LSystem,
+        lods: usize,
+        meshes: &mut Assets<Mesh>,
+    ) -> LSystemLods {
+        self.cache
+            .entry(lsystem.cache_key(lods))
+            .or_insert_with(|| LSystemLods {
+                meshes: (0..lods.max(1))
+                    .map(|lod| meshes.add(lsystem.mesh(lod)))
+                    .collect(),
+            })
+            .clone()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
@@ -0,0 +1,268 @@
+//! Panel for tuning custom shader material uniforms live.
+//!
+//! F3+T lists every material asset from outside Bevy that is registered for reflection, e.g. with
+//! `app.register_asset_reflect::<HolographicMaterial>()`, with a slider per `f32` and a colour
+//! picker per [`LinearRgba`] in its uniform structs. Padding fields, named with a leading
+//! underscore, are hidden. "Copy" puts the current values on the clipboard as a Rust struct
+//! literal, ready to paste back into code or a scene file.
+
+use std::fmt::Write as _;
+use std::ops::RangeInclusive;
+
+use bevy::asset::{ReflectAsset, UntypedAssetId};
+use bevy::prelude::*;
+use bevy::reflect::{ReflectKind, ReflectMut, ReflectRef};
+use bevy_inspector_egui::bevy_egui::{
+    EguiContext, EguiPlugin, EguiPrimaryContextPass, PrimaryEguiContext,
+};
+use bevy_inspector_egui::egui;
+use leafwing_input_manager::prelude::*;
+
+use crate::controls::keyboard_not_captured;
+use crate::inspector_widgets::edit_color;
+use crate::kiosk::not_in_kiosk_mode;
+use crate::window::CursorPolicy;
+
+pub struct MaterialTweakPlugin;
+
+impl Plugin for MaterialTweakPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin::default());
+        }
+        app.init_state::<MaterialTweakState>()
+            .add_plugins(InputManagerPlugin::<ToggleMaterialTweaksAction>::default())
+            .add_systems(Startup, setup_actions)
+            .add_systems(
+                Update,
+                handle_actions
+                    .run_if(not_in_kiosk_mode)
+                    .run_if(keyboard_not_captured),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                material_tweak_ui.run_if(in_state(MaterialTweakState::Visible)),
+            )
+            .add_systems(OnEnter(MaterialTweakState::Visible), release_cursor)
+            .add_systems(OnExit(MaterialTweakState::Visible), restore_cursor);
+    }
+}
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MaterialTweakState {
+    Visible,
+    #[default]
+    Hidden,
+}
+
+/// Asset types from crates with this prefix are Bevy's own rather than custom materials.
+const BEVY_CRATE_PREFIX: &str = "bevy";
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+struct ToggleMaterialTweaksAction;
+
+fn setup_actions(mut commands: Commands) {
+    let toggle_map = InputMap::new([(
+        ToggleMaterialTweaksAction,
+        ButtonlikeChord::new([KeyCode::F3, KeyCode::KeyT]),
+    )]);
+    commands.spawn((Name::new("Material tweak controls"), toggle_map));
+}
+
+fn handle_actions(
+    action_state: Single<&ActionState<ToggleMaterialTweaksAction>>,
+    current_state: Res<State<MaterialTweakState>>,
+    mut next_state: ResMut<NextState<MaterialTweakState>>,
+) {
+    if action_state.just_pressed(&ToggleMaterialTweaksAction) {
+        match current_state.get() {
+            MaterialTweakState::Visible => next_state.set(MaterialTweakState::Hidden),
+            MaterialTweakState::Hidden => next_state.set(MaterialTweakState::Visible),
+        }
+    }
+}
+
+fn release_cursor(mut policy: ResMut<CursorPolicy>) {
+    policy.request_release("material tweaks");
+}
+
+fn restore_cursor(mut policy: ResMut<CursorPolicy>) {
+    policy.cancel_release("material tweaks");
+}
+
+fn material_tweak_ui(world: &mut World) {
+    let Ok(egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryEguiContext>>()
+        .single(world)
+    else {
+        return;
+    };
+    let mut egui_context = egui_context.clone();
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let mut material_types: Vec<(&str, &ReflectAsset)> = registry
+        .iter()
+        .filter(|registration| {
+            registration
+                .type_info()
+                .type_path_table()
+                .crate_name()
+                .is_some_and(|name| !name.starts_with(BEVY_CRATE_PREFIX))
+        })
+        .filter_map(|registration| {
+            let reflect_asset = registration.data::<ReflectAsset>()?;
+            Some((
+                registration.type_info().type_path_table().short_path(),
+                reflect_asset,
+            ))
+        })
+        .collect();
+    material_types.sort_by_key(|(name, _)| *name);
+
+    egui::Window::new("Material tweaks")
+        .default_width(320.0)
+        .show(egui_context.get_mut(), |ui| {
+            if material_types.is_empty() {
+                ui.label("No reflected custom materials are registered");
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (name, reflect_asset) in &material_types {
+                    let ids: Vec<UntypedAssetId> = reflect_asset.ids(world).collect();
+                    ui.collapsing(format!("{name} ({})", ids.len()), |ui| {
+                        for id in ids {
+                            tweak_asset(ui, world, reflect_asset, id);
+                        }
+                    });
+                }
+            });
+        });
+}
+
+fn tweak_asset(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    reflect_asset: &ReflectAsset,
+    id: UntypedAssetId,
+) {
+    let label = format!("{id}");
+    ui.push_id(id, |ui| {
+        ui.collapsing(label, |ui| {
+            // Look the asset up read-only first, so it's only marked modified when a value changes
+            let Some(mut edited) = reflect_asset
+                .get(world, id)
+                .and_then(|asset| asset.reflect_clone().ok())
+            else {
+                return;
+            };
+            if ui.button("Copy").clicked() {
+                let mut literal = String::new();
+                write_literal(&mut literal, edited.as_partial_reflect(), 0);
+                ui.ctx().copy_text(literal);
+            }
+            if tweak_fields(ui, edited.as_partial_reflect_mut()) {
+                if let Some(asset) = reflect_asset.get_mut(world, id) {
+                    asset.apply(edited.as_partial_reflect());
+                }
+            }
+        });
+    });
+}
+
+fn tweak_fields(ui: &mut egui::Ui, value: &mut dyn PartialReflect) -> bool {
+    let ReflectMut::Struct(value) = value.reflect_mut() else {
+        return false;
+    };
+    let mut changed = false;
+    for index in 0..value.field_len() {
+        let name = value.name_at(index).unwrap_or_default().to_string();
+        if name.starts_with('_') {
+            continue;
+        }
+        if let Some(field) = value.field_at_mut(index) {
+            changed |= ui.push_id(&name, |ui| tweak_field(ui, &name, field)).inner;
+        }
+    }
+    changed
+}
+
+fn tweak_field(ui: &mut egui::Ui, name: &str, field: &mut dyn PartialReflect) -> bool {
+    if let Some(value) = field.try_downcast_mut::<f32>() {
+        // Keep the range from the first value seen, so it doesn't move while dragging
+        let initial = *value;
+        let range_id = ui.id().with("range");
+        let range = ui.data_mut(|data| {
+            data.get_temp_mut_or_insert_with(range_id, || slider_range(initial))
+                .clone()
+        });
+        return ui
+            .add(
+                egui::Slider::new(value, range)
+                    .clamping(egui::SliderClamping::Never)
+                    .text(name),
+            )
+            .changed();
+    }
+    if let Some(color) = field.try_downcast_mut::<LinearRgba>() {
+        return ui
+            .horizontal(|ui| {
+                let changed = edit_color(ui, color);
+                ui.label(name);
+                changed
+            })
+            .inner;
+    }
+    if field.reflect_kind() == ReflectKind::Struct {
+        return ui
+            .collapsing(name, |ui| tweak_fields(ui, field))
+            .body_returned
+            .unwrap_or(false);
+    }
+    false
+}
+
+/// Slider range for a uniform, wide enough to explore around its starting value. Negative values
+/// are only offered for uniforms that start out negative.
+fn slider_range(initial: f32) -> RangeInclusive<f32> {
+    let max = (initial.abs() * 4.0).max(1.0);
+    if initial < 0.0 { -max..=max } else { 0.0..=max }
+}
+
+fn write_literal(out: &mut String, value: &dyn PartialReflect, depth: usize) {
+    if let Some(value) = value.try_downcast_ref::<f32>() {
+        let _ = write!(out, "{value:?}");
+        return;
+    }
+    if let Some(color) = value.try_downcast_ref::<LinearRgba>() {
+        let LinearRgba {
+            red,
+            green,
+            blue,
+            alpha,
+        } = color;
+        let _ = write!(
+            out,
+            "LinearRgba::new({red:?}, {green:?}, {blue:?}, {alpha:?})"
+        );
+        return;
+    }
+    let ReflectRef::Struct(value) = value.reflect_ref() else {
+        let _ = write!(out, "{value:?}");
+        return;
+    };
+    let name = value
+        .get_represented_type_info()
+        .map_or("_", |info| info.type_path_table().short_path());
+    let indent = "    ".repeat(depth.saturating_add(1));
+    let _ = writeln!(out, "{name} {{");
+    for index in 0..value.field_len() {
+        let (Some(field_name), Some(field)) = (value.name_at(index), value.field_at(index)) else {
+            continue;
+        };
+        let _ = write!(out, "{indent}{field_name}: ");
+        write_literal(out, field, depth.saturating_add(1));
+        out.push_str(",\n");
+    }
+    let _ = write!(out, "{}}}", "    ".repeat(depth));
+}
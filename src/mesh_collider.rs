@@ -0,0 +1,174 @@
+//! Colliders derived from rendered meshes, and a check that colliders still fit their meshes.
+//!
+//! [`ColliderFromMesh`] gives an entity a [`Collider`] built from its [`Mesh3d`] once the mesh has
+//! loaded, rebuilding it if the mesh changes, so sizes don't have to be written out twice. The
+//! collider is scaled with the entity's transform like any other.
+//!
+//! Inserting a [`ColliderValidation`] resource, which the `physics-debug` feature does, warns about
+//! entities whose collider's bounding box differs from their mesh's by more than its tolerance,
+//! e.g. a `Cuboid` mesh and `Collider::cuboid` given different sizes.
+
+#![allow(clippy::useless_conversion)]
+use avian3d::math::{AdjustPrecision, AsF32, Vector};
+use avian3d::prelude::*;
+use bevy::camera::primitives::Aabb;
+use bevy::camera::visibility::VisibilitySystems;
+use bevy::ecs::entity::EntityHashSet;
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::*;
+
+pub struct MeshColliderPlugin;
+
+impl Plugin for MeshColliderPlugin {
+    fn build(&self, app: &mut App) {
+        // Before the fixed loop, so a mesh spawned at startup has a collider by the first step
+        app.add_systems(PreUpdate, build_mesh_colliders)
+            .add_systems(
+                PostUpdate,
+                validate_colliders
+                    .after(VisibilitySystems::CalculateBounds)
+                    .run_if(resource_exists::<ColliderValidation>),
+            );
+    }
+}
+
+/// Builds this entity's [`Collider`] from its [`Mesh3d`].
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum ColliderFromMesh {
+    /// The smallest convex shape around every vertex, which is cheap and suits dynamic bodies.
+    #[default]
+    ConvexHull,
+    /// Every triangle of the mesh, for concave static geometry such as terrain or arches.
+    Trimesh,
+}
+
+impl ColliderFromMesh {
+    /// Builds a collider from `mesh`, or `None` if it has no positions or isn't made of
+    /// triangles.
+    pub fn collider(self, mesh: &Mesh) -> Option<Collider> {
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return None;
+        };
+        let points: Vec<_> = positions
+            .iter()
+            .map(|position| Vec3::from_array(*position).adjust_precision())
+            .collect();
+        match self {
+            Self::ConvexHull => Collider::convex_hull(points),
+            Self::Trimesh => {
+                if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+                    return None;
+                }
+                let indices: Vec<u32> = match mesh.indices() {
+                    Some(Indices::U32(indices)) => indices.clone(),
+                    Some(Indices::U16(indices)) => indices.iter().map(|&i| u32::from(i)).collect(),
+                    // Unindexed meshes list each triangle's vertices in turn
+                    None => (0..u32::try_from(points.len()).ok()?).collect(),
+                };
+                let triangles: Vec<[u32; 3]> = indices
+                    .chunks_exact(3)
+                    .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+                    .collect();
+                (!triangles.is_empty()).then(|| Collider::trimesh(points, triangles))
+            }
+        }
+    }
+}
+
+/// Warns about colliders that don't match their meshes.
+#[derive(Resource, Debug, Clone)]
+pub struct ColliderValidation {
+    /// How far, in world units, the sides of a collider's bounding box can be from the mesh's.
+    pub tolerance: f32,
+}
+
+impl Default for ColliderValidation {
+    fn default() -> Self {
+        Self { tolerance: 0.05 }
+    }
+}
+
+fn build_mesh_colliders(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<Mesh>>,
+    meshes: Res<Assets<Mesh>>,
+    entities: Query<(
+        Entity,
+        Ref<Mesh3d>,
+        Ref<ColliderFromMesh>,
+        Has<Collider>,
+        Option<&Name>,
+    )>,
+    mut failed: Local<EntityHashSet>,
+) {
+    let modified: Vec<AssetId<Mesh>> = events
+        .read()
+        .filter_map(|event| match *event {
+            AssetEvent::Modified { id } => Some(id),
+            _ => None,
+        })
+        .collect();
+    for (entity, mesh, from_mesh, has_collider, name) in &entities {
+        let changed = mesh.is_changed() || from_mesh.is_changed() || modified.contains(&mesh.id());
+        if changed {
+            failed.remove(&entity);
+        }
+        if !changed && (has_collider || failed.contains(&entity)) {
+            continue;
+        }
+        // Not loaded yet, so try again next frame
+        let Some(asset) = meshes.get(&mesh.0) else {
+            continue;
+        };
+        match from_mesh.collider(asset) {
+            Some(collider) => {
+                commands.entity(entity).insert(collider);
+            }
+            // Retried once the mesh or kind changes
+            None => {
+                warn!(
+                    "Can't build a {:?} collider from the mesh of {}",
+                    *from_mesh,
+                    name.map_or_else(|| entity.to_string(), ToString::to_string)
+                );
+                failed.insert(entity);
+            }
+        }
+    }
+}
+
+fn validate_colliders(
+    validation: Res<ColliderValidation>,
+    colliders: Query<
+        (Entity, &Collider, &Aabb, Option<&Name>),
+        Or<(Changed<Collider>, Changed<Aabb>)>,
+    >,
+    mut reported: Local<EntityHashSet>,
+) {
+    for (entity, collider, aabb, name) in &colliders {
+        // Scaled the same as the collider, which follows its transform's scale
+        let scale = collider.scale().f32().abs();
+        let mesh_min = (Vec3::from(aabb.center) - Vec3::from(aabb.half_extents)) * scale;
+        let mesh_max = (Vec3::from(aabb.center) + Vec3::from(aabb.half_extents)) * scale;
+        let collider_aabb = collider.aabb(Vector::ZERO, Rotation::IDENTITY);
+        let (collider_min, collider_max) = (collider_aabb.min.f32(), collider_aabb.max.f32());
+
+        let divergence = (collider_min - mesh_min)
+            .abs()
+            .max((collider_max - mesh_max).abs())
+            .max_element();
+        if divergence <= validation.tolerance {
+            reported.remove(&entity);
+            continue;
+        }
+        if reported.insert(entity) {
+            warn!(
+                "Collider of {} is {divergence:.2} off its mesh: collider spans {collider_min} to {collider_max}, mesh spans {mesh_min} to {mesh_max}",
+                name.map_or_else(|| entity.to_string(), ToString::to_string)
+            );
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Metaballs: blobs drifting around a box that melt together and pull apart like liquid.
+//!
+//! An entity with [`Metaballs`] gets a mesh of the blobs' merged surface, rebuilt every frame as
+//! they move. Each blob's influence falls off with the square of the distance from its centre, and
+//! the surface is where the blobs' influences add up to 1, meshed with the same surface nets as
+//! [`SdfChunk`]. Blobs follow one of a few [`MetaballMotion`] patterns, each varied per blob by the
+//! seed, and move on [`GameTime`], so they hold still while the game's paused. Add a material to
+//! render them; meshes have UVs wrapped around the vertical axis, for materials that ripple or
+//! scroll.
+
+use std::f32::consts::TAU;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::*;
+
+use crate::game_time::GameTime;
+use crate::rng::SplitMix64;
+use crate::sdf_terrain::{SdfChunk, SdfField};
+use crate::state::PausableSystems;
+
+pub struct MetaballsPlugin;
+
+impl Plugin for MetaballsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Metaballs>()
+            .add_observer(add_metaball_mesh)
+            .add_systems(Update, remesh_metaballs.in_set(PausableSystems));
+    }
+}
+
+/// How the blobs move around their box.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum MetaballMotion {
+    /// Circling the centre on tilted orbits, each at its own speed.
+    #[default]
+    Orbit,
+    /// Bouncing off the sides of the box in straight lines.
+    Bounce,
+    /// Slowly rising and sinking, like wax in a lava lamp.
+    Lava,
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+pub struct Metaballs {
+    pub count: u32,
+    pub motion: MetaballMotion,
+    /// Radius of a blob on its own, which grows as blobs merge.
+    pub radius: f32,
+    /// Half the size of the box the blobs stay within, centred on the entity.
+    pub half_size: Vec3,
+    /// Edge length of each sampled cell. Smaller cells give smoother blobs but take longer to
+    /// rebuild every frame.
+    pub cell_size: f32,
+    /// Multiplies how fast the blobs move.
+    pub speed: f32,
+    pub seed: u64,
+}
+
+impl Default for Metaballs {
+    fn default() -> Self {
+        Self {
+            count: 6,
+            motion: MetaballMotion::default(),
+            radius: 0.35,
+            half_size: Vec3::ONE,
+            cell_size: 0.08,
+            speed: 1.0,
+            seed: 0,
+        }
+    }
+}
+
+impl Metaballs {
+    pub fn new(count: u32) -> Self {
+        Self { count, ..default() }
+    }
+
+    pub fn with_motion(mut self, motion: MetaballMotion) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius.max(0.01);
+        self
+    }
+
+    pub fn with_half_size(mut self, half_size: Vec3) -> Self {
+        self.half_size = half_size.max(Vec3::splat(0.01));
+        self
+    }
+
+    pub fn with_cell_size(mut self, cell_size: f32) -> Self {
+        self.cell_size = cell_size.max(0.01);
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Where each blob's centre is at `time` seconds, relative to the entity.
+    pub fn centers(&self, time: f32) -> Vec<Vec3> {
+        // Kept far enough in from the sides that a blob on its own isn't cut off
+        let extent = (self.half_size - Vec3::splat(self.radius)).max(Vec3::ZERO);
+        let time = time * self.speed;
+        (0..self.count)
+            .map(|blob| {
+                let mut rng = SplitMix64::new(self.seed.wrapping_add(u64::from(blob)));
+                let phase = rng.range_f32(0.0, TAU);
+                let unit = match self.motion {
+                    MetaballMotion::Orbit => {
+                        let axis = Vec3::new(
+                            rng.range_f32(-1.0, 1.0),
+                            rng.range_f32(0.5, 1.0),
+                            rng.range_f32(-1.0, 1.0),
+                        )
+                        .normalize();
+                        let radius = rng.range_f32(0.3, 0.9);
+                        let angle = phase + time * rng.range_f32(0.4, 1.2);
+                        Quat::from_axis_angle(axis, angle) * axis.any_orthonormal_vector() * radius
+                    }
+                    MetaballMotion::Bounce => {
+                        let frequency = Vec3::new(
+                            rng.range_f32(0.1, 0.3),
+                            rng.range_f32(0.1, 0.3),
+                            rng.range_f32(0.1, 0.3),
+                        );
+                        let offset = Vec3::new(rng.next_f32(), rng.next_f32(), rng.next_f32());
+                        // Triangle wave, sweeping evenly from side to side
+                        let t = offset + frequency * time;
+                        (t - t.floor() - Vec3::splat(0.5)).abs() * 4.0 - Vec3::ONE
+                    }
+                    MetaballMotion::Lava => {
+                        let rise = rng.range_f32(0.1, 0.25);
+                        let drift = rng.range_f32(0.05, 0.15);
+                        let (x, z) = (rng.range_f32(-0.5, 0.5), rng.range_f32(-0.5, 0.5));
+                        Vec3::new(
+                            x + (phase + time * drift).sin() * 0.3,
+                            (phase + time * rise * TAU).sin(),
+                            z + (phase + time * drift).cos() * 0.3,
+                        )
+                    }
+                };
+                unit.clamp(Vec3::NEG_ONE, Vec3::ONE) * extent
+            })
+            .collect()
+    }
+
+    /// Field that's negative where the blobs' influences add up to more than 1.
+    fn field(&self, time: f32) -> SdfField {
+        let centers = self.centers(time);
+        let radius_squared = self.radius * self.radius;
+        SdfField::new(move |point| {
+            let influence: f32 = centers
+                .iter()
+                .map(|center| radius_squared / point.distance_squared(*center).max(1e-6))
+                .sum();
+            1.0 - influence
+        })
+    }
+
+    /// Builds the blobs' surface at `time` seconds.
+    pub fn mesh(&self, time: f32) -> Mesh {
+        let origin = -self.half_size;
+        let chunk = SdfChunk::new(
+            self.field(time),
+            origin,
+            self.half_size * 2.0,
+            self.cell_size,
+        );
+        let Some(mut mesh) = chunk.mesh() else {
+            return Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, Vec::<[f32; 3]>::new())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, Vec::<[f32; 2]>::new())
+            .with_inserted_indices(Indices::U32(Vec::new()));
+        };
+        // Chunk meshes are relative to their origin, so bring the box back round the entity
+        mesh = mesh.translated_by(origin);
+        let uvs: Vec<[f32; 2]> = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions
+                .iter()
+                .map(|[x, y, z]| [z.atan2(*x) / TAU + 0.5, 0.5 - y / (self.half_size.y * 2.0)])
+                .collect(),
+            _ => Vec::new(),
+        };
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh
+    }
+}
+
+fn add_metaball_mesh(
+    add: On<Add, Metaballs>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    time: Res<GameTime>,
+    metaballs: Query<&Metaballs>,
+) {
+    let Ok(metaballs) = metaballs.get(add.entity) else {
+        return;
+    };
+    let mesh = meshes.add(metaballs.mesh(time.elapsed_secs()));
+    commands.entity(add.entity).insert(Mesh3d(mesh));
+}
+
+fn remesh_metaballs(
+    time: Res<GameTime>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    metaballs: Query<(&Metaballs, &Mesh3d)>,
+) {
+    for (metaballs, mesh) in &metaballs {
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            *mesh = metaballs.mesh(time.elapsed_secs());
+        }
+    }
+}
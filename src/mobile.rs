@@ -0,0 +1,274 @@
+//! Hanging mobiles: tiers of balanced arms and weights strung from a ceiling.
+//!
+//! A [`Mobile`] spawns a static anchor and, hanging below it, a chain of arms. Each arm hangs by a
+//! thread from its balance point, with a weight at one end and the next arm down hanging from the
+//! other, or a second weight on the lowest arm. Arms and weights are dynamic rigid bodies joined by
+//! spherical joints, so they turn and sway when the player bumps them or a
+//! [`WindVolume`](crate::physics::WindVolume) blows through them, and slowly settle again. Threads
+//! are drawn as lines between the joints.
+
+#![allow(clippy::useless_conversion)]
+
+use std::f32::consts::FRAC_PI_2;
+
+use avian3d::math::AdjustPrecision;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+pub struct MobilePlugin;
+
+impl Plugin for MobilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, draw_threads.after(TransformSystems::Propagate));
+    }
+}
+
+const THREAD_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.8);
+/// Golden angle, turning each tier's arm well away from the one above.
+const TIER_TURN: f32 = 2.4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mobile {
+    /// Number of arms, one below another.
+    pub tiers: u32,
+    /// Length of the top arm.
+    pub arm_length: f32,
+    /// How long each arm is compared to the one above.
+    pub arm_scale: f32,
+    /// Length of the threads each arm and weight hangs from.
+    pub thread_length: f32,
+    /// Radius of the flat, disc-shaped weights.
+    pub weight_radius: f32,
+    pub weight_mass: f32,
+    /// Fraction of linear and angular velocity lost per second, which settles the mobile after
+    /// it's disturbed.
+    pub damping: f32,
+}
+
+impl Default for Mobile {
+    fn default() -> Self {
+        Self {
+            tiers: 3,
+            arm_length: 1.6,
+            arm_scale: 0.75,
+            thread_length: 0.5,
+            weight_radius: 0.18,
+            weight_mass: 0.5,
+            damping: 0.3,
+        }
+    }
+}
+
+impl Mobile {
+    pub fn new(tiers: u32) -> Self {
+        Self {
+            tiers: tiers.max(1),
+            ..default()
+        }
+    }
+
+    pub fn with_arm_length(mut self, arm_length: f32, arm_scale: f32) -> Self {
+        self.arm_length = arm_length.max(0.01);
+        self.arm_scale = arm_scale.max(0.01);
+        self
+    }
+
+    pub fn with_thread_length(mut self, thread_length: f32) -> Self {
+        self.thread_length = thread_length.max(0.01);
+        self
+    }
+
+    pub fn with_weight(mut self, radius: f32, mass: f32) -> Self {
+        self.weight_radius = radius.max(0.01);
+        self.weight_mass = mass.max(0.001);
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping.max(0.0);
+        self
+    }
+
+    fn arm_length(&self, tier: u32) -> f32 {
+        self.arm_length * self.arm_scale.powi(tier as i32)
+    }
+
+    /// Arms are light next to the weights, but heavy enough to swing believably.
+    fn arm_mass(&self, tier: u32) -> f32 {
+        self.weight_mass * 0.2 * self.arm_length(tier) / self.arm_length
+    }
+
+    /// Mass hanging from the far end of arm `tier`.
+    fn end_load(&self, tier: u32) -> f32 {
+        if tier + 1 >= self.tiers {
+            return self.weight_mass;
+        }
+        let below = tier + 1;
+        self.arm_mass(below) + self.weight_mass + self.end_load(below)
+    }
+
+    /// Distance from arm `tier`'s centre towards its far end at which it balances.
+    fn balance_point(&self, tier: u32) -> f32 {
+        let (near, far, arm) = (self.weight_mass, self.end_load(tier), self.arm_mass(tier));
+        self.arm_length(tier) * 0.5 * (far - near) / (near + far + arm)
+    }
+
+    /// Spawns the mobile hanging from `transform`, e.g. a point on a ceiling, returning the
+    /// anchor, which despawns the rest of the mobile with it.
+    pub fn spawn<M: Material>(
+        &self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        arm_material: Handle<M>,
+        weight_material: Handle<M>,
+        transform: Transform,
+    ) -> Entity {
+        let thickness = 0.02;
+        let weight_depth = self.weight_radius * 0.15;
+        let weight_mesh = meshes.add(Cylinder::new(self.weight_radius, weight_depth));
+        // Short enough that a weight clears the arm it hangs from
+        let weight_drop = self.thread_length * 0.5 + self.weight_radius;
+
+        let anchor = commands
+            .spawn((
+                Name::new("Mobile"),
+                transform,
+                Visibility::default(),
+                RigidBody::Static,
+            ))
+            .id();
+
+        let spawn_weight = |commands: &mut Commands,
+                            parent: Entity,
+                            parent_anchor: Vec3,
+                            position: Vec3,
+                            rotation: Quat,
+                            index: u32| {
+            // Discs hang edge on to the arm, so they catch the wind across it
+            let rotation = rotation * Quat::from_rotation_x(FRAC_PI_2);
+            let weight = commands
+                .spawn((
+                    Name::new(format!("Mobile Weight {index}")),
+                    MobilePartOf(anchor),
+                    Mesh3d(weight_mesh.clone()),
+                    MeshMaterial3d(weight_material.clone()),
+                    Transform::from_translation(position).with_rotation(rotation),
+                    RigidBody::Dynamic,
+                    Collider::cylinder(
+                        self.weight_radius.adjust_precision(),
+                        weight_depth.adjust_precision(),
+                    ),
+                    Mass(self.weight_mass),
+                    LinearDamping(self.damping.adjust_precision()),
+                    AngularDamping(self.damping.adjust_precision()),
+                ))
+                .id();
+            thread(
+                commands,
+                anchor,
+                (parent, parent_anchor),
+                (weight, rotation.inverse() * Vec3::Y * weight_drop),
+            );
+        };
+
+        let mut parent = (anchor, Vec3::ZERO);
+        let mut pivot = transform.translation - Vec3::Y * self.thread_length;
+        let mut weights = 0;
+        for tier in 0..self.tiers {
+            let length = self.arm_length(tier);
+            let balance = self.balance_point(tier);
+            let rotation = transform.rotation * Quat::from_rotation_y(tier as f32 * TIER_TURN);
+            let direction = rotation * Vec3::X;
+            let center = pivot - direction * balance;
+            let arm = commands
+                .spawn((
+                    Name::new(format!("Mobile Arm {}", tier + 1)),
+                    MobilePartOf(anchor),
+                    Mesh3d(meshes.add(Cuboid::new(length, thickness, thickness))),
+                    MeshMaterial3d(arm_material.clone()),
+                    Transform::from_translation(center).with_rotation(rotation),
+                    RigidBody::Dynamic,
+                    Collider::cuboid(
+                        length.adjust_precision(),
+                        thickness.adjust_precision(),
+                        thickness.adjust_precision(),
+                    ),
+                    Mass(self.arm_mass(tier)),
+                    LinearDamping(self.damping.adjust_precision()),
+                    AngularDamping(self.damping.adjust_precision()),
+                ))
+                .id();
+            thread(
+                commands,
+                anchor,
+                parent,
+                (arm, Vec3::new(balance, self.thread_length, 0.0)),
+            );
+
+            let (near, far) = (-Vec3::X * length * 0.5, Vec3::X * length * 0.5);
+            weights += 1;
+            spawn_weight(
+                commands,
+                arm,
+                near,
+                center - direction * length * 0.5 - Vec3::Y * weight_drop,
+                rotation,
+                weights,
+            );
+            if tier + 1 == self.tiers {
+                weights += 1;
+                spawn_weight(
+                    commands,
+                    arm,
+                    far,
+                    center + direction * length * 0.5 - Vec3::Y * weight_drop,
+                    rotation,
+                    weights,
+                );
+            }
+            parent = (arm, far);
+            pivot = center + direction * length * 0.5 - Vec3::Y * self.thread_length;
+        }
+        anchor
+    }
+}
+
+/// On each body and thread of a mobile, pointing at its anchor.
+#[derive(Component, Debug)]
+#[relationship(relationship_target = MobileParts)]
+pub struct MobilePartOf(pub Entity);
+
+/// On a mobile's anchor, pointing at its bodies and threads, which are despawned with it.
+#[derive(Component, Debug)]
+#[relationship_target(relationship = MobilePartOf, linked_spawn)]
+pub struct MobileParts(Vec<Entity>);
+
+/// A thread between two bodies, held taut by a spherical joint, at points local to each.
+#[derive(Component, Debug, Clone, Copy)]
+struct Thread {
+    from: (Entity, Vec3),
+    to: (Entity, Vec3),
+}
+
+fn thread(commands: &mut Commands, anchor: Entity, from: (Entity, Vec3), to: (Entity, Vec3)) {
+    commands.spawn((
+        Name::new("Mobile Thread"),
+        MobilePartOf(anchor),
+        Thread { from, to },
+        SphericalJoint::new(from.0, to.0)
+            .with_local_anchor1(from.1.adjust_precision())
+            .with_local_anchor2(to.1.adjust_precision()),
+    ));
+}
+
+fn draw_threads(mut gizmos: Gizmos, threads: Query<&Thread>, transforms: Query<&GlobalTransform>) {
+    for thread in &threads {
+        if let (Ok(from), Ok(to)) = (transforms.get(thread.from.0), transforms.get(thread.to.0)) {
+            gizmos.line(
+                from.transform_point(thread.from.1),
+                to.transform_point(thread.to.1),
+                THREAD_COLOR,
+            );
+        }
+    }
+}
@@ -0,0 +1,428 @@
+//! Ambient particle presets, such as dust motes, fireflies, drifting spores and falling leaves.
+//!
+//! Adding [`Motes`] to an entity fills a box around it with small particles that drift, wander and
+//! twinkle, fading out and in again elsewhere in the box so it never empties. Start from a preset
+//! such as [`Motes::dust`] and adjust its fields, or add [`MotesFile`] to load the settings from a
+//! `.motes.ron` file in an app's assets, e.g.
+//!
+//! ```text
+//! (
+//!     count: 40,
+//!     extent: (6.0, 3.0, 6.0),
+//!     color: Srgba((red: 1.0, green: 0.85, blue: 0.4, alpha: 1.0)),
+//!     emissive: 4.0,
+//!     twinkle: 0.8,
+//! )
+//! ```
+//!
+//! Fields left out of the file keep the [`Motes::dust`] defaults. The file is hot reloaded if
+//! Bevy's `file_watcher` feature is enabled. Motes are drawn by a child [`ParticleEmitter`] built
+//! with [`Motes::emitter`], so however many there are they're a single draw call, and only the few
+//! carrying lights are entities. Both move on [`GameTime`], so they freeze while paused.
+
+use std::f32::consts::TAU;
+use std::io;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_time::GameTime;
+use crate::particles::{
+    EmitterShape, LifeCurve, LifeGradient, ParticleEmitter, ParticleSpace, ParticleSprite,
+};
+use crate::rng::SplitMix64;
+
+pub struct MotesPlugin;
+
+impl Plugin for MotesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Motes>()
+            .init_asset::<Motes>()
+            .register_asset_loader(MotesLoader)
+            .add_systems(
+                Update,
+                (
+                    load_motes_files,
+                    apply_motes_files,
+                    spawn_motes,
+                    animate_mote_lights,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Seconds a mote lives, fading in and out, unless drifting would carry it out of the box sooner.
+const MOTE_LIFETIME: f32 = 10.0;
+/// Average twinkles per second.
+const TWINKLE_RATE: f32 = 1.5;
+
+#[derive(Serialize, Deserialize, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MoteShape {
+    #[default]
+    Sphere,
+    /// Flat, double-sided quad, for leaves and petals.
+    Leaf,
+}
+
+/// Fills a box around this entity with ambient particles.
+#[derive(Component, Asset, Serialize, Deserialize, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+#[serde(default)]
+pub struct Motes {
+    pub count: u32,
+    /// Half the size of the box motes fill, in the entity's local space.
+    pub extent: Vec3,
+    /// Mote radius, or half the length of a leaf.
+    pub size: f32,
+    /// Random size variation, as a fraction of `size`.
+    pub size_jitter: f32,
+    pub shape: MoteShape,
+    pub color: Color,
+    /// Emissive strength as a multiple of `color`, with 0 for motes that only reflect light.
+    pub emissive: f32,
+    /// Steady velocity, in units per second.
+    pub drift: Vec3,
+    /// How far motes wander either side of their drifting path.
+    pub wander: f32,
+    /// Wander cycles per second.
+    pub wander_speed: f32,
+    /// How much motes shrink and dim as they twinkle, from 0 for steady to 1 for fully out.
+    pub twinkle: f32,
+    /// Tumbling speed, in radians per second.
+    pub spin: f32,
+    /// How many of the motes carry a small point light.
+    pub lights: u32,
+    pub light_intensity: f32,
+    pub seed: u64,
+}
+
+impl Default for Motes {
+    fn default() -> Self {
+        Self::dust()
+    }
+}
+
+impl Motes {
+    /// Faint, slowly swirling dust, to catch the light in sunbeams and spotlights.
+    pub fn dust() -> Self {
+        Self {
+            count: 80,
+            extent: Vec3::splat(3.0),
+            size: 0.012,
+            size_jitter: 0.4,
+            shape: MoteShape::Sphere,
+            color: Color::srgba(1.0, 0.95, 0.85, 0.6),
+            emissive: 0.5,
+            drift: Vec3::new(0.0, 0.01, 0.0),
+            wander: 0.15,
+            wander_speed: 0.05,
+            twinkle: 0.3,
+            spin: 0.0,
+            lights: 0,
+            light_intensity: 0.0,
+            seed: 0,
+        }
+    }
+
+    /// Glowing, blinking fireflies hovering over a wide area, a few of which light their
+    /// surroundings.
+    pub fn fireflies() -> Self {
+        Self {
+            count: 20,
+            extent: Vec3::new(8.0, 1.5, 8.0),
+            size: 0.05,
+            size_jitter: 0.2,
+            color: Color::srgb(1.0, 0.85, 0.4),
+            emissive: 6.0,
+            drift: Vec3::ZERO,
+            wander: 0.8,
+            wander_speed: 0.12,
+            twinkle: 0.9,
+            lights: 4,
+            light_intensity: 600.0,
+            ..Self::dust()
+        }
+    }
+
+    /// Softly glowing spores rising and drifting on a light breeze.
+    pub fn spores() -> Self {
+        Self {
+            count: 60,
+            extent: Vec3::new(6.0, 4.0, 6.0),
+            size: 0.04,
+            size_jitter: 0.5,
+            color: Color::srgba(0.45, 1.0, 0.8, 0.8),
+            emissive: 2.0,
+            drift: Vec3::new(0.05, 0.12, 0.0),
+            wander: 0.3,
+            wander_speed: 0.08,
+            twinkle: 0.4,
+            ..Self::dust()
+        }
+    }
+
+    /// Autumn leaves tumbling down and sideways.
+    pub fn falling_leaves() -> Self {
+        Self {
+            count: 30,
+            extent: Vec3::new(6.0, 5.0, 6.0),
+            size: 0.12,
+            size_jitter: 0.3,
+            shape: MoteShape::Leaf,
+            color: Color::srgb(0.8, 0.45, 0.15),
+            emissive: 0.0,
+            drift: Vec3::new(0.3, -0.6, 0.1),
+            wander: 0.5,
+            wander_speed: 0.3,
+            twinkle: 0.0,
+            spin: 2.0,
+            ..Self::dust()
+        }
+    }
+
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn with_extent(mut self, extent: Vec3) -> Self {
+        self.extent = extent.max(Vec3::ZERO);
+        self
+    }
+
+    pub fn with_color(mut self, color: impl Into<Color>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn with_drift(mut self, drift: Vec3) -> Self {
+        self.drift = drift;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The particle emitter that keeps the box filled with these motes.
+    pub fn emitter(&self) -> ParticleEmitter {
+        // Short enough that motes fade before drifting far out of the box
+        let crossing =
+            (self.extent * 2.0 / self.drift.abs().max(Vec3::splat(f32::EPSILON))).min_element();
+        let lifetime = MOTE_LIFETIME.min(crossing).max(0.5);
+        // Glowing motes are drawn at their colour plus its glow, and the rest are shaded
+        let lit = self.emissive <= 0.0;
+        let color = self.color.to_linear();
+        let color = if lit {
+            color
+        } else {
+            (color * (1.0 + self.emissive)).with_alpha(color.alpha)
+        };
+        ParticleEmitter {
+            rate: self.count as f32 / lifetime,
+            burst: 0,
+            max_particles: self.count * 2,
+            shape: EmitterShape::Box {
+                half_extents: self.extent,
+            },
+            space: ParticleSpace::Local,
+            lifetime,
+            lifetime_jitter: 0.3,
+            velocity: self.drift,
+            velocity_jitter: self.drift.abs() * 0.2,
+            wobble: self.wander,
+            wobble_speed: self.wander_speed,
+            size: self.size * 2.0,
+            size_jitter: self.size_jitter,
+            size_over_life: LifeCurve::new(twinkle_keys(self.twinkle, lifetime)),
+            sprite: match self.shape {
+                MoteShape::Sphere => ParticleSprite::Dot,
+                MoteShape::Leaf => ParticleSprite::Leaf,
+            },
+            spin: self.spin,
+            color_over_life: LifeGradient::fade_in_out(color, 0.1),
+            lit,
+            prewarm: true,
+            seed: self.seed,
+            ..default()
+        }
+    }
+}
+
+/// Size over a mote's life, shrinking `twinkle` of the way out and back about [`TWINKLE_RATE`]
+/// times a second. Lifetimes vary, so motes twinkle at their own rates.
+fn twinkle_keys(twinkle: f32, lifetime: f32) -> Vec<(f32, f32)> {
+    if twinkle <= 0.0 {
+        return vec![(0.0, 1.0)];
+    }
+    let steps = (lifetime * TWINKLE_RATE).round().max(1.0) as u32 * 4;
+    (0..=steps)
+        .map(|i| {
+            let pulse = (TAU * i as f32 / 4.0).sin();
+            (i as f32 / steps as f32, 1.0 - twinkle * (0.5 + 0.5 * pulse))
+        })
+        .collect()
+}
+
+/// Asset path of a RON file to load this entity's [`Motes`] from, replacing them once loaded.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct MotesFile {
+    pub path: String,
+    handle: Option<Handle<Motes>>,
+}
+
+impl MotesFile {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            handle: None,
+        }
+    }
+}
+
+#[derive(Default, TypePath)]
+struct MotesLoader;
+
+impl AssetLoader for MotesLoader {
+    type Asset = Motes;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> io::Result<Motes> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["motes.ron"]
+    }
+}
+
+/// The particle emitter drawing a [`Motes`] parent's motes.
+#[derive(Component, Debug)]
+struct MoteParticles;
+
+/// One of the few motes carrying a light, which wanders and twinkles like the particles around it.
+#[derive(Component, Debug)]
+struct MoteLight {
+    /// Starting point within the box, before drifting and wandering.
+    home: Vec3,
+    phase: f32,
+}
+
+fn load_motes_files(
+    mut files: Query<&mut MotesFile, Added<MotesFile>>,
+    asset_server: Res<AssetServer>,
+) {
+    for mut file in &mut files {
+        file.handle = Some(asset_server.load(file.path.clone()));
+    }
+}
+
+fn apply_motes_files(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<Motes>>,
+    files: Query<(Entity, &MotesFile)>,
+    configs: Res<Assets<Motes>>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+        let Some(config) = configs.get(id) else {
+            continue;
+        };
+        for (entity, file) in &files {
+            if file.handle.as_ref().is_some_and(|handle| handle.id() == id) {
+                debug!("Applying motes from {}", file.path);
+                commands.entity(entity).insert(config.clone());
+            }
+        }
+    }
+}
+
+/// (Re)spawns the motes of every [`Motes`] that was added or changed.
+fn spawn_motes(
+    mut commands: Commands,
+    emitters: Query<(Entity, &Motes, Option<&Children>), Changed<Motes>>,
+    existing: Query<(), Or<(With<MoteParticles>, With<MoteLight>)>>,
+) {
+    for (entity, motes, children) in &emitters {
+        for child in children.into_iter().flatten() {
+            if existing.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
+
+        let mut rng = SplitMix64::new(motes.seed);
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((Name::new("Mote particles"), MoteParticles, motes.emitter()));
+            for i in 0..motes.lights {
+                let home = Vec3::new(
+                    rng.range_f32(-1.0, 1.0),
+                    rng.range_f32(-1.0, 1.0),
+                    rng.range_f32(-1.0, 1.0),
+                ) * motes.extent;
+                parent.spawn((
+                    Name::new(format!("Mote light {}", i + 1)),
+                    MoteLight {
+                        home,
+                        phase: rng.next_f32() * TAU,
+                    },
+                    PointLight {
+                        color: motes.color.with_alpha(1.0),
+                        intensity: motes.light_intensity,
+                        range: 6.0,
+                        shadows_enabled: false,
+                        ..default()
+                    },
+                    Transform::from_translation(home),
+                ));
+            }
+        });
+    }
+}
+
+fn animate_mote_lights(
+    time: Res<GameTime>,
+    emitters: Query<(&Motes, &Children)>,
+    mut lights: Query<(&MoteLight, &mut Transform, &mut PointLight)>,
+) {
+    let t = time.elapsed_secs();
+    for (settings, children) in &emitters {
+        let extent = settings.extent.max(Vec3::splat(f32::EPSILON));
+        for child in children {
+            let Ok((mote, mut transform, mut light)) = lights.get_mut(*child) else {
+                continue;
+            };
+            // Lights wrap around the box rather than fading, so there's always the same number
+            let drifted =
+                (mote.home + settings.drift * t + extent).rem_euclid(extent * 2.0) - extent;
+            let cycle = TAU * settings.wander_speed * t + mote.phase;
+            let wander = Vec3::new(
+                cycle.sin(),
+                (cycle * 0.8 + mote.phase).sin() * 0.5,
+                (cycle * 1.1 + mote.phase * 0.6).cos(),
+            ) * settings.wander;
+            // Each light twinkles at its own rate, between 1 and 2.5 cycles per second
+            let rate = 1.0 + mote.phase / TAU * 1.5;
+            let pulse = 1.0 - settings.twinkle * (0.5 + 0.5 * (TAU * rate * t + mote.phase).sin());
+
+            transform.translation = drifted + wander;
+            light.intensity = settings.light_intensity * pulse.max(0.0);
+        }
+    }
+}
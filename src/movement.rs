@@ -0,0 +1,225 @@
+//! Flying and swimming, as alternatives to walking on the ground.
+//!
+//! The player's [`MovementMode`] can be changed at any time, or set by standing inside a
+//! [`MovementModeVolume`]. Flying and swimming both ignore gravity, move towards wherever the camera
+//! is looking, and go straight up or down with the ascend and descend bindings. Flying stops as
+//! soon as input does, while swimming builds up speed, coasts to a stop against drag and drifts by
+//! [`MovementModeSettings::buoyancy`] when idle.
+
+#![allow(clippy::useless_conversion)]
+use avian3d::math::{AdjustPrecision, AsF32};
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_tnua::TnuaToggle;
+use serde::{Deserialize, Serialize};
+
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::firstsight::{MovementDisabled, PlayerCamera, PlayerController};
+use crate::kinematic::KinematicController;
+use crate::swim::Swimming;
+
+pub(crate) struct MovementModePlugin;
+
+impl Plugin for MovementModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MovementModeSettings>()
+            .register_type::<MovementMode>()
+            .add_systems(
+                Update,
+                (apply_movement_mode_volumes, apply_movement_mode).chain(),
+            );
+    }
+}
+
+/// How the player moves.
+#[derive(
+    Component, Reflect, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash,
+)]
+#[reflect(Component)]
+pub enum MovementMode {
+    /// On the ground, under gravity, with jumping and crouching.
+    #[default]
+    Walk,
+    /// Free flight, stopping as soon as input does.
+    Fly,
+    /// Free movement with momentum, drag and buoyancy. The player also gets [`Swimming`].
+    Swim,
+}
+
+impl MovementMode {
+    pub fn is_free(self) -> bool {
+        self != MovementMode::Walk
+    }
+}
+
+/// Speeds and forces for flying and swimming.
+#[derive(Resource, Debug, Clone)]
+pub struct MovementModeSettings {
+    pub fly_speed: f32,
+    /// Speed swum at full input, once drag has balanced out the stroke.
+    pub swim_speed: f32,
+    /// How quickly swimming speed is shed, per second. Higher stops sooner.
+    pub swim_drag: f32,
+    /// Vertical acceleration while swimming, in units per second squared. Negative slowly sinks,
+    /// positive floats up.
+    pub buoyancy: f32,
+}
+
+impl Default for MovementModeSettings {
+    fn default() -> Self {
+        Self {
+            fly_speed: 12.0,
+            swim_speed: 5.0,
+            swim_drag: 2.0,
+            buoyancy: -0.3,
+        }
+    }
+}
+
+/// A box that switches the player into `mode` while they're inside it, and back to the mode they
+/// had before once they leave.
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct MovementModeVolume {
+    pub half_extents: Vec3,
+    pub mode: MovementMode,
+}
+
+impl MovementModeVolume {
+    pub fn new(half_extents: Vec3, mode: MovementMode) -> Self {
+        Self { half_extents, mode }
+    }
+
+    fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let local = transform.affine().inverse().transform_point3(point);
+        local.abs().cmple(self.half_extents).all()
+    }
+}
+
+/// The mode the player had before entering a [`MovementModeVolume`], to go back to on leaving.
+#[derive(Component, Debug)]
+struct ModeBeforeVolume(MovementMode);
+
+fn apply_movement_mode_volumes(
+    mut commands: Commands,
+    player: Single<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut MovementMode,
+            Option<&ModeBeforeVolume>,
+        ),
+        With<PlayerController>,
+    >,
+    volumes: Query<(&MovementModeVolume, &GlobalTransform)>,
+) {
+    let (entity, transform, mut mode, before) = player.into_inner();
+    let position = transform.translation();
+    let inside = volumes
+        .iter()
+        .find(|(volume, volume_transform)| volume.contains(volume_transform, position))
+        .map(|(volume, _)| volume.mode);
+    match (inside, before) {
+        (Some(volume_mode), before) => {
+            if before.is_none() {
+                commands.entity(entity).insert(ModeBeforeVolume(*mode));
+            }
+            mode.set_if_neq(volume_mode);
+        }
+        (None, Some(ModeBeforeVolume(previous))) => {
+            mode.set_if_neq(*previous);
+            commands.entity(entity).remove::<ModeBeforeVolume>();
+        }
+        (None, None) => {}
+    }
+}
+
+/// Hands a dynamic body over from Tnua and gravity when leaving walk mode, and back on returning to
+/// it.
+fn apply_movement_mode(
+    mut commands: Commands,
+    players: Query<
+        (Entity, &MovementMode, Has<KinematicController>),
+        (With<PlayerController>, Changed<MovementMode>),
+    >,
+) {
+    for (entity, mode, kinematic) in &players {
+        let mut player = commands.entity(entity);
+        if !kinematic {
+            if mode.is_free() {
+                player.insert((TnuaToggle::Disabled, GravityScale(0.0)));
+            } else {
+                player.insert((TnuaToggle::Enabled, GravityScale(1.0)));
+            }
+        }
+        if *mode == MovementMode::Swim {
+            player.insert(Swimming);
+        } else {
+            player.remove::<Swimming>();
+        }
+    }
+}
+
+/// Moves the player while flying or swimming, in place of walking.
+pub(crate) fn handle_free_movement(
+    time: Res<Time>,
+    input: ControlsInput,
+    controls: Res<ControlsConfig>,
+    settings: Res<MovementModeSettings>,
+    player: Single<
+        (
+            &MovementMode,
+            &mut LinearVelocity,
+            Option<&mut KinematicController>,
+        ),
+        (With<PlayerController>, Without<MovementDisabled>),
+    >,
+    player_camera: Single<&Transform, With<PlayerCamera>>,
+) {
+    let (mode, mut velocity, kinematic) = player.into_inner();
+    if !mode.is_free() {
+        return;
+    }
+
+    // Unlike walking, looking up or down moves up or down too
+    let forward = *player_camera.forward();
+    let right = player_camera.right().with_y(0.0).normalize_or_zero();
+    let mut wish = Vec3::ZERO;
+    if input.pressed(&controls.move_forward) {
+        wish += forward;
+    }
+    if input.pressed(&controls.move_back) {
+        wish -= forward;
+    }
+    if input.pressed(&controls.move_left) {
+        wish -= right;
+    }
+    if input.pressed(&controls.move_right) {
+        wish += right;
+    }
+    let stick = input.move_axis(controls.stick_deadzone);
+    wish += forward * stick.y + right * stick.x;
+    if input.pressed(&controls.ascend) {
+        wish += Vec3::Y;
+    }
+    if input.pressed(&controls.descend) {
+        wish -= Vec3::Y;
+    }
+    let wish = wish.clamp_length_max(1.0);
+
+    let dt = time.delta_secs();
+    let new_velocity = match mode {
+        MovementMode::Fly => wish * settings.fly_speed,
+        _ => {
+            // Strokes accelerate against drag, which settles at `swim_speed` under full input
+            let acceleration =
+                wish * settings.swim_speed * settings.swim_drag + Vec3::Y * settings.buoyancy;
+            (velocity.0.f32() + acceleration * dt) * (-settings.swim_drag * dt).exp()
+        }
+    };
+
+    match kinematic {
+        Some(mut kinematic) => kinematic.drive_free(new_velocity),
+        None => velocity.0 = new_velocity.adjust_precision(),
+    }
+}
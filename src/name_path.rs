@@ -0,0 +1,128 @@
+//! Looking up entities by the path of [`Name`]s from a root entity, e.g.
+//! `"Museum Root/Second Room/Display Areas/Pedestal 3"`.
+//!
+//! Scripting, console commands and scene files can use paths to refer to hand-built hierarchy
+//! nodes without holding on to [`Entity`] ids. Resolved paths are cached in [`NamePathCache`], which
+//! is cleared whenever an entity along a cached path is renamed, moved or despawned. Entities named
+//! or spawned elsewhere leave it alone, so a path cached before a same-named sibling is added
+//! keeps resolving to the original.
+
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+pub struct NamePathPlugin;
+
+impl Plugin for NamePathPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NamePathCache>()
+            .add_systems(PostUpdate, invalidate_name_paths);
+    }
+}
+
+/// Separates names in a path.
+pub const NAME_PATH_SEPARATOR: char = '/';
+
+/// Cache of resolved paths, shared by every [`NamePaths`].
+#[derive(Resource, Default, Debug)]
+pub struct NamePathCache {
+    resolved: HashMap<String, Entity>,
+    /// Every entity along a resolved path, from its root down.
+    along_paths: HashSet<Entity>,
+}
+
+impl NamePathCache {
+    pub fn clear(&mut self) {
+        self.resolved.clear();
+        self.along_paths.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.resolved.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resolved.is_empty()
+    }
+}
+
+/// Resolves entities by name path, and builds paths for entities.
+///
+/// If siblings share a name, the first one is used, so names along a path should be unique.
+#[derive(SystemParam)]
+pub struct NamePaths<'w, 's> {
+    cache: ResMut<'w, NamePathCache>,
+    roots: Query<'w, 's, (Entity, &'static Name), Without<ChildOf>>,
+    names: Query<'w, 's, &'static Name>,
+    children: Query<'w, 's, &'static Children>,
+    parents: Query<'w, 's, &'static ChildOf>,
+}
+
+impl NamePaths<'_, '_> {
+    /// The entity at `path`, if there is one. Leading, trailing and repeated separators are
+    /// ignored.
+    pub fn resolve(&mut self, path: &str) -> Option<Entity> {
+        // Entities despawned this frame aren't invalidated until PostUpdate
+        let cached = self.cache.resolved.get(path).copied();
+        if let Some(entity) = cached.filter(|entity| self.names.contains(*entity)) {
+            return Some(entity);
+        }
+        let mut segments = path
+            .split(NAME_PATH_SEPARATOR)
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty());
+        let root = segments.next()?;
+        let mut current = self
+            .roots
+            .iter()
+            .find(|(_, name)| name.as_str() == root)
+            .map(|(entity, _)| entity)?;
+        let mut along_path = vec![current];
+        for segment in segments {
+            current = self.child_named(current, segment)?;
+            along_path.push(current);
+        }
+        self.cache.resolved.insert(path.to_string(), current);
+        self.cache.along_paths.extend(along_path);
+        Some(current)
+    }
+
+    /// The path from `entity`'s root to `entity`, or `None` if it or an ancestor has no [`Name`].
+    pub fn path_of(&self, entity: Entity) -> Option<String> {
+        let mut names = vec![self.names.get(entity).ok()?.as_str()];
+        let mut current = entity;
+        while let Ok(child_of) = self.parents.get(current) {
+            current = child_of.parent();
+            names.push(self.names.get(current).ok()?.as_str());
+        }
+        names.reverse();
+        Some(names.join(&NAME_PATH_SEPARATOR.to_string()))
+    }
+
+    fn child_named(&self, parent: Entity, name: &str) -> Option<Entity> {
+        self.children.get(parent).ok()?.iter().find(|child| {
+            self.names
+                .get(*child)
+                .is_ok_and(|child_name| child_name.as_str() == name)
+        })
+    }
+}
+
+fn invalidate_name_paths(
+    mut cache: ResMut<NamePathCache>,
+    changed: Query<Entity, Or<(Changed<Name>, Changed<ChildOf>)>>,
+    mut unnamed: RemovedComponents<Name>,
+    mut orphaned: RemovedComponents<ChildOf>,
+) {
+    // Folded rather than short-circuited, so the removal readers are always drained
+    let stale = changed
+        .iter()
+        .chain(unnamed.read())
+        .chain(orphaned.read())
+        .fold(false, |stale, entity| {
+            stale || cache.along_paths.contains(&entity)
+        });
+    if stale {
+        cache.clear();
+    }
+}
@@ -0,0 +1,207 @@
+//! Nameplates floating over creatures and characters, so visitors can tell similar ones apart.
+//!
+//! Tagging an entity with [`Nameplate`] labels it with its [`DisplayName`], or its [`Name`] if it
+//! has none, while a [`NameplateSettings`] resource is inserted and enabled. Like hint labels,
+//! nameplates float over the top of their entity's collider, fade out with distance from the
+//! camera, and are hidden while colliders block the view of them or their entity is hidden. The
+//! pause menu can switch them on and off in scenes that have them.
+
+use avian3d::math::AsF32;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::firstsight::PlayerCamera;
+use crate::picking::is_occluded;
+use crate::player::Player;
+use crate::state::GameState;
+
+pub struct NameplatePlugin;
+
+impl Plugin for NameplatePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<NameplateSettings>()
+            .add_observer(spawn_nameplate)
+            .add_systems(Update, place_nameplates.run_if(in_state(GameState::Active)));
+    }
+}
+
+/// Floats a nameplate over this entity.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Nameplate;
+
+/// The name shown on an entity's [`Nameplate`], when its [`Name`] is only fit for debugging.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct DisplayName(pub String);
+
+impl DisplayName {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct NameplateSettings {
+    pub enabled: bool,
+    /// Nameplates further than this from the camera are hidden.
+    pub max_distance: f32,
+    /// Distance over which nameplates fade out before `max_distance`.
+    pub fade_distance: f32,
+    /// How far above its entity a nameplate floats, from the top of its collider if it has one.
+    pub height: f32,
+    /// Whether nameplates are hidden while colliders block the view of them.
+    pub occlusion: bool,
+    pub font_size: f32,
+    pub color: Color,
+}
+
+impl Default for NameplateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_distance: 15.0,
+            fade_distance: 5.0,
+            height: 0.4,
+            occlusion: true,
+            font_size: 14.0,
+            color: Color::srgb(0.85, 0.95, 1.0),
+        }
+    }
+}
+
+impl NameplateSettings {
+    pub fn with_distance(mut self, max_distance: f32, fade_distance: f32) -> Self {
+        self.max_distance = max_distance.max(0.0);
+        self.fade_distance = fade_distance.clamp(0.0, self.max_distance);
+        self
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn with_occlusion(mut self, occlusion: bool) -> Self {
+        self.occlusion = occlusion;
+        self
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+/// On a nameplate's text, pointing at the entity it names.
+#[derive(Component)]
+#[relationship(relationship_target = NameplateShown)]
+struct NameplateOf(Entity);
+
+/// On a named entity, pointing at its nameplate, which is despawned with it.
+#[derive(Component)]
+#[relationship_target(relationship = NameplateOf, linked_spawn)]
+struct NameplateShown(Entity);
+
+fn spawn_nameplate(add: On<Add, Nameplate>, mut commands: Commands) {
+    commands.spawn((
+        Name::new("Nameplate"),
+        NameplateOf(add.entity),
+        Text::default(),
+        TextFont::default(),
+        TextColor(Color::NONE),
+        Node {
+            position_type: PositionType::Absolute,
+            padding: UiRect::axes(Val::Px(5.0), Val::Px(2.0)),
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+fn place_nameplates(
+    settings: Option<Res<NameplateSettings>>,
+    spatial_query: SpatialQuery,
+    camera: Single<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    targets: Query<(
+        &GlobalTransform,
+        Option<&DisplayName>,
+        Option<&Name>,
+        Option<&ColliderAabb>,
+        Option<&InheritedVisibility>,
+    )>,
+    parents: Query<&ChildOf>,
+    players: Query<Entity, With<Player>>,
+    mut nameplates: Query<(
+        &NameplateOf,
+        &mut Node,
+        &mut Text,
+        &mut TextFont,
+        &mut TextColor,
+        &mut Visibility,
+        &ComputedNode,
+    )>,
+) {
+    let (camera, camera_transform) = *camera;
+    let origin = camera_transform.translation();
+    for (nameplate, mut node, mut text, mut font, mut color, mut visibility, computed) in
+        &mut nameplates
+    {
+        let (Some(settings), Ok((transform, display_name, name, aabb, inherited))) =
+            (settings.as_ref(), targets.get(nameplate.0))
+        else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        let top = aabb.map_or(transform.translation(), |aabb| {
+            transform.translation().with_y(aabb.max.y.f32())
+        });
+        let anchor = top + Vec3::Y * settings.height;
+        let distance = origin.distance(anchor);
+        let shown = settings.enabled
+            && distance <= settings.max_distance
+            && inherited.is_none_or(|inherited| inherited.get())
+            && !(settings.occlusion
+                && is_occluded(
+                    &spatial_query,
+                    &parents,
+                    &players,
+                    origin,
+                    anchor,
+                    nameplate.0,
+                ));
+        let position = camera
+            .world_to_viewport(camera_transform, anchor)
+            .ok()
+            .filter(|_| shown);
+        let Some(position) = position else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let label = display_name
+            .map(|display_name| display_name.0.as_str())
+            .or(name.map(Name::as_str))
+            .unwrap_or_default();
+        if text.0 != label {
+            text.0 = label.to_string();
+        }
+        if font.font_size != settings.font_size {
+            font.font_size = settings.font_size;
+        }
+        // Centred over the anchor, sized from the last layout
+        let size = computed.size() * computed.inverse_scale_factor();
+        node.left = Val::Px(position.x - size.x / 2.0);
+        node.top = Val::Px(position.y - size.y);
+        let opacity = ((settings.max_distance - distance)
+            / settings.fade_distance.max(f32::EPSILON))
+        .clamp(0.0, 1.0);
+        color.0 = settings.color.with_alpha(opacity);
+    }
+}
@@ -0,0 +1,597 @@
+//! Navigation grids baked from a scene's static colliders, and agents that find their way around
+//! them.
+//!
+//! A [`NavGrid`] resource covers a box of the scene with square cells, and once physics has
+//! started it's baked by casting down onto the static colliders in each cell, marking cells where
+//! the floor isn't too steep and there's room for an agent to stand. A [`NavAgent`] given a
+//! destination finds a path over the grid with A*, straightened wherever there's a clear line
+//! between cells, and walks it at a steady speed, following the floor up and down. Agents look
+//! ahead for anything in the way that wasn't baked, such as the player or a carried object, and
+//! find a way around it, waiting for it to move if there's none. They trigger
+//! [`DestinationReached`] on arriving, or [`DestinationUnreachable`] if there's no path at all, and
+//! hold still like a patrol while a [`LookAtPlayer`] is looking.
+//!
+//! The grid is a single layer, so it only covers the highest floor in each cell, and is baked once;
+//! call [`NavGrid::rebake`] after moving static colliders.
+
+#![allow(clippy::useless_conversion)]
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use avian3d::math::AsF32;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::game_time::GameTime;
+use crate::npc::LookAtPlayer;
+use crate::state::PausableSystems;
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                bake_nav_grid.run_if(resource_exists::<NavGrid>),
+                follow_nav_paths.in_set(PausableSystems),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Costs of moving to a neighbouring cell straight and diagonally, roughly 1 and √2 scaled up to
+/// whole numbers.
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+/// Furthest a point can be from a walkable cell, in cells, to path from it.
+const SNAP_CELLS: i32 = 2;
+
+/// Seconds an agent remembers something in its way for, when finding a way around it.
+const OBSTRUCTION_MEMORY: f32 = 5.0;
+
+/// Seconds an agent waits before trying again when something's in the only way through.
+const BLOCKED_WAIT: f32 = 1.0;
+
+#[derive(Resource, Debug, Clone)]
+pub struct NavGrid {
+    /// Middle of the box covered, in world space.
+    pub centre: Vec3,
+    pub half_extents: Vec3,
+    pub cell_size: f32,
+    /// Distance agents are kept from walls.
+    pub agent_radius: f32,
+    pub agent_height: f32,
+    /// Highest step agents can climb between neighbouring cells.
+    pub max_climb: f32,
+    /// Steepest floor agents can walk on, in radians.
+    pub max_slope: f32,
+    size: UVec2,
+    /// Floor height of each cell, by row along X, or `None` where agents can't stand.
+    floors: Vec<Option<f32>>,
+    baked: bool,
+}
+
+impl NavGrid {
+    pub fn new(centre: Vec3, half_extents: Vec3, cell_size: f32) -> Self {
+        let cell_size = cell_size.max(0.05);
+        let size = (half_extents.xz() * 2.0 / cell_size)
+            .ceil()
+            .max(Vec2::ONE)
+            .as_uvec2();
+        Self {
+            centre,
+            half_extents: half_extents.abs(),
+            cell_size,
+            agent_radius: 0.3,
+            agent_height: 1.8,
+            max_climb: 0.3,
+            max_slope: 35f32.to_radians(),
+            size,
+            floors: Vec::new(),
+            baked: false,
+        }
+    }
+
+    pub fn with_agent(mut self, radius: f32, height: f32) -> Self {
+        self.agent_radius = radius.max(0.0);
+        self.agent_height = height.max(self.max_climb + 0.01);
+        self
+    }
+
+    pub fn with_max_climb(mut self, max_climb: f32) -> Self {
+        self.max_climb = max_climb.clamp(0.0, self.agent_height - 0.01);
+        self
+    }
+
+    pub fn with_max_slope(mut self, max_slope: f32) -> Self {
+        self.max_slope = max_slope;
+        self
+    }
+
+    pub fn is_baked(&self) -> bool {
+        self.baked
+    }
+
+    /// Bakes the grid again next frame, e.g. after static colliders have moved.
+    pub fn rebake(&mut self) {
+        self.baked = false;
+    }
+
+    /// Height of the floor agents can stand on at `point`, if there's any.
+    pub fn floor_at(&self, point: Vec3) -> Option<f32> {
+        self.floors.get(self.cell(point)?).copied().flatten()
+    }
+
+    /// Points to walk through from `from` to `to`, not including `from`, or `None` if there's no
+    /// way there.
+    pub fn find_path(&self, from: Vec3, to: Vec3) -> Option<Vec<Vec3>> {
+        self.find_path_avoiding(from, to, |_| false)
+    }
+
+    /// Like [`NavGrid::find_path`], but treating cells whose middle is in `avoid` as blocked.
+    pub fn find_path_avoiding(
+        &self,
+        from: Vec3,
+        to: Vec3,
+        avoid: impl Fn(Vec3) -> bool,
+    ) -> Option<Vec<Vec3>> {
+        let start = self.nearest_cell(from)?;
+        let goal = self.nearest_cell(to)?;
+        // Agents can always leave the cell they're in, even if something's in it with them
+        let passable = |cell: usize| {
+            cell == start || (self.floors[cell].is_some() && !avoid(self.cell_point(cell)))
+        };
+        if !passable(goal) {
+            return None;
+        }
+
+        let mut costs = vec![u32::MAX; self.floors.len()];
+        let mut came_from = vec![usize::MAX; self.floors.len()];
+        let mut open = BinaryHeap::new();
+        costs[start] = 0;
+        open.push(Reverse((self.estimate(start, goal), start)));
+        while let Some(Reverse((_, cell))) = open.pop() {
+            if cell == goal {
+                break;
+            }
+            for (neighbour, step) in self.neighbours(cell, &passable) {
+                let cost = costs[cell].saturating_add(step);
+                if cost < costs[neighbour] {
+                    costs[neighbour] = cost;
+                    came_from[neighbour] = cell;
+                    let estimate = cost.saturating_add(self.estimate(neighbour, goal));
+                    open.push(Reverse((estimate, neighbour)));
+                }
+            }
+        }
+        if costs[goal] == u32::MAX {
+            return None;
+        }
+
+        let mut cells = vec![goal];
+        while let Some(&cell) = cells.last().filter(|&&cell| cell != start) {
+            cells.push(came_from[cell]);
+        }
+        cells.reverse();
+
+        // Skips every cell that can be walked past in a straight line
+        let mut path = Vec::new();
+        let mut anchor = 0;
+        while anchor + 1 < cells.len() {
+            let next = (anchor + 2..cells.len())
+                .rev()
+                .find(|&index| self.is_clear(cells[anchor], cells[index], &passable))
+                .unwrap_or(anchor + 1);
+            path.push(self.cell_point(cells[next]));
+            anchor = next;
+        }
+        // Ends right at the destination if it's on the grid
+        let on_goal = self.cell(to) == Some(goal);
+        if let (Some(last), Some(floor), true) = (path.last_mut(), self.floor_at(to), on_goal) {
+            *last = Vec3::new(to.x, floor, to.z);
+        }
+        Some(path)
+    }
+
+    fn bake(&mut self, spatial_query: &SpatialQuery, fixed: impl Fn(Entity) -> bool) {
+        let clear_height = self.agent_height - self.max_climb;
+        let clearance = Collider::cylinder(self.agent_radius.into(), clear_height.into());
+        let top = self.centre.y + self.half_extents.y;
+        let depth = self.half_extents.y * 2.0;
+        let filter = SpatialQueryFilter::default();
+        let min_slope = self.max_slope.cos();
+
+        let count = self.size.x as usize * self.size.y as usize;
+        self.floors = (0..count)
+            .map(|cell| {
+                let xz = self.cell_centre(cell);
+                let hit = spatial_query.cast_ray_predicate(
+                    Vec3::new(xz.x, top, xz.y).into(),
+                    Dir3::NEG_Y,
+                    depth.into(),
+                    true,
+                    &filter,
+                    &fixed,
+                )?;
+                // Also rules out starting inside a collider, which hits with no normal
+                if hit.normal.f32().y < min_slope {
+                    return None;
+                }
+                let floor = top - hit.distance.f32();
+                let body = Vec3::new(xz.x, floor + self.max_climb + clear_height / 2.0, xz.y);
+                let blocked = spatial_query
+                    .shape_intersections(&clearance, body.into(), Quat::IDENTITY.into(), &filter)
+                    .into_iter()
+                    .any(&fixed);
+                (!blocked).then_some(floor)
+            })
+            .collect();
+        self.baked = true;
+        let walkable = self.floors.iter().flatten().count();
+        debug!("Baked navigation grid with {walkable} of {count} cells walkable");
+    }
+
+    fn cell(&self, point: Vec3) -> Option<usize> {
+        let corner = self.centre.xz() - self.half_extents.xz();
+        let cell = ((point.xz() - corner) / self.cell_size).floor();
+        let in_grid = cell.cmpge(Vec2::ZERO).all() && cell.cmplt(self.size.as_vec2()).all();
+        let cell = cell.as_uvec2();
+        in_grid.then(|| cell.y as usize * self.size.x as usize + cell.x as usize)
+    }
+
+    fn coordinates(&self, cell: usize) -> IVec2 {
+        let width = self.size.x as usize;
+        IVec2::new((cell % width) as i32, (cell / width) as i32)
+    }
+
+    fn index(&self, coordinates: IVec2) -> Option<usize> {
+        let in_grid =
+            coordinates.cmpge(IVec2::ZERO).all() && coordinates.cmplt(self.size.as_ivec2()).all();
+        in_grid.then(|| coordinates.y as usize * self.size.x as usize + coordinates.x as usize)
+    }
+
+    fn cell_centre(&self, cell: usize) -> Vec2 {
+        let corner = self.centre.xz() - self.half_extents.xz();
+        corner + (self.coordinates(cell).as_vec2() + 0.5) * self.cell_size
+    }
+
+    /// Middle of a cell, on its floor.
+    fn cell_point(&self, cell: usize) -> Vec3 {
+        let xz = self.cell_centre(cell);
+        let floor = self
+            .floors
+            .get(cell)
+            .copied()
+            .flatten()
+            .unwrap_or(self.centre.y);
+        Vec3::new(xz.x, floor, xz.y)
+    }
+
+    /// The walkable cell nearest `point`, if one's close by.
+    fn nearest_cell(&self, point: Vec3) -> Option<usize> {
+        let corner = self.centre.xz() - self.half_extents.xz();
+        let centre = ((point.xz() - corner) / self.cell_size).floor().as_ivec2();
+        (-SNAP_CELLS..=SNAP_CELLS)
+            .flat_map(|x| (-SNAP_CELLS..=SNAP_CELLS).map(move |y| IVec2::new(x, y)))
+            .filter_map(|offset| self.index(centre + offset))
+            .filter(|&cell| self.floors.get(cell).is_some_and(Option::is_some))
+            .min_by(|&a, &b| {
+                let a = self.cell_centre(a).distance_squared(point.xz());
+                let b = self.cell_centre(b).distance_squared(point.xz());
+                a.total_cmp(&b)
+            })
+    }
+
+    /// Lower bound on the cost from `cell` to `goal`, moving diagonally as much as possible.
+    fn estimate(&self, cell: usize, goal: usize) -> u32 {
+        let apart = (self.coordinates(goal) - self.coordinates(cell))
+            .abs()
+            .as_uvec2();
+        let diagonal = apart.min_element();
+        let straight = apart.max_element() - diagonal;
+        diagonal * DIAGONAL_COST + straight * STRAIGHT_COST
+    }
+
+    /// Cells that can be stepped to from `cell`, and the cost of each step. Corners can't be cut.
+    fn neighbours<'a>(
+        &'a self,
+        cell: usize,
+        passable: &'a impl Fn(usize) -> bool,
+    ) -> impl Iterator<Item = (usize, u32)> + 'a {
+        let here = self.coordinates(cell);
+        let step = move |offset: IVec2| {
+            let neighbour = self.index(here + offset)?;
+            let climbable = self.climbable(cell, neighbour);
+            (passable(neighbour) && climbable).then_some(neighbour)
+        };
+        (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |y| IVec2::new(x, y)))
+            .filter(|&offset| offset != IVec2::ZERO)
+            .filter_map(move |offset| {
+                if offset.x != 0 && offset.y != 0 {
+                    step(offset.with_y(0))?;
+                    step(offset.with_x(0))?;
+                    Some((step(offset)?, DIAGONAL_COST))
+                } else {
+                    Some((step(offset)?, STRAIGHT_COST))
+                }
+            })
+    }
+
+    fn climbable(&self, from: usize, to: usize) -> bool {
+        match (self.floors[from], self.floors[to]) {
+            (Some(from), Some(to)) => (to - from).abs() <= self.max_climb,
+            _ => false,
+        }
+    }
+
+    /// Whether there's a straight walk from the middle of `from` to the middle of `to`.
+    fn is_clear(&self, from: usize, to: usize, passable: &impl Fn(usize) -> bool) -> bool {
+        let start = self.cell_centre(from);
+        let end = self.cell_centre(to);
+        let samples = (start.distance(end) / (self.cell_size * 0.25)).ceil() as usize;
+        let mut previous = from;
+        for sample in 1..=samples {
+            let xz = start.lerp(end, sample as f32 / samples as f32);
+            let Some(cell) = self.cell(Vec3::new(xz.x, 0.0, xz.y)) else {
+                return false;
+            };
+            if cell == previous {
+                continue;
+            }
+            if !passable(cell) || !self.climbable(previous, cell) {
+                return false;
+            }
+            previous = cell;
+        }
+        true
+    }
+}
+
+/// Walks its entity to a destination set with [`NavAgent::set_destination`], finding a way
+/// around walls and whatever else is in the way over the scene's [`NavGrid`].
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct NavAgent {
+    /// Units per second.
+    pub speed: f32,
+    /// Radians per second to turn towards the direction of travel.
+    pub turn_speed: f32,
+    /// Height of the entity's origin above the floor it walks on.
+    pub ground_offset: f32,
+    /// How far ahead it looks for things in its way.
+    pub lookahead: f32,
+    destination: Option<Vec3>,
+    path: Vec<Vec3>,
+    needs_path: bool,
+    waiting: f32,
+    obstructions: Vec<Obstruction>,
+}
+
+/// The footprint of something found in an agent's way, and when it was found.
+#[derive(Debug, Clone, Reflect)]
+struct Obstruction {
+    min: Vec2,
+    max: Vec2,
+    found: f32,
+}
+
+impl NavAgent {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            speed: speed.max(0.0),
+            turn_speed: 4.0,
+            ground_offset: 0.0,
+            lookahead: 1.5,
+            destination: None,
+            path: Vec::new(),
+            needs_path: false,
+            waiting: 0.0,
+            obstructions: Vec::new(),
+        }
+    }
+
+    pub fn with_turn_speed(mut self, turn_speed: f32) -> Self {
+        self.turn_speed = turn_speed.max(0.0);
+        self
+    }
+
+    pub fn with_ground_offset(mut self, ground_offset: f32) -> Self {
+        self.ground_offset = ground_offset;
+        self
+    }
+
+    pub fn with_lookahead(mut self, lookahead: f32) -> Self {
+        self.lookahead = lookahead.max(0.0);
+        self
+    }
+
+    /// Sets off for `destination`, finding a path there next frame.
+    pub fn set_destination(&mut self, destination: Vec3) {
+        self.destination = Some(destination);
+        self.path.clear();
+        self.needs_path = true;
+        self.waiting = 0.0;
+        self.obstructions.clear();
+    }
+
+    /// Stops where it is.
+    pub fn clear_destination(&mut self) {
+        self.destination = None;
+        self.path.clear();
+        self.needs_path = false;
+    }
+
+    pub fn destination(&self) -> Option<Vec3> {
+        self.destination
+    }
+
+    /// Points still to walk through, on the floor, ending at the destination.
+    pub fn path(&self) -> &[Vec3] {
+        &self.path
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.destination.is_some()
+    }
+}
+
+/// Triggered on a [`NavAgent`] when it arrives at its destination.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct DestinationReached {
+    pub entity: Entity,
+}
+
+/// Triggered on a [`NavAgent`] when there's no path to its destination, which it gives up on.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct DestinationUnreachable {
+    pub entity: Entity,
+}
+
+/// Whether `entity` is part of a static body, so baked into the grid.
+fn is_fixed(
+    entity: Entity,
+    colliders: &Query<(Option<&ColliderOf>, Has<Sensor>)>,
+    bodies: &Query<&RigidBody>,
+) -> bool {
+    colliders.get(entity).is_ok_and(|(collider_of, sensor)| {
+        // A collider without a body can't move either
+        !sensor
+            && collider_of.is_none_or(|collider_of| {
+                bodies.get(collider_of.body).is_ok_and(RigidBody::is_static)
+            })
+    })
+}
+
+fn bake_nav_grid(
+    mut grid: ResMut<NavGrid>,
+    physics_time: Res<Time<Physics>>,
+    spatial_query: SpatialQuery,
+    colliders: Query<(Option<&ColliderOf>, Has<Sensor>)>,
+    bodies: Query<&RigidBody>,
+) {
+    // Colliders can only be queried once physics has stepped
+    if grid.baked || physics_time.elapsed_secs() <= 0.0 {
+        return;
+    }
+    grid.bake(&spatial_query, |entity| {
+        is_fixed(entity, &colliders, &bodies)
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn follow_nav_paths(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    grid: Option<Res<NavGrid>>,
+    spatial_query: SpatialQuery,
+    colliders: Query<(Option<&ColliderOf>, Has<Sensor>)>,
+    bodies: Query<&RigidBody>,
+    aabbs: Query<&ColliderAabb>,
+    mut agents: Query<(Entity, &mut Transform, &mut NavAgent, Option<&LookAtPlayer>)>,
+) {
+    let Some(grid) = grid.filter(|grid| grid.is_baked()) else {
+        return;
+    };
+    let dt = time.delta_secs();
+    let now = time.elapsed_secs();
+    for (entity, mut transform, mut agent, look) in &mut agents {
+        let Some(destination) = agent.destination else {
+            continue;
+        };
+        if look.is_some_and(|look| look.is_looking() && look.holds_patrol) {
+            continue;
+        }
+        if agent.waiting > 0.0 {
+            agent.waiting -= dt;
+            continue;
+        }
+        let feet = transform.translation - Vec3::Y * agent.ground_offset;
+
+        if agent.needs_path {
+            agent
+                .obstructions
+                .retain(|obstruction| now - obstruction.found < OBSTRUCTION_MEMORY);
+            let margin = Vec2::splat(grid.agent_radius);
+            let obstructions = &agent.obstructions;
+            let path = grid.find_path_avoiding(feet, destination, |point| {
+                obstructions.iter().any(|obstruction| {
+                    let point = point.xz();
+                    point.cmpge(obstruction.min - margin).all()
+                        && point.cmple(obstruction.max + margin).all()
+                })
+            });
+            match path {
+                Some(path) => {
+                    agent.path = path;
+                    agent.needs_path = false;
+                }
+                // Something's in the only way through, so wait for it to move
+                None if !agent.obstructions.is_empty() => {
+                    agent.waiting = BLOCKED_WAIT;
+                    continue;
+                }
+                None => {
+                    agent.clear_destination();
+                    commands.trigger(DestinationUnreachable { entity });
+                    continue;
+                }
+            }
+        }
+
+        let Some(&next) = agent.path.first() else {
+            agent.clear_destination();
+            commands.trigger(DestinationReached { entity });
+            continue;
+        };
+
+        // Looks for anything in the way at waist height that wasn't baked into the grid
+        let ahead = (next - feet).with_y(0.0);
+        let waist = feet + Vec3::Y * (grid.max_climb + grid.agent_height) / 2.0;
+        let obstruction = Dir3::new(ahead)
+            .ok()
+            .filter(|_| agent.lookahead > 0.0)
+            .and_then(|direction| {
+                spatial_query.cast_ray_predicate(
+                    waist.into(),
+                    direction,
+                    (ahead.length() + grid.agent_radius)
+                        .min(agent.lookahead)
+                        .into(),
+                    true,
+                    &SpatialQueryFilter::from_excluded_entities([entity]),
+                    &|hit| !is_fixed(hit, &colliders, &bodies),
+                )
+            })
+            .and_then(|hit| aabbs.get(hit.entity).ok());
+        if let Some(aabb) = obstruction {
+            agent.obstructions.push(Obstruction {
+                min: aabb.min.f32().xz(),
+                max: aabb.max.f32().xz(),
+                found: now,
+            });
+            agent.needs_path = true;
+            continue;
+        }
+
+        let target = next + Vec3::Y * agent.ground_offset;
+        let offset = target - transform.translation;
+        let step = agent.speed * dt;
+        if offset.length() <= step {
+            transform.translation = target;
+            agent.path.remove(0);
+            continue;
+        }
+        transform.translation += offset.normalize() * step;
+        if let Ok(direction) = Dir3::new(offset.with_y(0.0)) {
+            let facing = Transform::IDENTITY.looking_to(direction, Vec3::Y).rotation;
+            transform.rotation = transform
+                .rotation
+                .rotate_towards(facing, agent.turn_speed * dt);
+        }
+    }
+}
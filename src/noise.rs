@@ -0,0 +1,128 @@
+//! Seeded 2D and 3D Perlin gradient noise, as used to shape [`Heightmap`]s and [`Rock`]s.
+//!
+//! [`Perlin::get`] and [`Perlin::get_3d`] are roughly from -1 to 1, smooth between whole-number
+//! points and 0 on them.
+//!
+//! [`Heightmap`]: crate::terrain::Heightmap
+//! [`Rock`]: crate::rock::Rock
+
+use crate::rng::SplitMix64;
+
+#[derive(Debug, Clone)]
+pub struct Perlin {
+    /// The numbers 0 to 255 shuffled by the seed, twice over so lookups can run past the end.
+    permutation: [u8; 512],
+}
+
+impl Perlin {
+    pub fn new(seed: u32) -> Self {
+        let mut rng = SplitMix64::new(u64::from(seed).wrapping_add(0x9E37_79B9_7F4A_7C15));
+        let mut shuffled: [u8; 256] = std::array::from_fn(|i| i as u8);
+        for i in (1..256).rev() {
+            shuffled.swap(i, rng.index(i + 1));
+        }
+        Self {
+            permutation: std::array::from_fn(|i| shuffled[i % 256]),
+        }
+    }
+
+    /// Noise at `point`, roughly from -1 to 1.
+    pub fn get(&self, point: [f64; 2]) -> f64 {
+        let [x, y] = point;
+        let (x_floor, y_floor) = (x.floor(), y.floor());
+        let xi = (x_floor as i64 & 255) as usize;
+        let yi = (y_floor as i64 & 255) as usize;
+        let (x, y) = (x - x_floor, y - y_floor);
+        let (u, v) = (fade(x), fade(y));
+
+        let p = &self.permutation;
+        let a = p[xi] as usize + yi;
+        let b = p[xi + 1] as usize + yi;
+        let bottom = lerp(gradient(p[a], x, y), gradient(p[b], x - 1.0, y), u);
+        let top = lerp(
+            gradient(p[a + 1], x, y - 1.0),
+            gradient(p[b + 1], x - 1.0, y - 1.0),
+            u,
+        );
+        lerp(bottom, top, v)
+    }
+
+    /// Noise at a 3D `point`, roughly from -1 to 1.
+    pub fn get_3d(&self, point: [f64; 3]) -> f64 {
+        let [x, y, z] = point;
+        let (x_floor, y_floor, z_floor) = (x.floor(), y.floor(), z.floor());
+        let xi = (x_floor as i64 & 255) as usize;
+        let yi = (y_floor as i64 & 255) as usize;
+        let zi = (z_floor as i64 & 255) as usize;
+        let (x, y, z) = (x - x_floor, y - y_floor, z - z_floor);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let p = &self.permutation;
+        let a = p[xi] as usize + yi;
+        let b = p[xi + 1] as usize + yi;
+        let (aa, ab) = (p[a] as usize + zi, p[a + 1] as usize + zi);
+        let (ba, bb) = (p[b] as usize + zi, p[b + 1] as usize + zi);
+        let near = lerp(
+            lerp(
+                gradient_3d(p[aa], x, y, z),
+                gradient_3d(p[ba], x - 1.0, y, z),
+                u,
+            ),
+            lerp(
+                gradient_3d(p[ab], x, y - 1.0, z),
+                gradient_3d(p[bb], x - 1.0, y - 1.0, z),
+                u,
+            ),
+            v,
+        );
+        let far = lerp(
+            lerp(
+                gradient_3d(p[aa + 1], x, y, z - 1.0),
+                gradient_3d(p[ba + 1], x - 1.0, y, z - 1.0),
+                u,
+            ),
+            lerp(
+                gradient_3d(p[ab + 1], x, y - 1.0, z - 1.0),
+                gradient_3d(p[bb + 1], x - 1.0, y - 1.0, z - 1.0),
+                u,
+            ),
+            v,
+        );
+        lerp(near, far, w)
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + t * (b - a)
+}
+
+fn gradient(hash: u8, x: f64, y: f64) -> f64 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+/// Dot product with one of the twelve cube edge directions, picked by `hash`.
+fn gradient_3d(hash: u8, x: f64, y: f64, z: f64) -> f64 {
+    let hash = hash & 15;
+    let u = if hash < 8 { x } else { y };
+    let v = match hash {
+        0..4 => y,
+        12 | 14 => x,
+        _ => z,
+    };
+    let u = if hash & 1 == 0 { u } else { -u };
+    let v = if hash & 2 == 0 { v } else { -v };
+    u + v
+}
@@ -0,0 +1,233 @@
+//! Simple behaviours for characters and creatures that wander a scene.
+//!
+//! A [`WaypointPatrol`] walks its entity through a list of waypoints at a steady speed, turning to
+//! face where it's going, and either loops back to the start or turns around at the end. It can
+//! idle for a while at each waypoint, and triggers [`WaypointReached`] on arriving. A
+//! [`LookAtPlayer`] turns its entity to face the player whenever they come within range, and by
+//! default holds any patrol while it does, so a docent stops to greet visitors.
+
+use bevy::prelude::*;
+
+use crate::game_time::GameTime;
+use crate::player::Player;
+use crate::state::PausableSystems;
+
+pub struct NpcPlugin;
+
+impl Plugin for NpcPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (look_at_player, follow_patrols)
+                .chain()
+                .in_set(PausableSystems),
+        );
+    }
+}
+
+/// What a [`WaypointPatrol`] does after its last waypoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum PatrolMode {
+    /// Carries on to the first waypoint and round again.
+    #[default]
+    Loop,
+    /// Turns around and goes back through the waypoints in reverse.
+    PingPong,
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct WaypointPatrol {
+    /// Positions to visit in order, in world space as long as the entity has no parent.
+    pub waypoints: Vec<Vec3>,
+    /// Units per second.
+    pub speed: f32,
+    pub mode: PatrolMode,
+    /// Seconds to idle at each waypoint before moving on.
+    pub pause_seconds: f32,
+    /// Radians per second to turn towards the direction of travel.
+    pub turn_speed: f32,
+    target: usize,
+    reversing: bool,
+    paused: f32,
+}
+
+impl WaypointPatrol {
+    pub fn new(waypoints: impl IntoIterator<Item = Vec3>, speed: f32) -> Self {
+        Self {
+            waypoints: waypoints.into_iter().collect(),
+            speed: speed.max(0.0),
+            mode: PatrolMode::Loop,
+            pause_seconds: 0.0,
+            turn_speed: 3.0,
+            target: 0,
+            reversing: false,
+            paused: 0.0,
+        }
+    }
+
+    pub fn ping_pong(mut self) -> Self {
+        self.mode = PatrolMode::PingPong;
+        self
+    }
+
+    pub fn with_pause(mut self, seconds: f32) -> Self {
+        self.pause_seconds = seconds.max(0.0);
+        self
+    }
+
+    pub fn with_turn_speed(mut self, turn_speed: f32) -> Self {
+        self.turn_speed = turn_speed.max(0.0);
+        self
+    }
+
+    /// Index of the waypoint being headed for.
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    /// Whether the patrol is idling at a waypoint.
+    pub fn is_paused(&self) -> bool {
+        self.paused > 0.0
+    }
+
+    fn advance(&mut self) {
+        let count = self.waypoints.len();
+        if count < 2 {
+            return;
+        }
+        match self.mode {
+            PatrolMode::Loop => self.target = (self.target + 1) % count,
+            PatrolMode::PingPong => {
+                let at_end = if self.reversing {
+                    self.target == 0
+                } else {
+                    self.target == count - 1
+                };
+                if at_end {
+                    self.reversing = !self.reversing;
+                }
+                if self.reversing {
+                    self.target -= 1;
+                } else {
+                    self.target += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Triggered on a [`WaypointPatrol`] when it arrives at a waypoint.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct WaypointReached {
+    pub entity: Entity,
+    pub index: usize,
+}
+
+/// Turns to face the player while they're within range, only about the vertical axis so it stays
+/// upright.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct LookAtPlayer {
+    pub range: f32,
+    /// Radians per second to turn towards the player.
+    pub turn_speed: f32,
+    /// Whether to hold a [`WaypointPatrol`] while looking at the player.
+    pub holds_patrol: bool,
+    looking: bool,
+}
+
+impl LookAtPlayer {
+    pub fn new(range: f32) -> Self {
+        Self {
+            range,
+            turn_speed: 4.0,
+            holds_patrol: true,
+            looking: false,
+        }
+    }
+
+    pub fn with_turn_speed(mut self, turn_speed: f32) -> Self {
+        self.turn_speed = turn_speed.max(0.0);
+        self
+    }
+
+    /// Keeps patrolling while looking, e.g. for a creature that just follows the player with its
+    /// eyes.
+    pub fn without_holding_patrol(mut self) -> Self {
+        self.holds_patrol = false;
+        self
+    }
+
+    pub fn is_looking(&self) -> bool {
+        self.looking
+    }
+}
+
+fn look_at_player(
+    time: Res<GameTime>,
+    player: Single<&GlobalTransform, With<Player>>,
+    mut lookers: Query<(&mut Transform, &mut LookAtPlayer)>,
+) {
+    let player = player.translation();
+    for (mut transform, mut look) in &mut lookers {
+        let to_player = (player - transform.translation).with_y(0.0);
+        let looking = to_player.length_squared() <= look.range * look.range
+            && to_player.length_squared() > f32::EPSILON;
+        if look.looking != looking {
+            look.looking = looking;
+        }
+        if !looking {
+            continue;
+        }
+        let facing = Transform::IDENTITY.looking_to(to_player, Vec3::Y).rotation;
+        transform.rotation = transform
+            .rotation
+            .rotate_towards(facing, look.turn_speed * time.delta_secs());
+    }
+}
+
+fn follow_patrols(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut patrols: Query<(
+        Entity,
+        &mut Transform,
+        &mut WaypointPatrol,
+        Option<&LookAtPlayer>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut transform, mut patrol, look) in &mut patrols {
+        if look.is_some_and(|look| look.is_looking() && look.holds_patrol) {
+            continue;
+        }
+        if patrol.paused > 0.0 {
+            patrol.paused -= dt;
+            continue;
+        }
+        let Some(&target) = patrol.waypoints.get(patrol.target) else {
+            continue;
+        };
+        let offset = target - transform.translation;
+        let step = patrol.speed * dt;
+        if offset.length() <= step {
+            transform.translation = target;
+            commands.trigger(WaypointReached {
+                entity,
+                index: patrol.target,
+            });
+            patrol.paused = patrol.pause_seconds;
+            patrol.advance();
+            continue;
+        }
+        let direction = offset.normalize();
+        transform.translation += direction * step;
+        let facing = Transform::IDENTITY.looking_to(direction, Vec3::Y).rotation;
+        transform.rotation = transform
+            .rotation
+            .rotate_towards(facing, patrol.turn_speed * dt);
+    }
+}
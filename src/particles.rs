@@ -0,0 +1,721 @@
+//! Particle effects: emitters spawning short-lived particles, drawn as one mesh per emitter.
+//!
+//! A [`ParticleEmitter`] spawns particles from within its [`EmitterShape`], steadily at its `rate`
+//! and all at once in a `burst` when it's added. Each particle lives for its lifetime, moving off
+//! at its starting velocity under `acceleration` and `drag`, with its speed, size and colour
+//! following [`LifeCurve`]s and a [`LifeGradient`] from birth to death. Particles aren't entities:
+//! they're kept in a pool on their emitter, reused as they die and spawn, and written into a single
+//! mesh of quads facing the player camera every frame, so an emitter is one draw call however many
+//! particles it has. Quads are drawn with the emitter's [`ParticleSprite`], such as a soft round
+//! dot or a leaf, tinted by the gradient through vertex colours and optionally spinning.
+//!
+//! Particles move on [`GameTime`], so they freeze while the game's paused, and emitters that are
+//! [`Culled`] stop simulating and drawing until they're back. Start from a preset such as
+//! [`ParticleEmitter::bubbles`] and adjust it with the `with_*` methods.
+
+use std::f32::consts::TAU;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::NoFrustumCulling;
+use bevy::color::ColorToComponents;
+use bevy::light::NotShadowCaster;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::cull::Culled;
+use crate::firstsight::PlayerCamera;
+use crate::game_time::GameTime;
+use crate::rng::SplitMix64;
+use crate::state::PausableSystems;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ParticleEmitter>()
+            .init_resource::<ParticleMaterials>()
+            .add_observer(add_particle_mesh)
+            .add_systems(
+                Update,
+                (simulate_particles, build_particle_meshes)
+                    .chain()
+                    .in_set(PausableSystems),
+            );
+    }
+}
+
+/// Step used to run a prewarming emitter forward, in seconds.
+const PREWARM_STEP: f32 = 0.1;
+/// Width and height of the textures particles are drawn with.
+const SPRITE_SIZE: u32 = 32;
+
+/// Where within the emitter particles spawn, centred on it in its local space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+pub enum EmitterShape {
+    #[default]
+    Point,
+    Sphere {
+        radius: f32,
+    },
+    Box {
+        half_extents: Vec3,
+    },
+}
+
+impl EmitterShape {
+    fn sample(self, rng: &mut SplitMix64) -> Vec3 {
+        let mut unit = || {
+            Vec3::new(
+                rng.range_f32(-1.0, 1.0),
+                rng.range_f32(-1.0, 1.0),
+                rng.range_f32(-1.0, 1.0),
+            )
+        };
+        match self {
+            EmitterShape::Point => Vec3::ZERO,
+            EmitterShape::Sphere { radius } => loop {
+                let point = unit();
+                if point.length_squared() <= 1.0 {
+                    break point * radius;
+                }
+            },
+            EmitterShape::Box { half_extents } => unit() * half_extents,
+        }
+    }
+}
+
+/// What each particle's quad is drawn as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum ParticleSprite {
+    /// A dot, solid in the middle and softening towards the edge.
+    #[default]
+    Dot,
+    /// A pointed leaf or petal, lying along the quad's width.
+    Leaf,
+}
+
+impl ParticleSprite {
+    /// Opacity at `offset` from the sprite's centre, in `-1..=1` on each axis.
+    fn alpha(self, offset: Vec2) -> f32 {
+        match self {
+            ParticleSprite::Dot => ((1.0 - offset.length()) * 2.0).clamp(0.0, 1.0),
+            ParticleSprite::Leaf => {
+                let half_width = 0.5 * (1.0 - offset.x * offset.x);
+                ((half_width - offset.y.abs()) * 8.0).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+/// Whether spawned particles are left behind as the emitter moves, or carried along with it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum ParticleSpace {
+    /// Particles move through the world once spawned, such as a trail of smoke.
+    #[default]
+    World,
+    /// Particles move relative to the emitter, such as sparks circling a torch someone's carrying.
+    Local,
+}
+
+/// A value over a particle's life, keyed by the fraction of its life lived, from 0 at birth to 1 at
+/// death, and blended linearly between keys.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct LifeCurve(Vec<(f32, f32)>);
+
+impl LifeCurve {
+    /// Keys of life fraction and value, in any order.
+    pub fn new(keys: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        Self(sorted_keys(keys))
+    }
+
+    pub fn constant(value: f32) -> Self {
+        Self(vec![(0.0, value)])
+    }
+
+    pub fn linear(start: f32, end: f32) -> Self {
+        Self(vec![(0.0, start), (1.0, end)])
+    }
+
+    pub fn sample(&self, life: f32) -> f32 {
+        sample_keys(&self.0, life, |a, b, t| a + (b - a) * t).unwrap_or(1.0)
+    }
+}
+
+/// A colour over a particle's life, keyed like a [`LifeCurve`]. Colours brighter than 1 glow with
+/// bloom.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct LifeGradient(Vec<(f32, LinearRgba)>);
+
+impl LifeGradient {
+    /// Keys of life fraction and colour, in any order.
+    pub fn new<C: Into<LinearRgba>>(keys: impl IntoIterator<Item = (f32, C)>) -> Self {
+        Self(sorted_keys(
+            keys.into_iter().map(|(life, color)| (life, color.into())),
+        ))
+    }
+
+    pub fn constant(color: impl Into<LinearRgba>) -> Self {
+        Self(vec![(0.0, color.into())])
+    }
+
+    pub fn linear(start: impl Into<LinearRgba>, end: impl Into<LinearRgba>) -> Self {
+        Self(vec![(0.0, start.into()), (1.0, end.into())])
+    }
+
+    /// `color`, fading to transparent by the end of a particle's life.
+    pub fn fade_out(color: impl Into<LinearRgba>) -> Self {
+        let color = color.into();
+        Self::linear(color, color.with_alpha(0.0))
+    }
+
+    /// `color`, fading in from transparent over the first `fade` of a particle's life and back out
+    /// over the last.
+    pub fn fade_in_out(color: impl Into<LinearRgba>, fade: f32) -> Self {
+        let color = color.into();
+        let fade = fade.clamp(0.0, 0.5);
+        Self(vec![
+            (0.0, color.with_alpha(0.0)),
+            (fade, color),
+            (1.0 - fade, color),
+            (1.0, color.with_alpha(0.0)),
+        ])
+    }
+
+    pub fn sample(&self, life: f32) -> LinearRgba {
+        sample_keys(&self.0, life, |a, b, t| a * (1.0 - t) + b * t).unwrap_or(LinearRgba::WHITE)
+    }
+}
+
+fn sorted_keys<T>(keys: impl IntoIterator<Item = (f32, T)>) -> Vec<(f32, T)> {
+    let mut keys: Vec<_> = keys
+        .into_iter()
+        .map(|(life, value)| (life.clamp(0.0, 1.0), value))
+        .collect();
+    keys.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    keys
+}
+
+fn sample_keys<T: Copy>(keys: &[(f32, T)], life: f32, blend: impl Fn(T, T, f32) -> T) -> Option<T> {
+    let next = keys.partition_point(|(at, _)| *at <= life);
+    let previous = next.checked_sub(1).map(|index| keys[index]);
+    match (previous, keys.get(next)) {
+        (Some((at, a)), Some(&(next_at, b))) => {
+            Some(blend(a, b, (life - at) / (next_at - at).max(f32::EPSILON)))
+        }
+        (Some((_, value)), None) | (None, Some(&(_, value))) => Some(value),
+        (None, None) => None,
+    }
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility, ParticlePool)]
+pub struct ParticleEmitter {
+    /// Particles spawned per second.
+    pub rate: f32,
+    /// Particles spawned at once when the emitter's added.
+    pub burst: u32,
+    /// Most particles alive at once. No more spawn until some die.
+    pub max_particles: u32,
+    pub shape: EmitterShape,
+    pub space: ParticleSpace,
+    /// Seconds a particle lives.
+    pub lifetime: f32,
+    /// Random lifetime variation, as a fraction of `lifetime`.
+    pub lifetime_jitter: f32,
+    /// Starting velocity, in the emitter's local space.
+    pub velocity: Vec3,
+    /// Random starting velocity added on each axis, up to this much either way.
+    pub velocity_jitter: Vec3,
+    /// Steady pull on every particle in world space, such as gravity or buoyancy.
+    pub acceleration: Vec3,
+    /// Fraction of velocity lost per second.
+    pub drag: f32,
+    /// Multiplies how fast particles move over their life, e.g. to burst out and then hang.
+    pub speed_over_life: LifeCurve,
+    /// How far particles sway either side of their path, horizontally.
+    pub wobble: f32,
+    /// Sways per second.
+    pub wobble_speed: f32,
+    /// Width of a particle.
+    pub size: f32,
+    /// Random size variation, as a fraction of `size`.
+    pub size_jitter: f32,
+    /// Multiplies `size` over particles' life.
+    pub size_over_life: LifeCurve,
+    pub sprite: ParticleSprite,
+    /// How fast particles turn about the view direction, in radians per second.
+    pub spin: f32,
+    pub color_over_life: LifeGradient,
+    /// Shades particles with the scene's lights, rather than drawing them at their colour.
+    pub lit: bool,
+    /// Fills the emitter when it's added as if it had already been running for a lifetime, for
+    /// ambient effects that shouldn't be seen starting up.
+    pub prewarm: bool,
+    /// Despawns the emitter once it's not spawning any more and its last particle has died, for
+    /// one-off bursts.
+    pub despawn_when_done: bool,
+    pub seed: u64,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            rate: 10.0,
+            burst: 0,
+            max_particles: 256,
+            shape: EmitterShape::default(),
+            space: ParticleSpace::default(),
+            lifetime: 2.0,
+            lifetime_jitter: 0.2,
+            velocity: Vec3::Y,
+            velocity_jitter: Vec3::splat(0.2),
+            acceleration: Vec3::ZERO,
+            drag: 0.0,
+            speed_over_life: LifeCurve::constant(1.0),
+            wobble: 0.0,
+            wobble_speed: 1.0,
+            size: 0.1,
+            size_jitter: 0.2,
+            size_over_life: LifeCurve::constant(1.0),
+            sprite: ParticleSprite::default(),
+            spin: 0.0,
+            color_over_life: LifeGradient::fade_out(LinearRgba::WHITE),
+            lit: false,
+            prewarm: false,
+            despawn_when_done: false,
+            seed: 0,
+        }
+    }
+}
+
+impl ParticleEmitter {
+    /// Bubbles rising and wobbling from across a patch of seabed, popping as they fade.
+    pub fn bubbles() -> Self {
+        Self {
+            rate: 4.0,
+            max_particles: 128,
+            shape: EmitterShape::Box {
+                half_extents: Vec3::new(1.0, 0.0, 1.0),
+            },
+            lifetime: 8.0,
+            lifetime_jitter: 0.3,
+            velocity: Vec3::Y * 1.8,
+            velocity_jitter: Vec3::new(0.0, 0.8, 0.0),
+            wobble: 0.5,
+            wobble_speed: 0.2,
+            size: 0.14,
+            size_jitter: 0.6,
+            color_over_life: LifeGradient::fade_in_out(Color::srgba(0.8, 0.9, 1.0, 0.4), 0.05),
+            lit: true,
+            prewarm: true,
+            ..default()
+        }
+    }
+
+    /// A one-off burst of glowing sparks that fly out, fall and shrink away, then despawn.
+    pub fn sparkles() -> Self {
+        Self {
+            rate: 0.0,
+            burst: 16,
+            shape: EmitterShape::Sphere { radius: 0.05 },
+            lifetime: 1.0,
+            velocity: Vec3::Y * 1.0,
+            velocity_jitter: Vec3::splat(2.0),
+            acceleration: Vec3::NEG_Y * 5.0,
+            drag: 1.0,
+            size: 0.1,
+            size_over_life: LifeCurve::linear(1.0, 0.0),
+            color_over_life: LifeGradient::fade_out(LinearRgba::rgb(4.0, 3.0, 0.6)),
+            despawn_when_done: true,
+            ..default()
+        }
+    }
+
+    pub fn with_rate(mut self, rate: f32) -> Self {
+        self.rate = rate.max(0.0);
+        self
+    }
+
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    pub fn with_max_particles(mut self, max_particles: u32) -> Self {
+        self.max_particles = max_particles;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: EmitterShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_space(mut self, space: ParticleSpace) -> Self {
+        self.space = space;
+        self
+    }
+
+    pub fn with_lifetime(mut self, lifetime: f32, jitter: f32) -> Self {
+        self.lifetime = lifetime.max(0.01);
+        self.lifetime_jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: Vec3, jitter: Vec3) -> Self {
+        self.velocity = velocity;
+        self.velocity_jitter = jitter.abs();
+        self
+    }
+
+    pub fn with_acceleration(mut self, acceleration: Vec3) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.drag = drag.max(0.0);
+        self
+    }
+
+    pub fn with_speed_over_life(mut self, speed: LifeCurve) -> Self {
+        self.speed_over_life = speed;
+        self
+    }
+
+    pub fn with_wobble(mut self, wobble: f32, wobble_speed: f32) -> Self {
+        self.wobble = wobble;
+        self.wobble_speed = wobble_speed;
+        self
+    }
+
+    pub fn with_size(mut self, size: f32, jitter: f32) -> Self {
+        self.size = size.max(0.0);
+        self.size_jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_size_over_life(mut self, size: LifeCurve) -> Self {
+        self.size_over_life = size;
+        self
+    }
+
+    pub fn with_sprite(mut self, sprite: ParticleSprite) -> Self {
+        self.sprite = sprite;
+        self
+    }
+
+    pub fn with_spin(mut self, spin: f32) -> Self {
+        self.spin = spin;
+        self
+    }
+
+    pub fn with_color_over_life(mut self, color: LifeGradient) -> Self {
+        self.color_over_life = color;
+        self
+    }
+
+    pub fn with_lit(mut self, lit: bool) -> Self {
+        self.lit = lit;
+        self
+    }
+
+    pub fn with_prewarm(mut self) -> Self {
+        self.prewarm = true;
+        self
+    }
+
+    pub fn with_despawn_when_done(mut self) -> Self {
+        self.despawn_when_done = true;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    fn spawn_particle(&self, rng: &mut SplitMix64, transform: &GlobalTransform) -> Particle {
+        let position = self.shape.sample(rng);
+        let jitter = Vec3::new(
+            rng.range_f32(-1.0, 1.0),
+            rng.range_f32(-1.0, 1.0),
+            rng.range_f32(-1.0, 1.0),
+        ) * self.velocity_jitter;
+        let velocity = self.velocity + jitter;
+        let (position, velocity) = match self.space {
+            ParticleSpace::World => (
+                transform.transform_point(position),
+                transform.rotation() * velocity,
+            ),
+            ParticleSpace::Local => (position, velocity),
+        };
+        Particle {
+            position,
+            velocity,
+            age: 0.0,
+            lifetime: (self.lifetime * (1.0 + rng.range_f32(-1.0, 1.0) * self.lifetime_jitter))
+                .max(0.01),
+            size: self.size * (1.0 + rng.range_f32(-1.0, 1.0) * self.size_jitter),
+            phase: rng.next_f32() * TAU,
+        }
+    }
+
+    /// Ages and moves `pool`'s particles by `dt` seconds, then spawns any new ones due.
+    fn step(&self, pool: &mut ParticlePool, transform: &GlobalTransform, dt: f32) {
+        let acceleration = match self.space {
+            ParticleSpace::World => self.acceleration,
+            ParticleSpace::Local => transform.rotation().inverse() * self.acceleration,
+        };
+        let kept = (-self.drag * dt).exp();
+        pool.particles.retain_mut(|particle| {
+            particle.age += dt;
+            if particle.age >= particle.lifetime {
+                return false;
+            }
+            let speed = self
+                .speed_over_life
+                .sample(particle.age / particle.lifetime);
+            particle.velocity = (particle.velocity + acceleration * dt) * kept;
+            particle.position += particle.velocity * speed * dt;
+            true
+        });
+
+        pool.owed += self.rate * dt;
+        let due = pool.owed.floor();
+        pool.owed -= due;
+        self.spawn(pool, transform, due as u32);
+    }
+
+    fn spawn(&self, pool: &mut ParticlePool, transform: &GlobalTransform, count: u32) {
+        let room = (self.max_particles as usize).saturating_sub(pool.particles.len());
+        let ParticlePool { particles, rng, .. } = pool;
+        particles
+            .extend((0..(count as usize).min(room)).map(|_| self.spawn_particle(rng, transform)));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    /// In world space, or the emitter's for [`ParticleSpace::Local`], before wobbling.
+    position: Vec3,
+    velocity: Vec3,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    phase: f32,
+}
+
+/// An emitter's live particles. Dead ones are dropped in place, keeping the vector's capacity for
+/// new ones, so a running emitter stops allocating once it's full.
+#[derive(Component, Debug)]
+struct ParticlePool {
+    particles: Vec<Particle>,
+    /// Fraction of a particle the rate has built up towards the next spawn.
+    owed: f32,
+    started: bool,
+    rng: SplitMix64,
+}
+
+impl Default for ParticlePool {
+    fn default() -> Self {
+        Self {
+            particles: Vec::new(),
+            owed: 0.0,
+            started: false,
+            rng: SplitMix64::new(0),
+        }
+    }
+}
+
+/// Shared by every emitter, as particles take their colour from the mesh: one lit and one unlit
+/// material for each sprite.
+#[derive(Resource)]
+struct ParticleMaterials {
+    dot: [Handle<StandardMaterial>; 2],
+    leaf: [Handle<StandardMaterial>; 2],
+}
+
+impl ParticleMaterials {
+    fn get(&self, sprite: ParticleSprite, lit: bool) -> Handle<StandardMaterial> {
+        let materials = match sprite {
+            ParticleSprite::Dot => &self.dot,
+            ParticleSprite::Leaf => &self.leaf,
+        };
+        materials[lit as usize].clone()
+    }
+}
+
+impl FromWorld for ParticleMaterials {
+    fn from_world(world: &mut World) -> Self {
+        let centre = (SPRITE_SIZE as f32 - 1.0) / 2.0;
+        let texture = |sprite: ParticleSprite| {
+            let data = (0..SPRITE_SIZE * SPRITE_SIZE)
+                .flat_map(|i| {
+                    let offset =
+                        Vec2::new((i % SPRITE_SIZE) as f32, (i / SPRITE_SIZE) as f32) - centre;
+                    let alpha = sprite.alpha(offset / (centre + 0.5));
+                    [255, 255, 255, (alpha * 255.0) as u8]
+                })
+                .collect();
+            Image::new(
+                Extent3d {
+                    width: SPRITE_SIZE,
+                    height: SPRITE_SIZE,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                data,
+                TextureFormat::Rgba8UnormSrgb,
+                RenderAssetUsages::RENDER_WORLD,
+            )
+        };
+        let mut images = world.resource_mut::<Assets<Image>>();
+        let dot = images.add(texture(ParticleSprite::Dot));
+        let leaf = images.add(texture(ParticleSprite::Leaf));
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut pair = |texture: Handle<Image>| {
+            [true, false].map(|unlit| {
+                materials.add(StandardMaterial {
+                    base_color_texture: Some(texture.clone()),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit,
+                    ..default()
+                })
+            })
+        };
+        Self {
+            dot: pair(dot),
+            leaf: pair(leaf),
+        }
+    }
+}
+
+fn particle_mesh(
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+) -> Mesh {
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+fn add_particle_mesh(
+    add: On<Add, ParticleEmitter>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    materials: Res<ParticleMaterials>,
+    emitters: Query<&ParticleEmitter>,
+) {
+    let Ok(emitter) = emitters.get(add.entity) else {
+        return;
+    };
+    let material = materials.get(emitter.sprite, emitter.lit);
+    let mesh = particle_mesh(Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    commands.entity(add.entity).insert((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(material),
+        // Bounds would need recomputing every frame as particles move
+        NoFrustumCulling,
+        NotShadowCaster,
+    ));
+}
+
+fn simulate_particles(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut emitters: Query<
+        (
+            Entity,
+            &ParticleEmitter,
+            &mut ParticlePool,
+            &GlobalTransform,
+        ),
+        Without<Culled>,
+    >,
+) {
+    let dt = time.delta_secs();
+    for (entity, emitter, mut pool, transform) in &mut emitters {
+        if !pool.started {
+            pool.started = true;
+            // Seeded from the entity too, so emitters sharing a preset don't move in lockstep
+            pool.rng = SplitMix64::new(emitter.seed ^ entity.to_bits());
+            emitter.spawn(&mut pool, transform, emitter.burst);
+            if emitter.prewarm {
+                let longest = emitter.lifetime * (1.0 + emitter.lifetime_jitter);
+                for _ in 0..(longest / PREWARM_STEP).ceil() as u32 {
+                    emitter.step(&mut pool, transform, PREWARM_STEP);
+                }
+            }
+        }
+        emitter.step(&mut pool, transform, dt);
+
+        if emitter.despawn_when_done && emitter.rate <= 0.0 && pool.particles.is_empty() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn build_particle_meshes(
+    camera: Option<Single<&GlobalTransform, With<PlayerCamera>>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    emitters: Query<(&ParticleEmitter, &ParticlePool, &GlobalTransform, &Mesh3d), Without<Culled>>,
+) {
+    let camera_rotation = camera.map_or(Quat::IDENTITY, |camera| camera.rotation());
+    for (emitter, pool, transform, mesh) in &emitters {
+        let Some(mesh) = meshes.get_mut(&mesh.0) else {
+            continue;
+        };
+        // Quads are built in the emitter's space, facing the camera from there
+        let to_local = transform.affine().inverse();
+        let facing = transform.rotation().inverse() * camera_rotation;
+        let (right, up, normal) = (facing * Vec3::X, facing * Vec3::Y, facing * Vec3::Z);
+
+        let count = pool.particles.len();
+        let mut positions = Vec::with_capacity(count * 4);
+        let mut colors = Vec::with_capacity(count * 4);
+        let mut indices = Vec::with_capacity(count * 6);
+        for particle in &pool.particles {
+            let life = particle.age / particle.lifetime;
+            let sway = TAU * emitter.wobble_speed * particle.age + particle.phase;
+            let wobble = Vec3::new(sway.sin(), 0.0, (sway * 1.3).cos()) * emitter.wobble;
+            let centre = match emitter.space {
+                ParticleSpace::World => to_local.transform_point3(particle.position + wobble),
+                ParticleSpace::Local => particle.position + wobble,
+            };
+            let half = particle.size * emitter.size_over_life.sample(life) / 2.0;
+            let color = emitter.color_over_life.sample(life).to_f32_array();
+            let (sin, cos) = (emitter.spin * particle.age + particle.phase).sin_cos();
+            let (right, up) = if emitter.spin == 0.0 {
+                (right, up)
+            } else {
+                (right * cos + up * sin, up * cos - right * sin)
+            };
+
+            let base = positions.len() as u32;
+            for corner in [-right - up, right - up, right + up, up - right] {
+                positions.push((centre + corner * half).to_array());
+                colors.push(color);
+            }
+            indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+        let uvs = [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]]
+            .into_iter()
+            .cycle()
+            .take(count * 4)
+            .collect();
+        let normals = vec![normal.to_array(); count * 4];
+        *mesh = particle_mesh(positions, normals, uvs, colors, indices);
+    }
+}
@@ -0,0 +1,165 @@
+//! Overlay shown while the game is paused, with buttons to resume or quit.
+//!
+//! Pausing already stops physics, player movement and the [`PausableSystems`] set, and releases the
+//! cursor so the buttons can be clicked. The overlay is hidden while attract mode is touring the
+//! scene, and the quit button is left out in kiosk mode. In scenes with [`NameplateSettings`],
+//! there's a button to switch nameplates on and off too.
+//!
+//! [`PausableSystems`]: crate::state::PausableSystems
+
+use bevy::prelude::*;
+
+use crate::attract::AttractState;
+use crate::kiosk::KioskMode;
+use crate::nameplate::NameplateSettings;
+use crate::state::GameState;
+
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Paused), spawn_pause_menu)
+            .add_systems(OnExit(GameState::Paused), despawn_pause_menu)
+            .add_systems(
+                Update,
+                (
+                    handle_pause_buttons,
+                    hide_during_attract_mode.run_if(state_changed::<AttractState>),
+                )
+                    .run_if(in_state(GameState::Paused)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct PauseMenu;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseButton {
+    Resume,
+    Nameplates,
+    Quit,
+}
+
+impl PauseButton {
+    fn label(self, nameplates: bool) -> &'static str {
+        match self {
+            Self::Resume => "Resume",
+            Self::Nameplates if nameplates => "Nameplates: On",
+            Self::Nameplates => "Nameplates: Off",
+            Self::Quit => "Quit",
+        }
+    }
+}
+
+const BUTTON_COLOR: Color = Color::srgba(0.15, 0.15, 0.15, 0.9);
+const BUTTON_HOVERED_COLOR: Color = Color::srgba(0.3, 0.3, 0.3, 0.9);
+
+fn spawn_pause_menu(
+    mut commands: Commands,
+    kiosk: Option<Res<KioskMode>>,
+    nameplates: Option<Res<NameplateSettings>>,
+) {
+    let mut buttons = vec![PauseButton::Resume];
+    if nameplates.is_some() {
+        buttons.push(PauseButton::Nameplates);
+    }
+    if kiosk.is_none() {
+        buttons.push(PauseButton::Quit);
+    }
+    let nameplates_enabled = nameplates.is_some_and(|nameplates| nameplates.enabled);
+    commands
+        .spawn((
+            Name::new("Pause menu"),
+            PauseMenu,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            GlobalZIndex(i32::MAX - 1),
+        ))
+        .with_children(|menu| {
+            menu.spawn((
+                Text::new("Paused"),
+                TextFont::from_font_size(48.0),
+                Node {
+                    margin: UiRect::bottom(Val::Px(12.0)),
+                    ..default()
+                },
+            ));
+            for button in buttons {
+                menu.spawn((
+                    button,
+                    Button,
+                    Node {
+                        width: Val::Px(200.0),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    BackgroundColor(BUTTON_COLOR),
+                ))
+                .with_child(Text::new(button.label(nameplates_enabled)));
+            }
+        });
+}
+
+fn despawn_pause_menu(mut commands: Commands, menus: Query<Entity, With<PauseMenu>>) {
+    for entity in &menus {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn handle_pause_buttons(
+    mut buttons: Query<
+        (&Interaction, &PauseButton, &Children, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut labels: Query<&mut Text>,
+    mut nameplates: Option<ResMut<NameplateSettings>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    for (interaction, button, children, mut color) in &mut buttons {
+        match interaction {
+            Interaction::Pressed => match button {
+                PauseButton::Resume => next_state.set(GameState::Active),
+                PauseButton::Nameplates => {
+                    if let Some(nameplates) = nameplates.as_mut() {
+                        nameplates.enabled = !nameplates.enabled;
+                        let label = button.label(nameplates.enabled);
+                        let mut texts = labels.iter_many_mut(children);
+                        while let Some(mut text) = texts.fetch_next() {
+                            text.0 = label.to_string();
+                        }
+                    }
+                }
+                PauseButton::Quit => {
+                    exit.write(AppExit::Success);
+                }
+            },
+            Interaction::Hovered => color.0 = BUTTON_HOVERED_COLOR,
+            Interaction::None => color.0 = BUTTON_COLOR,
+        }
+    }
+}
+
+fn hide_during_attract_mode(
+    attract: Res<State<AttractState>>,
+    mut menus: Query<&mut Visibility, With<PauseMenu>>,
+) {
+    let visibility = match attract.get() {
+        AttractState::Playing => Visibility::Hidden,
+        AttractState::Inactive => Visibility::Inherited,
+    };
+    for mut menu in &mut menus {
+        *menu = visibility;
+    }
+}
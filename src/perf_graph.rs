@@ -0,0 +1,333 @@
+//! Time-series graphs of performance diagnostics, shown alongside the performance UI.
+//!
+//! Graphs cover frame time, entity count, physics step time and draw calls, plus any diagnostic a
+//! scene registers under the [`SCENE_DIAGNOSTIC_PREFIX`] path prefix. `RenderDiagnosticsPlugin`
+//! only times render passes, so draw calls are counted as the meshes visible to any camera, one
+//! draw each before Bevy batches them; the real count can be lower. F3+P freezes the graphs so a
+//! spike can be inspected: hovering a bar shows the value of that sample.
+
+use std::collections::VecDeque;
+
+use avian3d::prelude::*;
+use bevy::camera::visibility::VisibilitySystems;
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, EntityCountDiagnosticsPlugin,
+    FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+};
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::controls::keyboard_not_captured;
+use crate::debug_window::DebugUi;
+use crate::diag::DiagState;
+
+pub(crate) struct PerfGraphPlugin;
+
+impl Plugin for PerfGraphPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(PHYSICS_STEP_TIME).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(DRAW_CALLS))
+            .init_resource::<PerfGraphs>()
+            .init_resource::<PhysicsStepTimer>()
+            .add_plugins(InputManagerPlugin::<FreezeGraphsAction>::default())
+            .add_systems(Startup, setup_actions)
+            .add_systems(
+                FixedPostUpdate,
+                (
+                    start_physics_step_timer.before(PhysicsSystems::StepSimulation),
+                    end_physics_step_timer.after(PhysicsSystems::StepSimulation),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                count_draw_calls
+                    .after(VisibilitySystems::CheckVisibility)
+                    .run_if(in_state(DiagState::Enabled)),
+            )
+            .add_systems(OnEnter(DiagState::Enabled), show_graphs)
+            .add_systems(OnExit(DiagState::Enabled), hide_graphs)
+            .add_systems(
+                Update,
+                (
+                    handle_actions.run_if(keyboard_not_captured),
+                    sample_diagnostics,
+                    update_graphs,
+                )
+                    .chain()
+                    .run_if(in_state(DiagState::Enabled)),
+            );
+    }
+}
+
+/// Diagnostics whose path starts with this are graphed automatically, e.g. `scene/gems_collected`.
+pub const SCENE_DIAGNOSTIC_PREFIX: &str = "scene/";
+
+const PHYSICS_STEP_TIME: DiagnosticPath = DiagnosticPath::const_new("physics/step_time");
+const DRAW_CALLS: DiagnosticPath = DiagnosticPath::const_new("render/draw_calls");
+
+/// Number of samples kept for each graph, one per frame.
+const HISTORY_LEN: usize = 120;
+const BAR_WIDTH: f32 = 2.0;
+const GRAPH_HEIGHT: f32 = 40.0;
+
+#[derive(Resource, Default)]
+struct PerfGraphs {
+    series: Vec<Series>,
+    frozen: bool,
+    /// Graph and sample index under the cursor while frozen.
+    inspected: Option<(usize, usize)>,
+}
+
+struct Series {
+    path: DiagnosticPath,
+    suffix: String,
+    samples: VecDeque<f64>,
+}
+
+impl Series {
+    fn new(path: DiagnosticPath, suffix: impl Into<String>) -> Self {
+        Self {
+            path,
+            suffix: suffix.into(),
+            samples: VecDeque::with_capacity(HISTORY_LEN),
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if self.samples.len() >= HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    fn max(&self) -> f64 {
+        self.samples.iter().copied().fold(0.0, f64::max)
+    }
+
+    fn summary(&self) -> String {
+        let path = &self.path;
+        let suffix = &self.suffix;
+        let Some(latest) = self.samples.back() else {
+            return format!("{path}: -");
+        };
+        let min = self.samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self.max();
+        let mean = self.samples.iter().sum::<f64>() / self.samples.len() as f64;
+        format!("{path}: {latest:.2}{suffix} (min {min:.2}, avg {mean:.2}, max {max:.2})")
+    }
+}
+
+#[derive(Resource, Default)]
+struct PhysicsStepTimer(Option<Instant>);
+
+#[derive(Component)]
+struct PerfGraphsUi;
+
+#[derive(Component)]
+struct GraphLabel(usize);
+
+#[derive(Component)]
+struct GraphBar {
+    series: usize,
+    sample: usize,
+}
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+struct FreezeGraphsAction;
+
+fn setup_actions(mut commands: Commands) {
+    let freeze_map = InputMap::new([(
+        FreezeGraphsAction,
+        ButtonlikeChord::new([KeyCode::F3, KeyCode::KeyP]),
+    )]);
+    commands.spawn((Name::new("Performance graph controls"), freeze_map));
+}
+
+fn handle_actions(
+    action_state: Single<&ActionState<FreezeGraphsAction>>,
+    mut graphs: ResMut<PerfGraphs>,
+) {
+    if action_state.just_pressed(&FreezeGraphsAction) {
+        graphs.frozen = !graphs.frozen;
+        graphs.inspected = None;
+    }
+}
+
+fn start_physics_step_timer(mut timer: ResMut<PhysicsStepTimer>) {
+    timer.0 = Some(Instant::now());
+}
+
+fn end_physics_step_timer(mut timer: ResMut<PhysicsStepTimer>, mut diagnostics: Diagnostics) {
+    if let Some(start) = timer.0.take() {
+        diagnostics.add_measurement(&PHYSICS_STEP_TIME, || {
+            start.elapsed().as_secs_f64() * 1000.0
+        });
+    }
+}
+
+fn count_draw_calls(meshes: Query<&ViewVisibility, With<Mesh3d>>, mut diagnostics: Diagnostics) {
+    diagnostics.add_measurement(&DRAW_CALLS, || {
+        meshes.iter().filter(|visibility| visibility.get()).count() as f64
+    });
+}
+
+fn tracked_paths(store: &DiagnosticsStore) -> Vec<(DiagnosticPath, String)> {
+    let builtin = [
+        FrameTimeDiagnosticsPlugin::FRAME_TIME,
+        EntityCountDiagnosticsPlugin::ENTITY_COUNT,
+        PHYSICS_STEP_TIME,
+        DRAW_CALLS,
+    ];
+    let scene = store
+        .iter()
+        .map(|diagnostic| diagnostic.path().clone())
+        .filter(|path| path.as_str().starts_with(SCENE_DIAGNOSTIC_PREFIX));
+
+    builtin
+        .into_iter()
+        .chain(scene)
+        .map(|path| {
+            let suffix = store
+                .get(&path)
+                .map(|diagnostic| diagnostic.suffix.to_string())
+                .unwrap_or_default();
+            (path, suffix)
+        })
+        .collect()
+}
+
+fn show_graphs(
+    mut commands: Commands,
+    mut graphs: ResMut<PerfGraphs>,
+    store: Res<DiagnosticsStore>,
+) {
+    graphs.series = tracked_paths(&store)
+        .into_iter()
+        .map(|(path, suffix)| Series::new(path, suffix))
+        .collect();
+    graphs.inspected = None;
+
+    commands
+        .spawn((
+            Name::new("Performance graphs"),
+            PerfGraphsUi,
+            DebugUi,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                right: Val::Px(12.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        ))
+        .with_children(|parent| {
+            for series in 0..graphs.series.len() {
+                parent.spawn((
+                    GraphLabel(series),
+                    Text::default(),
+                    TextFont::from_font_size(12.0),
+                ));
+                parent
+                    .spawn(Node {
+                        width: Val::Px(BAR_WIDTH * HISTORY_LEN as f32),
+                        height: Val::Px(GRAPH_HEIGHT),
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    })
+                    .with_children(|graph| {
+                        for sample in 0..HISTORY_LEN {
+                            graph.spawn((
+                                GraphBar { series, sample },
+                                Node {
+                                    width: Val::Px(BAR_WIDTH),
+                                    height: Val::Percent(0.0),
+                                    ..default()
+                                },
+                                BackgroundColor(Color::srgb(0.3, 0.8, 0.4)),
+                            ));
+                        }
+                    });
+            }
+        })
+        .observe(inspect_bar);
+}
+
+fn hide_graphs(mut commands: Commands, ui: Query<Entity, With<PerfGraphsUi>>) {
+    for entity in ui.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn sample_diagnostics(store: Res<DiagnosticsStore>, mut graphs: ResMut<PerfGraphs>) {
+    if graphs.frozen {
+        return;
+    }
+    for series in graphs.series.iter_mut() {
+        if let Some(value) = store.get(&series.path).and_then(Diagnostic::value) {
+            series.push(value);
+        }
+    }
+}
+
+/// Hover events bubble up from the bars to the graph root, which records the bar under the cursor.
+fn inspect_bar(over: On<Pointer<Over>>, bars: Query<&GraphBar>, mut graphs: ResMut<PerfGraphs>) {
+    if !graphs.frozen {
+        return;
+    }
+    if let Ok(bar) = bars.get(over.original_event_target()) {
+        graphs.inspected = Some((bar.series, bar.sample));
+    }
+}
+
+fn update_graphs(
+    graphs: Res<PerfGraphs>,
+    mut labels: Query<(&GraphLabel, &mut Text)>,
+    mut bars: Query<(&GraphBar, &mut Node, &mut BackgroundColor)>,
+) {
+    for (GraphLabel(index), mut text) in labels.iter_mut() {
+        let Some(series) = graphs.series.get(*index) else {
+            continue;
+        };
+        text.0 = match graphs.inspected {
+            Some((inspected, sample)) if inspected == *index => {
+                let value = series.samples.get(sample).copied().unwrap_or_default();
+                let age = series
+                    .samples
+                    .len()
+                    .saturating_sub(sample)
+                    .saturating_sub(1);
+                format!(
+                    "{}: {value:.2}{} ({age} frames ago)",
+                    series.path, series.suffix
+                )
+            }
+            _ => series.summary(),
+        };
+        if graphs.frozen {
+            text.0.push_str(" [frozen]");
+        }
+    }
+
+    for (bar, mut node, mut color) in bars.iter_mut() {
+        let Some(series) = graphs.series.get(bar.series) else {
+            continue;
+        };
+        let max = series.max();
+        let value = series.samples.get(bar.sample).copied().unwrap_or_default();
+        let fraction = if max > 0.0 { value / max } else { 0.0 };
+        node.height = Val::Percent(fraction as f32 * 100.0);
+        color.0 = if graphs.inspected == Some((bar.series, bar.sample)) {
+            Color::WHITE
+        } else {
+            Color::srgb(
+                0.3 + 0.6 * fraction as f32,
+                0.8 - 0.5 * fraction as f32,
+                0.4,
+            )
+        };
+    }
+}
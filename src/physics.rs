@@ -1,18 +1,356 @@
+#![allow(clippy::useless_conversion)]
+use avian3d::math::{AdjustPrecision, AsF32};
 use avian3d::prelude::*;
+use bevy::ecs::entity::EntityHashSet;
 use bevy::prelude::*;
 
+use crate::player::Player;
 use crate::state::GameState;
 
-pub struct PhysicsPlugin;
+/// Sets up physics. Physics steps at a fixed rate, so by default every rigid body's transform is
+/// interpolated between the last two steps each frame, and the player camera following it, so
+/// movement looks smooth whatever the refresh rate.
+#[derive(Debug, Clone)]
+pub struct PhysicsPlugin {
+    /// Physics steps per second, or `None` for Bevy's default of 64.
+    pub fixed_hz: Option<f64>,
+    /// Whether rigid body transforms are interpolated between physics steps.
+    pub interpolation: bool,
+}
+
+impl Default for PhysicsPlugin {
+    fn default() -> Self {
+        Self {
+            fixed_hz: None,
+            interpolation: true,
+        }
+    }
+}
+
+impl PhysicsPlugin {
+    pub fn with_fixed_hz(mut self, fixed_hz: f64) -> Self {
+        self.fixed_hz = Some(fixed_hz);
+        self
+    }
+
+    /// Shows rigid bodies where the last physics step left them, which can stutter when the
+    /// physics rate doesn't match the frame rate.
+    pub fn without_interpolation(mut self) -> Self {
+        self.interpolation = false;
+        self
+    }
+}
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(avian3d::prelude::PhysicsPlugins::default())
+        let interpolation = if self.interpolation {
+            PhysicsInterpolationPlugin::interpolate_all()
+        } else {
+            PhysicsInterpolationPlugin::default()
+        };
+        if let Some(fixed_hz) = self.fixed_hz {
+            app.insert_resource(Time::<Fixed>::from_hz(fixed_hz));
+        }
+        app.add_plugins(avian3d::prelude::PhysicsPlugins::default().set(interpolation))
+            .configure_sets(
+                FixedPostUpdate,
+                PhysicsSyncSet.after(PhysicsSystems::Writeback),
+            )
+            .add_systems(FixedUpdate, (apply_water_volumes, apply_wind_volumes))
+            .add_systems(FixedPostUpdate, detect_triggers.in_set(PhysicsSyncSet))
             .add_systems(OnEnter(GameState::Paused), pause_physics)
             .add_systems(OnEnter(GameState::Active), resume_physics);
     }
 }
 
+/// Runs in [`FixedPostUpdate`] once simulated positions have been written back to [`Transform`]s,
+/// for systems that need to react to where physics moved things this step.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhysicsSyncSet;
+
+/// A box of water that buoys up and slows down the dynamic rigid bodies inside it, and carries
+/// them along with its current.
+///
+/// Buoyancy and drag scale with how much of a body's bounding box is below the surface, so bodies
+/// lighter than water bob at the surface and settle there. The player is left to its
+/// [`MovementMode`](crate::player::MovementMode) instead.
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct WaterVolume {
+    pub half_extents: Vec3,
+    /// World height of the water surface, if it's below the top of the box. Without one the whole
+    /// box is underwater, so bodies are fully buoyed wherever they are in it and don't float.
+    pub surface_height: Option<f32>,
+    /// Upward push on a fully submerged body, as a multiple of gravity. Above 1 floats, below 1
+    /// sinks slowly. Bodies can override this with [`Buoyancy`].
+    pub buoyancy: f32,
+    /// Fraction of linear velocity lost per second when fully submerged.
+    pub linear_drag: f32,
+    /// Fraction of angular velocity lost per second when fully submerged.
+    pub angular_drag: f32,
+    /// Velocity of the water, which linear drag pulls submerged bodies towards.
+    pub current: Vec3,
+}
+
+impl WaterVolume {
+    pub fn new(half_extents: Vec3) -> Self {
+        Self {
+            half_extents,
+            surface_height: None,
+            buoyancy: 1.5,
+            linear_drag: 1.5,
+            angular_drag: 1.0,
+            current: Vec3::ZERO,
+        }
+    }
+
+    pub fn with_surface_height(mut self, surface_height: f32) -> Self {
+        self.surface_height = Some(surface_height);
+        self
+    }
+
+    pub fn with_buoyancy(mut self, buoyancy: f32) -> Self {
+        self.buoyancy = buoyancy;
+        self
+    }
+
+    pub fn with_drag(mut self, linear_drag: f32, angular_drag: f32) -> Self {
+        self.linear_drag = linear_drag.max(0.0);
+        self.angular_drag = angular_drag.max(0.0);
+        self
+    }
+
+    pub fn with_current(mut self, current: Vec3) -> Self {
+        self.current = current;
+        self
+    }
+
+    /// Whether the world-space `point` is underwater.
+    pub fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let local = transform.affine().inverse().transform_point3(point);
+        local.abs().cmple(self.half_extents).all()
+            && self.surface_height.is_none_or(|surface| point.y <= surface)
+    }
+
+    /// Fraction of the world-space box from `min` to `max` that's underwater, from 0 to 1.
+    fn submerged(&self, transform: &GlobalTransform, min: Vec3, max: Vec3) -> f32 {
+        let local = transform
+            .affine()
+            .inverse()
+            .transform_point3((min + max) / 2.0);
+        if local.abs().cmpgt(self.half_extents).any() {
+            return 0.0;
+        }
+        let Some(surface) = self.surface_height else {
+            return 1.0;
+        };
+        let height = (max.y - min.y).max(f32::EPSILON);
+        ((surface - min.y) / height).clamp(0.0, 1.0)
+    }
+}
+
+/// How buoyant a body is in a [`WaterVolume`], replacing the volume's own buoyancy, e.g. below 1
+/// for something heavy that should sink.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Buoyancy(pub f32);
+
+fn apply_water_volumes(
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    gravity: Res<Gravity>,
+    volumes: Query<(&WaterVolume, &GlobalTransform)>,
+    mut bodies: Query<
+        (
+            &RigidBody,
+            &ColliderAabb,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            Option<&GravityScale>,
+            Option<&Buoyancy>,
+        ),
+        Without<Player>,
+    >,
+) {
+    if physics_time.is_paused() || volumes.is_empty() {
+        return;
+    }
+    let dt = time.delta_secs();
+    let gravity = gravity.0.f32();
+    for (body, aabb, mut linear, mut angular, gravity_scale, buoyancy) in &mut bodies {
+        if !body.is_dynamic() {
+            continue;
+        }
+        let (min, max) = (aabb.min.f32(), aabb.max.f32());
+        let Some((volume, submerged)) = volumes
+            .iter()
+            .map(|(volume, transform)| (volume, volume.submerged(transform, min, max)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .filter(|(_, submerged)| *submerged > 0.0)
+        else {
+            continue;
+        };
+
+        let buoyancy = buoyancy.map_or(volume.buoyancy, |buoyancy| buoyancy.0);
+        let scale = gravity_scale.map_or(1.0, |scale| scale.0.f32());
+        // Pushes against gravity, so heavier gravity buoys harder as it does in real water
+        let lift = -gravity * scale * buoyancy * submerged;
+        linear.0 += (lift * dt).adjust_precision();
+        // Drag acts on the body's speed through the water, so a current carries it along
+        let current = volume.current.adjust_precision();
+        linear.0 = current
+            + (linear.0 - current)
+                * (-volume.linear_drag * submerged * dt)
+                    .exp()
+                    .adjust_precision();
+        angular.0 *= (-volume.angular_drag * submerged * dt)
+            .exp()
+            .adjust_precision();
+    }
+}
+
+/// A box of moving air that carries the dynamic rigid bodies inside it along with it, in gusts,
+/// e.g. a draft from a vent or wind across an exposed ridge. The player is left alone.
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct WindVolume {
+    pub half_extents: Vec3,
+    /// Velocity of the air at its steadiest.
+    pub velocity: Vec3,
+    /// Fraction of a body's velocity relative to the wind lost per second, i.e. how strongly the
+    /// wind takes hold of it.
+    pub drag: f32,
+    /// How far gusts speed up and drop off the wind, as a fraction of its velocity.
+    pub gustiness: f32,
+}
+
+impl WindVolume {
+    pub fn new(half_extents: Vec3, velocity: Vec3) -> Self {
+        Self {
+            half_extents,
+            velocity,
+            drag: 0.5,
+            gustiness: 0.5,
+        }
+    }
+
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.drag = drag.max(0.0);
+        self
+    }
+
+    pub fn with_gustiness(mut self, gustiness: f32) -> Self {
+        self.gustiness = gustiness.max(0.0);
+        self
+    }
+
+    /// Whether the world-space `point` is in the wind.
+    pub fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let local = transform.affine().inverse().transform_point3(point);
+        local.abs().cmple(self.half_extents).all()
+    }
+
+    /// The wind's velocity `elapsed` seconds in, with gusts.
+    pub fn velocity_at(&self, elapsed: f32) -> Vec3 {
+        // Two unrelated frequencies, so gusts don't settle into an obvious rhythm
+        let gust = (elapsed * 0.7).sin() * 0.6 + (elapsed * 1.9 + 1.3).sin() * 0.4;
+        self.velocity * (1.0 + self.gustiness * gust).max(0.0)
+    }
+}
+
+fn apply_wind_volumes(
+    time: Res<Time>,
+    physics_time: Res<Time<Physics>>,
+    volumes: Query<(&WindVolume, &GlobalTransform)>,
+    mut bodies: Query<(&RigidBody, &ColliderAabb, &mut LinearVelocity), Without<Player>>,
+) {
+    if physics_time.is_paused() || volumes.is_empty() {
+        return;
+    }
+    let dt = time.delta_secs();
+    let elapsed = physics_time.elapsed_secs();
+    for (body, aabb, mut linear) in &mut bodies {
+        if !body.is_dynamic() {
+            continue;
+        }
+        let center = ((aabb.min + aabb.max) / 2.0).f32();
+        let Some((volume, _)) = volumes
+            .iter()
+            .find(|(volume, transform)| volume.contains(transform, center))
+        else {
+            continue;
+        };
+        let wind = volume.velocity_at(elapsed).adjust_precision();
+        let pull = 1.0 - (-volume.drag * dt).exp();
+        linear.0 += (wind - linear.0) * pull.adjust_precision();
+    }
+}
+
+/// A sensor that triggers [`TriggerEnter`] and [`TriggerExit`] on itself as the player, or any
+/// entity tagged [`TriggerActivator`], starts and stops overlapping it. Give it a [`Collider`] for
+/// its shape. Activators are matched by their collider entity, so an activator's collider shouldn't
+/// be on a child of it.
+#[derive(Component, Debug, Default, Clone)]
+#[require(Transform, Sensor, CollidingEntities)]
+pub struct TriggerVolume {
+    occupants: EntityHashSet,
+}
+
+impl TriggerVolume {
+    /// Whether `entity` is inside the volume.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.occupants.contains(&entity)
+    }
+
+    /// The player and activators inside the volume.
+    pub fn occupants(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.occupants.iter().copied()
+    }
+}
+
+/// Tags an entity other than the player as setting off [`TriggerVolume`]s.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct TriggerActivator;
+
+/// Triggered on a [`TriggerVolume`] when something that sets it off starts overlapping it.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct TriggerEnter {
+    pub entity: Entity,
+    /// The player or [`TriggerActivator`] that entered.
+    pub activator: Entity,
+}
+
+/// Triggered on a [`TriggerVolume`] when something that set it off stops overlapping it, including
+/// when it's despawned.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct TriggerExit {
+    pub entity: Entity,
+    /// The player or [`TriggerActivator`] that left.
+    pub activator: Entity,
+}
+
+fn detect_triggers(
+    mut commands: Commands,
+    mut volumes: Query<(Entity, &CollidingEntities, &mut TriggerVolume)>,
+    activators: Query<(), Or<(With<Player>, With<TriggerActivator>)>>,
+) {
+    for (entity, colliding, mut volume) in &mut volumes {
+        let occupants: EntityHashSet = colliding
+            .iter()
+            .copied()
+            .filter(|other| activators.contains(*other))
+            .collect();
+        for &activator in occupants.difference(&volume.occupants) {
+            commands.trigger(TriggerEnter { entity, activator });
+        }
+        for &activator in volume.occupants.difference(&occupants) {
+            commands.trigger(TriggerExit { entity, activator });
+        }
+        if occupants != volume.occupants {
+            volume.occupants = occupants;
+        }
+    }
+}
+
 fn pause_physics(mut time: ResMut<Time<Physics>>) {
     time.pause();
 }
@@ -29,6 +367,10 @@ pub mod debug {
     use leafwing_input_manager::plugin::InputManagerPlugin;
     use leafwing_input_manager::prelude::{ActionState, ButtonlikeChord, InputMap};
 
+    use crate::controls::keyboard_not_captured;
+    use crate::kiosk::not_in_kiosk_mode;
+    use crate::mesh_collider::ColliderValidation;
+
     pub struct PhysicsDebugPlugin;
 
     impl Plugin for PhysicsDebugPlugin {
@@ -41,9 +383,15 @@ pub mod debug {
                         ..default()
                     },
                 )
+                .init_resource::<ColliderValidation>()
                 .add_plugins(InputManagerPlugin::<ToggleGizmosAction>::default())
                 .add_systems(Startup, setup_actions)
-                .add_systems(Update, handle_actions);
+                .add_systems(
+                    Update,
+                    handle_actions
+                        .run_if(not_in_kiosk_mode)
+                        .run_if(keyboard_not_captured),
+                );
         }
     }
 
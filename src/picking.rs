@@ -1,10 +1,70 @@
+#![allow(clippy::useless_conversion)]
+
+use avian3d::math::AsF32;
+use avian3d::prelude::*;
+use bevy::asset::uuid::Uuid;
+use bevy::camera::NormalizedRenderTarget;
+use bevy::camera::primitives::Aabb;
+use bevy::camera::visibility::RenderLayers;
 use bevy::color::palettes::tailwind::{PINK_100, RED_500};
-use bevy::picking::pointer::PointerInteraction;
+use bevy::light::NotShadowCaster;
+use bevy::picking::PickingSystems;
+use bevy::picking::backend::ray::RayMap;
+use bevy::picking::backend::{HitData, PointerHits};
+use bevy::picking::pointer::{
+    Location, PointerAction, PointerButton, PointerId, PointerInput, PointerInteraction,
+    PointerLocation, PointerPress,
+};
 use bevy::prelude::*;
+use bevy::render::render_resource::Face;
+use bevy::window::{CursorGrabMode, CursorOptions, PrimaryWindow, WindowRef};
 
+use crate::firstsight::PlayerCamera;
+use crate::interact::Interactable;
+use crate::player::Player;
 use crate::state::GameState;
 
-pub(crate) struct PickingPlugin;
+/// Reports what the player is pointing at and its [`Hint`] in the corner of the screen, and
+/// outlines it if it has a [`Hint`] or is [`Interactable`]. With
+/// [`PickingPlugin::with_world_labels`], also floats each hint over its entity in the world, and
+/// with [`PickingPlugin::with_crosshair`], picks from a crosshair while the cursor is grabbed.
+#[derive(Debug, Clone)]
+pub struct PickingPlugin {
+    /// Settings for hint labels in the world, or `None` for none.
+    pub world_labels: Option<HintLabels>,
+    /// How what the player is pointing at is outlined, or `None` for no outline.
+    pub hover_outline: Option<HoverOutline>,
+    /// Settings for picking from the middle of the screen, or `None` to always pick from the
+    /// cursor.
+    pub crosshair: Option<CrosshairPicking>,
+}
+
+impl Default for PickingPlugin {
+    fn default() -> Self {
+        Self {
+            world_labels: None,
+            hover_outline: Some(HoverOutline::default()),
+            crosshair: None,
+        }
+    }
+}
+
+impl PickingPlugin {
+    pub fn with_world_labels(mut self, labels: HintLabels) -> Self {
+        self.world_labels = Some(labels);
+        self
+    }
+
+    pub fn with_hover_outline(mut self, outline: Option<HoverOutline>) -> Self {
+        self.hover_outline = outline;
+        self
+    }
+
+    pub fn with_crosshair(mut self, crosshair: CrosshairPicking) -> Self {
+        self.crosshair = Some(crosshair);
+        self
+    }
+}
 
 #[derive(Component)]
 struct PickingDisplay;
@@ -15,13 +75,54 @@ impl Plugin for PickingPlugin {
             .add_systems(
                 Update,
                 (draw_mesh_intersections, update_picking_display)
+                    .in_set(PickingSet)
                     .run_if(in_state(GameState::Active)),
             )
             .add_systems(OnEnter(GameState::Active), setup_picking_ui)
             .add_systems(OnExit(GameState::Active), cleanup_picking_ui);
+        if let Some(labels) = &self.world_labels {
+            app.insert_resource(labels.clone())
+                .add_observer(spawn_hint_label)
+                .add_observer(despawn_hint_label)
+                .add_systems(
+                    Update,
+                    place_hint_labels
+                        .in_set(PickingSet)
+                        .run_if(resource_exists::<HintLabels>),
+                );
+        }
+        if let Some(outline) = &self.hover_outline {
+            app.insert_resource(outline.clone())
+                .init_resource::<Outlined>()
+                .add_systems(
+                    Update,
+                    outline_hovered
+                        .in_set(PickingSet)
+                        .run_if(resource_exists::<HoverOutline>.and(in_state(GameState::Active))),
+                )
+                .add_systems(OnExit(GameState::Active), clear_outline);
+        }
+        if let Some(crosshair) = &self.crosshair {
+            app.insert_resource(crosshair.clone())
+                .add_systems(Startup, spawn_crosshair)
+                .add_systems(
+                    PreUpdate,
+                    (
+                        aim_crosshair
+                            .after(PickingSystems::ProcessInput)
+                            .before(PickingSystems::Backend),
+                        crosshair_hits.in_set(PickingSystems::Backend),
+                    )
+                        .run_if(resource_exists::<CrosshairPicking>),
+                );
+        }
     }
 }
 
+/// Systems that report what the player is pointing at, in [`Update`].
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PickingSet;
+
 /// A component that can be added to entities to provide hints on what happens when they are picked.
 #[derive(Component)]
 pub struct Hint {
@@ -34,6 +135,154 @@ impl Hint {
     }
 }
 
+/// How [`Hint`]s are labelled in the world: floating over their entities, fading out with
+/// distance, and hidden behind colliders.
+#[derive(Resource, Debug, Clone)]
+pub struct HintLabels {
+    /// Labels further than this from the camera are hidden.
+    pub max_distance: f32,
+    /// Distance over which labels fade out before `max_distance`.
+    pub fade_distance: f32,
+    /// How far above its entity a label floats, from the top of its collider if it has one.
+    pub height: f32,
+    /// Whether labels are hidden while colliders block the view of them.
+    pub occlusion: bool,
+    pub font_size: f32,
+}
+
+impl Default for HintLabels {
+    fn default() -> Self {
+        Self {
+            max_distance: 8.0,
+            fade_distance: 2.0,
+            height: 0.3,
+            occlusion: true,
+            font_size: 16.0,
+        }
+    }
+}
+
+impl HintLabels {
+    pub fn with_distance(mut self, max_distance: f32, fade_distance: f32) -> Self {
+        self.max_distance = max_distance.max(0.0);
+        self.fade_distance = fade_distance.clamp(0.0, self.max_distance);
+        self
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn with_occlusion(mut self, occlusion: bool) -> Self {
+        self.occlusion = occlusion;
+        self
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+}
+
+/// How the entity the player is pointing at is outlined, if it has a [`Hint`] or is
+/// [`Interactable`]. Each of its meshes gets a slightly larger copy of itself behind it, drawn
+/// inside out in a flat colour so only the rim shows.
+#[derive(Resource, Debug, Clone)]
+pub struct HoverOutline {
+    pub color: Color,
+    /// How far the outline reaches past the meshes, in their own units.
+    pub width: f32,
+}
+
+impl Default for HoverOutline {
+    fn default() -> Self {
+        Self {
+            color: Color::srgb(1.0, 0.85, 0.3),
+            width: 0.02,
+        }
+    }
+}
+
+impl HoverOutline {
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width.max(0.0);
+        self
+    }
+}
+
+/// Picking from the middle of the screen, along the player camera's forward vector, while the
+/// cursor is grabbed for mouse look. Picks are made by the [`CROSSHAIR_POINTER`] in place of the
+/// mouse, and trigger the same hover and click [`Pointer`] events, with the left mouse button
+/// clicking.
+#[derive(Resource, Debug, Clone)]
+pub struct CrosshairPicking {
+    /// Nothing further away than this is picked.
+    pub max_distance: f32,
+    /// Whether a dot is drawn in the middle of the screen while picking from it.
+    pub show_crosshair: bool,
+}
+
+impl Default for CrosshairPicking {
+    fn default() -> Self {
+        Self {
+            max_distance: 10.0,
+            show_crosshair: true,
+        }
+    }
+}
+
+impl CrosshairPicking {
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance.max(0.0);
+        self
+    }
+
+    pub fn with_crosshair_shown(mut self, show_crosshair: bool) -> Self {
+        self.show_crosshair = show_crosshair;
+        self
+    }
+}
+
+/// The pointer that picks from the middle of the screen with [`CrosshairPicking`].
+pub const CROSSHAIR_POINTER: PointerId =
+    PointerId::Custom(Uuid::from_u128(0x3c5e_9a41_7d2b_4f60_8e13_b7a4_c0d9_2f58));
+
+#[derive(Component)]
+struct Crosshair;
+
+/// The entity outlined, and the material its outline is drawn with.
+#[derive(Resource, Default)]
+struct Outlined {
+    entity: Option<Entity>,
+    material: Option<Handle<StandardMaterial>>,
+}
+
+/// An outline mesh, the child of the mesh it outlines.
+#[derive(Component)]
+struct OutlineMesh;
+
+/// On a [`Hint`]'s label, pointing at the hinted entity.
+#[derive(Component)]
+#[relationship(relationship_target = HintLabelled)]
+struct HintLabelOf(Entity);
+
+/// On a hinted entity, pointing at its label, which is despawned with it.
+#[derive(Component)]
+#[relationship_target(relationship = HintLabelOf, linked_spawn)]
+struct HintLabelled(Entity);
+
+/// Marks see-through geometry, such as glass display cases, so picking rays pass through it to
+/// whatever is behind, keeping [`Hint`]s on enclosed entities reachable.
+#[derive(Component, Default)]
+#[require(Pickable = Pickable::IGNORE)]
+pub struct PickThrough;
+
 fn draw_mesh_intersections(pointers: Query<&PointerInteraction>, mut gizmos: Gizmos) {
     for (point, normal) in pointers
         .iter()
@@ -62,16 +311,23 @@ fn update_picking_display(
     pointers: Query<&PointerInteraction>,
     names: Query<&Name>,
     hints: Query<&Hint>,
+    parents: Query<&ChildOf>,
     mut text_query: Query<&mut Text, With<PickingDisplay>>,
 ) {
     let mut picked_entity_name = None;
 
     // Find the nearest picked entity
     for interaction in pointers.iter() {
-        if let Some((entity, _hit)) = interaction.get_nearest_hit() {
-            if let Ok(name) = names.get(*entity) {
+        if let Some((hit_entity, _hit)) = interaction.get_nearest_hit() {
+            // Meshes are often unnamed children, e.g. levels of detail, so describe the nearest
+            // named ancestor instead
+            let entity = std::iter::once(*hit_entity)
+                .chain(parents.iter_ancestors(*hit_entity))
+                .find(|entity| names.contains(*entity))
+                .unwrap_or(*hit_entity);
+            if let Ok(name) = names.get(entity) {
                 let mut txt = name.as_str().to_string();
-                if let Ok(hint) = hints.get(*entity) {
+                if let Ok(hint) = hints.get(entity) {
                     txt.push_str(" - ");
                     txt.push_str((hint.text).as_str());
                 }
@@ -97,3 +353,375 @@ fn cleanup_picking_ui(mut commands: Commands, query: Query<Entity, With<PickingD
         commands.entity(entity).despawn();
     }
 }
+
+fn spawn_hint_label(
+    add: On<Add, Hint>,
+    mut commands: Commands,
+    labels: Res<HintLabels>,
+    hints: Query<&Hint>,
+) {
+    let Ok(hint) = hints.get(add.entity) else {
+        return;
+    };
+    commands.spawn((
+        Name::new("Hint label"),
+        HintLabelOf(add.entity),
+        Text::new(hint.text.clone()),
+        TextFont {
+            font_size: labels.font_size,
+            ..default()
+        },
+        TextColor(Color::NONE),
+        Node {
+            position_type: PositionType::Absolute,
+            max_width: Val::Px(280.0),
+            padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+fn despawn_hint_label(
+    remove: On<Remove, Hint>,
+    mut commands: Commands,
+    labelled: Query<&HintLabelled>,
+) {
+    if let Ok(label) = labelled.get(remove.entity) {
+        commands.entity(label.0).try_despawn();
+    }
+}
+
+/// Whether colliders block the view from `origin` to `target`'s label at `anchor`, besides
+/// `target` itself and its relatives.
+pub(crate) fn is_occluded(
+    spatial_query: &SpatialQuery,
+    parents: &Query<&ChildOf>,
+    players: &Query<Entity, With<Player>>,
+    origin: Vec3,
+    anchor: Vec3,
+    target: Entity,
+) -> bool {
+    let Ok(direction) = Dir3::new(anchor - origin) else {
+        return false;
+    };
+    let filter = SpatialQueryFilter::from_excluded_entities(players.iter());
+    let Some(hit) = spatial_query.cast_ray(
+        origin.into(),
+        direction,
+        origin.distance(anchor).into(),
+        true,
+        &filter,
+    ) else {
+        return false;
+    };
+    // Parts of the hinted entity, or what it's part of, don't count
+    let related = |entity: Entity| {
+        entity == target
+            || parents
+                .iter_ancestors(entity)
+                .any(|ancestor| ancestor == target)
+    };
+    !related(hit.entity)
+        && !parents
+            .iter_ancestors(target)
+            .any(|ancestor| ancestor == hit.entity)
+}
+
+fn place_hint_labels(
+    labels: Res<HintLabels>,
+    spatial_query: SpatialQuery,
+    camera: Single<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    targets: Query<(
+        &Hint,
+        &GlobalTransform,
+        Option<&ColliderAabb>,
+        Option<&InheritedVisibility>,
+    )>,
+    parents: Query<&ChildOf>,
+    players: Query<Entity, With<Player>>,
+    mut label_nodes: Query<(
+        &HintLabelOf,
+        &mut Node,
+        &mut Text,
+        &mut TextColor,
+        &mut BackgroundColor,
+        &mut Visibility,
+        &ComputedNode,
+    )>,
+) {
+    let (camera, camera_transform) = *camera;
+    let origin = camera_transform.translation();
+    for (label, mut node, mut text, mut color, mut background, mut visibility, computed) in
+        &mut label_nodes
+    {
+        let Ok((hint, transform, aabb, inherited)) = targets.get(label.0) else {
+            continue;
+        };
+        let top = aabb.map_or(transform.translation(), |aabb| {
+            transform.translation().with_y(aabb.max.y.f32())
+        });
+        let anchor = top + Vec3::Y * labels.height;
+        let distance = origin.distance(anchor);
+        let shown = distance <= labels.max_distance
+            && inherited.is_none_or(|inherited| inherited.get())
+            && !(labels.occlusion
+                && is_occluded(&spatial_query, &parents, &players, origin, anchor, label.0));
+        let position = camera
+            .world_to_viewport(camera_transform, anchor)
+            .ok()
+            .filter(|_| shown);
+        let Some(position) = position else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        if text.0 != hint.text {
+            text.0.clone_from(&hint.text);
+        }
+        // Centred over the anchor, sized from the last layout
+        let size = computed.size() * computed.inverse_scale_factor();
+        node.left = Val::Px(position.x - size.x / 2.0);
+        node.top = Val::Px(position.y - size.y);
+        let opacity = ((labels.max_distance - distance) / labels.fade_distance.max(f32::EPSILON))
+            .clamp(0.0, 1.0);
+        color.0 = Color::WHITE.with_alpha(opacity);
+        background.0 = Color::srgba(0.0, 0.0, 0.0, 0.6 * opacity);
+    }
+}
+
+/// Outlines the nearest [`Hint`] or [`Interactable`] the player is pointing at, or what it's part
+/// of.
+#[allow(clippy::too_many_arguments)]
+fn outline_hovered(
+    mut commands: Commands,
+    settings: Res<HoverOutline>,
+    mut outlined: ResMut<Outlined>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    pointers: Query<&PointerInteraction>,
+    targets: Query<(), Or<(With<Hint>, With<Interactable>)>>,
+    parents: Query<&ChildOf>,
+    children: Query<&Children>,
+    meshes: Query<(&Mesh3d, &Aabb, Option<&RenderLayers>), Without<OutlineMesh>>,
+    outlines: Query<Entity, With<OutlineMesh>>,
+) {
+    let hovered = pointers
+        .iter()
+        .filter_map(|interaction| interaction.get_nearest_hit())
+        .find_map(|(entity, _)| {
+            std::iter::once(*entity)
+                .chain(parents.iter_ancestors(*entity))
+                .find(|entity| targets.contains(*entity))
+        });
+    if settings.is_changed() {
+        let material = outline_material(&settings);
+        match outlined
+            .material
+            .as_ref()
+            .and_then(|handle| materials.get_mut(handle))
+        {
+            Some(existing) => *existing = material,
+            None => outlined.material = Some(materials.add(material)),
+        }
+    }
+    if hovered == outlined.entity && !settings.is_changed() {
+        return;
+    }
+    for outline in &outlines {
+        commands.entity(outline).despawn();
+    }
+    outlined.entity = hovered;
+    let (Some(hovered), Some(material)) = (hovered, outlined.material.clone()) else {
+        return;
+    };
+
+    for entity in std::iter::once(hovered).chain(children.iter_descendants(hovered)) {
+        let Ok((mesh, aabb, layers)) = meshes.get(entity) else {
+            continue;
+        };
+        // Only meshes the player camera draws, so details hidden on other layers stay hidden
+        if layers.is_some_and(|layers| !layers.intersects(&RenderLayers::default())) {
+            continue;
+        }
+        // Grown about the middle of the mesh, by the same width on every side
+        let center = Vec3::from(aabb.center);
+        let size = (Vec3::from(aabb.half_extents) * 2.0).max(Vec3::splat(f32::EPSILON));
+        let scale = Vec3::ONE + 2.0 * settings.width / size;
+        commands.entity(entity).with_child((
+            Name::new("Hover outline"),
+            OutlineMesh,
+            mesh.clone(),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(center - center * scale).with_scale(scale),
+            NotShadowCaster,
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+fn outline_material(settings: &HoverOutline) -> StandardMaterial {
+    StandardMaterial {
+        base_color: settings.color,
+        unlit: true,
+        // Inside out, so only the rim around the mesh in front shows
+        cull_mode: Some(Face::Front),
+        ..default()
+    }
+}
+
+fn clear_outline(
+    mut commands: Commands,
+    mut outlined: ResMut<Outlined>,
+    outlines: Query<Entity, With<OutlineMesh>>,
+) {
+    outlined.entity = None;
+    for outline in &outlines {
+        commands.entity(outline).despawn();
+    }
+}
+
+fn spawn_crosshair(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Crosshair pointer"),
+        CROSSHAIR_POINTER,
+        PointerLocation::default(),
+        PointerPress::default(),
+        PointerInteraction::default(),
+    ));
+    commands.spawn((
+        Name::new("Crosshair"),
+        Crosshair,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(50.0),
+            top: Val::Percent(50.0),
+            width: Val::Px(4.0),
+            height: Val::Px(4.0),
+            margin: UiRect::all(Val::Px(-2.0)),
+            ..default()
+        },
+        BackgroundColor(Color::WHITE.with_alpha(0.8)),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+/// Moves picking to the middle of the screen while the cursor is grabbed and the game's active,
+/// and back to the cursor otherwise.
+#[allow(clippy::too_many_arguments)]
+fn aim_crosshair(
+    settings: Res<CrosshairPicking>,
+    state: Res<State<GameState>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    window: Single<(Entity, &Window, &CursorOptions), With<PrimaryWindow>>,
+    mut ray_map: ResMut<RayMap>,
+    mut pointers: Query<(&PointerId, &mut PointerLocation)>,
+    mut crosshair: Single<&mut Visibility, With<Crosshair>>,
+    mut input: MessageWriter<PointerInput>,
+) {
+    let (window_entity, window, cursor_options) = *window;
+    let active =
+        *state.get() == GameState::Active && cursor_options.grab_mode != CursorGrabMode::None;
+    let location = WindowRef::Entity(window_entity)
+        .normalize(None)
+        .map(|window_ref| Location {
+            target: NormalizedRenderTarget::Window(window_ref),
+            position: window.size() / 2.0,
+        })
+        .filter(|_| active);
+
+    for (id, mut pointer_location) in &mut pointers {
+        if *id == CROSSHAIR_POINTER {
+            if pointer_location.location != location {
+                pointer_location.location = location.clone();
+            }
+        } else if *id == PointerId::Mouse && active && pointer_location.location.is_some() {
+            // The locked cursor is wherever it was left, so it mustn't pick too
+            pointer_location.location = None;
+        }
+    }
+    // Picked by `crosshair_hits` instead, which limits how far it picks
+    ray_map
+        .map
+        .retain(|ray_id, _| ray_id.pointer != CROSSHAIR_POINTER);
+    crosshair.set_if_neq(if location.is_some() && settings.show_crosshair {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+
+    let Some(location) = location else {
+        return;
+    };
+    if mouse_buttons.just_pressed(MouseButton::Left) {
+        input.write(PointerInput::new(
+            CROSSHAIR_POINTER,
+            location.clone(),
+            PointerAction::Press(PointerButton::Primary),
+        ));
+    }
+    if mouse_buttons.just_released(MouseButton::Left) {
+        input.write(PointerInput::new(
+            CROSSHAIR_POINTER,
+            location,
+            PointerAction::Release(PointerButton::Primary),
+        ));
+    }
+}
+
+/// Picking backend for the [`CROSSHAIR_POINTER`], casting along the player camera's forward vector
+/// against meshes like mesh picking does, up to [`CrosshairPicking::max_distance`].
+fn crosshair_hits(
+    settings: Res<CrosshairPicking>,
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    camera: Single<(Entity, &Camera, &GlobalTransform), With<PlayerCamera>>,
+    pickables: Query<&Pickable>,
+    mut ray_cast: MeshRayCast,
+    mut hits: MessageWriter<PointerHits>,
+) {
+    let aiming = pointers
+        .iter()
+        .any(|(id, location)| *id == CROSSHAIR_POINTER && location.location.is_some());
+    if !aiming {
+        return;
+    }
+    let (camera_entity, camera, transform) = *camera;
+    let ray = Ray3d::new(transform.translation(), transform.forward());
+    let filter = |entity: Entity| {
+        pickables
+            .get(entity)
+            .ok()
+            .is_none_or(|pickable| pickable.is_hoverable)
+    };
+    let early_exit = |entity: Entity| {
+        pickables
+            .get(entity)
+            .ok()
+            .is_none_or(|pickable| pickable.should_block_lower)
+    };
+    let cast_settings = MeshRayCastSettings::default()
+        .with_filter(&filter)
+        .with_early_exit_test(&early_exit);
+    let picks = ray_cast
+        .cast_ray(ray, &cast_settings)
+        .iter()
+        .filter(|(_, hit)| hit.distance <= settings.max_distance)
+        .map(|(entity, hit)| {
+            let data = HitData::new(
+                camera_entity,
+                hit.distance,
+                Some(hit.point),
+                Some(hit.normal),
+            );
+            (*entity, data)
+        })
+        .collect::<Vec<_>>();
+    hits.write(PointerHits::new(
+        CROSSHAIR_POINTER,
+        picks,
+        camera.order as f32,
+    ));
+}
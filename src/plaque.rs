@@ -0,0 +1,290 @@
+//! Plaques that show live statistics in the world, as UI rendered to a texture.
+//!
+//! A [`StatsPlaque`] on an entity puts a screen in front of it, facing +Z, showing a title and a
+//! line per [`PlaqueStat`]. The text is laid out with `bevy_ui` by a camera rendering to an image,
+//! which the screen's material displays, so the same approach works for any UI a scene wants on a
+//! surface. Stats can show the time spent in the scene or the latest value of any diagnostic, such
+//! as a scene's own `scene/...` measurements.
+
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::RenderTarget;
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, DiagnosticsStore};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy::ui::UiTargetCamera;
+
+use crate::game_time::GameTime;
+
+pub struct PlaquePlugin;
+
+impl Plugin for PlaquePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(build_plaque)
+            .add_observer(remove_plaque)
+            .add_systems(Update, update_plaques);
+    }
+}
+
+/// How often plaques redraw their text, as their stats rarely change faster.
+const UPDATE_SECONDS: f32 = 0.25;
+
+/// Where a [`PlaqueStat`]'s value comes from.
+#[derive(Debug, Clone)]
+pub enum StatSource {
+    /// Time spent in the scene, not counting time paused.
+    TimeInScene,
+    /// The latest value of a diagnostic.
+    Diagnostic(DiagnosticPath),
+}
+
+/// A labelled line on a [`StatsPlaque`].
+#[derive(Debug, Clone)]
+pub struct PlaqueStat {
+    pub label: String,
+    pub source: StatSource,
+}
+
+/// A screen showing live stats, built in front of its entity.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct StatsPlaque {
+    pub title: String,
+    pub stats: Vec<PlaqueStat>,
+    /// Width and height of the screen in world units.
+    pub size: Vec2,
+    /// Pixels per world unit of the rendered texture.
+    pub pixels_per_unit: f32,
+    pub text_color: Color,
+    pub background: Color,
+}
+
+impl StatsPlaque {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            stats: Vec::new(),
+            size: Vec2::new(1.6, 0.9),
+            pixels_per_unit: 320.0,
+            text_color: Color::srgb(0.95, 0.9, 0.75),
+            background: Color::srgb(0.08, 0.07, 0.06),
+        }
+    }
+
+    pub fn with_stat(mut self, label: impl Into<String>, source: StatSource) -> Self {
+        self.stats.push(PlaqueStat {
+            label: label.into(),
+            source,
+        });
+        self
+    }
+
+    pub fn with_time_in_scene(self, label: impl Into<String>) -> Self {
+        self.with_stat(label, StatSource::TimeInScene)
+    }
+
+    pub fn with_diagnostic(self, label: impl Into<String>, path: DiagnosticPath) -> Self {
+        self.with_stat(label, StatSource::Diagnostic(path))
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size.max(Vec2::splat(0.1));
+        self
+    }
+
+    pub fn with_colors(mut self, text: Color, background: Color) -> Self {
+        self.text_color = text;
+        self.background = background;
+        self
+    }
+
+    fn resolution(&self) -> UVec2 {
+        (self.size * self.pixels_per_unit)
+            .round()
+            .as_uvec2()
+            .max(UVec2::ONE)
+    }
+}
+
+/// What a plaque's built from, with the camera and UI rendering its texture living outside its
+/// hierarchy.
+#[derive(Component)]
+struct PlaqueParts {
+    screen: Entity,
+    camera: Entity,
+    ui: Entity,
+    text: Entity,
+    since_update: f32,
+}
+
+fn build_plaque(
+    add: On<Add, StatsPlaque>,
+    mut commands: Commands,
+    plaques: Query<&StatsPlaque>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok(plaque) = plaques.get(add.entity) else {
+        return;
+    };
+    let resolution = plaque.resolution();
+    let (image, camera) =
+        spawn_ui_texture(&mut commands, &mut images, resolution, plaque.background);
+    commands.entity(camera).insert(Name::new("Plaque camera"));
+    let scale = resolution.y as f32 / 288.0;
+    let text = commands
+        .spawn((
+            Text::default(),
+            TextFont {
+                font_size: 26.0 * scale,
+                ..default()
+            },
+            TextColor(plaque.text_color),
+        ))
+        .id();
+    let ui = commands
+        .spawn((
+            Name::new("Plaque UI"),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(20.0 * scale)),
+                row_gap: Val::Px(12.0 * scale),
+                ..default()
+            },
+            UiTargetCamera(camera),
+            children![(
+                Text::new(plaque.title.clone()),
+                TextFont {
+                    font_size: 34.0 * scale,
+                    ..default()
+                },
+                TextColor(plaque.text_color),
+            )],
+        ))
+        .add_child(text)
+        .id();
+
+    let screen = commands
+        .spawn((
+            Name::new("Plaque screen"),
+            Mesh3d(meshes.add(Rectangle::from_size(plaque.size))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color_texture: Some(image),
+                unlit: true,
+                ..default()
+            })),
+        ))
+        .id();
+    commands
+        .entity(add.entity)
+        .add_child(screen)
+        .insert(PlaqueParts {
+            screen,
+            camera,
+            ui,
+            text,
+            // Filled in on the first update
+            since_update: UPDATE_SECONDS,
+        });
+}
+
+/// Makes a texture for UI to be rendered to, returning it with the camera that renders it, which
+/// UI roots target with a [`UiTargetCamera`].
+pub(crate) fn spawn_ui_texture(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    resolution: UVec2,
+    background: Color,
+) -> (Handle<Image>, Entity) {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: resolution.x,
+            height: resolution.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    let image = images.add(image);
+
+    let camera = commands
+        .spawn((
+            Camera2d,
+            Camera {
+                // Before the main camera, so the texture's ready for it
+                order: -1,
+                clear_color: ClearColorConfig::Custom(background),
+                ..default()
+            },
+            RenderTarget::Image(image.clone().into()),
+        ))
+        .id();
+    (image, camera)
+}
+
+fn remove_plaque(
+    remove: On<Remove, StatsPlaque>,
+    mut commands: Commands,
+    parts: Query<&PlaqueParts>,
+) {
+    let Ok(parts) = parts.get(remove.entity) else {
+        return;
+    };
+    commands.entity(parts.screen).try_despawn();
+    commands.entity(parts.ui).try_despawn();
+    commands.entity(parts.camera).try_despawn();
+}
+
+fn format_stat(source: &StatSource, game_time: &GameTime, store: &DiagnosticsStore) -> String {
+    match source {
+        StatSource::TimeInScene => {
+            let seconds = game_time.elapsed_secs() as u32;
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        }
+        StatSource::Diagnostic(path) => match store.get(path).and_then(Diagnostic::value) {
+            Some(value) if value.fract() == 0.0 => format!("{value}"),
+            Some(value) => format!("{value:.1}"),
+            None => "-".to_string(),
+        },
+    }
+}
+
+fn update_plaques(
+    time: Res<Time>,
+    game_time: Res<GameTime>,
+    store: Res<DiagnosticsStore>,
+    mut plaques: Query<(&StatsPlaque, &mut PlaqueParts)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (plaque, mut parts) in &mut plaques {
+        parts.since_update += time.delta_secs();
+        if parts.since_update < UPDATE_SECONDS {
+            continue;
+        }
+        parts.since_update = 0.0;
+        let Ok(mut text) = texts.get_mut(parts.text) else {
+            continue;
+        };
+        let lines: Vec<String> = plaque
+            .stats
+            .iter()
+            .map(|stat| {
+                format!(
+                    "{}: {}",
+                    stat.label,
+                    format_stat(&stat.source, &game_time, &store)
+                )
+            })
+            .collect();
+        let lines = lines.join("\n");
+        if text.0 != lines {
+            text.0 = lines;
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use avian3d::prelude::*;
 use bevy::anti_alias::contrast_adaptive_sharpening::ContrastAdaptiveSharpening;
 use bevy::anti_alias::taa::TemporalAntiAliasing;
 use bevy::core_pipeline::prepass::DepthPrepass;
@@ -6,36 +7,82 @@ use bevy::prelude::*;
 use bevy::render::experimental::occlusion_culling::OcclusionCulling;
 use bevy::render::view::Hdr;
 
+use crate::avatar::AvatarPlugin;
 use crate::firstsight::{
     DEFAULT_PLAYER_HEIGHT, DEFAULT_PLAYER_RADIUS, FirstSightPlugin, PlayerControllerBundle,
-    create_player_control_scheme_config,
+    create_player_control_scheme_config, dynamic_body,
 };
+pub use crate::firstsight::{PlayerCameraSet, PlayerInputSet};
+pub use crate::kinematic::KinematicController;
+pub use crate::movement::{MovementMode, MovementModeSettings, MovementModeVolume};
+use crate::player_events::PlayerEventsPlugin;
+use crate::thirdperson::ThirdPersonPlugin;
 
-pub(crate) struct PlayerPlugin;
+/// Spawns the player and its camera. The player has a dynamic physics body by default, see
+/// [`PlayerPlugin::kinematic`] for the alternative.
+#[derive(Debug, Clone, Default)]
+pub struct PlayerPlugin {
+    /// Settings for a kinematic body, or `None` for a dynamic one.
+    pub kinematic: Option<KinematicController>,
+}
+
+impl PlayerPlugin {
+    /// A kinematic body, which sweeps its collider through the scene instead of being simulated.
+    /// It doesn't jitter on thin colliders, but isn't pushed by other bodies or carried by moving
+    /// platforms.
+    pub fn kinematic() -> Self {
+        Self::kinematic_with(KinematicController::default())
+    }
+
+    pub fn kinematic_with(controller: KinematicController) -> Self {
+        Self {
+            kinematic: Some(controller),
+        }
+    }
+}
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(FirstSightPlugin)
-            .add_systems(Startup, setup);
+        app.add_plugins((
+            FirstSightPlugin,
+            ThirdPersonPlugin,
+            AvatarPlugin,
+            PlayerEventsPlugin,
+        ))
+        .insert_resource(PlayerBody(self.kinematic.clone()))
+        .add_systems(Startup, setup);
     }
 }
 
-/// Attached to the player entity, of which there should only ever be one, so it can be accessed using `Single<&Player>` queries.
+/// The kinematic settings the player is spawned with, if any.
+#[derive(Resource)]
+struct PlayerBody(Option<KinematicController>);
+
+/// Attached to the player entity, of which there should only ever be one, so it can be accessed
+/// using `Single<&Player>` queries.
 #[derive(Component)]
 #[require(Transform)]
 pub struct Player;
 
 fn setup(
     mut commands: Commands,
+    body: Res<PlayerBody>,
     mut control_scheme_configs: ResMut<Assets<crate::firstsight::PlayerControlSchemeConfig>>,
 ) {
-    let control_config = create_player_control_scheme_config(&mut control_scheme_configs);
-
-    commands.spawn((
+    let mut player = commands.spawn((
         Name::new("Player"),
         Player,
-        PlayerControllerBundle::new(DEFAULT_PLAYER_RADIUS, DEFAULT_PLAYER_HEIGHT, control_config),
+        PlayerControllerBundle::new(DEFAULT_PLAYER_RADIUS, DEFAULT_PLAYER_HEIGHT),
     ));
+    match &body.0 {
+        Some(kinematic) => {
+            player.insert((RigidBody::Kinematic, kinematic.clone()));
+        }
+        None => {
+            let control_config = create_player_control_scheme_config(&mut control_scheme_configs);
+            player.insert(dynamic_body(DEFAULT_PLAYER_RADIUS, control_config));
+        }
+    }
 
     commands.spawn((
         Name::new("Player camera"),
@@ -50,5 +97,6 @@ fn setup(
         // ScreenSpaceAmbientOcclusion::default(), // ❌ can cause screen flashing
         TemporalAntiAliasing::default(),
         Msaa::Off,
+        SpatialListener::new(0.3),
     ));
 }
@@ -0,0 +1,209 @@
+//! Events marking moments in the player's life, for scenes to hook gameplay onto.
+//!
+//! Each is an [`EntityEvent`] on the player, so scenes can observe them globally or on the player
+//! entity, rather than querying the player every frame to spot them:
+//!
+//! - [`PlayerSpawned`] once the player is spawned.
+//! - [`PlayerRespawned`] after triggering [`RespawnPlayer`] moves them back to a spawn point.
+//! - [`PlayerEnteredWater`] and [`PlayerLeftWater`] as they start and stop swimming, or go below
+//!   and back above an [`Underwater`] surface.
+//! - [`PlayerLanded`] on touching down after being in the air, with how fast they were going.
+//! - [`PlayerInteracted`] whenever they interact with an
+//!   [`Interactable`](crate::interact::Interactable).
+
+#![allow(clippy::useless_conversion)]
+use avian3d::math::AsF32;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::footsteps::GROUND_PROBE;
+use crate::interact::Interacted;
+use crate::movement::MovementMode;
+use crate::player::Player;
+use crate::state::PausableSystems;
+use crate::swim::Swimming;
+use crate::underwater::Underwater;
+
+pub(crate) struct PlayerEventsPlugin;
+
+impl Plugin for PlayerEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(announce_spawn)
+            .add_observer(respawn_player)
+            .add_observer(announce_interaction)
+            .add_systems(Update, (track_water, track_landing).in_set(PausableSystems));
+    }
+}
+
+/// Seconds the player has to be in the air for touching down to count as landing, so walking
+/// over bumps doesn't.
+const MIN_AIR_TIME: f32 = 0.1;
+
+/// Triggered on the player once they're spawned.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct PlayerSpawned {
+    pub entity: Entity,
+}
+
+/// Moves the player to `transform` and stops them, e.g. after falling off the world. Triggered by
+/// scenes with `commands.trigger(...)`.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RespawnPlayer {
+    pub transform: Transform,
+}
+
+impl RespawnPlayer {
+    pub fn at(position: Vec3) -> Self {
+        Self {
+            transform: Transform::from_translation(position),
+        }
+    }
+}
+
+/// Triggered on the player after a [`RespawnPlayer`] has moved them.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct PlayerRespawned {
+    pub entity: Entity,
+    /// Where they were before respawning.
+    pub from: Vec3,
+    pub position: Vec3,
+}
+
+/// Triggered on the player when they start swimming, or go below the [`Underwater`] surface.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct PlayerEnteredWater {
+    pub entity: Entity,
+}
+
+/// Triggered on the player when they're no longer swimming or below the [`Underwater`] surface.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct PlayerLeftWater {
+    pub entity: Entity,
+}
+
+/// Triggered on the player when they touch down on the ground after being in the air.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct PlayerLanded {
+    pub entity: Entity,
+    /// The player's velocity just before touching down, so a hard landing has a large negative y.
+    pub velocity: Vec3,
+}
+
+/// Triggered on the player when they interact with an interactable.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct PlayerInteracted {
+    pub entity: Entity,
+    /// The interactable that was interacted with.
+    pub target: Entity,
+}
+
+/// Whether the player was last in the water and on the ground, to spot them changing.
+#[derive(Component, Debug, Default)]
+struct LifecycleTracker {
+    in_water: bool,
+    grounded: bool,
+    air_time: f32,
+    velocity: Vec3,
+}
+
+fn announce_spawn(add: On<Add, Player>, mut commands: Commands) {
+    commands
+        .entity(add.entity)
+        .insert(LifecycleTracker::default());
+    commands.trigger(PlayerSpawned { entity: add.entity });
+}
+
+fn respawn_player(
+    respawn: On<RespawnPlayer>,
+    mut commands: Commands,
+    player: Single<(Entity, &mut Transform, Option<&mut LinearVelocity>), With<Player>>,
+) {
+    let (entity, mut transform, velocity) = player.into_inner();
+    let from = transform.translation;
+    *transform = respawn.transform;
+    if let Some(mut velocity) = velocity {
+        velocity.0 = Vec3::ZERO;
+    }
+    commands.trigger(PlayerRespawned {
+        entity,
+        from,
+        position: transform.translation,
+    });
+}
+
+fn announce_interaction(
+    interacted: On<Interacted>,
+    mut commands: Commands,
+    player: Single<Entity, With<Player>>,
+) {
+    commands.trigger(PlayerInteracted {
+        entity: *player,
+        target: interacted.entity,
+    });
+}
+
+fn track_water(
+    mut commands: Commands,
+    underwater: Option<Res<Underwater>>,
+    player: Single<(Entity, &Transform, Has<Swimming>, &mut LifecycleTracker), With<Player>>,
+) {
+    let (entity, transform, swimming, mut tracker) = player.into_inner();
+    let below_surface =
+        underwater.is_some_and(|underwater| transform.translation.y < underwater.surface_height);
+    let in_water = swimming || below_surface;
+    if in_water == tracker.in_water {
+        return;
+    }
+    tracker.in_water = in_water;
+    if in_water {
+        commands.trigger(PlayerEnteredWater { entity });
+    } else {
+        commands.trigger(PlayerLeftWater { entity });
+    }
+}
+
+fn track_landing(
+    mut commands: Commands,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    player: Single<
+        (
+            Entity,
+            &Transform,
+            &MovementMode,
+            Option<&LinearVelocity>,
+            &mut LifecycleTracker,
+        ),
+        With<Player>,
+    >,
+) {
+    let (entity, transform, mode, velocity, mut tracker) = player.into_inner();
+    // Flying and swimming never land
+    let grounded = mode.is_free()
+        || spatial_query
+            .cast_ray(
+                transform.translation.into(),
+                transform.down(),
+                GROUND_PROBE.into(),
+                true,
+                &SpatialQueryFilter::from_excluded_entities([entity]),
+            )
+            .is_some();
+    let landed = grounded && !tracker.grounded && !mode.is_free();
+    if landed && tracker.air_time >= MIN_AIR_TIME {
+        commands.trigger(PlayerLanded {
+            entity,
+            velocity: tracker.velocity,
+        });
+    }
+    tracker.grounded = grounded;
+    tracker.air_time = if grounded {
+        0.0
+    } else {
+        tracker.air_time + time.delta_secs()
+    };
+    // Remembered from the last frame in the air, as it's already stopped by the frame it lands
+    if !grounded {
+        tracker.velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.0.f32());
+    }
+}
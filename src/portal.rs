@@ -0,0 +1,280 @@
+//! Portals that teleport whatever steps into them to a linked portal elsewhere in the scene.
+//!
+//! A [`Portal`] is a box around its entity, usually a doorway-sized slab, linked to a destination
+//! portal. When the player's inside it they're moved through to the destination, coming out at the
+//! same offset from it as they went in and moving in the same direction relative to it, so walking
+//! into the front of one portal walks out of the front of the other. The view briefly fades in from
+//! black to cover the jump. Portals can also carry dynamic bodies with
+//! [`Portal::with_carry_bodies`]. Anything that's just come through a portal can't go back through
+//! one until it's left them all, so arriving inside the destination doesn't send it straight back.
+//! [`Teleported`] is triggered on the portal each time something goes through.
+
+#![allow(clippy::useless_conversion)]
+
+use std::f32::consts::PI;
+
+use avian3d::math::{AdjustPrecision, AsF32};
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::firstsight::PlayerCamera;
+use crate::player::Player;
+
+pub struct PortalPlugin;
+
+impl Plugin for PortalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                release_travellers,
+                teleport_player,
+                teleport_bodies,
+                fade_portal_overlay,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct Portal {
+    /// The portal to come out of, which usually links back to this one.
+    pub destination: Entity,
+    pub half_extents: Vec3,
+    /// Whether dynamic bodies go through too, or only the player.
+    pub carry_bodies: bool,
+    /// Seconds for the view to fade in from black after the player goes through.
+    pub fade_seconds: f32,
+}
+
+impl Portal {
+    pub fn new(destination: Entity, half_extents: Vec3) -> Self {
+        Self {
+            destination,
+            half_extents,
+            carry_bodies: false,
+            fade_seconds: 0.4,
+        }
+    }
+
+    /// Links `a` and `b` to each other, both with the size and settings of `portal`, whose own
+    /// destination is ignored.
+    pub fn pair(commands: &mut Commands, a: Entity, b: Entity, portal: Portal) {
+        commands.entity(a).insert(Portal {
+            destination: b,
+            ..portal.clone()
+        });
+        commands.entity(b).insert(Portal {
+            destination: a,
+            ..portal
+        });
+    }
+
+    pub fn with_carry_bodies(mut self, carry_bodies: bool) -> Self {
+        self.carry_bodies = carry_bodies;
+        self
+    }
+
+    pub fn with_fade_seconds(mut self, seconds: f32) -> Self {
+        self.fade_seconds = seconds.max(0.0);
+        self
+    }
+
+    fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let local = transform.affine().inverse().transform_point3(point);
+        local.abs().cmple(self.half_extents).all()
+    }
+}
+
+/// Triggered on a [`Portal`] when something goes through it.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct Teleported {
+    pub entity: Entity,
+    pub traveller: Entity,
+    pub destination: Entity,
+}
+
+/// On something that's come through a portal, until it's outside every portal again.
+#[derive(Component)]
+struct JustTeleported;
+
+/// Full-screen overlay fading out after the player goes through a portal.
+#[derive(Component)]
+struct PortalFade {
+    elapsed: f32,
+    seconds: f32,
+}
+
+/// Where a traveller at `transform` ends up going from portal `from` to `to`, and the rotation
+/// from one to the other, which also applies to its velocity.
+fn through(
+    from: &GlobalTransform,
+    to: &GlobalTransform,
+    transform: &Transform,
+) -> (Transform, Quat) {
+    let (_, from_rotation, _) = from.to_scale_rotation_translation();
+    let (_, to_rotation, _) = to.to_scale_rotation_translation();
+    // Turned around, so going in the front means coming out the front
+    let rotation = to_rotation * Quat::from_rotation_y(PI) * from_rotation.inverse();
+    let local = from
+        .affine()
+        .inverse()
+        .transform_point3(transform.translation);
+    let translation = to
+        .affine()
+        .transform_point3(Quat::from_rotation_y(PI) * local);
+    let moved = Transform {
+        translation,
+        rotation: rotation * transform.rotation,
+        scale: transform.scale,
+    };
+    (moved, rotation)
+}
+
+/// The portal whose destination `point` should go to, if it's inside one.
+fn entered<'a>(
+    portals: &'a Query<(Entity, &Portal, &GlobalTransform)>,
+    point: Vec3,
+) -> Option<(Entity, &'a Portal, &'a GlobalTransform)> {
+    portals
+        .iter()
+        .find(|(_, portal, transform)| portal.contains(transform, point))
+}
+
+fn release_travellers(
+    mut commands: Commands,
+    portals: Query<(Entity, &Portal, &GlobalTransform)>,
+    travellers: Query<(Entity, &GlobalTransform), With<JustTeleported>>,
+) {
+    for (entity, transform) in &travellers {
+        if entered(&portals, transform.translation()).is_none() {
+            commands.entity(entity).remove::<JustTeleported>();
+        }
+    }
+}
+
+fn teleport_player(
+    mut commands: Commands,
+    portals: Query<(Entity, &Portal, &GlobalTransform)>,
+    player: Single<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Transform,
+            Option<&mut LinearVelocity>,
+        ),
+        (With<Player>, Without<JustTeleported>),
+    >,
+    mut cameras: Query<&mut PlayerCamera>,
+) {
+    let (entity, global_transform, mut transform, velocity) = player.into_inner();
+    let Some((portal_entity, portal, portal_transform)) =
+        entered(&portals, global_transform.translation())
+    else {
+        return;
+    };
+    let Ok((_, _, destination)) = portals.get(portal.destination) else {
+        return;
+    };
+    let (moved, rotation) = through(portal_transform, destination, &transform);
+    *transform = moved;
+    if let Some(mut velocity) = velocity {
+        velocity.0 = (rotation * velocity.0.f32()).adjust_precision();
+    }
+    let (yaw, _, _) = rotation.to_euler(EulerRot::YXZ);
+    for mut camera in &mut cameras {
+        camera.turn(yaw);
+    }
+
+    commands.entity(entity).insert(JustTeleported);
+    commands.trigger(Teleported {
+        entity: portal_entity,
+        traveller: entity,
+        destination: portal.destination,
+    });
+    if portal.fade_seconds > 0.0 {
+        commands.spawn((
+            Name::new("Portal fade"),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+            Pickable::IGNORE,
+            // Below the pause menu and loading screen
+            GlobalZIndex(i32::MAX - 2),
+            PortalFade {
+                elapsed: 0.0,
+                seconds: portal.fade_seconds,
+            },
+        ));
+    }
+}
+
+fn teleport_bodies(
+    mut commands: Commands,
+    portals: Query<(Entity, &Portal, &GlobalTransform)>,
+    mut bodies: Query<
+        (
+            Entity,
+            &RigidBody,
+            &GlobalTransform,
+            &mut Transform,
+            Option<&mut LinearVelocity>,
+            Option<&mut AngularVelocity>,
+        ),
+        (Without<Player>, Without<JustTeleported>, Without<Portal>),
+    >,
+) {
+    for (entity, body, global_transform, mut transform, linear, angular) in &mut bodies {
+        if !body.is_dynamic() {
+            continue;
+        }
+        let Some((portal_entity, portal, portal_transform)) =
+            entered(&portals, global_transform.translation())
+        else {
+            continue;
+        };
+        if !portal.carry_bodies {
+            continue;
+        }
+        let Ok((_, _, destination)) = portals.get(portal.destination) else {
+            continue;
+        };
+        let (moved, rotation) = through(portal_transform, destination, &transform);
+        *transform = moved;
+        if let Some(mut linear) = linear {
+            linear.0 = (rotation * linear.0.f32()).adjust_precision();
+        }
+        if let Some(mut angular) = angular {
+            angular.0 = (rotation * angular.0.f32()).adjust_precision();
+        }
+        commands.entity(entity).insert(JustTeleported);
+        commands.trigger(Teleported {
+            entity: portal_entity,
+            traveller: entity,
+            destination: portal.destination,
+        });
+    }
+}
+
+fn fade_portal_overlay(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut overlays: Query<(Entity, &mut PortalFade, &mut BackgroundColor)>,
+) {
+    for (entity, mut fade, mut color) in &mut overlays {
+        fade.elapsed += time.delta_secs();
+        let opacity = 1.0 - fade.elapsed / fade.seconds.max(f32::EPSILON);
+        if opacity <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        color.0.set_alpha(opacity);
+    }
+}
@@ -0,0 +1,235 @@
+//! A simple electrical network, so props can lose power and have it restored.
+//!
+//! Power starts at a running [`Generator`] and flows down [`PoweredBy`] relationships to anything
+//! wired to it, through any number of [`Breaker`]s, which cut power to everything downstream while
+//! open. Everything power reaches has the [`Powered`] marker, and [`PowerRestored`] and
+//! [`PowerCut`] are triggered on each entity as power reaches it, including the first time, and is
+//! lost again. Lights wired in go out without power, and scenes observe the events to do the same
+//! for their own devices. Generators and breakers with an [`Interactable`] are switched over when
+//! interacted with, so restoring power to a wing can be an objective.
+//!
+//! A [`Wire`] builds a drooping cable mesh to show where power runs, but is only for show.
+//!
+//! [`Interactable`]: crate::interact::Interactable
+
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+
+use crate::interact::Interacted;
+
+pub struct PowerPlugin;
+
+impl Plugin for PowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Generator>()
+            .register_type::<Breaker>()
+            .add_systems(Update, distribute_power)
+            .add_observer(throw_switch);
+    }
+}
+
+/// Longest chain of [`PoweredBy`] followed back to a generator, which also stops loops.
+const MAX_DEPTH: usize = 64;
+
+/// Supplies power to everything [`PoweredBy`] it while running.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct Generator {
+    pub running: bool,
+}
+
+impl Generator {
+    pub fn new(running: bool) -> Self {
+        Self { running }
+    }
+}
+
+/// Passes power on to everything [`PoweredBy`] it while closed, and cuts it off while open.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct Breaker {
+    pub closed: bool,
+}
+
+impl Breaker {
+    pub fn new(closed: bool) -> Self {
+        Self { closed }
+    }
+}
+
+/// The generator or breaker upstream of an entity in a power network.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[relationship(relationship_target = Powers)]
+pub struct PoweredBy(pub Entity);
+
+/// Everything wired downstream of a generator or breaker, kept up to date by [`PoweredBy`].
+#[derive(Component, Debug, Default)]
+#[relationship_target(relationship = PoweredBy)]
+pub struct Powers(Vec<Entity>);
+
+/// On generators, breakers and devices that power currently reaches.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Powered;
+
+/// Triggered on an entity in a power network when power reaches it.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct PowerRestored {
+    pub entity: Entity,
+}
+
+/// Triggered on an entity in a power network when it loses power.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct PowerCut {
+    pub entity: Entity,
+}
+
+/// A cable drooping between two points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wire {
+    pub from: Vec3,
+    pub to: Vec3,
+    /// How far the middle hangs below a straight line between the ends.
+    pub sag: f32,
+    pub radius: f32,
+}
+
+impl Wire {
+    pub fn new(from: Vec3, to: Vec3) -> Self {
+        Self {
+            from,
+            to,
+            sag: from.distance(to) * 0.1,
+            radius: 0.02,
+        }
+    }
+
+    pub fn with_sag(mut self, sag: f32) -> Self {
+        self.sag = sag.max(0.0);
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius.max(0.0);
+        self
+    }
+
+    /// Point `t` of the way along the cable, hanging in a parabola.
+    pub fn point(&self, t: f32) -> Vec3 {
+        self.from.lerp(self.to, t) - Vec3::Y * self.sag * 4.0 * t * (1.0 - t)
+    }
+
+    /// Builds the cable as a tube, in the same space as its ends, with UVs running around (u) and
+    /// along (v) it.
+    pub fn mesh(&self) -> Mesh {
+        const SEGMENTS: usize = 24;
+        const SIDES: usize = 6;
+        let points: Vec<Vec3> = (0..=SEGMENTS)
+            .map(|i| self.point(i as f32 / SEGMENTS as f32))
+            .collect();
+        let mut positions = Vec::with_capacity(points.len() * (SIDES + 1));
+        let mut normals = Vec::with_capacity(points.len() * (SIDES + 1));
+        let mut uvs = Vec::with_capacity(points.len() * (SIDES + 1));
+        // Carried along the cable so the rings don't twist
+        let mut across = (points[1] - points[0])
+            .normalize_or(Vec3::X)
+            .any_orthonormal_vector();
+        for (i, point) in points.iter().enumerate() {
+            let tangent =
+                (points[(i + 1).min(SEGMENTS)] - points[i.saturating_sub(1)]).normalize_or(Vec3::X);
+            across = across
+                .reject_from_normalized(tangent)
+                .normalize_or(tangent.any_orthonormal_vector());
+            let up = tangent.cross(across);
+            let v = i as f32 / SEGMENTS as f32;
+            for side in 0..=SIDES {
+                let u = side as f32 / SIDES as f32;
+                let (sin, cos) = (u * std::f32::consts::TAU).sin_cos();
+                let normal = across * cos + up * sin;
+                positions.push((*point + normal * self.radius).to_array());
+                normals.push(normal.to_array());
+                uvs.push([u, v]);
+            }
+        }
+        let mut indices = Vec::with_capacity(SEGMENTS * SIDES * 6);
+        let ring = (SIDES + 1) as u32;
+        for segment in 0..SEGMENTS as u32 {
+            for side in 0..SIDES as u32 {
+                let i = segment * ring + side;
+                indices.extend_from_slice(&[i, i + 1, i + ring, i + 1, i + ring + 1, i + ring]);
+            }
+        }
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+}
+
+type Node<'a> = (
+    Option<&'a Generator>,
+    Option<&'a Breaker>,
+    Option<&'a PoweredBy>,
+);
+
+/// Whether power reaches `entity`, following it back up the network to a generator.
+fn is_supplied(nodes: &Query<Node>, entity: Entity) -> bool {
+    let mut current = entity;
+    for _ in 0..MAX_DEPTH {
+        let Ok((generator, breaker, upstream)) = nodes.get(current) else {
+            return false;
+        };
+        if breaker.is_some_and(|breaker| !breaker.closed) {
+            return false;
+        }
+        if let Some(generator) = generator {
+            return generator.running;
+        }
+        let Some(upstream) = upstream else {
+            return false;
+        };
+        current = upstream.0;
+    }
+    false
+}
+
+fn distribute_power(
+    mut commands: Commands,
+    nodes: Query<Node>,
+    network: Query<(Entity, Has<Powered>), Or<(With<Generator>, With<Breaker>, With<PoweredBy>)>>,
+) {
+    for (entity, powered) in &network {
+        let supplied = is_supplied(&nodes, entity);
+        if supplied == powered {
+            continue;
+        }
+        if supplied {
+            commands.entity(entity).insert(Powered);
+            commands.trigger(PowerRestored { entity });
+        } else {
+            commands.entity(entity).remove::<Powered>();
+            commands.trigger(PowerCut { entity });
+        }
+    }
+}
+
+fn throw_switch(
+    interacted: On<Interacted>,
+    mut switches: Query<(Option<&mut Generator>, Option<&mut Breaker>)>,
+) {
+    let Ok((generator, breaker)) = switches.get_mut(interacted.entity) else {
+        return;
+    };
+    if let Some(mut generator) = generator {
+        generator.running = !generator.running;
+    }
+    if let Some(mut breaker) = breaker {
+        breaker.closed = !breaker.closed;
+    }
+}
@@ -0,0 +1,355 @@
+//! Prefabs: hierarchies of entities described as data, and spawned whole with one call.
+//!
+//! A [`Prefab`] is a node with a name, transform, and optionally a mesh, material, collider and
+//! rigid body, and any number of child prefabs. Prefabs are built in Rust with `Prefab::cuboid`,
+//! `Prefab::cylinder` and so on, or read from a `.prefab.ron` file, and [`Prefab::spawn`] spawns the
+//! whole hierarchy. Every node is given a [`Name`], primitive shapes get a matching mesh and
+//! collider, and nodes with a collider become [`RigidBody::Static`] unless they set a body of their
+//! own or are part of a prefab that does. Meshes and materials are referred to by [`SceneAssets`]
+//! key or asset path, as in scene files, or by handle from Rust, which can also add any other
+//! components with [`Prefab::with_components`].
+//!
+//! A [`PrefabFile`] spawns a prefab file under its entity once loaded, e.g.
+//!
+//! ```text
+//! (
+//!     name: "Bench",
+//!     children: [
+//!         (
+//!             name: "Seat",
+//!             transform: (translation: (0.0, 0.45, 0.0), rotation: (0.0, 0.0, 0.0, 1.0), scale: (1.0, 1.0, 1.0)),
+//!             shape: Some(Cuboid(size: (2.0, 0.1, 0.5))),
+//!             material: Some("oak"),
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+//! The file is respawned when it changes if Bevy's `file_watcher` feature is enabled.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::{fmt, fs, io};
+
+use avian3d::prelude::*;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::scene::{SceneAssets, SceneBody, SceneCollider};
+
+pub struct PrefabPlugin;
+
+impl Plugin for PrefabPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Prefab>()
+            .register_asset_loader(PrefabLoader)
+            .add_systems(Update, (load_prefab_files, spawn_prefab_files).chain());
+    }
+}
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Prefab {
+    pub name: String,
+    #[serde(default)]
+    pub transform: Transform,
+    /// Primitive shape of the mesh, and of the collider unless another is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<SceneCollider>,
+    /// Mesh by [`SceneAssets`] key or asset path, shown instead of the shape's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesh: Option<String>,
+    /// [`StandardMaterial`] by [`SceneAssets`] key or asset path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<String>,
+    #[serde(default)]
+    pub collider: PrefabCollider,
+    /// The node's rigid body, or `None` to make it static if it has a collider and isn't part of
+    /// a body already.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<SceneBody>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Prefab>,
+    #[serde(skip)]
+    material_handle: Option<Handle<StandardMaterial>>,
+    #[serde(skip)]
+    components: Vec<InsertComponents>,
+}
+
+/// Which collider a [`Prefab`] node has.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum PrefabCollider {
+    /// One matching its shape, if it has one.
+    #[default]
+    Auto,
+    None,
+    Shape(SceneCollider),
+}
+
+/// Inserts components added with [`Prefab::with_components`].
+#[derive(Clone)]
+struct InsertComponents(Arc<dyn Fn(&mut EntityWorldMut) + Send + Sync>);
+
+impl fmt::Debug for InsertComponents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("InsertComponents(..)")
+    }
+}
+
+impl Prefab {
+    /// A node with no mesh, e.g. to group others under.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..default()
+        }
+    }
+
+    /// A node with a primitive mesh and collider of `shape`.
+    pub fn shape(name: impl Into<String>, shape: SceneCollider) -> Self {
+        Self {
+            shape: Some(shape),
+            ..Self::new(name)
+        }
+    }
+
+    pub fn cuboid(name: impl Into<String>, size: Vec3) -> Self {
+        Self::shape(name, SceneCollider::Cuboid { size })
+    }
+
+    pub fn sphere(name: impl Into<String>, radius: f32) -> Self {
+        Self::shape(name, SceneCollider::Sphere { radius })
+    }
+
+    pub fn cylinder(name: impl Into<String>, radius: f32, height: f32) -> Self {
+        Self::shape(name, SceneCollider::Cylinder { radius, height })
+    }
+
+    pub fn capsule(name: impl Into<String>, radius: f32, length: f32) -> Self {
+        Self::shape(name, SceneCollider::Capsule { radius, length })
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    pub fn with_translation(mut self, translation: Vec3) -> Self {
+        self.transform.translation = translation;
+        self
+    }
+
+    /// Shows the mesh registered in [`SceneAssets`] under `key`, or at asset path `key`.
+    pub fn with_mesh(mut self, key: impl Into<String>) -> Self {
+        self.mesh = Some(key.into());
+        self
+    }
+
+    /// Uses the material registered in [`SceneAssets`] under `key`, or at asset path `key`.
+    pub fn with_material(mut self, key: impl Into<String>) -> Self {
+        self.material = Some(key.into());
+        self
+    }
+
+    /// Uses `material` directly, for materials created in code. It isn't saved with the prefab.
+    pub fn with_material_handle(mut self, material: Handle<StandardMaterial>) -> Self {
+        self.material_handle = Some(material);
+        self
+    }
+
+    pub fn with_collider(mut self, collider: SceneCollider) -> Self {
+        self.collider = PrefabCollider::Shape(collider);
+        self
+    }
+
+    pub fn without_collider(mut self) -> Self {
+        self.collider = PrefabCollider::None;
+        self
+    }
+
+    pub fn with_body(mut self, body: RigidBody) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn with_child(mut self, child: Prefab) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn with_children(mut self, children: impl IntoIterator<Item = Prefab>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Adds `components` to the node each time it's spawned. They aren't saved with the prefab.
+    pub fn with_components(mut self, components: impl Bundle + Clone) -> Self {
+        self.components.push(InsertComponents(Arc::new(
+            move |entity: &mut EntityWorldMut| {
+                entity.insert(components.clone());
+            },
+        )));
+        self
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(io::Error::other)?;
+        fs::write(path, text)
+    }
+
+    /// Spawns the prefab and everything in it, returning its root entity.
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let root = commands.spawn_empty().id();
+        commands.queue(move |world: &mut World| self.build(world, root, false));
+        root
+    }
+
+    fn build(&self, world: &mut World, entity: Entity, in_body: bool) {
+        let mesh = match (&self.mesh, self.shape) {
+            (Some(key), _) => Some(
+                world
+                    .resource::<SceneAssets>()
+                    .mesh(key)
+                    .unwrap_or_else(|| world.resource::<AssetServer>().load(key.clone())),
+            ),
+            (None, Some(shape)) => Some(world.resource_mut::<Assets<Mesh>>().add(shape.to_mesh())),
+            (None, None) => None,
+        };
+        let material = self.material_handle.clone().or_else(|| {
+            let key = self.material.as_ref()?;
+            Some(
+                world
+                    .resource::<SceneAssets>()
+                    .material(key)
+                    .unwrap_or_else(|| world.resource::<AssetServer>().load(key.clone())),
+            )
+        });
+        let collider = match self.collider {
+            PrefabCollider::Auto => self.shape,
+            PrefabCollider::None => None,
+            PrefabCollider::Shape(collider) => Some(collider),
+        };
+        let body = self
+            .body
+            .map(RigidBody::from)
+            .or((collider.is_some() && !in_body).then_some(RigidBody::Static));
+
+        // Despawned before the prefab got the chance to spawn
+        let Ok(mut node) = world.get_entity_mut(entity) else {
+            return;
+        };
+        node.insert((
+            Name::new(self.name.clone()),
+            self.transform,
+            Visibility::default(),
+        ));
+        if let Some(mesh) = mesh {
+            node.insert(Mesh3d(mesh));
+        }
+        if let Some(material) = material {
+            node.insert(MeshMaterial3d(material));
+        }
+        if let Some(collider) = collider {
+            node.insert(collider.to_collider());
+        }
+        if let Some(body) = body {
+            node.insert(body);
+        }
+        for insert in &self.components {
+            (insert.0)(&mut node);
+        }
+
+        for child in &self.children {
+            let child_entity = world.spawn(ChildOf(entity)).id();
+            child.build(world, child_entity, in_body || body.is_some());
+        }
+    }
+}
+
+/// Asset path of a `.prefab.ron` file to spawn under this entity once loaded.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct PrefabFile {
+    pub path: String,
+    handle: Option<Handle<Prefab>>,
+    spawned: Option<Entity>,
+}
+
+impl PrefabFile {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            handle: None,
+            spawned: None,
+        }
+    }
+}
+
+#[derive(Default, TypePath)]
+struct PrefabLoader;
+
+impl AssetLoader for PrefabLoader {
+    type Asset = Prefab;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> io::Result<Prefab> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["prefab.ron"]
+    }
+}
+
+fn load_prefab_files(
+    mut files: Query<&mut PrefabFile, Added<PrefabFile>>,
+    asset_server: Res<AssetServer>,
+) {
+    for mut file in &mut files {
+        file.handle = Some(asset_server.load(file.path.clone()));
+    }
+}
+
+fn spawn_prefab_files(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<Prefab>>,
+    prefabs: Res<Assets<Prefab>>,
+    mut files: Query<(Entity, &mut PrefabFile)>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+        let Some(prefab) = prefabs.get(id) else {
+            continue;
+        };
+        for (entity, mut file) in &mut files {
+            if !file.handle.as_ref().is_some_and(|handle| handle.id() == id) {
+                continue;
+            }
+            debug!("Spawning prefab {}", file.path);
+            if let Some(previous) = file.spawned.take() {
+                commands.entity(previous).try_despawn();
+            }
+            let spawned = prefab.clone().spawn(&mut commands);
+            commands.entity(spawned).insert(ChildOf(entity));
+            file.spawned = Some(spawned);
+        }
+    }
+}
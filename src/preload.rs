@@ -0,0 +1,177 @@
+//! Loading screen that preloads a scene's assets and warms up render pipelines.
+//!
+//! Scenes list the assets they need up front in a [`PreloadManifest`]. While in
+//! [`PreloadState::Loading`] those are loaded behind an opaque overlay, then during
+//! [`PreloadState::WarmingUp`] frustum culling is briefly disabled so every mesh is drawn at least
+//! once, forcing its material's pipeline to be compiled before the scene is revealed. This avoids
+//! hitches the first time a custom material or large mesh comes into view.
+
+use bevy::asset::LoadedUntypedAsset;
+use bevy::camera::visibility::NoFrustumCulling;
+use bevy::prelude::*;
+
+pub struct PreloadPlugin;
+
+impl Plugin for PreloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<PreloadState>()
+            .init_resource::<PreloadManifest>()
+            .add_systems(
+                OnEnter(PreloadState::Loading),
+                (start_loading, show_loading_ui),
+            )
+            .add_systems(
+                Update,
+                check_loading_progress.run_if(in_state(PreloadState::Loading)),
+            )
+            .add_systems(OnEnter(PreloadState::WarmingUp), start_warmup)
+            .add_systems(
+                Update,
+                tick_warmup.run_if(in_state(PreloadState::WarmingUp)),
+            )
+            .add_systems(
+                OnExit(PreloadState::WarmingUp),
+                (end_warmup, hide_loading_ui),
+            );
+    }
+}
+
+/// Frames to keep every mesh drawn for, giving pipelines time to compile.
+const WARMUP_FRAMES: u32 = 30;
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PreloadState {
+    #[default]
+    Loading,
+    WarmingUp,
+    Ready,
+}
+
+/// Asset paths (meshes, textures, shaders, ...) to load before a scene is shown.
+#[derive(Resource, Default, Clone)]
+pub struct PreloadManifest {
+    pub paths: Vec<String>,
+}
+
+impl PreloadManifest {
+    pub fn new(paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            paths: paths.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Handles for the manifest's assets, kept alive for the rest of the session.
+#[derive(Resource)]
+struct PreloadedAssets {
+    handles: Vec<Handle<LoadedUntypedAsset>>,
+}
+
+#[derive(Resource)]
+struct Warmup {
+    frames_remaining: u32,
+}
+
+#[derive(Component)]
+struct LoadingUi;
+
+#[derive(Component)]
+struct LoadingText;
+
+/// Marks meshes whose culling was disabled for warm-up, so only those get it re-enabled.
+#[derive(Component)]
+struct WarmupUnculled;
+
+fn start_loading(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    manifest: Res<PreloadManifest>,
+) {
+    let handles = manifest
+        .paths
+        .iter()
+        .map(|path| asset_server.load_untyped(path.as_str()))
+        .collect();
+    commands.insert_resource(PreloadedAssets { handles });
+}
+
+fn show_loading_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Loading screen"),
+            LoadingUi,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+            GlobalZIndex(i32::MAX),
+        ))
+        .with_child((LoadingText, Text::new("Loading...")));
+}
+
+fn check_loading_progress(
+    asset_server: Res<AssetServer>,
+    preloaded: Res<PreloadedAssets>,
+    mut text: Single<&mut Text, With<LoadingText>>,
+    mut next_state: ResMut<NextState<PreloadState>>,
+) {
+    let total = preloaded.handles.len();
+    let mut loaded = 0usize;
+    for handle in &preloaded.handles {
+        if asset_server.is_loaded_with_dependencies(handle) {
+            loaded = loaded.saturating_add(1);
+        } else if asset_server.load_state(handle).is_failed() {
+            // Don't hold up the scene for an asset that will never arrive
+            warn!("Failed to preload {:?}", asset_server.get_path(handle));
+            loaded = loaded.saturating_add(1);
+        }
+    }
+
+    text.0 = format!("Loading... {loaded}/{total}");
+    if loaded >= total {
+        next_state.set(PreloadState::WarmingUp);
+    }
+}
+
+fn start_warmup(
+    mut commands: Commands,
+    mut text: Single<&mut Text, With<LoadingText>>,
+    meshes: Query<Entity, (With<Mesh3d>, Without<NoFrustumCulling>)>,
+) {
+    text.0 = "Warming up...".to_string();
+    for entity in meshes.iter() {
+        commands
+            .entity(entity)
+            .insert((NoFrustumCulling, WarmupUnculled));
+    }
+    commands.insert_resource(Warmup {
+        frames_remaining: WARMUP_FRAMES,
+    });
+}
+
+fn tick_warmup(mut warmup: ResMut<Warmup>, mut next_state: ResMut<NextState<PreloadState>>) {
+    warmup.frames_remaining = warmup.frames_remaining.saturating_sub(1);
+    if warmup.frames_remaining == 0 {
+        next_state.set(PreloadState::Ready);
+    }
+}
+
+fn end_warmup(mut commands: Commands, unculled: Query<Entity, With<WarmupUnculled>>) {
+    for entity in unculled.iter() {
+        commands
+            .entity(entity)
+            .remove::<(NoFrustumCulling, WarmupUnculled)>();
+    }
+    commands.remove_resource::<Warmup>();
+}
+
+fn hide_loading_ui(mut commands: Commands, ui: Query<Entity, With<LoadingUi>>) {
+    for entity in ui.iter() {
+        commands.entity(entity).despawn();
+    }
+}
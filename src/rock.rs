@@ -0,0 +1,230 @@
+//! Procedural rocks: noise-displaced icospheres with flat-shaded facets.
+//!
+//! A [`Rock`] builds both a render mesh and a much simpler convex [`Collider`], sampled from the
+//! same displaced surface at a lower subdivision, so physics stays cheap however detailed the mesh
+//! is. [`RockMeshes`] caches built rocks, so scattering many rocks from a handful of seeds only
+//! generates each shape once.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use avian3d::math::AdjustPrecision;
+use avian3d::prelude::*;
+use bevy::mesh::VertexAttributeValues;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::noise::Perlin;
+
+pub struct RockPlugin;
+
+impl Plugin for RockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RockMeshes>();
+    }
+}
+
+/// Icosphere subdivisions used to sample the convex collider.
+const COLLIDER_SUBDIVISIONS: u32 = 1;
+/// Octaves of noise summed to displace the surface.
+const OCTAVES: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rock {
+    pub radius: f32,
+    /// Icosphere subdivisions of the render mesh. Low values give larger, blockier facets.
+    pub subdivisions: u32,
+    /// How far the surface is pushed in or out, as a fraction of the radius.
+    pub roughness: f32,
+    /// Noise frequency over the unit sphere. Higher values give more, smaller bumps.
+    pub frequency: f32,
+    /// Per-axis scale applied after displacement, e.g. to flatten boulders into slabs.
+    pub scale: Vec3,
+    pub seed: u64,
+}
+
+impl Default for Rock {
+    fn default() -> Self {
+        Self {
+            radius: 1.0,
+            subdivisions: 3,
+            roughness: 0.3,
+            frequency: 1.5,
+            scale: Vec3::ONE,
+            seed: 0,
+        }
+    }
+}
+
+impl Rock {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            ..default()
+        }
+    }
+
+    pub fn with_subdivisions(mut self, subdivisions: u32) -> Self {
+        self.subdivisions = subdivisions;
+        self
+    }
+
+    pub fn with_roughness(mut self, roughness: f32, frequency: f32) -> Self {
+        self.roughness = roughness;
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Rounded, half-buried seafloor boulder, slightly wider than it is tall.
+    pub fn boulder() -> Self {
+        Self::new(1.0)
+            .with_roughness(0.25, 1.2)
+            .with_scale(Vec3::new(1.1, 0.75, 1.0))
+    }
+
+    /// Small, sharp and angular stone for loose slopes.
+    pub fn scree() -> Self {
+        Self::new(0.35)
+            .with_subdivisions(1)
+            .with_roughness(0.45, 2.5)
+            .with_scale(Vec3::new(1.2, 0.6, 0.9))
+    }
+
+    /// Smooth, low-profile stone for gardens and displays.
+    pub fn garden_stone() -> Self {
+        Self::new(0.6)
+            .with_subdivisions(4)
+            .with_roughness(0.12, 0.8)
+            .with_scale(Vec3::new(1.3, 0.55, 1.0))
+    }
+
+    /// Noise for each octave, seeded from the rock's seed.
+    fn octaves(&self) -> Vec<Perlin> {
+        (0..OCTAVES)
+            .map(|octave| {
+                let seed = self.seed.wrapping_add(u64::from(octave));
+                Perlin::new((seed ^ (seed >> 32)) as u32)
+            })
+            .collect()
+    }
+
+    /// Position on the displaced surface in the unit-sphere `direction`.
+    fn surface(&self, octaves: &[Perlin], direction: Vec3) -> Vec3 {
+        let mut displacement = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = self.frequency;
+        for noise in octaves {
+            let point = (direction * frequency).as_dvec3().to_array();
+            displacement += amplitude * noise.get_3d(point) as f32;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        // Octave amplitudes sum to just under 2, so this keeps displacement within roughness
+        let offset = 1.0 + self.roughness * displacement * 0.5;
+        direction * self.radius * offset.max(0.1) * self.scale
+    }
+
+    fn points(&self, subdivisions: u32) -> Vec<Vec3> {
+        let Ok(sphere) = Sphere::new(1.0).mesh().ico(subdivisions) else {
+            return Vec::new();
+        };
+        let octaves = self.octaves();
+        match sphere.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions
+                .iter()
+                .map(|position| {
+                    let direction = Vec3::from_array(*position).normalize_or(Vec3::Y);
+                    self.surface(&octaves, direction)
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Builds the flat-shaded render mesh.
+    pub fn mesh(&self) -> Mesh {
+        // Icospheres only fail past 80 subdivisions, which would be far too many vertices anyway
+        let mut mesh = Sphere::new(1.0)
+            .mesh()
+            .ico(self.subdivisions)
+            .unwrap_or_else(|_| Mesh::from(Sphere::new(1.0)));
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            let octaves = self.octaves();
+            for position in positions.iter_mut() {
+                let direction = Vec3::from_array(*position).normalize_or(Vec3::Y);
+                *position = self.surface(&octaves, direction).to_array();
+            }
+        }
+        // Unshared vertices with face normals give each triangle its own flat facet
+        mesh.duplicate_vertices();
+        mesh.compute_flat_normals();
+        mesh
+    }
+
+    /// Builds a convex hull around a coarser sampling of the surface, falling back to a sphere if
+    /// the hull is degenerate.
+    pub fn collider(&self) -> Collider {
+        let points = self
+            .points(COLLIDER_SUBDIVISIONS)
+            .iter()
+            .map(AdjustPrecision::adjust_precision)
+            .collect();
+        Collider::convex_hull(points).unwrap_or_else(|| {
+            Collider::sphere((self.radius * self.scale.min_element()).adjust_precision())
+        })
+    }
+
+    fn cache_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for value in [self.radius, self.roughness, self.frequency] {
+            value.to_bits().hash(&mut hasher);
+        }
+        for value in self.scale.to_array() {
+            value.to_bits().hash(&mut hasher);
+        }
+        self.subdivisions.hash(&mut hasher);
+        self.seed.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// A built rock's render mesh and collider.
+#[derive(Debug, Clone)]
+pub struct RockAsset {
+    pub mesh: Handle<Mesh>,
+    pub collider: Collider,
+}
+
+/// Cache of built rocks, keyed by their parameters and seed.
+#[derive(Resource, Default, Debug)]
+pub struct RockMeshes {
+    cache: HashMap<u64, RockAsset>,
+}
+
+impl RockMeshes {
+    /// The mesh and collider for `rock`, building them on first use.
+    pub fn get_or_build(&mut self, rock: &Rock, meshes: &mut Assets<Mesh>) -> RockAsset {
+        self.cache
+            .entry(rock.cache_key())
+            .or_insert_with(|| RockAsset {
+                mesh: meshes.add(rock.mesh()),
+                collider: rock.collider(),
+            })
+            .clone()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
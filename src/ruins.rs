@@ -0,0 +1,375 @@
+//! Ruined structures assembled from weathered stone blocks.
+//!
+//! [`Ruins`] lays out [`RuinPiece`]s, such as broken columns, arches and toppled walls, then builds
+//! each one from unit cuboid and cylinder blocks with seeded wear: blocks are nudged, tilted and
+//! eroded, and the more damaged a site is, the more blocks have fallen. Spawning produces a
+//! hierarchy of named pieces under a static root, with a collider on every block.
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::rng::SplitMix64;
+
+/// A structure within a ruin site. Pieces stand on their local origin, facing along X.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuinPiece {
+    Column {
+        radius: f32,
+        height: f32,
+    },
+    Arch {
+        span: f32,
+        height: f32,
+        depth: f32,
+    },
+    Wall {
+        length: f32,
+        height: f32,
+        thickness: f32,
+    },
+}
+
+impl RuinPiece {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Column { .. } => "Column",
+            Self::Arch { .. } => "Arch",
+            Self::Wall { .. } => "Wall",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockShape {
+    /// A unit cube.
+    Cuboid,
+    /// A cylinder of unit diameter and height, along Y.
+    Cylinder,
+}
+
+/// A stone block, whose transform scales its unit [`BlockShape`] to size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuinBlock {
+    pub shape: BlockShape,
+    pub transform: Transform,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ruins {
+    pub pieces: Vec<(RuinPiece, Transform)>,
+    /// How ruined the site is, from 0 for intact to 1 for mostly collapsed.
+    pub damage: f32,
+    /// Typical length of a wall block or height of a column drum.
+    pub block_size: f32,
+    pub seed: u64,
+}
+
+impl Ruins {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            pieces: Vec::new(),
+            damage: 0.5,
+            block_size: 0.6,
+            seed,
+        }
+    }
+
+    pub fn with_piece(mut self, piece: RuinPiece, transform: Transform) -> Self {
+        self.pieces.push((piece, transform));
+        self
+    }
+
+    pub fn with_damage(mut self, damage: f32) -> Self {
+        self.damage = damage.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_block_size(mut self, block_size: f32) -> Self {
+        self.block_size = block_size.max(0.05);
+        self
+    }
+
+    /// A colonnade flanking an arched gateway, with walls around the back, about 16 units across.
+    pub fn temple(seed: u64) -> Self {
+        let mut ruins = Self::new(seed).with_piece(
+            RuinPiece::Arch {
+                span: 3.0,
+                height: 4.5,
+                depth: 0.8,
+            },
+            Transform::from_xyz(0.0, 0.0, 6.0),
+        );
+        for side in [-1.0, 1.0] {
+            for row in 0..4 {
+                ruins = ruins.with_piece(
+                    RuinPiece::Column {
+                        radius: 0.4,
+                        height: 5.0,
+                    },
+                    Transform::from_xyz(side * 4.0, 0.0, 4.0 - row as f32 * 3.0),
+                );
+            }
+            ruins = ruins.with_piece(
+                RuinPiece::Wall {
+                    length: 10.0,
+                    height: 3.0,
+                    thickness: 0.6,
+                },
+                Transform::from_xyz(side * 7.0, 0.0, -1.0)
+                    .with_rotation(Quat::from_rotation_y(FRAC_PI_2)),
+            );
+        }
+        ruins.with_piece(
+            RuinPiece::Wall {
+                length: 14.0,
+                height: 3.5,
+                thickness: 0.6,
+            },
+            Transform::from_xyz(0.0, 0.0, -6.0),
+        )
+    }
+
+    /// The weathered blocks making up the piece at `index`, in the piece's local space.
+    pub fn blocks(&self, index: usize) -> Vec<RuinBlock> {
+        let Some((piece, _)) = self.pieces.get(index) else {
+            return Vec::new();
+        };
+        // Each piece draws from its own stream, so adding pieces doesn't reshuffle the others
+        let mut rng = SplitMix64::new(self.seed ^ (index as u64).wrapping_mul(0x9E37_79B9));
+        let mut blocks = match *piece {
+            RuinPiece::Column { radius, height } => self.column(radius, height, &mut rng),
+            RuinPiece::Arch {
+                span,
+                height,
+                depth,
+            } => self.arch(span, height, depth, &mut rng),
+            RuinPiece::Wall {
+                length,
+                height,
+                thickness,
+            } => self.wall(length, height, thickness, &mut rng),
+        };
+        for block in &mut blocks {
+            self.weather(block, &mut rng);
+        }
+        blocks
+    }
+
+    /// Stacked drums, broken off partway up, with the fallen drums lying around the base.
+    fn column(&self, radius: f32, height: f32, rng: &mut SplitMix64) -> Vec<RuinBlock> {
+        let drums = (height / self.block_size).round().max(1.0) as usize;
+        let drum_height = height / drums as f32;
+        let standing = self.surviving(drums, rng);
+        let mut blocks = Vec::with_capacity(drums.saturating_add(1));
+
+        // Base plinth
+        blocks.push(RuinBlock {
+            shape: BlockShape::Cuboid,
+            transform: Transform::from_xyz(0.0, drum_height * 0.25, 0.0).with_scale(Vec3::new(
+                radius * 2.6,
+                drum_height * 0.5,
+                radius * 2.6,
+            )),
+        });
+        let base = drum_height * 0.5;
+        let drum_scale = Vec3::new(radius * 2.0, drum_height, radius * 2.0);
+        for drum in 0..standing {
+            blocks.push(RuinBlock {
+                shape: BlockShape::Cylinder,
+                transform: Transform::from_xyz(0.0, base + drum_height * (drum as f32 + 0.5), 0.0)
+                    .with_scale(drum_scale),
+            });
+        }
+        // Fallen drums lie roughly in one direction, further out the higher they fell from
+        let fall = rng.range_f32(0.0, TAU);
+        for drum in standing..drums {
+            let direction = fall + rng.range_f32(-0.4, 0.4);
+            let distance = radius * 2.0 + drum_height * (drum as f32 - standing as f32 + 1.0);
+            let position = Vec3::new(direction.cos(), 0.0, direction.sin()) * distance;
+            blocks.push(RuinBlock {
+                shape: BlockShape::Cylinder,
+                transform: Transform::from_xyz(position.x, radius, position.z)
+                    .with_rotation(
+                        Quat::from_rotation_y(rng.range_f32(0.0, TAU))
+                            * Quat::from_rotation_z(FRAC_PI_2),
+                    )
+                    .with_scale(drum_scale),
+            });
+        }
+        blocks
+    }
+
+    /// Two block piers carrying a semicircle of voussoirs, some of which have fallen.
+    fn arch(&self, span: f32, height: f32, depth: f32, rng: &mut SplitMix64) -> Vec<RuinBlock> {
+        let pier_width = self.block_size;
+        let arch_radius = span * 0.5 + pier_width * 0.5;
+        let pier_height = (height - arch_radius - pier_width * 0.5).max(self.block_size);
+        let courses = (pier_height / self.block_size).round().max(1.0) as usize;
+        let course_height = pier_height / courses as f32;
+        let mut blocks = Vec::new();
+
+        for side in [-1.0, 1.0] {
+            for course in 0..courses {
+                blocks.push(RuinBlock {
+                    shape: BlockShape::Cuboid,
+                    transform: Transform::from_xyz(
+                        side * arch_radius,
+                        course_height * (course as f32 + 0.5),
+                        0.0,
+                    )
+                    .with_scale(Vec3::new(pier_width, course_height, depth)),
+                });
+            }
+        }
+
+        let voussoirs = ((PI * arch_radius) / self.block_size).round().max(3.0) as usize;
+        let voussoir_length = PI * arch_radius / voussoirs as f32;
+        for voussoir in 0..voussoirs {
+            let angle = PI * (voussoir as f32 + 0.5) / voussoirs as f32;
+            let fallen = rng.next_f32() < self.damage * 0.4;
+            let transform = if fallen {
+                let side = if rng.next_f32() < 0.5 { -1.0 } else { 1.0 };
+                Transform::from_xyz(
+                    arch_radius * angle.cos() + rng.range_f32(-1.0, 1.0),
+                    pier_width * 0.5,
+                    side * depth * rng.range_f32(0.5, 1.5),
+                )
+                .with_rotation(Quat::from_rotation_y(rng.range_f32(0.0, TAU)))
+            } else {
+                Transform::from_xyz(
+                    arch_radius * angle.cos(),
+                    pier_height + arch_radius * angle.sin(),
+                    0.0,
+                )
+                .with_rotation(Quat::from_rotation_z(angle - FRAC_PI_2))
+            };
+            blocks.push(RuinBlock {
+                shape: BlockShape::Cuboid,
+                transform: transform.with_scale(Vec3::new(voussoir_length, pier_width, depth)),
+            });
+        }
+        blocks
+    }
+
+    /// Courses of staggered blocks with a ragged top, or if badly damaged, the whole wall toppled
+    /// onto its side.
+    fn wall(
+        &self,
+        length: f32,
+        height: f32,
+        thickness: f32,
+        rng: &mut SplitMix64,
+    ) -> Vec<RuinBlock> {
+        let courses = (height / (self.block_size * 0.5)).round().max(1.0) as usize;
+        let course_height = height / courses as f32;
+        let columns = (length / self.block_size).round().max(1.0) as usize;
+        let block_length = length / columns as f32;
+        let toppled = rng.next_f32() < self.damage * 0.3;
+        let mut blocks = Vec::new();
+
+        for column in 0..columns {
+            let standing = self.surviving(courses, rng);
+            for course in 0..standing {
+                // Every other course is offset by half a block, like real masonry
+                let offset = if course % 2 == 0 { 0.0 } else { 0.5 };
+                let x = block_length * (column as f32 + offset + 0.5) - length * 0.5;
+                if x + block_length * 0.5 > length * 0.5 + f32::EPSILON {
+                    continue;
+                }
+                let y = course_height * (course as f32 + 0.5);
+                let position = if toppled {
+                    Vec3::new(x, thickness * 0.5, y)
+                } else {
+                    Vec3::new(x, y, 0.0)
+                };
+                let rotation = if toppled {
+                    Quat::from_rotation_x(FRAC_PI_2)
+                } else {
+                    Quat::IDENTITY
+                };
+                blocks.push(RuinBlock {
+                    shape: BlockShape::Cuboid,
+                    transform: Transform::from_translation(position)
+                        .with_rotation(rotation)
+                        .with_scale(Vec3::new(block_length, course_height, thickness)),
+                });
+            }
+        }
+        blocks
+    }
+
+    /// How many of `count` stacked blocks are still standing.
+    fn surviving(&self, count: usize, rng: &mut SplitMix64) -> usize {
+        let lost = count as f32 * self.damage * rng.next_f32();
+        count.saturating_sub(lost.round() as usize).max(1)
+    }
+
+    /// Nudges, tilts and erodes a block, more so on more damaged sites.
+    fn weather(&self, block: &mut RuinBlock, rng: &mut SplitMix64) {
+        let wear = 0.2 + self.damage;
+        let nudge = self.block_size * 0.03 * wear;
+        let tilt = 0.04 * wear;
+        block.transform.translation += Vec3::new(
+            rng.range_f32(-nudge, nudge),
+            0.0,
+            rng.range_f32(-nudge, nudge),
+        );
+        block.transform.rotation = Quat::from_euler(
+            EulerRot::XYZ,
+            rng.range_f32(-tilt, tilt),
+            rng.range_f32(-tilt, tilt),
+            rng.range_f32(-tilt, tilt),
+        ) * block.transform.rotation;
+        block.transform.scale *= 1.0 - rng.range_f32(0.0, 0.08 * wear);
+    }
+
+    /// Spawns the site as a static body, with a child entity per piece and a grandchild per block.
+    pub fn spawn<M: Material>(
+        &self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        material: Handle<M>,
+        transform: Transform,
+    ) -> Entity {
+        let cuboid = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
+        let cylinder = meshes.add(Cylinder::new(0.5, 1.0));
+        commands
+            .spawn((
+                Name::new("Ruins"),
+                transform,
+                Visibility::default(),
+                RigidBody::Static,
+            ))
+            .with_children(|site| {
+                for (index, (piece, piece_transform)) in self.pieces.iter().enumerate() {
+                    let blocks = self.blocks(index);
+                    site.spawn((
+                        Name::new(format!("{} {}", piece.name(), index.saturating_add(1))),
+                        *piece_transform,
+                        Visibility::default(),
+                    ))
+                    .with_children(|piece| {
+                        for block in blocks {
+                            let (mesh, collider) = match block.shape {
+                                BlockShape::Cuboid => {
+                                    (cuboid.clone(), Collider::cuboid(1.0, 1.0, 1.0))
+                                }
+                                BlockShape::Cylinder => {
+                                    (cylinder.clone(), Collider::cylinder(0.5, 1.0))
+                                }
+                            };
+                            piece.spawn((
+                                Mesh3d(mesh),
+                                MeshMaterial3d(material.clone()),
+                                block.transform,
+                                collider,
+                            ));
+                        }
+                    });
+                }
+            })
+            .id()
+    }
+}
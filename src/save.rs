@@ -0,0 +1,448 @@
+//! Saving and loading the player's position and scene state to a local RON file.
+//!
+//! Inserting a [`SaveGame`] resource enables saving: triggering [`SaveRequest`] writes the
+//! player's transform and any resources and components registered with
+//! [`SaveGame::with_resource`] and [`SaveGame::with_component`] to its path, and triggering
+//! [`LoadRequest`] restores them. Saved components are matched back up to entities by [`Name`], so
+//! entities with registered components need unique names to be restored reliably. The
+//! [`GameState`] isn't saved: the only way to quit is from the pause menu, so a saved state would
+//! always bring the next run up paused.
+//!
+//! Saves can also be made automatically, every so often, whenever the scene triggers
+//! [`CheckpointReached`], and as the app exits. With more than one slot, each save goes to the
+//! slot after the last one, so a save that goes wrong only loses that slot, and loading reads the
+//! newest slot that can be read. Files are written on a background task, to a temporary file that
+//! then replaces the slot's, so saving doesn't hitch the frame or leave a half-written file behind.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::{fs, io};
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy::tasks::futures_lite::future;
+use bevy::tasks::{IoTaskPool, Task, block_on};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::player::Player;
+use crate::state::GameState;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveWriter>()
+            .add_systems(
+                PostStartup,
+                load_on_startup.run_if(resource_exists::<SaveGame>),
+            )
+            .add_systems(
+                Update,
+                (
+                    autosave_on_interval.run_if(in_state(GameState::Active)),
+                    finish_writing,
+                )
+                    .run_if(resource_exists::<SaveGame>),
+            )
+            .add_systems(Last, autosave_on_exit.run_if(resource_exists::<SaveGame>))
+            .add_observer(on_save_request)
+            .add_observer(on_load_request)
+            .add_observer(on_checkpoint_reached);
+    }
+}
+
+/// Writes the save file, triggered by scenes with `commands.trigger(SaveRequest)`.
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct SaveRequest;
+
+/// Restores the save file, if there is one, triggered by scenes with
+/// `commands.trigger(LoadRequest)`.
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct LoadRequest;
+
+/// Triggered by scenes when the player reaches a checkpoint, saving the game if
+/// [`SaveGame::autosave_on_checkpoint`] is set.
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct CheckpointReached;
+
+/// Where to save to, and which resources and components to save besides the player's position.
+#[derive(Resource, Debug, Clone)]
+pub struct SaveGame {
+    pub path: PathBuf,
+    /// Load the save file once the scene has been spawned, if it exists.
+    pub load_on_startup: bool,
+    /// Save the player's position, and move the player back there on load.
+    pub save_player: bool,
+    /// Seconds of play between automatic saves, or `None` to only save when asked.
+    pub autosave_interval: Option<f32>,
+    pub autosave_on_checkpoint: bool,
+    pub autosave_on_exit: bool,
+    /// Number of files saves rotate between, the first at `path` and the rest numbered beside it.
+    pub slots: u32,
+    resources: Vec<SavedType>,
+    components: Vec<SavedType>,
+}
+
+impl SaveGame {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            load_on_startup: false,
+            save_player: true,
+            autosave_interval: None,
+            autosave_on_checkpoint: false,
+            autosave_on_exit: false,
+            slots: 1,
+            resources: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    pub fn with_load_on_startup(mut self) -> Self {
+        self.load_on_startup = true;
+        self
+    }
+
+    /// Leaves the player's position out, for save files that only keep scene state.
+    pub fn without_player(mut self) -> Self {
+        self.save_player = false;
+        self
+    }
+
+    /// Saves every `interval` seconds of play, not counting time paused.
+    pub fn with_autosave_interval(mut self, interval: f32) -> Self {
+        self.autosave_interval = Some(interval.max(1.0));
+        self
+    }
+
+    pub fn with_autosave_on_checkpoint(mut self) -> Self {
+        self.autosave_on_checkpoint = true;
+        self
+    }
+
+    pub fn with_autosave_on_exit(mut self) -> Self {
+        self.autosave_on_exit = true;
+        self
+    }
+
+    /// Rotates saves between `slots` files, so a bad save doesn't overwrite the only good one.
+    pub fn with_slots(mut self, slots: u32) -> Self {
+        self.slots = slots.max(1);
+        self
+    }
+
+    /// Path of save slot `slot`: `path` for the first, and `path` with the slot number before its
+    /// extension for the rest, e.g. `game.save.2.ron`.
+    pub fn slot_path(&self, slot: u32) -> PathBuf {
+        if slot == 0 {
+            return self.path.clone();
+        }
+        let stem = self
+            .path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        let name = match self.path.extension() {
+            Some(extension) => format!("{stem}.{slot}.{}", extension.to_string_lossy()),
+            None => format!("{stem}.{slot}"),
+        };
+        self.path.with_file_name(name)
+    }
+
+    /// Slots that have been saved to, newest first.
+    pub fn saved_slots(&self) -> Vec<u32> {
+        let mut saved: Vec<(u32, SystemTime)> = (0..self.slots)
+            .filter_map(|slot| {
+                let modified = fs::metadata(self.slot_path(slot)).ok()?.modified().ok()?;
+                Some((slot, modified))
+            })
+            .collect();
+        saved.sort_by(|a, b| b.1.cmp(&a.1));
+        saved.into_iter().map(|(slot, _)| slot).collect()
+    }
+
+    /// Saves resource `R`, replacing it with the saved copy on load.
+    pub fn with_resource<R: Resource + Serialize + DeserializeOwned>(mut self) -> Self {
+        self.resources.push(SavedType {
+            name: std::any::type_name::<R>(),
+            save: save_resource::<R>,
+            load: load_resource::<R>,
+        });
+        self
+    }
+
+    /// Saves component `C` of every named entity, inserting the saved copy on the entity with the
+    /// same name on load.
+    pub fn with_component<C: Component + Serialize + DeserializeOwned>(mut self) -> Self {
+        self.components.push(SavedType {
+            name: std::any::type_name::<C>(),
+            save: save_components::<C>,
+            load: load_components::<C>,
+        });
+        self
+    }
+}
+
+/// Type-erased save and load functions for a registered resource or component. Values are keyed
+/// by entity name for components, and by an empty string for resources.
+#[derive(Debug, Clone, Copy)]
+struct SavedType {
+    name: &'static str,
+    save: fn(&mut World) -> BTreeMap<String, String>,
+    load: fn(&mut World, &BTreeMap<String, String>),
+}
+
+/// A snapshot, as stored in a save file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SaveData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<Transform>,
+    /// Registered resources and components, by type name, as RON.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub saved: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl SaveData {
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Writes to a temporary file beside `path` first, then moves it into place, so `path` is
+    /// never left half written.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(io::Error::other)?;
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, text)?;
+        fs::rename(&temporary, path)
+    }
+}
+
+/// Where the next save goes, and the save still being written, if any.
+#[derive(Resource, Default)]
+struct SaveWriter {
+    /// Worked out from the saved files' ages on the first save.
+    next_slot: Option<u32>,
+    writing: Option<Task<()>>,
+    /// Seconds of play since the last save.
+    since_save: f32,
+}
+
+fn save_resource<R: Resource + Serialize>(world: &mut World) -> BTreeMap<String, String> {
+    let Some(resource) = world.get_resource::<R>() else {
+        return BTreeMap::new();
+    };
+    match ron::to_string(resource) {
+        Ok(text) => BTreeMap::from([(String::new(), text)]),
+        Err(e) => {
+            warn!("Not saving {}: {e}", std::any::type_name::<R>());
+            BTreeMap::new()
+        }
+    }
+}
+
+fn load_resource<R: Resource + DeserializeOwned>(
+    world: &mut World,
+    saved: &BTreeMap<String, String>,
+) {
+    let Some(text) = saved.get("") else {
+        return;
+    };
+    match ron::from_str::<R>(text) {
+        Ok(resource) => world.insert_resource(resource),
+        Err(e) => warn!("Not loading {}: {e}", std::any::type_name::<R>()),
+    }
+}
+
+fn save_components<C: Component + Serialize>(world: &mut World) -> BTreeMap<String, String> {
+    let mut query = world.query::<(&Name, &C)>();
+    query
+        .iter(world)
+        .filter_map(|(name, component)| match ron::to_string(component) {
+            Ok(text) => Some((name.to_string(), text)),
+            Err(e) => {
+                warn!("Not saving {} of {name}: {e}", std::any::type_name::<C>());
+                None
+            }
+        })
+        .collect()
+}
+
+fn load_components<C: Component + DeserializeOwned>(
+    world: &mut World,
+    saved: &BTreeMap<String, String>,
+) {
+    let mut query = world.query::<(Entity, &Name)>();
+    let entities: Vec<_> = query
+        .iter(world)
+        .filter_map(|(entity, name)| Some((entity, saved.get(name.as_str())?)))
+        .collect();
+    for (entity, text) in entities {
+        match ron::from_str::<C>(text) {
+            Ok(component) => {
+                world.entity_mut(entity).insert(component);
+            }
+            Err(e) => warn!(
+                "Not loading {} of {entity}: {e}",
+                std::any::type_name::<C>()
+            ),
+        }
+    }
+}
+
+fn on_save_request(_request: On<SaveRequest>, mut commands: Commands) {
+    commands.queue(save);
+}
+
+fn on_load_request(_request: On<LoadRequest>, mut commands: Commands) {
+    commands.queue(load);
+}
+
+fn on_checkpoint_reached(
+    _checkpoint: On<CheckpointReached>,
+    mut commands: Commands,
+    save_game: Option<Res<SaveGame>>,
+) {
+    if save_game.is_some_and(|save_game| save_game.autosave_on_checkpoint) {
+        commands.trigger(SaveRequest);
+    }
+}
+
+fn load_on_startup(mut commands: Commands, save_game: Res<SaveGame>) {
+    if save_game.load_on_startup && !save_game.saved_slots().is_empty() {
+        commands.trigger(LoadRequest);
+    }
+}
+
+fn autosave_on_interval(
+    mut commands: Commands,
+    time: Res<Time>,
+    save_game: Res<SaveGame>,
+    mut writer: ResMut<SaveWriter>,
+) {
+    let Some(interval) = save_game.autosave_interval else {
+        return;
+    };
+    writer.since_save += time.delta_secs();
+    if writer.since_save >= interval {
+        // Reset here too, in case saving fails before it gets the chance
+        writer.since_save = 0.0;
+        commands.trigger(SaveRequest);
+    }
+}
+
+fn autosave_on_exit(
+    mut commands: Commands,
+    mut exits: MessageReader<AppExit>,
+    save_game: Res<SaveGame>,
+) {
+    if exits.read().count() == 0 || !save_game.autosave_on_exit {
+        return;
+    }
+    commands.queue(|world: &mut World| {
+        save(world);
+        // The app's about to close, so the write can't be left running in the background
+        if let Some(task) = world.resource_mut::<SaveWriter>().writing.take() {
+            block_on(task);
+        }
+    });
+}
+
+fn finish_writing(mut writer: ResMut<SaveWriter>) {
+    let finished = writer
+        .writing
+        .as_mut()
+        .is_some_and(|task| block_on(future::poll_once(task)).is_some());
+    if finished {
+        writer.writing = None;
+    }
+}
+
+fn save(world: &mut World) {
+    let Some(save_game) = world.get_resource::<SaveGame>().cloned() else {
+        warn!("Ignoring save request, as there is no SaveGame resource");
+        return;
+    };
+    let player = world
+        .query_filtered::<&Transform, With<Player>>()
+        .single(world)
+        .ok()
+        .copied()
+        .filter(|_| save_game.save_player);
+    let saved = save_game
+        .resources
+        .iter()
+        .chain(&save_game.components)
+        .map(|saved_type| (saved_type.name.to_string(), (saved_type.save)(world)))
+        .collect();
+    let data = SaveData { player, saved };
+
+    let mut writer = world.resource_mut::<SaveWriter>();
+    let slot = *writer.next_slot.get_or_insert_with(|| {
+        save_game
+            .saved_slots()
+            .first()
+            .map_or(0, |newest| (newest + 1) % save_game.slots)
+    });
+    writer.next_slot = Some((slot + 1) % save_game.slots);
+    writer.since_save = 0.0;
+    // Only one write at a time, so two saves to the same slot can't interleave
+    if let Some(previous) = writer.writing.take() {
+        block_on(previous);
+    }
+    let path = save_game.slot_path(slot);
+    writer.writing = Some(IoTaskPool::get().spawn(async move {
+        match data.write(&path) {
+            Ok(()) => info!("Saved game to {}", path.display()),
+            Err(e) => error!("Failed to save game {}: {e}", path.display()),
+        }
+    }));
+}
+
+fn load(world: &mut World) {
+    let Some(save_game) = world.get_resource::<SaveGame>().cloned() else {
+        warn!("Ignoring load request, as there is no SaveGame resource");
+        return;
+    };
+    // A save still being written is newer than anything on disk
+    if let Some(task) = world.resource_mut::<SaveWriter>().writing.take() {
+        block_on(task);
+    }
+    // Falls back to older slots if the newest can't be read
+    let mut loaded = None;
+    for slot in save_game.saved_slots() {
+        let path = save_game.slot_path(slot);
+        match SaveData::read(&path) {
+            Ok(data) => {
+                loaded = Some((data, path));
+                break;
+            }
+            Err(e) => error!("Failed to load game {}: {e}", path.display()),
+        }
+    }
+    let Some((data, path)) = loaded else {
+        warn!("No save to load from {}", save_game.path.display());
+        return;
+    };
+    if let Some(saved_transform) = data.player.filter(|_| save_game.save_player) {
+        let mut players =
+            world.query_filtered::<(&mut Transform, Option<&mut LinearVelocity>), With<Player>>();
+        for (mut transform, velocity) in players.iter_mut(world) {
+            *transform = saved_transform;
+            // Don't carry a fall from before loading over to the restored position
+            if let Some(mut velocity) = velocity {
+                *velocity = LinearVelocity::ZERO;
+            }
+        }
+    }
+    for saved_type in save_game.resources.iter().chain(&save_game.components) {
+        if let Some(saved) = data.saved.get(saved_type.name) {
+            (saved_type.load)(world, saved);
+        }
+    }
+    info!("Loaded game from {}", path.display());
+}
@@ -0,0 +1,470 @@
+//! Saving diorama layouts to RON files and reloading them at runtime.
+//!
+//! Adding a [`DioramaSceneRoot`] to an entity ties its hierarchy to a scene file. When the file
+//! exists, its contents are spawned under the root; F3+S saves the current hierarchy to the file
+//! and F3+R reloads it, so layouts can be edited in the file and iterated on without recompiling.
+//! A typical workflow is to build a room in Rust once, save it, then delete the Rust code.
+//!
+//! Only names, transforms, meshes, [`StandardMaterial`]s, colliders and rigid bodies are saved.
+//! Anything else under a root, such as lights or scene-specific components, is lost on reload, so
+//! keep it outside of the root. Meshes and materials are referred to by asset path, or for ones
+//! created in code, by the key they were registered under in [`SceneAssets`].
+//!
+//! A top-level node with a [`SceneNode::parent`] name path is spawned under that entity instead of
+//! the root, so a scene file can add to hierarchy built in code, e.g. putting a sculpture on
+//! `"Museum Root/Second Room/Display Areas/Pedestal 3"`. It's still saved to and reloaded from the
+//! root's file. Warnings about nodes that can't be saved name them by their path.
+
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use avian3d::prelude::*;
+use bevy::asset::UntypedAssetId;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::controls::keyboard_not_captured;
+use crate::kiosk::not_in_kiosk_mode;
+use crate::name_path::NamePaths;
+
+pub struct ScenePlugin;
+
+impl Plugin for ScenePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SceneAssets>()
+            .add_plugins(InputManagerPlugin::<SceneAction>::default())
+            .add_systems(Startup, setup_actions)
+            .add_systems(
+                Update,
+                handle_actions
+                    .run_if(not_in_kiosk_mode)
+                    .run_if(keyboard_not_captured),
+            )
+            .add_observer(load_added_root);
+    }
+}
+
+/// Ties an entity's descendants to the scene file at `path`.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct DioramaSceneRoot {
+    pub path: PathBuf,
+}
+
+impl DioramaSceneRoot {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+/// Names for meshes and materials created in code, which have no asset path to save them by.
+#[derive(Resource, Default, Debug)]
+pub struct SceneAssets {
+    meshes: HashMap<String, Handle<Mesh>>,
+    materials: HashMap<String, Handle<StandardMaterial>>,
+    keys: HashMap<UntypedAssetId, String>,
+}
+
+impl SceneAssets {
+    /// Registers `mesh` under `key`, returning it so this can wrap `meshes.add(..)`.
+    pub fn add_mesh(&mut self, key: impl Into<String>, mesh: Handle<Mesh>) -> Handle<Mesh> {
+        let key = key.into();
+        self.keys.insert(mesh.id().untyped(), key.clone());
+        self.meshes.insert(key, mesh.clone());
+        mesh
+    }
+
+    /// Registers `material` under `key`, returning it so this can wrap `materials.add(..)`.
+    pub fn add_material(
+        &mut self,
+        key: impl Into<String>,
+        material: Handle<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        let key = key.into();
+        self.keys.insert(material.id().untyped(), key.clone());
+        self.materials.insert(key, material.clone());
+        material
+    }
+
+    /// The mesh registered under `key`, if any.
+    pub fn mesh(&self, key: &str) -> Option<Handle<Mesh>> {
+        self.meshes.get(key).cloned()
+    }
+
+    /// The material registered under `key`, if any.
+    pub fn material(&self, key: &str) -> Option<Handle<StandardMaterial>> {
+        self.materials.get(key).cloned()
+    }
+
+    fn key_of<A: Asset>(&self, handle: &Handle<A>) -> Option<String> {
+        self.keys
+            .get(&handle.id().untyped())
+            .cloned()
+            .or_else(|| handle.path().map(ToString::to_string))
+    }
+}
+
+/// A saved hierarchy, as stored in a scene file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DioramaScene {
+    pub nodes: Vec<SceneNode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SceneNode {
+    /// Name path of an existing entity to spawn this node under instead of the scene root. Only
+    /// read on top-level nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub transform: Transform,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesh: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collider: Option<SceneCollider>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<SceneBody>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SceneNode>,
+}
+
+/// Collider shapes that can be saved. Other shapes, such as trimeshes, are skipped with a warning.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SceneCollider {
+    Cuboid { size: Vec3 },
+    Sphere { radius: f32 },
+    Capsule { radius: f32, length: f32 },
+    Cylinder { radius: f32, height: f32 },
+}
+
+impl SceneCollider {
+    fn from_collider(collider: &Collider) -> Option<Self> {
+        let shape = collider.shape();
+        if let Some(cuboid) = shape.as_cuboid() {
+            let half_extents = cuboid.half_extents;
+            return Some(Self::Cuboid {
+                size: Vec3::new(half_extents.x, half_extents.y, half_extents.z) * 2.0,
+            });
+        }
+        if let Some(ball) = shape.as_ball() {
+            return Some(Self::Sphere {
+                radius: ball.radius,
+            });
+        }
+        if let Some(capsule) = shape.as_capsule() {
+            return Some(Self::Capsule {
+                radius: capsule.radius,
+                length: capsule.height(),
+            });
+        }
+        if let Some(cylinder) = shape.as_cylinder() {
+            return Some(Self::Cylinder {
+                radius: cylinder.radius,
+                height: cylinder.half_height * 2.0,
+            });
+        }
+        None
+    }
+
+    /// A mesh of the same shape, for prefabs.
+    pub(crate) fn to_mesh(self) -> Mesh {
+        match self {
+            Self::Cuboid { size } => Cuboid::from_size(size).into(),
+            Self::Sphere { radius } => Sphere::new(radius).into(),
+            Self::Capsule { radius, length } => Capsule3d::new(radius, length).into(),
+            Self::Cylinder { radius, height } => Cylinder::new(radius, height).into(),
+        }
+    }
+
+    pub(crate) fn to_collider(self) -> Collider {
+        match self {
+            Self::Cuboid { size } => Collider::cuboid(size.x, size.y, size.z),
+            Self::Sphere { radius } => Collider::sphere(radius),
+            Self::Capsule { radius, length } => Collider::capsule(radius, length),
+            Self::Cylinder { radius, height } => Collider::cylinder(radius, height),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneBody {
+    Static,
+    Dynamic,
+    Kinematic,
+}
+
+impl From<RigidBody> for SceneBody {
+    fn from(body: RigidBody) -> Self {
+        match body {
+            RigidBody::Static => Self::Static,
+            RigidBody::Dynamic => Self::Dynamic,
+            RigidBody::Kinematic => Self::Kinematic,
+        }
+    }
+}
+
+impl From<SceneBody> for RigidBody {
+    fn from(body: SceneBody) -> Self {
+        match body {
+            SceneBody::Static => Self::Static,
+            SceneBody::Dynamic => Self::Dynamic,
+            SceneBody::Kinematic => Self::Kinematic,
+        }
+    }
+}
+
+impl DioramaScene {
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(io::Error::other)?;
+        fs::write(path, text)
+    }
+}
+
+/// Spawned from the scene file of the root entity, under a [`SceneNode::parent`] outside of it.
+#[derive(Component)]
+struct PlacedBy(Entity);
+
+/// The components of a hierarchy that are saved to scene files.
+type SceneNodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Name>,
+        &'static Transform,
+        Option<&'static Mesh3d>,
+        Option<&'static MeshMaterial3d<StandardMaterial>>,
+        Option<&'static Collider>,
+        Option<&'static RigidBody>,
+    ),
+>;
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+enum SceneAction {
+    Save,
+    Reload,
+}
+
+fn setup_actions(mut commands: Commands) {
+    let scene_map = InputMap::new([
+        (
+            SceneAction::Save,
+            ButtonlikeChord::new([KeyCode::F3, KeyCode::KeyS]),
+        ),
+        (
+            SceneAction::Reload,
+            ButtonlikeChord::new([KeyCode::F3, KeyCode::KeyR]),
+        ),
+    ]);
+    commands.spawn((Name::new("Scene controls"), scene_map));
+}
+
+fn handle_actions(
+    mut commands: Commands,
+    action_state: Single<&ActionState<SceneAction>>,
+    roots: Query<(Entity, &DioramaSceneRoot)>,
+    children: Query<&Children>,
+    placed: Query<(Entity, &PlacedBy, &ChildOf)>,
+    nodes: SceneNodes,
+    assets: Res<SceneAssets>,
+    asset_server: Res<AssetServer>,
+    mut paths: NamePaths,
+) {
+    if action_state.just_pressed(&SceneAction::Save) {
+        for (root, scene_root) in roots.iter() {
+            let under_root = children
+                .get(root)
+                .into_iter()
+                .flatten()
+                .filter_map(|child| capture_node(*child, &children, &nodes, &assets, &paths));
+            let elsewhere = placed
+                .iter()
+                .filter(|(_, placed_by, _)| placed_by.0 == root)
+                .filter_map(|(entity, _, child_of)| {
+                    let mut node = capture_node(entity, &children, &nodes, &assets, &paths)?;
+                    node.parent = paths.path_of(child_of.parent());
+                    Some(node)
+                });
+            let scene = DioramaScene {
+                nodes: under_root.chain(elsewhere).collect(),
+            };
+            match scene.write(&scene_root.path) {
+                Ok(()) => info!("Saved scene to {}", scene_root.path.display()),
+                Err(e) => error!("Failed to save scene {}: {e}", scene_root.path.display()),
+            }
+        }
+    }
+    if action_state.just_pressed(&SceneAction::Reload) {
+        for (root, scene_root) in roots.iter() {
+            load_into(
+                &mut commands,
+                root,
+                &scene_root.path,
+                &placed,
+                &assets,
+                &asset_server,
+                &mut paths,
+            );
+        }
+    }
+}
+
+fn capture_node(
+    entity: Entity,
+    children: &Query<&Children>,
+    nodes: &SceneNodes,
+    assets: &SceneAssets,
+    paths: &NamePaths,
+) -> Option<SceneNode> {
+    let (name, transform, mesh, material, collider, body) = nodes.get(entity).ok()?;
+    let label = paths.path_of(entity).unwrap_or_else(|| format!("{entity}"));
+    let mesh = mesh.and_then(|mesh| {
+        let key = assets.key_of(&mesh.0);
+        if key.is_none() {
+            warn!("Not saving mesh of {label}: it has no asset path or SceneAssets key");
+        }
+        key
+    });
+    let material = material.and_then(|material| {
+        let key = assets.key_of(&material.0);
+        if key.is_none() {
+            warn!("Not saving material of {label}: it has no asset path or SceneAssets key");
+        }
+        key
+    });
+    let collider = collider.and_then(|collider| {
+        let shape = SceneCollider::from_collider(collider);
+        if shape.is_none() {
+            warn!("Not saving collider of {label}: its shape isn't supported");
+        }
+        shape
+    });
+    Some(SceneNode {
+        parent: None,
+        name: name.map(ToString::to_string),
+        transform: *transform,
+        mesh,
+        material,
+        collider,
+        body: body.copied().map(SceneBody::from),
+        children: children
+            .get(entity)
+            .into_iter()
+            .flatten()
+            .filter_map(|child| capture_node(*child, children, nodes, assets, paths))
+            .collect(),
+    })
+}
+
+fn load_added_root(
+    add: On<Add, DioramaSceneRoot>,
+    mut commands: Commands,
+    roots: Query<&DioramaSceneRoot>,
+    placed: Query<(Entity, &PlacedBy, &ChildOf)>,
+    assets: Res<SceneAssets>,
+    asset_server: Res<AssetServer>,
+    mut paths: NamePaths,
+) {
+    let Ok(scene_root) = roots.get(add.entity) else {
+        return;
+    };
+    // No file yet means the layout is still built in code, ready to be saved with F3+S
+    if scene_root.path.exists() {
+        load_into(
+            &mut commands,
+            add.entity,
+            &scene_root.path,
+            &placed,
+            &assets,
+            &asset_server,
+            &mut paths,
+        );
+    }
+}
+
+fn load_into(
+    commands: &mut Commands,
+    root: Entity,
+    path: &Path,
+    placed: &Query<(Entity, &PlacedBy, &ChildOf)>,
+    assets: &SceneAssets,
+    asset_server: &AssetServer,
+    paths: &mut NamePaths,
+) {
+    let scene = match DioramaScene::read(path) {
+        Ok(scene) => scene,
+        Err(e) => {
+            error!("Failed to load scene {}: {e}", path.display());
+            return;
+        }
+    };
+    commands.entity(root).despawn_related::<Children>();
+    for (entity, placed_by, _) in placed {
+        if placed_by.0 == root {
+            commands.entity(entity).despawn();
+        }
+    }
+    for node in &scene.nodes {
+        let Some(parent_path) = &node.parent else {
+            spawn_node(commands, root, node, assets, asset_server);
+            continue;
+        };
+        let Some(parent) = paths.resolve(parent_path) else {
+            warn!(
+                "Not loading a node from scene {} under {parent_path}: nothing has that name path",
+                path.display()
+            );
+            continue;
+        };
+        let entity = spawn_node(commands, parent, node, assets, asset_server);
+        commands.entity(entity).insert(PlacedBy(root));
+    }
+    info!("Loaded scene from {}", path.display());
+}
+
+fn spawn_node(
+    commands: &mut Commands,
+    parent: Entity,
+    node: &SceneNode,
+    assets: &SceneAssets,
+    asset_server: &AssetServer,
+) -> Entity {
+    let mut entity = commands.spawn((node.transform, Visibility::default(), ChildOf(parent)));
+    if let Some(name) = &node.name {
+        entity.insert(Name::new(name.clone()));
+    }
+    if let Some(key) = &node.mesh {
+        let mesh = assets
+            .mesh(key)
+            .unwrap_or_else(|| asset_server.load(key.clone()));
+        entity.insert(Mesh3d(mesh));
+    }
+    if let Some(key) = &node.material {
+        let material = assets
+            .material(key)
+            .unwrap_or_else(|| asset_server.load(key.clone()));
+        entity.insert(MeshMaterial3d(material));
+    }
+    if let Some(collider) = node.collider {
+        entity.insert(collider.to_collider());
+    }
+    if let Some(body) = node.body {
+        entity.insert(RigidBody::from(body));
+    }
+    let entity = entity.id();
+    for child in &node.children {
+        spawn_node(commands, entity, child, assets, asset_server);
+    }
+    entity
+}
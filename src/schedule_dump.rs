@@ -0,0 +1,157 @@
+//! Dumps diorama's systems and their ordering to a Graphviz file for debugging.
+//!
+//! Pressing F3+O writes `schedules.dot` to the working directory, with a cluster per schedule
+//! containing every diorama system and set, the systems and sets they are ordered against, and
+//! red dashed edges between systems with ambiguous ordering. Render it with
+//! `dot -Tsvg schedules.dot -o schedules.svg`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+use bevy::ecs::schedule::{NodeId, ScheduleGraph, Schedules};
+use bevy::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+use leafwing_input_manager::prelude::*;
+
+pub struct ScheduleDumpPlugin;
+
+impl Plugin for ScheduleDumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InputManagerPlugin::<DumpSchedulesAction>::default())
+            .add_systems(Startup, setup_actions)
+            .add_systems(
+                Last,
+                dump_schedules.run_if(action_just_pressed(DumpSchedulesAction)),
+            );
+    }
+}
+
+const OUTPUT_PATH: &str = "schedules.dot";
+/// Systems and sets are considered diorama's own if their names start with this.
+const CRATE_PREFIX: &str = "diorama::";
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+struct DumpSchedulesAction;
+
+fn setup_actions(mut commands: Commands) {
+    let dump_map = InputMap::new([(
+        DumpSchedulesAction,
+        ButtonlikeChord::new([KeyCode::F3, KeyCode::KeyO]),
+    )]);
+    commands.spawn((Name::new("Schedule dump controls"), dump_map));
+}
+
+fn dump_schedules(schedules: Res<Schedules>) {
+    let mut dot = String::from("digraph schedules {\n  rankdir=LR;\n  node [shape=box];\n");
+    let mut ambiguities = 0usize;
+    for (label, schedule) in schedules.iter() {
+        ambiguities = ambiguities.saturating_add(write_schedule(
+            &mut dot,
+            &format!("{label:?}"),
+            schedule.graph(),
+        ));
+    }
+    dot.push_str("}\n");
+
+    match std::fs::write(OUTPUT_PATH, dot) {
+        Ok(()) => info!("Wrote schedule graph to {OUTPUT_PATH} ({ambiguities} ambiguities)"),
+        Err(e) => error!("Failed to write schedule graph: {e}"),
+    }
+}
+
+/// Writes one schedule as a cluster, returning how many ambiguous system pairs involve diorama.
+fn write_schedule(dot: &mut String, schedule: &str, graph: &ScheduleGraph) -> usize {
+    let mut names = Vec::new();
+    for (key, system, _) in graph.systems() {
+        names.push((NodeId::System(key), system.name().to_string(), false));
+    }
+    for (key, set, _) in graph.system_sets() {
+        names.push((NodeId::Set(key), format!("{set:?}"), true));
+    }
+    let owned: HashSet<NodeId> = names
+        .iter()
+        .filter(|(_, name, _)| name.starts_with(CRATE_PREFIX))
+        .map(|(node, _, _)| *node)
+        .collect();
+    if owned.is_empty() {
+        return 0;
+    }
+
+    let involves_owned = |a: &NodeId, b: &NodeId| owned.contains(a) || owned.contains(b);
+    let hierarchy: Vec<_> = graph
+        .hierarchy()
+        .graph()
+        .all_edges()
+        .filter(|(a, b)| involves_owned(a, b))
+        .collect();
+    let dependencies: Vec<_> = graph
+        .dependency()
+        .graph()
+        .all_edges()
+        .filter(|(a, b)| involves_owned(a, b))
+        .collect();
+    let conflicts: Vec<_> = graph
+        .conflicting_systems()
+        .iter()
+        .map(|(a, b, _)| (NodeId::System(*a), NodeId::System(*b)))
+        .filter(|(a, b)| involves_owned(a, b))
+        .collect();
+
+    // Include anything diorama is ordered against, so constraints with scene systems show up
+    let mut included = owned.clone();
+    for (a, b) in hierarchy.iter().chain(&dependencies).chain(&conflicts) {
+        included.insert(*a);
+        included.insert(*b);
+    }
+
+    let id = |node: &NodeId| format!("\"{schedule}/{node:?}\"");
+    let _ = writeln!(
+        dot,
+        "  subgraph \"cluster_{schedule}\" {{\n    label=\"{schedule}\";"
+    );
+    for (node, name, is_set) in names.iter().filter(|(node, _, _)| included.contains(node)) {
+        let label = name.replace('"', "'");
+        let shape = if *is_set { "ellipse" } else { "box" };
+        let style = if owned.contains(node) {
+            "bold"
+        } else {
+            "dashed"
+        };
+        let _ = writeln!(
+            dot,
+            "    {} [label=\"{label}\", shape={shape}, style={style}];",
+            id(node)
+        );
+    }
+    for (set, member) in &hierarchy {
+        let _ = writeln!(
+            dot,
+            "    {} -> {} [color=gray, arrowhead=none];",
+            id(set),
+            id(member)
+        );
+    }
+    for (before, after) in &dependencies {
+        let _ = writeln!(dot, "    {} -> {};", id(before), id(after));
+    }
+    for (a, b) in &conflicts {
+        let _ = writeln!(
+            dot,
+            "    {} -> {} [color=red, style=dashed, dir=none, constraint=false];",
+            id(a),
+            id(b)
+        );
+    }
+    dot.push_str("  }\n");
+
+    let lookup: HashMap<NodeId, &str> = names
+        .iter()
+        .map(|(node, name, _)| (*node, name.as_str()))
+        .collect();
+    for (a, b) in &conflicts {
+        let a = lookup.get(a).copied().unwrap_or_default();
+        let b = lookup.get(b).copied().unwrap_or_default();
+        warn!("Ambiguous system order in {schedule}: {a} and {b}");
+    }
+    conflicts.len()
+}
@@ -0,0 +1,507 @@
+//! Procedural sculptures, from superformula supershapes and seeded SDF compositions.
+//!
+//! A [`Sculpture`] is built from a [`SculptureForm`]: either a supershape, sweeping one
+//! [`Superformula`] around and another from base to top, or blobs smoothly blended along a
+//! wandering spine with holes bored through, laid out by the seed. Either is fitted to the
+//! sculpture's size, standing on the origin, then twisted and tapered towards the top, and given
+//! smooth normals. The seed also varies a supershape's parameters, so one preset gives a whole
+//! family of related shapes. Meshes can be built at several levels of detail, shown either as
+//! [`VisibilityRange`] children or swapped on one entity with a [`Lod`], and [`SculptureMeshes`]
+//! caches them, so identical sculptures are only generated once.
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use avian3d::math::AdjustPrecision;
+use avian3d::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::VisibilityRange;
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::lod::Lod;
+use crate::rng::SplitMix64;
+use crate::sdf_terrain::{SdfChunk, SdfField};
+
+pub struct SculpturePlugin;
+
+impl Plugin for SculpturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SculptureMeshes>();
+    }
+}
+
+/// Segments around a supershape at the highest level of detail, with half as many from base to
+/// top. Each further level halves this, down to [`MIN_RESOLUTION`].
+const SUPERSHAPE_SEGMENTS: u32 = 96;
+/// Cells across an SDF composition at the highest level of detail. Each further level halves this,
+/// down to [`MIN_RESOLUTION`].
+const SDF_CELLS: u32 = 48;
+const MIN_RESOLUTION: u32 = 8;
+/// Level of detail sampled for the convex collider.
+const COLLIDER_LOD: usize = 3;
+
+/// Gielis' superformula, a radius around a closed curve that covers circles, polygons, stars and
+/// flowers depending on its parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Superformula {
+    /// Rotational symmetry, i.e. how many lobes or corners the curve has.
+    pub m: f32,
+    /// Overall sharpness. Low values pinch the curve into spikes, high values puff it out.
+    pub n1: f32,
+    /// Shape of the curve towards its corners.
+    pub n2: f32,
+    /// Shape of the curve between its corners.
+    pub n3: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Default for Superformula {
+    /// A circle.
+    fn default() -> Self {
+        Self::new(0.0, 1.0, 1.0, 1.0)
+    }
+}
+
+impl Superformula {
+    pub fn new(m: f32, n1: f32, n2: f32, n3: f32) -> Self {
+        Self {
+            m,
+            n1,
+            n2,
+            n3,
+            a: 1.0,
+            b: 1.0,
+        }
+    }
+
+    pub fn with_ab(mut self, a: f32, b: f32) -> Self {
+        self.a = a;
+        self.b = b;
+        self
+    }
+
+    /// Radius of the curve at `angle` radians.
+    pub fn radius(&self, angle: f32) -> f32 {
+        let quarter = self.m * angle / 4.0;
+        let sum = (quarter.cos() / self.a).abs().powf(self.n2)
+            + (quarter.sin() / self.b).abs().powf(self.n3);
+        if sum <= f32::EPSILON {
+            return 0.0;
+        }
+        let radius = sum.powf(-1.0 / self.n1.max(0.01));
+        if radius.is_finite() { radius } else { 0.0 }
+    }
+
+    /// Each parameter scaled by up to `variation` either way.
+    fn varied(&self, rng: &mut SplitMix64, variation: f32) -> Self {
+        let mut vary = |value: f32| value * (1.0 + rng.range_f32(-variation, variation));
+        Self {
+            m: self.m,
+            n1: vary(self.n1).max(0.01),
+            n2: vary(self.n2).max(0.0),
+            n3: vary(self.n3).max(0.0),
+            a: vary(self.a).max(0.01),
+            b: vary(self.b).max(0.01),
+        }
+    }
+
+    fn hash_into(&self, hasher: &mut DefaultHasher) {
+        for value in [self.m, self.n1, self.n2, self.n3, self.a, self.b] {
+            value.to_bits().hash(hasher);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SculptureForm {
+    /// Sweeps `longitude` around the vertical axis and `latitude` from base to top.
+    Supershape {
+        longitude: Superformula,
+        latitude: Superformula,
+    },
+    /// `parts` blobs stacked along a wandering spine and smoothly blended together, with `holes`
+    /// tunnels bored through them, all laid out by the seed.
+    Blobs { parts: u32, holes: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sculpture {
+    pub form: SculptureForm,
+    /// Width, height and depth, before twisting and tapering.
+    pub size: Vec3,
+    /// Radians the sculpture turns through from base to top.
+    pub twist: f32,
+    /// How much narrower the top is than the base, from 0 for straight sides to 1 for a point.
+    pub taper: f32,
+    /// How far the seed varies a supershape's parameters, as a fraction of each.
+    pub variation: f32,
+    pub seed: u64,
+}
+
+impl Default for Sculpture {
+    fn default() -> Self {
+        Self {
+            form: SculptureForm::Supershape {
+                longitude: Superformula::default(),
+                latitude: Superformula::default(),
+            },
+            size: Vec3::ONE,
+            twist: 0.0,
+            taper: 0.0,
+            variation: 0.2,
+            seed: 0,
+        }
+    }
+}
+
+impl Sculpture {
+    pub fn new(form: SculptureForm) -> Self {
+        Self { form, ..default() }
+    }
+
+    pub fn supershape(longitude: Superformula, latitude: Superformula) -> Self {
+        Self::new(SculptureForm::Supershape {
+            longitude,
+            latitude,
+        })
+    }
+
+    pub fn blobs(parts: u32, holes: u32) -> Self {
+        Self::new(SculptureForm::Blobs {
+            parts: parts.max(1),
+            holes,
+        })
+    }
+
+    pub fn with_size(mut self, size: Vec3) -> Self {
+        self.size = size.max(Vec3::splat(0.01));
+        self
+    }
+
+    pub fn with_twist(mut self, twist: f32) -> Self {
+        self.twist = twist;
+        self
+    }
+
+    pub fn with_taper(mut self, taper: f32) -> Self {
+        self.taper = taper.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_variation(mut self, variation: f32) -> Self {
+        self.variation = variation.max(0.0);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Tall, star-sectioned spire turning through half a revolution.
+    pub fn twisted_spire() -> Self {
+        Self::supershape(
+            Superformula::new(5.0, 0.6, 1.0, 1.0),
+            Superformula::new(2.0, 2.0, 2.0, 2.0),
+        )
+        .with_size(Vec3::new(0.8, 1.8, 0.8))
+        .with_twist(PI)
+        .with_taper(0.6)
+    }
+
+    /// Rounded, gallery-sized mass of blended blobs with a couple of holes through it.
+    pub fn organic() -> Self {
+        Self::blobs(5, 2).with_size(Vec3::new(1.2, 1.5, 1.2))
+    }
+
+    /// Slab with softened edges, slightly tapered, for standing stones and alien monoliths.
+    pub fn monolith() -> Self {
+        Self::supershape(
+            Superformula::new(4.0, 12.0, 12.0, 12.0),
+            Superformula::new(4.0, 12.0, 12.0, 12.0),
+        )
+        .with_size(Vec3::new(1.6, 6.0, 0.6))
+        .with_taper(0.25)
+        .with_variation(0.4)
+    }
+
+    /// Builds the mesh at level of detail `lod`, where 0 is the most detailed.
+    pub fn mesh(&self, lod: usize) -> Mesh {
+        let mut mesh = match &self.form {
+            SculptureForm::Supershape {
+                longitude,
+                latitude,
+            } => {
+                let mut rng = SplitMix64::new(self.seed);
+                let longitude = longitude.varied(&mut rng, self.variation);
+                let latitude = latitude.varied(&mut rng, self.variation);
+                supershape(&longitude, &latitude, resolution(SUPERSHAPE_SEGMENTS, lod))
+            }
+            SculptureForm::Blobs { parts, holes } => {
+                let cells = resolution(SDF_CELLS, lod);
+                SdfChunk::new(
+                    blobs(*parts, *holes, self.seed),
+                    Vec3::splat(-1.2),
+                    Vec3::splat(2.4),
+                    2.4 / cells as f32,
+                )
+                .mesh()
+                .unwrap_or_else(|| supershape(&default(), &default(), cells))
+            }
+        };
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            self.fit(positions);
+        }
+        // Normals are worked out after fitting, as scaling, twisting and tapering all turn them
+        mesh.compute_smooth_normals();
+        mesh
+    }
+
+    /// Fits unit-space `positions` to the sculpture's size, standing on the origin, then twists
+    /// and tapers them.
+    fn fit(&self, positions: &mut [[f32; 3]]) {
+        let (min, max) = positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), position| {
+                let position = Vec3::from_array(*position);
+                (min.min(position), max.max(position))
+            },
+        );
+        let center = (min + max) * 0.5;
+        let half_size = ((max - min) * 0.5).max(Vec3::splat(f32::EPSILON));
+        for position in positions.iter_mut() {
+            let unit = (Vec3::from_array(*position) - center) / half_size;
+            // How far up the sculpture, from 0 at the base to 1 at the top
+            let height = (unit.y + 1.0) * 0.5;
+            let across = Vec2::from_angle(self.twist * height)
+                .rotate(Vec2::new(unit.x, unit.z) * (1.0 - self.taper * height));
+            let fitted = Vec3::new(across.x, unit.y + 1.0, across.y) * self.size * 0.5;
+            *position = fitted.to_array();
+        }
+    }
+
+    /// Builds a convex hull around a coarse version of the mesh, filling in any holes, and falling
+    /// back to a box if the hull is degenerate.
+    pub fn collider(&self) -> Collider {
+        let mesh = self.mesh(COLLIDER_LOD);
+        let points = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions
+                .iter()
+                .map(|position| Vec3::from_array(*position).adjust_precision())
+                .collect(),
+            _ => Vec::new(),
+        };
+        Collider::convex_hull(points).unwrap_or_else(|| {
+            Collider::compound(vec![(
+                Vec3::Y * self.size.y * 0.5,
+                Quat::IDENTITY,
+                Collider::cuboid(
+                    self.size.x.adjust_precision(),
+                    self.size.y.adjust_precision(),
+                    self.size.z.adjust_precision(),
+                ),
+            )])
+        })
+    }
+
+    fn cache_key(&self, lods: usize) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        match &self.form {
+            SculptureForm::Supershape {
+                longitude,
+                latitude,
+            } => {
+                0u8.hash(&mut hasher);
+                longitude.hash_into(&mut hasher);
+                latitude.hash_into(&mut hasher);
+            }
+            SculptureForm::Blobs { parts, holes } => {
+                1u8.hash(&mut hasher);
+                parts.hash(&mut hasher);
+                holes.hash(&mut hasher);
+            }
+        }
+        for value in self.size.to_array() {
+            value.to_bits().hash(&mut hasher);
+        }
+        for value in [self.twist, self.taper, self.variation] {
+            value.to_bits().hash(&mut hasher);
+        }
+        self.seed.hash(&mut hasher);
+        lods.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+fn resolution(highest: u32, lod: usize) -> u32 {
+    highest
+        .checked_shr(lod as u32)
+        .unwrap_or(0)
+        .max(MIN_RESOLUTION)
+}
+
+/// Unit supershape with `segments` around it, closed with a single vertex at each pole so its
+/// normals are smooth all the way round.
+fn supershape(longitude: &Superformula, latitude: &Superformula, segments: u32) -> Mesh {
+    let rings = (segments / 2).max(2);
+    let mut positions = Vec::with_capacity((segments * (rings - 1) + 2) as usize);
+    positions.push([0.0, -latitude.radius(-FRAC_PI_2), 0.0]);
+    for ring in 1..rings {
+        let phi = -FRAC_PI_2 + PI * ring as f32 / rings as f32;
+        let r2 = latitude.radius(phi);
+        for segment in 0..segments {
+            let theta = -PI + TAU * segment as f32 / segments as f32;
+            let r1 = longitude.radius(theta);
+            positions.push([
+                r1 * theta.cos() * r2 * phi.cos(),
+                r2 * phi.sin(),
+                r1 * theta.sin() * r2 * phi.cos(),
+            ]);
+        }
+    }
+    let top = positions.len() as u32;
+    positions.push([0.0, latitude.radius(FRAC_PI_2), 0.0]);
+
+    let row = |ring: u32| 1 + (ring - 1) * segments;
+    let mut indices = Vec::with_capacity((segments * rings * 6) as usize);
+    for segment in 0..segments {
+        let next = (segment + 1) % segments;
+        indices.extend_from_slice(&[0, row(1) + segment, row(1) + next]);
+        for ring in 1..rings - 1 {
+            let (a, b) = (row(ring) + segment, row(ring) + next);
+            let (c, d) = (row(ring + 1) + segment, row(ring + 1) + next);
+            indices.extend_from_slice(&[a, c, b, b, c, d]);
+        }
+        indices.extend_from_slice(&[row(rings - 1) + segment, top, row(rings - 1) + next]);
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Seeded composition of blended blobs within the unit cube, with tunnels bored through it.
+fn blobs(parts: u32, holes: u32, seed: u64) -> SdfField {
+    let mut rng = SplitMix64::new(seed);
+    let parts = parts.max(1);
+    let mut blob = |part: u32| {
+        let height = if parts == 1 {
+            0.5
+        } else {
+            part as f32 / (parts - 1) as f32
+        };
+        let center = Vec3::new(
+            rng.range_f32(-0.4, 0.4),
+            -0.7 + 1.4 * height,
+            rng.range_f32(-0.4, 0.4),
+        );
+        (center, rng.range_f32(0.2, 0.35))
+    };
+
+    let (mut previous, radius) = blob(0);
+    let mut field = SdfField::sphere(previous, radius);
+    for part in 1..parts {
+        let (center, radius) = blob(part);
+        // Necks between neighbouring blobs keep the whole sculpture in one piece
+        let neck = SdfField::capsule(previous, center, radius * 0.5);
+        field = field.smooth_union(SdfField::sphere(center, radius).union(neck), 0.3);
+        previous = center;
+    }
+    for _ in 0..holes {
+        let direction = Vec2::from_angle(rng.range_f32(0.0, TAU));
+        let through = Vec3::new(direction.x, 0.0, direction.y) * 1.5;
+        let center = Vec3::new(
+            rng.range_f32(-0.2, 0.2),
+            rng.range_f32(-0.5, 0.5),
+            rng.range_f32(-0.2, 0.2),
+        );
+        let tunnel = SdfField::capsule(
+            center - through,
+            center + through,
+            rng.range_f32(0.08, 0.15),
+        );
+        field = field.smooth_subtract(tunnel, 0.1);
+    }
+    field
+}
+
+/// A built sculpture's meshes, from most to least detailed, and its collider.
+#[derive(Debug, Clone)]
+pub struct SculptureLods {
+    pub meshes: Vec<Handle<Mesh>>,
+    pub collider: Collider,
+}
+
+impl SculptureLods {
+    /// Components for one child entity per level of detail, each visible for `lod_distance` units
+    /// further from the camera than the last. The least detailed level stays visible beyond that.
+    pub fn bundles<M: Material>(
+        &self,
+        material: Handle<M>,
+        lod_distance: f32,
+    ) -> impl Iterator<Item = (Mesh3d, MeshMaterial3d<M>, VisibilityRange)> + '_ {
+        let last = self.meshes.len().saturating_sub(1);
+        self.meshes.iter().enumerate().map(move |(lod, mesh)| {
+            let start = lod as f32 * lod_distance;
+            let end = if lod == last {
+                f32::MAX
+            } else {
+                start + lod_distance
+            };
+            (
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                VisibilityRange::abrupt(start, end),
+            )
+        })
+    }
+
+    /// A [`Lod`] swapping between the meshes on one entity, each level taking over `lod_distance`
+    /// units further from the camera than the last.
+    pub fn lod(&self, lod_distance: f32) -> Lod {
+        let mut meshes = self.meshes.iter().cloned();
+        let first = Lod::new(meshes.next().unwrap_or_default());
+        (1..).zip(meshes).fold(first, |lod, (level, mesh)| {
+            lod.with_level(level as f32 * lod_distance, mesh)
+        })
+    }
+}
+
+/// Cache of built sculptures, keyed by their form, parameters and seed.
+#[derive(Resource, Default, Debug)]
+pub struct SculptureMeshes {
+    cache: HashMap<u64, SculptureLods>,
+}
+
+impl SculptureMeshes {
+    /// Meshes for `sculpture` at `lods` levels of detail, and its collider, building them on first
+    /// use.
+    pub fn get_or_build(
+        &mut self,
+        sculpture: &Sculpture,
+        lods: usize,
+        meshes: &mut Assets<Mesh>,
+    ) -> SculptureLods {
+        self.cache
+            .entry(sculpture.cache_key(lods))
+            .or_insert_with(|| SculptureLods {
+                meshes: (0..lods.max(1))
+                    .map(|lod| meshes.add(sculpture.mesh(lod)))
+                    .collect(),
+                collider: sculpture.collider(),
+            })
+            .clone()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
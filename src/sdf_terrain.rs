@@ -0,0 +1,331 @@
+//! Signed distance field terrain, for caves, overhangs and grottos that heightmaps can't express.
+//!
+//! An [`SdfField`] is negative inside solid ground and positive in open space. It can be built from
+//! a heightmap and carved with shapes such as capsule tunnels and spherical chambers. Each
+//! [`SdfChunk`] meshes one box of the field with surface nets, a simple form of dual contouring,
+//! and gets a matching trimesh collider. Chunks that share a field and sit at their
+//! [`SdfChunk::origin`] line up without seams, so large cave systems can be split up with
+//! [`SdfChunk::grid`], and only the chunks flagged as caves need to use this instead of a
+//! heightmap.
+
+use std::sync::Arc;
+
+use avian3d::math::AdjustPrecision;
+use avian3d::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+pub struct SdfTerrainPlugin;
+
+impl Plugin for SdfTerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, build_sdf_chunks);
+    }
+}
+
+/// A signed distance function: negative inside solid ground, positive outside.
+#[derive(Clone)]
+pub struct SdfField(Arc<dyn Fn(Vec3) -> f32 + Send + Sync>);
+
+impl std::fmt::Debug for SdfField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SdfField")
+    }
+}
+
+impl SdfField {
+    pub fn new(distance: impl Fn(Vec3) -> f32 + Send + Sync + 'static) -> Self {
+        Self(Arc::new(distance))
+    }
+
+    pub fn distance(&self, point: Vec3) -> f32 {
+        (self.0)(point)
+    }
+
+    /// Ground below `height(x, z)`. Only exact for flat ground, which is close enough for meshing
+    /// gentle slopes.
+    pub fn heightmap(height: impl Fn(f32, f32) -> f32 + Send + Sync + 'static) -> Self {
+        Self::new(move |point| point.y - height(point.x, point.z))
+    }
+
+    pub fn sphere(center: Vec3, radius: f32) -> Self {
+        Self::new(move |point| point.distance(center) - radius)
+    }
+
+    /// A rounded tube from `start` to `end`, e.g. for tunnels when subtracted.
+    pub fn capsule(start: Vec3, end: Vec3, radius: f32) -> Self {
+        let segment = end - start;
+        let length_squared = segment.length_squared().max(f32::EPSILON);
+        Self::new(move |point| {
+            let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+            point.distance(start + segment * t) - radius
+        })
+    }
+
+    pub fn cuboid(center: Vec3, half_size: Vec3) -> Self {
+        Self::new(move |point| {
+            let q = (point - center).abs() - half_size;
+            q.max(Vec3::ZERO).length() + q.max_element().min(0.0)
+        })
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self::new(move |point| self.distance(point).min(other.distance(point)))
+    }
+
+    /// Carves `other` out of this field.
+    pub fn subtract(self, other: Self) -> Self {
+        Self::new(move |point| self.distance(point).max(-other.distance(point)))
+    }
+
+    /// Union that blends the two surfaces over roughly `smoothness` units.
+    pub fn smooth_union(self, other: Self, smoothness: f32) -> Self {
+        let k = smoothness.max(f32::EPSILON);
+        Self::new(move |point| {
+            let (a, b) = (self.distance(point), other.distance(point));
+            let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+            b.lerp(a, h) - k * h * (1.0 - h)
+        })
+    }
+
+    /// Subtraction that rounds off the carved edges over roughly `smoothness` units.
+    pub fn smooth_subtract(self, other: Self, smoothness: f32) -> Self {
+        let k = smoothness.max(f32::EPSILON);
+        Self::new(move |point| {
+            let (a, b) = (self.distance(point), -other.distance(point));
+            let h = (0.5 - 0.5 * (b - a) / k).clamp(0.0, 1.0);
+            a.lerp(b, h) + k * h * (1.0 - h)
+        })
+    }
+
+    /// Field value gradient, pointing out of the ground.
+    fn normal(&self, point: Vec3, step: f32) -> Vec3 {
+        let dx = Vec3::X * step;
+        let dy = Vec3::Y * step;
+        let dz = Vec3::Z * step;
+        Vec3::new(
+            self.distance(point + dx) - self.distance(point - dx),
+            self.distance(point + dy) - self.distance(point - dy),
+            self.distance(point + dz) - self.distance(point - dz),
+        )
+        .normalize_or(Vec3::Y)
+    }
+}
+
+/// A box of an [`SdfField`] to mesh, spanning `origin..origin + size` in field space. The mesh is
+/// relative to `origin`, so place the entity's [`Transform`] at `origin` for neighbouring chunks to
+/// line up. A mesh and collider are (re)built whenever this component changes; add a material to
+/// render it.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility, RigidBody::Static)]
+pub struct SdfChunk {
+    pub field: SdfField,
+    pub origin: Vec3,
+    pub size: Vec3,
+    /// Edge length of each sampled cell. Smaller cells capture finer detail but take longer to
+    /// build.
+    pub cell_size: f32,
+}
+
+impl SdfChunk {
+    pub fn new(field: SdfField, origin: Vec3, size: Vec3, cell_size: f32) -> Self {
+        Self {
+            field,
+            origin,
+            size,
+            cell_size: cell_size.max(0.01),
+        }
+    }
+
+    /// Chunks of up to `chunk_size` covering `min..max`, each with the transform to spawn it at.
+    /// Neighbouring chunks only meet exactly if `chunk_size` is a multiple of `cell_size`.
+    pub fn grid(
+        field: SdfField,
+        min: Vec3,
+        max: Vec3,
+        chunk_size: f32,
+        cell_size: f32,
+    ) -> impl Iterator<Item = (Transform, Self)> {
+        let chunk_size = chunk_size.max(cell_size);
+        let counts = ((max - min) / chunk_size).ceil().max(Vec3::ONE).as_uvec3();
+        (0..counts.x).flat_map(move |x| {
+            let field = field.clone();
+            (0..counts.y).flat_map(move |y| {
+                let field = field.clone();
+                (0..counts.z).map(move |z| {
+                    let origin = min + UVec3::new(x, y, z).as_vec3() * chunk_size;
+                    let size = (max - origin).min(Vec3::splat(chunk_size));
+                    (
+                        Transform::from_translation(origin),
+                        Self::new(field.clone(), origin, size, cell_size),
+                    )
+                })
+            })
+        })
+    }
+
+    /// Builds the chunk's surface, or `None` if it's entirely solid or entirely open.
+    pub fn mesh(&self) -> Option<Mesh> {
+        let surface = self.surface_nets();
+        (!surface.is_empty()).then(|| surface.mesh())
+    }
+
+    fn surface_nets(&self) -> Surface {
+        let cells = (self.size / self.cell_size)
+            .ceil()
+            .max(Vec3::ONE)
+            .as_uvec3();
+        // Samples start one cell before the origin, so cells on the boundary match the
+        // neighbouring chunk's and the surfaces meet without a seam
+        let samples = cells + UVec3::splat(2);
+        let sample_index = |p: UVec3| (p.x + samples.x * (p.y + samples.y * p.z)) as usize;
+        let sample_position = |p: UVec3| (p.as_vec3() - Vec3::ONE) * self.cell_size;
+
+        let mut distances = Vec::with_capacity((samples.x * samples.y * samples.z) as usize);
+        for z in 0..samples.z {
+            for y in 0..samples.y {
+                for x in 0..samples.x {
+                    let local = sample_position(UVec3::new(x, y, z));
+                    distances.push(self.field.distance(self.origin + local));
+                }
+            }
+        }
+        let distance = |p: UVec3| distances[sample_index(p)];
+
+        // One vertex per cell the surface passes through, at the average of its edge crossings
+        let mut surface = Surface::default();
+        let mut cell_vertices = HashMap::new();
+        for z in 0..=cells.z {
+            for y in 0..=cells.y {
+                for x in 0..=cells.x {
+                    let cell = UVec3::new(x, y, z);
+                    let mut crossings = Vec3::ZERO;
+                    let mut count = 0;
+                    for (a, b) in CELL_EDGES {
+                        let (a, b) = (cell + a, cell + b);
+                        let (da, db) = (distance(a), distance(b));
+                        if (da < 0.0) != (db < 0.0) {
+                            let t = da / (da - db);
+                            crossings += sample_position(a).lerp(sample_position(b), t);
+                            count += 1;
+                        }
+                    }
+                    if count == 0 {
+                        continue;
+                    }
+                    let position = crossings / count as f32;
+                    let normal = self
+                        .field
+                        .normal(self.origin + position, self.cell_size * 0.5);
+                    cell_vertices.insert(cell, surface.positions.len() as u32);
+                    surface.positions.push(position.to_array());
+                    surface.normals.push(normal.to_array());
+                }
+            }
+        }
+
+        // One quad per sign-changing edge owned by this chunk, joining the four cells around it
+        for z in 1..=cells.z {
+            for y in 1..=cells.y {
+                for x in 1..=cells.x {
+                    let point = UVec3::new(x, y, z);
+                    let inside = distance(point) < 0.0;
+                    for (axis, u, v) in [
+                        (UVec3::X, UVec3::Y, UVec3::Z),
+                        (UVec3::Y, UVec3::Z, UVec3::X),
+                        (UVec3::Z, UVec3::X, UVec3::Y),
+                    ] {
+                        if inside == (distance(point + axis) < 0.0) {
+                            continue;
+                        }
+                        let quad = [point - u - v, point - v, point, point - u]
+                            .map(|cell| cell_vertices.get(&cell).copied());
+                        let [Some(a), Some(b), Some(c), Some(d)] = quad else {
+                            continue;
+                        };
+                        // Counter-clockwise around the axis faces along it, which is outwards
+                        // when the ground is on the near side
+                        if inside {
+                            surface.indices.extend_from_slice(&[a, b, c, a, c, d]);
+                        } else {
+                            surface.indices.extend_from_slice(&[a, c, b, a, d, c]);
+                        }
+                    }
+                }
+            }
+        }
+        surface
+    }
+}
+
+/// Corner pairs of the 12 edges of a cell, relative to its minimum corner.
+const CELL_EDGES: [(UVec3, UVec3); 12] = [
+    (UVec3::new(0, 0, 0), UVec3::new(1, 0, 0)),
+    (UVec3::new(0, 1, 0), UVec3::new(1, 1, 0)),
+    (UVec3::new(0, 0, 1), UVec3::new(1, 0, 1)),
+    (UVec3::new(0, 1, 1), UVec3::new(1, 1, 1)),
+    (UVec3::new(0, 0, 0), UVec3::new(0, 1, 0)),
+    (UVec3::new(1, 0, 0), UVec3::new(1, 1, 0)),
+    (UVec3::new(0, 0, 1), UVec3::new(0, 1, 1)),
+    (UVec3::new(1, 0, 1), UVec3::new(1, 1, 1)),
+    (UVec3::new(0, 0, 0), UVec3::new(0, 0, 1)),
+    (UVec3::new(1, 0, 0), UVec3::new(1, 0, 1)),
+    (UVec3::new(0, 1, 0), UVec3::new(0, 1, 1)),
+    (UVec3::new(1, 1, 0), UVec3::new(1, 1, 1)),
+];
+
+#[derive(Default)]
+struct Surface {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    indices: Vec<u32>,
+}
+
+impl Surface {
+    fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    fn collider(&self) -> Collider {
+        let vertices = self
+            .positions
+            .iter()
+            .map(|position| Vec3::from_array(*position).adjust_precision())
+            .collect();
+        let triangles = self
+            .indices
+            .chunks_exact(3)
+            .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+            .collect();
+        Collider::trimesh(vertices, triangles)
+    }
+
+    fn mesh(self) -> Mesh {
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals)
+        .with_inserted_indices(Indices::U32(self.indices))
+    }
+}
+
+fn build_sdf_chunks(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    chunks: Query<(Entity, &SdfChunk), Changed<SdfChunk>>,
+) {
+    for (entity, chunk) in &chunks {
+        let surface = chunk.surface_nets();
+        let mut entity = commands.entity(entity);
+        if surface.is_empty() {
+            entity.remove::<(Mesh3d, Collider)>();
+            continue;
+        }
+        let collider = surface.collider();
+        entity.insert((Mesh3d(meshes.add(surface.mesh())), collider));
+    }
+}
@@ -0,0 +1,148 @@
+//! How rough the sea is, coordinating everything in a scene that should feel it.
+//!
+//! There's no weather system to drive it yet, so a scene (or a future weather integration) sets
+//! [`SeaState::storminess`] directly, from 0 for flat calm to 1 for a full storm, and the sea eases
+//! towards it over [`SeaState::transition_seconds`]. As it roughens every [`WaterVolume`]'s drag
+//! strengthens and its current picks up, towards the storm settings on the resource. Wave
+//! amplitude and caustics brightness are worked out here for the scene's water surface and caustics
+//! to read, via [`SeaState::wave_amplitude`] and [`SeaState::caustics_intensity`], as they're drawn
+//! by the scene.
+
+use bevy::prelude::*;
+
+use crate::game_time::GameTime;
+use crate::physics::WaterVolume;
+use crate::state::PausableSystems;
+
+pub struct SeaStatePlugin;
+
+impl Plugin for SeaStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (roughen_sea, stir_water_volumes)
+                .chain()
+                .in_set(PausableSystems)
+                .run_if(resource_exists::<SeaState>),
+        );
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct SeaState {
+    /// How stormy the sea should be, from 0 for calm to 1 for a storm.
+    pub storminess: f32,
+    /// Seconds for the sea to go from calm to storm, or back.
+    pub transition_seconds: f32,
+    /// Height of the waves in calm and stormy seas, in world units.
+    pub wave_amplitude: (f32, f32),
+    /// Brightness of caustics in calm and stormy seas, with clouds and churned water dimming them.
+    pub caustics_intensity: (f32, f32),
+    /// Multiplier on each water volume's own drag in a storm.
+    pub storm_drag: f32,
+    /// Current added to each water volume's own in a storm.
+    pub storm_current: Vec3,
+    level: f32,
+}
+
+impl Default for SeaState {
+    fn default() -> Self {
+        Self {
+            storminess: 0.0,
+            transition_seconds: 20.0,
+            wave_amplitude: (0.2, 1.5),
+            caustics_intensity: (1.0, 0.25),
+            storm_drag: 2.0,
+            storm_current: Vec3::new(1.5, 0.0, 0.5),
+            level: 0.0,
+        }
+    }
+}
+
+impl SeaState {
+    pub fn with_storminess(mut self, storminess: f32) -> Self {
+        self.storminess = storminess.clamp(0.0, 1.0);
+        self.level = self.storminess;
+        self
+    }
+
+    pub fn with_transition_seconds(mut self, seconds: f32) -> Self {
+        self.transition_seconds = seconds.max(0.0);
+        self
+    }
+
+    pub fn with_waves(mut self, calm: f32, storm: f32) -> Self {
+        self.wave_amplitude = (calm, storm);
+        self
+    }
+
+    pub fn with_caustics(mut self, calm: f32, storm: f32) -> Self {
+        self.caustics_intensity = (calm, storm);
+        self
+    }
+
+    pub fn with_storm_currents(mut self, drag: f32, current: Vec3) -> Self {
+        self.storm_drag = drag.max(0.0);
+        self.storm_current = current;
+        self
+    }
+
+    /// How stormy the sea is right now, easing towards [`SeaState::storminess`].
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    pub fn wave_amplitude(&self) -> f32 {
+        self.wave_amplitude
+            .0
+            .lerp(self.wave_amplitude.1, self.level)
+    }
+
+    pub fn caustics_intensity(&self) -> f32 {
+        self.caustics_intensity
+            .0
+            .lerp(self.caustics_intensity.1, self.level)
+    }
+}
+
+/// A water volume's settings in a calm sea, kept so the storm can scale from them.
+#[derive(Component, Debug, Clone, Copy)]
+struct CalmWater {
+    linear_drag: f32,
+    angular_drag: f32,
+    current: Vec3,
+}
+
+fn roughen_sea(time: Res<GameTime>, mut sea: ResMut<SeaState>) {
+    let target = sea.storminess.clamp(0.0, 1.0);
+    if sea.level == target {
+        return;
+    }
+    let step = time.delta_secs() / sea.transition_seconds.max(f32::EPSILON);
+    sea.level += (target - sea.level).clamp(-step, step);
+}
+
+fn stir_water_volumes(
+    mut commands: Commands,
+    sea: Res<SeaState>,
+    mut volumes: Query<(Entity, &mut WaterVolume, Option<&CalmWater>)>,
+) {
+    for (entity, mut volume, calm) in &mut volumes {
+        let calm = match calm {
+            Some(calm) => *calm,
+            None => {
+                let calm = CalmWater {
+                    linear_drag: volume.linear_drag,
+                    angular_drag: volume.angular_drag,
+                    current: volume.current,
+                };
+                commands.entity(entity).insert(calm);
+                calm
+            }
+        };
+        let drag = 1.0_f32.lerp(sea.storm_drag, sea.level);
+        volume.linear_drag = calm.linear_drag * drag;
+        volume.angular_drag = calm.angular_drag * drag;
+        volume.current = calm.current + sea.storm_current * sea.level;
+    }
+}
@@ -0,0 +1,207 @@
+//! Entity selection while the world inspector is open.
+//!
+//! Clicking an entity in the scene selects it, and a "Selection" panel shows its components along
+//! with per-entity debugging toggles, such as drawing a wireframe over its shaded meshes to check
+//! generated terrain or decimated LOD meshes. The selected hierarchy is outlined with gizmos:
+//! bounding boxes, pivot axes and lines from each parent to its children.
+
+use bevy::camera::primitives::Aabb;
+use bevy::color::palettes::tailwind::{AMBER_400, CYAN_400};
+use bevy::pbr::wireframe::Wireframe;
+use bevy::picking::pointer::PointerButton;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPrimaryContextPass, PrimaryEguiContext};
+use bevy_inspector_egui::bevy_inspector::ui_for_entity;
+use bevy_inspector_egui::egui;
+
+use crate::inspector::InspectorState;
+
+pub(crate) struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Selection>()
+            .init_resource::<SelectionGizmos>()
+            .add_observer(select_on_click)
+            .add_systems(
+                Update,
+                (
+                    forget_despawned_selection,
+                    draw_selection_gizmos.run_if(in_state(InspectorState::Enabled)),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                selection_ui.run_if(in_state(InspectorState::Enabled)),
+            )
+            .add_systems(OnExit(InspectorState::Enabled), clear_selection);
+    }
+}
+
+/// The entity picked in the scene while the inspector is open.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct Selection(pub(crate) Option<Entity>);
+
+/// Which gizmos are drawn for the selected hierarchy.
+#[derive(Resource, Debug)]
+struct SelectionGizmos {
+    bounds: bool,
+    axes: bool,
+    links: bool,
+}
+
+impl Default for SelectionGizmos {
+    fn default() -> Self {
+        Self {
+            bounds: true,
+            axes: true,
+            links: true,
+        }
+    }
+}
+
+/// Pivot axes are drawn this long, in world units.
+const AXES_LENGTH: f32 = 0.5;
+
+fn select_on_click(
+    click: On<Pointer<Click>>,
+    state: Res<State<InspectorState>>,
+    mut selection: ResMut<Selection>,
+) {
+    // Clicks bubble up the hierarchy, so only select the entity that was actually hit
+    if *state.get() != InspectorState::Enabled
+        || click.button != PointerButton::Primary
+        || click.entity != click.original_event_target()
+    {
+        return;
+    }
+    selection.0 = Some(click.entity);
+}
+
+fn forget_despawned_selection(mut selection: ResMut<Selection>, entities: Query<()>) {
+    if selection.0.is_some_and(|entity| !entities.contains(entity)) {
+        selection.0 = None;
+    }
+}
+
+fn clear_selection(mut selection: ResMut<Selection>) {
+    selection.0 = None;
+}
+
+fn selection_ui(world: &mut World) {
+    let Some(entity) = world.resource::<Selection>().0 else {
+        return;
+    };
+    if world.get_entity(entity).is_err() {
+        return;
+    }
+    let Ok(egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryEguiContext>>()
+        .single(world)
+    else {
+        return;
+    };
+    let mut egui_context = egui_context.clone();
+
+    let title = world
+        .get::<Name>(entity)
+        .map_or_else(|| format!("{entity}"), |name| format!("{name} ({entity})"));
+    egui::Window::new("Selection")
+        .default_width(320.0)
+        .show(egui_context.get_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(title);
+                if ui.button("Deselect").clicked() {
+                    world.resource_mut::<Selection>().0 = None;
+                }
+            });
+
+            let mut wireframe = has_wireframe_overlay(world, entity);
+            if ui.checkbox(&mut wireframe, "Wireframe overlay").changed() {
+                set_wireframe_overlay(world, entity, wireframe);
+            }
+            ui.horizontal(|ui| {
+                let mut gizmos = world.resource_mut::<SelectionGizmos>();
+                ui.checkbox(&mut gizmos.bounds, "Bounds");
+                ui.checkbox(&mut gizmos.axes, "Axes");
+                ui.checkbox(&mut gizmos.links, "Links");
+            });
+
+            ui.separator();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui_for_entity(world, entity, ui);
+            });
+        });
+}
+
+fn draw_selection_gizmos(
+    selection: Res<Selection>,
+    settings: Res<SelectionGizmos>,
+    children: Query<&Children>,
+    transforms: Query<(&GlobalTransform, Option<&Aabb>)>,
+    mut gizmos: Gizmos,
+) {
+    let Some(selected) = selection.0 else {
+        return;
+    };
+    for entity in std::iter::once(selected).chain(children.iter_descendants(selected)) {
+        let Ok((transform, aabb)) = transforms.get(entity) else {
+            continue;
+        };
+        if let Some(aabb) = aabb.filter(|_| settings.bounds) {
+            let bounds = Transform::from_translation(aabb.center.into())
+                .with_scale((aabb.half_extents * 2.0).into());
+            gizmos.cube(*transform * bounds, AMBER_400);
+        }
+        if settings.axes {
+            gizmos.axes(*transform, AXES_LENGTH);
+        }
+        if settings.links {
+            for child in children.get(entity).into_iter().flatten() {
+                if let Ok((child_transform, _)) = transforms.get(*child) {
+                    gizmos.line(
+                        transform.translation(),
+                        child_transform.translation(),
+                        CYAN_400,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Whether `entity`, or any mesh under it, has a wireframe overlay.
+fn has_wireframe_overlay(world: &World, entity: Entity) -> bool {
+    mesh_hierarchy(world, entity).any(|mesh| world.get::<Wireframe>(mesh).is_some())
+}
+
+/// Draws a wireframe over the shaded meshes of `entity` and its descendants, independently of the
+/// global F3+G toggle. Imported scenes keep their meshes on child entities, so the whole
+/// hierarchy is covered.
+fn set_wireframe_overlay(world: &mut World, entity: Entity, enabled: bool) {
+    let meshes: Vec<Entity> = mesh_hierarchy(world, entity).collect();
+    for mesh in meshes {
+        let mut mesh = world.entity_mut(mesh);
+        if enabled {
+            mesh.insert(Wireframe);
+        } else {
+            mesh.remove::<Wireframe>();
+        }
+    }
+}
+
+fn mesh_hierarchy(world: &World, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+    let mut stack = vec![entity];
+    std::iter::from_fn(move || {
+        while let Some(next) = stack.pop() {
+            if let Some(children) = world.get::<Children>(next) {
+                stack.extend(children.iter());
+            }
+            if world.get::<Mesh3d>(next).is_some() {
+                return Some(next);
+            }
+        }
+        None
+    })
+}
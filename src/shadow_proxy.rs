@@ -0,0 +1,109 @@
+//! Cheap stand-in meshes for shadow casting.
+//!
+//! Adding a [`ShadowProxy`] to the root of a complex assembly stops every mesh beneath it from
+//! casting shadows, and spawns a single simple mesh in their place. The proxy lives on
+//! [`SHADOW_PROXY_LAYER`], which cameras don't render but lights are added to, so it only ever
+//! shows up in shadow passes.
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::light::NotShadowCaster;
+use bevy::prelude::*;
+
+pub struct ShadowProxyPlugin;
+
+impl Plugin for ShadowProxyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(spawn_shadow_proxy)
+            .add_observer(include_shadow_proxy_layer::<DirectionalLight>)
+            .add_observer(include_shadow_proxy_layer::<PointLight>)
+            .add_observer(include_shadow_proxy_layer::<SpotLight>)
+            .add_systems(Update, disable_detailed_shadows);
+    }
+}
+
+/// Render layer that only lights see, used for shadow proxy meshes.
+pub const SHADOW_PROXY_LAYER: usize = 31;
+
+/// Casts shadows for this entity and all of its descendants using `mesh` instead.
+#[derive(Component, Clone)]
+#[require(Transform, Visibility)]
+pub struct ShadowProxy {
+    pub mesh: Handle<Mesh>,
+    /// Placement of the proxy mesh relative to this entity.
+    pub transform: Transform,
+}
+
+impl ShadowProxy {
+    pub fn new(mesh: Handle<Mesh>) -> Self {
+        Self {
+            mesh,
+            transform: Transform::default(),
+        }
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+}
+
+/// Marks the spawned stand-in mesh so it keeps casting shadows.
+#[derive(Component)]
+struct ShadowProxyMesh;
+
+fn spawn_shadow_proxy(
+    add: On<Add, ShadowProxy>,
+    proxies: Query<&ShadowProxy>,
+    mut commands: Commands,
+) {
+    let Ok(proxy) = proxies.get(add.entity) else {
+        return;
+    };
+    commands.entity(add.entity).with_child((
+        Name::new("Shadow proxy"),
+        ShadowProxyMesh,
+        Mesh3d(proxy.mesh.clone()),
+        MeshMaterial3d::<StandardMaterial>::default(),
+        proxy.transform,
+        RenderLayers::layer(SHADOW_PROXY_LAYER),
+    ));
+}
+
+fn include_shadow_proxy_layer<L: Component>(
+    add: On<Add, L>,
+    layers: Query<Option<&RenderLayers>>,
+    mut commands: Commands,
+) {
+    let Ok(existing) = layers.get(add.entity) else {
+        return;
+    };
+    let layers = existing.cloned().unwrap_or_default();
+    commands
+        .entity(add.entity)
+        .insert(layers.with(SHADOW_PROXY_LAYER));
+}
+
+/// Stops detailed meshes under a [`ShadowProxy`] from casting their own shadows.
+fn disable_detailed_shadows(
+    mut commands: Commands,
+    meshes: Query<
+        Entity,
+        (
+            Added<Mesh3d>,
+            Without<ShadowProxyMesh>,
+            Without<NotShadowCaster>,
+        ),
+    >,
+    parents: Query<&ChildOf>,
+    proxies: Query<(), With<ShadowProxy>>,
+) {
+    for entity in meshes.iter() {
+        let proxied = proxies.contains(entity)
+            || parents
+                .iter_ancestors(entity)
+                .any(|ancestor| proxies.contains(ancestor));
+        if proxied {
+            commands.entity(entity).insert(NotShadowCaster);
+        }
+    }
+}
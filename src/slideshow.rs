@@ -0,0 +1,374 @@
+//! Projected slideshows: images shown one after another on a screen, lit by a projector's beam.
+//!
+//! A [`Slideshow`] puts a screen in front of its entity, facing +Z, that shows each of its slides
+//! for `interval` seconds, dipping to black between them. Slides are stretched to fill the screen,
+//! so they're best made at its aspect ratio. With a projector, a spotlight and a faint cone of
+//! light shine on the screen from in front of it, and with dimming, an [`AmbientLightVolume`]
+//! darkens the room in front of the screen while the player's in it. Bevy can't decode video, so
+//! slides are still images only.
+//!
+//! Slides can be image handles, such as generated textures, or listed in a `.slides.ron` file in
+//! an app's assets and loaded with a [`SlideshowFile`], e.g.
+//!
+//! ```text
+//! (
+//!     slides: ["slides/harbour.png", "slides/lighthouse.png"],
+//!     interval: 10.0,
+//! )
+//! ```
+//!
+//! The file is hot reloaded if Bevy's `file_watcher` feature is enabled.
+
+use std::f32::consts::FRAC_PI_2;
+use std::io;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::light::NotShadowCaster;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::ambient_volume::AmbientLightVolume;
+use crate::game_time::GameTime;
+
+pub struct SlideshowPlugin;
+
+impl Plugin for SlideshowPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SlideList>()
+            .register_asset_loader(SlideListLoader)
+            .add_observer(build_slideshow)
+            .add_systems(
+                Update,
+                (load_slideshow_files, apply_slideshow_files, advance_slides).chain(),
+            );
+    }
+}
+
+/// Peak intensity of a projector's spotlight, in lumens per square unit of screen.
+const PROJECTOR_INTENSITY: f32 = 40_000.0;
+
+/// A screen showing a slideshow, built in front of its entity.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct Slideshow {
+    pub slides: Vec<Handle<Image>>,
+    /// Seconds each slide shows for, fades included.
+    pub interval: f32,
+    /// Seconds taken to fade to black and back between slides.
+    pub fade: f32,
+    /// Width and height of the screen in world units.
+    pub size: Vec2,
+    /// How bright the screen is, with 1 showing slides at their own colours.
+    pub brightness: f32,
+    /// Distance in front of the screen of a projector shining on it, or `None` for a screen that
+    /// glows by itself, like a monitor.
+    pub projector_distance: Option<f32>,
+    /// Half the size of the box in front of the screen to dim, and the ambient brightness inside.
+    pub dimming: Option<(Vec3, f32)>,
+}
+
+impl Default for Slideshow {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl Slideshow {
+    pub fn new(slides: Vec<Handle<Image>>) -> Self {
+        Self {
+            slides,
+            interval: 8.0,
+            fade: 1.0,
+            size: Vec2::new(1.6, 0.9),
+            brightness: 1.0,
+            projector_distance: Some(4.0),
+            dimming: None,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: f32, fade: f32) -> Self {
+        self.interval = interval.max(0.1);
+        self.fade = fade.clamp(0.0, self.interval);
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size.max(Vec2::splat(0.1));
+        self
+    }
+
+    pub fn with_brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness.max(0.0);
+        self
+    }
+
+    pub fn with_projector(mut self, distance: f32) -> Self {
+        self.projector_distance = Some(distance.max(0.1));
+        self
+    }
+
+    pub fn without_projector(mut self) -> Self {
+        self.projector_distance = None;
+        self
+    }
+
+    /// Dims the ambient light to `brightness` within `half_extents` of the middle of the space in
+    /// front of the screen.
+    pub fn with_dimming(mut self, half_extents: Vec3, brightness: f32) -> Self {
+        self.dimming = Some((half_extents, brightness));
+        self
+    }
+
+    /// How far through fading out or in the slide is at `shown_for` seconds, from 0 for black to
+    /// 1 for fully shown.
+    fn level(&self, shown_for: f32) -> f32 {
+        let half_fade = self.fade / 2.0;
+        if half_fade <= 0.0 {
+            return 1.0;
+        }
+        (shown_for.min(self.interval - shown_for) / half_fade).clamp(0.0, 1.0)
+    }
+}
+
+/// Asset path of a `.slides.ron` file to load this entity's slides from, replacing its
+/// [`Slideshow`]'s slides, and interval if the file has one, once loaded.
+#[derive(Component, Debug, Clone)]
+#[require(Slideshow)]
+pub struct SlideshowFile {
+    pub path: String,
+    handle: Option<Handle<SlideList>>,
+}
+
+impl SlideshowFile {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            handle: None,
+        }
+    }
+}
+
+/// Slides listed in a `.slides.ron` file, by asset path.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct SlideList {
+    pub slides: Vec<String>,
+    #[serde(default)]
+    pub interval: Option<f32>,
+}
+
+#[derive(Default, TypePath)]
+struct SlideListLoader;
+
+impl AssetLoader for SlideListLoader {
+    type Asset = SlideList;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> io::Result<SlideList> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        ron::de::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["slides.ron"]
+    }
+}
+
+/// What a slideshow's built from, and where it's up to.
+#[derive(Component)]
+struct SlideshowParts {
+    material: Handle<StandardMaterial>,
+    projector: Option<Entity>,
+    current: usize,
+    shown_for: f32,
+    /// Slide and fade level last written to the screen, so it's only touched when they change.
+    applied: Option<(usize, f32)>,
+}
+
+fn build_slideshow(
+    add: On<Add, Slideshow>,
+    mut commands: Commands,
+    slideshows: Query<&Slideshow>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok(slideshow) = slideshows.get(add.entity) else {
+        return;
+    };
+    let material = materials.add(StandardMaterial {
+        base_color: Color::BLACK,
+        base_color_texture: slideshow.slides.first().cloned(),
+        unlit: true,
+        ..default()
+    });
+    let screen = commands
+        .spawn((
+            Name::new("Slideshow screen"),
+            Mesh3d(meshes.add(Rectangle::from_size(slideshow.size))),
+            MeshMaterial3d(material.clone()),
+            // Just off the wall it's hung on, so it doesn't flicker through it
+            Transform::from_xyz(0.0, 0.0, 0.01),
+        ))
+        .id();
+    let mut children = vec![screen];
+
+    let mut projector = None;
+    if let Some(distance) = slideshow.projector_distance {
+        let half_diagonal = slideshow.size.length() / 2.0;
+        let spread = (half_diagonal / distance).atan();
+        let beam = Cone {
+            radius: slideshow.size.y / 2.0,
+            height: distance,
+        };
+        let beam = commands
+            .spawn((
+                Name::new("Projector beam"),
+                Mesh3d(meshes.add(beam)),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::srgba(1.0, 0.97, 0.9, 0.03),
+                    alpha_mode: AlphaMode::Add,
+                    unlit: true,
+                    double_sided: true,
+                    cull_mode: None,
+                    ..default()
+                })),
+                // Cones point up, so tip it over towards the projector
+                Transform::from_xyz(0.0, 0.0, distance / 2.0)
+                    .with_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+                NotShadowCaster,
+            ))
+            .id();
+        let light = commands
+            .spawn((
+                Name::new("Projector"),
+                SpotLight {
+                    intensity: 0.0,
+                    color: Color::srgb(1.0, 0.97, 0.9),
+                    range: distance * 1.5,
+                    radius: 0.05,
+                    shadows_enabled: false,
+                    inner_angle: spread * 0.8,
+                    outer_angle: spread,
+                    ..default()
+                },
+                Transform::from_xyz(0.0, 0.0, distance).looking_at(Vec3::ZERO, Vec3::Y),
+            ))
+            .id();
+        children.extend([beam, light]);
+        projector = Some(light);
+    }
+
+    if let Some((half_extents, brightness)) = slideshow.dimming {
+        let dimming = commands
+            .spawn((
+                Name::new("Slideshow dimming"),
+                AmbientLightVolume::new(half_extents, Color::srgb(0.85, 0.85, 0.9), brightness),
+                Transform::from_xyz(0.0, 0.0, half_extents.z),
+            ))
+            .id();
+        children.push(dimming);
+    }
+
+    commands
+        .entity(add.entity)
+        .add_children(&children)
+        .insert(SlideshowParts {
+            material,
+            projector,
+            current: 0,
+            shown_for: 0.0,
+            applied: None,
+        });
+}
+
+fn load_slideshow_files(
+    mut files: Query<&mut SlideshowFile, Added<SlideshowFile>>,
+    asset_server: Res<AssetServer>,
+) {
+    for mut file in &mut files {
+        file.handle = Some(asset_server.load(file.path.clone()));
+    }
+}
+
+fn apply_slideshow_files(
+    mut events: MessageReader<AssetEvent<SlideList>>,
+    asset_server: Res<AssetServer>,
+    lists: Res<Assets<SlideList>>,
+    mut slideshows: Query<(&SlideshowFile, &mut Slideshow, Option<&mut SlideshowParts>)>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+        let Some(list) = lists.get(id) else {
+            continue;
+        };
+        for (file, mut slideshow, parts) in &mut slideshows {
+            if !file.handle.as_ref().is_some_and(|handle| handle.id() == id) {
+                continue;
+            }
+            debug!("Applying slides from {}", file.path);
+            slideshow.slides = list
+                .slides
+                .iter()
+                .map(|path| asset_server.load(path.clone()))
+                .collect();
+            if let Some(interval) = list.interval {
+                slideshow.interval = interval.max(0.1);
+                slideshow.fade = slideshow.fade.min(slideshow.interval);
+            }
+            // Back to the first slide, faded in from black
+            if let Some(mut parts) = parts {
+                parts.current = 0;
+                parts.shown_for = 0.0;
+                parts.applied = None;
+            }
+        }
+    }
+}
+
+fn advance_slides(
+    time: Res<GameTime>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut slideshows: Query<(&Slideshow, &mut SlideshowParts)>,
+    mut projectors: Query<&mut SpotLight>,
+) {
+    for (slideshow, mut parts) in &mut slideshows {
+        parts.shown_for += time.delta_secs();
+        if parts.shown_for >= slideshow.interval {
+            parts.shown_for %= slideshow.interval;
+            parts.current += 1;
+        }
+        if slideshow.slides.is_empty() {
+            parts.current = 0;
+        } else {
+            parts.current %= slideshow.slides.len();
+        }
+        let level = if slideshow.slides.is_empty() {
+            0.0
+        } else {
+            slideshow.level(parts.shown_for)
+        };
+
+        if parts.applied == Some((parts.current, level)) {
+            continue;
+        }
+        parts.applied = Some((parts.current, level));
+        if let Some(material) = materials.get_mut(&parts.material) {
+            let value = slideshow.brightness * level;
+            material.base_color_texture = slideshow.slides.get(parts.current).cloned();
+            material.base_color = LinearRgba::rgb(value, value, value).into();
+        }
+        if let Some(mut light) = parts.projector.and_then(|p| projectors.get_mut(p).ok()) {
+            light.intensity = PROJECTOR_INTENSITY * slideshow.size.x * slideshow.size.y * level;
+        }
+    }
+}
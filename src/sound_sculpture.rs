@@ -0,0 +1,190 @@
+//! Sound sculptures: exhibits that play a generative melody of chimes as spatial audio.
+//!
+//! A [`SoundSculpture`] steps through a pattern generated from its seed, striking a chime from its
+//! scale of [`SoundSculpture::notes`] on some steps and resting on others. It stays silent until the
+//! [`SpatialListener`] comes within [`SoundSculpture::range`], and the nearer the listener gets
+//! the louder it plays and the fewer steps it rests on. Walking around it transposes the pattern
+//! through the scale by the listener's bearing from the sculpture, so each side has its own tune.
+//! Chimes are synthesised for each note when the sculpture is added, so no audio files are needed.
+//! Sculptures with a caption are listed in [`SoundCaptions`] while they're playing.
+
+use std::f32::consts::TAU;
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::audio::synthesise_chime;
+use crate::captions::SoundCaptions;
+use crate::rng::SplitMix64;
+
+pub struct SoundSculpturePlugin;
+
+impl Plugin for SoundSculpturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(synthesise_notes)
+            .add_systems(Update, play_sound_sculptures);
+    }
+}
+
+/// Semitones above the root of each note in a major pentatonic scale, which has no clashing
+/// intervals however the notes fall.
+const PENTATONIC: [i32; 5] = [0, 2, 4, 7, 9];
+
+#[derive(Component, Debug, Clone)]
+#[require(Transform)]
+pub struct SoundSculpture {
+    /// Frequencies of the notes it can play, in Hz, from low to high.
+    pub notes: Vec<f32>,
+    /// Length of the pattern, in steps, before it repeats.
+    pub steps: u32,
+    /// Steps per second.
+    pub tempo: f32,
+    /// Distance from the listener beyond which it's silent.
+    pub range: f32,
+    pub volume: f32,
+    /// Picks the pattern, so sculptures with the same notes play different tunes.
+    pub seed: u64,
+    /// What it sounds like, e.g. "chimes ringing", captioned while it's playing.
+    pub caption: Option<String>,
+    clips: Vec<Handle<AudioSource>>,
+    step: u32,
+    timer: f32,
+}
+
+impl SoundSculpture {
+    pub fn new(notes: Vec<f32>) -> Self {
+        Self {
+            notes,
+            steps: 16,
+            tempo: 3.0,
+            range: 12.0,
+            volume: 0.6,
+            seed: 0,
+            caption: None,
+            clips: Vec::new(),
+            step: 0,
+            timer: 0.0,
+        }
+    }
+
+    /// A major pentatonic scale from `root` Hz up `octaves` octaves.
+    pub fn pentatonic(root: f32, octaves: u32) -> Self {
+        let notes = (0..octaves.max(1) as i32)
+            .flat_map(|octave| PENTATONIC.map(|semitones| semitones + octave * 12))
+            .map(|semitones| root * 2f32.powf(semitones as f32 / 12.0))
+            .collect();
+        Self::new(notes)
+    }
+
+    pub fn with_steps(mut self, steps: u32) -> Self {
+        self.steps = steps.max(1);
+        self
+    }
+
+    pub fn with_tempo(mut self, tempo: f32) -> Self {
+        self.tempo = tempo.max(0.01);
+        self
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range.max(f32::EPSILON);
+        self
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// The note `step` of the pattern strikes, before transposing, and how readily it's played,
+    /// from 0 (whenever the sculpture plays at all) to 1 (only with the listener right beside it).
+    fn pattern(&self, step: u32) -> (usize, f32) {
+        let mut rng = SplitMix64::new(self.seed ^ u64::from(step).wrapping_mul(0x9E37_79B9));
+        let note = rng.index(self.notes.len());
+        // The downbeat of every four always plays, holding the tune together when it's sparse
+        let reluctance = if step % 4 == 0 { 0.0 } else { rng.next_f32() };
+        (note, reluctance)
+    }
+}
+
+fn synthesise_notes(
+    add: On<Add, SoundSculpture>,
+    mut sources: ResMut<Assets<AudioSource>>,
+    mut sculptures: Query<&mut SoundSculpture>,
+) {
+    let Ok(mut sculpture) = sculptures.get_mut(add.entity) else {
+        return;
+    };
+    sculpture.clips = sculpture
+        .notes
+        .iter()
+        .map(|&frequency| sources.add(synthesise_chime(frequency)))
+        .collect();
+}
+
+fn play_sound_sculptures(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sound_captions: ResMut<SoundCaptions>,
+    listener: Option<Single<&GlobalTransform, With<SpatialListener>>>,
+    mut sculptures: Query<(Entity, &mut SoundSculpture, &GlobalTransform)>,
+) {
+    let Some(listener) = listener else {
+        return;
+    };
+    let listener_position = listener.translation();
+
+    for (entity, mut sculpture, transform) in &mut sculptures {
+        let distance = transform.translation().distance(listener_position);
+        if distance >= sculpture.range || sculpture.clips.is_empty() {
+            // Starts from the top of the pattern each time the listener comes back
+            sculpture.step = 0;
+            sculpture.timer = 0.0;
+            continue;
+        }
+
+        sculpture.timer -= time.delta_secs();
+        if sculpture.timer > 0.0 {
+            continue;
+        }
+        sculpture.timer += 1.0 / sculpture.tempo;
+        let step = sculpture.step;
+        sculpture.step = (step + 1) % sculpture.steps;
+
+        let closeness = 1.0 - distance / sculpture.range;
+        let (note, reluctance) = sculpture.pattern(step);
+        if reluctance > closeness {
+            continue;
+        }
+        let local = transform
+            .affine()
+            .inverse()
+            .transform_point3(listener_position);
+        let bearing = local.z.atan2(local.x) / TAU + 0.5;
+        let notes = sculpture.clips.len();
+        let transpose = (bearing * notes as f32) as usize;
+        let clip = sculpture.clips[(note + transpose) % notes].clone();
+        if let Some(caption) = &sculpture.caption {
+            sound_captions.heard(caption, Some(transform.translation()));
+        }
+
+        commands.entity(entity).with_child((
+            Name::new("Sound sculpture note"),
+            Transform::default(),
+            AudioPlayer::new(clip),
+            PlaybackSettings::DESPAWN
+                .with_spatial(true)
+                .with_volume(Volume::Linear(sculpture.volume * closeness * closeness)),
+        ));
+    }
+}
@@ -1,9 +1,11 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::firstsight::{LookDisabled, MovementDisabled, PlayerCamera};
+use crate::dialogue_log::DialogueInProgress;
+use crate::firstsight::MovementDisabled;
 use crate::player::Player;
 
-#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum GameState {
     #[default]
     Active,
@@ -14,33 +16,44 @@ pub struct StatePlugin;
 
 impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Paused), on_pause)
+        app.configure_sets(Update, PausableSystems.run_if(not_paused()))
+            .add_systems(OnEnter(GameState::Paused), on_pause)
             .add_systems(OnEnter(GameState::Active), on_resume);
     }
 }
 
-fn on_pause(
-    mut commands: Commands,
-    player: Single<(Entity, &Player), Without<MovementDisabled>>,
-    player_camera: Single<(Entity, &PlayerCamera), Without<LookDisabled>>,
-) {
-    commands
-        .entity(player.into_inner().0)
-        .insert(MovementDisabled);
-    commands
-        .entity(player_camera.into_inner().0)
-        .insert(LookDisabled);
+/// Gameplay systems in [`Update`], such as moving platforms and scene animations, that freeze while
+/// the game is paused, like physics does.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PausableSystems;
+
+/// Run condition that is true unless the game is paused.
+pub fn not_paused() -> impl FnMut(Option<Res<State<GameState>>>) -> bool + Clone {
+    |state: Option<Res<State<GameState>>>| {
+        state.is_none_or(|state| *state.get() != GameState::Paused)
+    }
+}
+
+/// Run condition for gameplay systems, such as scene animations, that should halt while the game
+/// is paused or a conversation is in progress.
+pub fn in_gameplay()
+-> impl FnMut(Option<Res<State<GameState>>>, Query<(), With<DialogueInProgress>>) -> bool + Clone {
+    let mut not_paused = not_paused();
+    move |state: Option<Res<State<GameState>>>, dialogues: Query<(), With<DialogueInProgress>>| {
+        not_paused(state) && dialogues.is_empty()
+    }
 }
 
-fn on_resume(
-    mut commands: Commands,
-    player: Single<(Entity, &Player), With<MovementDisabled>>,
-    player_camera: Single<(Entity, &PlayerCamera), With<LookDisabled>>,
-) {
+const PAUSE_REASON: &str = "paused";
+
+fn on_pause(mut commands: Commands, player: Single<Entity, With<Player>>) {
     commands
-        .entity(player.into_inner().0)
-        .remove::<MovementDisabled>();
+        .entity(*player)
+        .queue(MovementDisabled::request(PAUSE_REASON));
+}
+
+fn on_resume(mut commands: Commands, player: Single<Entity, With<Player>>) {
     commands
-        .entity(player_camera.into_inner().0)
-        .remove::<LookDisabled>();
+        .entity(*player)
+        .queue(MovementDisabled::cancel(PAUSE_REASON));
 }
@@ -0,0 +1,242 @@
+//! Stroke sounds, controller rumble and exhaled bubbles while the player swims.
+//!
+//! While the player has the [`Swimming`] marker, which [`MovementMode::Swim`] gives them, holding
+//! any movement input takes a stroke every [`SwimFeedback::stroke_interval`] seconds, the first as
+//! soon as movement starts. Each stroke triggers a [`SwimStroke`], which plays one of the stroke
+//! sounds, pulses the rumble motors of every connected gamepad and, every few strokes, breathes out
+//! a burst of bubbles that wobble up from in front of the camera. Like footsteps, stroke sounds
+//! need adding to [`SwimFeedback`] to be heard.
+//!
+//! [`MovementMode::Swim`]: crate::player::MovementMode::Swim
+
+use std::time::Duration;
+
+use bevy::audio::Volume;
+use bevy::input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest};
+use bevy::prelude::*;
+
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::firstsight::{MovementDisabled, PlayerCamera};
+use crate::game_time::GameTime;
+use crate::particles::{EmitterShape, LifeCurve, LifeGradient, ParticleEmitter};
+use crate::player::Player;
+use crate::rng::SplitMix64;
+use crate::state::PausableSystems;
+
+pub struct SwimPlugin;
+
+impl Plugin for SwimPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SwimFeedback>()
+            .add_systems(Update, track_strokes.in_set(PausableSystems))
+            .add_observer(respond_to_stroke);
+    }
+}
+
+/// Marks the player as swimming, so movement input takes strokes. Kept in step with
+/// [`MovementMode::Swim`](crate::player::MovementMode::Swim), so set that rather than inserting
+/// this directly.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Swimming;
+
+/// Triggered each time the swimming player takes a stroke.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SwimStroke {
+    /// Position of the player's head.
+    pub position: Vec3,
+    /// Direction the camera is facing.
+    pub forward: Vec3,
+    /// How hard the stroke is, from the movement input, between 0 and 1.
+    pub strength: f32,
+    /// Whether this stroke breathes out bubbles.
+    pub exhale: bool,
+}
+
+/// How swim strokes look, sound and feel.
+#[derive(Resource, Debug, Clone)]
+pub struct SwimFeedback {
+    /// Seconds between strokes while movement input is held.
+    pub stroke_interval: f32,
+    /// Clips to pick from at random for each stroke.
+    pub stroke_sounds: Vec<Handle<AudioSource>>,
+    pub volume: f32,
+    /// Maximum playback speed deviation either side of 1.0.
+    pub pitch_variance: f32,
+    /// Strength of the rumble pulse on a full stroke, between 0 and 1, or 0 for none.
+    pub rumble: f32,
+    pub rumble_duration: Duration,
+    /// Strokes per breath, so bubbles come out every this many strokes.
+    pub strokes_per_breath: u32,
+    /// Bubbles in each exhaled burst.
+    pub bubbles: u32,
+    pub bubble_color: Color,
+    /// Seconds a bubble rises for before popping.
+    pub bubble_lifetime: f32,
+}
+
+impl Default for SwimFeedback {
+    fn default() -> Self {
+        Self {
+            stroke_interval: 0.9,
+            stroke_sounds: Vec::new(),
+            volume: 0.5,
+            pitch_variance: 0.08,
+            rumble: 0.3,
+            rumble_duration: Duration::from_millis(120),
+            strokes_per_breath: 3,
+            bubbles: 8,
+            bubble_color: Color::srgba(0.85, 0.95, 1.0, 0.35),
+            bubble_lifetime: 2.5,
+        }
+    }
+}
+
+impl SwimFeedback {
+    pub fn with_stroke_sounds(mut self, sounds: Vec<Handle<AudioSource>>) -> Self {
+        self.stroke_sounds = sounds;
+        self
+    }
+
+    pub fn with_rumble(mut self, rumble: f32) -> Self {
+        self.rumble = rumble.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_bubbles(mut self, strokes_per_breath: u32, bubbles: u32) -> Self {
+        self.strokes_per_breath = strokes_per_breath.max(1);
+        self.bubbles = bubbles;
+        self
+    }
+}
+
+/// Average upward speed of a bubble, in units per second.
+const BUBBLE_RISE_SPEED: f32 = 0.76;
+/// How far in front of and below the camera bubbles come out.
+const MOUTH_OFFSET: Vec3 = Vec3::new(0.0, -0.15, -0.35);
+
+/// Time towards the next stroke, and strokes since the last breath out.
+#[derive(Component, Debug, Default)]
+struct StrokeTracker {
+    elapsed: f32,
+    moving: bool,
+    strokes: u32,
+}
+
+fn track_strokes(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    input: ControlsInput,
+    controls: Res<ControlsConfig>,
+    feedback: Res<SwimFeedback>,
+    player: Single<
+        (Entity, Option<&mut StrokeTracker>),
+        (With<Player>, With<Swimming>, Without<MovementDisabled>),
+    >,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+) {
+    let (entity, tracker) = player.into_inner();
+    let Some(mut tracker) = tracker else {
+        commands.entity(entity).insert(StrokeTracker::default());
+        return;
+    };
+
+    let keys = [
+        &controls.move_forward,
+        &controls.move_back,
+        &controls.move_left,
+        &controls.move_right,
+        &controls.ascend,
+        &controls.descend,
+    ];
+    let strength = if keys.into_iter().any(|binding| input.pressed(binding)) {
+        1.0
+    } else {
+        input.move_axis(controls.stick_deadzone).length().min(1.0)
+    };
+    if strength <= 0.0 {
+        tracker.moving = false;
+        return;
+    }
+
+    // Start with a stroke straight away, rather than after a delay
+    if !tracker.moving {
+        tracker.moving = true;
+        tracker.elapsed = feedback.stroke_interval;
+    } else {
+        tracker.elapsed += time.delta_secs();
+    }
+    if tracker.elapsed < feedback.stroke_interval {
+        return;
+    }
+    tracker.elapsed = 0.0;
+    tracker.strokes += 1;
+    let exhale = tracker.strokes >= feedback.strokes_per_breath;
+    if exhale {
+        tracker.strokes = 0;
+    }
+
+    commands.trigger(SwimStroke {
+        position: camera.translation(),
+        forward: *camera.forward(),
+        strength,
+        exhale,
+    });
+}
+
+fn respond_to_stroke(
+    stroke: On<SwimStroke>,
+    mut commands: Commands,
+    feedback: Res<SwimFeedback>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    mut rumble: MessageWriter<GamepadRumbleRequest>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    mut rng: Local<Option<SplitMix64>>,
+) {
+    let rng = rng.get_or_insert_with(|| SplitMix64::new(0xB0B));
+
+    if !feedback.stroke_sounds.is_empty() {
+        let clip = feedback.stroke_sounds[rng.index(feedback.stroke_sounds.len())].clone();
+        let pitch = 1.0 + rng.range_f32(-feedback.pitch_variance, feedback.pitch_variance);
+        commands.spawn((
+            Name::new("Swim stroke sound"),
+            Transform::from_translation(stroke.position),
+            AudioPlayer::new(clip),
+            PlaybackSettings::DESPAWN
+                .with_spatial(true)
+                .with_volume(Volume::Linear(feedback.volume * stroke.strength))
+                .with_speed(pitch),
+        ));
+    }
+
+    let intensity = feedback.rumble * stroke.strength;
+    if intensity > 0.0 {
+        for gamepad in &gamepads {
+            rumble.write(GamepadRumbleRequest::Add {
+                gamepad,
+                intensity: GamepadRumbleIntensity::weak_motor(intensity),
+                duration: feedback.rumble_duration,
+            });
+        }
+    }
+
+    if stroke.exhale && feedback.bubbles > 0 {
+        // Bubbles sway as they rise, and grow a little as the pressure drops
+        commands.spawn((
+            Name::new("Bubbles"),
+            Transform::from_translation(camera.transform_point(MOUTH_OFFSET)),
+            ParticleEmitter::default()
+                .with_rate(0.0)
+                .with_burst(feedback.bubbles)
+                .with_shape(EmitterShape::Sphere { radius: 0.05 })
+                .with_lifetime(feedback.bubble_lifetime * 0.85, 0.18)
+                .with_velocity(Vec3::Y * BUBBLE_RISE_SPEED, Vec3::new(0.0, 0.2, 0.0))
+                .with_wobble(0.025, 1.0)
+                .with_size(0.045, 0.5)
+                .with_size_over_life(LifeCurve::linear(1.0, 1.5))
+                .with_color_over_life(LifeGradient::constant(feedback.bubble_color))
+                .with_lit(true)
+                .with_seed(rng.next_u64())
+                .with_despawn_when_done(),
+        ));
+    }
+}
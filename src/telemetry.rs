@@ -0,0 +1,328 @@
+//! Opt-in playtest telemetry written to a local CSV file.
+//!
+//! Inserting a [`Telemetry`] resource, or setting the `DIORAMA_TELEMETRY` environment variable to
+//! an output path, starts recording anonymised gameplay events: periodic player position samples,
+//! deaths, clicked interactables and time spent in each [`AmbienceZone`]. Scenes report their own
+//! events by triggering a [`TelemetryEvent`], or with [`Telemetry::record`]. Recorded events are
+//! written to the output file on exit, or on demand with F9.
+//!
+//! Each row is `time,event,x,y,z,detail`, with time in seconds since recording started and the
+//! player's position when the event happened.
+//!
+//! [`AmbienceZone`]: crate::ambience::AmbienceZone
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::ambience::ActiveAmbience;
+use crate::controls::keyboard_not_captured;
+use crate::kiosk::not_in_kiosk_mode;
+use crate::player::Player;
+
+pub struct TelemetryPlugin;
+
+impl Plugin for TelemetryPlugin {
+    fn build(&self, app: &mut App) {
+        if let Ok(path) = std::env::var(TELEMETRY_ENV_VAR) {
+            app.insert_resource(Telemetry::new(path));
+        }
+        app.add_plugins(InputManagerPlugin::<ExportTelemetryAction>::default())
+            .add_systems(Startup, setup_actions)
+            .add_systems(
+                Update,
+                (
+                    sample_position,
+                    track_rooms,
+                    handle_actions
+                        .run_if(not_in_kiosk_mode)
+                        .run_if(keyboard_not_captured),
+                )
+                    .run_if(resource_exists::<Telemetry>),
+            )
+            .add_systems(Last, export_on_exit.run_if(resource_exists::<Telemetry>))
+            .add_observer(record_event)
+            .add_observer(record_interaction);
+    }
+}
+
+/// Environment variable holding the path to record telemetry to.
+const TELEMETRY_ENV_VAR: &str = "DIORAMA_TELEMETRY";
+
+/// A gameplay event to record, triggered by scenes with `commands.trigger(...)`.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum TelemetryEvent {
+    Position,
+    Death,
+    Interaction(String),
+    RoomTime { room: String, seconds: f32 },
+}
+
+impl TelemetryEvent {
+    fn kind(&self) -> &'static str {
+        match self {
+            TelemetryEvent::Position => "position",
+            TelemetryEvent::Death => "death",
+            TelemetryEvent::Interaction(_) => "interaction",
+            TelemetryEvent::RoomTime { .. } => "room_time",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryRecord {
+    pub time: f32,
+    pub position: Vec3,
+    pub event: TelemetryEvent,
+}
+
+impl fmt::Display for TelemetryRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Vec3 { x, y, z } = self.position;
+        let time = self.time;
+        let kind = self.event.kind();
+        let detail = match &self.event {
+            TelemetryEvent::Position | TelemetryEvent::Death => String::new(),
+            // Strip separators so free-form names can't break the row
+            TelemetryEvent::Interaction(name) => name.replace([',', '\n'], " "),
+            TelemetryEvent::RoomTime { room, seconds } => {
+                format!("{}:{seconds:.2}", room.replace([',', '\n', ':'], " "))
+            }
+        };
+        write!(f, "{time:.2},{kind},{x:.2},{y:.2},{z:.2},{detail}")
+    }
+}
+
+impl FromStr for TelemetryRecord {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = line.splitn(6, ',').collect();
+        let &[time, kind, x, y, z, ref rest @ ..] = fields.as_slice() else {
+            return Err(format!("too few fields in {line:?}"));
+        };
+        let number = |field: &str, value: &str| {
+            value
+                .parse::<f32>()
+                .map_err(|e| format!("invalid {field} {value:?}: {e}"))
+        };
+        let time = number("time", time)?;
+        let position = Vec3::new(number("x", x)?, number("y", y)?, number("z", z)?);
+        let detail = rest.first().copied().unwrap_or_default();
+
+        let event = match kind {
+            "position" => TelemetryEvent::Position,
+            "death" => TelemetryEvent::Death,
+            "interaction" => TelemetryEvent::Interaction(detail.to_string()),
+            "room_time" => {
+                let (room, seconds) = detail
+                    .rsplit_once(':')
+                    .ok_or_else(|| format!("invalid room time {detail:?}"))?;
+                TelemetryEvent::RoomTime {
+                    room: room.to_string(),
+                    seconds: seconds
+                        .parse()
+                        .map_err(|e| format!("invalid room seconds {seconds:?}: {e}"))?,
+                }
+            }
+            other => return Err(format!("unknown event {other:?}")),
+        };
+        Ok(Self {
+            time,
+            position,
+            event,
+        })
+    }
+}
+
+/// Reads records previously written by [`Telemetry::export`], skipping the header and any rows
+/// that fail to parse.
+pub fn read_records(path: impl AsRef<Path>) -> io::Result<Vec<TelemetryRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+    for line in reader.lines().skip(1) {
+        match line?.parse() {
+            Ok(record) => records.push(record),
+            Err(e) => warn!("Skipping telemetry row: {e}"),
+        }
+    }
+    Ok(records)
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct Telemetry {
+    pub path: PathBuf,
+    /// Seconds between player position samples.
+    pub sample_interval: f32,
+    records: Vec<TelemetryRecord>,
+    started_at: Option<f32>,
+    since_sample: f32,
+    current_room: Option<(Entity, f32)>,
+}
+
+impl Telemetry {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            sample_interval: 1.0,
+            records: Vec::new(),
+            started_at: None,
+            since_sample: 0.0,
+            current_room: None,
+        }
+    }
+
+    pub fn with_sample_interval(mut self, sample_interval: f32) -> Self {
+        self.sample_interval = sample_interval;
+        self
+    }
+
+    pub fn records(&self) -> &[TelemetryRecord] {
+        &self.records
+    }
+
+    /// Records `event` at `position`, for scenes that need a position other than the player's
+    /// current one. `now` is the elapsed [`Time`] in seconds.
+    pub fn record(&mut self, now: f32, position: Vec3, event: TelemetryEvent) {
+        let started_at = *self.started_at.get_or_insert(now);
+        self.records.push(TelemetryRecord {
+            time: now - started_at,
+            position,
+            event,
+        });
+    }
+
+    /// Writes every record so far to [`Telemetry::path`], replacing any previous export.
+    pub fn export(&self) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        writeln!(writer, "time,event,x,y,z,detail")?;
+        for record in &self.records {
+            writeln!(writer, "{record}")?;
+        }
+        writer.flush()
+    }
+}
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+struct ExportTelemetryAction;
+
+fn setup_actions(mut commands: Commands) {
+    let export_map = InputMap::new([(ExportTelemetryAction, KeyCode::F9)]);
+    commands.spawn((Name::new("Telemetry controls"), export_map));
+}
+
+fn handle_actions(
+    action_state: Single<&ActionState<ExportTelemetryAction>>,
+    telemetry: Res<Telemetry>,
+) {
+    if action_state.just_pressed(&ExportTelemetryAction) {
+        export(&telemetry);
+    }
+}
+
+fn export(telemetry: &Telemetry) {
+    match telemetry.export() {
+        Ok(()) => info!(
+            "Exported {} telemetry records to {}",
+            telemetry.records.len(),
+            telemetry.path.display()
+        ),
+        Err(e) => error!("Failed to export telemetry: {e}"),
+    }
+}
+
+fn export_on_exit(mut exits: MessageReader<AppExit>, telemetry: Res<Telemetry>) {
+    if exits.read().next().is_some() {
+        export(&telemetry);
+    }
+}
+
+fn sample_position(
+    time: Res<Time>,
+    mut telemetry: ResMut<Telemetry>,
+    player: Single<&GlobalTransform, With<Player>>,
+) {
+    telemetry.since_sample += time.delta_secs();
+    if telemetry.since_sample < telemetry.sample_interval {
+        return;
+    }
+    telemetry.since_sample = 0.0;
+    telemetry.record(
+        time.elapsed_secs(),
+        player.translation(),
+        TelemetryEvent::Position,
+    );
+}
+
+fn track_rooms(
+    time: Res<Time>,
+    active: Res<ActiveAmbience>,
+    mut telemetry: ResMut<Telemetry>,
+    player: Single<&GlobalTransform, With<Player>>,
+    names: Query<&Name>,
+) {
+    let now = time.elapsed_secs();
+    let current = telemetry.current_room.map(|(zone, _)| zone);
+    if current == active.zone {
+        return;
+    }
+
+    if let Some((zone, entered_at)) = telemetry.current_room.take() {
+        let room = names
+            .get(zone)
+            .map_or_else(|_| format!("{zone}"), |name| name.to_string());
+        telemetry.record(
+            now,
+            player.translation(),
+            TelemetryEvent::RoomTime {
+                room,
+                seconds: now - entered_at,
+            },
+        );
+    }
+    telemetry.current_room = active.zone.map(|zone| (zone, now));
+}
+
+fn record_event(
+    event: On<TelemetryEvent>,
+    time: Res<Time>,
+    telemetry: Option<ResMut<Telemetry>>,
+    player: Single<&GlobalTransform, With<Player>>,
+) {
+    if let Some(mut telemetry) = telemetry {
+        telemetry.record(
+            time.elapsed_secs(),
+            player.translation(),
+            event.event().clone(),
+        );
+    }
+}
+
+fn record_interaction(
+    click: On<Pointer<Click>>,
+    time: Res<Time>,
+    telemetry: Option<ResMut<Telemetry>>,
+    player: Single<&GlobalTransform, With<Player>>,
+    names: Query<&Name>,
+) {
+    let Some(mut telemetry) = telemetry else {
+        return;
+    };
+    // Clicks bubble up the hierarchy, so only record the entity that was actually hit
+    if click.entity != click.original_event_target() {
+        return;
+    }
+    let Ok(name) = names.get(click.entity) else {
+        return;
+    };
+    telemetry.record(
+        time.elapsed_secs(),
+        player.translation(),
+        TelemetryEvent::Interaction(name.to_string()),
+    );
+}
@@ -0,0 +1,531 @@
+//! Heightmap terrain: ground shaped by layers of noise, with a mesh, a collider and height queries.
+//!
+//! A [`Heightmap`] sums layers of seeded gradient noise, each at its own frequency and amplitude,
+//! over a square centred on the origin. [`Heightmap::build`] meshes it as a grid of `resolution`
+//! cells along each side, with a matching trimesh collider, and [`Heightmap::build_with`] lets
+//! scenes reshape the ground on top of the noise, e.g. to carve a riverbed. Inserted as a resource,
+//! it's how everything else in a scene finds the ground with [`Heightmap::height_at`], which reads
+//! the noise directly, so it can be queried anywhere, in or out of the meshed square.
+//!
+//! For ground with no edge, a [`TerrainStreamer`] builds it in square chunks around the player
+//! instead, on background tasks, despawning chunks left behind. The chunk the player's standing in
+//! is waited for if it isn't ready, so they can't fall through ground that hasn't loaded.
+//!
+//! Heightmaps can be authored in external tools too, as grayscale images from black for the lowest
+//! ground to white for the highest. [`Heightmap::from_image`] stretches an image over its square,
+//! and a [`HeightmapImage`] builds ground from an image asset, such as a PNG or an EXR, once it's
+//! loaded, and again whenever it changes if Bevy's `file_watcher` feature is enabled.
+
+use std::sync::Arc;
+
+use avian3d::math::AdjustPrecision;
+use avian3d::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageLoaderSettings;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use bevy::tasks::futures_lite::future;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on};
+
+use crate::noise::Perlin;
+use crate::player::Player;
+
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (stream_terrain_chunks, finish_terrain_chunks).chain(),
+                (load_heightmap_images, build_heightmap_images).chain(),
+            ),
+        );
+    }
+}
+
+/// One layer of noise: its frequency in cycles per world unit, and its height either side of zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseLayer {
+    pub frequency: f32,
+    pub amplitude: f32,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct Heightmap {
+    /// Width and depth of the meshed square, in world units.
+    pub size: f32,
+    /// Grid cells along each side of the mesh.
+    pub resolution: u32,
+    /// Height of the ground where every layer is at zero.
+    pub base: f32,
+    pub layers: Vec<NoiseLayer>,
+    /// Height of white in the heightmap's image, if it has one, above black at `base`.
+    pub vertical_scale: f32,
+    seed: u32,
+    perlin: Perlin,
+    image: Option<HeightSamples>,
+}
+
+impl Heightmap {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            size: 100.0,
+            resolution: 64,
+            base: 0.0,
+            layers: Vec::new(),
+            vertical_scale: 0.0,
+            seed,
+            perlin: Perlin::new(seed),
+            image: None,
+        }
+    }
+
+    /// A heightmap shaped by a grayscale `image` stretched over a square `size` across, with white
+    /// `vertical_scale` above black, meshed at the image's own resolution up to 256 cells a side.
+    /// Noise layers added to it roughen it up. `None` if the image's pixels can't be read, such as
+    /// a compressed texture or one only kept on the GPU.
+    pub fn from_image(image: &Image, size: f32, vertical_scale: f32) -> Option<Self> {
+        let samples = HeightSamples::from_image(image)?;
+        let resolution = samples.size.max_element().saturating_sub(1).clamp(1, 256);
+        let mut heightmap = Self::new(0).with_size(size, resolution);
+        heightmap.vertical_scale = vertical_scale;
+        heightmap.image = Some(samples);
+        Some(heightmap)
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    pub fn with_size(mut self, size: f32, resolution: u32) -> Self {
+        self.size = size.max(0.01);
+        self.resolution = resolution.max(1);
+        self
+    }
+
+    pub fn with_base(mut self, base: f32) -> Self {
+        self.base = base;
+        self
+    }
+
+    pub fn with_layer(mut self, frequency: f32, amplitude: f32) -> Self {
+        self.layers.push(NoiseLayer {
+            frequency,
+            amplitude,
+        });
+        self
+    }
+
+    /// World height of the ground at `x`, `z`.
+    pub fn height_at(&self, x: f32, z: f32) -> f32 {
+        let height: f64 = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let frequency = f64::from(layer.frequency);
+                self.perlin
+                    .get([f64::from(x) * frequency, f64::from(z) * frequency])
+                    * f64::from(layer.amplitude)
+            })
+            .sum();
+        let image = self.image.as_ref().map_or(0.0, |image| {
+            image.sample(Vec2::new(x, z) / self.size + 0.5) * self.vertical_scale
+        });
+        self.base + height as f32 + image
+    }
+
+    /// Mesh and collider of the ground, in world space.
+    pub fn build(&self) -> (Mesh, Collider) {
+        self.build_with(|x, z| self.height_at(x, z))
+    }
+
+    /// Mesh and collider of the grid, with each vertex at `height(x, z)` instead of
+    /// [`Heightmap::height_at`], for ground shaped by more than the noise.
+    pub fn build_with(&self, height: impl Fn(f32, f32) -> f32) -> (Mesh, Collider) {
+        build_grid(Vec2::ZERO, self.size, self.resolution, height)
+    }
+}
+
+/// Heights read from an image, from 0 for black to 1 for white, by pixel row by row.
+#[derive(Clone)]
+struct HeightSamples {
+    size: UVec2,
+    values: Arc<[f32]>,
+}
+
+impl std::fmt::Debug for HeightSamples {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HeightSamples({}x{})", self.size.x, self.size.y)
+    }
+}
+
+impl HeightSamples {
+    fn from_image(image: &Image) -> Option<Self> {
+        let size = image.size();
+        if size.x == 0 || size.y == 0 {
+            return None;
+        }
+        let values = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| (x, y)))
+            .map(|(x, y)| pixel_height(image, x, y))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            size,
+            values: values.into(),
+        })
+    }
+
+    /// Height at `uv`, from 0 to 1 across the image, blended between the nearest pixels and held
+    /// at the edge outside it.
+    fn sample(&self, uv: Vec2) -> f32 {
+        let last = (self.size - 1).as_vec2();
+        let position = (uv * self.size.as_vec2() - 0.5).clamp(Vec2::ZERO, last);
+        let low = position.floor().as_uvec2();
+        let high = (low + 1).min(self.size - 1);
+        let t = position - low.as_vec2();
+        let at = |x: u32, y: u32| self.values[(y * self.size.x + x) as usize];
+        let top = at(low.x, low.y).lerp(at(high.x, low.y), t.x);
+        let bottom = at(low.x, high.y).lerp(at(high.x, high.y), t.x);
+        top.lerp(bottom, t.y)
+    }
+}
+
+/// Brightness of the pixel at `x`, `y` from 0 to 1, from its first channel.
+fn pixel_height(image: &Image, x: u32, y: u32) -> Option<f32> {
+    let bytes = image.pixel_bytes(UVec3::new(x, y, 0))?;
+    match image.texture_descriptor.format {
+        TextureFormat::R8Unorm | TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+            Some(f32::from(bytes[0]) / f32::from(u8::MAX))
+        }
+        TextureFormat::R16Unorm
+        | TextureFormat::R16Uint
+        | TextureFormat::Rgba16Unorm
+        | TextureFormat::Rgba16Uint => {
+            Some(f32::from(u16::from_le_bytes([bytes[0], bytes[1]])) / f32::from(u16::MAX))
+        }
+        // EXR images load as 32-bit float
+        TextureFormat::R32Float | TextureFormat::Rgba32Float => {
+            Some(f32::from_le_bytes(bytes[..4].try_into().ok()?))
+        }
+        _ => image
+            .get_color_at(x, y)
+            .ok()
+            .map(|color| color.to_linear().red),
+    }
+}
+
+/// Ground built from a grayscale heightmap image asset once it's loaded, and rebuilt if it's
+/// modified. Add a material to it to give it a surface. Once built, its [`Heightmap`] is inserted
+/// as the resource, so things can be placed on it relative to this entity, and
+/// [`HeightmapImageBuilt`] is triggered on it.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct HeightmapImage {
+    pub path: String,
+    /// Width and depth the image is stretched over.
+    pub size: f32,
+    /// Height of white above black.
+    pub vertical_scale: f32,
+    /// Height of black.
+    pub base: f32,
+    handle: Option<Handle<Image>>,
+}
+
+impl HeightmapImage {
+    pub fn new(path: impl Into<String>, size: f32, vertical_scale: f32) -> Self {
+        Self {
+            path: path.into(),
+            size,
+            vertical_scale,
+            base: 0.0,
+            handle: None,
+        }
+    }
+
+    pub fn with_base(mut self, base: f32) -> Self {
+        self.base = base;
+        self
+    }
+}
+
+/// Triggered on a [`HeightmapImage`] each time its ground is built.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct HeightmapImageBuilt {
+    pub entity: Entity,
+}
+
+fn load_heightmap_images(
+    mut images: Query<&mut HeightmapImage, Added<HeightmapImage>>,
+    asset_server: Res<AssetServer>,
+) {
+    for mut image in &mut images {
+        // Heights are stored linearly, not as sRGB colours
+        image.handle = Some(
+            asset_server
+                .load_with_settings(image.path.clone(), |settings: &mut ImageLoaderSettings| {
+                    settings.is_srgb = false
+                }),
+        );
+    }
+}
+
+fn build_heightmap_images(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    images: Res<Assets<Image>>,
+    terrains: Query<(Entity, &HeightmapImage)>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+        let Some(image) = images.get(id) else {
+            continue;
+        };
+        for (entity, terrain) in &terrains {
+            if !terrain
+                .handle
+                .as_ref()
+                .is_some_and(|handle| handle.id() == id)
+            {
+                continue;
+            }
+            let Some(heightmap) =
+                Heightmap::from_image(image, terrain.size, terrain.vertical_scale)
+            else {
+                warn!("Can't read heights from {}", terrain.path);
+                continue;
+            };
+            let heightmap = heightmap.with_base(terrain.base);
+            debug!("Building terrain from {}", terrain.path);
+            let (mesh, collider) = heightmap.build();
+            commands
+                .entity(entity)
+                .insert((Mesh3d(meshes.add(mesh)), RigidBody::Static, collider));
+            commands.insert_resource(heightmap);
+            commands.trigger(HeightmapImageBuilt { entity });
+        }
+    }
+}
+
+/// Builds ground in chunks around the player, as its children, from a [`Heightmap`] or any other
+/// function of height.
+#[derive(Component, Clone)]
+#[require(Transform, Visibility)]
+pub struct TerrainStreamer {
+    pub material: Handle<StandardMaterial>,
+    /// Width and depth of each chunk.
+    pub chunk_size: f32,
+    /// Grid cells along each side of a chunk.
+    pub chunk_resolution: u32,
+    /// Distance from the player within which chunks are loaded. They're unloaded a chunk's width
+    /// further out, so walking back and forth over a boundary doesn't keep rebuilding them.
+    pub radius: f32,
+    ground: Arc<dyn Fn(f32, f32) -> f32 + Send + Sync>,
+    loaded: HashMap<IVec2, Entity>,
+    underfoot: Option<Entity>,
+}
+
+impl std::fmt::Debug for TerrainStreamer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TerrainStreamer")
+            .field("chunk_size", &self.chunk_size)
+            .field("chunk_resolution", &self.chunk_resolution)
+            .field("radius", &self.radius)
+            .field("loaded", &self.loaded.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TerrainStreamer {
+    pub fn new(heightmap: &Heightmap, material: Handle<StandardMaterial>) -> Self {
+        let heightmap = heightmap.clone();
+        Self {
+            material,
+            chunk_size: 32.0,
+            chunk_resolution: 16,
+            radius: 128.0,
+            ground: Arc::new(move |x, z| heightmap.height_at(x, z)),
+            loaded: HashMap::default(),
+            underfoot: None,
+        }
+    }
+
+    /// Shapes the ground with `height(x, z)` instead, relative to the streamer, e.g. a heightmap
+    /// with a riverbed carved into it.
+    pub fn with_ground(mut self, height: impl Fn(f32, f32) -> f32 + Send + Sync + 'static) -> Self {
+        self.ground = Arc::new(height);
+        self
+    }
+
+    pub fn with_chunks(mut self, size: f32, resolution: u32) -> Self {
+        self.chunk_size = size.max(0.01);
+        self.chunk_resolution = resolution.max(1);
+        self.radius = self.radius.max(self.chunk_size);
+        self
+    }
+
+    /// Never less than a chunk's width, so the chunk the player's in is always loaded.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius.max(self.chunk_size);
+        self
+    }
+
+    /// Height of the ground at `x`, `z`, relative to the streamer, whether or not it's loaded.
+    pub fn height_at(&self, x: f32, z: f32) -> f32 {
+        (self.ground)(x, z)
+    }
+}
+
+/// A chunk of streamed ground, until it's built.
+#[derive(Component)]
+struct TerrainChunk {
+    task: Option<Task<(Mesh, Collider)>>,
+}
+
+fn stream_terrain_chunks(
+    mut commands: Commands,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+    mut streamers: Query<(Entity, &mut TerrainStreamer, &GlobalTransform)>,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    let pool = AsyncComputeTaskPool::get();
+    for (entity, mut streamer, transform) in &mut streamers {
+        let local = transform
+            .affine()
+            .inverse()
+            .transform_point3(player.translation());
+        let centre = local.xz();
+        let size = streamer.chunk_size;
+
+        let unload_distance = streamer.radius + size;
+        streamer.loaded.retain(|coord, chunk| {
+            let keep = (coord.as_vec2() * size).distance(centre) <= unload_distance;
+            if !keep {
+                // Cancels the chunk's task too, if it's still building
+                commands.entity(*chunk).despawn();
+            }
+            keep
+        });
+
+        let here = (centre / size).round().as_ivec2();
+        let reach = (streamer.radius / size).ceil() as i32;
+        for z in -reach..=reach {
+            for x in -reach..=reach {
+                let coord = here + IVec2::new(x, z);
+                let chunk_centre = coord.as_vec2() * size;
+                if chunk_centre.distance(centre) > streamer.radius
+                    || streamer.loaded.contains_key(&coord)
+                {
+                    continue;
+                }
+                let ground = streamer.ground.clone();
+                let resolution = streamer.chunk_resolution;
+                let task = pool.spawn(async move {
+                    build_grid(chunk_centre, size, resolution, |x, z| ground(x, z))
+                });
+                let chunk = commands
+                    .spawn((
+                        Name::new(format!("Terrain Chunk {} {}", coord.x, coord.y)),
+                        TerrainChunk { task: Some(task) },
+                        Transform::from_xyz(chunk_centre.x, 0.0, chunk_centre.y),
+                        ChildOf(entity),
+                    ))
+                    .id();
+                streamer.loaded.insert(coord, chunk);
+            }
+        }
+        streamer.underfoot = streamer.loaded.get(&here).copied();
+    }
+}
+
+fn finish_terrain_chunks(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    streamers: Query<&TerrainStreamer>,
+    mut chunks: Query<(Entity, &mut TerrainChunk, &ChildOf)>,
+) {
+    for (entity, mut chunk, child_of) in &mut chunks {
+        let Ok(streamer) = streamers.get(child_of.parent()) else {
+            continue;
+        };
+        let Some(task) = chunk.task.as_mut() else {
+            continue;
+        };
+        let built = if streamer.underfoot == Some(entity) {
+            Some(block_on(task))
+        } else {
+            block_on(future::poll_once(task))
+        };
+        let Some((mesh, collider)) = built else {
+            continue;
+        };
+        chunk.task = None;
+        commands.entity(entity).insert((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(streamer.material.clone()),
+            RigidBody::Static,
+            collider,
+        ));
+    }
+}
+
+/// Mesh and collider of a square grid around `centre`, relative to it, with each vertex at
+/// `height(x, z)` of its position relative to the origin.
+fn build_grid(
+    centre: Vec2,
+    size: f32,
+    resolution: u32,
+    height: impl Fn(f32, f32) -> f32,
+) -> (Mesh, Collider) {
+    let columns = resolution + 1;
+    let step = size / resolution as f32;
+    let half = size / 2.0;
+
+    let mut positions = Vec::with_capacity((columns * columns) as usize);
+    let mut uvs = Vec::with_capacity(positions.capacity());
+    for row in 0..columns {
+        for column in 0..columns {
+            let (x, z) = (column as f32 * step - half, row as f32 * step - half);
+            positions.push([x, height(centre.x + x, centre.y + z), z]);
+            uvs.push([
+                column as f32 / resolution as f32,
+                row as f32 / resolution as f32,
+            ]);
+        }
+    }
+
+    let mut triangles = Vec::with_capacity((resolution * resolution * 2) as usize);
+    for row in 0..resolution {
+        for column in 0..resolution {
+            let corner = row * columns + column;
+            // Wound anticlockwise seen from above, so the ground faces up
+            triangles.push([corner, corner + columns, corner + 1]);
+            triangles.push([corner + 1, corner + columns, corner + columns + 1]);
+        }
+    }
+
+    let collider = Collider::trimesh(
+        positions
+            .iter()
+            .map(|position| Vec3::from_array(*position).adjust_precision())
+            .collect(),
+        triangles.clone(),
+    );
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(triangles.into_iter().flatten().collect()));
+    mesh.compute_normals();
+    (mesh, collider)
+}
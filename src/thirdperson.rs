@@ -0,0 +1,127 @@
+//! Optional third-person camera mode layered on top of the first-person controller.
+//!
+//! The camera orbits behind the player using the same yaw/pitch as first-person look. A sphere is
+//! cast from the pivot towards the desired camera position so the camera never clips through
+//! walls or terrain, snapping in while obstructed and easing back out once clear.
+
+#![allow(clippy::useless_conversion)]
+use avian3d::math::AsF32;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::controls::keyboard_not_captured;
+use crate::firstsight::{
+    PlayerCamera, PlayerCameraHeight, PlayerCameraSet, PlayerController, update_camera_looking_at,
+    update_camera_position,
+};
+
+pub struct ThirdPersonPlugin;
+
+impl Plugin for ThirdPersonPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InputManagerPlugin::<ToggleThirdPersonAction>::default())
+            .add_systems(Startup, setup_actions)
+            .add_systems(Update, handle_actions.run_if(keyboard_not_captured))
+            .add_systems(
+                PostUpdate,
+                update_third_person_camera
+                    .after(update_camera_position)
+                    .after(update_camera_looking_at)
+                    .in_set(PlayerCameraSet),
+            );
+    }
+}
+
+const DEFAULT_DISTANCE: f32 = 4.;
+/// Radius of the sphere cast used to keep the camera clear of geometry.
+const CAMERA_PROBE_RADIUS: f32 = 0.2;
+/// Closest the camera may be pulled in towards the pivot when obstructed.
+const MIN_DISTANCE: f32 = 0.3;
+/// How quickly the camera eases back out once the obstruction clears, in units per second.
+const ZOOM_OUT_SPEED: f32 = 4.;
+
+/// When attached to the [`PlayerCamera`], the camera follows the player from behind.
+#[derive(Component)]
+pub struct ThirdPersonCamera {
+    /// Desired distance from the pivot when unobstructed.
+    pub distance: f32,
+    current_distance: f32,
+}
+
+impl ThirdPersonCamera {
+    pub fn new(distance: f32) -> Self {
+        Self {
+            distance,
+            current_distance: distance,
+        }
+    }
+}
+
+impl Default for ThirdPersonCamera {
+    fn default() -> Self {
+        Self::new(DEFAULT_DISTANCE)
+    }
+}
+
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+struct ToggleThirdPersonAction;
+
+fn setup_actions(mut commands: Commands) {
+    let toggle_map = InputMap::new([(ToggleThirdPersonAction, KeyCode::KeyV)]);
+    commands.spawn((Name::new("Third-person camera controls"), toggle_map));
+}
+
+fn handle_actions(
+    mut commands: Commands,
+    action_state: Single<&ActionState<ToggleThirdPersonAction>>,
+    player_camera: Single<(Entity, Has<ThirdPersonCamera>), With<PlayerCamera>>,
+) {
+    if action_state.just_pressed(&ToggleThirdPersonAction) {
+        let (entity, is_third_person) = player_camera.into_inner();
+        if is_third_person {
+            commands.entity(entity).remove::<ThirdPersonCamera>();
+        } else {
+            commands.entity(entity).insert(ThirdPersonCamera::default());
+        }
+    }
+}
+
+/// Pulls the camera back behind the player, shortening the boom when geometry is in the way.
+fn update_third_person_camera(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    camera: Single<(&mut Transform, &mut ThirdPersonCamera), With<PlayerCamera>>,
+    player_controller: Single<
+        (Entity, &Transform, &PlayerCameraHeight),
+        (With<PlayerController>, Without<PlayerCamera>),
+    >,
+) {
+    let (mut camera_transform, mut third_person) = camera.into_inner();
+    let (player_entity, player_transform, PlayerCameraHeight(height)) =
+        player_controller.into_inner();
+
+    let pivot = player_transform.translation + Vec3::new(0.0, *height, 0.0);
+    let direction = camera_transform.back();
+
+    let target_distance = third_person.distance.max(MIN_DISTANCE);
+    let clear_distance = spatial_query
+        .cast_shape(
+            &Collider::sphere(CAMERA_PROBE_RADIUS.into()),
+            pivot.into(),
+            Quat::IDENTITY.into(),
+            direction,
+            &ShapeCastConfig::from_max_distance(target_distance.into()),
+            &SpatialQueryFilter::from_excluded_entities([player_entity]),
+        )
+        .map_or(target_distance, |hit| hit.distance.f32().max(MIN_DISTANCE));
+
+    // Pull in immediately so the camera never ends up inside geometry, but ease back out
+    third_person.current_distance = if clear_distance < third_person.current_distance {
+        clear_distance
+    } else {
+        (third_person.current_distance + ZOOM_OUT_SPEED * time.delta_secs()).min(clear_distance)
+    };
+
+    camera_transform.translation = pivot + direction * third_person.current_distance;
+}
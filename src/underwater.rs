@@ -0,0 +1,272 @@
+//! Effects that deepen with the camera's depth below a water surface.
+//!
+//! Inserting an [`Underwater`] resource sets where the surface is. Each frame the camera's depth
+//! below it is written to the [`WaterDepth`] resource, along with how far it is towards
+//! [`Underwater::full_depth`], where every effect is at its strongest. Going deeper darkens the
+//! edges of the screen with a vignette, lowers exposure, and cools and desaturates the image as
+//! water absorbs the reds.
+//!
+//! The deeper it goes, the more muffled sound gets, following [`WaterDepth::lowpass_cutoff`].
+//! `bevy_audio` has no filters, so [`SpatialAudioEmitter`]s and [`AmbientLoop`]s approximate it
+//! by playing quieter and slower, which dulls them, by [`WaterDepth::muffled_volume`] and
+//! [`WaterDepth::muffled_speed`]. Integrations with a real low-pass filter can use the cutoff
+//! directly.
+//!
+//! [`SpatialAudioEmitter`]: crate::audio::SpatialAudioEmitter
+//! [`AmbientLoop`]: crate::audio::AmbientLoop
+
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::view::ColorGrading;
+
+use crate::firstsight::PlayerCamera;
+
+pub struct UnderwaterPlugin;
+
+impl Plugin for UnderwaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WaterDepth>().add_systems(
+            Update,
+            (
+                update_water_depth,
+                (spawn_vignette, update_vignette, grade_for_depth),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Low-pass cutoff with no muffling, above anything audible.
+const OPEN_CUTOFF_HZ: f32 = 20_000.0;
+const VIGNETTE_SIZE: u32 = 128;
+
+/// Where the water surface is, and how strongly depth effects apply.
+#[derive(Resource, Debug, Clone)]
+pub struct Underwater {
+    /// Height of the water surface, in world units.
+    pub surface_height: f32,
+    /// Depth below the surface at which every effect reaches full strength.
+    pub full_depth: f32,
+    pub vignette_color: Color,
+    /// Opacity of the vignette at full depth, between 0 and 1.
+    pub vignette: f32,
+    /// Exposure lost at full depth, in stops.
+    pub darkening: f32,
+    /// Colour temperature shift at full depth. Negative cools the image as reds are absorbed.
+    pub absorption: f32,
+    /// Low-pass cutoff frequency at full depth, in hertz.
+    pub muffled_cutoff: f32,
+}
+
+impl Underwater {
+    pub fn new(surface_height: f32) -> Self {
+        Self {
+            surface_height,
+            full_depth: 30.0,
+            vignette_color: Color::srgb(0.0, 0.03, 0.08),
+            vignette: 0.85,
+            darkening: 1.5,
+            absorption: -0.4,
+            muffled_cutoff: 600.0,
+        }
+    }
+
+    pub fn with_full_depth(mut self, full_depth: f32) -> Self {
+        self.full_depth = full_depth.max(f32::EPSILON);
+        self
+    }
+
+    pub fn with_vignette(mut self, color: Color, opacity: f32) -> Self {
+        self.vignette_color = color;
+        self.vignette = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_darkening(mut self, stops: f32) -> Self {
+        self.darkening = stops;
+        self
+    }
+
+    pub fn with_absorption(mut self, absorption: f32) -> Self {
+        self.absorption = absorption;
+        self
+    }
+
+    pub fn with_muffled_cutoff(mut self, cutoff: f32) -> Self {
+        self.muffled_cutoff = cutoff.clamp(20.0, OPEN_CUTOFF_HZ);
+        self
+    }
+}
+
+/// How deep the player camera is, updated every frame.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct WaterDepth {
+    /// Distance below the water surface, or 0 above it or without [`Underwater`].
+    pub depth: f32,
+    /// How far towards [`Underwater::full_depth`] the camera is, eased, from 0 to 1.
+    pub pressure: f32,
+    /// Cutoff frequency for a low-pass filter on the scene's audio, in hertz, falling
+    /// exponentially with pressure so muffling is heard evenly all the way down.
+    pub lowpass_cutoff: f32,
+}
+
+impl Default for WaterDepth {
+    fn default() -> Self {
+        Self {
+            depth: 0.0,
+            pressure: 0.0,
+            lowpass_cutoff: OPEN_CUTOFF_HZ,
+        }
+    }
+}
+
+impl WaterDepth {
+    pub fn is_submerged(&self) -> bool {
+        self.depth > 0.0
+    }
+
+    /// Volume multiplier for sound heard through the water, from 1 at the surface to about 0.6
+    /// at the default [`Underwater::muffled_cutoff`].
+    pub fn muffled_volume(&self) -> f32 {
+        self.open_share().powf(0.15)
+    }
+
+    /// Playback speed multiplier for sound heard through the water, lowering its pitch to stand
+    /// in for the high frequencies lost to the cutoff.
+    pub fn muffled_speed(&self) -> f32 {
+        self.open_share().powf(0.04)
+    }
+
+    fn open_share(&self) -> f32 {
+        (self.lowpass_cutoff / OPEN_CUTOFF_HZ).clamp(f32::EPSILON, 1.0)
+    }
+}
+
+#[derive(Component)]
+struct DepthVignette;
+
+/// The camera's colour grading from before depth effects were applied, to grade from each frame.
+#[derive(Component)]
+struct UngradedColorGrading(ColorGrading);
+
+fn update_water_depth(
+    underwater: Option<Res<Underwater>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    mut water_depth: ResMut<WaterDepth>,
+) {
+    let Some(underwater) = underwater else {
+        water_depth.set_if_neq(WaterDepth::default());
+        return;
+    };
+    let depth = (underwater.surface_height - camera.translation().y).max(0.0);
+    let linear = (depth / underwater.full_depth.max(f32::EPSILON)).min(1.0);
+    // Eases out, so leaving the surface is felt straight away and the change levels off lower down
+    let pressure = linear * (2.0 - linear);
+    let lowpass_cutoff =
+        OPEN_CUTOFF_HZ * (underwater.muffled_cutoff / OPEN_CUTOFF_HZ).powf(pressure);
+    water_depth.set_if_neq(WaterDepth {
+        depth,
+        pressure,
+        lowpass_cutoff,
+    });
+}
+
+/// Clear in the middle, fading to opaque towards the corners.
+fn vignette_image() -> Image {
+    let mut data = Vec::with_capacity((VIGNETTE_SIZE * VIGNETTE_SIZE * 4) as usize);
+    let half = VIGNETTE_SIZE as f32 / 2.0;
+    for y in 0..VIGNETTE_SIZE {
+        for x in 0..VIGNETTE_SIZE {
+            let offset = (Vec2::new(x as f32, y as f32) + 0.5 - half) / half;
+            // Corners are at a distance of sqrt(2)
+            let edge = ((offset.length() - 0.45) / 0.95).clamp(0.0, 1.0);
+            let alpha = (edge * edge * (3.0 - 2.0 * edge) * 255.0) as u8;
+            data.extend_from_slice(&[255, 255, 255, alpha]);
+        }
+    }
+    let mut image = Image::new(
+        Extent3d {
+            width: VIGNETTE_SIZE,
+            height: VIGNETTE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    // The app defaults to nearest sampling, which would show the texels when stretched
+    image.sampler = ImageSampler::linear();
+    image
+}
+
+fn spawn_vignette(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    underwater: Option<Res<Underwater>>,
+    vignette: Query<(), With<DepthVignette>>,
+) {
+    if underwater.is_none() || !vignette.is_empty() {
+        return;
+    }
+    commands.spawn((
+        Name::new("Depth vignette"),
+        DepthVignette,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        ImageNode::new(images.add(vignette_image())).with_color(Color::NONE),
+        Pickable::IGNORE,
+        // Below the pause menu and loading screen
+        GlobalZIndex(i32::MAX - 2),
+    ));
+}
+
+fn update_vignette(
+    underwater: Option<Res<Underwater>>,
+    water_depth: Res<WaterDepth>,
+    mut vignette: Query<&mut ImageNode, With<DepthVignette>>,
+) {
+    for mut image in &mut vignette {
+        let color = match &underwater {
+            Some(underwater) => underwater
+                .vignette_color
+                .with_alpha(underwater.vignette * water_depth.pressure),
+            None => Color::NONE,
+        };
+        if image.color != color {
+            image.color = color;
+        }
+    }
+}
+
+fn grade_for_depth(
+    mut commands: Commands,
+    underwater: Option<Res<Underwater>>,
+    water_depth: Res<WaterDepth>,
+    camera: Single<(Entity, &mut ColorGrading, Option<&UngradedColorGrading>), With<PlayerCamera>>,
+) {
+    let (entity, mut grading, ungraded) = camera.into_inner();
+    let Some(ungraded) = ungraded else {
+        commands
+            .entity(entity)
+            .insert(UngradedColorGrading(grading.clone()));
+        return;
+    };
+    if !water_depth.is_changed() && underwater.as_ref().is_none_or(|u| !u.is_changed()) {
+        return;
+    }
+    let mut graded = ungraded.0.clone();
+    if let Some(underwater) = underwater {
+        let pressure = water_depth.pressure;
+        graded.global.exposure -= underwater.darkening * pressure;
+        graded.global.temperature += underwater.absorption * pressure;
+        graded.global.post_saturation *= 1.0 - 0.4 * pressure;
+    }
+    *grading = graded;
+}
@@ -0,0 +1,464 @@
+//! Thermal and night vision views through a handheld scanner.
+//!
+//! The [`ViewMode`] resource picks how the world is seen. In thermal view each mesh is drawn in a
+//! flat false-colour palette by how hot it is, from its own [`Temperature`] or its nearest
+//! ancestor's, with anything burning as a [`Flammable`] at its hottest, and everything else cold.
+//! Night vision draws each mesh in shades of green by how bright its material is, without needing
+//! any light, under animated film grain. Both add bloom to the player camera so hot spots and
+//! lights glow.
+//!
+//! Views are switched by swapping meshes' [`StandardMaterial`]s for unlit overrides, one per
+//! original material and shade, and swapping them back on returning to [`ViewMode::Normal`]. Meshes
+//! with a custom material are covered too once its type implements [`ViewModeStandIn`] and a
+//! [`ViewModeMaterialPlugin`] is added for it: while a view is on, they're given the
+//! [`StandardMaterial`] it stands in with, which is then overridden like any other. With a
+//! [`ViewModeScanner`] resource, the use item binding cycles through the modes while the player
+//! holds its item.
+
+use std::marker::PhantomData;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::color::{Luminance, Mix};
+use bevy::diagnostic::FrameCount;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::post_process::bloom::Bloom;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::ui::widget::NodeImageMode;
+
+use crate::carry::Carrying;
+use crate::controls::{ControlsConfig, ControlsInput};
+use crate::fire::Flammable;
+use crate::firstsight::PlayerCamera;
+use crate::inventory::Inventory;
+use crate::rng::SplitMix64;
+use crate::state::PausableSystems;
+
+pub struct ViewModePlugin;
+
+impl Plugin for ViewModePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ViewMode>()
+            .register_type::<Temperature>()
+            .init_resource::<ViewMode>()
+            .init_resource::<OverrideMaterials>()
+            .add_systems(
+                Update,
+                (
+                    use_scanner
+                        .run_if(resource_exists::<ViewModeScanner>)
+                        .in_set(PausableSystems),
+                    (override_materials, bloom_camera, spawn_grain, flicker_grain),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Shades of each palette, so overrides can be shared between meshes.
+const SHADES: f32 = 15.0;
+const GRAIN_SIZE: u32 = 96;
+/// Different grain images to flick between, each also flipped, for the grain to look random.
+const GRAIN_FRAMES: usize = 4;
+
+/// How the player camera sees the world.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Resource)]
+pub enum ViewMode {
+    #[default]
+    Normal,
+    Thermal,
+    NightVision,
+}
+
+impl ViewMode {
+    /// The mode after this one, wrapping back round to normal.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Normal => Self::Thermal,
+            Self::Thermal => Self::NightVision,
+            Self::NightVision => Self::Normal,
+        }
+    }
+}
+
+/// How hot an entity and its descendants look in thermal view, from 0 for the surroundings to 1
+/// for as hot as fire.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Temperature(pub f32);
+
+/// Lets the player cycle view modes with the use item binding while holding `item` from their
+/// [`Inventory`].
+#[derive(Resource, Debug, Clone)]
+pub struct ViewModeScanner {
+    pub item: String,
+}
+
+impl ViewModeScanner {
+    pub fn new(item: impl Into<String>) -> Self {
+        Self { item: item.into() }
+    }
+}
+
+/// A custom material that can be seen in thermal and night vision views, by the
+/// [`StandardMaterial`] that stands in for it there.
+pub trait ViewModeStandIn: Material {
+    /// A material that looks like this one, for its colour, glow, transparency and culling to be
+    /// carried over into the view.
+    fn stand_in(&self) -> StandardMaterial;
+}
+
+/// Covers meshes with a custom material `M` in thermal and night vision views, alongside its
+/// `MaterialPlugin`.
+pub struct ViewModeMaterialPlugin<M>(PhantomData<M>);
+
+impl<M> Default for ViewModeMaterialPlugin<M> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<M: ViewModeStandIn> Plugin for ViewModeMaterialPlugin<M> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                cover_material::<M>.before(override_materials),
+                uncover_material::<M>.after(override_materials),
+            ),
+        );
+    }
+}
+
+/// A mesh's own custom material, while it's swapped for a [`ViewModeStandIn`].
+#[derive(Component)]
+struct CoveredMaterial<M: Material>(Handle<M>);
+
+/// A mesh's own material, while it's swapped for an override.
+#[derive(Component)]
+struct OriginalMaterial(Handle<StandardMaterial>);
+
+/// Override materials by the original they stand in for, the mode, and the shade.
+#[derive(Resource, Default)]
+struct OverrideMaterials {
+    materials: HashMap<(AssetId<StandardMaterial>, ViewMode, u8), Handle<StandardMaterial>>,
+    overrides: HashSet<AssetId<StandardMaterial>>,
+}
+
+#[derive(Component)]
+struct NightVisionGrain {
+    frames: Vec<Handle<Image>>,
+}
+
+/// Bloom added to the player camera for a view mode, to take off again afterwards.
+#[derive(Component)]
+struct ViewModeBloom;
+
+fn use_scanner(
+    config: Res<ControlsConfig>,
+    input: ControlsInput,
+    scanner: Res<ViewModeScanner>,
+    inventory: Res<Inventory>,
+    carrying: Query<(), With<Carrying>>,
+    mut mode: ResMut<ViewMode>,
+) {
+    if !inventory.is_holding(&scanner.item) {
+        // Putting the scanner away takes it off the player's eyes
+        mode.set_if_neq(ViewMode::Normal);
+        return;
+    }
+    // Using an item while carrying something throws it instead
+    if input.just_pressed(&config.use_item) && carrying.is_empty() {
+        *mode = mode.next();
+    }
+}
+
+/// How hot `entity` is, from itself or its nearest ancestor with a [`Temperature`].
+fn temperature(
+    heat: &Query<(Option<&Temperature>, Option<&Flammable>)>,
+    parents: &Query<&ChildOf>,
+    entity: Entity,
+) -> f32 {
+    for entity in std::iter::once(entity).chain(parents.iter_ancestors(entity)) {
+        let Ok((temperature, flammable)) = heat.get(entity) else {
+            continue;
+        };
+        if flammable.is_some_and(Flammable::is_burning) {
+            return 1.0;
+        }
+        if let Some(temperature) = temperature {
+            return temperature.0.clamp(0.0, 1.0);
+        }
+    }
+    0.0
+}
+
+/// How bright a material looks, from its base colour and glow, from 0 to 1.
+fn brightness(material: &StandardMaterial) -> f32 {
+    let emissive = Color::from(material.emissive).luminance();
+    (material.base_color.luminance() + emissive).clamp(0.0, 1.0)
+}
+
+/// Black through purple, red, orange and yellow to white, for heat from 0 to 1.
+fn thermal_palette(heat: f32) -> LinearRgba {
+    const STOPS: [(f32, Srgba); 6] = [
+        (0.0, Srgba::rgb(0.02, 0.0, 0.1)),
+        (0.3, Srgba::rgb(0.35, 0.0, 0.55)),
+        (0.55, Srgba::rgb(0.9, 0.1, 0.1)),
+        (0.75, Srgba::rgb(1.0, 0.55, 0.0)),
+        (0.9, Srgba::rgb(1.0, 1.0, 0.2)),
+        (1.0, Srgba::WHITE),
+    ];
+    let heat = heat.clamp(0.0, 1.0);
+    let upper = STOPS
+        .iter()
+        .position(|(stop, _)| *stop >= heat)
+        .unwrap_or(STOPS.len() - 1)
+        .max(1);
+    let (from, from_color) = STOPS[upper - 1];
+    let (to, to_color) = STOPS[upper];
+    LinearRgba::from(from_color).mix(&to_color.into(), (heat - from) / (to - from))
+}
+
+/// An unlit stand-in for `original` in `mode`, at `shade` out of [`SHADES`].
+fn override_material(original: &StandardMaterial, mode: ViewMode, shade: f32) -> StandardMaterial {
+    let (color, emissive) = match mode {
+        ViewMode::Normal => return original.clone(),
+        ViewMode::Thermal => {
+            let color = thermal_palette(shade);
+            // Only the hottest things are bright enough to bloom
+            (color, color * ((shade - 0.6) * 5.0).max(0.0))
+        }
+        ViewMode::NightVision => {
+            // Lifted so dark things stay visible, as an image intensifier would show them
+            let value = 0.12 + 0.88 * shade.sqrt();
+            let color = LinearRgba::rgb(0.25 * value, value, 0.3 * value);
+            let glows = original.emissive != LinearRgba::BLACK;
+            (
+                color,
+                if glows {
+                    color * 4.0
+                } else {
+                    LinearRgba::BLACK
+                },
+            )
+        }
+    };
+    StandardMaterial {
+        base_color: Color::from(color).with_alpha(original.base_color.alpha()),
+        emissive,
+        unlit: true,
+        alpha_mode: original.alpha_mode,
+        double_sided: original.double_sided,
+        cull_mode: original.cull_mode,
+        ..default()
+    }
+}
+
+fn override_materials(
+    mut commands: Commands,
+    mode: Res<ViewMode>,
+    mut cache: ResMut<OverrideMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: Query<(
+        Entity,
+        &mut MeshMaterial3d<StandardMaterial>,
+        Option<&OriginalMaterial>,
+    )>,
+    heat: Query<(Option<&Temperature>, Option<&Flammable>)>,
+    parents: Query<&ChildOf>,
+) {
+    if *mode == ViewMode::Normal {
+        if mode.is_changed() {
+            for (entity, mut material, original) in &mut meshes {
+                let Some(original) = original else {
+                    continue;
+                };
+                material.0 = original.0.clone();
+                commands.entity(entity).remove::<OriginalMaterial>();
+            }
+            cache.materials.clear();
+            cache.overrides.clear();
+        }
+        return;
+    }
+    for (entity, mut material, original) in &mut meshes {
+        // Anything else is a new material the scene has swapped in since
+        let own = if cache.overrides.contains(&material.id()) {
+            match original {
+                Some(original) => original.0.clone(),
+                None => continue,
+            }
+        } else {
+            material.0.clone()
+        };
+        let Some(own_material) = materials.get(&own) else {
+            continue;
+        };
+        let shade = match *mode {
+            ViewMode::Thermal => {
+                temperature(&heat, &parents, entity) * 0.85 + brightness(own_material) * 0.15
+            }
+            _ => brightness(own_material),
+        };
+        let step = (shade * SHADES).round() as u8;
+        let key = (own.id(), *mode, step);
+        let stand_in = match cache.materials.get(&key) {
+            Some(stand_in) => stand_in.clone(),
+            None => {
+                let stand_in = override_material(own_material, *mode, step as f32 / SHADES);
+                let stand_in = materials.add(stand_in);
+                cache.overrides.insert(stand_in.id());
+                cache.materials.insert(key, stand_in.clone());
+                stand_in
+            }
+        };
+        if material.0 != stand_in {
+            material.0 = stand_in;
+        }
+        if original.is_none_or(|original| original.0 != own) {
+            commands.entity(entity).insert(OriginalMaterial(own));
+        }
+    }
+}
+
+fn cover_material<M: ViewModeStandIn>(
+    mut commands: Commands,
+    mode: Res<ViewMode>,
+    custom_materials: Res<Assets<M>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut stand_ins: Local<HashMap<AssetId<M>, Handle<StandardMaterial>>>,
+    meshes: Query<(Entity, &MeshMaterial3d<M>)>,
+) {
+    if *mode == ViewMode::Normal {
+        stand_ins.clear();
+        return;
+    }
+    for (entity, material) in &meshes {
+        let Some(custom_material) = custom_materials.get(&material.0) else {
+            continue;
+        };
+        let stand_in = stand_ins
+            .entry(material.id())
+            .or_insert_with(|| materials.add(custom_material.stand_in()))
+            .clone();
+        commands
+            .entity(entity)
+            .remove::<MeshMaterial3d<M>>()
+            .insert((
+                CoveredMaterial(material.0.clone()),
+                MeshMaterial3d(stand_in),
+            ));
+    }
+}
+
+fn uncover_material<M: ViewModeStandIn>(
+    mut commands: Commands,
+    mode: Res<ViewMode>,
+    meshes: Query<(Entity, &CoveredMaterial<M>)>,
+) {
+    if *mode != ViewMode::Normal {
+        return;
+    }
+    for (entity, covered) in &meshes {
+        commands
+            .entity(entity)
+            .remove::<(CoveredMaterial<M>, MeshMaterial3d<StandardMaterial>)>()
+            .insert(MeshMaterial3d(covered.0.clone()));
+    }
+}
+
+fn bloom_camera(
+    mut commands: Commands,
+    mode: Res<ViewMode>,
+    camera: Single<(Entity, Has<Bloom>, Has<ViewModeBloom>), With<PlayerCamera>>,
+) {
+    let (entity, has_bloom, added) = *camera;
+    if *mode == ViewMode::Normal {
+        if added {
+            commands.entity(entity).remove::<(Bloom, ViewModeBloom)>();
+        }
+    } else if !has_bloom {
+        commands
+            .entity(entity)
+            .insert((Bloom::NATURAL, ViewModeBloom));
+    }
+}
+
+/// Specks of white noise, mostly clear.
+fn grain_image(rng: &mut SplitMix64) -> Image {
+    let mut data = Vec::with_capacity((GRAIN_SIZE * GRAIN_SIZE * 4) as usize);
+    for _ in 0..GRAIN_SIZE * GRAIN_SIZE {
+        let speck = rng.next_f32();
+        let alpha = (speck * speck * 90.0) as u8;
+        data.extend_from_slice(&[255, 255, 255, alpha]);
+    }
+    // Left with the app's nearest sampling, so each texel is a sharp speck
+    Image::new(
+        Extent3d {
+            width: GRAIN_SIZE,
+            height: GRAIN_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+fn spawn_grain(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mode: Res<ViewMode>,
+    grain: Query<(), With<NightVisionGrain>>,
+) {
+    if *mode != ViewMode::NightVision || !grain.is_empty() {
+        return;
+    }
+    let mut rng = SplitMix64::new(0x6EA1_0000);
+    let frames: Vec<_> = (0..GRAIN_FRAMES)
+        .map(|_| images.add(grain_image(&mut rng)))
+        .collect();
+    commands.spawn((
+        Name::new("Night vision grain"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        ImageNode::new(frames[0].clone()).with_mode(NodeImageMode::Tiled {
+            tile_x: true,
+            tile_y: true,
+            stretch_value: 2.0,
+        }),
+        NightVisionGrain { frames },
+        Pickable::IGNORE,
+        // Below the pause menu and loading screen
+        GlobalZIndex(i32::MAX - 2),
+    ));
+}
+
+fn flicker_grain(
+    mode: Res<ViewMode>,
+    frame: Res<FrameCount>,
+    mut grain: Query<(&mut ImageNode, &mut Visibility, &NightVisionGrain)>,
+) {
+    for (mut image, mut visibility, grain) in &mut grain {
+        let visible = *mode == ViewMode::NightVision;
+        visibility.set_if_neq(if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if !visible {
+            continue;
+        }
+        let mut rng = SplitMix64::new(frame.0 as u64);
+        image.image = grain.frames[rng.index(grain.frames.len())].clone();
+        image.flip_x = rng.next_f32() < 0.5;
+        image.flip_y = rng.next_f32() < 0.5;
+    }
+}
@@ -0,0 +1,254 @@
+//! Randomised creature calls played as spatial audio.
+//!
+//! A [`CreatureVoice`] picks a clip for the creature's current [`CreatureMood`] at random
+//! intervals, with some pitch variance so repeated calls don't sound identical. Calls fade out with
+//! distance from the [`SpatialListener`] and aren't played at all beyond `max_distance`. Switching
+//! to an alert or fleeing mood triggers a call straight away. Voices with a caption list each call
+//! they play in [`SoundCaptions`], pointing to where it came from.
+//!
+//! Creatures driven by the crate's behaviours have their mood kept up to date: a [`LookAtPlayer`]
+//! watching the player is alert, and a [`DailyActivity`] heading for shelter is fleeing. Others
+//! keep whatever mood their scene sets. Hidden creatures don't call, such as ones that have gone to
+//! roost. [`CallRecipe`] synthesises calls for scenes without recordings.
+//!
+//! [`CallRecipe`]: crate::audio::CallRecipe
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::activity::DailyActivity;
+use crate::captions::SoundCaptions;
+use crate::npc::LookAtPlayer;
+use crate::rng::SplitMix64;
+
+pub struct CreatureVoicePlugin;
+
+impl Plugin for CreatureVoicePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                follow_creature_behaviour,
+                react_to_mood_changes,
+                play_creature_calls,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Behavioural state of a creature, set by whatever AI drives it.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum CreatureMood {
+    #[default]
+    Idle,
+    Alert,
+    Fleeing,
+}
+
+impl CreatureMood {
+    /// Agitated creatures call more often.
+    fn interval_scale(self) -> f32 {
+        match self {
+            CreatureMood::Idle => 1.0,
+            CreatureMood::Alert => 0.5,
+            CreatureMood::Fleeing => 0.25,
+        }
+    }
+}
+
+#[derive(Component)]
+#[require(Transform, CreatureMood)]
+pub struct CreatureVoice {
+    pub idle_calls: Vec<Handle<AudioSource>>,
+    /// Falls back to `idle_calls` when empty.
+    pub alert_calls: Vec<Handle<AudioSource>>,
+    /// Falls back to `alert_calls`, then `idle_calls`, when empty.
+    pub flee_calls: Vec<Handle<AudioSource>>,
+    /// Seconds between idle calls, scaled down when alert or fleeing.
+    pub min_interval: f32,
+    pub max_interval: f32,
+    /// Maximum playback speed deviation either side of 1.0.
+    pub pitch_variance: f32,
+    pub volume: f32,
+    /// Calls further than this from the listener are skipped.
+    pub max_distance: f32,
+    /// What the calls sound like, e.g. "distant whale song", captioned as they're played.
+    pub caption: Option<String>,
+    cooldown: Option<f32>,
+    rng: Option<SplitMix64>,
+}
+
+impl CreatureVoice {
+    pub fn new(idle_calls: Vec<Handle<AudioSource>>) -> Self {
+        Self {
+            idle_calls,
+            alert_calls: Vec::new(),
+            flee_calls: Vec::new(),
+            min_interval: 4.0,
+            max_interval: 12.0,
+            pitch_variance: 0.1,
+            volume: 1.0,
+            max_distance: 40.0,
+            caption: None,
+            cooldown: None,
+            rng: None,
+        }
+    }
+
+    pub fn with_alert_calls(mut self, alert_calls: Vec<Handle<AudioSource>>) -> Self {
+        self.alert_calls = alert_calls;
+        self
+    }
+
+    pub fn with_flee_calls(mut self, flee_calls: Vec<Handle<AudioSource>>) -> Self {
+        self.flee_calls = flee_calls;
+        self
+    }
+
+    pub fn with_interval(mut self, min_interval: f32, max_interval: f32) -> Self {
+        self.min_interval = min_interval;
+        self.max_interval = max_interval.max(min_interval);
+        self
+    }
+
+    pub fn with_pitch_variance(mut self, pitch_variance: f32) -> Self {
+        self.pitch_variance = pitch_variance;
+        self
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+}
+
+/// Picks the clips for `mood`, falling back to calmer moods' clips when none are set.
+fn calls_for<'a>(
+    idle_calls: &'a [Handle<AudioSource>],
+    alert_calls: &'a [Handle<AudioSource>],
+    flee_calls: &'a [Handle<AudioSource>],
+    mood: CreatureMood,
+) -> &'a [Handle<AudioSource>] {
+    let candidates = match mood {
+        CreatureMood::Idle => [idle_calls, idle_calls, idle_calls],
+        CreatureMood::Alert => [alert_calls, idle_calls, idle_calls],
+        CreatureMood::Fleeing => [flee_calls, alert_calls, idle_calls],
+    };
+    candidates
+        .into_iter()
+        .find(|calls| !calls.is_empty())
+        .unwrap_or(idle_calls)
+}
+
+fn follow_creature_behaviour(
+    mut creatures: Query<
+        (
+            &mut CreatureMood,
+            Option<&LookAtPlayer>,
+            Option<&DailyActivity>,
+        ),
+        (
+            With<CreatureVoice>,
+            Or<(With<LookAtPlayer>, With<DailyActivity>)>,
+        ),
+    >,
+) {
+    for (mut mood, look, activity) in &mut creatures {
+        let behaviour = if activity.is_some_and(|activity| !activity.is_active()) {
+            CreatureMood::Fleeing
+        } else if look.is_some_and(LookAtPlayer::is_looking) {
+            CreatureMood::Alert
+        } else {
+            CreatureMood::Idle
+        };
+        mood.set_if_neq(behaviour);
+    }
+}
+
+fn react_to_mood_changes(
+    mut voices: Query<(&mut CreatureVoice, &CreatureMood), Changed<CreatureMood>>,
+) {
+    for (mut voice, mood) in voices.iter_mut() {
+        if *mood != CreatureMood::Idle {
+            voice.cooldown = Some(0.0);
+        }
+    }
+}
+
+fn play_creature_calls(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sound_captions: ResMut<SoundCaptions>,
+    listener: Option<Single<&GlobalTransform, With<SpatialListener>>>,
+    mut voices: Query<(
+        Entity,
+        &mut CreatureVoice,
+        &CreatureMood,
+        &GlobalTransform,
+        Option<&InheritedVisibility>,
+    )>,
+) {
+    let Some(listener) = listener else {
+        return;
+    };
+    let listener_position = listener.translation();
+
+    for (entity, mut voice, mood, transform, visibility) in voices.iter_mut() {
+        let voice = &mut *voice;
+        // Seed from the entity so creatures spawned together don't call in unison
+        let rng = voice
+            .rng
+            .get_or_insert_with(|| SplitMix64::new(entity.to_bits()));
+        let scale = mood.interval_scale();
+        let cooldown = voice
+            .cooldown
+            .get_or_insert_with(|| rng.range_f32(voice.min_interval, voice.max_interval) * scale);
+
+        *cooldown -= time.delta_secs();
+        if *cooldown > 0.0 {
+            continue;
+        }
+        *cooldown = rng.range_f32(voice.min_interval, voice.max_interval) * scale;
+
+        let distance = transform.translation().distance(listener_position);
+        if distance > voice.max_distance || visibility.is_some_and(|visible| !visible.get()) {
+            continue;
+        }
+        let calls = calls_for(
+            &voice.idle_calls,
+            &voice.alert_calls,
+            &voice.flee_calls,
+            *mood,
+        );
+        if calls.is_empty() {
+            continue;
+        }
+        let clip = calls[rng.index(calls.len())].clone();
+        let falloff = 1.0 - distance / voice.max_distance;
+        let pitch = 1.0 + rng.range_f32(-voice.pitch_variance, voice.pitch_variance);
+
+        commands.entity(entity).with_child((
+            Name::new("Creature call"),
+            Transform::default(),
+            AudioPlayer::new(clip),
+            PlaybackSettings::DESPAWN
+                .with_spatial(true)
+                .with_volume(Volume::Linear(voice.volume * falloff * falloff))
+                .with_speed(pitch),
+        ));
+        if let Some(caption) = &voice.caption {
+            sound_captions.heard(caption, Some(transform.translation()));
+        }
+    }
+}
@@ -1,9 +1,21 @@
+//! Primary window controls and cursor grabbing.
+//!
+//! The cursor is grabbed for mouse look while the game is active, and released whenever something
+//! needs the pointer: the pause screen, conversations, or anything that calls
+//! [`CursorPolicy::request_release`], such as the world inspector.
+
+use std::collections::HashSet;
+
 use bevy::prelude::*;
 use bevy::window::{
     CursorGrabMode, CursorOptions, MonitorSelection, PrimaryWindow, VideoModeSelection, WindowMode,
 };
 use leafwing_input_manager::prelude::*;
 
+use crate::controls::keyboard_not_captured;
+use crate::dialogue_log::DialogueInProgress;
+use crate::firstsight::{LookDisabled, PlayerCamera};
+use crate::kiosk::not_in_kiosk_mode;
 use crate::state::GameState;
 
 pub struct WindowPlugin;
@@ -11,10 +23,48 @@ pub struct WindowPlugin;
 impl Plugin for WindowPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<ToggleFullscreenAction>::default())
-            .add_systems(Startup, (setup, setup_actions))
-            .add_systems(Update, handle_actions)
-            .add_systems(OnEnter(GameState::Paused), on_pause)
-            .add_systems(OnEnter(GameState::Active), on_resume);
+            .init_resource::<CursorPolicy>()
+            .add_systems(Startup, setup_actions)
+            .add_systems(
+                Update,
+                handle_actions
+                    .run_if(not_in_kiosk_mode)
+                    .run_if(keyboard_not_captured),
+            )
+            .add_systems(PostUpdate, apply_cursor_policy);
+    }
+}
+
+/// When the cursor should be grabbed for mouse look.
+#[derive(Resource, Debug, Clone)]
+pub struct CursorPolicy {
+    /// Wait for a left click or gamepad button before grabbing the cursor again, rather than
+    /// grabbing as soon as nothing needs it.
+    pub grab_on_click: bool,
+    /// Release the cursor while a conversation is in progress, so dialogue options can be clicked.
+    pub release_during_dialogue: bool,
+    release_requests: HashSet<&'static str>,
+}
+
+impl Default for CursorPolicy {
+    fn default() -> Self {
+        Self {
+            grab_on_click: false,
+            release_during_dialogue: true,
+            release_requests: HashSet::new(),
+        }
+    }
+}
+
+impl CursorPolicy {
+    /// Keeps the cursor released until [`CursorPolicy::cancel_release`] is called with the same
+    /// `reason`, e.g. while a scene's UI panel is open.
+    pub fn request_release(&mut self, reason: &'static str) {
+        self.release_requests.insert(reason);
+    }
+
+    pub fn cancel_release(&mut self, reason: &'static str) {
+        self.release_requests.remove(reason);
     }
 }
 
@@ -48,32 +98,45 @@ fn handle_actions(
     }
 }
 
-fn setup(
-    mut window: Single<&mut Window, With<PrimaryWindow>>,
-    mut cursor_options: Single<&mut CursorOptions, With<PrimaryWindow>>,
-) {
-    cursor_options.grab_mode = CursorGrabMode::Locked;
-    cursor_options.visible = false;
-
-    center_cursor(&mut window);
-}
-
-fn on_pause(
-    mut window: Single<&mut Window, With<PrimaryWindow>>,
-    mut cursor_options: Single<&mut CursorOptions, With<PrimaryWindow>>,
+fn apply_cursor_policy(
+    mut commands: Commands,
+    policy: Res<CursorPolicy>,
+    state: Res<State<GameState>>,
+    dialogues: Query<(), With<DialogueInProgress>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    window: Single<(&mut Window, &mut CursorOptions), With<PrimaryWindow>>,
+    camera: Single<(Entity, Has<LookDisabled>), With<PlayerCamera>>,
 ) {
-    center_cursor(&mut window);
+    let needs_cursor = *state.get() == GameState::Paused
+        || (policy.release_during_dialogue && !dialogues.is_empty())
+        || !policy.release_requests.is_empty();
+    let (mut window, mut cursor_options) = window.into_inner();
+    let grabbed = cursor_options.grab_mode != CursorGrabMode::None;
 
-    cursor_options.grab_mode = CursorGrabMode::None;
-    cursor_options.visible = true;
-}
-
-fn on_resume(
-    mut window: Single<&mut Window, With<PrimaryWindow>>,
-    mut cursor_options: Single<&mut CursorOptions, With<PrimaryWindow>>,
-) {
-    cursor_options.grab_mode = CursorGrabMode::Locked;
-    cursor_options.visible = false;
+    if needs_cursor && grabbed {
+        center_cursor(&mut window);
+        cursor_options.grab_mode = CursorGrabMode::None;
+        cursor_options.visible = true;
+    } else if !needs_cursor
+        && !grabbed
+        && (!policy.grab_on_click
+            || mouse_buttons.just_pressed(MouseButton::Left)
+            || gamepads
+                .iter()
+                .any(|gamepad| gamepad.get_just_pressed().next().is_some()))
+    {
+        cursor_options.grab_mode = CursorGrabMode::Locked;
+        cursor_options.visible = false;
+        center_cursor(&mut window);
+    }
 
-    center_cursor(&mut window);
+    // Mouse look only makes sense while the cursor is held
+    let (camera, look_disabled) = camera.into_inner();
+    let grabbed = cursor_options.grab_mode != CursorGrabMode::None;
+    if grabbed && look_disabled {
+        commands.entity(camera).remove::<LookDisabled>();
+    } else if !grabbed && !look_disabled {
+        commands.entity(camera).insert(LookDisabled);
+    }
 }
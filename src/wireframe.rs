@@ -2,7 +2,10 @@ use bevy::pbr::wireframe::WireframeConfig;
 use bevy::prelude::*;
 use leafwing_input_manager::Actionlike;
 use leafwing_input_manager::plugin::InputManagerPlugin;
-use leafwing_input_manager::prelude::{ActionState, ButtonlikeChord, InputMap};
+use leafwing_input_manager::prelude::{ActionState, InputMap};
+
+use crate::controls::{ControlsConfig, keyboard_not_captured};
+use crate::kiosk::not_in_kiosk_mode;
 
 pub struct WireframePlugin;
 
@@ -11,21 +14,33 @@ impl Plugin for WireframePlugin {
         app.add_plugins(bevy::pbr::wireframe::WireframePlugin::default())
             .add_plugins(InputManagerPlugin::<ToggleWireframesAction>::default())
             .add_systems(Startup, setup_actions)
-            .add_systems(Update, handle_actions);
+            .add_systems(
+                Update,
+                (
+                    rebind_actions.run_if(resource_changed::<ControlsConfig>),
+                    handle_actions
+                        .run_if(not_in_kiosk_mode)
+                        .run_if(keyboard_not_captured),
+                ),
+            );
     }
 }
 
 #[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
 struct ToggleWireframesAction;
 
-fn setup_actions(mut commands: Commands) {
-    let toggle_map = InputMap::new([(
-        ToggleWireframesAction,
-        ButtonlikeChord::new([KeyCode::F3, KeyCode::KeyG]),
-    )]);
+fn setup_actions(mut commands: Commands, controls: Res<ControlsConfig>) {
+    let toggle_map = controls.toggle_wireframe.input_map(ToggleWireframesAction);
     commands.spawn((Name::new("Wireframe controls"), toggle_map));
 }
 
+fn rebind_actions(
+    controls: Res<ControlsConfig>,
+    mut input_map: Single<&mut InputMap<ToggleWireframesAction>>,
+) {
+    **input_map = controls.toggle_wireframe.input_map(ToggleWireframesAction);
+}
+
 fn handle_actions(
     action_state: Single<&ActionState<ToggleWireframesAction>>,
     mut wireframe_config: ResMut<WireframeConfig>,